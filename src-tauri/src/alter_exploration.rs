@@ -9,17 +9,24 @@ use std::{
 
 use atomicwrites::{AllowOverwrite, AtomicFile};
 use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
 
-use crate::second_brain::config::{active_profile, ProviderProfile, SecondBrainConfig};
+use crate::conversation_search::index_alter_exploration;
+use crate::second_brain::config::{
+    role_profile, role_route, LlmRoute, ModelRole, ProviderProfile, SecondBrainConfig,
+};
+use crate::second_brain::llm::run_llm_stream;
+use crate::second_brain::prompt_packs::{
+    fill_template, select_prompt_pack, PromptLocale, PromptPack,
+};
 use crate::second_brain::session_store::estimate_tokens;
+use crate::second_brain::usage::LlmFeature;
 use crate::settings;
 use crate::{
-    active_workspace_root, now_ms, next_index_run_id, AppError, Result,
+    active_workspace_root, ensure_index_schema, next_index_run_id, now_ms, open_db, AppError,
+    Result,
 };
 
-#[cfg(test)]
-use std::collections::VecDeque;
-
 const EXPLORATION_PREFIX: &str = "alter-explore";
 const EXPLORATIONS_DIR: &str = "alter-explorations";
 const MIN_ALTERS: usize = 2;
@@ -28,9 +35,15 @@ const MIN_ROUNDS: i64 = 2;
 const MAX_ROUNDS: i64 = 3;
 const CONTEXT_PROMPT_BUDGET_TOKENS: usize = 6_500;
 const CONTEXT_MAX_FILE_TOKENS: usize = 1_200;
-const TRUNCATION_MARKER: &str = "\n[CONTENU TRONQUE]\n";
+const CANCELLED_MESSAGE: &str = "Exploration cancelled.";
+const REQUEST_FAILED_MESSAGE: &str = "Exploration request failed.";
+const STEP_START_EVENT: &str = "alter-exploration://step-start";
+const STEP_DELTA_EVENT: &str = "alter-exploration://step-delta";
+const STEP_COMPLETE_EVENT: &str = "alter-exploration://step-complete";
+const STEP_ERROR_EVENT: &str = "alter-exploration://step-error";
 
 static CANCELLED_SESSIONS: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+static RUNNING_SESSIONS: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -82,6 +95,16 @@ pub struct AlterRoundResult {
     pub content: String,
     #[serde(default)]
     pub references_alter_ids: Vec<String>,
+    /// When the result was produced; `0` for results saved before it was recorded.
+    #[serde(default)]
+    pub created_at_ms: u64,
+}
+
+/// Digest of a finished round, kept so a resumed exploration does not recompute it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlterRoundDigest {
+    pub round_number: i64,
+    pub content: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -96,6 +119,8 @@ pub struct AlterExplorationSession {
     pub state: AlterExplorationState,
     #[serde(default)]
     pub round_results: Vec<AlterRoundResult>,
+    #[serde(default)]
+    pub round_digests: Vec<AlterRoundDigest>,
     pub final_synthesis: Option<String>,
     pub error_message: Option<String>,
     pub created_at_ms: u64,
@@ -119,6 +144,36 @@ pub struct LoadAlterExplorationPayload {
 #[derive(Debug, Clone, Deserialize)]
 pub struct RunAlterExplorationPayload {
     pub session_id: String,
+    /// Continues a failed exploration from its last completed step instead of restarting.
+    #[serde(default)]
+    pub resume: bool,
+}
+
+/// LLM call of an exploration that a progress event refers to.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AlterExplorationStepKind {
+    /// One Alter answering in a round.
+    Round,
+    /// Digest of a finished round, fed to the next one.
+    Digest,
+    Synthesis,
+}
+
+/// Payload of the `alter-exploration://step-*` events.
+///
+/// `chunk` holds the streamed text on delta events and the full output on complete events.
+#[derive(Debug, Clone, Serialize)]
+pub struct AlterExplorationStepEvent {
+    pub session_id: String,
+    pub step: AlterExplorationStepKind,
+    pub round_number: Option<i64>,
+    pub alter_id: Option<String>,
+    pub chunk: String,
+    pub error: Option<String>,
+    /// Profile that produced the chunk or output, which may be a fallback profile.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -140,10 +195,57 @@ struct ExplorationContextEntry {
     content: String,
 }
 
-#[derive(Debug, Clone)]
-enum ExplorationModelRole {
-    Basic,
-    Normal,
+/// Receives the progress events of a running exploration.
+type StepEmitter<'a> = &'a (dyn Fn(&str, AlterExplorationStepEvent) + Sync);
+
+#[derive(Debug, Clone, Copy)]
+struct ExplorationStep<'a> {
+    kind: AlterExplorationStepKind,
+    round_number: Option<i64>,
+    alter_id: Option<&'a str>,
+}
+
+impl<'a> ExplorationStep<'a> {
+    fn round(round_number: i64, alter_id: &'a str) -> Self {
+        Self {
+            kind: AlterExplorationStepKind::Round,
+            round_number: Some(round_number),
+            alter_id: Some(alter_id),
+        }
+    }
+
+    fn digest(round_number: i64) -> Self {
+        Self {
+            kind: AlterExplorationStepKind::Digest,
+            round_number: Some(round_number),
+            alter_id: None,
+        }
+    }
+
+    fn synthesis() -> Self {
+        Self {
+            kind: AlterExplorationStepKind::Synthesis,
+            round_number: None,
+            alter_id: None,
+        }
+    }
+
+    fn event(
+        &self,
+        session_id: &str,
+        chunk: String,
+        error: Option<String>,
+    ) -> AlterExplorationStepEvent {
+        AlterExplorationStepEvent {
+            session_id: session_id.to_string(),
+            step: self.kind,
+            round_number: self.round_number,
+            alter_id: self.alter_id.map(str::to_string),
+            chunk,
+            error,
+            profile_id: None,
+        }
+    }
 }
 
 fn cancel_registry() -> &'static Mutex<HashSet<String>> {
@@ -169,6 +271,32 @@ fn is_cancelled(session_id: &str) -> bool {
         .unwrap_or(false)
 }
 
+fn running_registry() -> &'static Mutex<HashSet<String>> {
+    RUNNING_SESSIONS.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+/// Marks a session as run by this process until the returned guard is dropped.
+///
+/// Returns `None` while another task runs it. A session saved as `Running` but absent
+/// from the registry was left behind by a run that never finished (crash, quit).
+fn claim_running(session_id: &str) -> Option<RunningSession> {
+    let claimed = running_registry()
+        .lock()
+        .map(|mut guard| guard.insert(session_id.to_string()))
+        .unwrap_or(true);
+    claimed.then(|| RunningSession(session_id.to_string()))
+}
+
+struct RunningSession(String);
+
+impl Drop for RunningSession {
+    fn drop(&mut self) {
+        if let Ok(mut guard) = running_registry().lock() {
+            guard.remove(&self.0);
+        }
+    }
+}
+
 fn next_id(prefix: &str) -> String {
     format!("{prefix}-{}-{}", now_ms(), next_index_run_id())
 }
@@ -259,7 +387,7 @@ fn parse_context_source_paths(source_id: Option<&str>) -> Vec<String> {
     out
 }
 
-fn truncate_text_for_tokens(text: &str, max_tokens: usize) -> String {
+fn truncate_text_for_tokens(text: &str, max_tokens: usize, marker: &str) -> String {
     if max_tokens == 0 {
         return String::new();
     }
@@ -268,16 +396,16 @@ fn truncate_text_for_tokens(text: &str, max_tokens: usize) -> String {
     }
 
     let max_chars = max_tokens.saturating_mul(4);
-    let marker_len = TRUNCATION_MARKER.chars().count();
+    let marker_len = marker.chars().count();
     if max_chars <= marker_len + 32 {
-        return TRUNCATION_MARKER.trim().to_string();
+        return marker.trim().to_string();
     }
 
     let keep_each = (max_chars - marker_len) / 2;
     let start: String = text.chars().take(keep_each).collect();
     let end_vec: Vec<char> = text.chars().rev().take(keep_each).collect();
     let end: String = end_vec.into_iter().rev().collect();
-    format!("{start}{TRUNCATION_MARKER}{end}")
+    format!("{start}{marker}{end}")
 }
 
 fn load_exploration_context_entries(subject: &AlterExplorationSubject) -> Result<Vec<ExplorationContextEntry>> {
@@ -294,12 +422,17 @@ fn load_exploration_context_entries(subject: &AlterExplorationSubject) -> Result
     Ok(entries)
 }
 
-fn build_context_section(entries: &[ExplorationContextEntry], budget_tokens: usize) -> String {
+fn build_context_section(
+    entries: &[ExplorationContextEntry],
+    budget_tokens: usize,
+    pack: &PromptPack,
+) -> String {
     if entries.is_empty() || budget_tokens == 0 {
         return String::new();
     }
 
-    let mut section = String::from("Contextes fournis:\n");
+    let heading = pack.exploration.context_heading;
+    let mut section = format!("{heading}\n");
     let mut consumed = estimate_tokens(&section);
     for entry in entries {
         let remaining = budget_tokens.saturating_sub(consumed);
@@ -318,14 +451,15 @@ fn build_context_section(entries: &[ExplorationContextEntry], budget_tokens: usi
             break;
         }
 
-        let content = truncate_text_for_tokens(&entry.content, content_budget);
+        let content =
+            truncate_text_for_tokens(&entry.content, content_budget, pack.truncation_marker);
         section.push_str(&header);
         section.push_str(&content);
         section.push('\n');
         consumed = consumed.saturating_add(estimate_tokens(&header) + estimate_tokens(&content));
     }
 
-    if section.trim() == "Contextes fournis:" {
+    if section.trim() == heading {
         String::new()
     } else {
         section.trim().to_string()
@@ -342,7 +476,7 @@ fn exploration_path(session_id: &str) -> Result<PathBuf> {
     Ok(explorations_dir()?.join(format!("{normalized}.json")))
 }
 
-fn list_exploration_sessions() -> Result<Vec<AlterExplorationSession>> {
+pub(crate) fn list_exploration_sessions() -> Result<Vec<AlterExplorationSession>> {
     let dir = explorations_dir()?;
     if !dir.is_dir() {
         return Ok(Vec::new());
@@ -383,7 +517,18 @@ fn write_session_file(session: &AlterExplorationSession) -> Result<()> {
             file.sync_all()?;
             Ok(())
         })
-        .map_err(|_: atomicwrites::Error<std::io::Error>| AppError::OperationFailed)
+        .map_err(|_: atomicwrites::Error<std::io::Error>| AppError::OperationFailed)?;
+    index_for_search(session);
+    Ok(())
+}
+
+/// Keeps conversation search in sync; indexing is best effort and never fails the write.
+fn index_for_search(session: &AlterExplorationSession) {
+    if let Ok(conn) = open_db() {
+        if ensure_index_schema(&conn).is_ok() {
+            let _ = index_alter_exploration(&conn, session);
+        }
+    }
 }
 
 fn ensure_valid_payload(payload: &CreateAlterExplorationPayload) -> Result<()> {
@@ -443,74 +588,57 @@ fn load_invocations(alter_ids: &[String]) -> Result<Vec<AlterInvocation>> {
     Ok(out)
 }
 
-fn resolve_model_role_profile<'a>(
-    config: &'a SecondBrainConfig,
-    role: ExplorationModelRole,
-) -> Result<&'a ProviderProfile> {
-    let target = match role {
-        ExplorationModelRole::Basic => "gpt-oss-20b",
-        ExplorationModelRole::Normal => "gpt-oss-120b",
-    };
-    let mut candidate = config
-        .profiles
-        .iter()
-        .find(|profile| profile.model.to_lowercase().contains(target) && profile.capabilities.text);
-    if candidate.is_none() {
-        candidate = active_profile(config).filter(|profile| profile.capabilities.text);
-    }
-    if candidate.is_none() {
-        candidate = config.profiles.iter().find(|profile| profile.capabilities.text);
-    }
-    candidate.ok_or_else(|| {
+/// Resolves the profile configured for `role`, falling back to the active profile.
+fn resolve_model_role_profile(
+    config: &SecondBrainConfig,
+    role: ModelRole,
+) -> Result<&ProviderProfile> {
+    role_profile(config, role).ok_or_else(|| {
         AppError::InvalidOperation("No text-capable model profile available.".to_string())
     })
 }
 
-fn mode_guidance(mode: &AlterExplorationMode) -> &'static str {
+fn mode_guidance(mode: &AlterExplorationMode, pack: &PromptPack) -> &'static str {
+    let texts = &pack.exploration;
     match mode {
-        AlterExplorationMode::Challenge => {
-            "Expose weaknesses, contradictions, and fragile assumptions."
-        }
-        AlterExplorationMode::Explore => {
-            "Widen the space of interpretation and surface alternatives."
-        }
-        AlterExplorationMode::Decide => {
-            "Surface trade-offs and converge on a direction."
-        }
-        AlterExplorationMode::Refine => {
-            "Improve the draft through confrontation and targeted adjustments."
-        }
+        AlterExplorationMode::Challenge => texts.challenge_guidance,
+        AlterExplorationMode::Explore => texts.explore_guidance,
+        AlterExplorationMode::Decide => texts.decide_guidance,
+        AlterExplorationMode::Refine => texts.refine_guidance,
     }
 }
 
-fn output_format_guidance(format: &AlterExplorationOutputFormat) -> &'static str {
+fn output_format_guidance(
+    format: &AlterExplorationOutputFormat,
+    pack: &PromptPack,
+) -> &'static str {
+    let texts = &pack.exploration;
     match format {
-        AlterExplorationOutputFormat::Summary => {
-            "Provide a concise narrative summary with key agreements, disagreements, trade-offs, and a recommended next step."
-        }
-        AlterExplorationOutputFormat::TensionMap => {
-            "Return a structured tension map with: Agreements, Disagreements, Unresolved Issues, Trade-offs, and Next Step."
-        }
-        AlterExplorationOutputFormat::DecisionBrief => {
-            "Return a decision brief with: Options, Risks, Preferred Path, and Rationale."
-        }
-        AlterExplorationOutputFormat::RefinedProposal => {
-            "Rewrite the subject into a refined proposal, then list the key changes applied."
-        }
+        AlterExplorationOutputFormat::Summary => texts.summary_format,
+        AlterExplorationOutputFormat::TensionMap => texts.tension_map_format,
+        AlterExplorationOutputFormat::DecisionBrief => texts.decision_brief_format,
+        AlterExplorationOutputFormat::RefinedProposal => texts.refined_proposal_format,
     }
 }
 
+fn subject_type_label(subject: &AlterExplorationSubject) -> String {
+    format!("{:?}", subject.subject_type).to_lowercase()
+}
+
 fn round1_prompt(
     subject: &AlterExplorationSubject,
     mode: &AlterExplorationMode,
     context_section: &str,
+    pack: &PromptPack,
 ) -> String {
-    format!(
-        "Alter Exploration Mode (Round 1)\nMode guidance: {}\n\nSubject ({}):\n{}\n\n{}\n\nInstructions:\n- Provide your reading of the subject.\n- State your main concern.\n- State your main recommendation.\n\nConstraints:\n- Keep each section short and dense.\n- Do not roleplay.\n\nResponse format:\nReading: ...\nConcern: ...\nRecommendation: ...",
-        mode_guidance(mode),
-        format!("{:?}", subject.subject_type).to_lowercase(),
-        subject.text.trim(),
-        context_section
+    fill_template(
+        pack.exploration.round1_template,
+        &[
+            ("mode_guidance", mode_guidance(mode, pack)),
+            ("subject_type", &subject_type_label(subject)),
+            ("subject", subject.text.trim()),
+            ("context", context_section),
+        ],
     )
 }
 
@@ -521,18 +649,19 @@ fn round2_prompt(
     target_name: &str,
     target_content: &str,
     round_digest: &str,
+    pack: &PromptPack,
 ) -> String {
-    format!(
-        "Alter Exploration Mode (Round 2)\nMode guidance: {}\n\nSubject ({}):\n{}\n\n{}\n\nRound 1 digest:\n{}\n\nYou must react to {}'s position below. Reference them explicitly.\n\n--- {} position ---\n{}\n\nInstructions:\n- Respond with agreement, disagreement, or refinement.\n- Add something new; no restating your own Round 1.\n\nResponse format:\nReaction (to {}): ...\nAgreement/Disagreement: ...\nAdjustment: ...",
-        mode_guidance(mode),
-        format!("{:?}", subject.subject_type).to_lowercase(),
-        subject.text.trim(),
-        context_section,
-        round_digest.trim(),
-        target_name,
-        target_name,
-        target_content.trim(),
-        target_name
+    fill_template(
+        pack.exploration.round2_template,
+        &[
+            ("mode_guidance", mode_guidance(mode, pack)),
+            ("subject_type", &subject_type_label(subject)),
+            ("subject", subject.text.trim()),
+            ("context", context_section),
+            ("digest", round_digest.trim()),
+            ("target", target_name),
+            ("target_content", target_content.trim()),
+        ],
     )
 }
 
@@ -541,14 +670,17 @@ fn round3_prompt(
     mode: &AlterExplorationMode,
     context_section: &str,
     tension_digest: &str,
+    pack: &PromptPack,
 ) -> String {
-    format!(
-        "Alter Exploration Mode (Round 3)\nMode guidance: {}\n\nSubject ({}):\n{}\n\n{}\n\nRound 2 tension digest:\n{}\n\nInstructions:\n- State what you now see as the strongest point.\n- State what remains unresolved.\n- State what should happen next.\n\nResponse format:\nStrongest point: ...\nUnresolved: ...\nNext step: ...",
-        mode_guidance(mode),
-        format!("{:?}", subject.subject_type).to_lowercase(),
-        subject.text.trim(),
-        context_section,
-        tension_digest.trim()
+    fill_template(
+        pack.exploration.round3_template,
+        &[
+            ("mode_guidance", mode_guidance(mode, pack)),
+            ("subject_type", &subject_type_label(subject)),
+            ("subject", subject.text.trim()),
+            ("context", context_section),
+            ("digest", tension_digest.trim()),
+        ],
     )
 }
 
@@ -558,20 +690,33 @@ fn synthesis_prompt(
     output_format: &AlterExplorationOutputFormat,
     context_section: &str,
     rounds_text: &str,
+    pack: &PromptPack,
 ) -> String {
-    format!(
-        "You are the silent moderator. Produce the final artifact.\nMode guidance: {}\nOutput format: {}\n\nSubject ({}):\n{}\n\n{}\n\nRound results:\n{}\n\nConstraints:\n- Be actionable.\n- Keep it concise and dense.\n- Do not roleplay.\n\n{}",
-        mode_guidance(mode),
-        format!("{:?}", output_format).to_lowercase(),
-        format!("{:?}", subject.subject_type).to_lowercase(),
-        subject.text.trim(),
-        context_section,
-        rounds_text.trim(),
-        output_format_guidance(output_format)
+    fill_template(
+        pack.exploration.synthesis_template,
+        &[
+            ("mode_guidance", mode_guidance(mode, pack)),
+            (
+                "output_format",
+                &format!("{:?}", output_format).to_lowercase(),
+            ),
+            ("subject_type", &subject_type_label(subject)),
+            ("subject", subject.text.trim()),
+            ("context", context_section),
+            ("rounds", rounds_text.trim()),
+            (
+                "format_guidance",
+                output_format_guidance(output_format, pack),
+            ),
+        ],
     )
 }
 
-fn render_round_results(round_results: &[AlterRoundResult], alter_names: &HashMap<String, String>) -> String {
+fn render_round_results(
+    round_results: &[AlterRoundResult],
+    alter_names: &HashMap<String, String>,
+    pack: &PromptPack,
+) -> String {
     let mut grouped: HashMap<i64, Vec<&AlterRoundResult>> = HashMap::new();
     for result in round_results {
         grouped.entry(result.round_number).or_default().push(result);
@@ -580,7 +725,11 @@ fn render_round_results(round_results: &[AlterRoundResult], alter_names: &HashMa
     rounds.sort();
     let mut out = String::new();
     for round in rounds {
-        out.push_str(&format!("Round {round}:\n"));
+        out.push_str(&fill_template(
+            pack.exploration.round_heading_template,
+            &[("round", &round.to_string())],
+        ));
+        out.push('\n');
         if let Some(items) = grouped.get(&round) {
             for item in items {
                 let name = alter_names
@@ -595,47 +744,6 @@ fn render_round_results(round_results: &[AlterRoundResult], alter_names: &HashMa
     out.trim().to_string()
 }
 
-#[cfg(test)]
-fn mock_llm_queue() -> &'static Mutex<VecDeque<String>> {
-    static MOCK: OnceLock<Mutex<VecDeque<String>>> = OnceLock::new();
-    MOCK.get_or_init(|| Mutex::new(VecDeque::new()))
-}
-
-#[cfg(test)]
-fn set_mock_llm_responses(responses: Vec<&str>) {
-    let mut guard = mock_llm_queue().lock().expect("mock queue");
-    guard.clear();
-    for response in responses {
-        guard.push_back(response.to_string());
-    }
-}
-
-#[cfg(test)]
-async fn run_llm_for_exploration(
-    _profile: &ProviderProfile,
-    _system_prompt: &str,
-    _user_prompt: &str,
-    _temperature: Option<f64>,
-) -> std::result::Result<String, String> {
-    let mut guard = mock_llm_queue().lock().expect("mock queue");
-    Ok(guard
-        .pop_front()
-        .unwrap_or_else(|| "mock-response".to_string()))
-}
-
-#[cfg(not(test))]
-use crate::second_brain::llm::run_llm;
-
-#[cfg(not(test))]
-async fn run_llm_for_exploration(
-    profile: &ProviderProfile,
-    system_prompt: &str,
-    user_prompt: &str,
-    temperature: Option<f64>,
-) -> std::result::Result<String, String> {
-    run_llm(profile, system_prompt, user_prompt, temperature).await
-}
-
 fn summarize_round_results(round_results: &[AlterRoundResult]) -> String {
     let mut out = String::new();
     for item in round_results {
@@ -657,45 +765,150 @@ fn fail_session(session: &mut AlterExplorationSession, message: &str) -> Result<
     Ok(())
 }
 
+/// Stops the exploration between steps when a cancellation was requested.
+fn ensure_not_cancelled(session: &mut AlterExplorationSession) -> Result<()> {
+    if !is_cancelled(&session.id) {
+        return Ok(());
+    }
+    fail_session(session, CANCELLED_MESSAGE)?;
+    clear_cancelled(&session.id);
+    Err(AppError::InvalidOperation(CANCELLED_MESSAGE.to_string()))
+}
+
+fn has_round_result(session: &AlterExplorationSession, round_number: i64, alter_id: &str) -> bool {
+    session
+        .round_results
+        .iter()
+        .any(|item| item.round_number == round_number && item.alter_id == alter_id)
+}
+
+fn push_round_result(
+    session: &mut AlterExplorationSession,
+    result: AlterRoundResult,
+) -> Result<()> {
+    session.round_results.push(result);
+    session.updated_at_ms = now_ms();
+    write_session_file(session)
+}
+
+/// Runs one LLM call and emits its start, delta, complete and error events.
+///
+/// A cancellation requested while the step streams aborts it at the next chunk.
 async fn run_llm_step(
     session: &mut AlterExplorationSession,
-    profile: &ProviderProfile,
+    step: ExplorationStep<'_>,
+    emit: StepEmitter<'_>,
+    route: &LlmRoute,
     system_prompt: &str,
     user_prompt: &str,
     temperature: Option<f64>,
 ) -> Result<String> {
-    match run_llm_for_exploration(profile, system_prompt, user_prompt, temperature).await {
-        Ok(output) => Ok(output),
-        Err(_) => {
-            let _ = fail_session(session, "Exploration request failed.");
-            clear_cancelled(&session.id);
-            Err(AppError::InvalidOperation(
-                "Exploration request failed.".to_string(),
-            ))
+    let session_id = session.id.clone();
+    emit(
+        STEP_START_EVENT,
+        step.event(&session_id, String::new(), None),
+    );
+    let on_chunk = |profile: &ProviderProfile, chunk: &str| {
+        if is_cancelled(&session_id) {
+            return Err(CANCELLED_MESSAGE.to_string());
+        }
+        emit(
+            STEP_DELTA_EVENT,
+            AlterExplorationStepEvent {
+                profile_id: Some(profile.id.clone()),
+                ..step.event(&session_id, chunk.to_string(), None)
+            },
+        );
+        Ok(())
+    };
+    let result = run_llm_stream(
+        route,
+        LlmFeature::Exploration,
+        system_prompt,
+        user_prompt,
+        temperature,
+        on_chunk,
+    )
+    .await;
+    // A partial step output would skew the next rounds, so interrupted streams fail.
+    match result {
+        Ok(reply) if reply.interrupted.is_none() => {
+            emit(
+                STEP_COMPLETE_EVENT,
+                AlterExplorationStepEvent {
+                    profile_id: Some(reply.profile_id),
+                    ..step.event(&session_id, reply.text.clone(), None)
+                },
+            );
+            Ok(reply.text)
+        }
+        _ => {
+            let message = if is_cancelled(&session_id) {
+                CANCELLED_MESSAGE
+            } else {
+                REQUEST_FAILED_MESSAGE
+            };
+            emit(
+                STEP_ERROR_EVENT,
+                step.event(&session_id, String::new(), Some(message.to_string())),
+            );
+            let _ = fail_session(session, message);
+            clear_cancelled(&session_id);
+            Err(AppError::InvalidOperation(message.to_string()))
         }
     }
 }
 
+/// Returns the digest of `round_number`, computing and persisting it when missing.
+async fn resolve_round_digest(
+    session: &mut AlterExplorationSession,
+    round_number: i64,
+    emit: StepEmitter<'_>,
+    route: &LlmRoute,
+    system_prompt: &str,
+) -> Result<String> {
+    if let Some(existing) = session
+        .round_digests
+        .iter()
+        .find(|item| item.round_number == round_number)
+    {
+        return Ok(existing.content.clone());
+    }
+    let summary = summarize_round_results(
+        &session
+            .round_results
+            .iter()
+            .filter(|item| item.round_number == round_number)
+            .cloned()
+            .collect::<Vec<_>>(),
+    );
+    let digest = run_llm_step(
+        session,
+        ExplorationStep::digest(round_number),
+        emit,
+        route,
+        system_prompt,
+        &summary,
+        None,
+    )
+    .await?;
+    session.round_digests.push(AlterRoundDigest {
+        round_number,
+        content: digest.clone(),
+    });
+    session.updated_at_ms = now_ms();
+    write_session_file(session)?;
+    Ok(digest)
+}
+
 async fn run_exploration(
-    mut session: AlterExplorationSession,
+    session: AlterExplorationSession,
+    resume: bool,
+    emit: StepEmitter<'_>,
 ) -> Result<AlterExplorationSession> {
-    if matches!(session.state, AlterExplorationState::Running) {
-        return Err(AppError::InvalidOperation(
-            "Exploration session is already running.".to_string(),
-        ));
-    }
     if matches!(session.state, AlterExplorationState::Completed) {
         return Ok(session);
     }
-
-    clear_cancelled(&session.id);
-    session.state = AlterExplorationState::Running;
-    session.round_results.clear();
-    session.final_synthesis = None;
-    session.error_message = None;
-    session.updated_at_ms = now_ms();
-    write_session_file(&session)?;
-
     let config = settings::load_llm_for_runtime().map_err(|err| {
         if matches!(err, AppError::InvalidOperation(_)) {
             err
@@ -703,9 +916,38 @@ async fn run_exploration(
             AppError::InvalidOperation("Second Brain configuration is unavailable.".to_string())
         }
     })?;
+    run_exploration_with_config(session, &config, resume, emit).await
+}
+
+/// Runs the missing steps of an exploration, persisting each result as it arrives.
+///
+/// Without `resume`, or when the session neither failed nor was left `Running` by an
+/// interrupted run, previous results are discarded.
+async fn run_exploration_with_config(
+    mut session: AlterExplorationSession,
+    config: &SecondBrainConfig,
+    resume: bool,
+    emit: StepEmitter<'_>,
+) -> Result<AlterExplorationSession> {
+    if matches!(session.state, AlterExplorationState::Completed) {
+        return Ok(session);
+    }
+    let Some(_running) = claim_running(&session.id) else {
+        return Err(AppError::InvalidOperation(
+            "Exploration session is already running.".to_string(),
+        ));
+    };
 
-    let basic_profile = resolve_model_role_profile(&config, ExplorationModelRole::Basic)?;
-    let normal_profile = resolve_model_role_profile(&config, ExplorationModelRole::Normal)?;
+    let fast_route = role_route(
+        config,
+        resolve_model_role_profile(config, ModelRole::Fast)?,
+        ModelRole::Fast,
+    );
+    let deep_route = role_route(
+        config,
+        resolve_model_role_profile(config, ModelRole::Deep)?,
+        ModelRole::Deep,
+    );
     let invocations = load_invocations(&session.alter_ids)?;
     let alter_names: HashMap<String, String> = invocations
         .iter()
@@ -715,62 +957,75 @@ async fn run_exploration(
     let subject = session.subject.clone();
     let mode = session.mode.clone();
     let context_entries = load_exploration_context_entries(&subject)?;
-    let context_section = build_context_section(&context_entries, CONTEXT_PROMPT_BUDGET_TOKENS);
+    let pack = select_prompt_pack(
+        config.prompt_language,
+        &std::iter::once(subject.text.as_str())
+            .chain(context_entries.iter().map(|entry| entry.content.as_str()))
+            .collect::<Vec<_>>(),
+        PromptLocale::En,
+    );
+    let context_section =
+        build_context_section(&context_entries, CONTEXT_PROMPT_BUDGET_TOKENS, pack);
+
+    let resuming = resume
+        && matches!(
+            session.state,
+            AlterExplorationState::Failed | AlterExplorationState::Running
+        );
+    clear_cancelled(&session.id);
+    session.state = AlterExplorationState::Running;
+    if !resuming {
+        session.round_results.clear();
+        session.round_digests.clear();
+    }
+    session.final_synthesis = None;
+    session.error_message = None;
+    session.updated_at_ms = now_ms();
+    write_session_file(&session)?;
 
     for alter in &invocations {
-        if is_cancelled(&session.id) {
-            fail_session(&mut session, "Exploration cancelled.")?;
-            clear_cancelled(&session.id);
-            return Err(AppError::InvalidOperation("Exploration cancelled.".to_string()));
+        if has_round_result(&session, 1, &alter.id) {
+            continue;
         }
-        let prompt = round1_prompt(&subject, &mode, &context_section);
+        ensure_not_cancelled(&mut session)?;
+        let prompt = round1_prompt(&subject, &mode, &context_section, pack);
         let response = run_llm_step(
             &mut session,
-            normal_profile,
+            ExplorationStep::round(1, &alter.id),
+            emit,
+            &deep_route,
             &alter.invocation_prompt,
             &prompt,
             Some(alter.temperature),
         )
         .await?;
-        session.round_results.push(AlterRoundResult {
-            round_number: 1,
-            alter_id: alter.id.clone(),
-            content: response,
-            references_alter_ids: Vec::new(),
-        });
-        session.updated_at_ms = now_ms();
-        write_session_file(&session)?;
-    }
-
-    if is_cancelled(&session.id) {
-        fail_session(&mut session, "Exploration cancelled.")?;
-        clear_cancelled(&session.id);
-        return Err(AppError::InvalidOperation("Exploration cancelled.".to_string()));
+        push_round_result(
+            &mut session,
+            AlterRoundResult {
+                round_number: 1,
+                alter_id: alter.id.clone(),
+                content: response,
+                references_alter_ids: Vec::new(),
+                created_at_ms: now_ms(),
+            },
+        )?;
     }
 
-    let round1_summary = summarize_round_results(
-        &session
-            .round_results
-            .iter()
-            .filter(|item| item.round_number == 1)
-            .cloned()
-            .collect::<Vec<_>>(),
-    );
-    let round1_digest = run_llm_step(
+    ensure_not_cancelled(&mut session)?;
+    let round1_digest = resolve_round_digest(
         &mut session,
-        basic_profile,
-        "You are a silent moderator. Summarize Round 1 into concise bullets highlighting overlaps and differences.",
-        &round1_summary,
-        None,
+        1,
+        emit,
+        &fast_route,
+        pack.exploration.round1_digest_system,
     )
     .await?;
 
     for (index, alter) in invocations.iter().enumerate() {
-        if is_cancelled(&session.id) {
-            fail_session(&mut session, "Exploration cancelled.")?;
-            clear_cancelled(&session.id);
-            return Err(AppError::InvalidOperation("Exploration cancelled.".to_string()));
+        if has_round_result(&session, 2, &alter.id) {
+            continue;
         }
+        ensure_not_cancelled(&mut session)?;
         let target_index = (index + 1) % invocations.len();
         let target = &invocations[target_index];
         let target_content = session
@@ -786,87 +1041,87 @@ async fn run_exploration(
             &target.name,
             &target_content,
             &round1_digest,
+            pack,
         );
         let response = run_llm_step(
             &mut session,
-            normal_profile,
+            ExplorationStep::round(2, &alter.id),
+            emit,
+            &deep_route,
             &alter.invocation_prompt,
             &prompt,
             Some(alter.temperature),
         )
         .await?;
-        session.round_results.push(AlterRoundResult {
-            round_number: 2,
-            alter_id: alter.id.clone(),
-            content: response,
-            references_alter_ids: vec![target.id.clone()],
-        });
-        session.updated_at_ms = now_ms();
-        write_session_file(&session)?;
+        push_round_result(
+            &mut session,
+            AlterRoundResult {
+                round_number: 2,
+                alter_id: alter.id.clone(),
+                content: response,
+                references_alter_ids: vec![target.id.clone()],
+                created_at_ms: now_ms(),
+            },
+        )?;
     }
 
     if session.rounds == 3 {
-        let round2_summary = summarize_round_results(
-            &session
-                .round_results
-                .iter()
-                .filter(|item| item.round_number == 2)
-                .cloned()
-                .collect::<Vec<_>>(),
-        );
-        let round2_digest = run_llm_step(
+        ensure_not_cancelled(&mut session)?;
+        let round2_digest = resolve_round_digest(
             &mut session,
-            basic_profile,
-            "You are a silent moderator. Summarize Round 2 into the key tensions and convergences.",
-            &round2_summary,
-            None,
+            2,
+            emit,
+            &fast_route,
+            pack.exploration.round2_digest_system,
         )
         .await?;
 
         for alter in &invocations {
-            if is_cancelled(&session.id) {
-                fail_session(&mut session, "Exploration cancelled.")?;
-                clear_cancelled(&session.id);
-                return Err(AppError::InvalidOperation("Exploration cancelled.".to_string()));
+            if has_round_result(&session, 3, &alter.id) {
+                continue;
             }
-            let prompt = round3_prompt(&subject, &mode, &context_section, &round2_digest);
+            ensure_not_cancelled(&mut session)?;
+            let prompt = round3_prompt(&subject, &mode, &context_section, &round2_digest, pack);
             let response = run_llm_step(
                 &mut session,
-                normal_profile,
+                ExplorationStep::round(3, &alter.id),
+                emit,
+                &deep_route,
                 &alter.invocation_prompt,
                 &prompt,
                 Some(alter.temperature),
             )
             .await?;
-            session.round_results.push(AlterRoundResult {
-                round_number: 3,
-                alter_id: alter.id.clone(),
-                content: response,
-                references_alter_ids: Vec::new(),
-            });
-            session.updated_at_ms = now_ms();
-            write_session_file(&session)?;
+            push_round_result(
+                &mut session,
+                AlterRoundResult {
+                    round_number: 3,
+                    alter_id: alter.id.clone(),
+                    content: response,
+                    references_alter_ids: Vec::new(),
+                    created_at_ms: now_ms(),
+                },
+            )?;
         }
     }
 
-    if is_cancelled(&session.id) {
-        fail_session(&mut session, "Exploration cancelled.")?;
-        clear_cancelled(&session.id);
-        return Err(AppError::InvalidOperation("Exploration cancelled.".to_string()));
-    }
+    ensure_not_cancelled(&mut session)?;
 
-    let rounds_text = render_round_results(&session.round_results, &alter_names);
+    let rounds_text = render_round_results(&session.round_results, &alter_names, pack);
     let synth_prompt = synthesis_prompt(
         &subject,
         &mode,
         &session.output_format,
         &context_section,
         &rounds_text,
+        pack,
     );
     let synthesis = run_llm_step(
         &mut session,
-        normal_profile,
-        "You are the exploration moderator.",
+        ExplorationStep::synthesis(),
+        emit,
+        &deep_route,
+        pack.exploration.synthesis_system,
         &synth_prompt,
         None,
     )
@@ -914,6 +1169,7 @@ pub fn create_alter_exploration_session(
         output_format: payload.output_format,
         state: AlterExplorationState::Draft,
         round_results: Vec::new(),
+        round_digests: Vec::new(),
         final_synthesis: None,
         error_message: None,
         created_at_ms: ts,
@@ -941,8 +1197,10 @@ pub fn list_alter_exploration_sessions() -> Result<Vec<AlterExplorationSession>>
     list_exploration_sessions()
 }
 
+/// Runs an exploration and reports its progress through `alter-exploration://step-*` events.
 #[tauri::command]
 pub async fn run_alter_exploration_session(
+    app: AppHandle,
     payload: RunAlterExplorationPayload,
 ) -> Result<AlterExplorationSession> {
     let path = exploration_path(&payload.session_id)?;
@@ -952,7 +1210,10 @@ pub async fn run_alter_exploration_session(
         ));
     }
     let session = read_session_file(&path)?;
-    run_exploration(session).await
+    let emit = move |event: &str, step: AlterExplorationStepEvent| {
+        let _ = app.emit(event, step);
+    };
+    run_exploration(session, payload.resume, &emit).await
 }
 
 #[tauri::command]
@@ -967,6 +1228,8 @@ pub fn cancel_alter_exploration_session(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::second_brain::config::{MockProviderSettings, MockResponse, ModelRoles};
+    use crate::second_brain::prompt_packs::prompt_pack;
     use std::fs;
 
     struct TestWorkspace {
@@ -1024,10 +1287,11 @@ mod tests {
             profiles: vec![
                 ProviderProfile {
                     id: "p1".to_string(),
-                    label: "Basic".to_string(),
-                    provider: "openai".to_string(),
-                    model: "gpt-oss-20b".to_string(),
+                    label: "Fast".to_string(),
+                    provider: "groq".to_string(),
+                    model: "llama-3.1-8b-instant".to_string(),
                     api_key: "x".to_string(),
+                    key_source: Default::default(),
                     default_temperature: 0.15,
                     system_prompt: String::new(),
                     base_url: None,
@@ -1039,13 +1303,16 @@ mod tests {
                         tool_calling: false,
                         streaming: true,
                     },
+                    mock: None,
+                    budget: None,
                 },
                 ProviderProfile {
                     id: "p2".to_string(),
-                    label: "Normal".to_string(),
-                    provider: "openai".to_string(),
-                    model: "gpt-oss-120b".to_string(),
+                    label: "Deep".to_string(),
+                    provider: "anthropic".to_string(),
+                    model: "claude-sonnet-4-5".to_string(),
                     api_key: "x".to_string(),
+                    key_source: Default::default(),
                     default_temperature: 0.15,
                     system_prompt: String::new(),
                     base_url: None,
@@ -1057,13 +1324,22 @@ mod tests {
                         tool_calling: false,
                         streaming: true,
                     },
+                    mock: None,
+                    budget: None,
                 },
             ],
+            prompt_language: None,
+            model_roles: ModelRoles {
+                fast: Some("p1".to_string()),
+                ..ModelRoles::default()
+            },
+            model_fallbacks: Default::default(),
+            request_policy: Default::default(),
         };
-        let basic = resolve_model_role_profile(&config, ExplorationModelRole::Basic).unwrap();
-        assert_eq!(basic.id, "p1");
-        let normal = resolve_model_role_profile(&config, ExplorationModelRole::Normal).unwrap();
-        assert_eq!(normal.id, "p2");
+        let fast = resolve_model_role_profile(&config, ModelRole::Fast).unwrap();
+        assert_eq!(fast.id, "p1");
+        let deep = resolve_model_role_profile(&config, ModelRole::Deep).unwrap();
+        assert_eq!(deep.id, "p2");
     }
 
     #[test]
@@ -1089,6 +1365,7 @@ mod tests {
                 output_format: payload.output_format,
                 state: AlterExplorationState::Draft,
                 round_results: Vec::new(),
+                round_digests: Vec::new(),
                 final_synthesis: None,
                 error_message: None,
                 created_at_ms: ts,
@@ -1104,18 +1381,49 @@ mod tests {
 
     #[test]
     fn builds_round_prompts() {
+        let pack = prompt_pack(PromptLocale::En);
         let subject = sample_subject();
         let mode = AlterExplorationMode::Challenge;
-        let context_section = "Contextes fournis:\n--- SOURCE: notes/a.md ---\nEvidence from note";
-        let round1 = round1_prompt(&subject, &mode, context_section);
+        let context_section = "Provided context:\n--- SOURCE: notes/a.md ---\nEvidence from note";
+        let round1 = round1_prompt(&subject, &mode, context_section, pack);
         assert!(round1.contains("Round 1"));
         assert!(round1.contains("Evidence from note"));
-        let round2 = round2_prompt(&subject, &mode, context_section, "Alter A", "content", "digest");
+        let round2 = round2_prompt(
+            &subject,
+            &mode,
+            context_section,
+            "Alter A",
+            "content",
+            "digest",
+            pack,
+        );
         assert!(round2.contains("Alter A"));
-        let round3 = round3_prompt(&subject, &mode, context_section, "tension");
+        assert!(round2.contains("--- Alter A position ---\ncontent"));
+        let round3 = round3_prompt(&subject, &mode, context_section, "tension", pack);
         assert!(round3.contains("Round 3"));
-        let synth = synthesis_prompt(&subject, &mode, &AlterExplorationOutputFormat::Summary, context_section, "rounds");
+        let synth = synthesis_prompt(
+            &subject,
+            &mode,
+            &AlterExplorationOutputFormat::Summary,
+            context_section,
+            "rounds",
+            pack,
+        );
         assert!(synth.contains("Evidence from note"));
+        assert!(synth.contains("Output format: summary"));
+    }
+
+    #[test]
+    fn builds_french_round_prompts() {
+        let pack = prompt_pack(PromptLocale::Fr);
+        let subject = sample_subject();
+        let mode = AlterExplorationMode::Decide;
+        let round1 = round1_prompt(&subject, &mode, "", pack);
+        assert!(round1.contains("Tour 1"));
+        assert!(round1.contains("Fais ressortir les arbitrages"));
+        assert!(round1.contains("Should we add runtime blocks?"));
+        let round2 = round2_prompt(&subject, &mode, "", "Alter A", "content", "digest", pack);
+        assert!(round2.contains("--- Position de Alter A ---\ncontent"));
     }
 
     #[test]
@@ -1133,91 +1441,222 @@ mod tests {
             };
             let entries = load_exploration_context_entries(&subject)?;
             assert_eq!(entries.len(), 1);
-            let context = build_context_section(&entries, CONTEXT_PROMPT_BUDGET_TOKENS);
+            let context = build_context_section(
+                &entries,
+                CONTEXT_PROMPT_BUDGET_TOKENS,
+                prompt_pack(PromptLocale::Fr),
+            );
             assert!(context.contains("This note should be injected."));
             assert!(context.contains("SOURCE: notes/source.md"));
             Ok(())
         })
     }
 
+    fn create_test_alter(name: &str, mission: &str) -> Result<crate::alters::AlterRecord> {
+        crate::alters::create_alter(crate::alters::CreateAlterPayload {
+            name: name.to_string(),
+            description: "Test alter".to_string(),
+            icon: None,
+            color: None,
+            category: None,
+            mission: mission.to_string(),
+            inspirations: Vec::new(),
+            principles: vec!["Keep it short.".to_string()],
+            reflexes: Vec::new(),
+            values: Vec::new(),
+            critiques: Vec::new(),
+            blind_spots: Vec::new(),
+            system_hints: Vec::new(),
+            style: crate::alters::AlterStyle {
+                tone: "strategic".to_string(),
+                verbosity: "short".to_string(),
+                temperature: 0.1,
+                contradiction_level: 40,
+                exploration_level: 50,
+                influence_intensity: "balanced".to_string(),
+                response_style: "analytic".to_string(),
+                cite_hypotheses: false,
+                signal_biases: false,
+            },
+            is_favorite: false,
+        })
+    }
+
+    fn create_test_session() -> Result<AlterExplorationSession> {
+        let alter_a = create_test_alter("Alter A", "Provide pragmatic input.")?;
+        let alter_b = create_test_alter("Alter B", "Provide critical input.")?;
+        let session = create_alter_exploration_session(CreateAlterExplorationPayload {
+            subject: sample_subject(),
+            alter_ids: vec![alter_a.id, alter_b.id],
+            mode: AlterExplorationMode::Explore,
+            rounds: 2,
+            output_format: AlterExplorationOutputFormat::Summary,
+        })?;
+        read_session_file(&exploration_path(&session.id)?)
+    }
+
+    /// Config with one `mock` profile replying with `responses`, in order, without retries.
+    fn test_config(responses: &[&str]) -> SecondBrainConfig {
+        let profile_id = format!("mock-exploration-{}", next_index_run_id());
+        SecondBrainConfig {
+            active_profile: profile_id.clone(),
+            profiles: vec![ProviderProfile {
+                id: profile_id,
+                label: "Test".to_string(),
+                provider: "mock".to_string(),
+                model: "scripted".to_string(),
+                api_key: String::new(),
+                key_source: Default::default(),
+                default_temperature: 0.15,
+                system_prompt: String::new(),
+                base_url: None,
+                default_mode: None,
+                capabilities: crate::second_brain::config::ProfileCapabilities {
+                    text: true,
+                    streaming: true,
+                    ..Default::default()
+                },
+                mock: Some(MockProviderSettings {
+                    responses: responses
+                        .iter()
+                        .map(|text| MockResponse::Text(text.to_string()))
+                        .collect(),
+                    ..MockProviderSettings::default()
+                }),
+                budget: None,
+            }],
+            prompt_language: None,
+            model_roles: ModelRoles::default(),
+            model_fallbacks: Default::default(),
+            request_policy: crate::second_brain::config::RequestPolicy {
+                max_retries: 0,
+                ..Default::default()
+            },
+        }
+    }
+
     #[test]
     fn runs_happy_path_with_mocked_llm() -> Result<()> {
         use_test_workspace(|| {
-            set_mock_llm_responses(vec![
-                "r1-a",
-                "r1-b",
-                "digest1",
-                "r2-a",
-                "r2-b",
-                "final",
-            ]);
-            let alter_a = crate::alters::create_alter(crate::alters::CreateAlterPayload {
-                name: "Alter A".to_string(),
-                description: "Test alter".to_string(),
-                icon: None,
-                color: None,
-                category: None,
-                mission: "Provide pragmatic input.".to_string(),
-                inspirations: Vec::new(),
-                principles: vec!["Keep it short.".to_string()],
-                reflexes: Vec::new(),
-                values: Vec::new(),
-                critiques: Vec::new(),
-                blind_spots: Vec::new(),
-                system_hints: Vec::new(),
-                style: crate::alters::AlterStyle {
-                    tone: "strategic".to_string(),
-                    verbosity: "short".to_string(),
-                    temperature: 0.1,
-                    contradiction_level: 40,
-                    exploration_level: 50,
-                    influence_intensity: "balanced".to_string(),
-                    response_style: "analytic".to_string(),
-                    cite_hypotheses: false,
-                    signal_biases: false,
-                },
-                is_favorite: false,
-            })?;
-            let alter_b = crate::alters::create_alter(crate::alters::CreateAlterPayload {
-                name: "Alter B".to_string(),
-                description: "Test alter".to_string(),
-                icon: None,
-                color: None,
-                category: None,
-                mission: "Provide critical input.".to_string(),
-                inspirations: Vec::new(),
-                principles: vec!["Be direct.".to_string()],
-                reflexes: Vec::new(),
-                values: Vec::new(),
-                critiques: Vec::new(),
-                blind_spots: Vec::new(),
-                system_hints: Vec::new(),
-                style: crate::alters::AlterStyle {
-                    tone: "direct".to_string(),
-                    verbosity: "short".to_string(),
-                    temperature: 0.1,
-                    contradiction_level: 40,
-                    exploration_level: 50,
-                    influence_intensity: "balanced".to_string(),
-                    response_style: "analytic".to_string(),
-                    cite_hypotheses: false,
-                    signal_biases: false,
-                },
-                is_favorite: false,
-            })?;
-            let payload = CreateAlterExplorationPayload {
-                subject: sample_subject(),
-                alter_ids: vec![alter_a.id.clone(), alter_b.id.clone()],
-                mode: AlterExplorationMode::Explore,
-                rounds: 2,
-                output_format: AlterExplorationOutputFormat::Summary,
+            let session = create_test_session()?;
+            let events = Mutex::new(Vec::new());
+            let emit = |event: &str, step: AlterExplorationStepEvent| {
+                events
+                    .lock()
+                    .expect("events")
+                    .push((event.to_string(), step));
             };
-            let session = create_alter_exploration_session(payload)?;
-            let session = read_session_file(&exploration_path(&session.id)?)?;
-            let result = tauri::async_runtime::block_on(run_exploration(session))?;
+            let result = tauri::async_runtime::block_on(run_exploration_with_config(
+                session,
+                &test_config(&["r1-a", "r1-b", "digest1", "r2-a", "r2-b", "final"]),
+                false,
+                &emit,
+            ))?;
             assert!(matches!(result.state, AlterExplorationState::Completed));
             assert_eq!(result.round_results.len(), 4);
             assert_eq!(result.final_synthesis.as_deref(), Some("final"));
+
+            let events = events.into_inner().expect("events");
+            assert_eq!(events.len(), 18);
+            let (name, first) = &events[1];
+            assert_eq!(name, STEP_DELTA_EVENT);
+            assert_eq!(first.step, AlterExplorationStepKind::Round);
+            assert_eq!(first.round_number, Some(1));
+            assert_eq!(first.chunk, "r1-a");
+            let (name, last) = events.last().expect("last event");
+            assert_eq!(name, STEP_COMPLETE_EVENT);
+            assert_eq!(last.step, AlterExplorationStepKind::Synthesis);
+            assert_eq!(last.chunk, "final");
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn resumes_failed_exploration_from_last_completed_step() -> Result<()> {
+        use_test_workspace(|| {
+            let mut config = test_config(&["r1-a", "r1-b", "digest1", "r2-a"]);
+            if let Some(settings) = config.profiles[0].mock.as_mut() {
+                settings.responses.push(MockResponse::Scripted {
+                    text: String::new(),
+                    error: Some("connection reset".to_string()),
+                    error_after_chunks: 0,
+                });
+            }
+            let no_events = |_: &str, _: AlterExplorationStepEvent| {};
+            let session = create_test_session()?;
+            let session_id = session.id.clone();
+            let result = tauri::async_runtime::block_on(run_exploration_with_config(
+                session, &config, false, &no_events,
+            ));
+            assert!(result.is_err());
+
+            let failed = read_session_file(&exploration_path(&session_id)?)?;
+            assert!(matches!(failed.state, AlterExplorationState::Failed));
+            assert_eq!(failed.round_results.len(), 3);
+            assert_eq!(failed.round_digests.len(), 1);
+
+            let resumed = tauri::async_runtime::block_on(run_exploration_with_config(
+                failed,
+                &test_config(&["r2-b", "final"]),
+                true,
+                &no_events,
+            ))?;
+            assert!(matches!(resumed.state, AlterExplorationState::Completed));
+            let contents = resumed
+                .round_results
+                .iter()
+                .map(|item| item.content.as_str())
+                .collect::<Vec<_>>();
+            assert_eq!(contents, vec!["r1-a", "r1-b", "r2-a", "r2-b"]);
+            assert_eq!(resumed.final_synthesis.as_deref(), Some("final"));
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn resumes_a_session_left_running_by_an_interrupted_run() -> Result<()> {
+        use_test_workspace(|| {
+            let mut config = test_config(&["r1-a", "r1-b", "digest1", "r2-a"]);
+            if let Some(settings) = config.profiles[0].mock.as_mut() {
+                settings.responses.push(MockResponse::Scripted {
+                    text: String::new(),
+                    error: Some("connection reset".to_string()),
+                    error_after_chunks: 0,
+                });
+            }
+            let no_events = |_: &str, _: AlterExplorationStepEvent| {};
+            let session = create_test_session()?;
+            let session_id = session.id.clone();
+            let result = tauri::async_runtime::block_on(run_exploration_with_config(
+                session, &config, false, &no_events,
+            ));
+            assert!(result.is_err());
+
+            // The app quit mid-run: the file still says `Running` but no task owns it.
+            let mut stale = read_session_file(&exploration_path(&session_id)?)?;
+            stale.state = AlterExplorationState::Running;
+            write_session_file(&stale)?;
+
+            let live = claim_running(&session_id).expect("claim session");
+            let refused = tauri::async_runtime::block_on(run_exploration_with_config(
+                stale.clone(),
+                &test_config(&[]),
+                true,
+                &no_events,
+            ));
+            assert!(matches!(refused, Err(AppError::InvalidOperation(_))));
+            drop(live);
+
+            let resumed = tauri::async_runtime::block_on(run_exploration_with_config(
+                stale,
+                &test_config(&["r2-b", "final"]),
+                true,
+                &no_events,
+            ))?;
+            assert!(matches!(resumed.state, AlterExplorationState::Completed));
+            assert_eq!(resumed.round_results.len(), 4);
+            assert_eq!(resumed.final_synthesis.as_deref(), Some("final"));
+            assert!(claim_running(&session_id).is_some());
             Ok(())
         })
     }
@@ -0,0 +1,617 @@
+//! Portable Alter packs: export, import and the built-in pack shipped with the app.
+//!
+//! A pack is one JSON file holding the current version of each Alter; file-backed
+//! storage keeps no revision history, so packs carry none. Imports always assign fresh
+//! ids, so the same pack can be shared between workspaces, and refuse packs written
+//! with a newer schema. The built-in pack uses the same format with stable ids and a
+//! `pack_version`; syncing it only replaces built-in Alters the user never edited, and
+//! never reinstalls one the user deleted.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use atomicwrites::{AllowOverwrite, AtomicFile};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::alters::{
+    alters_dir, list_alter_records, load_alter_record, normalize_imported_payload, record_payload,
+    slugify, write_alter_record, AlterRecord,
+};
+use crate::fs_ops::ConflictStrategy;
+use crate::{now_ms, AppError, Result};
+
+pub const ALTER_PACK_FORMAT: &str = "tomosona.alter-pack";
+pub const ALTER_PACK_SCHEMA_VERSION: u32 = 1;
+const BUILT_IN_PACK: &str = include_str!("../resources/built_in_alters.json");
+/// Sync state of the built-in pack, stored next to the `alters` folder.
+const BUILT_IN_STATE_FILE_NAME: &str = "built-in-alters.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlterPack {
+    pub format: String,
+    pub schema_version: u32,
+    /// Content version of a distributed pack; exports leave it at 0.
+    #[serde(default)]
+    pub pack_version: u32,
+    pub exported_at_ms: u64,
+    pub alters: Vec<AlterPackEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlterPackEntry {
+    pub alter: AlterRecord,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExportAltersPayload {
+    pub alter_ids: Vec<String>,
+    /// Absolute path of the pack file; its folder must exist.
+    pub output_path: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportAltersResult {
+    pub output_path: String,
+    pub alter_count: usize,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ImportAlterPackPayload {
+    /// Pack file on disk, outside or inside the workspace.
+    pub source_path: String,
+    /// Applied when an imported Alter has the slug of an existing one.
+    pub conflict_strategy: ConflictStrategy,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportedAlter {
+    /// Id of the Alter in the pack.
+    pub source_id: String,
+    /// Id of the Alter in this workspace.
+    pub alter_id: String,
+    pub name: String,
+    /// The Alter replaced an existing one with the same slug.
+    pub replaced: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportAlterPackResult {
+    pub imported: Vec<ImportedAlter>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BuiltInAltersSyncResult {
+    pub pack_version: u32,
+    pub installed: Vec<String>,
+    pub updated: Vec<String>,
+    /// Built-in ids left alone because the user edited or deleted them.
+    pub kept: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct BuiltInPackState {
+    pack_version: u32,
+    /// Built-in ids installed at least once; a missing record means the user deleted it.
+    installed_ids: Vec<String>,
+}
+
+fn invalid_pack(message: &str) -> AppError {
+    AppError::InvalidOperation(message.to_string())
+}
+
+/// Parses a pack, checking its format and schema version before its content.
+fn parse_pack(raw: &str) -> Result<AlterPack> {
+    let value: Value =
+        serde_json::from_str(raw).map_err(|_| invalid_pack("Alter pack is not valid JSON."))?;
+    if value.get("format").and_then(Value::as_str) != Some(ALTER_PACK_FORMAT) {
+        return Err(invalid_pack("File is not a Tomosona Alter pack."));
+    }
+    let version = value
+        .get("schema_version")
+        .and_then(Value::as_u64)
+        .filter(|version| *version > 0)
+        .ok_or_else(|| invalid_pack("Alter pack schema version is missing."))?;
+    if version > u64::from(ALTER_PACK_SCHEMA_VERSION) {
+        return Err(AppError::InvalidOperation(format!(
+            "Alter pack schema version {version} is newer than the supported version {ALTER_PACK_SCHEMA_VERSION}."
+        )));
+    }
+    serde_json::from_value(value).map_err(|_| invalid_pack("Alter pack is malformed."))
+}
+
+/// Drops state that only makes sense in the source workspace.
+fn portable_record(mut record: AlterRecord) -> AlterRecord {
+    for inspiration in &mut record.inspirations {
+        inspiration.note_fingerprint = None;
+    }
+    record.inspiration_issues.clear();
+    record
+}
+
+fn build_pack(alter_ids: &[String]) -> Result<AlterPack> {
+    let mut seen = HashSet::new();
+    let mut alters = Vec::new();
+    for alter_id in alter_ids {
+        let alter_id = alter_id.trim();
+        if !seen.insert(alter_id.to_string()) {
+            continue;
+        }
+        let alter = portable_record(load_alter_record(alter_id)?);
+        alters.push(AlterPackEntry { alter });
+    }
+    if alters.is_empty() {
+        return Err(invalid_pack("Select at least one Alter to export."));
+    }
+    Ok(AlterPack {
+        format: ALTER_PACK_FORMAT.to_string(),
+        schema_version: ALTER_PACK_SCHEMA_VERSION,
+        pack_version: 0,
+        exported_at_ms: now_ms(),
+        alters,
+    })
+}
+
+/// Writes `value` as pretty JSON through a temp file, so a failed write never leaves
+/// a truncated pack or state file behind.
+fn write_json_atomically<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    let json = serde_json::to_string_pretty(value).map_err(|_| AppError::OperationFailed)?;
+    AtomicFile::new(path, AllowOverwrite)
+        .write(|file| {
+            file.write_all(json.as_bytes())?;
+            file.write_all(b"\n")?;
+            file.flush()?;
+            file.sync_all()?;
+            Ok(())
+        })
+        .map_err(|err| match err {
+            atomicwrites::Error::Internal(error) | atomicwrites::Error::User(error) => {
+                AppError::Io(error)
+            }
+        })
+}
+
+fn export_alters_sync(payload: ExportAltersPayload) -> Result<ExportAltersResult> {
+    let output_path = PathBuf::from(payload.output_path.trim());
+    if !output_path.is_absolute() || output_path.is_dir() {
+        return Err(AppError::InvalidPath);
+    }
+    if !output_path.parent().is_some_and(Path::is_dir) {
+        return Err(AppError::InvalidPath);
+    }
+    let pack = build_pack(&payload.alter_ids)?;
+    write_json_atomically(&output_path, &pack)?;
+    Ok(ExportAltersResult {
+        output_path: output_path.to_string_lossy().to_string(),
+        alter_count: pack.alters.len(),
+    })
+}
+
+/// First `Name (n)` whose slug is not taken yet, like renamed files in `fs_ops`.
+fn available_name(name: &str, taken: &HashSet<String>) -> Result<String> {
+    let name = name.trim();
+    for idx in 1..10_000 {
+        let candidate = format!("{name} ({idx})");
+        if !taken.contains(&slugify(&candidate)) {
+            return Ok(candidate);
+        }
+    }
+    Err(AppError::OperationFailed)
+}
+
+/// Writes the Alters of `pack` under fresh ids, handling slug collisions with `strategy`.
+///
+/// Every record is validated before the first write, so a bad entry imports nothing.
+fn import_pack(pack: AlterPack, strategy: ConflictStrategy) -> Result<ImportAlterPackResult> {
+    let existing = list_alter_records()?;
+    let mut by_slug = HashMap::new();
+    for record in &existing {
+        by_slug
+            .entry(record.slug.clone())
+            .or_insert_with(|| record.clone());
+    }
+    if matches!(strategy, ConflictStrategy::Fail)
+        && pack
+            .alters
+            .iter()
+            .any(|entry| by_slug.contains_key(&slugify(&entry.alter.name)))
+    {
+        return Err(AppError::AlreadyExists);
+    }
+
+    let mut taken = by_slug.keys().cloned().collect::<HashSet<_>>();
+    let mut imported_slugs = HashSet::new();
+    let mut planned = Vec::new();
+    for entry in pack.alters {
+        let source_id = entry.alter.id.clone();
+        let mut payload = record_payload(entry.alter);
+        let slug = slugify(&payload.name);
+        // Two Alters of the same pack never replace each other.
+        let replaced = match strategy {
+            ConflictStrategy::Overwrite if !imported_slugs.contains(&slug) => by_slug.get(&slug),
+            _ => None,
+        };
+        let record = match replaced {
+            Some(current) => normalize_imported_payload(
+                payload,
+                Some(current.id.clone()),
+                Some(current.created_at_ms),
+            )?,
+            None => {
+                if taken.contains(&slug) {
+                    payload.name = available_name(&payload.name, &taken)?;
+                }
+                normalize_imported_payload(payload, None, None)?
+            }
+        };
+        taken.insert(record.slug.clone());
+        imported_slugs.insert(record.slug.clone());
+        planned.push((source_id, record, replaced.is_some()));
+    }
+
+    let mut imported = Vec::with_capacity(planned.len());
+    for (source_id, record, replaced) in planned {
+        write_alter_record(&record, replaced)?;
+        imported.push(ImportedAlter {
+            source_id,
+            alter_id: record.id,
+            name: record.name,
+            replaced,
+        });
+    }
+    Ok(ImportAlterPackResult { imported })
+}
+
+fn import_alter_pack_sync(payload: ImportAlterPackPayload) -> Result<ImportAlterPackResult> {
+    let source_path = PathBuf::from(payload.source_path.trim());
+    if !source_path.is_file() {
+        return Err(AppError::InvalidPath);
+    }
+    let pack = parse_pack(&fs::read_to_string(source_path)?)?;
+    import_pack(pack, payload.conflict_strategy)
+}
+
+fn built_in_state_path() -> Result<PathBuf> {
+    Ok(alters_dir()?.with_file_name(BUILT_IN_STATE_FILE_NAME))
+}
+
+fn read_built_in_state() -> Result<BuiltInPackState> {
+    let path = built_in_state_path()?;
+    if !path.is_file() {
+        return Ok(BuiltInPackState::default());
+    }
+    let raw = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&raw).unwrap_or_default())
+}
+
+fn write_built_in_state(state: &BuiltInPackState) -> Result<()> {
+    let path = built_in_state_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    write_json_atomically(&path, state)
+}
+
+/// Installs new built-in Alters and refreshes the ones still matching an older pack.
+///
+/// Editing a built-in Alter turns it into a user Alter (`is_built_in` is cleared), which
+/// is how user edits are told apart from pristine copies.
+fn sync_built_in_pack(pack: AlterPack) -> Result<BuiltInAltersSyncResult> {
+    let mut state = read_built_in_state()?;
+    let existing = list_alter_records()?
+        .into_iter()
+        .map(|record| (record.id.clone(), record))
+        .collect::<HashMap<_, _>>();
+    let mut result = BuiltInAltersSyncResult {
+        pack_version: pack.pack_version,
+        installed: Vec::new(),
+        updated: Vec::new(),
+        kept: Vec::new(),
+    };
+
+    for entry in pack.alters {
+        let alter_id = entry.alter.id.clone();
+        let previously_installed = state.installed_ids.contains(&alter_id);
+        let created_at_ms = match existing.get(&alter_id) {
+            Some(current) if !current.is_built_in => {
+                result.kept.push(alter_id);
+                continue;
+            }
+            Some(current) if current.built_in_version >= Some(pack.pack_version) => continue,
+            Some(current) => Some(current.created_at_ms),
+            None if previously_installed => {
+                result.kept.push(alter_id);
+                continue;
+            }
+            None => None,
+        };
+
+        let mut record = normalize_imported_payload(
+            record_payload(entry.alter),
+            Some(alter_id.clone()),
+            created_at_ms,
+        )?;
+        record.is_built_in = true;
+        record.built_in_version = Some(pack.pack_version);
+        write_alter_record(&record, created_at_ms.is_some())?;
+        if created_at_ms.is_some() {
+            result.updated.push(alter_id.clone());
+        } else {
+            result.installed.push(alter_id.clone());
+        }
+        if !previously_installed {
+            state.installed_ids.push(alter_id);
+        }
+    }
+
+    state.pack_version = state.pack_version.max(pack.pack_version);
+    write_built_in_state(&state)?;
+    Ok(result)
+}
+
+/// Exports the current version of Alters to one portable pack file.
+#[tauri::command]
+pub fn export_alters(payload: ExportAltersPayload) -> Result<ExportAltersResult> {
+    export_alters_sync(payload)
+}
+
+/// Imports every Alter of a pack file into the active workspace under new ids.
+#[tauri::command]
+pub fn import_alter_pack(payload: ImportAlterPackPayload) -> Result<ImportAlterPackResult> {
+    import_alter_pack_sync(payload)
+}
+
+/// Brings the built-in Alters of the active workspace up to date with the shipped pack.
+#[tauri::command]
+pub fn sync_built_in_alters() -> Result<BuiltInAltersSyncResult> {
+    sync_built_in_pack(parse_pack(BUILT_IN_PACK)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alters::{
+        create_alter, default_style, delete_alter, load_alter, update_alter, AlterInspiration,
+        AlterInspirationSourceType, CreateAlterPayload, UpdateAlterPayload,
+    };
+    use crate::next_index_run_id;
+
+    struct TestWorkspace {
+        root: PathBuf,
+    }
+
+    impl Drop for TestWorkspace {
+        fn drop(&mut self) {
+            let _ = crate::clear_active_workspace();
+            let _ = fs::remove_dir_all(&self.root);
+        }
+    }
+
+    fn use_test_workspace<R>(run: impl FnOnce(&Path) -> Result<R>) -> Result<R> {
+        let _guard = crate::workspace_test_guard();
+        let root = std::env::temp_dir().join(format!(
+            "tomosona-alter-packs-test-{}-{}",
+            now_ms(),
+            next_index_run_id()
+        ));
+        fs::create_dir_all(&root)?;
+        let root = fs::canonicalize(root)?;
+        let root_str = root.to_string_lossy().to_string();
+        crate::set_active_workspace(&root_str)?;
+        let _cleanup = TestWorkspace { root: root.clone() };
+        run(&root)
+    }
+
+    fn sample_payload(name: &str) -> CreateAlterPayload {
+        CreateAlterPayload {
+            name: name.to_string(),
+            description: "Alter used by pack tests.".to_string(),
+            icon: None,
+            color: None,
+            category: None,
+            mission: "Keep packs portable.".to_string(),
+            inspirations: Vec::new(),
+            principles: vec!["Prefer explicit behavior".to_string()],
+            reflexes: Vec::new(),
+            values: Vec::new(),
+            critiques: Vec::new(),
+            blind_spots: Vec::new(),
+            system_hints: Vec::new(),
+            style: default_style(),
+            is_favorite: false,
+        }
+    }
+
+    fn update_payload(record: &AlterRecord, mission: &str) -> UpdateAlterPayload {
+        let payload = record_payload(record.clone());
+        UpdateAlterPayload {
+            id: record.id.clone(),
+            name: payload.name,
+            description: payload.description,
+            icon: payload.icon,
+            color: payload.color,
+            category: payload.category,
+            mission: mission.to_string(),
+            inspirations: payload.inspirations,
+            principles: payload.principles,
+            reflexes: payload.reflexes,
+            values: payload.values,
+            critiques: payload.critiques,
+            blind_spots: payload.blind_spots,
+            system_hints: payload.system_hints,
+            style: payload.style,
+            is_favorite: payload.is_favorite,
+            revision_reason: None,
+        }
+    }
+
+    fn pack_with(alters: Vec<AlterRecord>, pack_version: u32) -> AlterPack {
+        AlterPack {
+            format: ALTER_PACK_FORMAT.to_string(),
+            schema_version: ALTER_PACK_SCHEMA_VERSION,
+            pack_version,
+            exported_at_ms: 0,
+            alters: alters
+                .into_iter()
+                .map(|alter| AlterPackEntry { alter })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn export_then_import_remaps_ids_and_renames_slug_collisions() -> Result<()> {
+        use_test_workspace(|root| {
+            let mut payload = sample_payload("Strategist");
+            fs::create_dir_all(root.join("notes"))?;
+            fs::write(root.join("notes/plan.md"), "# Plan\nShip it.\n")?;
+            payload.inspirations = vec![AlterInspiration {
+                id: String::new(),
+                label: "Plan".to_string(),
+                source_type: AlterInspirationSourceType::Note,
+                weight: None,
+                reference_id: Some("notes/plan.md".to_string()),
+                note_fingerprint: None,
+            }];
+            let source = create_alter(payload)?;
+            let output = root.join("strategist.alters.json");
+            let exported = export_alters(ExportAltersPayload {
+                alter_ids: vec![source.id.clone(), source.id.clone()],
+                output_path: output.to_string_lossy().to_string(),
+            })?;
+            assert_eq!(exported.alter_count, 1);
+            let raw = fs::read_to_string(&output)?;
+            assert!(!raw.contains("note_fingerprint"));
+
+            fs::remove_file(root.join("notes/plan.md"))?;
+            let result = import_alter_pack(ImportAlterPackPayload {
+                source_path: output.to_string_lossy().to_string(),
+                conflict_strategy: ConflictStrategy::Rename,
+            })?;
+            assert_eq!(result.imported.len(), 1);
+            let imported = &result.imported[0];
+            assert_eq!(imported.source_id, source.id);
+            assert_ne!(imported.alter_id, source.id);
+            assert_eq!(imported.name, "Strategist (1)");
+            assert!(!imported.replaced);
+
+            let record = load_alter(imported.alter_id.clone())?;
+            assert_eq!(record.slug, "strategist-1");
+            assert_eq!(
+                record.inspirations[0].reference_id.as_deref(),
+                Some("notes/plan.md")
+            );
+            assert_eq!(record.inspiration_issues.len(), 1);
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn import_applies_fail_and_overwrite_strategies() -> Result<()> {
+        use_test_workspace(|_| {
+            let current = create_alter(sample_payload("Critic"))?;
+            let mut incoming = current.clone();
+            incoming.id = "alter-from-elsewhere".to_string();
+            incoming.mission = "Challenge every plan.".to_string();
+
+            let failed = import_pack(pack_with(vec![incoming.clone()], 0), ConflictStrategy::Fail);
+            assert!(matches!(failed, Err(AppError::AlreadyExists)));
+            assert_eq!(list_alter_records()?.len(), 1);
+
+            let mut twin = incoming.clone();
+            twin.id = "alter-twin".to_string();
+            let result = import_pack(
+                pack_with(vec![incoming, twin], 0),
+                ConflictStrategy::Overwrite,
+            )?;
+            assert!(result.imported[0].replaced);
+            assert_eq!(result.imported[0].alter_id, current.id);
+            assert!(!result.imported[1].replaced);
+            assert_eq!(result.imported[1].name, "Critic (1)");
+            let replaced = load_alter(current.id.clone())?;
+            assert_eq!(replaced.mission, "Challenge every plan.");
+            assert_eq!(replaced.created_at_ms, current.created_at_ms);
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn parse_pack_checks_format_and_schema_version() {
+        let newer = format!(
+            "{{\"format\":\"{ALTER_PACK_FORMAT}\",\"schema_version\":{},\"exported_at_ms\":0,\"alters\":[]}}",
+            ALTER_PACK_SCHEMA_VERSION + 1
+        );
+        let err = parse_pack(&newer).expect_err("newer schema");
+        assert!(err.to_string().contains("newer than the supported version"));
+        assert!(parse_pack("{\"format\":\"other\",\"schema_version\":1}").is_err());
+        assert!(parse_pack("{\"format\":\"tomosona.alter-pack\"}").is_err());
+    }
+
+    #[test]
+    fn built_in_pack_is_valid() -> Result<()> {
+        use_test_workspace(|_| {
+            let pack = parse_pack(BUILT_IN_PACK)?;
+            assert!(pack.pack_version > 0);
+            let result = sync_built_in_pack(pack.clone())?;
+            assert_eq!(result.installed.len(), pack.alters.len());
+            for entry in &pack.alters {
+                let record = load_alter(entry.alter.id.clone())?;
+                assert!(record.is_built_in);
+                assert_eq!(record.slug, entry.alter.slug);
+                assert!(!record.invocation_prompt.is_empty());
+            }
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn built_in_sync_keeps_user_edits_and_deletions() -> Result<()> {
+        use_test_workspace(|_| {
+            let mut first = normalize_imported_payload(
+                sample_payload("Built-in One"),
+                Some("builtin-one".to_string()),
+                None,
+            )?;
+            let mut second = first.clone();
+            second.id = "builtin-two".to_string();
+            second.name = "Built-in Two".to_string();
+            let mut third = first.clone();
+            third.id = "builtin-three".to_string();
+            third.name = "Built-in Three".to_string();
+            first.mission = "Version one.".to_string();
+
+            let result = sync_built_in_pack(pack_with(
+                vec![first.clone(), second.clone(), third.clone()],
+                1,
+            ))?;
+            assert_eq!(result.installed.len(), 3);
+
+            let installed = load_alter(second.id.clone())?;
+            update_alter(update_payload(&installed, "Edited by the user."))?;
+            delete_alter(third.id.clone())?;
+
+            first.mission = "Version two.".to_string();
+            second.mission = "Version two.".to_string();
+            third.mission = "Version two.".to_string();
+            let result = sync_built_in_pack(pack_with(vec![first, second, third], 2))?;
+            assert_eq!(result.updated, vec!["builtin-one".to_string()]);
+            assert_eq!(
+                result.kept,
+                vec!["builtin-two".to_string(), "builtin-three".to_string()]
+            );
+            assert!(result.installed.is_empty());
+
+            let updated = load_alter("builtin-one".to_string())?;
+            assert_eq!(updated.mission, "Version two.");
+            assert_eq!(updated.built_in_version, Some(2));
+            let edited = load_alter("builtin-two".to_string())?;
+            assert_eq!(edited.mission, "Edited by the user.");
+            assert!(!edited.is_built_in);
+            assert!(load_alter("builtin-three".to_string()).is_err());
+            Ok(())
+        })
+    }
+}
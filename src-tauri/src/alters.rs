@@ -9,9 +9,16 @@ use std::{
 use atomicwrites::{AllowOverwrite, AtomicFile, DisallowOverwrite};
 use rusqlite::{params, Connection};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 
-use crate::second_brain::config::active_profile;
-use crate::second_brain::llm::run_llm;
+use crate::second_brain::config::{active_profile, role_route, ModelRole};
+use crate::second_brain::llm::run_llm_json;
+use crate::second_brain::prompt_packs::{
+    fill_template, select_prompt_pack, PromptLocale, PromptPack,
+};
+use crate::second_brain::session_store::estimate_tokens;
+use crate::second_brain::structured_output::JsonOutput;
+use crate::second_brain::usage::LlmFeature;
 use crate::settings;
 use crate::{
     ensure_index_schema, next_index_run_id, normalize_workspace_relative_from_input, now_ms,
@@ -20,6 +27,38 @@ use crate::{
 
 const ALTER_PREFIX: &str = "alter";
 pub const ALTER_DEFAULT_TEMPERATURE: f64 = 0.15;
+/// Tokens of note excerpts shared by all note inspirations of one Alter.
+const INSPIRATION_NOTES_BUDGET_TOKENS: usize = 1_500;
+/// Shares smaller than this are dropped instead of producing a useless excerpt.
+const INSPIRATION_NOTE_MIN_TOKENS: usize = 32;
+/// JSON shape requested from the quick start model; keys stay identical across prompt packs.
+const ALTER_DRAFT_JSON_SHAPE: &str = "{
+  \"name\": string,
+  \"description\": string,
+  \"icon\": null,
+  \"color\": string,
+  \"category\": string,
+  \"mission\": string,
+  \"inspirations\": [{\"label\": string, \"source_type\": \"manual\" | \"template\" | \"reference_figure\" | \"note\", \"weight\": number | null, \"reference_id\": string | null}],
+  \"principles\": string[],
+  \"reflexes\": string[],
+  \"values\": string[],
+  \"critiques\": string[],
+  \"blind_spots\": string[],
+  \"system_hints\": string[],
+  \"style\": {
+    \"tone\": \"neutral\" | \"direct\" | \"socratic\" | \"strategic\" | \"creative\",
+    \"verbosity\": \"short\" | \"medium\" | \"long\",
+    \"temperature\": number,
+    \"contradiction_level\": number,
+    \"exploration_level\": number,
+    \"influence_intensity\": \"light\" | \"balanced\" | \"strong\",
+    \"response_style\": \"concise\" | \"analytic\" | \"dialectic\" | \"frontal\",
+    \"cite_hypotheses\": boolean,
+    \"signal_biases\": boolean
+  },
+  \"is_favorite\": boolean
+}";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -37,6 +76,17 @@ pub struct AlterInspiration {
     pub source_type: AlterInspirationSourceType,
     pub weight: Option<f64>,
     pub reference_id: Option<String>,
+    /// Hash of the note content compiled into the invocation prompt.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note_fingerprint: Option<String>,
+}
+
+/// Note inspiration whose reference no longer resolves to a workspace note.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AlterInspirationIssue {
+    pub inspiration_id: String,
+    pub reference_id: String,
+    pub message: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -72,8 +122,14 @@ pub struct AlterRecord {
     pub system_hints: Vec<String>,
     pub style: AlterStyle,
     pub invocation_prompt: String,
+    /// Broken note references found the last time the record was compiled or loaded.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inspiration_issues: Vec<AlterInspirationIssue>,
     pub is_favorite: bool,
     pub is_built_in: bool,
+    /// Built-in pack version this record was installed from; cleared once the user edits it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub built_in_version: Option<u32>,
     pub created_at_ms: u64,
     pub updated_at_ms: u64,
 }
@@ -244,7 +300,7 @@ fn sanitize_lines(values: &[String]) -> Vec<String> {
         .collect()
 }
 
-fn default_style() -> AlterStyle {
+pub(crate) fn default_style() -> AlterStyle {
     AlterStyle {
         tone: "strategic".to_string(),
         verbosity: "medium".to_string(),
@@ -258,7 +314,7 @@ fn default_style() -> AlterStyle {
     }
 }
 
-fn slugify(name: &str) -> String {
+pub(crate) fn slugify(name: &str) -> String {
     let mut slug = String::new();
     let mut last_dash = false;
     for ch in name.trim().chars() {
@@ -297,43 +353,101 @@ fn normalize_generated_source_type(raw: Option<&str>) -> AlterInspirationSourceT
     }
 }
 
-fn extract_json_object(raw: &str) -> Option<&str> {
-    let start = raw.find('{')?;
-    let mut depth = 0usize;
-    let mut in_string = false;
-    let mut escaped = false;
-
-    for (offset, ch) in raw[start..].char_indices() {
-        if in_string {
-            if escaped {
-                escaped = false;
-                continue;
-            }
-            if ch == '\\' {
-                escaped = true;
-                continue;
-            }
-            if ch == '"' {
-                in_string = false;
-            }
-            continue;
-        }
-
-        match ch {
-            '"' => in_string = true,
-            '{' => depth += 1,
-            '}' => {
-                depth = depth.saturating_sub(1);
-                if depth == 0 {
-                    let end = start + offset + ch.len_utf8();
-                    return Some(&raw[start..end]);
-                }
-            }
-            _ => {}
-        }
+/// Schema of quick start replies, mirroring [`ALTER_DRAFT_JSON_SHAPE`].
+fn alter_draft_output() -> JsonOutput {
+    let strings = json!({"type": "array", "items": {"type": "string"}});
+    JsonOutput {
+        name: "alter_draft",
+        description: "Alter configuration generated from the brief.",
+        schema: json!({
+            "type": "object",
+            "properties": {
+                "name": {"type": "string"},
+                "description": {"type": "string"},
+                "icon": {"type": ["string", "null"]},
+                "color": {"type": "string"},
+                "category": {"type": "string"},
+                "mission": {"type": "string"},
+                "inspirations": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "label": {"type": "string"},
+                            "source_type": {
+                                "type": "string",
+                                "enum": ["manual", "template", "reference_figure", "note"]
+                            },
+                            "weight": {"type": ["number", "null"]},
+                            "reference_id": {"type": ["string", "null"]}
+                        },
+                        "required": ["label", "source_type", "weight", "reference_id"],
+                        "additionalProperties": false
+                    }
+                },
+                "principles": strings,
+                "reflexes": strings,
+                "values": strings,
+                "critiques": strings,
+                "blind_spots": strings,
+                "system_hints": strings,
+                "style": {
+                    "type": "object",
+                    "properties": {
+                        "tone": {
+                            "type": "string",
+                            "enum": ["neutral", "direct", "socratic", "strategic", "creative"]
+                        },
+                        "verbosity": {"type": "string", "enum": ["short", "medium", "long"]},
+                        "temperature": {"type": "number"},
+                        "contradiction_level": {"type": "integer"},
+                        "exploration_level": {"type": "integer"},
+                        "influence_intensity": {
+                            "type": "string",
+                            "enum": ["light", "balanced", "strong"]
+                        },
+                        "response_style": {
+                            "type": "string",
+                            "enum": ["concise", "analytic", "dialectic", "frontal"]
+                        },
+                        "cite_hypotheses": {"type": "boolean"},
+                        "signal_biases": {"type": "boolean"}
+                    },
+                    "required": [
+                        "tone",
+                        "verbosity",
+                        "temperature",
+                        "contradiction_level",
+                        "exploration_level",
+                        "influence_intensity",
+                        "response_style",
+                        "cite_hypotheses",
+                        "signal_biases"
+                    ],
+                    "additionalProperties": false
+                },
+                "is_favorite": {"type": "boolean"}
+            },
+            "required": [
+                "name",
+                "description",
+                "icon",
+                "color",
+                "category",
+                "mission",
+                "inspirations",
+                "principles",
+                "reflexes",
+                "values",
+                "critiques",
+                "blind_spots",
+                "system_hints",
+                "style",
+                "is_favorite"
+            ],
+            "additionalProperties": false
+        }),
     }
-
-    None
 }
 
 fn fallback_name_from_prompt(prompt: &str) -> String {
@@ -413,6 +527,7 @@ fn normalize_generated_draft(parsed: GeneratedAlterDraft, prompt: &str) -> Creat
                     source_type: safe_source_type,
                     weight: item.weight,
                     reference_id,
+                    note_fingerprint: None,
                 })
             })
             .collect(),
@@ -493,7 +608,14 @@ fn validate_style(style: &AlterStyle) -> Result<()> {
     Ok(())
 }
 
-fn validate_inspirations(items: &[AlterInspiration]) -> Result<Vec<AlterInspiration>> {
+/// Normalizes inspirations coming from the editor or from an imported pack.
+///
+/// With `strict_note_references` unset, a note reference that cannot be resolved in this
+/// workspace is kept as written and later reported through `inspiration_issues`.
+fn validate_inspirations(
+    items: &[AlterInspiration],
+    strict_note_references: bool,
+) -> Result<Vec<AlterInspiration>> {
     let root = active_workspace_root()?;
     let mut out = Vec::with_capacity(items.len());
     for item in items {
@@ -511,7 +633,11 @@ fn validate_inspirations(items: &[AlterInspiration]) -> Result<Vec<AlterInspirat
                         "Note inspiration requires a note reference.".to_string(),
                     ));
                 }
-                Some(normalize_workspace_relative_from_input(&root, raw)?)
+                match normalize_workspace_relative_from_input(&root, raw) {
+                    Ok(reference) => Some(reference),
+                    Err(_) if !strict_note_references => Some(raw.to_string()),
+                    Err(err) => return Err(err),
+                }
             }
             _ => empty_to_none(item.reference_id.clone()),
         };
@@ -525,34 +651,239 @@ fn validate_inspirations(items: &[AlterInspiration]) -> Result<Vec<AlterInspirat
             source_type: item.source_type.clone(),
             weight: item.weight,
             reference_id,
+            note_fingerprint: None,
         });
     }
     Ok(out)
 }
 
-fn compile_invocation_prompt(record: &AlterRecord) -> String {
-    let mut out = String::from("Alter invocation contract.\n");
-    out.push_str(&format!("Identity: {}\n", record.name));
+/// Workspace note behind a note inspiration.
+struct InspirationNote {
+    inspiration_id: String,
+    path: String,
+    content: String,
+    fingerprint: String,
+}
+
+/// Note inspirations resolved against the active workspace.
+#[derive(Default)]
+struct ResolvedInspirationNotes {
+    notes: Vec<InspirationNote>,
+    issues: Vec<AlterInspirationIssue>,
+}
+
+/// Returns the workspace-relative path and content of the note behind `reference_id`.
+fn resolve_note_reference(
+    root: &Path,
+    reference_id: &str,
+) -> std::result::Result<(String, String), String> {
+    let direct = root.join(reference_id);
+    let candidate = if direct.is_file() {
+        direct
+    } else {
+        root.join(format!("{reference_id}.md"))
+    };
+    if reference_id.is_empty() || !candidate.is_file() {
+        return Err("Note not found.".to_string());
+    }
+    let canonical = fs::canonicalize(&candidate).map_err(|err| err.to_string())?;
+    let Ok(relative) = canonical.strip_prefix(root) else {
+        return Err("Note is outside the workspace.".to_string());
+    };
+    let is_markdown = canonical
+        .extension()
+        .and_then(|value| value.to_str())
+        .map(|ext| ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown"))
+        .unwrap_or(false);
+    if !is_markdown {
+        return Err("Only markdown notes can ground an inspiration.".to_string());
+    }
+    let content = fs::read_to_string(&canonical).map_err(|err| err.to_string())?;
+    Ok((relative.to_string_lossy().replace('\\', "/"), content))
+}
+
+/// Reads the notes referenced by note inspirations; without a workspace nothing resolves.
+fn resolve_inspiration_notes(inspirations: &[AlterInspiration]) -> ResolvedInspirationNotes {
+    let mut resolved = ResolvedInspirationNotes::default();
+    let Ok(root) = active_workspace_root() else {
+        return resolved;
+    };
+    for item in inspirations {
+        if !matches!(item.source_type, AlterInspirationSourceType::Note) {
+            continue;
+        }
+        let reference_id = item.reference_id.as_deref().unwrap_or("").trim();
+        match resolve_note_reference(&root, reference_id) {
+            Ok((path, content)) => resolved.notes.push(InspirationNote {
+                inspiration_id: item.id.clone(),
+                path,
+                fingerprint: blake3::hash(content.as_bytes()).to_hex().to_string(),
+                content,
+            }),
+            Err(message) => resolved.issues.push(AlterInspirationIssue {
+                inspiration_id: item.id.clone(),
+                reference_id: reference_id.to_string(),
+                message,
+            }),
+        }
+    }
+    resolved
+}
+
+/// Stores the fingerprints of the resolved notes and reports whether any changed.
+fn apply_note_fingerprints(record: &mut AlterRecord, resolved: &ResolvedInspirationNotes) -> bool {
+    let mut changed = false;
+    for item in &mut record.inspirations {
+        if !matches!(item.source_type, AlterInspirationSourceType::Note) {
+            continue;
+        }
+        let fingerprint = resolved
+            .notes
+            .iter()
+            .find(|note| note.inspiration_id == item.id)
+            .map(|note| note.fingerprint.clone());
+        if item.note_fingerprint != fingerprint {
+            item.note_fingerprint = fingerprint;
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// Splits the excerpt budget across note inspirations in proportion to their weight.
+///
+/// Missing weights count as `1`; when every weight is zero the budget is split evenly.
+fn note_excerpt_budgets(record: &AlterRecord, notes: &[InspirationNote]) -> Vec<usize> {
+    let weights = notes
+        .iter()
+        .map(|note| {
+            record
+                .inspirations
+                .iter()
+                .find(|item| item.id == note.inspiration_id)
+                .and_then(|item| item.weight)
+                .unwrap_or(1.0)
+                .max(0.0)
+        })
+        .collect::<Vec<_>>();
+    let total = weights.iter().sum::<f64>();
+    weights
+        .iter()
+        .map(|weight| {
+            let share = if total > 0.0 {
+                weight / total
+            } else {
+                1.0 / notes.len() as f64
+            };
+            (INSPIRATION_NOTES_BUDGET_TOKENS as f64 * share).floor() as usize
+        })
+        .collect()
+}
+
+fn strip_frontmatter(content: &str) -> &str {
+    let mut offset = 0;
+    for (index, line) in content.split_inclusive('\n').enumerate() {
+        offset += line.len();
+        let is_fence = line.trim_end() == "---";
+        if index == 0 && !is_fence {
+            return content;
+        }
+        if index > 0 && is_fence {
+            return &content[offset..];
+        }
+    }
+    content
+}
+
+/// Keeps the beginning of the note body within `max_tokens`.
+fn note_excerpt(content: &str, max_tokens: usize, marker: &str) -> String {
+    let body = strip_frontmatter(content).trim();
+    if estimate_tokens(body) <= max_tokens {
+        return body.to_string();
+    }
+    let head: String = body.chars().take(max_tokens.saturating_mul(4)).collect();
+    let cut = head
+        .rfind(char::is_whitespace)
+        .filter(|index| *index > head.len() / 2)
+        .unwrap_or(head.len());
+    format!("{}{}", head[..cut].trim_end(), marker.trim_end())
+}
+
+/// Picks the prompt pack from settings, or from the language the Alter is written in.
+fn invocation_prompt_pack(record: &AlterRecord) -> &'static PromptPack {
+    let lines = [
+        &record.principles,
+        &record.reflexes,
+        &record.values,
+        &record.critiques,
+        &record.blind_spots,
+    ]
+    .iter()
+    .flat_map(|values| values.iter().map(String::as_str))
+    .collect::<Vec<_>>()
+    .join("\n");
+    select_prompt_pack(
+        settings::configured_prompt_locale(),
+        &[&record.description, &record.mission, &lines],
+        PromptLocale::En,
+    )
+}
+
+fn compile_invocation_prompt(
+    record: &AlterRecord,
+    pack: &PromptPack,
+    notes: &[InspirationNote],
+) -> String {
+    let texts = &pack.alter;
+    let mut out = format!("{}\n", texts.contract_heading);
+    out.push_str(&format!("{}: {}\n", texts.identity_label, record.name));
     if !record.description.trim().is_empty() {
-        out.push_str(&format!("Description: {}\n", record.description.trim()));
+        out.push_str(&format!(
+            "{}: {}\n",
+            texts.description_label,
+            record.description.trim()
+        ));
     }
-    out.push_str(&format!("Mission: {}\n", record.mission.trim()));
+    out.push_str(&format!(
+        "{}: {}\n",
+        texts.mission_label,
+        record.mission.trim()
+    ));
     if !record.inspirations.is_empty() {
-        out.push_str("Inspirations:\n");
+        let budgets = note_excerpt_budgets(record, notes);
+        out.push_str(&format!("{}:\n", texts.inspirations_label));
         for item in &record.inspirations {
             out.push_str(&format!("- {} ({:?})", item.label, item.source_type).to_lowercase());
             if let Some(weight) = item.weight {
                 out.push_str(&format!(" weight={weight}"));
             }
             out.push('\n');
+            let Some((note, budget)) = notes
+                .iter()
+                .zip(&budgets)
+                .find(|(note, _)| note.inspiration_id == item.id)
+            else {
+                continue;
+            };
+            if *budget < INSPIRATION_NOTE_MIN_TOKENS {
+                continue;
+            }
+            let excerpt = note_excerpt(&note.content, *budget, pack.truncation_marker);
+            if excerpt.is_empty() {
+                continue;
+            }
+            out.push_str(&format!("  {} {}:\n", texts.note_excerpt_label, note.path));
+            for line in excerpt.lines() {
+                out.push_str(&format!("  > {line}\n"));
+            }
         }
     }
     for (label, values) in [
-        ("Principles", &record.principles),
-        ("Reflexes", &record.reflexes),
-        ("Values", &record.values),
-        ("Critiques", &record.critiques),
-        ("Blind spots", &record.blind_spots),
+        (texts.principles_label, &record.principles),
+        (texts.reflexes_label, &record.reflexes),
+        (texts.values_label, &record.values),
+        (texts.critiques_label, &record.critiques),
+        (texts.blind_spots_label, &record.blind_spots),
     ] {
         if values.is_empty() {
             continue;
@@ -563,7 +894,8 @@ fn compile_invocation_prompt(record: &AlterRecord) -> String {
         }
     }
     out.push_str(&format!(
-        "Style: tone={}, verbosity={}, contradiction_level={}, exploration_level={}, intensity={}, response_style={}\n",
+        "{}: tone={}, verbosity={}, contradiction_level={}, exploration_level={}, intensity={}, response_style={}\n",
+        texts.style_label,
         record.style.tone,
         record.style.verbosity,
         record.style.contradiction_level,
@@ -572,18 +904,20 @@ fn compile_invocation_prompt(record: &AlterRecord) -> String {
         record.style.response_style
     ));
     if record.style.cite_hypotheses {
-        out.push_str("Always cite hypotheses explicitly.\n");
+        out.push_str(texts.cite_hypotheses);
+        out.push('\n');
     }
     if record.style.signal_biases {
-        out.push_str("Signal potential biases and blind spots in the answer.\n");
+        out.push_str(texts.signal_biases);
+        out.push('\n');
     }
     if !record.system_hints.is_empty() {
-        out.push_str("Hints:\n");
+        out.push_str(&format!("{}:\n", texts.hints_label));
         for hint in &record.system_hints {
             out.push_str(&format!("- {hint}\n"));
         }
     }
-    out.push_str("Respond in markdown and keep the Alter framing explicit but not theatrical.");
+    out.push_str(texts.closing);
     out
 }
 
@@ -591,6 +925,24 @@ fn normalize_create_payload(
     payload: CreateAlterPayload,
     existing_id: Option<String>,
     created_at_ms: Option<u64>,
+) -> Result<AlterRecord> {
+    build_alter_record(payload, existing_id, created_at_ms, true)
+}
+
+/// Normalizes an Alter read from a pack; see [`validate_inspirations`] for note references.
+pub(crate) fn normalize_imported_payload(
+    payload: CreateAlterPayload,
+    existing_id: Option<String>,
+    created_at_ms: Option<u64>,
+) -> Result<AlterRecord> {
+    build_alter_record(payload, existing_id, created_at_ms, false)
+}
+
+fn build_alter_record(
+    payload: CreateAlterPayload,
+    existing_id: Option<String>,
+    created_at_ms: Option<u64>,
+    strict_note_references: bool,
 ) -> Result<AlterRecord> {
     let name = payload.name.trim().to_string();
     if name.is_empty() {
@@ -606,7 +958,7 @@ fn normalize_create_payload(
     }
     validate_style(&payload.style)?;
     let ts = now_ms();
-    let inspirations = validate_inspirations(&payload.inspirations)?;
+    let inspirations = validate_inspirations(&payload.inspirations, strict_note_references)?;
     let mut record = AlterRecord {
         id: existing_id.unwrap_or_else(|| next_id(ALTER_PREFIX)),
         name: name.clone(),
@@ -625,15 +977,50 @@ fn normalize_create_payload(
         system_hints: sanitize_lines(&payload.system_hints),
         style: payload.style,
         invocation_prompt: String::new(),
+        inspiration_issues: Vec::new(),
         is_favorite: payload.is_favorite,
         is_built_in: false,
+        built_in_version: None,
         created_at_ms: created_at_ms.unwrap_or(ts),
         updated_at_ms: ts,
     };
-    record.invocation_prompt = compile_invocation_prompt(&record);
+    compile_record(&mut record);
     Ok(record)
 }
 
+/// Editable fields of `record`, as accepted by the create and import paths.
+pub(crate) fn record_payload(record: AlterRecord) -> CreateAlterPayload {
+    CreateAlterPayload {
+        name: record.name,
+        description: record.description,
+        icon: record.icon,
+        color: record.color,
+        category: record.category,
+        mission: record.mission,
+        inspirations: record.inspirations,
+        principles: record.principles,
+        reflexes: record.reflexes,
+        values: record.values,
+        critiques: record.critiques,
+        blind_spots: record.blind_spots,
+        system_hints: record.system_hints,
+        style: record.style,
+        is_favorite: record.is_favorite,
+    }
+}
+
+/// Resolves note inspirations and recompiles the invocation prompt of `record`.
+///
+/// Returns whether a source note changed since the last compilation.
+fn compile_record(record: &mut AlterRecord) -> bool {
+    let resolved = resolve_inspiration_notes(&record.inspirations);
+    let changed = apply_note_fingerprints(record, &resolved);
+    record.invocation_prompt =
+        compile_invocation_prompt(record, invocation_prompt_pack(record), &resolved.notes);
+    record.inspiration_issues = resolved.issues;
+    changed
+}
+
 fn normalize_alter_id(alter_id: &str) -> Result<String> {
     let trimmed = alter_id.trim();
     if trimmed.is_empty() {
@@ -652,7 +1039,7 @@ fn normalize_alter_id(alter_id: &str) -> Result<String> {
     Ok(trimmed.to_string())
 }
 
-fn alters_dir() -> Result<PathBuf> {
+pub(crate) fn alters_dir() -> Result<PathBuf> {
     let root = active_workspace_root()?;
     Ok(root.join(".tomosona").join("alters"))
 }
@@ -684,7 +1071,7 @@ fn read_alter_file(path: &Path) -> Result<AlterRecord> {
     serde_json::from_str(&raw).map_err(|_| AppError::OperationFailed)
 }
 
-fn list_alter_records() -> Result<Vec<AlterRecord>> {
+pub(crate) fn list_alter_records() -> Result<Vec<AlterRecord>> {
     let dir = alters_dir()?;
     if !dir.is_dir() {
         return Ok(Vec::new());
@@ -713,7 +1100,7 @@ fn list_alter_records() -> Result<Vec<AlterRecord>> {
     Ok(records)
 }
 
-fn load_alter_record(alter_id: &str) -> Result<AlterRecord> {
+pub(crate) fn load_alter_record(alter_id: &str) -> Result<AlterRecord> {
     let path = alter_path(alter_id)?;
     if !path.is_file() {
         return Err(AppError::InvalidOperation("Alter not found.".to_string()));
@@ -721,7 +1108,26 @@ fn load_alter_record(alter_id: &str) -> Result<AlterRecord> {
     read_alter_file(&path)
 }
 
-fn write_alter_record(alter: &AlterRecord, overwrite: bool) -> Result<()> {
+/// Loads an Alter with its invocation prompt in sync with its note inspirations.
+///
+/// The record is rewritten when a source note changed since it was compiled.
+fn load_grounded_alter_record(alter_id: &str) -> Result<AlterRecord> {
+    let mut record = load_alter_record(alter_id)?;
+    let has_notes = record
+        .inspirations
+        .iter()
+        .any(|item| matches!(item.source_type, AlterInspirationSourceType::Note));
+    if !has_notes {
+        record.inspiration_issues.clear();
+        return Ok(record);
+    }
+    if compile_record(&mut record) {
+        write_alter_record(&record, true)?;
+    }
+    Ok(record)
+}
+
+pub(crate) fn write_alter_record(alter: &AlterRecord, overwrite: bool) -> Result<()> {
     let path = alter_path(&alter.id)?;
     let parent = path.parent().ok_or(AppError::OperationFailed)?;
     fs::create_dir_all(parent)?;
@@ -777,7 +1183,7 @@ pub fn resolve_invocation_prompt(
     let Some(alter_id) = alter_id.map(str::trim).filter(|value| !value.is_empty()) else {
         return Ok(None);
     };
-    let alter = load_alter_record(alter_id)?;
+    let alter = load_grounded_alter_record(alter_id)?;
     Ok(Some(alter.invocation_prompt))
 }
 
@@ -795,59 +1201,20 @@ pub fn effective_generation_temperature(temperature: Option<f64>) -> f64 {
     temperature.unwrap_or(ALTER_DEFAULT_TEMPERATURE)
 }
 
-fn quick_start_system_prompt() -> &'static str {
-    "You design structured Alter personas for a workspace-centric thinking tool.
-
-Return exactly one JSON object and nothing else.
-Do not wrap in markdown fences.
-Generate a pragmatic, usable Alter configuration from the user's brief.
-
-Required JSON shape:
-{
-  \"name\": string,
-  \"description\": string,
-  \"icon\": null,
-  \"color\": string,
-  \"category\": string,
-  \"mission\": string,
-  \"inspirations\": [{\"label\": string, \"source_type\": \"manual\" | \"template\" | \"reference_figure\" | \"note\", \"weight\": number | null, \"reference_id\": string | null}],
-  \"principles\": string[],
-  \"reflexes\": string[],
-  \"values\": string[],
-  \"critiques\": string[],
-  \"blind_spots\": string[],
-  \"system_hints\": string[],
-  \"style\": {
-    \"tone\": \"neutral\" | \"direct\" | \"socratic\" | \"strategic\" | \"creative\",
-    \"verbosity\": \"short\" | \"medium\" | \"long\",
-    \"temperature\": number,
-    \"contradiction_level\": number,
-    \"exploration_level\": number,
-    \"influence_intensity\": \"light\" | \"balanced\" | \"strong\",
-    \"response_style\": \"concise\" | \"analytic\" | \"dialectic\" | \"frontal\",
-    \"cite_hypotheses\": boolean,
-    \"signal_biases\": boolean
-  },
-  \"is_favorite\": boolean
-}
-
-Constraints:
-- Make the name compact and product-ready.
-- Generate category, description, and mission automatically.
-- Prefer 3 to 6 items for each list when relevant.
-- Keep inspirations concrete and use reference_figure/manual unless the user explicitly implies a note.
-- Use null for unknown optional values.
-- Keep temperature between 0 and 1.
-- Keep contradiction_level and exploration_level between 0 and 100."
-}
-
-fn quick_start_user_prompt(prompt: &str) -> String {
+fn quick_start_system_prompt(pack: &PromptPack) -> String {
     format!(
-        "User brief for the Alter quick start:\n{}\n\nGenerate the full Alter JSON now.",
-        prompt.trim()
+        "{}\n\n{}\n{}\n\n{}",
+        pack.alter.draft_intro,
+        pack.alter.draft_shape_heading,
+        ALTER_DRAFT_JSON_SHAPE,
+        pack.alter.draft_constraints
     )
 }
 
+fn quick_start_user_prompt(prompt: &str, pack: &PromptPack) -> String {
+    fill_template(pack.alter.draft_user_template, &[("brief", prompt.trim())])
+}
+
 #[tauri::command]
 pub async fn generate_alter_draft(
     payload: GenerateAlterDraftPayload,
@@ -875,19 +1242,22 @@ pub async fn generate_alter_draft(
         ));
     }
 
-    let raw = run_llm(
-        active,
-        quick_start_system_prompt(),
-        &quick_start_user_prompt(&normalized_prompt),
+    let pack = select_prompt_pack(
+        config.prompt_language,
+        &[&normalized_prompt],
+        PromptLocale::En,
+    );
+    let parsed: GeneratedAlterDraft = run_llm_json(
+        &role_route(&config, active, ModelRole::Chat),
+        LlmFeature::AlterDraft,
+        &alter_draft_output(),
+        &quick_start_system_prompt(pack),
+        &quick_start_user_prompt(&normalized_prompt, pack),
         None,
     )
     .await
-    .map_err(AppError::InvalidOperation)?;
-    let json = extract_json_object(&raw).ok_or_else(|| {
-        AppError::InvalidOperation("Could not parse Alter quick start response.".to_string())
-    })?;
-    let parsed: GeneratedAlterDraft = serde_json::from_str(json).map_err(|_| {
-        AppError::InvalidOperation("Alter quick start returned invalid JSON.".to_string())
+    .map_err(|message| {
+        AppError::InvalidOperation(format!("Alter quick start failed: {message}"))
     })?;
     Ok(normalize_generated_draft(parsed, &normalized_prompt))
 }
@@ -906,7 +1276,7 @@ pub fn create_alter(payload: CreateAlterPayload) -> Result<AlterRecord> {
 
 #[tauri::command]
 pub fn load_alter(alter_id: String) -> Result<AlterRecord> {
-    load_alter_record(&alter_id)
+    load_grounded_alter_record(&alter_id)
 }
 
 #[tauri::command]
@@ -957,9 +1327,10 @@ pub fn duplicate_alter(alter_id: String) -> Result<AlterRecord> {
     clone.name = format!("{} Copy", current.name);
     clone.slug = slugify(&clone.name);
     clone.is_built_in = false;
+    clone.built_in_version = None;
     clone.created_at_ms = now_ms();
     clone.updated_at_ms = clone.created_at_ms;
-    clone.invocation_prompt = compile_invocation_prompt(&clone);
+    compile_record(&mut clone);
     write_alter_record(&clone, false)?;
     Ok(clone)
 }
@@ -996,6 +1367,7 @@ pub fn preview_alter(payload: PreviewAlterPayload) -> Result<PreviewAlterResult>
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::second_brain::structured_output::validate;
     use std::fs;
 
     struct TestWorkspace {
@@ -1037,6 +1409,7 @@ mod tests {
                 source_type: AlterInspirationSourceType::Manual,
                 weight: Some(1.0),
                 reference_id: None,
+                note_fingerprint: None,
             }],
             principles: vec!["Prefer explicit behavior".to_string()],
             reflexes: vec!["Ask for the concrete failure mode".to_string()],
@@ -1050,9 +1423,44 @@ mod tests {
     }
 
     #[test]
-    fn extract_json_object_accepts_fenced_or_prefixed_output() {
-        let raw = "```json\n{\"name\":\"A\"}\n```";
-        assert_eq!(extract_json_object(raw), Some("{\"name\":\"A\"}"));
+    fn alter_draft_schema_matches_generated_drafts() {
+        let schema = alter_draft_output().schema;
+        let mut draft = json!({
+            "name": "Skeptic",
+            "description": "Questions assumptions",
+            "icon": null,
+            "color": "#336699",
+            "category": "critique",
+            "mission": "Find the weak spot",
+            "inspirations": [
+                {"label": "Popper", "source_type": "reference_figure", "weight": null, "reference_id": null}
+            ],
+            "principles": ["Falsify first"],
+            "reflexes": [],
+            "values": [],
+            "critiques": [],
+            "blind_spots": [],
+            "system_hints": [],
+            "style": {
+                "tone": "direct",
+                "verbosity": "short",
+                "temperature": 0.3,
+                "contradiction_level": 70,
+                "exploration_level": 40,
+                "influence_intensity": "strong",
+                "response_style": "frontal",
+                "cite_hypotheses": true,
+                "signal_biases": true
+            },
+            "is_favorite": false
+        });
+        assert!(validate(&schema, &draft).is_empty());
+        let parsed: GeneratedAlterDraft = serde_json::from_value(draft.clone()).expect("parse");
+        assert_eq!(parsed.name.as_deref(), Some("Skeptic"));
+
+        draft["style"]["tone"] = json!("loud");
+        draft["inspirations"][0]["source_type"] = json!("book");
+        assert_eq!(validate(&schema, &draft).len(), 2);
     }
 
     #[test]
@@ -1087,6 +1495,20 @@ mod tests {
         assert_eq!(draft.color.as_deref(), Some("#8d6e63"));
     }
 
+    #[test]
+    fn invocation_prompt_uses_the_selected_prompt_pack() {
+        use crate::second_brain::prompt_packs::prompt_pack;
+
+        let record = normalize_create_payload(sample_create_payload("Test Alter"), None, None)
+            .expect("valid payload");
+        let english = compile_invocation_prompt(&record, prompt_pack(PromptLocale::En), &[]);
+        assert!(english.starts_with("Alter invocation contract."));
+        assert!(english.contains("Blind spots:\n- May overfit to tests"));
+        let french = compile_invocation_prompt(&record, prompt_pack(PromptLocale::Fr), &[]);
+        assert!(french.contains("Angles morts:\n- May overfit to tests"));
+        assert!(french.ends_with("sans etre theatral."));
+    }
+
     #[test]
     fn effective_generation_temperature_defaults_to_neutral() {
         assert_eq!(
@@ -1332,4 +1754,91 @@ mod tests {
         })
         .expect("invalid files should not break valid alters")
     }
+
+    #[test]
+    fn note_excerpts_follow_weights_and_skip_frontmatter() {
+        let mut record = normalize_create_payload(sample_create_payload("Test Alter"), None, None)
+            .expect("valid payload");
+        record.inspirations = ["a", "b"]
+            .iter()
+            .zip([Some(3.0), None])
+            .map(|(id, weight)| AlterInspiration {
+                id: id.to_string(),
+                label: id.to_string(),
+                source_type: AlterInspirationSourceType::Note,
+                weight,
+                reference_id: Some(format!("{id}.md")),
+                note_fingerprint: None,
+            })
+            .collect();
+        let notes = ["a", "b"]
+            .iter()
+            .map(|id| InspirationNote {
+                inspiration_id: id.to_string(),
+                path: format!("{id}.md"),
+                content: String::new(),
+                fingerprint: String::new(),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(note_excerpt_budgets(&record, &notes), vec![1_125, 375]);
+
+        let content = "---\ntags: [pricing]\n---\nValue first.\n\nThen the rest of a long note.";
+        assert_eq!(
+            note_excerpt(content, 100, "\n[CUT]\n"),
+            "Value first.\n\nThen the rest of a long note."
+        );
+        assert_eq!(note_excerpt(content, 4, "\n[CUT]\n"), "Value first.\n[CUT]");
+        assert_eq!(strip_frontmatter("No frontmatter"), "No frontmatter");
+    }
+
+    #[test]
+    fn file_backed_alters_ground_note_inspirations() {
+        use_test_workspace(|| {
+            let root = active_workspace_root()?;
+            fs::create_dir_all(root.join("notes"))?;
+            fs::write(
+                root.join("notes/strategy.md"),
+                "---\ntags: [pricing]\n---\nPrice on value, not cost.\n",
+            )?;
+            let mut payload = sample_create_payload("Grounded");
+            payload.inspirations.push(AlterInspiration {
+                id: String::new(),
+                label: "Pricing memo".to_string(),
+                source_type: AlterInspirationSourceType::Note,
+                weight: Some(2.0),
+                reference_id: Some("notes/strategy".to_string()),
+                note_fingerprint: None,
+            });
+            let created = create_alter(payload)?;
+            assert!(created
+                .invocation_prompt
+                .contains("strategy.md:\n  > Price on value, not cost.\n"));
+            assert!(!created.invocation_prompt.contains("tags: [pricing]"));
+            assert!(created.inspirations[1].note_fingerprint.is_some());
+            assert!(created.inspiration_issues.is_empty());
+
+            fs::write(root.join("notes/strategy.md"), "Bundle the support plan.\n")?;
+            let refreshed = load_alter(created.id.clone())?;
+            assert!(refreshed
+                .invocation_prompt
+                .contains("  > Bundle the support plan.\n"));
+            let stored = read_alter_file(&alter_path(&created.id)?)?;
+            assert_eq!(stored.invocation_prompt, refreshed.invocation_prompt);
+
+            fs::remove_file(root.join("notes/strategy.md"))?;
+            let broken = load_alter(created.id.clone())?;
+            assert_eq!(broken.inspiration_issues.len(), 1);
+            assert_eq!(broken.inspiration_issues[0].message, "Note not found.");
+            assert!(!broken
+                .invocation_prompt
+                .contains("Bundle the support plan."));
+            assert_eq!(
+                resolve_invocation_prompt(&Connection::open_in_memory()?, Some(&created.id))?,
+                Some(broken.invocation_prompt)
+            );
+
+            Ok(())
+        })
+        .expect("note inspirations should ground the invocation prompt")
+    }
 }
@@ -0,0 +1,682 @@
+//! Full-text search across Second Brain sessions and Alter explorations.
+//!
+//! Messages and exploration results are copied into `conversation_search_items`, kept in
+//! sync with an FTS5 table by triggers (same layout as `chunks`/`chunks_fts`). Second
+//! Brain messages are indexed when persisted and explorations when their file is
+//! written, updating only the items that changed. Conversations stored before the index
+//! existed are caught up once, on the first search against a fresh index.
+//!
+//! The context note filter reads a Second Brain session's current context items at query
+//! time, so context edits apply to its past messages without reindexing them.
+
+use std::collections::HashSet;
+
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+use crate::alter_exploration::{list_exploration_sessions, AlterExplorationSession};
+use crate::search_index::build_prefix_fts_query;
+use crate::second_brain::session_store::MessageRow;
+use crate::{
+    active_workspace_root, ensure_index_schema, normalize_workspace_relative_from_input, open_db,
+    Result,
+};
+
+const DEFAULT_LIMIT: usize = 50;
+const MAX_LIMIT: usize = 200;
+/// `internal_meta` key set once stored conversations have been caught up.
+const BACKFILL_META_KEY: &str = "conversation_search_backfilled";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConversationSource {
+    SecondBrain,
+    AlterExploration,
+}
+
+impl ConversationSource {
+    fn key(self) -> &'static str {
+        match self {
+            Self::SecondBrain => "second_brain",
+            Self::AlterExploration => "alter_exploration",
+        }
+    }
+
+    fn from_key(key: &str) -> Self {
+        if key == "alter_exploration" {
+            Self::AlterExploration
+        } else {
+            Self::SecondBrain
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SearchConversationsPayload {
+    pub query: String,
+    pub source: Option<ConversationSource>,
+    pub alter_id: Option<String>,
+    pub mode: Option<String>,
+    pub from_ms: Option<u64>,
+    pub to_ms: Option<u64>,
+    pub context_path: Option<String>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConversationSearchHit {
+    pub source: ConversationSource,
+    pub session_id: String,
+    /// Message id for Second Brain, `subject`, `synthesis` or `round-<n>-<alter>` for explorations.
+    pub message_id: String,
+    pub role: String,
+    pub snippet: String,
+    pub created_at_ms: u64,
+    pub score: f64,
+}
+
+/// One searchable text with the metadata used by filters.
+struct SearchItem<'a> {
+    source: ConversationSource,
+    session_id: &'a str,
+    item_id: String,
+    role: &'a str,
+    alter_ids: Vec<String>,
+    mode: &'a str,
+    context_paths: &'a [String],
+    created_at_ms: u64,
+    content: &'a str,
+}
+
+fn upsert_item(conn: &Connection, item: &SearchItem<'_>) -> Result<()> {
+    conn.execute(
+        "INSERT INTO conversation_search_items(
+            source_kind, session_id, item_id, role, alter_ids, mode, context_paths, created_at_ms, content
+         )
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+         ON CONFLICT(source_kind, session_id, item_id) DO UPDATE SET
+            role = excluded.role,
+            alter_ids = excluded.alter_ids,
+            mode = excluded.mode,
+            context_paths = excluded.context_paths,
+            created_at_ms = excluded.created_at_ms,
+            content = excluded.content
+         WHERE (role, alter_ids, mode, context_paths, created_at_ms, content)
+            IS NOT (excluded.role, excluded.alter_ids, excluded.mode, excluded.context_paths,
+                    excluded.created_at_ms, excluded.content)",
+        params![
+            item.source.key(),
+            item.session_id,
+            item.item_id,
+            item.role,
+            join_filter_values(&item.alter_ids),
+            item.mode,
+            join_filter_values(item.context_paths),
+            item.created_at_ms as i64,
+            item.content
+        ],
+    )?;
+    Ok(())
+}
+
+/// Multi-valued filter columns are newline separated so `instr` can match whole values.
+fn join_filter_values(values: &[String]) -> String {
+    values
+        .iter()
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Indexes a persisted Second Brain message with the Alter that produced or received it.
+pub(crate) fn index_second_brain_message(
+    conn: &Connection,
+    session_id: &str,
+    message: &MessageRow,
+    alter_id: &str,
+) -> Result<()> {
+    upsert_item(
+        conn,
+        &SearchItem {
+            source: ConversationSource::SecondBrain,
+            session_id,
+            item_id: message.id.clone(),
+            role: &message.role,
+            alter_ids: vec![alter_id.to_string()],
+            mode: &message.mode,
+            context_paths: &[],
+            created_at_ms: message.created_at_ms,
+            content: &message.content_md,
+        },
+    )
+}
+
+pub(crate) fn delete_second_brain_session_items(conn: &Connection, session_id: &str) -> Result<()> {
+    conn.execute(
+        "DELETE FROM conversation_search_items WHERE source_kind = ?1 AND session_id = ?2",
+        params![ConversationSource::SecondBrain.key(), session_id],
+    )?;
+    Ok(())
+}
+
+/// Brings the indexed subject, round results and synthesis of an exploration in line
+/// with the session, rewriting only items that changed.
+pub(crate) fn index_alter_exploration(
+    conn: &Connection,
+    session: &AlterExplorationSession,
+) -> Result<()> {
+    let mode = serde_json::to_value(&session.mode)
+        .ok()
+        .and_then(|value| value.as_str().map(ToOwned::to_owned))
+        .unwrap_or_default();
+    let context_paths = session
+        .subject
+        .source_id
+        .as_deref()
+        .unwrap_or("")
+        .split(['\n', ','])
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToOwned::to_owned)
+        .collect::<Vec<_>>();
+
+    let mut entries = vec![(
+        "subject".to_string(),
+        "subject",
+        session.alter_ids.clone(),
+        session.created_at_ms,
+        session.subject.text.as_str(),
+    )];
+    for result in &session.round_results {
+        entries.push((
+            format!("round-{}-{}", result.round_number, result.alter_id),
+            "round",
+            vec![result.alter_id.clone()],
+            match result.created_at_ms {
+                0 => session.created_at_ms,
+                created_at_ms => created_at_ms,
+            },
+            result.content.as_str(),
+        ));
+    }
+    if let Some(synthesis) = session.final_synthesis.as_deref() {
+        entries.push((
+            "synthesis".to_string(),
+            "synthesis",
+            session.alter_ids.clone(),
+            session.updated_at_ms,
+            synthesis,
+        ));
+    }
+    let tx = conn.unchecked_transaction()?;
+    let item_ids = entries
+        .iter()
+        .map(|entry| entry.0.clone())
+        .collect::<Vec<_>>();
+    tx.execute(
+        "DELETE FROM conversation_search_items
+         WHERE source_kind = ?1 AND session_id = ?2
+           AND instr(char(10) || ?3 || char(10), char(10) || item_id || char(10)) = 0",
+        params![
+            ConversationSource::AlterExploration.key(),
+            session.id,
+            item_ids.join("\n")
+        ],
+    )?;
+    for (item_id, role, alter_ids, created_at_ms, content) in entries {
+        upsert_item(
+            &tx,
+            &SearchItem {
+                source: ConversationSource::AlterExploration,
+                session_id: &session.id,
+                item_id,
+                role,
+                alter_ids,
+                mode: &mode,
+                context_paths: &context_paths,
+                created_at_ms,
+                content,
+            },
+        )?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Indexes Second Brain messages that were persisted before the search index existed.
+///
+/// The session Alter stands in for the per-message Alter, which was not recorded then.
+fn catch_up_second_brain_messages(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "INSERT INTO conversation_search_items(
+            source_kind, session_id, item_id, role, alter_ids, mode, context_paths, created_at_ms, content
+         )
+         SELECT ?1, m.session_id, m.id, m.role, COALESCE(s.alter_id, ''), m.mode, '',
+                m.created_at_ms, m.content_md
+         FROM second_brain_messages m
+         LEFT JOIN second_brain_sessions s ON s.id = m.session_id
+         WHERE NOT EXISTS (
+            SELECT 1 FROM conversation_search_items i
+            WHERE i.source_kind = ?1 AND i.session_id = m.session_id AND i.item_id = m.id
+         )",
+        params![ConversationSource::SecondBrain.key()],
+    )?;
+    Ok(())
+}
+
+/// Indexes exploration files written before the search index existed.
+fn catch_up_alter_explorations(conn: &Connection) -> Result<()> {
+    let indexed = {
+        let mut stmt = conn.prepare(
+            "SELECT DISTINCT session_id FROM conversation_search_items WHERE source_kind = ?1",
+        )?;
+        let rows = stmt.query_map(params![ConversationSource::AlterExploration.key()], |row| {
+            row.get::<_, String>(0)
+        })?;
+        rows.collect::<std::result::Result<HashSet<_>, _>>()?
+    };
+    for session in list_exploration_sessions()? {
+        if !indexed.contains(&session.id) {
+            index_alter_exploration(conn, &session)?;
+        }
+    }
+    Ok(())
+}
+
+/// Catches up stored conversations the first time a fresh index is searched.
+///
+/// Later writes index themselves, so searches do not rescan every exploration file.
+fn backfill_once(conn: &Connection) -> Result<()> {
+    let done = conn
+        .query_row(
+            "SELECT 1 FROM internal_meta WHERE key = ?1",
+            params![BACKFILL_META_KEY],
+            |_| Ok(()),
+        )
+        .is_ok();
+    if done {
+        return Ok(());
+    }
+    catch_up_second_brain_messages(conn)?;
+    catch_up_alter_explorations(conn)?;
+    conn.execute(
+        "INSERT OR REPLACE INTO internal_meta(key, value) VALUES (?1, '1')",
+        params![BACKFILL_META_KEY],
+    )?;
+    Ok(())
+}
+
+fn normalize_context_filter(raw: Option<&str>) -> String {
+    let value = raw.map(str::trim).unwrap_or("");
+    if value.is_empty() {
+        return String::new();
+    }
+    active_workspace_root()
+        .ok()
+        .and_then(|root| normalize_workspace_relative_from_input(&root, value).ok())
+        .unwrap_or_else(|| value.replace('\\', "/"))
+}
+
+fn search_items(
+    conn: &Connection,
+    fts_query: &str,
+    payload: &SearchConversationsPayload,
+) -> Result<Vec<ConversationSearchHit>> {
+    let mut stmt = conn.prepare(
+        r#"
+    SELECT i.source_kind,
+           i.session_id,
+           i.item_id,
+           i.role,
+           i.created_at_ms,
+           snippet(conversation_search_fts, 0, '<b>', '</b>', '...', 12) AS snip,
+           bm25(conversation_search_fts) AS score
+    FROM conversation_search_fts
+    JOIN conversation_search_items i ON conversation_search_fts.rowid = i.id
+    WHERE conversation_search_fts MATCH ?1
+      AND (?2 = '' OR i.source_kind = ?2)
+      AND (?3 = '' OR instr(char(10) || i.alter_ids || char(10), char(10) || ?3 || char(10)) > 0)
+      AND (?4 = '' OR i.mode = ?4)
+      AND (?5 < 0 OR i.created_at_ms >= ?5)
+      AND (?6 < 0 OR i.created_at_ms <= ?6)
+      AND (?7 = '' OR CASE
+            WHEN i.source_kind = ?9 THEN EXISTS (
+              SELECT 1 FROM second_brain_context_items c
+              WHERE c.session_id = i.session_id AND c.path = ?7
+            )
+            ELSE instr(char(10) || i.context_paths || char(10), char(10) || ?7 || char(10)) > 0
+          END)
+    ORDER BY score
+    LIMIT ?8;
+  "#,
+    )?;
+    let limit = payload.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let mut rows = stmt.query(params![
+        fts_query,
+        payload.source.map(ConversationSource::key).unwrap_or(""),
+        payload.alter_id.as_deref().map(str::trim).unwrap_or(""),
+        payload.mode.as_deref().map(str::trim).unwrap_or(""),
+        payload.from_ms.map(|value| value as i64).unwrap_or(-1),
+        payload.to_ms.map(|value| value as i64).unwrap_or(-1),
+        normalize_context_filter(payload.context_path.as_deref()),
+        limit as i64,
+        ConversationSource::SecondBrain.key()
+    ])?;
+
+    let mut hits = Vec::new();
+    while let Some(row) = rows.next()? {
+        hits.push(ConversationSearchHit {
+            source: ConversationSource::from_key(&row.get::<_, String>(0)?),
+            session_id: row.get::<_, String>(1)?,
+            message_id: row.get::<_, String>(2)?,
+            role: row.get::<_, String>(3)?,
+            created_at_ms: row.get::<_, i64>(4)? as u64,
+            snippet: row.get::<_, String>(5)?,
+            score: -row.get::<_, f64>(6)?,
+        });
+    }
+    Ok(hits)
+}
+
+/// Searches past Second Brain messages and Alter exploration results, best matches first.
+#[tauri::command]
+pub fn search_conversations(
+    payload: SearchConversationsPayload,
+) -> Result<Vec<ConversationSearchHit>> {
+    let Some(fts_query) = build_prefix_fts_query(payload.query.trim()) else {
+        return Ok(Vec::new());
+    };
+    let conn = open_db()?;
+    ensure_index_schema(&conn)?;
+    backfill_once(&conn)?;
+    search_items(&conn, &fts_query, &payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memory_db() -> Connection {
+        let conn = Connection::open_in_memory().expect("open memory db");
+        ensure_index_schema(&conn).expect("create schema");
+        conn
+    }
+
+    fn message(id: &str, mode: &str, content: &str, created_at_ms: u64) -> MessageRow {
+        MessageRow {
+            id: id.to_string(),
+            parent_id: String::new(),
+            role: "user".to_string(),
+            mode: mode.to_string(),
+            content_md: content.to_string(),
+            citations_json: "[]".to_string(),
+            attachments_json: "[]".to_string(),
+            created_at_ms,
+        }
+    }
+
+    fn index(conn: &Connection, session_id: &str, message_id: &str, content: &str, ts: u64) {
+        index_second_brain_message(
+            conn,
+            session_id,
+            &message(message_id, "freestyle", content, ts),
+            "alter-a",
+        )
+        .expect("index message");
+    }
+
+    fn search(conn: &Connection, payload: SearchConversationsPayload) -> Vec<String> {
+        let fts_query = build_prefix_fts_query(&payload.query).expect("fts query");
+        search_items(conn, &fts_query, &payload)
+            .expect("search")
+            .into_iter()
+            .map(|hit| hit.message_id)
+            .collect()
+    }
+
+    #[test]
+    fn finds_messages_by_prefix_with_snippets() {
+        let conn = memory_db();
+        index(
+            &conn,
+            "s1",
+            "m1",
+            "Strategie de publication trimestrielle",
+            10,
+        );
+        index(&conn, "s1", "m2", "Autre sujet", 20);
+
+        let hits = search_items(
+            &conn,
+            &build_prefix_fts_query("publi").unwrap(),
+            &SearchConversationsPayload::default(),
+        )
+        .unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].session_id, "s1");
+        assert_eq!(hits[0].message_id, "m1");
+        assert!(hits[0].snippet.contains("<b>publication</b>"));
+    }
+
+    #[test]
+    fn filters_by_alter_mode_and_date_range() {
+        let conn = memory_db();
+        index(&conn, "s1", "m1", "roadmap early", 10);
+        index(&conn, "s1", "m2", "roadmap late", 50);
+        index_second_brain_message(
+            &conn,
+            "s2",
+            &message("m3", "diagnostic", "roadmap other", 30),
+            "alter-b",
+        )
+        .unwrap();
+
+        let by_alter = search(
+            &conn,
+            SearchConversationsPayload {
+                query: "roadmap".to_string(),
+                alter_id: Some("alter-b".to_string()),
+                ..Default::default()
+            },
+        );
+        assert_eq!(by_alter, vec!["m3"]);
+
+        let by_mode = search(
+            &conn,
+            SearchConversationsPayload {
+                query: "roadmap".to_string(),
+                mode: Some("freestyle".to_string()),
+                ..Default::default()
+            },
+        );
+        assert_eq!(by_mode.len(), 2);
+
+        let mut by_date = search(
+            &conn,
+            SearchConversationsPayload {
+                query: "roadmap".to_string(),
+                from_ms: Some(20),
+                to_ms: Some(60),
+                ..Default::default()
+            },
+        );
+        by_date.sort();
+        assert_eq!(by_date, vec!["m2", "m3"]);
+    }
+
+    #[test]
+    fn filters_by_context_note_and_catches_up_legacy_messages() {
+        let conn = memory_db();
+        conn.execute_batch(
+            r#"
+            INSERT INTO second_brain_sessions(id, alter_id) VALUES ('s1', 'alter-a');
+            INSERT INTO second_brain_context_items(session_id, path, sort_order)
+              VALUES ('s1', 'notes/plan.md', 0), ('s1', 'notes/plan-old.md', 1);
+            INSERT INTO second_brain_messages(id, session_id, role, content_md, created_at_ms)
+              VALUES ('m1', 's1', 'assistant', 'budget review', 10);
+            "#,
+        )
+        .unwrap();
+        catch_up_second_brain_messages(&conn).unwrap();
+        catch_up_second_brain_messages(&conn).unwrap();
+
+        let hits = search(
+            &conn,
+            SearchConversationsPayload {
+                query: "budget".to_string(),
+                alter_id: Some("alter-a".to_string()),
+                context_path: Some("notes/plan.md".to_string()),
+                ..Default::default()
+            },
+        );
+        assert_eq!(hits, vec!["m1"]);
+
+        let other_note = search(
+            &conn,
+            SearchConversationsPayload {
+                query: "budget".to_string(),
+                context_path: Some("notes/plan-new.md".to_string()),
+                ..Default::default()
+            },
+        );
+        assert!(other_note.is_empty());
+    }
+
+    #[test]
+    fn context_filter_follows_the_current_session_context() {
+        let conn = memory_db();
+        conn.execute_batch(
+            r#"
+            INSERT INTO second_brain_sessions(id, alter_id) VALUES ('s1', 'alter-a');
+            INSERT INTO second_brain_context_items(session_id, path, sort_order)
+              VALUES ('s1', 'notes/plan.md', 0);
+            "#,
+        )
+        .unwrap();
+        index(&conn, "s1", "m1", "budget review", 10);
+        conn.execute_batch(
+            r#"
+            DELETE FROM second_brain_context_items WHERE session_id = 's1';
+            INSERT INTO second_brain_context_items(session_id, path, sort_order)
+              VALUES ('s1', 'notes/plan-new.md', 0);
+            "#,
+        )
+        .unwrap();
+
+        let by_context = |path: &str| {
+            search(
+                &conn,
+                SearchConversationsPayload {
+                    query: "budget".to_string(),
+                    context_path: Some(path.to_string()),
+                    ..Default::default()
+                },
+            )
+        };
+        assert!(by_context("notes/plan.md").is_empty());
+        assert_eq!(by_context("notes/plan-new.md"), vec!["m1"]);
+    }
+
+    #[test]
+    fn exploration_rounds_keep_their_own_time_and_drop_stale_items() {
+        let conn = memory_db();
+        let mut session: AlterExplorationSession = serde_json::from_value(serde_json::json!({
+            "id": "e1",
+            "workspace_id": "w",
+            "subject": {"subject_type": "prompt", "text": "pricing plan", "source_id": null},
+            "alter_ids": ["alter-a", "alter-b"],
+            "mode": "explore",
+            "rounds": 2,
+            "output_format": "summary",
+            "state": "completed",
+            "round_results": [
+                {"round_number": 1, "alter_id": "alter-a", "content": "pricing tiers", "created_at_ms": 100},
+                {"round_number": 2, "alter_id": "alter-b", "content": "pricing risks", "created_at_ms": 200},
+                {"round_number": 1, "alter_id": "alter-b", "content": "pricing legacy"}
+            ],
+            "final_synthesis": "pricing synthesis",
+            "error_message": null,
+            "created_at_ms": 10,
+            "updated_at_ms": 300
+        }))
+        .expect("session");
+        index_alter_exploration(&conn, &session).unwrap();
+
+        let mut late = search(
+            &conn,
+            SearchConversationsPayload {
+                query: "pricing".to_string(),
+                from_ms: Some(150),
+                ..Default::default()
+            },
+        );
+        late.sort();
+        assert_eq!(late, vec!["round-2-alter-b", "synthesis"]);
+        let early = search(
+            &conn,
+            SearchConversationsPayload {
+                query: "pricing".to_string(),
+                to_ms: Some(50),
+                ..Default::default()
+            },
+        );
+        assert_eq!(early.len(), 2);
+
+        session.round_results.truncate(1);
+        session.final_synthesis = None;
+        index_alter_exploration(&conn, &session).unwrap();
+        let mut remaining = search(
+            &conn,
+            SearchConversationsPayload {
+                query: "pricing".to_string(),
+                ..Default::default()
+            },
+        );
+        remaining.sort();
+        assert_eq!(remaining, vec!["round-1-alter-a", "subject"]);
+    }
+
+    #[test]
+    fn stored_conversations_are_caught_up_once_per_index() {
+        let conn = memory_db();
+        conn.execute_batch(
+            r#"
+            INSERT INTO second_brain_sessions(id, alter_id) VALUES ('s1', 'alter-a');
+            INSERT INTO second_brain_messages(id, session_id, role, content_md, created_at_ms)
+              VALUES ('m1', 's1', 'assistant', 'budget review', 10);
+            "#,
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO internal_meta(key, value) VALUES (?1, '1')",
+            params![BACKFILL_META_KEY],
+        )
+        .unwrap();
+
+        backfill_once(&conn).unwrap();
+        assert!(search(
+            &conn,
+            SearchConversationsPayload {
+                query: "budget".to_string(),
+                ..Default::default()
+            },
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn deleting_a_session_removes_its_hits() {
+        let conn = memory_db();
+        index(&conn, "s1", "m1", "archived idea", 10);
+        delete_second_brain_session_items(&conn, "s1").unwrap();
+        assert!(search(
+            &conn,
+            SearchConversationsPayload {
+                query: "archived".to_string(),
+                ..Default::default()
+            },
+        )
+        .is_empty());
+    }
+}
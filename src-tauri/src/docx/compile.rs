@@ -0,0 +1,715 @@
+//! Multi-note DOCX compilation.
+//!
+//! Several notes become one document: a folder in path order, an explicit list,
+//! or a manuscript note whose wikilinks give the reading order. Every note gets a
+//! bookmark so wikilinks between compiled notes turn into internal hyperlinks;
+//! links to notes left out of the compilation degrade to plain text. Footnotes
+//! are numbered across the whole document.
+
+use std::{collections::HashMap, fs, path::Path};
+
+use comrak::{
+    nodes::{AstNode, NodeValue},
+    parse_document, Arena,
+};
+use rdocx::Document;
+use serde::{Deserialize, Serialize};
+
+use crate::docx::conversion::{
+    append_heading, append_paragraph, collect_inline_segments_for_node, is_markdown_path,
+    load_template_style, markdown_options, next_available_output_path, render_block,
+    resolve_image_sources, rewrite_docx, save_document, RenderContext, RunSegment, LINK_COLOR,
+    TEMPLATE_DIR_NAME,
+};
+use crate::docx::default_style::{TemplateStyle, TextStyle};
+use crate::docx::links::{link_wikilinks, take_footnotes, Footnote, WikilinkMode};
+use crate::markdown_index::{
+    normalize_wikilink_target, parse_wikilink_targets, parse_yaml_frontmatter_properties,
+    strip_yaml_frontmatter,
+};
+use crate::wikilink_graph::NoteKeyResolver;
+use crate::{
+    active_workspace_root, ensure_within_root, list_markdown_files_via_find,
+    normalize_workspace_path, normalize_workspace_relative_path, AppError, Result,
+};
+
+const CONTENTS_TITLE: &str = "Contents";
+// Private-use characters marking the paragraph that opens each note until the
+// XML post-processing pass replaces it with a bookmark.
+const NOTE_MARKER: char = '\u{F8F2}';
+const NOTE_BREAK_MARKER: char = '\u{F8F3}';
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CompileSource {
+    /// Every note of a folder, ordered by path.
+    Folder {
+        path: String,
+        #[serde(default)]
+        recursive: bool,
+    },
+    /// Notes in the given order.
+    Notes { paths: Vec<String> },
+    /// The notes a manuscript note links to, in link order. The manuscript
+    /// itself is not part of the output.
+    Manuscript { path: String },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CompileDocxPayload {
+    pub source: CompileSource,
+    /// Start every note with its title as a level-1 heading.
+    #[serde(default = "default_true")]
+    pub include_titles: bool,
+    /// Levels added to every heading inside the notes. By default headings are
+    /// shifted just enough to sit below the inserted titles.
+    #[serde(default)]
+    pub heading_shift: Option<u8>,
+    #[serde(default = "default_true")]
+    pub page_breaks: bool,
+    /// Open the document with a linked list of the compiled notes.
+    #[serde(default)]
+    pub table_of_contents: bool,
+    /// Output file name without extension.
+    #[serde(default)]
+    pub output_name: Option<String>,
+    #[serde(default)]
+    pub wikilinks: WikilinkMode,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CompileDocxResult {
+    pub output_path: String,
+    /// Workspace-relative paths of the compiled notes, in document order.
+    pub notes: Vec<String>,
+    /// Manuscript links that did not resolve to a note.
+    pub skipped: Vec<String>,
+    /// Wikilink targets that do not point at a compiled note.
+    pub unresolved_links: Vec<String>,
+}
+
+struct CompiledNote {
+    relative: String,
+    title: String,
+    markdown: String,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[tauri::command]
+pub async fn compile_notes_to_docx(payload: CompileDocxPayload) -> Result<CompileDocxResult> {
+    tauri::async_runtime::spawn_blocking(move || compile_notes_to_docx_sync(payload))
+        .await
+        .map_err(|_| AppError::OperationFailed)?
+}
+
+fn compile_notes_to_docx_sync(payload: CompileDocxPayload) -> Result<CompileDocxResult> {
+    let root = active_workspace_root()?;
+    let workspace_notes = list_markdown_files_via_find(&root)?
+        .into_iter()
+        .filter_map(|path| normalize_workspace_relative_path(&root, &path).ok())
+        .collect::<Vec<_>>();
+    let resolver = NoteKeyResolver::from_paths(&root, &workspace_notes);
+
+    let (sources, skipped, output_dir, default_name) = match &payload.source {
+        CompileSource::Folder { path, recursive } => {
+            let folder = normalize_workspace_path(&root, path)?;
+            ensure_within_root(&root, &folder)?;
+            if !folder.is_dir() {
+                return Err(AppError::InvalidPath);
+            }
+            let name = folder
+                .file_name()
+                .filter(|_| folder != root)
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| "Workspace".to_string());
+            let notes = folder_notes(&root, &folder, *recursive)?;
+            (notes, Vec::new(), folder, name)
+        }
+        CompileSource::Notes { paths } => {
+            let mut notes = Vec::new();
+            for path in paths {
+                let relative = workspace_note(&root, path)?;
+                if !notes.contains(&relative) {
+                    notes.push(relative);
+                }
+            }
+            let first = notes.first().ok_or_else(nothing_to_compile)?;
+            let output_dir = root.join(first).parent().unwrap_or(&root).to_path_buf();
+            (notes, Vec::new(), output_dir, "Compilation".to_string())
+        }
+        CompileSource::Manuscript { path } => {
+            let relative = workspace_note(&root, path)?;
+            let markdown = fs::read_to_string(root.join(&relative))?;
+            let (notes, skipped) = manuscript_notes(&markdown, &relative, &resolver);
+            let path = root.join(&relative);
+            let stem = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .ok_or(AppError::InvalidPath)?;
+            let output_dir = path.parent().unwrap_or(&root).to_path_buf();
+            (notes, skipped, output_dir, stem)
+        }
+    };
+    if sources.is_empty() {
+        return Err(nothing_to_compile());
+    }
+
+    let name = match payload.output_name.as_deref().map(str::trim) {
+        Some(name) if !name.is_empty() => {
+            if name.contains(['/', '\\']) || name.starts_with('.') {
+                return Err(AppError::InvalidName);
+            }
+            name.to_string()
+        }
+        _ => default_name,
+    };
+    let output_path = next_available_output_path(&output_dir.join(format!("{name}.docx")));
+
+    let mut notes = Vec::with_capacity(sources.len());
+    for relative in sources {
+        let markdown = fs::read_to_string(root.join(&relative))?;
+        let title = note_title(&relative, &markdown);
+        notes.push(CompiledNote {
+            relative,
+            title,
+            markdown,
+        });
+    }
+
+    let (template_style, template_path) = load_template_style(&root)?;
+    let (mut doc, unresolved_links, footnotes) =
+        build_compiled_document(&root, &notes, &payload, &resolver, &template_style);
+    save_document(&mut doc, &output_path, template_path.as_deref(), &footnotes)?;
+    place_note_bookmarks(&output_path)?;
+
+    Ok(CompileDocxResult {
+        output_path: output_path.to_string_lossy().to_string(),
+        notes: notes.into_iter().map(|note| note.relative).collect(),
+        skipped,
+        unresolved_links,
+    })
+}
+
+fn nothing_to_compile() -> AppError {
+    AppError::InvalidOperation("No notes to compile.".to_string())
+}
+
+fn workspace_note(root: &Path, path: &str) -> Result<String> {
+    let path = normalize_workspace_path(root, path)?;
+    ensure_within_root(root, &path)?;
+    if !path.is_file() || !is_markdown_path(&path) {
+        return Err(AppError::InvalidPath);
+    }
+    normalize_workspace_relative_path(root, &path)
+}
+
+fn folder_notes(root: &Path, folder: &Path, recursive: bool) -> Result<Vec<String>> {
+    let mut notes = list_markdown_files_via_find(folder)?
+        .into_iter()
+        .filter(|path| recursive || path.parent() == Some(folder))
+        .filter(|path| {
+            !path
+                .strip_prefix(root)
+                .map(|relative| relative.starts_with(TEMPLATE_DIR_NAME))
+                .unwrap_or(true)
+        })
+        .filter_map(|path| normalize_workspace_relative_path(root, &path).ok())
+        .collect::<Vec<_>>();
+    notes.sort_by_key(|relative| relative.to_lowercase());
+    Ok(notes)
+}
+
+/// Resolves the manuscript's wikilinks in reading order. Repeated links keep
+/// their first position and links back to the manuscript are ignored.
+fn manuscript_notes(
+    markdown: &str,
+    manuscript: &str,
+    resolver: &NoteKeyResolver,
+) -> (Vec<String>, Vec<String>) {
+    let mut notes = Vec::new();
+    let mut skipped = Vec::new();
+    for target in parse_wikilink_targets(strip_yaml_frontmatter(markdown)) {
+        let resolved = normalize_wikilink_target(&target)
+            .and_then(|key| resolver.resolve(&key).map(str::to_string));
+        match resolved {
+            Some(relative) if relative == manuscript => {}
+            Some(relative) => {
+                if !notes.contains(&relative) {
+                    notes.push(relative);
+                }
+            }
+            None => {
+                if !skipped.contains(&target) {
+                    skipped.push(target);
+                }
+            }
+        }
+    }
+    (notes, skipped)
+}
+
+/// Frontmatter `title`, then a leading level-1 heading, then the file stem.
+fn note_title(relative: &str, markdown: &str) -> String {
+    let frontmatter_title = parse_yaml_frontmatter_properties(markdown)
+        .into_iter()
+        .find(|property| property.key == "title")
+        .and_then(|property| property.value_text)
+        .map(|title| title.trim().to_string())
+        .filter(|title| !title.is_empty());
+    let heading = strip_yaml_frontmatter(markdown)
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .and_then(|line| line.strip_prefix("# "))
+        .map(|heading| heading.trim().to_string());
+    frontmatter_title.or(heading).unwrap_or_else(|| {
+        Path::new(relative)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| relative.to_string())
+    })
+}
+
+fn build_compiled_document(
+    root: &Path,
+    notes: &[CompiledNote],
+    payload: &CompileDocxPayload,
+    resolver: &NoteKeyResolver,
+    template: &TemplateStyle,
+) -> (Document, Vec<String>, Vec<Footnote>) {
+    let bookmark_by_note = notes
+        .iter()
+        .enumerate()
+        .map(|(index, note)| (note.relative.clone(), note_bookmark(index)))
+        .collect::<HashMap<_, _>>();
+    let mut unresolved = Vec::new();
+    let mut footnotes = Vec::<Footnote>::new();
+    let mut doc = Document::new();
+
+    if payload.table_of_contents {
+        append_heading(&mut doc, 1, plain_segments(CONTENTS_TITLE), template);
+        for (index, note) in notes.iter().enumerate() {
+            let style = TextStyle {
+                link: Some(format!("#{}", note_bookmark(index))),
+                color: Some(LINK_COLOR.to_string()),
+                ..template.body().run.clone()
+            };
+            let segments = vec![RunSegment {
+                text: note.title.clone(),
+                style,
+            }];
+            append_paragraph(&mut doc, segments, template, RenderContext::default());
+        }
+    }
+
+    let options = markdown_options();
+    for (index, note) in notes.iter().enumerate() {
+        let page_break = payload.page_breaks && (index > 0 || payload.table_of_contents);
+        let marker = if page_break {
+            NOTE_BREAK_MARKER
+        } else {
+            NOTE_MARKER
+        };
+        let _ = doc.add_paragraph(&format!("{marker}{}", index + 1));
+
+        let arena = Arena::new();
+        let ast = parse_document(&arena, strip_yaml_frontmatter(&note.markdown), &options);
+        if payload.include_titles {
+            append_heading(&mut doc, 1, plain_segments(&note.title), template);
+            drop_leading_title(ast, &note.title, template);
+        }
+        let shift = payload.heading_shift.unwrap_or_else(|| {
+            match (payload.include_titles, top_heading_level(ast)) {
+                (true, Some(level)) => 2u8.saturating_sub(level),
+                _ => 0,
+            }
+        });
+        for node in ast.descendants() {
+            if let NodeValue::Heading(heading) = &mut node.data.borrow_mut().value {
+                heading.level = heading.level.saturating_add(shift).min(6);
+            }
+        }
+
+        let scope = format!("n{}", index + 1);
+        let note_unresolved = link_wikilinks(ast, payload.wikilinks, &scope, |target| {
+            let relative = if target.is_empty() {
+                Some(note.relative.as_str())
+            } else {
+                normalize_wikilink_target(target).and_then(|key| resolver.resolve(&key))
+            };
+            relative.and_then(|relative| bookmark_by_note.get(relative).cloned())
+        });
+        for target in note_unresolved {
+            if !unresolved.contains(&target) {
+                unresolved.push(target);
+            }
+        }
+        let note_dir = root.join(&note.relative);
+        resolve_image_sources(ast, note_dir.parent().unwrap_or(root), root);
+        let offset = footnotes.last().map_or(0, |footnote| footnote.id);
+        footnotes.extend(take_footnotes(ast, template, offset));
+
+        for child in ast.children() {
+            render_block(child, &mut doc, template, RenderContext::default());
+        }
+    }
+
+    (doc, unresolved, footnotes)
+}
+
+fn note_bookmark(index: usize) -> String {
+    format!("note_{}", index + 1)
+}
+
+fn plain_segments(text: &str) -> Vec<RunSegment> {
+    vec![RunSegment {
+        text: text.to_string(),
+        style: TextStyle::default(),
+    }]
+}
+
+/// Removes the note's opening level-1 heading when it repeats the title.
+fn drop_leading_title<'a>(root: &'a AstNode<'a>, title: &str, template: &TemplateStyle) {
+    let Some(first) = root.first_child() else {
+        return;
+    };
+    if !matches!(first.data.borrow().value, NodeValue::Heading(ref heading) if heading.level == 1) {
+        return;
+    }
+    let text = collect_inline_segments_for_node(first, TextStyle::default(), template)
+        .iter()
+        .map(|segment| segment.text.as_str())
+        .collect::<String>();
+    if text.trim() == title {
+        first.detach();
+    }
+}
+
+fn top_heading_level<'a>(root: &'a AstNode<'a>) -> Option<u8> {
+    root.descendants()
+        .filter_map(|node| match &node.data.borrow().value {
+            NodeValue::Heading(heading) => Some(heading.level),
+            _ => None,
+        })
+        .min()
+}
+
+fn place_note_bookmarks(docx_path: &Path) -> Result<()> {
+    rewrite_docx(docx_path, |name, bytes| {
+        if name == "word/document.xml" {
+            let xml = String::from_utf8(bytes.to_vec()).map_err(|err| {
+                AppError::InvalidOperation(format!("DOCX postprocess decode xml failed: {err}"))
+            })?;
+            Ok(place_note_bookmarks_in_xml(&xml).into_bytes())
+        } else {
+            Ok(bytes.to_vec())
+        }
+    })
+}
+
+/// Replaces each note marker paragraph with a bookmark (and page break) on the
+/// paragraph that follows it, so no empty paragraph is left behind.
+fn place_note_bookmarks_in_xml(xml: &str) -> String {
+    let mut output = String::with_capacity(xml.len());
+    let mut remainder = xml;
+
+    while let Some(marker) = remainder.find([NOTE_MARKER, NOTE_BREAK_MARKER]) {
+        let paragraph_start = paragraph_start_before(&remainder[..marker]);
+        let paragraph_end = remainder[marker..]
+            .find("</w:p>")
+            .map(|end| marker + end + "</w:p>".len());
+        let (Some(paragraph_start), Some(paragraph_end)) = (paragraph_start, paragraph_end) else {
+            break;
+        };
+        let page_break = remainder[marker..].starts_with(NOTE_BREAK_MARKER);
+        let id = remainder[marker + NOTE_MARKER.len_utf8()..]
+            .chars()
+            .take_while(char::is_ascii_digit)
+            .collect::<String>();
+        let bookmark = format!(
+            r#"<w:bookmarkStart w:id="{id}" w:name="note_{id}"/><w:bookmarkEnd w:id="{id}"/>"#
+        );
+        output.push_str(&remainder[..paragraph_start]);
+        remainder = &remainder[paragraph_end..];
+
+        let next = remainder.trim_start();
+        let next_end = next.find("</w:p>").map(|end| end + "</w:p>".len());
+        match next_end {
+            Some(next_end) if next.starts_with("<w:p>") || next.starts_with("<w:p ") => {
+                output.push_str(&remainder[..remainder.len() - next.len()]);
+                output.push_str(&mark_paragraph(&next[..next_end], &bookmark, page_break));
+                remainder = &next[next_end..];
+            }
+            _ => {
+                let properties = if page_break {
+                    "<w:pPr><w:pageBreakBefore/></w:pPr>"
+                } else {
+                    ""
+                };
+                output.push_str(&format!("<w:p>{properties}{bookmark}</w:p>"));
+            }
+        }
+    }
+
+    output.push_str(remainder);
+    output
+}
+
+fn paragraph_start_before(xml: &str) -> Option<usize> {
+    [xml.rfind("<w:p>"), xml.rfind("<w:p ")]
+        .into_iter()
+        .flatten()
+        .max()
+}
+
+fn mark_paragraph(paragraph: &str, bookmark: &str, page_break: bool) -> String {
+    let open_end = paragraph
+        .find('>')
+        .map(|end| end + 1)
+        .unwrap_or(paragraph.len());
+    let properties = paragraph[open_end..]
+        .starts_with("<w:pPr>")
+        .then(|| paragraph[open_end..].find("</w:pPr>"))
+        .flatten()
+        .map(|end| open_end + end);
+
+    let mut output = String::with_capacity(paragraph.len() + bookmark.len() + 64);
+    match properties {
+        Some(properties_end) => {
+            let mut cursor = open_end + "<w:pPr>".len();
+            // `pageBreakBefore` follows `pStyle`, `keepNext` and `keepLines`.
+            for element in ["<w:pStyle", "<w:keepNext", "<w:keepLines"] {
+                if paragraph[cursor..].starts_with(element) {
+                    cursor += element_len(&paragraph[cursor..]);
+                }
+            }
+            output.push_str(&paragraph[..cursor]);
+            if page_break {
+                output.push_str("<w:pageBreakBefore/>");
+            }
+            output.push_str(&paragraph[cursor..properties_end + "</w:pPr>".len()]);
+            output.push_str(bookmark);
+            output.push_str(&paragraph[properties_end + "</w:pPr>".len()..]);
+        }
+        None => {
+            output.push_str(&paragraph[..open_end]);
+            if page_break {
+                output.push_str("<w:pPr><w:pageBreakBefore/></w:pPr>");
+            }
+            output.push_str(bookmark);
+            output.push_str(&paragraph[open_end..]);
+        }
+    }
+    output
+}
+
+/// Length of the XML element at the start of `xml`, self-closing or not.
+fn element_len(xml: &str) -> usize {
+    let Some(open_end) = xml.find('>') else {
+        return xml.len();
+    };
+    if xml[..open_end].ends_with('/') {
+        return open_end + 1;
+    }
+    let name = xml[1..open_end]
+        .split_whitespace()
+        .next()
+        .unwrap_or_default();
+    let closing = format!("</{name}>");
+    xml.find(&closing)
+        .map(|end| end + closing.len())
+        .unwrap_or(open_end + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        fs::File,
+        io::Read,
+        path::PathBuf,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    use zip::ZipArchive;
+
+    fn create_temp_workspace(prefix: &str) -> PathBuf {
+        let nonce = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|value| value.as_nanos())
+            .unwrap_or(0);
+        let dir = std::env::temp_dir().join(format!("{prefix}-{nonce}"));
+        fs::create_dir_all(&dir).expect("create temp workspace");
+        dir
+    }
+
+    fn read_document_xml(path: &str) -> String {
+        let file = File::open(path).expect("open docx file");
+        let mut archive = ZipArchive::new(file).expect("open docx zip");
+        let mut xml = String::new();
+        archive
+            .by_name("word/document.xml")
+            .expect("document entry")
+            .read_to_string(&mut xml)
+            .expect("read document xml");
+        xml
+    }
+
+    fn payload(source: CompileSource) -> CompileDocxPayload {
+        CompileDocxPayload {
+            source,
+            include_titles: true,
+            heading_shift: None,
+            page_breaks: true,
+            table_of_contents: false,
+            output_name: None,
+            wikilinks: WikilinkMode::default(),
+        }
+    }
+
+    #[test]
+    fn compiles_folder_in_path_order_with_linked_bookmarks() {
+        let _guard = crate::workspace_test_guard();
+        let workspace = create_temp_workspace("tomosona-docx-compile-folder");
+        let book = workspace.join("Book");
+        fs::create_dir_all(book.join("drafts")).expect("create folders");
+        fs::write(
+            book.join("02 Middle.md"),
+            concat!(
+                "# Middle\n\n## Scene\n\nBack to [[01 Start|the start]] and [[Elsewhere]].[^a]\n\n",
+                "See [[#Scene]].\n\n[^a]: Second.\n"
+            ),
+        )
+        .expect("write middle");
+        fs::write(
+            book.join("01 Start.md"),
+            "Opening line.[^a]\n\n[^a]: First.\n",
+        )
+        .expect("write start");
+        fs::write(book.join("drafts/03 Draft.md"), "Draft.\n").expect("write draft");
+
+        crate::set_active_workspace(&workspace.to_string_lossy()).expect("set workspace");
+        let result = compile_notes_to_docx_sync(CompileDocxPayload {
+            table_of_contents: true,
+            ..payload(CompileSource::Folder {
+                path: "Book".to_string(),
+                recursive: false,
+            })
+        })
+        .expect("compile");
+
+        assert_eq!(result.notes, vec!["Book/01 Start.md", "Book/02 Middle.md"]);
+        assert_eq!(result.unresolved_links, vec!["Elsewhere"]);
+        assert!(result.output_path.ends_with("Book/Book.docx"));
+
+        let doc = Document::open(&result.output_path).expect("open docx");
+        let headings = doc
+            .paragraphs()
+            .into_iter()
+            .filter_map(|paragraph| {
+                paragraph
+                    .style_id()
+                    .map(|style| (style.to_string(), paragraph.text()))
+            })
+            .collect::<Vec<_>>();
+        assert!(headings.contains(&("Heading1".to_string(), "Middle".to_string())));
+        assert!(headings.contains(&("Heading2".to_string(), "Scene".to_string())));
+        assert_eq!(
+            headings
+                .iter()
+                .filter(|(style, text)| style.starts_with("Heading") && text == "Middle")
+                .count(),
+            1
+        );
+
+        let xml = read_document_xml(&result.output_path);
+        assert!(xml.contains(r#"w:name="note_1""#));
+        assert!(xml.contains(r#"w:name="note_2""#));
+        assert_eq!(xml.matches("<w:pageBreakBefore/>").count(), 2);
+        assert_eq!(xml.matches(r#"w:anchor="note_1""#).count(), 2);
+        assert!(xml.contains(r#"w:anchor="note_2""#));
+        assert!(xml.contains(r#"w:anchor="n2_scene""#));
+        assert!(xml.contains(r#"<w:footnoteReference w:id="1"/>"#));
+        assert!(xml.contains(r#"<w:footnoteReference w:id="2"/>"#));
+        assert!(!xml.contains(NOTE_MARKER) && !xml.contains(NOTE_BREAK_MARKER));
+        assert!(!xml.contains('\u{F8F0}'));
+    }
+
+    #[test]
+    fn compiles_manuscript_in_link_order() {
+        let _guard = crate::workspace_test_guard();
+        let workspace = create_temp_workspace("tomosona-docx-compile-manuscript");
+        fs::create_dir_all(workspace.join("chapters")).expect("create chapters");
+        fs::write(workspace.join("chapters/b.md"), "Chapter B.\n").expect("write b");
+        fs::write(workspace.join("chapters/a.md"), "Chapter A.\n").expect("write a");
+        let manuscript = workspace.join("Novel.md");
+        fs::write(
+            &manuscript,
+            "# Novel\n\n1. [[b]]\n2. [[chapters/a]]\n3. [[b]]\n4. [[Missing]]\n5. [[Novel]]\n",
+        )
+        .expect("write manuscript");
+
+        crate::set_active_workspace(&workspace.to_string_lossy()).expect("set workspace");
+        let result = compile_notes_to_docx_sync(CompileDocxPayload {
+            include_titles: false,
+            page_breaks: false,
+            output_name: Some("Draft".to_string()),
+            ..payload(CompileSource::Manuscript {
+                path: manuscript.to_string_lossy().to_string(),
+            })
+        })
+        .expect("compile");
+
+        assert_eq!(result.notes, vec!["chapters/b.md", "chapters/a.md"]);
+        assert_eq!(result.skipped, vec!["Missing"]);
+        assert!(result.output_path.ends_with("Draft.docx"));
+
+        let doc = Document::open(&result.output_path).expect("open docx");
+        let texts = doc
+            .paragraphs()
+            .into_iter()
+            .map(|paragraph| paragraph.text())
+            .filter(|text| !text.trim().is_empty())
+            .collect::<Vec<_>>();
+        assert_eq!(texts, vec!["Chapter B.", "Chapter A."]);
+        let xml = read_document_xml(&result.output_path);
+        assert!(!xml.contains("<w:pageBreakBefore/>"));
+    }
+
+    #[test]
+    fn rejects_empty_compilations() {
+        let _guard = crate::workspace_test_guard();
+        let workspace = create_temp_workspace("tomosona-docx-compile-empty");
+        fs::create_dir_all(workspace.join("Empty")).expect("create folder");
+
+        crate::set_active_workspace(&workspace.to_string_lossy()).expect("set workspace");
+        let result = compile_notes_to_docx_sync(payload(CompileSource::Folder {
+            path: "Empty".to_string(),
+            recursive: true,
+        }));
+        assert!(matches!(result, Err(AppError::InvalidOperation(_))));
+    }
+
+    #[test]
+    fn places_bookmarks_on_the_following_paragraph() {
+        let xml = concat!(
+            "<w:body><w:p><w:r><w:t>\u{F8F3}2</w:t></w:r></w:p>",
+            r#"<w:p><w:pPr><w:pStyle w:val="Heading1"/><w:spacing w:after="0"/></w:pPr>"#,
+            "<w:r><w:t>Title</w:t></w:r></w:p>",
+            "<w:p><w:r><w:t>\u{F8F2}3</w:t></w:r></w:p><w:tbl></w:tbl></w:body>"
+        );
+
+        assert_eq!(
+            place_note_bookmarks_in_xml(xml),
+            concat!(
+                r#"<w:body><w:p><w:pPr><w:pStyle w:val="Heading1"/><w:pageBreakBefore/>"#,
+                r#"<w:spacing w:after="0"/></w:pPr><w:bookmarkStart w:id="2" w:name="note_2"/>"#,
+                r#"<w:bookmarkEnd w:id="2"/><w:r><w:t>Title</w:t></w:r></w:p>"#,
+                r#"<w:p><w:bookmarkStart w:id="3" w:name="note_3"/><w:bookmarkEnd w:id="3"/></w:p>"#,
+                "<w:tbl></w:tbl></w:body>"
+            )
+        );
+    }
+}
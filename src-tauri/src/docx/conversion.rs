@@ -19,29 +19,36 @@ use mermaid_rs_renderer::{
     render_with_options, write_output_png, RenderConfig, RenderOptions, Theme,
 };
 use rdocx::{Alignment, BorderStyle, Document, Length, VerticalAlignment};
+use serde::Deserialize;
 use zip::{write::SimpleFileOptions, ZipArchive, ZipWriter};
 
 use crate::docx::default_style::{ParagraphStyle, TemplateStyle, TextStyle};
+use crate::docx::links::{
+    apply_link_markers, is_external_url, link_wikilinks, marked_run_text, take_footnotes, Footnote,
+    WikilinkMode,
+};
 use crate::docx::style_from_docx::read_template_style;
+use crate::importers::percent_decode;
 use crate::markdown_index::{parse_yaml_frontmatter_properties, strip_yaml_frontmatter};
 use crate::{
     active_workspace_root, ensure_within_root, normalize_workspace_path, AppError, Result,
 };
 
-const TEMPLATE_DIR_NAME: &str = "_templates";
+pub(super) const TEMPLATE_DIR_NAME: &str = "_templates";
 const CODE_FONT: &str = "Courier New";
-const CODE_SIZE: u32 = 20;
-const TABLE_WIDTH_IN: f64 = 6.25;
+pub(super) const CODE_SIZE: u32 = 20;
+pub(super) const TABLE_WIDTH_IN: f64 = 6.25;
 const TABLE_CELL_MARGIN_X_PT: f64 = 0.6;
 const TABLE_CELL_MARGIN_Y_PT: f64 = 0.8;
 const TABLE_MIN_COLUMN_WIDTH_IN: f64 = 0.75;
 const TABLE_MAX_COLUMN_SHARE: f64 = 0.55;
 const TABLE_BORDER_SIZE: u32 = 2;
-const TABLE_HEADER_FILL: &str = "E8EEF4";
-const TABLE_BORDER_COLOR: &str = "B8C4CF";
-const CALLOUT_HEADER_TEXT: &str = "333333";
-const CALLOUT_BODY_TEXT: &str = "333333";
-const MERMAID_MAX_WIDTH_IN: f64 = 6.2;
+pub(super) const TABLE_HEADER_FILL: &str = "E8EEF4";
+pub(super) const TABLE_BORDER_COLOR: &str = "B8C4CF";
+pub(super) const CALLOUT_HEADER_TEXT: &str = "333333";
+pub(super) const CALLOUT_BODY_TEXT: &str = "333333";
+pub(super) const MERMAID_MAX_WIDTH_IN: f64 = 6.2;
+pub(super) const LINK_COLOR: &str = "0563C1";
 const MERMAID_FALLBACK_HEIGHT_IN: f64 = 3.5;
 const MERMAID_EMOJI_REPLACEMENTS: &[(&str, &str)] = &[
     ("✅", "[done]"),
@@ -58,26 +65,37 @@ const MERMAID_EMOJI_REPLACEMENTS: &[(&str, &str)] = &[
 ];
 
 #[derive(Debug, Clone)]
-struct RunSegment {
-    text: String,
-    style: TextStyle,
+pub(super) struct RunSegment {
+    pub(super) text: String,
+    pub(super) style: TextStyle,
 }
 
 #[derive(Debug, Clone)]
-struct TableRowData {
-    is_header: bool,
-    cells: Vec<Vec<RunSegment>>,
-    alignments: Vec<TableAlignment>,
+pub(super) struct TableRowData {
+    pub(super) is_header: bool,
+    pub(super) cells: Vec<Vec<RunSegment>>,
+    pub(super) alignments: Vec<TableAlignment>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct DocxExportOptions {
+    #[serde(default)]
+    pub wikilinks: WikilinkMode,
 }
 
 #[tauri::command]
-pub async fn convert_markdown_to_docx(path: String) -> Result<String> {
-    tauri::async_runtime::spawn_blocking(move || convert_markdown_to_docx_sync(path))
-        .await
-        .map_err(|_| AppError::OperationFailed)?
+pub async fn convert_markdown_to_docx(
+    path: String,
+    options: Option<DocxExportOptions>,
+) -> Result<String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        convert_markdown_to_docx_sync(path, options.unwrap_or_default())
+    })
+    .await
+    .map_err(|_| AppError::OperationFailed)?
 }
 
-fn convert_markdown_to_docx_sync(path: String) -> Result<String> {
+fn convert_markdown_to_docx_sync(path: String, options: DocxExportOptions) -> Result<String> {
     let workspace_root = active_workspace_root()?;
     let source_path = normalize_workspace_path(&workspace_root, &path)?;
     ensure_within_root(&workspace_root, &source_path)?;
@@ -91,51 +109,75 @@ fn convert_markdown_to_docx_sync(path: String) -> Result<String> {
     }
 
     let markdown = fs::read_to_string(&source_path)?;
-    let template_path = resolve_template_path(&workspace_root)?;
-    let mut copied_styles_path: Option<PathBuf> = None;
-    let template_style = match template_path {
-        Some(ref path) => match read_template_style(path) {
-            Ok(style) => {
-                copied_styles_path = Some(path.clone());
-                style
-            }
-            Err(_err) => TemplateStyle::default(),
-        },
-        None => TemplateStyle::default(),
+    let (template_style, copied_styles_path) = load_template_style(&workspace_root)?;
+
+    let note_dir = source_path.parent().unwrap_or(&workspace_root);
+    let (mut doc, footnotes) = build_document(
+        &markdown,
+        &template_style,
+        note_dir,
+        &workspace_root,
+        &options,
+    );
+    let output_path = resolve_output_path(&source_path, "docx")?;
+    let output_path = next_available_output_path(&output_path);
+    save_document(
+        &mut doc,
+        &output_path,
+        copied_styles_path.as_deref(),
+        &footnotes,
+    )?;
+
+    Ok(output_path.to_string_lossy().to_string())
+}
+
+/// Reads the workspace DOCX template, falling back to the built-in style when
+/// there is none or it cannot be parsed. The path is only returned for a
+/// template whose styles should be copied into the output.
+pub(super) fn load_template_style(root: &Path) -> Result<(TemplateStyle, Option<PathBuf>)> {
+    let Some(path) = resolve_template_path(root)? else {
+        return Ok((TemplateStyle::default(), None));
     };
+    match read_template_style(&path) {
+        Ok(style) => Ok((style, Some(path))),
+        Err(_err) => Ok((TemplateStyle::default(), None)),
+    }
+}
 
-    let mut doc = build_document(&markdown, &template_style);
-    let output_path = resolve_output_path(&source_path)?;
-    let output_path = next_available_output_path(&output_path);
-    doc.save(&output_path)
+pub(super) fn save_document(
+    doc: &mut Document,
+    output_path: &Path,
+    template_path: Option<&Path>,
+    footnotes: &[Footnote],
+) -> Result<()> {
+    doc.save(output_path)
         .map_err(|_| AppError::OperationFailed)?;
-    if let Some(template_path) = copied_styles_path.as_ref() {
-        copy_template_styles_into_output(&output_path, template_path)?;
+    if let Some(template_path) = template_path {
+        copy_template_styles_into_output(output_path, template_path)?;
     } else {
-        ensure_builtin_heading_styles(&output_path)?;
+        ensure_builtin_heading_styles(output_path)?;
     }
-    strip_leading_empty_table_paragraphs(&output_path)?;
-
-    Ok(output_path.to_string_lossy().to_string())
+    strip_leading_empty_table_paragraphs(output_path)?;
+    apply_link_markers(output_path, footnotes)
 }
 
-fn is_markdown_path(path: &Path) -> bool {
+pub(super) fn is_markdown_path(path: &Path) -> bool {
     path.extension()
         .and_then(|value| value.to_str())
         .map(|ext| ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown"))
         .unwrap_or(false)
 }
 
-fn resolve_output_path(source_path: &Path) -> Result<PathBuf> {
+pub(super) fn resolve_output_path(source_path: &Path, extension: &str) -> Result<PathBuf> {
     let stem = source_path
         .file_stem()
         .and_then(|value| value.to_str())
         .ok_or(AppError::InvalidPath)?;
     let parent = source_path.parent().ok_or(AppError::InvalidPath)?;
-    Ok(parent.join(format!("{stem}.docx")))
+    Ok(parent.join(format!("{stem}.{extension}")))
 }
 
-fn next_available_output_path(path: &Path) -> PathBuf {
+pub(super) fn next_available_output_path(path: &Path) -> PathBuf {
     if !path.exists() {
         return path.to_path_buf();
     }
@@ -145,17 +187,21 @@ fn next_available_output_path(path: &Path) -> PathBuf {
         .file_stem()
         .and_then(|value| value.to_str())
         .unwrap_or("output");
+    let extension = path
+        .extension()
+        .and_then(|value| value.to_str())
+        .unwrap_or("docx");
     for index in 1..10_000 {
-        let candidate = parent.join(format!("{stem} ({index}).docx"));
+        let candidate = parent.join(format!("{stem} ({index}).{extension}"));
         if !candidate.exists() {
             return candidate;
         }
     }
 
-    parent.join(format!("{stem} (9999).docx"))
+    parent.join(format!("{stem} (9999).{extension}"))
 }
 
-fn resolve_template_path(root: &Path) -> Result<Option<PathBuf>> {
+pub(super) fn resolve_template_path(root: &Path) -> Result<Option<PathBuf>> {
     let templates_dir = root.join(TEMPLATE_DIR_NAME);
     if !templates_dir.is_dir() {
         return Ok(None);
@@ -210,7 +256,7 @@ fn ensure_builtin_heading_styles(output_path: &Path) -> Result<()> {
     replace_docx_entry(output_path, "word/styles.xml", replacement.as_bytes())
 }
 
-fn read_docx_entry_text_file(path: &Path, entry_name: &str) -> Result<String> {
+pub(super) fn read_docx_entry_text_file(path: &Path, entry_name: &str) -> Result<String> {
     let file = File::open(path)?;
     let mut archive = ZipArchive::new(file)
         .map_err(|err| AppError::InvalidOperation(format!("DOCX entry open failed: {err}")))?;
@@ -268,17 +314,33 @@ fn builtin_heading_style_xml(level: u8) -> String {
     )
 }
 
-fn build_document(markdown: &str, template_style: &TemplateStyle) -> Document {
-    let arena = Arena::new();
+pub(super) fn markdown_options() -> Options<'static> {
     let mut options = Options::default();
     options.extension.table = true;
     options.extension.strikethrough = true;
     options.extension.tasklist = true;
     options.extension.autolink = true;
+    options.extension.wikilinks_title_after_pipe = true;
+    options.extension.footnotes = true;
+    options
+}
+
+fn build_document(
+    markdown: &str,
+    template_style: &TemplateStyle,
+    note_dir: &Path,
+    workspace_root: &Path,
+    options: &DocxExportOptions,
+) -> (Document, Vec<Footnote>) {
+    let arena = Arena::new();
 
     let frontmatter_rows = build_frontmatter_rows(markdown, template_style);
     let content = strip_yaml_frontmatter(markdown);
-    let root = parse_document(&arena, content, &options);
+    let root = parse_document(&arena, content, &markdown_options());
+    // Only headings of this note can be link targets in a single-note export.
+    let _unresolved = link_wikilinks(root, options.wikilinks, "h", |_| None);
+    resolve_image_sources(root, note_dir, workspace_root);
+    let footnotes = take_footnotes(root, template_style, 0);
     let mut doc = Document::new();
 
     if !frontmatter_rows.is_empty() {
@@ -293,18 +355,18 @@ fn build_document(markdown: &str, template_style: &TemplateStyle) -> Document {
         render_block(child, &mut doc, template_style, RenderContext::default());
     }
 
-    doc
+    (doc, footnotes)
 }
 
 #[derive(Debug, Clone, Copy)]
-struct RenderContext {
-    quote_depth: usize,
-    list_depth: usize,
-    callout: Option<CalloutKind>,
+pub(super) struct RenderContext {
+    pub(super) quote_depth: usize,
+    pub(super) list_depth: usize,
+    pub(super) callout: Option<CalloutKind>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum CalloutKind {
+pub(super) enum CalloutKind {
     Note,
     Abstract,
     Info,
@@ -320,13 +382,13 @@ enum CalloutKind {
 }
 
 #[derive(Debug, Clone, Copy)]
-struct CalloutStyle {
-    fill: &'static str,
-    border: &'static str,
+pub(super) struct CalloutStyle {
+    pub(super) fill: &'static str,
+    pub(super) border: &'static str,
 }
 
 impl CalloutKind {
-    fn label(self) -> &'static str {
+    pub(super) fn label(self) -> &'static str {
         match self {
             Self::Note => "Note",
             Self::Abstract => "Abstract",
@@ -343,7 +405,7 @@ impl CalloutKind {
         }
     }
 
-    fn style(self) -> CalloutStyle {
+    pub(super) fn style(self) -> CalloutStyle {
         match self {
             Self::Note | Self::Info => CalloutStyle {
                 fill: "E7F0FB",
@@ -379,7 +441,7 @@ impl Default for RenderContext {
     }
 }
 
-fn render_block<'a>(
+pub(super) fn render_block<'a>(
     node: &'a AstNode<'a>,
     doc: &mut Document,
     template: &TemplateStyle,
@@ -387,16 +449,11 @@ fn render_block<'a>(
 ) {
     match &node.data.borrow().value {
         NodeValue::Heading(heading) => {
-            let mut paragraph = doc.add_paragraph("");
-            if let Some(style_id) = template.heading_style_id(heading.level as u8) {
-                paragraph = paragraph.style(style_id);
-            }
-            append_segments_to_paragraph(
-                &mut paragraph,
+            append_heading(
+                doc,
+                heading.level,
                 build_inline_segments(node, TextStyle::default(), template, ctx),
                 template,
-                false,
-                false,
             );
         }
         NodeValue::Paragraph => {
@@ -406,12 +463,19 @@ fn render_block<'a>(
             } else if ctx.quote_depth > 0 {
                 style = template.quote().run.clone();
             }
-            append_paragraph(
-                doc,
-                build_inline_segments(node, style, template, ctx),
-                template,
-                ctx,
-            );
+            if node
+                .children()
+                .any(|child| embeddable_image(child).is_some())
+            {
+                render_paragraph_with_images(node, doc, style, template, ctx);
+            } else {
+                append_paragraph(
+                    doc,
+                    build_inline_segments(node, style, template, ctx),
+                    template,
+                    ctx,
+                );
+            }
         }
         NodeValue::BlockQuote | NodeValue::MultilineBlockQuote(_) => {
             if let Some(callout) = detect_callout(node, template) {
@@ -485,7 +549,7 @@ fn render_block<'a>(
                 render_block(child, doc, template, ctx);
             }
         }
-        NodeValue::HtmlBlock(_) | NodeValue::HtmlInline(_) => {}
+        NodeValue::HtmlBlock(_) | NodeValue::HtmlInline(_) | NodeValue::FootnoteDefinition(_) => {}
         _ => {
             for child in node.children() {
                 render_block(child, doc, template, ctx);
@@ -494,7 +558,7 @@ fn render_block<'a>(
     }
 }
 
-fn detect_callout<'a>(
+pub(super) fn detect_callout<'a>(
     node: &'a AstNode<'a>,
     template: &TemplateStyle,
 ) -> Option<(CalloutKind, Option<String>)> {
@@ -693,7 +757,7 @@ fn render_list<'a>(
     }
 }
 
-fn task_item_prefix(symbol: Option<char>) -> &'static str {
+pub(super) fn task_item_prefix(symbol: Option<char>) -> &'static str {
     if symbol.is_some() {
         "[x] "
     } else {
@@ -707,6 +771,19 @@ fn render_table<'a>(
     doc: &mut Document,
     template: &TemplateStyle,
 ) {
+    let rows = collect_table_rows(node, table_meta, template);
+    if rows.is_empty() {
+        return;
+    }
+
+    render_compact_table(doc, rows, template);
+}
+
+pub(super) fn collect_table_rows<'a>(
+    node: &'a AstNode<'a>,
+    table_meta: &NodeTable,
+    template: &TemplateStyle,
+) -> Vec<TableRowData> {
     let mut rows = Vec::new();
     for row in node.children() {
         let is_header = matches!(row.data.borrow().value, NodeValue::TableRow(true));
@@ -731,12 +808,7 @@ fn render_table<'a>(
             alignments: table_meta.alignments.clone(),
         });
     }
-
-    if rows.is_empty() {
-        return;
-    }
-
-    render_compact_table(doc, rows, template);
+    rows
 }
 
 fn render_key_value_table(doc: &mut Document, rows: &[TableRowData], template: &TemplateStyle) {
@@ -744,6 +816,14 @@ fn render_key_value_table(doc: &mut Document, rows: &[TableRowData], template: &
         return;
     }
 
+    render_compact_table(doc, key_value_table_rows(rows, template), template);
+}
+
+/// Prepends the `Key` / `Value` header row used by the frontmatter table.
+pub(super) fn key_value_table_rows(
+    rows: &[TableRowData],
+    template: &TemplateStyle,
+) -> Vec<TableRowData> {
     let mut data_rows = Vec::with_capacity(rows.len() + 1);
     data_rows.push(TableRowData {
         is_header: true,
@@ -766,21 +846,25 @@ fn render_key_value_table(doc: &mut Document, rows: &[TableRowData], template: &
         alignments: vec![TableAlignment::Left, TableAlignment::Left],
     });
     data_rows.extend(rows.iter().cloned());
-    render_compact_table(doc, data_rows, template);
+    data_rows
 }
 
-fn is_mermaid_code_block(info: &str) -> bool {
+pub(crate) fn is_mermaid_code_block(info: &str) -> bool {
     info.split_whitespace()
         .next()
         .map(|token| token.eq_ignore_ascii_case("mermaid"))
         .unwrap_or(false)
 }
 
-fn render_mermaid_block(code: &str, doc: &mut Document) -> bool {
+/// Renders Mermaid source to SVG markup, or `None` when the diagram is invalid.
+pub(crate) fn render_mermaid_svg(code: &str) -> Option<String> {
     let sanitized_code = sanitize_mermaid_emojis(code);
-    let Ok(svg) = render_with_options(&sanitized_code, RenderOptions::mermaid_default()) else {
-        return false;
-    };
+    render_with_options(&sanitized_code, RenderOptions::mermaid_default()).ok()
+}
+
+/// Rasterizes Mermaid source to PNG bytes on the default theme background.
+pub(crate) fn render_mermaid_png(code: &str) -> Option<Vec<u8>> {
+    let svg = render_mermaid_svg(code)?;
 
     let theme = Theme::mermaid_default();
     let render_cfg = RenderConfig {
@@ -794,14 +878,18 @@ fn render_mermaid_block(code: &str, doc: &mut Document) -> bool {
     ));
 
     if write_output_png(&svg, &png_path, &render_cfg, &theme).is_err() {
-        return false;
+        return None;
     }
 
-    let Ok(png) = fs::read(&png_path) else {
-        let _ = fs::remove_file(&png_path);
+    let png = fs::read(&png_path).ok();
+    let _ = fs::remove_file(&png_path);
+    png
+}
+
+fn render_mermaid_block(code: &str, doc: &mut Document) -> bool {
+    let Some(png) = render_mermaid_png(code) else {
         return false;
     };
-    let _ = fs::remove_file(&png_path);
 
     let Some((width_px, height_px)) = png_dimensions(&png) else {
         return false;
@@ -811,14 +899,110 @@ fn render_mermaid_block(code: &str, doc: &mut Document) -> bool {
         Length::inches(MERMAID_MAX_WIDTH_IN),
         Length::inches(MERMAID_FALLBACK_HEIGHT_IN),
     ));
+    append_picture(doc, &png, "mermaid.png", width, height);
+    true
+}
+
+fn append_picture(doc: &mut Document, bytes: &[u8], name: &str, width: Length, height: Length) {
     let mut paragraph = doc
-        .add_picture(&png, "mermaid.png", width, height)
+        .add_picture(bytes, name, width, height)
         .alignment(Alignment::Center)
         .space_before(Length::pt(2.0))
         .space_after(Length::pt(4.0));
     paragraph = paragraph.keep_together(true);
     let _ = paragraph;
-    true
+}
+
+/// Points every local image at the absolute path of a file that can be
+/// embedded. Paths are tried against the note folder, then the workspace root.
+/// Images that cannot be embedded get an empty URL and render as alt text.
+pub(super) fn resolve_image_sources<'a>(
+    root: &'a AstNode<'a>,
+    note_dir: &Path,
+    workspace_root: &Path,
+) {
+    for node in root.descendants() {
+        if let NodeValue::Image(image) = &mut node.data.borrow_mut().value {
+            image.url = resolve_image_path(&image.url, note_dir, workspace_root)
+                .map(|path| path.to_string_lossy().to_string())
+                .unwrap_or_default();
+        }
+    }
+}
+
+fn resolve_image_path(url: &str, note_dir: &Path, workspace_root: &Path) -> Option<PathBuf> {
+    let url = url.trim();
+    if url.is_empty() || is_external_url(url) || url.starts_with("data:") {
+        return None;
+    }
+    let decoded = percent_decode(url);
+    let mut candidates = Vec::new();
+    if let Some(path) = decoded.strip_prefix("file://") {
+        candidates.push(PathBuf::from(path));
+    } else {
+        candidates.push(note_dir.join(&decoded));
+        candidates.push(workspace_root.join(decoded.trim_start_matches('/')));
+        candidates.push(PathBuf::from(&decoded));
+    }
+    candidates.into_iter().find(|path| {
+        path.is_file()
+            && fs::read(path)
+                .ok()
+                .and_then(|bytes| image_dimensions(&bytes))
+                .is_some()
+    })
+}
+
+/// Local image a pre-resolved `Image` node embeds, if any.
+fn embeddable_image<'a>(node: &'a AstNode<'a>) -> Option<PathBuf> {
+    match &node.data.borrow().value {
+        NodeValue::Image(image) if !image.url.is_empty() => Some(PathBuf::from(&image.url)),
+        _ => None,
+    }
+}
+
+/// Splits a paragraph around its embedded images so each picture gets its own
+/// centered paragraph between the surrounding text.
+fn render_paragraph_with_images<'a>(
+    node: &'a AstNode<'a>,
+    doc: &mut Document,
+    style: TextStyle,
+    template: &TemplateStyle,
+    ctx: RenderContext,
+) {
+    let mut segments = Vec::new();
+    for child in node.children() {
+        let picture = embeddable_image(child).and_then(|path| {
+            let bytes = fs::read(&path).ok()?;
+            let (width_px, height_px) = image_dimensions(&bytes)?;
+            let (width, height) = png_display_size(width_px as f64, height_px as f64)?;
+            let name = path.file_name()?.to_string_lossy().to_string();
+            Some((bytes, name, width, height))
+        });
+        match picture {
+            Some((bytes, name, width, height)) => {
+                flush_text_segments(doc, &mut segments, template, ctx);
+                append_picture(doc, &bytes, &name, width, height);
+            }
+            None => collect_inline_segment(child, &style, &mut segments, template),
+        }
+    }
+    flush_text_segments(doc, &mut segments, template, ctx);
+}
+
+fn flush_text_segments(
+    doc: &mut Document,
+    segments: &mut Vec<RunSegment>,
+    template: &TemplateStyle,
+    ctx: RenderContext,
+) {
+    let segments = std::mem::take(segments);
+    if segments
+        .iter()
+        .any(|segment| !segment.text.trim().is_empty())
+    {
+        append_paragraph(doc, segments, template, ctx);
+    }
 }
 
 fn sanitize_mermaid_emojis(input: &str) -> String {
@@ -836,7 +1020,7 @@ fn unique_temp_stamp() -> u128 {
         .unwrap_or(0)
 }
 
-fn png_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+pub(crate) fn png_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
     const PNG_SIGNATURE: &[u8; 8] = b"\x89PNG\r\n\x1a\n";
     if bytes.len() < 24 || &bytes[..8] != PNG_SIGNATURE {
         return None;
@@ -847,6 +1031,51 @@ fn png_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
     Some((width, height))
 }
 
+/// Pixel size of a PNG, JPEG or GIF image.
+pub(crate) fn image_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    png_dimensions(bytes)
+        .or_else(|| jpeg_dimensions(bytes))
+        .or_else(|| gif_dimensions(bytes))
+}
+
+fn jpeg_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    if bytes.len() < 4 || bytes[..2] != [0xFF, 0xD8] {
+        return None;
+    }
+
+    let mut offset = 2;
+    while offset + 4 <= bytes.len() {
+        if bytes[offset] != 0xFF {
+            return None;
+        }
+        let marker = bytes[offset + 1];
+        if marker == 0xFF {
+            offset += 1;
+            continue;
+        }
+        let length = u16::from_be_bytes([bytes[offset + 2], bytes[offset + 3]]) as usize;
+        // Start-of-frame markers, excluding DHT, JPG and DAC.
+        if (0xC0..=0xCF).contains(&marker) && !matches!(marker, 0xC4 | 0xC8 | 0xCC) {
+            let frame = bytes.get(offset + 5..offset + 9)?;
+            let height = u16::from_be_bytes([frame[0], frame[1]]) as u32;
+            let width = u16::from_be_bytes([frame[2], frame[3]]) as u32;
+            return Some((width, height));
+        }
+        offset += 2 + length;
+    }
+    None
+}
+
+fn gif_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    if bytes.len() < 10 || !(bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a")) {
+        return None;
+    }
+
+    let width = u16::from_le_bytes([bytes[6], bytes[7]]) as u32;
+    let height = u16::from_le_bytes([bytes[8], bytes[9]]) as u32;
+    Some((width, height))
+}
+
 fn png_display_size(width_px: f64, height_px: f64) -> Option<(Length, Length)> {
     if width_px <= 0.0 || height_px <= 0.0 {
         return None;
@@ -866,7 +1095,10 @@ fn png_display_size(width_px: f64, height_px: f64) -> Option<(Length, Length)> {
     ))
 }
 
-fn build_frontmatter_rows(markdown: &str, template: &TemplateStyle) -> Vec<TableRowData> {
+pub(super) fn build_frontmatter_rows(
+    markdown: &str,
+    template: &TemplateStyle,
+) -> Vec<TableRowData> {
     let properties = parse_yaml_frontmatter_properties(markdown);
     if properties.is_empty() {
         return Vec::new();
@@ -938,12 +1170,10 @@ fn render_compact_table(doc: &mut Document, rows: Vec<TableRowData>, template: &
         .unwrap_or(1)
         .max(1);
     let row_count = rows.len();
-    let key_value_table = is_key_value_table(&rows);
-    let column_widths = if key_value_table {
-        key_value_column_widths(&rows)
-    } else {
-        compute_table_column_widths(&rows, column_count)
-    };
+    let column_widths = table_column_widths_in(&rows, column_count)
+        .into_iter()
+        .map(Length::inches)
+        .collect::<Vec<_>>();
     let mut table = doc
         .add_table(row_count, column_count)
         .width(Length::inches(TABLE_WIDTH_IN))
@@ -1002,6 +1232,15 @@ fn render_compact_table(doc: &mut Document, rows: Vec<TableRowData>, template: &
     }
 }
 
+/// Column widths in inches for a table spanning `TABLE_WIDTH_IN`.
+pub(super) fn table_column_widths_in(rows: &[TableRowData], column_count: usize) -> Vec<f64> {
+    if is_key_value_table(rows) {
+        key_value_column_widths(rows)
+    } else {
+        compute_table_column_widths(rows, column_count)
+    }
+}
+
 fn is_key_value_table(rows: &[TableRowData]) -> bool {
     let Some(first_row) = rows.first() else {
         return false;
@@ -1015,7 +1254,7 @@ fn is_key_value_table(rows: &[TableRowData]) -> bool {
     key.eq_ignore_ascii_case("key") && value.eq_ignore_ascii_case("value")
 }
 
-fn key_value_column_widths(rows: &[TableRowData]) -> Vec<Length> {
+fn key_value_column_widths(rows: &[TableRowData]) -> Vec<f64> {
     let key_score = rows
         .iter()
         .skip(1)
@@ -1025,10 +1264,10 @@ fn key_value_column_widths(rows: &[TableRowData]) -> Vec<Length> {
         .clamp(1.35, 1.9);
     let key_width = key_score.max(1.35);
     let value_width = (TABLE_WIDTH_IN - key_width).max(4.0);
-    vec![Length::inches(key_width), Length::inches(value_width)]
+    vec![key_width, value_width]
 }
 
-fn compute_table_column_widths(rows: &[TableRowData], column_count: usize) -> Vec<Length> {
+fn compute_table_column_widths(rows: &[TableRowData], column_count: usize) -> Vec<f64> {
     if column_count == 0 {
         return Vec::new();
     }
@@ -1045,13 +1284,12 @@ fn compute_table_column_widths(rows: &[TableRowData], column_count: usize) -> Ve
         }
     }
 
-    let widths = distribute_table_widths(
+    distribute_table_widths(
         scores,
         TABLE_WIDTH_IN,
         TABLE_MIN_COLUMN_WIDTH_IN,
         TABLE_WIDTH_IN * TABLE_MAX_COLUMN_SHARE,
-    );
-    widths.into_iter().map(Length::inches).collect()
+    )
 }
 
 fn distribute_table_widths(
@@ -1188,7 +1426,7 @@ fn replace_docx_entry(docx_path: &Path, target_name: &str, replacement: &[u8]) -
     })
 }
 
-fn rewrite_docx<F>(docx_path: &Path, mut transform: F) -> Result<()>
+pub(super) fn rewrite_docx<F>(docx_path: &Path, mut transform: F) -> Result<()>
 where
     F: FnMut(&str, &[u8]) -> Result<Vec<u8>>,
 {
@@ -1302,7 +1540,7 @@ fn paragraph_contains_text(paragraph_xml: &str) -> bool {
     false
 }
 
-fn build_inline_segments<'a>(
+pub(super) fn build_inline_segments<'a>(
     node: &'a AstNode<'a>,
     style: TextStyle,
     template: &TemplateStyle,
@@ -1320,52 +1558,7 @@ fn collect_inline_segments<'a>(
     template: &TemplateStyle,
 ) {
     for child in node.children() {
-        match &child.data.borrow().value {
-            NodeValue::Text(text) => {
-                push_segment(segments, text.as_ref(), base_style.clone());
-            }
-            NodeValue::Code(code) => {
-                let mut style = base_style.clone();
-                style.code = true;
-                style.font = Some(CODE_FONT.to_string());
-                style.size = Some(CODE_SIZE);
-                push_segment(segments, &code.literal, style);
-            }
-            NodeValue::Strong => {
-                let mut style = base_style.clone();
-                style.bold = true;
-                collect_inline_segments(child, &style, segments, template);
-            }
-            NodeValue::Emph => {
-                let mut style = base_style.clone();
-                style.italic = true;
-                collect_inline_segments(child, &style, segments, template);
-            }
-            NodeValue::Strikethrough => {
-                collect_inline_segments(child, base_style, segments, template);
-            }
-            NodeValue::Link(_) | NodeValue::WikiLink(_) | NodeValue::Image(_) => {
-                collect_inline_segments(child, base_style, segments, template);
-            }
-            NodeValue::SoftBreak => {
-                push_segment(segments, " ", base_style.clone());
-            }
-            NodeValue::LineBreak => {
-                push_segment(segments, " ", base_style.clone());
-            }
-            NodeValue::Raw(raw) => {
-                push_segment(segments, raw, base_style.clone());
-            }
-            NodeValue::HtmlInline(html) => {
-                push_segment(segments, html, base_style.clone());
-            }
-            NodeValue::TaskItem(_) => {}
-            _ => {
-                if child.data.borrow().value.contains_inlines() {
-                    collect_inline_segments(child, base_style, segments, template);
-                }
-            }
-        }
+        collect_inline_segment(child, base_style, segments, template);
     }
 
     if segments.is_empty() {
@@ -1376,7 +1569,85 @@ fn collect_inline_segments<'a>(
     }
 }
 
-fn collect_inline_segments_for_node<'a>(
+fn collect_inline_segment<'a>(
+    child: &'a AstNode<'a>,
+    base_style: &TextStyle,
+    segments: &mut Vec<RunSegment>,
+    template: &TemplateStyle,
+) {
+    match &child.data.borrow().value {
+        NodeValue::Text(text) => {
+            push_segment(segments, text.as_ref(), base_style.clone());
+        }
+        NodeValue::Code(code) => {
+            let mut style = base_style.clone();
+            style.code = true;
+            style.font = Some(CODE_FONT.to_string());
+            style.size = Some(CODE_SIZE);
+            push_segment(segments, &code.literal, style);
+        }
+        NodeValue::Strong => {
+            let mut style = base_style.clone();
+            style.bold = true;
+            collect_inline_segments(child, &style, segments, template);
+        }
+        NodeValue::Emph => {
+            let mut style = base_style.clone();
+            style.italic = true;
+            collect_inline_segments(child, &style, segments, template);
+        }
+        NodeValue::Strikethrough => {
+            collect_inline_segments(child, base_style, segments, template);
+        }
+        NodeValue::WikiLink(link) => {
+            // Resolved wikilinks carry a `#bookmark` inside the document.
+            let mut style = base_style.clone();
+            if link.url.len() > 1 && link.url.starts_with('#') {
+                style.link = Some(link.url.clone());
+                style.color = Some(LINK_COLOR.to_string());
+            }
+            collect_inline_segments(child, &style, segments, template);
+        }
+        NodeValue::Link(link) => {
+            let mut style = base_style.clone();
+            if is_external_url(&link.url) {
+                style.link = Some(link.url.trim().to_string());
+                style.color = Some(LINK_COLOR.to_string());
+            }
+            collect_inline_segments(child, &style, segments, template);
+        }
+        NodeValue::Image(_) => {
+            collect_inline_segments(child, base_style, segments, template);
+        }
+        NodeValue::FootnoteReference(reference) => {
+            let style = TextStyle {
+                footnote: Some(reference.ix),
+                ..base_style.clone()
+            };
+            push_segment(segments, &reference.ix.to_string(), style);
+        }
+        NodeValue::SoftBreak => {
+            push_segment(segments, " ", base_style.clone());
+        }
+        NodeValue::LineBreak => {
+            push_segment(segments, " ", base_style.clone());
+        }
+        NodeValue::Raw(raw) => {
+            push_segment(segments, raw, base_style.clone());
+        }
+        NodeValue::HtmlInline(html) => {
+            push_segment(segments, html, base_style.clone());
+        }
+        NodeValue::TaskItem(_) => {}
+        _ => {
+            if child.data.borrow().value.contains_inlines() {
+                collect_inline_segments(child, base_style, segments, template);
+            }
+        }
+    }
+}
+
+pub(super) fn collect_inline_segments_for_node<'a>(
     node: &'a AstNode<'a>,
     base_style: TextStyle,
     template: &TemplateStyle,
@@ -1404,7 +1675,20 @@ fn push_segment(segments: &mut Vec<RunSegment>, text: &str, style: TextStyle) {
     });
 }
 
-fn append_paragraph(
+pub(super) fn append_heading(
+    doc: &mut Document,
+    level: u8,
+    segments: Vec<RunSegment>,
+    template: &TemplateStyle,
+) {
+    let mut paragraph = doc.add_paragraph("");
+    if let Some(style_id) = template.heading_style_id(level) {
+        paragraph = paragraph.style(style_id);
+    }
+    append_segments_to_paragraph(&mut paragraph, segments, template, false, false);
+}
+
+pub(super) fn append_paragraph(
     doc: &mut Document,
     segments: Vec<RunSegment>,
     template: &TemplateStyle,
@@ -1554,7 +1838,7 @@ fn append_segments_to_paragraph<'a>(
         if header_row {
             style.bold = true;
         }
-        let run = paragraph.add_run(&segment.text);
+        let run = paragraph.add_run(&marked_run_text(&style, &segment.text));
         let _run = apply_run_style(run, &style, template, inherit_defaults);
     }
 }
@@ -1616,6 +1900,10 @@ mod tests {
         crate::workspace_test_guard()
     }
 
+    fn convert_markdown_to_docx_sync(path: String) -> Result<String> {
+        super::convert_markdown_to_docx_sync(path, DocxExportOptions::default())
+    }
+
     fn create_temp_workspace(prefix: &str) -> PathBuf {
         let nonce = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -1908,7 +2196,7 @@ mod tests {
         let workspace = create_temp_workspace("tomosona-docx-output");
         let source = workspace.join("note.md");
         fs::write(&source, "# Note").expect("write note");
-        let output = resolve_output_path(&source).expect("output");
+        let output = resolve_output_path(&source, "docx").expect("output");
         fs::write(&output, "occupied").expect("occupy output");
         let next = next_available_output_path(&output);
         assert!(next.ends_with("note (1).docx"));
@@ -2430,6 +2718,104 @@ mod tests {
             .any(|text| text.contains("After diagram.")));
     }
 
+    #[test]
+    fn convert_markdown_to_docx_embeds_images_links_and_footnotes() {
+        const PIXEL_PNG: &[u8] = &[
+            0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A, 0x00, 0x00, 0x00, 0x0D, 0x49, 0x48,
+            0x44, 0x52, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x06, 0x00, 0x00,
+            0x00, 0x1F, 0x15, 0xC4, 0x89, 0x00, 0x00, 0x00, 0x0A, 0x49, 0x44, 0x41, 0x54, 0x78,
+            0x9C, 0x63, 0x00, 0x01, 0x00, 0x00, 0x05, 0x00, 0x01, 0x0D, 0x0A, 0x2D, 0xB4, 0x00,
+            0x00, 0x00, 0x00, 0x49, 0x45, 0x4E, 0x44, 0xAE, 0x42, 0x60, 0x82,
+        ];
+        let _guard = workspace_test_guard();
+        let workspace = create_temp_workspace("tomosona-docx-references");
+        fs::create_dir_all(workspace.join("notes/assets")).expect("assets");
+        fs::write(workspace.join("notes/assets/pixel one.png"), PIXEL_PNG).expect("png");
+        let source = workspace.join("notes/refs.md");
+        fs::write(
+            &source,
+            concat!(
+                "# Setup\n\n",
+                "Intro ![Pixel](assets/pixel%20one.png) and ![Missing](nope.png).\n\n",
+                "See [the site](https://example.com/?a=1&b=2), [[#Setup|setup]] and [[Other]].",
+                "[^note]\n\n",
+                "[^note]: A **footnote**.\n",
+            ),
+        )
+        .expect("write note");
+
+        crate::set_active_workspace(&workspace.to_string_lossy()).expect("set workspace");
+        let output =
+            convert_markdown_to_docx_sync(source.to_string_lossy().to_string()).expect("convert");
+        let output = Path::new(&output);
+
+        let media = read_docx_media_names(output);
+        assert_eq!(media.len(), 1);
+        let document = read_docx_document_xml(output);
+        assert!(document.contains(r#"<w:hyperlink w:anchor="h_setup" w:history="1">"#));
+        assert!(document.contains(r#"<w:bookmarkStart w:id="100001" w:name="h_setup"/>"#));
+        assert!(document.contains(r#"<w:hyperlink r:id="rIdTomosonaLink1" w:history="1">"#));
+        assert!(document.contains(r#"<w:footnoteReference w:id="1"/>"#));
+        assert!(!document.contains('\u{F8F0}') && !document.contains('\u{F8F6}'));
+
+        let paragraphs = read_docx_paragraphs(output);
+        assert!(paragraphs.iter().any(|text| text.trim() == "Intro"));
+        assert!(paragraphs.iter().any(|text| text.contains("and Missing.")));
+        assert!(paragraphs
+            .iter()
+            .any(|text| text.contains("setup and Other.")));
+        assert!(!paragraphs.iter().any(|text| text.contains("A footnote.")));
+
+        let relationships = read_docx_entry_text(output, "word/_rels/document.xml.rels");
+        assert!(relationships.contains(r#"Target="https://example.com/?a=1&amp;b=2""#));
+        assert!(relationships.contains(r#"Target="footnotes.xml""#));
+        let footnotes = read_docx_entry_text(output, "word/footnotes.xml");
+        assert!(footnotes.contains(r#"<w:footnote w:id="1">"#));
+        assert!(footnotes.contains("<w:b/>"));
+        assert!(read_docx_entry_text(output, "[Content_Types].xml").contains("/word/footnotes.xml"));
+    }
+
+    #[test]
+    fn convert_markdown_to_docx_can_render_wikilinks_as_plain_text() {
+        let _guard = workspace_test_guard();
+        let workspace = create_temp_workspace("tomosona-docx-plain-links");
+        let source = workspace.join("plain.md");
+        fs::write(&source, "# Top\n\nBack to [[#Top]].\n").expect("write note");
+
+        crate::set_active_workspace(&workspace.to_string_lossy()).expect("set workspace");
+        let output = super::convert_markdown_to_docx_sync(
+            source.to_string_lossy().to_string(),
+            DocxExportOptions {
+                wikilinks: WikilinkMode::PlainText,
+            },
+        )
+        .expect("convert");
+
+        let document = read_docx_document_xml(Path::new(&output));
+        assert!(!document.contains("w:hyperlink"));
+        assert!(!document.contains("w:bookmarkStart"));
+        assert!(read_docx_paragraphs(Path::new(&output))
+            .iter()
+            .any(|text| text == "Back to #Top."));
+    }
+
+    #[test]
+    fn image_dimensions_reads_png_jpeg_and_gif_headers() {
+        let png = [
+            &b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"[..],
+            &[0, 0, 0, 3, 0, 0, 0, 2],
+        ]
+        .concat();
+        assert_eq!(image_dimensions(&png), Some((3, 2)));
+        let jpeg = [
+            0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x04, 0x00, 0x00, 0xFF, 0xC0, 0x00, 0x11, 0x08, 0x00,
+            0x20, 0x00, 0x40,
+        ];
+        assert_eq!(image_dimensions(&jpeg), Some((64, 32)));
+        assert_eq!(image_dimensions(b"GIF89a\x05\x00\x07\x00"), Some((5, 7)));
+        assert_eq!(image_dimensions(b"not an image"), None);
+    }
+
     #[test]
     fn sanitize_mermaid_emojis_replaces_supported_symbols() {
         let sanitized = sanitize_mermaid_emojis("Done ✅ / Fail ❌ / Warn ⚠️ / Fire 🔥");
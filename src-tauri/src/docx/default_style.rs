@@ -14,6 +14,10 @@ pub(crate) struct TextStyle {
     pub font: Option<String>,
     pub size: Option<u32>,
     pub color: Option<String>,
+    /// `#bookmark` inside the same document, or an external URL.
+    pub link: Option<String>,
+    /// Id of the footnote the run references.
+    pub footnote: Option<u32>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -0,0 +1,608 @@
+//! Hyperlinks, bookmarks and footnotes for DOCX export.
+//!
+//! rdocx only writes plain runs, so the renderer tags runs with private-use
+//! marker characters and this module rewrites `word/document.xml` once the file
+//! is saved: tagged runs become hyperlinks, bookmarked headings or footnote
+//! references, and the matching relationships and footnotes part are added.
+//! Wikilinks are resolved on the parsed AST before rendering.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs::OpenOptions,
+    io::Write,
+    path::Path,
+};
+
+use comrak::nodes::{AstNode, NodeValue};
+use serde::Deserialize;
+use zip::{write::SimpleFileOptions, ZipWriter};
+
+use crate::docx::conversion::{
+    collect_inline_segments_for_node, read_docx_entry_text_file, rewrite_docx, RunSegment,
+};
+use crate::docx::default_style::{TemplateStyle, TextStyle};
+use crate::workspace_paths::split_wikilink_target_suffix;
+use crate::{AppError, Result};
+
+const LINK_START: char = '\u{F8F0}';
+const LINK_END: char = '\u{F8F1}';
+const BOOKMARK_START: char = '\u{F8F4}';
+const BOOKMARK_END: char = '\u{F8F5}';
+const FOOTNOTE_START: char = '\u{F8F6}';
+const FOOTNOTE_END: char = '\u{F8F7}';
+const BOOKMARK_NAME_MAX_LEN: usize = 40;
+// Leaves the low ids to the note bookmarks written by the compiler.
+const HEADING_BOOKMARK_FIRST_ID: usize = 100_000;
+const FOOTNOTE_SIZE: u32 = 18;
+const RELATIONSHIP_PREFIX: &str = "rIdTomosona";
+const HYPERLINK_RELATIONSHIP: &str =
+    "http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink";
+const FOOTNOTES_RELATIONSHIP: &str =
+    "http://schemas.openxmlformats.org/officeDocument/2006/relationships/footnotes";
+const FOOTNOTES_CONTENT_TYPE: &str =
+    "application/vnd.openxmlformats-officedocument.wordprocessingml.footnotes+xml";
+const RELATIONSHIPS_NAMESPACE: &str =
+    "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+
+/// How wikilinks render in exported documents.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WikilinkMode {
+    /// Link to the bookmarked heading or note when it is part of the document.
+    #[default]
+    Bookmarks,
+    PlainText,
+}
+
+/// Footnote definition rendered into `word/footnotes.xml`.
+#[derive(Debug, Clone)]
+pub(super) struct Footnote {
+    pub(super) id: u32,
+    pub(super) paragraphs: Vec<Vec<RunSegment>>,
+}
+
+/// Text written for a run, prefixed with the markers its style asks for.
+pub(super) fn marked_run_text(style: &TextStyle, text: &str) -> String {
+    if let Some(id) = style.footnote {
+        return format!("{FOOTNOTE_START}{id}{FOOTNOTE_END}{id}");
+    }
+    match style.link.as_deref() {
+        Some(target) => format!("{LINK_START}{target}{LINK_END}{text}"),
+        None => text.to_string(),
+    }
+}
+
+pub(super) fn is_external_url(url: &str) -> bool {
+    let lower = url.trim().to_ascii_lowercase();
+    ["http://", "https://", "mailto:", "ftp://"]
+        .iter()
+        .any(|scheme| lower.starts_with(scheme))
+}
+
+/// Bookmarks every heading under `scope` and rewrites wikilinks to `#bookmark`
+/// targets, or clears them so they render as plain text. Same-note links go to
+/// the matching heading; anything else goes through `note_bookmark`, which maps
+/// a target (empty for the current note) to the bookmark of a note that is part
+/// of the document. Returns the targets that did not resolve.
+pub(super) fn link_wikilinks<'a>(
+    root: &'a AstNode<'a>,
+    mode: WikilinkMode,
+    scope: &str,
+    mut note_bookmark: impl FnMut(&str) -> Option<String>,
+) -> Vec<String> {
+    let mut headings = HashMap::new();
+    if mode == WikilinkMode::Bookmarks {
+        let mut used = HashSet::new();
+        for node in root.descendants() {
+            if !matches!(node.data.borrow().value, NodeValue::Heading(_)) {
+                continue;
+            }
+            let text = plain_text(node);
+            let key = heading_key(&text);
+            if key.is_empty() || headings.contains_key(&key) {
+                continue;
+            }
+            let name = unique_bookmark_name(scope, &text, &mut used);
+            if tag_first_text(node, &name) {
+                headings.insert(key, name);
+            }
+        }
+    }
+
+    let mut unresolved = Vec::new();
+    for node in root.descendants() {
+        let NodeValue::WikiLink(link) = &mut node.data.borrow_mut().value else {
+            continue;
+        };
+        let (target, suffix) = split_wikilink_target_suffix(&link.url);
+        let target = target.trim();
+        let bookmark = match mode {
+            WikilinkMode::PlainText => None,
+            WikilinkMode::Bookmarks => target
+                .is_empty()
+                .then(|| suffix.strip_prefix('#'))
+                .flatten()
+                .and_then(|heading| headings.get(&heading_key(heading)).cloned())
+                .or_else(|| note_bookmark(target)),
+        };
+        match bookmark {
+            Some(bookmark) => link.url = format!("#{bookmark}"),
+            None => {
+                let unresolved_target = if target.is_empty() {
+                    link.url.trim().to_string()
+                } else {
+                    target.to_string()
+                };
+                if mode == WikilinkMode::Bookmarks && !unresolved.contains(&unresolved_target) {
+                    unresolved.push(unresolved_target);
+                }
+                link.url.clear();
+            }
+        }
+    }
+    unresolved
+}
+
+fn plain_text<'a>(node: &'a AstNode<'a>) -> String {
+    let mut text = String::new();
+    for descendant in node.descendants() {
+        match &descendant.data.borrow().value {
+            NodeValue::Text(value) => text.push_str(value.as_ref()),
+            NodeValue::Code(code) => text.push_str(&code.literal),
+            _ => {}
+        }
+    }
+    text
+}
+
+fn heading_key(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Word bookmark names start with a letter, hold letters, digits and
+/// underscores, and are at most 40 characters long.
+fn unique_bookmark_name(scope: &str, text: &str, used: &mut HashSet<String>) -> String {
+    let mut slug = String::new();
+    for ch in text.chars().flat_map(char::to_lowercase) {
+        if ch.is_ascii_alphanumeric() {
+            slug.push(ch);
+        } else if !slug.ends_with('_') {
+            slug.push('_');
+        }
+    }
+    let base = format!("{scope}_{}", slug.trim_matches('_'));
+    let base = base.trim_end_matches('_');
+    let mut suffix = 1;
+    loop {
+        let tail = if suffix == 1 {
+            String::new()
+        } else {
+            format!("_{suffix}")
+        };
+        let keep = BOOKMARK_NAME_MAX_LEN - tail.len();
+        let name = format!("{}{tail}", &base[..base.len().min(keep)]);
+        if used.insert(name.clone()) {
+            return name;
+        }
+        suffix += 1;
+    }
+}
+
+fn tag_first_text<'a>(node: &'a AstNode<'a>, name: &str) -> bool {
+    for descendant in node.descendants() {
+        if let NodeValue::Text(value) = &mut descendant.data.borrow_mut().value {
+            *value = format!("{BOOKMARK_START}{name}{BOOKMARK_END}{value}").into();
+            return true;
+        }
+    }
+    false
+}
+
+/// Detaches the footnote definitions from `root` and returns them with ids
+/// shifted by `offset`, shifting the references to match.
+pub(super) fn take_footnotes<'a>(
+    root: &'a AstNode<'a>,
+    template: &TemplateStyle,
+    offset: u32,
+) -> Vec<Footnote> {
+    let mut ids = HashMap::new();
+    for node in root.descendants() {
+        if let NodeValue::FootnoteReference(reference) = &mut node.data.borrow_mut().value {
+            ids.insert(reference.name.clone(), reference.ix + offset);
+            reference.ix += offset;
+        }
+    }
+
+    let style = TextStyle {
+        size: Some(FOOTNOTE_SIZE),
+        ..template.body().run.clone()
+    };
+    let mut footnotes = Vec::new();
+    for node in root.children().collect::<Vec<_>>() {
+        let id = match &node.data.borrow().value {
+            NodeValue::FootnoteDefinition(definition) => ids.get(&definition.name).copied(),
+            _ => continue,
+        };
+        node.detach();
+        let Some(id) = id else {
+            continue;
+        };
+        let paragraphs = node
+            .descendants()
+            .filter(|child| matches!(child.data.borrow().value, NodeValue::Paragraph))
+            .map(|paragraph| collect_inline_segments_for_node(paragraph, style.clone(), template))
+            .collect();
+        footnotes.push(Footnote { id, paragraphs });
+    }
+    footnotes.sort_by_key(|footnote| footnote.id);
+    footnotes
+}
+
+/// Turns the tagged runs of a saved DOCX into hyperlinks, heading bookmarks and
+/// footnote references, and writes the footnotes part.
+pub(super) fn apply_link_markers(docx_path: &Path, footnotes: &[Footnote]) -> Result<()> {
+    let xml = read_docx_entry_text_file(docx_path, "word/document.xml")?;
+    let mut external = Vec::new();
+    let xml = rewrite_marked_runs(&xml, LINK_START, LINK_END, |target, run| {
+        let target = unescape_xml(target);
+        let run = add_run_property(&run, r#"<w:u w:val="single"/>"#);
+        match target.strip_prefix('#') {
+            Some(anchor) => format!(
+                r#"<w:hyperlink w:anchor="{}" w:history="1">{run}</w:hyperlink>"#,
+                escape_xml(anchor)
+            ),
+            None => {
+                let id = match external.iter().position(|url| *url == target) {
+                    Some(index) => index + 1,
+                    None => {
+                        external.push(target);
+                        external.len()
+                    }
+                };
+                format!(
+                    r#"<w:hyperlink r:id="{RELATIONSHIP_PREFIX}Link{id}" w:history="1">{run}</w:hyperlink>"#
+                )
+            }
+        }
+    });
+    let mut bookmark_id = HEADING_BOOKMARK_FIRST_ID;
+    let xml = rewrite_marked_runs(&xml, BOOKMARK_START, BOOKMARK_END, |name, run| {
+        bookmark_id += 1;
+        format!(
+            r#"<w:bookmarkStart w:id="{bookmark_id}" w:name="{name}"/><w:bookmarkEnd w:id="{bookmark_id}"/>{run}"#
+        )
+    });
+    let known_footnotes = footnotes
+        .iter()
+        .map(|footnote| footnote.id.to_string())
+        .collect::<HashSet<_>>();
+    let xml = rewrite_marked_runs(&xml, FOOTNOTE_START, FOOTNOTE_END, |id, run| {
+        if !known_footnotes.contains(id) {
+            return run;
+        }
+        let run = add_run_property(&run, r#"<w:vertAlign w:val="superscript"/>"#);
+        replace_run_text(&run, &format!(r#"<w:footnoteReference w:id="{id}"/>"#))
+    });
+    let xml = if (external.is_empty() && footnotes.is_empty()) || xml.contains("xmlns:r=") {
+        xml
+    } else {
+        xml.replacen(
+            "<w:document ",
+            &format!(r#"<w:document xmlns:r="{RELATIONSHIPS_NAMESPACE}" "#),
+            1,
+        )
+    };
+
+    let mut relationships = external
+        .iter()
+        .enumerate()
+        .map(|(index, url)| {
+            format!(
+                r#"<Relationship Id="{RELATIONSHIP_PREFIX}Link{}" Type="{HYPERLINK_RELATIONSHIP}" Target="{}" TargetMode="External"/>"#,
+                index + 1,
+                escape_xml(url)
+            )
+        })
+        .collect::<String>();
+    if !footnotes.is_empty() {
+        relationships.push_str(&format!(
+            r#"<Relationship Id="{RELATIONSHIP_PREFIX}Footnotes" Type="{FOOTNOTES_RELATIONSHIP}" Target="footnotes.xml"/>"#
+        ));
+    }
+
+    rewrite_docx(docx_path, |name, bytes| match name {
+        "word/document.xml" => Ok(xml.clone().into_bytes()),
+        "word/_rels/document.xml.rels" if !relationships.is_empty() => {
+            let rels = decode_xml(bytes)?;
+            Ok(append_to_root(&rels, "Relationships", &relationships).into_bytes())
+        }
+        "[Content_Types].xml" if !footnotes.is_empty() => {
+            let types = decode_xml(bytes)?;
+            let entry = format!(
+                r#"<Override PartName="/word/footnotes.xml" ContentType="{FOOTNOTES_CONTENT_TYPE}"/>"#
+            );
+            Ok(append_to_root(&types, "Types", &entry).into_bytes())
+        }
+        _ => Ok(bytes.to_vec()),
+    })?;
+
+    if !footnotes.is_empty() {
+        append_docx_entry(
+            docx_path,
+            "word/footnotes.xml",
+            footnotes_xml(footnotes).as_bytes(),
+        )?;
+    }
+    Ok(())
+}
+
+fn decode_xml(bytes: &[u8]) -> Result<String> {
+    String::from_utf8(bytes.to_vec()).map_err(|err| {
+        AppError::InvalidOperation(format!("DOCX postprocess decode xml failed: {err}"))
+    })
+}
+
+fn append_docx_entry(docx_path: &Path, name: &str, bytes: &[u8]) -> Result<()> {
+    let file = OpenOptions::new().read(true).write(true).open(docx_path)?;
+    let mut writer = ZipWriter::new_append(file).map_err(|err| {
+        AppError::InvalidOperation(format!("DOCX postprocess open failed: {err}"))
+    })?;
+    writer
+        .start_file(name, SimpleFileOptions::default())
+        .map_err(|err| {
+            AppError::InvalidOperation(format!("DOCX postprocess write file failed: {err}"))
+        })?;
+    writer.write_all(bytes).map_err(|err| {
+        AppError::InvalidOperation(format!("DOCX postprocess write bytes failed: {err}"))
+    })?;
+    writer.finish().map_err(|err| {
+        AppError::InvalidOperation(format!("DOCX postprocess finish failed: {err}"))
+    })?;
+    Ok(())
+}
+
+/// Inserts `content` before the closing tag of the root element `root`.
+fn append_to_root(xml: &str, root: &str, content: &str) -> String {
+    let closing = format!("</{root}>");
+    if let Some(position) = xml.rfind(&closing) {
+        return format!("{}{content}{}", &xml[..position], &xml[position..]);
+    }
+    // An empty root written as `<Root .../>`.
+    match xml.rfind("/>") {
+        Some(position) => format!("{}>{content}{closing}", &xml[..position]),
+        None => xml.to_string(),
+    }
+}
+
+/// Calls `transform` for every run holding `start payload end`, with the run
+/// stripped of the marker, and splices in what it returns.
+fn rewrite_marked_runs(
+    xml: &str,
+    start: char,
+    end: char,
+    mut transform: impl FnMut(&str, String) -> String,
+) -> String {
+    let mut output = String::with_capacity(xml.len());
+    let mut remainder = xml;
+
+    while let Some(marker) = remainder.find(start) {
+        let run_start = [
+            remainder[..marker].rfind("<w:r>"),
+            remainder[..marker].rfind("<w:r "),
+        ]
+        .into_iter()
+        .flatten()
+        .max();
+        let payload_start = marker + start.len_utf8();
+        let payload_end = remainder[payload_start..]
+            .find(end)
+            .map(|offset| payload_start + offset);
+        let run_end = remainder[marker..]
+            .find("</w:r>")
+            .map(|offset| marker + offset + "</w:r>".len());
+        let (Some(run_start), Some(payload_end), Some(run_end)) = (run_start, payload_end, run_end)
+        else {
+            break;
+        };
+        if payload_end > run_end {
+            break;
+        }
+
+        let run = format!(
+            "{}{}",
+            &remainder[run_start..marker],
+            &remainder[payload_end + end.len_utf8()..run_end]
+        );
+        output.push_str(&remainder[..run_start]);
+        output.push_str(&transform(&remainder[payload_start..payload_end], run));
+        remainder = &remainder[run_end..];
+    }
+
+    output.push_str(remainder);
+    output
+}
+
+fn add_run_property(run: &str, property: &str) -> String {
+    if run.contains("</w:rPr>") {
+        return run.replacen("</w:rPr>", &format!("{property}</w:rPr>"), 1);
+    }
+    let open_end = run.find('>').map(|end| end + 1).unwrap_or(run.len());
+    format!(
+        "{}<w:rPr>{property}</w:rPr>{}",
+        &run[..open_end],
+        &run[open_end..]
+    )
+}
+
+fn replace_run_text(run: &str, replacement: &str) -> String {
+    let text_start = [run.find("<w:t>"), run.find("<w:t ")]
+        .into_iter()
+        .flatten()
+        .min();
+    let Some(text_start) = text_start else {
+        return run.to_string();
+    };
+    let Some(text_end) = run[text_start..].find("</w:t>") else {
+        return run.to_string();
+    };
+    let text_end = text_start + text_end + "</w:t>".len();
+    format!("{}{replacement}{}", &run[..text_start], &run[text_end..])
+}
+
+fn footnotes_xml(footnotes: &[Footnote]) -> String {
+    let mut xml = format!(
+        concat!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+            r#"<w:footnotes xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main" xmlns:r="{}">"#,
+            r#"<w:footnote w:type="separator" w:id="-1"><w:p><w:pPr><w:spacing w:after="0" w:line="240" w:lineRule="auto"/></w:pPr><w:r><w:separator/></w:r></w:p></w:footnote>"#,
+            r#"<w:footnote w:type="continuationSeparator" w:id="0"><w:p><w:pPr><w:spacing w:after="0" w:line="240" w:lineRule="auto"/></w:pPr><w:r><w:continuationSeparator/></w:r></w:p></w:footnote>"#,
+        ),
+        RELATIONSHIPS_NAMESPACE
+    );
+    for footnote in footnotes {
+        xml.push_str(&format!(r#"<w:footnote w:id="{}">"#, footnote.id));
+        let paragraphs = if footnote.paragraphs.is_empty() {
+            vec![Vec::new()]
+        } else {
+            footnote.paragraphs.clone()
+        };
+        for (index, segments) in paragraphs.iter().enumerate() {
+            xml.push_str(r#"<w:p><w:pPr><w:spacing w:after="0"/></w:pPr>"#);
+            if index == 0 {
+                xml.push_str(concat!(
+                    r#"<w:r><w:rPr><w:vertAlign w:val="superscript"/></w:rPr><w:footnoteRef/></w:r>"#,
+                    r#"<w:r><w:t xml:space="preserve"> </w:t></w:r>"#
+                ));
+            }
+            for segment in segments.iter().filter(|segment| !segment.text.is_empty()) {
+                xml.push_str(&footnote_run_xml(segment));
+            }
+            xml.push_str("</w:p>");
+        }
+        xml.push_str("</w:footnote>");
+    }
+    xml.push_str("</w:footnotes>");
+    xml
+}
+
+fn footnote_run_xml(segment: &RunSegment) -> String {
+    let style = &segment.style;
+    let mut properties = String::new();
+    if let Some(font) = style.font.as_deref() {
+        let font = escape_xml(font);
+        properties.push_str(&format!(r#"<w:rFonts w:ascii="{font}" w:hAnsi="{font}"/>"#));
+    }
+    if style.bold {
+        properties.push_str("<w:b/>");
+    }
+    if style.italic {
+        properties.push_str("<w:i/>");
+    }
+    if let Some(color) = style.color.as_deref() {
+        properties.push_str(&format!(r#"<w:color w:val="{}"/>"#, escape_xml(color)));
+    }
+    properties.push_str(&format!(
+        r#"<w:sz w:val="{}"/>"#,
+        style.size.unwrap_or(FOOTNOTE_SIZE)
+    ));
+    format!(
+        r#"<w:r><w:rPr>{properties}</w:rPr><w:t xml:space="preserve">{}</w:t></w:r>"#,
+        escape_xml(&segment.text)
+    )
+}
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn unescape_xml(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use comrak::{parse_document, Arena};
+
+    use crate::docx::conversion::markdown_options;
+
+    #[test]
+    fn marked_runs_become_hyperlinks_and_footnote_references() {
+        let xml = concat!(
+            r#"<w:p><w:r><w:t>See </w:t></w:r><w:r><w:rPr><w:color w:val="0563C1"/></w:rPr>"#,
+            "<w:t xml:space=\"preserve\">\u{F8F0}#note_2\u{F8F1}Chapter</w:t></w:r>",
+            "<w:r><w:t>\u{F8F0}https://example.com/?a=1&amp;b=2\u{F8F1}Site</w:t></w:r>",
+            "<w:r><w:t>\u{F8F6}3\u{F8F7}3</w:t></w:r></w:p>"
+        );
+
+        let mut external = Vec::new();
+        let linked = rewrite_marked_runs(xml, LINK_START, LINK_END, |target, run| {
+            external.push(unescape_xml(target));
+            format!("<a:{target}>{}", add_run_property(&run, "<w:u/>"))
+        });
+        assert_eq!(external, vec!["#note_2", "https://example.com/?a=1&b=2"]);
+        assert_eq!(
+            linked,
+            concat!(
+                r#"<w:p><w:r><w:t>See </w:t></w:r><a:#note_2><w:r><w:rPr><w:color w:val="0563C1"/>"#,
+                r#"<w:u/></w:rPr><w:t xml:space="preserve">Chapter</w:t></w:r>"#,
+                r#"<a:https://example.com/?a=1&amp;b=2><w:r><w:rPr><w:u/></w:rPr><w:t>Site</w:t></w:r>"#,
+                "<w:r><w:t>\u{F8F6}3\u{F8F7}3</w:t></w:r></w:p>"
+            )
+        );
+
+        let referenced = rewrite_marked_runs(&linked, FOOTNOTE_START, FOOTNOTE_END, |id, run| {
+            replace_run_text(&run, &format!(r#"<w:footnoteReference w:id="{id}"/>"#))
+        });
+        assert!(referenced.ends_with(r#"<w:r><w:footnoteReference w:id="3"/></w:r></w:p>"#));
+    }
+
+    #[test]
+    fn wikilinks_resolve_to_heading_and_note_bookmarks() {
+        let arena = Arena::new();
+        let mut options = markdown_options();
+        options.extension.wikilinks_title_after_pipe = true;
+        let root = parse_document(
+            &arena,
+            "# Intro\n\n## Intro\n\nSee [[#intro]], [[Other|the other]] and [[Gone]].\n",
+            &options,
+        );
+
+        let unresolved = link_wikilinks(root, WikilinkMode::Bookmarks, "h", |target| {
+            (target == "Other").then(|| "note_2".to_string())
+        });
+        assert_eq!(unresolved, vec!["Gone"]);
+
+        let urls = root
+            .descendants()
+            .filter_map(|node| match &node.data.borrow().value {
+                NodeValue::WikiLink(link) => Some(link.url.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(urls, vec!["#h_intro", "#note_2", ""]);
+        assert_eq!(
+            plain_text(root.first_child().expect("heading")),
+            "\u{F8F4}h_intro\u{F8F5}Intro"
+        );
+
+        let mut used = HashSet::new();
+        let long = unique_bookmark_name("h", &"Very long heading ".repeat(5), &mut used);
+        assert_eq!(long.len(), BOOKMARK_NAME_MAX_LEN);
+        let again = unique_bookmark_name("h", &"Very long heading ".repeat(5), &mut used);
+        assert!(again.ends_with("_2") && again.len() == BOOKMARK_NAME_MAX_LEN);
+    }
+}
@@ -1,10 +1,19 @@
-//! DOCX export module tree.
+//! DOCX and PDF export module tree.
 //!
 //! This namespace groups the conversion pipeline plus its style sources so the
-//! rest of the backend can treat DOCX export as one bounded subsystem.
+//! rest of the backend can treat document export as one bounded subsystem. The
+//! PDF writer and the multi-note compiler reuse the DOCX building blocks rather
+//! than separate renderers; links, bookmarks and footnotes are patched into the
+//! saved DOCX by one shared post-processing pass.
 
+pub(crate) mod compile;
 pub(crate) mod conversion;
 pub(crate) mod default_style;
+mod links;
+pub(crate) mod pdf;
+mod pdf_fonts;
 pub(crate) mod style_from_docx;
 
+pub(crate) use compile::compile_notes_to_docx;
 pub(crate) use conversion::convert_markdown_to_docx;
+pub(crate) use pdf::convert_markdown_to_pdf;
@@ -0,0 +1,1516 @@
+//! Markdown to PDF conversion for the native shell command.
+//!
+//! The layout walks the same comrak tree as the DOCX export and reuses its
+//! callout detection, task prefixes, frontmatter table, table column widths,
+//! Mermaid rasterizing and template styles. Pages are written directly with the
+//! PDF standard fonts, so no browser or external binary is involved.
+
+use std::{
+    collections::BTreeSet,
+    fs,
+    io::Cursor,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use comrak::{
+    nodes::{AstNode, ListType, NodeList, NodeValue, TableAlignment},
+    parse_document, Arena, Options,
+};
+use miniz_oxide::deflate::compress_to_vec_zlib;
+use serde::{Deserialize, Serialize};
+
+use crate::docx::conversion::{
+    build_frontmatter_rows, build_inline_segments, collect_table_rows, detect_callout,
+    is_markdown_path, is_mermaid_code_block, key_value_table_rows, next_available_output_path,
+    png_dimensions, render_mermaid_png, resolve_output_path, resolve_template_path,
+    table_column_widths_in, task_item_prefix, CalloutKind, RenderContext, RunSegment, TableRowData,
+    CALLOUT_BODY_TEXT, CALLOUT_HEADER_TEXT, CODE_SIZE, MERMAID_MAX_WIDTH_IN, TABLE_BORDER_COLOR,
+    TABLE_HEADER_FILL, TABLE_WIDTH_IN,
+};
+use crate::docx::default_style::{ParagraphStyle, TemplateStyle, TextStyle};
+use crate::docx::pdf_fonts::{font_family, is_winansi, winansi_byte, FontFamily, PdfFont};
+use crate::docx::style_from_docx::read_template_style;
+use crate::markdown_index::strip_yaml_frontmatter;
+use crate::note_templates::civil_from_days;
+use crate::{
+    active_workspace_root, ensure_within_root, normalize_workspace_path, AppError, Result,
+};
+
+const POINTS_PER_INCH: f32 = 72.0;
+const POINTS_PER_MM: f32 = 72.0 / 25.4;
+const DEFAULT_MARGIN_MM: f32 = 20.0;
+const MIN_CONTENT_SIZE_PT: f32 = 72.0;
+const LINE_HEIGHT: f32 = 1.2;
+const LIST_INDENT_PT: f32 = 18.0;
+const LIST_MARKER_GAP_PT: f32 = 4.0;
+const BOX_PADDING_PT: f32 = 3.0;
+const TABLE_CELL_PADDING_PT: f32 = 3.0;
+const TABLE_BORDER_WIDTH_PT: f32 = 0.5;
+const FRONTMATTER_SPACE_AFTER_PT: f32 = 12.0;
+const RULE_COLOR: &str = "CCCCCC";
+const CODE_COLOR: &str = "2D2D2D";
+const HEADER_FOOTER_SIZE_PT: f32 = 9.0;
+const HEADER_FOOTER_COLOR: &str = "808080";
+const KEEP_WITH_NEXT_LINES: f32 = 2.0;
+const IMAGE_PIXELS_PER_INCH: f32 = 96.0;
+const COMPRESSION_LEVEL: u8 = 6;
+
+/// Paper sizes offered by the PDF export, in portrait orientation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PdfPageSize {
+    #[default]
+    A4,
+    A5,
+    Letter,
+    Legal,
+}
+
+impl PdfPageSize {
+    fn dimensions(self) -> (f32, f32) {
+        match self {
+            Self::A4 => (595.28, 841.89),
+            Self::A5 => (419.53, 595.28),
+            Self::Letter => (612.0, 792.0),
+            Self::Legal => (612.0, 1008.0),
+        }
+    }
+}
+
+/// Page margins in millimetres.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct PdfMargins {
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+    pub left: f32,
+}
+
+/// Page setup for PDF export.
+///
+/// `header` and `footer` accept `{title}`, `{page}`, `{pages}` and `{date}`
+/// placeholders.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PdfExportOptions {
+    #[serde(default)]
+    pub page_size: PdfPageSize,
+    #[serde(default)]
+    pub landscape: bool,
+    #[serde(default)]
+    pub margins_mm: Option<PdfMargins>,
+    #[serde(default)]
+    pub header: Option<String>,
+    #[serde(default)]
+    pub footer: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PdfExportResult {
+    pub output_path: String,
+    /// Characters the standard PDF fonts cannot show, printed as `?`, in code point order.
+    pub replaced_characters: Vec<String>,
+}
+
+#[tauri::command]
+pub async fn convert_markdown_to_pdf(
+    path: String,
+    options: Option<PdfExportOptions>,
+) -> Result<PdfExportResult> {
+    tauri::async_runtime::spawn_blocking(move || {
+        convert_markdown_to_pdf_sync(path, options.unwrap_or_default())
+    })
+    .await
+    .map_err(|_| AppError::OperationFailed)?
+}
+
+fn convert_markdown_to_pdf_sync(
+    path: String,
+    options: PdfExportOptions,
+) -> Result<PdfExportResult> {
+    let workspace_root = active_workspace_root()?;
+    let source_path = normalize_workspace_path(&workspace_root, &path)?;
+    ensure_within_root(&workspace_root, &source_path)?;
+
+    if !source_path.is_file() || !is_markdown_path(&source_path) {
+        return Err(AppError::InvalidPath);
+    }
+
+    let markdown = fs::read_to_string(&source_path)?;
+    let template_style = match resolve_template_path(&workspace_root)? {
+        Some(path) => read_template_style(&path).unwrap_or_default(),
+        None => TemplateStyle::default(),
+    };
+    let title = source_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let (bytes, replaced_characters) = render_pdf(&markdown, &template_style, &options, &title)?;
+    let output_path = next_available_output_path(&resolve_output_path(&source_path, "pdf")?);
+    fs::write(&output_path, bytes)?;
+    Ok(PdfExportResult {
+        output_path: output_path.to_string_lossy().to_string(),
+        replaced_characters,
+    })
+}
+
+fn render_pdf(
+    markdown: &str,
+    template: &TemplateStyle,
+    options: &PdfExportOptions,
+    title: &str,
+) -> Result<(Vec<u8>, Vec<String>)> {
+    let geometry = PageGeometry::from_options(options)?;
+    let mut layout = layout_markdown(markdown, template, geometry);
+    layout.draw_header_and_footer(options, title, &today());
+    let replaced = layout.replaced.iter().map(char::to_string).collect();
+    Ok((layout.into_pdf(title), replaced))
+}
+
+fn layout_markdown<'t>(
+    markdown: &str,
+    template: &'t TemplateStyle,
+    geometry: PageGeometry,
+) -> Layout<'t> {
+    let arena = Arena::new();
+    let mut options = Options::default();
+    options.extension.table = true;
+    options.extension.strikethrough = true;
+    options.extension.tasklist = true;
+    options.extension.autolink = true;
+
+    let mut layout = Layout::new(template, geometry);
+    let frontmatter_rows = build_frontmatter_rows(markdown, template);
+    if !frontmatter_rows.is_empty() {
+        layout.table(key_value_table_rows(&frontmatter_rows, template));
+        layout.y -= FRONTMATTER_SPACE_AFTER_PT;
+    }
+
+    let root = parse_document(&arena, strip_yaml_frontmatter(markdown), &options);
+    for child in root.children() {
+        layout.render_block(child, RenderContext::default());
+    }
+    layout
+}
+
+fn today() -> String {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or(0);
+    let (year, month, day) = civil_from_days(seconds.div_euclid(86_400));
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Rgb(f32, f32, f32);
+
+impl Rgb {
+    const BLACK: Rgb = Rgb(0.0, 0.0, 0.0);
+
+    fn parse(hex: &str) -> Option<Rgb> {
+        let hex = hex.trim().trim_start_matches('#');
+        if hex.len() != 6 || !hex.is_ascii() {
+            return None;
+        }
+        let channel = |range: std::ops::Range<usize>| {
+            u8::from_str_radix(&hex[range], 16)
+                .ok()
+                .map(|value| value as f32 / 255.0)
+        };
+        Some(Rgb(channel(0..2)?, channel(2..4)?, channel(4..6)?))
+    }
+
+    fn fill_op(self) -> String {
+        format!("{} {} {} rg", num(self.0), num(self.1), num(self.2))
+    }
+
+    fn stroke_op(self) -> String {
+        format!("{} {} {} RG", num(self.0), num(self.1), num(self.2))
+    }
+}
+
+/// Formats a coordinate without exponent notation or trailing zeros.
+fn num(value: f32) -> String {
+    let formatted = format!("{value:.2}");
+    let trimmed = formatted.trim_end_matches('0').trim_end_matches('.');
+    match trimmed {
+        "" | "-0" => "0".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Encodes text as a WinAnsi PDF literal string.
+fn pdf_string(text: &str) -> String {
+    let mut output = String::with_capacity(text.len() + 2);
+    output.push('(');
+    for ch in text.chars() {
+        match winansi_byte(ch) {
+            byte @ (b'(' | b')' | b'\\') => {
+                output.push('\\');
+                output.push(byte as char);
+            }
+            byte @ 32..=126 => output.push(byte as char),
+            byte => output.push_str(&format!("\\{byte:03o}")),
+        }
+    }
+    output.push(')');
+    output
+}
+
+#[derive(Debug, Clone, Copy)]
+struct PageGeometry {
+    width: f32,
+    height: f32,
+    top: f32,
+    bottom: f32,
+    left: f32,
+    right: f32,
+}
+
+impl PageGeometry {
+    fn from_options(options: &PdfExportOptions) -> Result<Self> {
+        let (mut width, mut height) = options.page_size.dimensions();
+        if options.landscape {
+            std::mem::swap(&mut width, &mut height);
+        }
+        let margins = options.margins_mm.unwrap_or(PdfMargins {
+            top: DEFAULT_MARGIN_MM,
+            right: DEFAULT_MARGIN_MM,
+            bottom: DEFAULT_MARGIN_MM,
+            left: DEFAULT_MARGIN_MM,
+        });
+        let [top, right, bottom, left] =
+            [margins.top, margins.right, margins.bottom, margins.left].map(|mm| mm * POINTS_PER_MM);
+        if [top, right, bottom, left]
+            .iter()
+            .any(|value| !value.is_finite() || *value < 0.0)
+            || width - left - right < MIN_CONTENT_SIZE_PT
+            || height - top - bottom < MIN_CONTENT_SIZE_PT
+        {
+            return Err(AppError::InvalidOperation(
+                "PDF margins leave no room for content.".to_string(),
+            ));
+        }
+        Ok(Self {
+            width,
+            height,
+            top: height - top,
+            bottom,
+            left,
+            right: width - right,
+        })
+    }
+
+    fn content_width(&self) -> f32 {
+        self.right - self.left
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Fragment {
+    text: String,
+    font: PdfFont,
+    size: f32,
+    color: Rgb,
+    width: f32,
+}
+
+#[derive(Debug, Clone)]
+struct Line {
+    fragments: Vec<Fragment>,
+    width: f32,
+    size: f32,
+}
+
+impl Line {
+    fn new(size: f32) -> Self {
+        Self {
+            fragments: Vec::new(),
+            width: 0.0,
+            size,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.fragments.is_empty()
+    }
+
+    fn push(&mut self, fragment: Fragment) {
+        self.width += fragment.width;
+        self.size = self.size.max(fragment.size);
+        if let Some(last) = self.fragments.last_mut() {
+            if last.font == fragment.font
+                && last.size == fragment.size
+                && last.color == fragment.color
+            {
+                last.text.push_str(&fragment.text);
+                last.width += fragment.width;
+                return;
+            }
+        }
+        self.fragments.push(fragment);
+    }
+
+    fn trim_end(&mut self) {
+        while let Some(last) = self.fragments.last_mut() {
+            let trimmed = last.text.trim_end().to_string();
+            if trimmed.len() == last.text.len() {
+                break;
+            }
+            let removed = last.width - last.font.text_width(&trimmed, last.size);
+            self.width -= removed;
+            if trimmed.is_empty() {
+                self.fragments.pop();
+            } else {
+                last.width -= removed;
+                last.text = trimmed;
+            }
+        }
+    }
+
+    fn height(&self, line_spacing: f32) -> f32 {
+        self.size * LINE_HEIGHT * line_spacing
+    }
+}
+
+/// Splits text into alternating whitespace and word tokens.
+fn split_tokens(text: &str) -> Vec<(bool, &str)> {
+    let mut tokens = Vec::new();
+    let mut start = 0usize;
+    let mut current: Option<bool> = None;
+    for (offset, ch) in text.char_indices() {
+        let is_space = ch.is_whitespace();
+        if current.is_some_and(|kind| kind != is_space) {
+            tokens.push((!is_space, &text[start..offset]));
+            start = offset;
+        }
+        current = Some(is_space);
+    }
+    if let Some(is_space) = current {
+        tokens.push((is_space, &text[start..]));
+    }
+    tokens
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+enum Align {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+impl From<TableAlignment> for Align {
+    fn from(alignment: TableAlignment) -> Self {
+        match alignment {
+            TableAlignment::Center => Self::Center,
+            TableAlignment::Right => Self::Right,
+            TableAlignment::None | TableAlignment::Left => Self::Left,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct BlockFrame {
+    indent_left: f32,
+    indent_right: f32,
+    space_before: f32,
+    space_after: f32,
+    line_spacing: f32,
+    padding: f32,
+    fill: Option<Rgb>,
+    border: Option<Rgb>,
+    rule_below: Option<(Rgb, f32)>,
+    keep_with_next: bool,
+    marker: Option<Line>,
+}
+
+impl Default for BlockFrame {
+    fn default() -> Self {
+        Self {
+            indent_left: 0.0,
+            indent_right: 0.0,
+            space_before: 0.0,
+            space_after: 0.0,
+            line_spacing: 1.0,
+            padding: 0.0,
+            fill: None,
+            border: None,
+            rule_below: None,
+            keep_with_next: false,
+            marker: None,
+        }
+    }
+}
+
+impl BlockFrame {
+    fn from_paragraph_style(style: &ParagraphStyle, indent_multiplier: f32) -> Self {
+        let fill = style.shading_fill.as_deref().and_then(Rgb::parse);
+        Self {
+            indent_left: style.indent_left.unwrap_or(0.0) as f32 * indent_multiplier.max(1.0),
+            indent_right: style.indent_right.unwrap_or(0.0) as f32,
+            space_before: style.space_before.unwrap_or(0.0) as f32,
+            space_after: style.space_after.unwrap_or(0.0) as f32,
+            line_spacing: style.line_spacing_multiple.unwrap_or(1.0) as f32,
+            padding: if fill.is_some() { BOX_PADDING_PT } else { 0.0 },
+            fill,
+            rule_below: style.border_bottom.as_ref().and_then(|border| {
+                Rgb::parse(&border.color).map(|color| (color, border.size_eighths_pt as f32 / 8.0))
+            }),
+            ..Self::default()
+        }
+    }
+
+    fn callout(kind: CalloutKind, space_before: f32, space_after: f32) -> Self {
+        let palette = kind.style();
+        Self {
+            space_before,
+            space_after,
+            padding: BOX_PADDING_PT,
+            fill: Rgb::parse(palette.fill),
+            border: Rgb::parse(palette.border),
+            ..Self::default()
+        }
+    }
+}
+
+/// A PNG decoded into Flate-compressed RGB samples plus an optional soft mask.
+struct PdfImage {
+    width: u32,
+    height: u32,
+    rgb: Vec<u8>,
+    alpha: Option<Vec<u8>>,
+}
+
+fn decode_png(bytes: &[u8]) -> Option<PdfImage> {
+    let mut decoder = png::Decoder::new(Cursor::new(bytes));
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().ok()?;
+    let mut buffer = vec![0; reader.output_buffer_size()?];
+    let info = reader.next_frame(&mut buffer).ok()?;
+    let pixels = &buffer[..info.buffer_size()];
+    let (rgb, alpha): (Vec<u8>, Option<Vec<u8>>) = match info.color_type {
+        png::ColorType::Rgb => (pixels.to_vec(), None),
+        png::ColorType::Rgba => (
+            pixels
+                .chunks_exact(4)
+                .flat_map(|pixel| pixel[..3].to_vec())
+                .collect(),
+            Some(pixels.chunks_exact(4).map(|pixel| pixel[3]).collect()),
+        ),
+        png::ColorType::Grayscale => (pixels.iter().flat_map(|gray| [*gray; 3]).collect(), None),
+        png::ColorType::GrayscaleAlpha => (
+            pixels
+                .chunks_exact(2)
+                .flat_map(|pixel| [pixel[0]; 3])
+                .collect(),
+            Some(pixels.chunks_exact(2).map(|pixel| pixel[1]).collect()),
+        ),
+        png::ColorType::Indexed => return None,
+    };
+    let alpha = alpha.filter(|alpha| alpha.iter().any(|value| *value != u8::MAX));
+    Some(PdfImage {
+        width: info.width,
+        height: info.height,
+        rgb: compress_to_vec_zlib(&rgb, COMPRESSION_LEVEL),
+        alpha: alpha.map(|alpha| compress_to_vec_zlib(&alpha, COMPRESSION_LEVEL)),
+    })
+}
+
+struct Layout<'t> {
+    template: &'t TemplateStyle,
+    page: PageGeometry,
+    pages: Vec<String>,
+    images: Vec<PdfImage>,
+    /// Drawn characters that fell back to `?`.
+    replaced: BTreeSet<char>,
+    y: f32,
+}
+
+impl<'t> Layout<'t> {
+    fn new(template: &'t TemplateStyle, page: PageGeometry) -> Self {
+        Self {
+            template,
+            page,
+            pages: vec![String::new()],
+            images: Vec::new(),
+            replaced: BTreeSet::new(),
+            y: page.top,
+        }
+    }
+
+    fn ops(&mut self) -> &mut String {
+        self.pages.last_mut().expect("layout always has a page")
+    }
+
+    fn emit(&mut self, op: &str) {
+        let ops = self.ops();
+        ops.push_str(op);
+        ops.push('\n');
+    }
+
+    fn new_page(&mut self) {
+        self.pages.push(String::new());
+        self.y = self.page.top;
+    }
+
+    fn at_page_top(&self) -> bool {
+        self.y >= self.page.top - 0.01
+    }
+
+    fn available(&self) -> f32 {
+        self.y - self.page.bottom
+    }
+
+    fn skip(&mut self, amount: f32) {
+        if !self.at_page_top() {
+            self.y -= amount;
+        }
+    }
+
+    fn resolve_font(&self, style: &TextStyle) -> (PdfFont, f32, Rgb) {
+        let family = if style.code {
+            FontFamily::Mono
+        } else {
+            font_family(
+                style
+                    .font
+                    .as_deref()
+                    .unwrap_or(self.template.default_font.as_str()),
+            )
+        };
+        let size = if style.code {
+            CODE_SIZE
+        } else {
+            style.size.unwrap_or(self.template.body_size)
+        };
+        let color = style
+            .color
+            .as_deref()
+            .and_then(Rgb::parse)
+            .unwrap_or(Rgb::BLACK);
+        (
+            PdfFont {
+                family,
+                bold: style.bold,
+                italic: style.italic,
+            },
+            size as f32 / 2.0,
+            color,
+        )
+    }
+
+    /// Greedy line breaking; `preserve_spaces` keeps code indentation intact.
+    fn break_lines(
+        &self,
+        segments: &[RunSegment],
+        max_width: f32,
+        preserve_spaces: bool,
+    ) -> Vec<Line> {
+        let base_size = segments
+            .first()
+            .map(|segment| self.resolve_font(&segment.style).1)
+            .unwrap_or(self.template.body_size as f32 / 2.0);
+        let mut lines = Vec::new();
+        let mut line = Line::new(base_size);
+        let mut pending_space: Option<Fragment> = None;
+
+        for segment in segments {
+            let (font, size, color) = self.resolve_font(&segment.style);
+            let fragment = |text: String| Fragment {
+                width: font.text_width(&text, size),
+                text,
+                font,
+                size,
+                color,
+            };
+            for (is_space, token) in split_tokens(&segment.text) {
+                if is_space && !preserve_spaces {
+                    if !line.is_empty() {
+                        pending_space = Some(fragment(" ".to_string()));
+                    }
+                    continue;
+                }
+                let token = fragment(token.replace('\t', "    "));
+                let space_width = pending_space.as_ref().map_or(0.0, |space| space.width);
+                if !line.is_empty() && line.width + space_width + token.width > max_width {
+                    line.trim_end();
+                    lines.push(std::mem::replace(&mut line, Line::new(base_size)));
+                    pending_space = None;
+                }
+                if let Some(space) = pending_space.take() {
+                    line.push(space);
+                }
+                if token.width <= max_width {
+                    line.push(token);
+                    continue;
+                }
+                for ch in token.text.chars() {
+                    let piece = fragment(ch.to_string());
+                    if !line.is_empty() && line.width + piece.width > max_width {
+                        lines.push(std::mem::replace(&mut line, Line::new(base_size)));
+                    }
+                    line.push(piece);
+                }
+            }
+        }
+        line.trim_end();
+        if !line.is_empty() || lines.is_empty() {
+            lines.push(line);
+        }
+        lines
+    }
+
+    fn line_op(&mut self, line: &Line, x: f32, baseline: f32) -> String {
+        let mut op = format!("BT {} {} Td", num(x), num(baseline));
+        for fragment in &line.fragments {
+            self.replaced
+                .extend(fragment.text.chars().filter(|ch| !is_winansi(*ch)));
+            op.push_str(&format!(
+                " /{} {} Tf {} {} Tj",
+                fragment.font.resource_name(),
+                num(fragment.size),
+                fragment.color.fill_op(),
+                pdf_string(&fragment.text)
+            ));
+        }
+        op.push_str(" ET");
+        op
+    }
+
+    fn draw_line(&mut self, line: &Line, x: f32, baseline: f32) {
+        if !line.is_empty() {
+            let op = self.line_op(line, x, baseline);
+            self.emit(&op);
+        }
+    }
+
+    fn draw_rect(
+        &mut self,
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        fill: Option<Rgb>,
+        border: Option<(Rgb, f32)>,
+    ) {
+        let rect = format!("{} {} {} {} re", num(x), num(y), num(width), num(height));
+        if let Some(fill) = fill {
+            self.emit(&format!("{} {rect} f", fill.fill_op()));
+        }
+        if let Some((color, line_width)) = border {
+            self.emit(&format!(
+                "{} {} w {rect} S",
+                color.stroke_op(),
+                num(line_width)
+            ));
+        }
+    }
+
+    fn draw_hline(&mut self, x1: f32, x2: f32, y: f32, color: Rgb, line_width: f32) {
+        self.emit(&format!(
+            "{} {} w {} {} m {} {} l S",
+            color.stroke_op(),
+            num(line_width),
+            num(x1),
+            num(y),
+            num(x2),
+            num(y)
+        ));
+    }
+
+    fn baseline(line: &Line, top: f32, height: f32) -> f32 {
+        top - (height + line.size * 0.6) / 2.0
+    }
+
+    fn paragraph(&mut self, lines: Vec<Line>, frame: &BlockFrame) {
+        if lines.is_empty() {
+            return;
+        }
+        self.skip(frame.space_before);
+        let x = self.page.left + frame.indent_left;
+        let width = (self.page.content_width() - frame.indent_left - frame.indent_right).max(1.0);
+        if frame.keep_with_next {
+            let needed = lines
+                .iter()
+                .map(|line| line.height(frame.line_spacing))
+                .sum::<f32>()
+                + frame.padding * 2.0
+                + self.template.body_size as f32 / 2.0 * LINE_HEIGHT * KEEP_WITH_NEXT_LINES;
+            if needed > self.available() && !self.at_page_top() {
+                self.new_page();
+            }
+        }
+
+        let mut index = 0usize;
+        let mut marker = frame.marker.clone();
+        while index < lines.len() {
+            let room = self.available() - frame.padding * 2.0;
+            let mut end = index;
+            let mut chunk_height = 0.0;
+            while end < lines.len() && chunk_height + lines[end].height(frame.line_spacing) <= room
+            {
+                chunk_height += lines[end].height(frame.line_spacing);
+                end += 1;
+            }
+            if end == index {
+                if !self.at_page_top() {
+                    self.new_page();
+                    continue;
+                }
+                chunk_height = lines[index].height(frame.line_spacing);
+                end = index + 1;
+            }
+
+            let box_height = chunk_height + frame.padding * 2.0;
+            if frame.fill.is_some() || frame.border.is_some() {
+                self.draw_rect(
+                    x,
+                    self.y - box_height,
+                    width,
+                    box_height,
+                    frame.fill,
+                    frame.border.map(|color| (color, 1.0)),
+                );
+            }
+            let mut top = self.y - frame.padding;
+            for line in &lines[index..end] {
+                let height = line.height(frame.line_spacing);
+                let baseline = Self::baseline(line, top, height);
+                self.draw_line(line, x + frame.padding, baseline);
+                if let Some(marker) = marker.take() {
+                    let marker_x = x - marker.width - LIST_MARKER_GAP_PT;
+                    self.draw_line(&marker, marker_x, baseline);
+                }
+                top -= height;
+            }
+            self.y -= box_height;
+            index = end;
+            if index < lines.len() {
+                self.new_page();
+            }
+        }
+
+        if let Some((color, line_width)) = frame.rule_below {
+            self.y -= line_width + 1.0;
+            self.draw_hline(x, x + width, self.y, color, line_width);
+        }
+        self.y -= frame.space_after;
+    }
+
+    fn rule(&mut self) {
+        self.skip(6.0);
+        if self.available() < 6.0 {
+            self.new_page();
+        }
+        let (left, right) = (self.page.left, self.page.right);
+        let color = Rgb::parse(RULE_COLOR).unwrap_or(Rgb::BLACK);
+        self.draw_hline(left, right, self.y, color, 0.75);
+        self.y -= 6.0;
+    }
+
+    fn image(&mut self, image: PdfImage, width: f32, height: f32) {
+        let content_height = self.page.top - self.page.bottom;
+        let scale = (self.page.content_width() / width)
+            .min(content_height / height)
+            .min(1.0);
+        let (width, height) = (width * scale, height * scale);
+        self.skip(2.0);
+        if height > self.available() && !self.at_page_top() {
+            self.new_page();
+        }
+        self.images.push(image);
+        let name = format!("Im{}", self.images.len());
+        let x = self.page.left + (self.page.content_width() - width) / 2.0;
+        let y = self.y - height;
+        self.emit(&format!(
+            "q {} 0 0 {} {} {} cm /{name} Do Q",
+            num(width),
+            num(height),
+            num(x),
+            num(y)
+        ));
+        self.y = y - 4.0;
+    }
+
+    fn table(&mut self, rows: Vec<TableRowData>) {
+        let column_count = rows.iter().map(|row| row.cells.len()).max().unwrap_or(0);
+        if column_count == 0 {
+            return;
+        }
+        let widths_in = table_column_widths_in(&rows, column_count);
+        let total_in = widths_in.iter().sum::<f64>().max(f64::EPSILON) as f32;
+        let table_width = self
+            .page
+            .content_width()
+            .min(TABLE_WIDTH_IN as f32 * POINTS_PER_INCH);
+        let widths = widths_in
+            .iter()
+            .map(|width| *width as f32 / total_in * table_width)
+            .collect::<Vec<_>>();
+
+        let laid_out = rows
+            .iter()
+            .map(|row| {
+                let cells = (0..column_count)
+                    .map(|column| {
+                        let mut segments = row.cells.get(column).cloned().unwrap_or_default();
+                        if row.is_header {
+                            for segment in &mut segments {
+                                segment.style.bold = true;
+                            }
+                        }
+                        let inner = (widths[column] - TABLE_CELL_PADDING_PT * 2.0).max(1.0);
+                        self.break_lines(&segments, inner, false)
+                    })
+                    .collect::<Vec<_>>();
+                let height = cells
+                    .iter()
+                    .map(|lines| lines.iter().map(|line| line.height(1.0)).sum::<f32>())
+                    .fold(0.0, f32::max)
+                    + TABLE_CELL_PADDING_PT * 2.0;
+                (cells, height)
+            })
+            .collect::<Vec<_>>();
+        let header = rows.first().filter(|row| row.is_header).map(|_| 0usize);
+
+        for (index, (cells, height)) in laid_out.iter().enumerate() {
+            if *height > self.available() && !self.at_page_top() {
+                self.new_page();
+                if let Some(header) = header.filter(|header| *header != index) {
+                    let (header_cells, header_height) = &laid_out[header];
+                    self.table_row(&rows[header], header_cells, *header_height, &widths);
+                }
+            }
+            self.table_row(&rows[index], cells, *height, &widths);
+        }
+        self.y -= 6.0;
+    }
+
+    fn table_row(&mut self, row: &TableRowData, cells: &[Vec<Line>], height: f32, widths: &[f32]) {
+        let border = Rgb::parse(TABLE_BORDER_COLOR).map(|color| (color, TABLE_BORDER_WIDTH_PT));
+        let fill = row
+            .is_header
+            .then(|| Rgb::parse(TABLE_HEADER_FILL))
+            .flatten();
+        let mut x = self.page.left;
+        for (column, lines) in cells.iter().enumerate() {
+            let width = widths[column];
+            self.draw_rect(x, self.y - height, width, height, fill, border);
+            let align = row
+                .alignments
+                .get(column)
+                .copied()
+                .map(Align::from)
+                .unwrap_or_default();
+            let inner = width - TABLE_CELL_PADDING_PT * 2.0;
+            let mut top = self.y - TABLE_CELL_PADDING_PT;
+            for line in lines {
+                let line_height = line.height(1.0);
+                let offset = match align {
+                    Align::Left => 0.0,
+                    Align::Center => (inner - line.width) / 2.0,
+                    Align::Right => inner - line.width,
+                };
+                let baseline = Self::baseline(line, top, line_height);
+                self.draw_line(line, x + TABLE_CELL_PADDING_PT + offset.max(0.0), baseline);
+                top -= line_height;
+            }
+            x += width;
+        }
+        self.y -= height;
+    }
+
+    fn text_frame(&self, ctx: RenderContext) -> BlockFrame {
+        if let Some(kind) = ctx.callout {
+            BlockFrame::callout(kind, 0.0, 2.0)
+        } else if ctx.quote_depth == 0 {
+            BlockFrame::from_paragraph_style(&self.template.body().paragraph, 1.0)
+        } else {
+            BlockFrame::from_paragraph_style(
+                &self.template.quote().paragraph,
+                ctx.quote_depth as f32,
+            )
+        }
+    }
+
+    fn render_block<'a>(&mut self, node: &'a AstNode<'a>, ctx: RenderContext) {
+        match &node.data.borrow().value {
+            NodeValue::Heading(heading) => {
+                let block = self.template.heading(heading.level);
+                let segments = build_inline_segments(node, block.run.clone(), self.template, ctx);
+                let lines = self.break_lines(&segments, self.page.content_width(), false);
+                let frame = BlockFrame {
+                    keep_with_next: true,
+                    ..BlockFrame::from_paragraph_style(&block.paragraph, 1.0)
+                };
+                self.paragraph(lines, &frame);
+            }
+            NodeValue::Paragraph => {
+                let mut style = self.template.body().run.clone();
+                if ctx.callout.is_some() {
+                    style.color = Some(CALLOUT_BODY_TEXT.to_string());
+                } else if ctx.quote_depth > 0 {
+                    style = self.template.quote().run.clone();
+                }
+                let segments = build_inline_segments(node, style, self.template, ctx);
+                let frame = self.text_frame(ctx);
+                let width = self.page.content_width()
+                    - frame.indent_left
+                    - frame.indent_right
+                    - frame.padding * 2.0;
+                let lines = self.break_lines(&segments, width, false);
+                self.paragraph(lines, &frame);
+            }
+            NodeValue::BlockQuote | NodeValue::MultilineBlockQuote(_) => {
+                if let Some(callout) = detect_callout(node, self.template) {
+                    self.render_callout(node, ctx, callout);
+                } else {
+                    let next = RenderContext {
+                        quote_depth: ctx.quote_depth + 1,
+                        ..ctx
+                    };
+                    for child in node.children() {
+                        self.render_block(child, next);
+                    }
+                }
+            }
+            NodeValue::CodeBlock(code_block) => {
+                if is_mermaid_code_block(&code_block.info)
+                    && self.render_mermaid(&code_block.literal)
+                {
+                    return;
+                }
+                self.render_code(&code_block.literal, ctx);
+            }
+            NodeValue::ThematicBreak => self.rule(),
+            NodeValue::List(list) => self.render_list(node, ctx, list),
+            NodeValue::Table(table) => {
+                let rows = collect_table_rows(node, table, self.template);
+                if !rows.is_empty() {
+                    self.table(rows);
+                }
+            }
+            NodeValue::HtmlBlock(_) | NodeValue::HtmlInline(_) => {}
+            _ => {
+                for child in node.children() {
+                    self.render_block(child, ctx);
+                }
+            }
+        }
+    }
+
+    fn render_callout<'a>(
+        &mut self,
+        node: &'a AstNode<'a>,
+        ctx: RenderContext,
+        callout: (CalloutKind, Option<String>),
+    ) {
+        let (kind, title) = callout;
+        let header_style = TextStyle {
+            color: Some(CALLOUT_HEADER_TEXT.to_string()),
+            ..self.template.body().run.clone()
+        };
+        let mut segments = vec![RunSegment {
+            text: kind.label().to_string(),
+            style: TextStyle {
+                bold: true,
+                ..header_style.clone()
+            },
+        }];
+        if let Some(title) = title {
+            segments.push(RunSegment {
+                text: format!(" — {title}"),
+                style: header_style,
+            });
+        }
+        let frame = BlockFrame {
+            keep_with_next: true,
+            ..BlockFrame::callout(kind, 2.0, 2.0)
+        };
+        let width = self.page.content_width() - frame.padding * 2.0;
+        let lines = self.break_lines(&segments, width, false);
+        self.paragraph(lines, &frame);
+
+        let body_ctx = RenderContext {
+            callout: Some(kind),
+            ..ctx
+        };
+        for child in node.children().skip(1) {
+            self.render_block(child, body_ctx);
+        }
+    }
+
+    fn render_mermaid(&mut self, code: &str) -> bool {
+        let Some(png) = render_mermaid_png(code) else {
+            return false;
+        };
+        let Some((width_px, height_px)) = png_dimensions(&png).filter(|(w, h)| *w > 0 && *h > 0)
+        else {
+            return false;
+        };
+        let Some(image) = decode_png(&png) else {
+            return false;
+        };
+        let max_width = MERMAID_MAX_WIDTH_IN as f32 * POINTS_PER_INCH;
+        let width = width_px as f32 / IMAGE_PIXELS_PER_INCH * POINTS_PER_INCH;
+        let height = height_px as f32 / IMAGE_PIXELS_PER_INCH * POINTS_PER_INCH;
+        let scale = (max_width / width).min(1.0);
+        self.image(image, width * scale, height * scale);
+        true
+    }
+
+    fn render_code(&mut self, literal: &str, ctx: RenderContext) {
+        let style = TextStyle {
+            code: true,
+            color: Some(CODE_COLOR.to_string()),
+            ..TextStyle::default()
+        };
+        let frame = BlockFrame {
+            line_spacing: 1.0,
+            ..self.text_frame(ctx)
+        };
+        let width = self.page.content_width()
+            - frame.indent_left
+            - frame.indent_right
+            - frame.padding * 2.0;
+        let mut code_lines = literal.lines().collect::<Vec<_>>();
+        if code_lines.is_empty() {
+            code_lines.push("");
+        }
+        let lines = code_lines
+            .into_iter()
+            .flat_map(|line| {
+                let segments = [RunSegment {
+                    text: line.to_string(),
+                    style: style.clone(),
+                }];
+                self.break_lines(&segments, width, true)
+            })
+            .collect::<Vec<_>>();
+        self.paragraph(lines, &frame);
+    }
+
+    fn render_list<'a>(&mut self, node: &'a AstNode<'a>, ctx: RenderContext, list: &NodeList) {
+        let item_ctx = RenderContext {
+            list_depth: ctx.list_depth + 1,
+            ..ctx
+        };
+        let base_style = if ctx.callout.is_some() {
+            TextStyle {
+                color: Some(CALLOUT_BODY_TEXT.to_string()),
+                ..self.template.body().run.clone()
+            }
+        } else if ctx.quote_depth > 0 {
+            self.template.quote().run.clone()
+        } else {
+            self.template.list().run.clone()
+        };
+        let frame = BlockFrame {
+            indent_left: LIST_INDENT_PT * item_ctx.list_depth as f32,
+            ..BlockFrame::from_paragraph_style(&self.template.list().paragraph, 1.0)
+        };
+        let width = self.page.content_width() - frame.indent_left - frame.indent_right;
+
+        for (index, item) in node.children().enumerate() {
+            let task_prefix = match &item.data.borrow().value {
+                NodeValue::Item(_) => None,
+                NodeValue::TaskItem(task_item) => Some(task_item_prefix(task_item.symbol)),
+                _ => continue,
+            };
+            let marker_text = match list.list_type {
+                ListType::Bullet => "•".to_string(),
+                ListType::Ordered => format!("{}.", list.start + index),
+            };
+            let mut marker = self
+                .break_lines(
+                    &[RunSegment {
+                        text: marker_text,
+                        style: base_style.clone(),
+                    }],
+                    LIST_INDENT_PT * 4.0,
+                    false,
+                )
+                .pop();
+            let mut rendered_primary_paragraph = false;
+
+            for child in item.children() {
+                if !matches!(child.data.borrow().value, NodeValue::Paragraph) {
+                    self.render_block(child, item_ctx);
+                    continue;
+                }
+                let mut segments =
+                    build_inline_segments(child, base_style.clone(), self.template, item_ctx);
+                if !rendered_primary_paragraph {
+                    if let Some(task_prefix) = task_prefix {
+                        segments.insert(
+                            0,
+                            RunSegment {
+                                text: task_prefix.to_string(),
+                                style: base_style.clone(),
+                            },
+                        );
+                    }
+                    rendered_primary_paragraph = true;
+                }
+                let lines = self.break_lines(&segments, width, false);
+                let item_frame = BlockFrame {
+                    marker: marker.take(),
+                    ..frame.clone()
+                };
+                self.paragraph(lines, &item_frame);
+            }
+
+            if !rendered_primary_paragraph {
+                let segments = task_prefix
+                    .map(|task_prefix| RunSegment {
+                        text: task_prefix.to_string(),
+                        style: base_style.clone(),
+                    })
+                    .into_iter()
+                    .collect::<Vec<_>>();
+                let lines = self.break_lines(&segments, width, false);
+                let item_frame = BlockFrame {
+                    marker: marker.take(),
+                    ..frame.clone()
+                };
+                self.paragraph(lines, &item_frame);
+            }
+        }
+    }
+
+    fn draw_header_and_footer(&mut self, options: &PdfExportOptions, title: &str, date: &str) {
+        let total = self.pages.len();
+        let style = TextStyle {
+            size: Some((HEADER_FOOTER_SIZE_PT * 2.0) as u32),
+            color: Some(HEADER_FOOTER_COLOR.to_string()),
+            ..self.template.body().run.clone()
+        };
+        let header_y = (self.page.top + self.page.height) / 2.0;
+        let footer_y = self.page.bottom / 2.0;
+        for page in 0..total {
+            for (template, align, baseline) in [
+                (options.header.as_deref(), Align::Left, header_y),
+                (options.footer.as_deref(), Align::Center, footer_y),
+            ] {
+                let Some(template) = template.filter(|value| !value.trim().is_empty()) else {
+                    continue;
+                };
+                let text = template
+                    .replace("{title}", title)
+                    .replace("{page}", &(page + 1).to_string())
+                    .replace("{pages}", &total.to_string())
+                    .replace("{date}", date);
+                let segments = [RunSegment {
+                    text,
+                    style: style.clone(),
+                }];
+                let Some(line) = self
+                    .break_lines(&segments, self.page.content_width(), false)
+                    .into_iter()
+                    .next()
+                else {
+                    continue;
+                };
+                let x = match align {
+                    Align::Center => {
+                        self.page.left + (self.page.content_width() - line.width).max(0.0) / 2.0
+                    }
+                    _ => self.page.left,
+                };
+                let op = self.line_op(&line, x, baseline);
+                self.pages[page].push_str(&op);
+                self.pages[page].push('\n');
+            }
+        }
+    }
+
+    fn into_pdf(self, title: &str) -> Vec<u8> {
+        const CATALOG: usize = 1;
+        const PAGES: usize = 2;
+        const INFO: usize = 3;
+        const FIRST_FONT: usize = 4;
+
+        let font_count = PdfFont::all().count();
+        let mut next_id = FIRST_FONT + font_count;
+        let mut image_ids = Vec::with_capacity(self.images.len());
+        for image in &self.images {
+            let mask = image.alpha.as_ref().map(|_| next_id + 1);
+            image_ids.push((next_id, mask));
+            next_id += 1 + usize::from(mask.is_some());
+        }
+        let page_ids = (0..self.pages.len())
+            .map(|index| (next_id + index * 2, next_id + index * 2 + 1))
+            .collect::<Vec<_>>();
+        let mut writer = PdfWriter::new(next_id + self.pages.len() * 2 - 1);
+
+        writer.object(CATALOG, &format!("<< /Type /Catalog /Pages {PAGES} 0 R >>"));
+        let kids = page_ids
+            .iter()
+            .map(|(page, _)| format!("{page} 0 R"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        writer.object(
+            PAGES,
+            &format!(
+                "<< /Type /Pages /Kids [{kids}] /Count {} >>",
+                page_ids.len()
+            ),
+        );
+        writer.object(
+            INFO,
+            &format!(
+                "<< /Title {} /Producer (Tomosona) >>",
+                pdf_text_string(title)
+            ),
+        );
+
+        let mut fonts = String::new();
+        for (offset, font) in PdfFont::all().enumerate() {
+            let id = FIRST_FONT + offset;
+            writer.object(
+                id,
+                &format!(
+                    "<< /Type /Font /Subtype /Type1 /BaseFont /{} /Encoding /WinAnsiEncoding >>",
+                    font.base_font()
+                ),
+            );
+            fonts.push_str(&format!(" /{} {id} 0 R", font.resource_name()));
+        }
+
+        let mut x_objects = String::new();
+        for (index, (image, (id, mask))) in self.images.iter().zip(&image_ids).enumerate() {
+            let dimensions = format!(
+                "/Type /XObject /Subtype /Image /Width {} /Height {} /BitsPerComponent 8 /Filter /FlateDecode",
+                image.width, image.height
+            );
+            let mask_ref = mask
+                .map(|mask| format!(" /SMask {mask} 0 R"))
+                .unwrap_or_default();
+            writer.stream(
+                *id,
+                &format!("{dimensions} /ColorSpace /DeviceRGB{mask_ref}"),
+                &image.rgb,
+            );
+            if let (Some(mask), Some(alpha)) = (mask, image.alpha.as_ref()) {
+                writer.stream(
+                    *mask,
+                    &format!("{dimensions} /ColorSpace /DeviceGray"),
+                    alpha,
+                );
+            }
+            x_objects.push_str(&format!(" /Im{} {id} 0 R", index + 1));
+        }
+
+        let resources = format!("<< /Font <<{fonts} >> /XObject <<{x_objects} >> >>");
+        for (content, (page, contents)) in self.pages.iter().zip(&page_ids) {
+            writer.object(
+                *page,
+                &format!(
+                    "<< /Type /Page /Parent {PAGES} 0 R /MediaBox [0 0 {} {}] /Resources {resources} /Contents {contents} 0 R >>",
+                    num(self.page.width),
+                    num(self.page.height)
+                ),
+            );
+            writer.stream(
+                *contents,
+                "/Filter /FlateDecode",
+                &compress_to_vec_zlib(content.as_bytes(), COMPRESSION_LEVEL),
+            );
+        }
+        writer.finish(CATALOG, INFO)
+    }
+}
+
+/// Encodes document metadata as a UTF-16BE hex string.
+fn pdf_text_string(text: &str) -> String {
+    let mut output = String::from("<FEFF");
+    for unit in text.encode_utf16() {
+        output.push_str(&format!("{unit:04X}"));
+    }
+    output.push('>');
+    output
+}
+
+/// Minimal PDF object serializer with a classic cross-reference table.
+struct PdfWriter {
+    bytes: Vec<u8>,
+    offsets: Vec<usize>,
+}
+
+impl PdfWriter {
+    fn new(object_count: usize) -> Self {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n");
+        Self {
+            bytes,
+            offsets: vec![0; object_count],
+        }
+    }
+
+    fn object(&mut self, id: usize, body: &str) {
+        self.offsets[id - 1] = self.bytes.len();
+        self.bytes
+            .extend_from_slice(format!("{id} 0 obj\n{body}\nendobj\n").as_bytes());
+    }
+
+    fn stream(&mut self, id: usize, dictionary: &str, data: &[u8]) {
+        self.offsets[id - 1] = self.bytes.len();
+        self.bytes.extend_from_slice(
+            format!(
+                "{id} 0 obj\n<< {dictionary} /Length {} >>\nstream\n",
+                data.len()
+            )
+            .as_bytes(),
+        );
+        self.bytes.extend_from_slice(data);
+        self.bytes.extend_from_slice(b"\nendstream\nendobj\n");
+    }
+
+    fn finish(mut self, root: usize, info: usize) -> Vec<u8> {
+        let xref_offset = self.bytes.len();
+        let mut xref = format!("xref\n0 {}\n0000000000 65535 f \n", self.offsets.len() + 1);
+        for offset in &self.offsets {
+            xref.push_str(&format!("{offset:010} 00000 n \n"));
+        }
+        xref.push_str(&format!(
+            "trailer\n<< /Size {} /Root {root} 0 R /Info {info} 0 R >>\nstartxref\n{xref_offset}\n%%EOF\n",
+            self.offsets.len() + 1
+        ));
+        self.bytes.extend_from_slice(xref.as_bytes());
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn create_temp_workspace(prefix: &str) -> PathBuf {
+        let nonce = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|value| value.as_nanos())
+            .unwrap_or(0);
+        let dir = std::env::temp_dir().join(format!("{prefix}-{nonce}"));
+        fs::create_dir_all(&dir).expect("create temp workspace");
+        dir
+    }
+
+    fn layout(markdown: &str, options: &PdfExportOptions) -> Vec<String> {
+        let template = TemplateStyle::default();
+        let geometry = PageGeometry::from_options(options).expect("geometry");
+        let mut layout = layout_markdown(markdown, &template, geometry);
+        layout.draw_header_and_footer(options, "Report", "2026-01-02");
+        layout.pages
+    }
+
+    #[test]
+    fn breaks_long_notes_across_pages_with_header_and_footer() {
+        let paragraph = "Lorem ipsum dolor sit amet, consectetur adipiscing elit. ".repeat(40);
+        let markdown = format!("# Title\n\n{}", format!("{paragraph}\n\n").repeat(6));
+        let options = PdfExportOptions {
+            page_size: PdfPageSize::A5,
+            header: Some("{title} — {date}".to_string()),
+            footer: Some("Page {page} of {pages}".to_string()),
+            ..PdfExportOptions::default()
+        };
+        let pages = layout(&markdown, &options);
+
+        assert!(pages.len() > 2);
+        let total = pages.len();
+        assert!(pages[0].contains("(Report \\227 2026-01-02) Tj"));
+        assert!(pages[0].contains(&format!("(Page 1 of {total}) Tj")));
+        assert!(pages[total - 1].contains(&format!("(Page {total} of {total}) Tj")));
+        assert!(pages[0].contains("/F2 18 Tf"));
+    }
+
+    #[test]
+    fn renders_callouts_tasks_tables_and_frontmatter() {
+        let markdown = "---\nstatus: draft\n---\n> [!warning] Careful\n>\n> Mind the gap.\n\n- [x] Shipped\n- [ ] Pending\n\n| Name | Score |\n| --- | ---: |\n| Ada | 10 |\n\n---\n\n```\n  indented (code)\n```\n";
+        let pages = layout(markdown, &PdfExportOptions::default());
+        let content = pages.join("\n");
+
+        assert!(content.contains("(Key) Tj"));
+        assert!(content.contains("(draft) Tj"));
+        assert!(content.contains("(Warning) Tj"));
+        assert!(content.contains("( \\227 Careful) Tj"));
+        assert!(content.contains(&Rgb::parse("F4E8DF").expect("fill").fill_op()));
+        assert!(content.contains("([x] Shipped) Tj"));
+        assert!(content.contains("([ ] Pending) Tj"));
+        assert!(content.contains("(\\225) Tj"));
+        assert!(content.contains("(Ada) Tj"));
+        assert!(content.contains("/F9 10 Tf"));
+        assert!(content.contains("(  indented \\(code\\)) Tj"));
+    }
+
+    #[test]
+    fn reports_characters_the_standard_fonts_cannot_show() {
+        let options = PdfExportOptions {
+            footer: Some("{title} ✓".to_string()),
+            ..PdfExportOptions::default()
+        };
+        let (bytes, replaced) = render_pdf(
+            "# Café 東京\n\nSee → there, “quoted” and 東京 again?",
+            &TemplateStyle::default(),
+            &options,
+            "Report",
+        )
+        .expect("render");
+
+        assert!(bytes.starts_with(b"%PDF-1.4"));
+        assert_eq!(replaced, vec!["→", "✓", "京", "東"]);
+    }
+
+    #[test]
+    fn rejects_margins_without_room_for_content() {
+        let options = PdfExportOptions {
+            margins_mm: Some(PdfMargins {
+                top: 150.0,
+                right: 20.0,
+                bottom: 150.0,
+                left: 20.0,
+            }),
+            ..PdfExportOptions::default()
+        };
+        assert!(matches!(
+            PageGeometry::from_options(&options),
+            Err(AppError::InvalidOperation(_))
+        ));
+    }
+
+    #[test]
+    fn convert_markdown_to_pdf_writes_next_to_note_with_collision_suffix() {
+        let _guard = crate::workspace_test_guard();
+        let workspace = create_temp_workspace("tomosona-pdf-export");
+        let source = workspace.join("note.md");
+        fs::write(&source, "# Title\n\nBody with é and “quotes”.").expect("write note");
+        fs::write(workspace.join("note.pdf"), "occupied").expect("occupy output");
+
+        crate::set_active_workspace(&workspace.to_string_lossy()).expect("set workspace");
+        let output = convert_markdown_to_pdf_sync(
+            source.to_string_lossy().to_string(),
+            PdfExportOptions {
+                landscape: true,
+                ..PdfExportOptions::default()
+            },
+        )
+        .expect("convert");
+
+        assert!(output.output_path.ends_with("note (1).pdf"));
+        assert!(output.replaced_characters.is_empty());
+        let bytes = fs::read(&output.output_path).expect("read pdf");
+        assert!(bytes.starts_with(b"%PDF-1.4"));
+        assert!(bytes.ends_with(b"%%EOF\n"));
+        let text = String::from_utf8_lossy(&bytes);
+        assert!(text.contains("/MediaBox [0 0 841.89 595.28]"));
+        assert!(text.contains("/BaseFont /Helvetica-Bold"));
+
+        let invalid = workspace.join("note.txt");
+        fs::write(&invalid, "text").expect("write text");
+        let result =
+            convert_markdown_to_pdf_sync(invalid.to_string_lossy().to_string(), Default::default());
+        assert!(matches!(result, Err(AppError::InvalidPath)));
+    }
+}
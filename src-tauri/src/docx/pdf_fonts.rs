@@ -0,0 +1,281 @@
+//! Standard PDF fonts used by the PDF export.
+//!
+//! The export only relies on the base-14 fonts every PDF reader provides, so
+//! text is encoded as WinAnsi and measured with the Adobe AFM advance widths
+//! below (codes 32..=255, in 1/1000 em). Characters outside WinAnsi print as
+//! `?`; `is_winansi` lets the export report them.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum FontFamily {
+    Sans,
+    Serif,
+    Mono,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct PdfFont {
+    pub(super) family: FontFamily,
+    pub(super) bold: bool,
+    pub(super) italic: bool,
+}
+
+const MONO_WIDTH: u16 = 600;
+const FAMILIES: [FontFamily; 3] = [FontFamily::Sans, FontFamily::Serif, FontFamily::Mono];
+
+impl PdfFont {
+    /// Every font variant, in resource order (`F1`..`F12`).
+    pub(super) fn all() -> impl Iterator<Item = PdfFont> {
+        FAMILIES.into_iter().flat_map(|family| {
+            [(false, false), (true, false), (false, true), (true, true)]
+                .into_iter()
+                .map(move |(bold, italic)| PdfFont {
+                    family,
+                    bold,
+                    italic,
+                })
+        })
+    }
+
+    fn index(self) -> usize {
+        let family = match self.family {
+            FontFamily::Sans => 0,
+            FontFamily::Serif => 1,
+            FontFamily::Mono => 2,
+        };
+        family * 4 + usize::from(self.bold) + usize::from(self.italic) * 2
+    }
+
+    pub(super) fn resource_name(self) -> String {
+        format!("F{}", self.index() + 1)
+    }
+
+    pub(super) fn base_font(self) -> &'static str {
+        match (self.family, self.bold, self.italic) {
+            (FontFamily::Sans, false, false) => "Helvetica",
+            (FontFamily::Sans, true, false) => "Helvetica-Bold",
+            (FontFamily::Sans, false, true) => "Helvetica-Oblique",
+            (FontFamily::Sans, true, true) => "Helvetica-BoldOblique",
+            (FontFamily::Serif, false, false) => "Times-Roman",
+            (FontFamily::Serif, true, false) => "Times-Bold",
+            (FontFamily::Serif, false, true) => "Times-Italic",
+            (FontFamily::Serif, true, true) => "Times-BoldItalic",
+            (FontFamily::Mono, false, false) => "Courier",
+            (FontFamily::Mono, true, false) => "Courier-Bold",
+            (FontFamily::Mono, false, true) => "Courier-Oblique",
+            (FontFamily::Mono, true, true) => "Courier-BoldOblique",
+        }
+    }
+
+    fn widths(self) -> Option<&'static [u16; 224]> {
+        match (self.family, self.bold, self.italic) {
+            (FontFamily::Sans, false, _) => Some(&HELVETICA_WIDTHS),
+            (FontFamily::Sans, true, _) => Some(&HELVETICA_BOLD_WIDTHS),
+            (FontFamily::Serif, false, false) => Some(&TIMES_ROMAN_WIDTHS),
+            (FontFamily::Serif, true, false) => Some(&TIMES_BOLD_WIDTHS),
+            (FontFamily::Serif, false, true) => Some(&TIMES_ITALIC_WIDTHS),
+            (FontFamily::Serif, true, true) => Some(&TIMES_BOLD_ITALIC_WIDTHS),
+            (FontFamily::Mono, _, _) => None,
+        }
+    }
+
+    /// Width of `text` in points once encoded as WinAnsi.
+    pub(super) fn text_width(self, text: &str, size: f32) -> f32 {
+        let widths = self.widths();
+        let units = text
+            .chars()
+            .map(|ch| {
+                let code = winansi_byte(ch).max(32);
+                widths
+                    .map(|widths| widths[usize::from(code - 32)])
+                    .unwrap_or(MONO_WIDTH) as f32
+            })
+            .sum::<f32>();
+        units * size / 1000.0
+    }
+}
+
+/// Picks the closest standard family for a DOCX template font name.
+pub(super) fn font_family(name: &str) -> FontFamily {
+    let name = name.to_lowercase();
+    if ["courier", "mono", "consolas", "menlo", "code"]
+        .iter()
+        .any(|token| name.contains(token))
+    {
+        FontFamily::Mono
+    } else if !name.contains("sans")
+        && [
+            "times", "serif", "georgia", "garamond", "cambria", "book", "palatino",
+        ]
+        .iter()
+        .any(|token| name.contains(token))
+    {
+        FontFamily::Serif
+    } else {
+        FontFamily::Sans
+    }
+}
+
+/// Encodes a character as WinAnsi, replacing anything outside it with `?`.
+pub(super) fn winansi_byte(ch: char) -> u8 {
+    match ch {
+        '\u{20}'..='\u{7e}' | '\u{a1}'..='\u{ff}' => ch as u8,
+        '\u{a0}' | '\u{2002}'..='\u{200a}' | '\u{202f}' | '\t' | '\n' | '\r' => b' ',
+        '€' => 0x80,
+        '‚' => 0x82,
+        'ƒ' => 0x83,
+        '„' => 0x84,
+        '…' => 0x85,
+        '†' => 0x86,
+        '‡' => 0x87,
+        'ˆ' => 0x88,
+        '‰' => 0x89,
+        'Š' => 0x8a,
+        '‹' => 0x8b,
+        'Œ' => 0x8c,
+        'Ž' => 0x8e,
+        '‘' => 0x91,
+        '’' => 0x92,
+        '“' => 0x93,
+        '”' => 0x94,
+        '•' => 0x95,
+        '–' | '‐' | '‑' | '−' => 0x96,
+        '—' => 0x97,
+        '˜' => 0x98,
+        '™' => 0x99,
+        'š' => 0x9a,
+        '›' => 0x9b,
+        'œ' => 0x9c,
+        'ž' => 0x9e,
+        'Ÿ' => 0x9f,
+        _ => b'?',
+    }
+}
+
+/// Whether `ch` prints as itself rather than as the `?` replacement.
+pub(super) fn is_winansi(ch: char) -> bool {
+    ch == '?' || winansi_byte(ch) != b'?'
+}
+
+const HELVETICA_WIDTHS: [u16; 224] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, 1015, 667, 667, 722, 722, 667,
+    611, 778, 722, 278, 500, 667, 556, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 278, 278, 278, 469, 556, 333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500,
+    222, 833, 556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+    350, 556, 350, 222, 556, 333, 1000, 556, 556, 333, 1000, 667, 333, 1000, 350, 611, 350, 350,
+    222, 222, 333, 333, 350, 556, 1000, 333, 1000, 500, 333, 944, 350, 500, 667, 278, 333, 556,
+    556, 556, 556, 260, 556, 333, 737, 370, 556, 584, 333, 737, 333, 400, 584, 333, 333, 333, 556,
+    537, 278, 333, 333, 365, 556, 834, 834, 834, 611, 667, 667, 667, 667, 667, 667, 1000, 722, 667,
+    667, 667, 667, 278, 278, 278, 278, 722, 722, 778, 778, 778, 778, 778, 584, 778, 722, 722, 722,
+    722, 667, 667, 611, 556, 556, 556, 556, 556, 556, 889, 500, 556, 556, 556, 556, 278, 278, 278,
+    278, 556, 556, 556, 556, 556, 556, 556, 584, 611, 556, 556, 556, 556, 500, 556, 500,
+];
+
+const HELVETICA_BOLD_WIDTHS: [u16; 224] = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611, 975, 722, 722, 722, 722, 667,
+    611, 778, 722, 278, 556, 722, 611, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 333, 278, 333, 584, 556, 333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556,
+    278, 889, 611, 611, 611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+    350, 556, 350, 278, 556, 500, 1000, 556, 556, 333, 1000, 667, 333, 1000, 350, 611, 350, 350,
+    278, 278, 500, 500, 350, 556, 1000, 333, 1000, 556, 333, 944, 350, 500, 667, 278, 333, 556,
+    556, 556, 556, 280, 556, 333, 737, 370, 556, 584, 333, 737, 333, 400, 584, 333, 333, 333, 611,
+    556, 278, 333, 333, 365, 556, 834, 834, 834, 611, 722, 722, 722, 722, 722, 722, 1000, 722, 667,
+    667, 667, 667, 278, 278, 278, 278, 722, 722, 778, 778, 778, 778, 778, 584, 778, 722, 722, 722,
+    722, 667, 667, 611, 556, 556, 556, 556, 556, 556, 889, 556, 556, 556, 556, 556, 278, 278, 278,
+    278, 611, 611, 611, 611, 611, 611, 611, 584, 611, 611, 611, 611, 611, 556, 611, 556,
+];
+
+const TIMES_ROMAN_WIDTHS: [u16; 224] = [
+    250, 333, 408, 500, 500, 833, 778, 180, 333, 333, 500, 564, 250, 333, 250, 278, 500, 500, 500,
+    500, 500, 500, 500, 500, 500, 500, 278, 278, 564, 564, 564, 444, 921, 722, 667, 667, 722, 611,
+    556, 722, 722, 333, 389, 722, 611, 889, 722, 722, 556, 722, 667, 556, 611, 722, 722, 944, 722,
+    722, 611, 333, 278, 333, 469, 500, 333, 444, 500, 444, 500, 444, 333, 500, 500, 278, 278, 500,
+    278, 778, 500, 500, 500, 500, 333, 389, 278, 500, 500, 722, 500, 500, 444, 480, 200, 480, 541,
+    350, 500, 350, 333, 500, 444, 1000, 500, 500, 333, 1000, 556, 333, 889, 350, 611, 350, 350,
+    333, 333, 444, 444, 350, 500, 1000, 333, 980, 389, 333, 722, 350, 444, 722, 250, 333, 500, 500,
+    500, 500, 200, 500, 333, 760, 276, 500, 564, 333, 760, 333, 400, 564, 300, 300, 333, 500, 453,
+    250, 333, 300, 310, 500, 750, 750, 750, 444, 722, 722, 722, 722, 722, 722, 889, 667, 611, 611,
+    611, 611, 333, 333, 333, 333, 722, 722, 722, 722, 722, 722, 722, 564, 722, 722, 722, 722, 722,
+    722, 556, 500, 444, 444, 444, 444, 444, 444, 667, 444, 444, 444, 444, 444, 278, 278, 278, 278,
+    500, 500, 500, 500, 500, 500, 500, 564, 500, 500, 500, 500, 500, 500, 500, 500,
+];
+
+const TIMES_BOLD_WIDTHS: [u16; 224] = [
+    250, 333, 555, 500, 500, 1000, 833, 278, 333, 333, 500, 570, 250, 333, 250, 278, 500, 500, 500,
+    500, 500, 500, 500, 500, 500, 500, 333, 333, 570, 570, 570, 500, 930, 722, 667, 722, 722, 667,
+    611, 778, 778, 389, 500, 778, 667, 944, 722, 778, 611, 778, 722, 556, 667, 722, 722, 1000, 722,
+    722, 667, 333, 278, 333, 581, 500, 333, 500, 556, 444, 556, 444, 333, 500, 556, 278, 333, 556,
+    278, 833, 556, 500, 556, 556, 444, 389, 333, 556, 500, 722, 500, 500, 444, 394, 220, 394, 520,
+    350, 500, 350, 333, 500, 500, 1000, 500, 500, 333, 1000, 556, 333, 1000, 350, 667, 350, 350,
+    333, 333, 500, 500, 350, 500, 1000, 333, 1000, 389, 333, 722, 350, 444, 722, 250, 333, 500,
+    500, 500, 500, 220, 500, 333, 747, 300, 500, 570, 333, 747, 333, 400, 570, 300, 300, 333, 556,
+    540, 250, 333, 300, 330, 500, 750, 750, 750, 500, 722, 722, 722, 722, 722, 722, 1000, 722, 667,
+    667, 667, 667, 389, 389, 389, 389, 722, 722, 778, 778, 778, 778, 778, 570, 778, 722, 722, 722,
+    722, 722, 611, 556, 500, 500, 500, 500, 500, 500, 722, 444, 444, 444, 444, 444, 278, 278, 278,
+    278, 500, 556, 500, 500, 500, 500, 500, 570, 500, 556, 556, 556, 556, 500, 556, 500,
+];
+
+const TIMES_ITALIC_WIDTHS: [u16; 224] = [
+    250, 333, 420, 500, 500, 833, 778, 214, 333, 333, 500, 675, 250, 333, 250, 278, 500, 500, 500,
+    500, 500, 500, 500, 500, 500, 500, 333, 333, 675, 675, 675, 500, 920, 611, 611, 667, 722, 611,
+    611, 722, 722, 333, 444, 667, 556, 833, 667, 722, 611, 722, 611, 500, 556, 722, 611, 833, 611,
+    556, 556, 389, 278, 389, 422, 500, 333, 500, 500, 444, 500, 444, 278, 500, 500, 278, 278, 444,
+    278, 722, 500, 500, 500, 500, 389, 389, 278, 500, 444, 667, 444, 444, 389, 400, 275, 400, 541,
+    350, 500, 350, 333, 500, 556, 889, 500, 500, 333, 1000, 500, 333, 944, 350, 556, 350, 350, 333,
+    333, 556, 556, 350, 500, 889, 333, 980, 389, 333, 667, 350, 389, 556, 250, 389, 500, 500, 500,
+    500, 275, 500, 333, 760, 276, 500, 675, 333, 760, 333, 400, 675, 300, 300, 333, 500, 523, 250,
+    333, 300, 310, 500, 750, 750, 750, 500, 611, 611, 611, 611, 611, 611, 889, 667, 611, 611, 611,
+    611, 333, 333, 333, 333, 722, 667, 722, 722, 722, 722, 722, 675, 722, 722, 722, 722, 722, 556,
+    611, 500, 500, 500, 500, 500, 500, 500, 667, 444, 444, 444, 444, 444, 278, 278, 278, 278, 500,
+    500, 500, 500, 500, 500, 500, 675, 500, 500, 500, 500, 500, 444, 500, 444,
+];
+
+const TIMES_BOLD_ITALIC_WIDTHS: [u16; 224] = [
+    250, 389, 555, 500, 500, 833, 778, 278, 333, 333, 500, 570, 250, 333, 250, 278, 500, 500, 500,
+    500, 500, 500, 500, 500, 500, 500, 333, 333, 570, 570, 570, 500, 832, 667, 667, 667, 722, 667,
+    667, 722, 778, 389, 500, 667, 611, 889, 722, 722, 611, 722, 667, 556, 611, 722, 667, 889, 667,
+    611, 611, 333, 278, 333, 570, 500, 333, 500, 500, 444, 500, 444, 333, 500, 556, 278, 278, 500,
+    278, 778, 556, 500, 500, 500, 389, 389, 278, 556, 444, 667, 500, 444, 389, 348, 220, 348, 570,
+    350, 500, 350, 333, 500, 500, 1000, 500, 500, 333, 1000, 556, 333, 944, 350, 611, 350, 350,
+    333, 333, 500, 500, 350, 500, 1000, 333, 1000, 389, 333, 722, 350, 389, 611, 250, 389, 500,
+    500, 500, 500, 220, 500, 333, 747, 266, 500, 606, 333, 747, 333, 400, 570, 300, 300, 333, 576,
+    500, 250, 333, 300, 300, 500, 750, 750, 750, 500, 667, 667, 667, 667, 667, 667, 944, 667, 667,
+    667, 667, 667, 389, 389, 389, 389, 722, 722, 722, 722, 722, 722, 722, 570, 722, 722, 722, 722,
+    722, 611, 611, 500, 500, 500, 500, 500, 500, 500, 722, 444, 444, 444, 444, 444, 278, 278, 278,
+    278, 500, 556, 500, 500, 500, 500, 500, 570, 500, 556, 556, 556, 556, 444, 500, 444,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_text_with_afm_widths() {
+        let regular = PdfFont {
+            family: FontFamily::Sans,
+            bold: false,
+            italic: false,
+        };
+        let mono = PdfFont {
+            family: FontFamily::Mono,
+            ..regular
+        };
+        assert!((regular.text_width("Hello", 10.0) - 22.78).abs() < 0.001);
+        assert!((mono.text_width("Hello", 10.0) - 30.0).abs() < 0.001);
+        assert_eq!(PdfFont::all().count(), 12);
+        assert_eq!(mono.resource_name(), "F9");
+    }
+
+    #[test]
+    fn maps_typography_to_winansi() {
+        assert_eq!(winansi_byte('é'), 0xe9);
+        assert_eq!(winansi_byte('—'), 0x97);
+        assert_eq!(winansi_byte('€'), 0x80);
+        assert_eq!(winansi_byte('中'), b'?');
+        assert!(is_winansi('?') && is_winansi('\n') && !is_winansi('中'));
+        assert_eq!(font_family("Times New Roman"), FontFamily::Serif);
+        assert_eq!(font_family("Source Sans Pro"), FontFamily::Sans);
+        assert_eq!(font_family("Courier New"), FontFamily::Mono);
+    }
+}
@@ -76,7 +76,7 @@ fn extract_heading_style(doc: &Document, level: u8, fallback: BlockStyle) -> Blo
     )
 }
 
-fn heading_style_candidates(level: u8) -> &'static [&'static str] {
+pub(crate) fn heading_style_candidates(level: u8) -> &'static [&'static str] {
     match level {
         1 => &["Titre1", "Heading1"],
         2 => &["Titre2", "Heading2"],
@@ -114,6 +114,8 @@ fn extract_block_style(
                     ),
                     size: rpr.sz.map(|size| size.0).or(fallback.run.size),
                     color: rpr.color.clone().or(fallback.run.color.clone()),
+                    link: None,
+                    footnote: None,
                 },
                 paragraph: ParagraphStyle {
                     space_before: ppr
@@ -16,8 +16,10 @@ use serde::{Deserialize, Serialize};
 
 use crate::editor_sync::record_workspace_mutation_write_from_disk;
 use crate::{
-    active_workspace_root, clear_active_workspace, note_link_target, set_active_workspace,
-    workspace_watch, AppError, Result,
+    active_workspace_root, clear_active_workspace, note_link_target,
+    note_templates::{self, NoteTemplateRequest},
+    operation_journal::{self, OperationKind},
+    set_active_workspace, trash, workspace_watch, AppError, Result,
 };
 
 const TRASH_DIR_NAME: &str = ".tomosona-trash";
@@ -58,6 +60,13 @@ pub enum EntryKind {
     Folder,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct CreatedEntry {
+    pub path: String,
+    /// Byte offset of the template's `{{cursor}}` marker in the written note.
+    pub cursor_offset: Option<usize>,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct ExtractedNoteResult {
     pub path: String,
@@ -382,7 +391,11 @@ fn next_available_path(path: &Path) -> Result<PathBuf> {
     Err(AppError::OperationFailed)
 }
 
-fn resolve_destination(path: PathBuf, strategy: ConflictStrategy, is_dir: bool) -> Result<PathBuf> {
+pub(crate) fn resolve_destination(
+    path: PathBuf,
+    strategy: ConflictStrategy,
+    is_dir: bool,
+) -> Result<PathBuf> {
     if !path.exists() {
         return Ok(path);
     }
@@ -402,7 +415,7 @@ fn resolve_destination(path: PathBuf, strategy: ConflictStrategy, is_dir: bool)
     }
 }
 
-fn copy_dir_recursive(source: &Path, destination: &Path) -> Result<()> {
+pub(crate) fn copy_dir_recursive(source: &Path, destination: &Path) -> Result<()> {
     fs::create_dir_all(destination)?;
 
     for entry in fs::read_dir(source)? {
@@ -531,6 +544,7 @@ pub fn select_working_folder(app_handle: tauri::AppHandle) -> Result<Option<Stri
     };
     let canonical = set_active_workspace(&path.to_string_lossy())?;
     workspace_watch::start_workspace_watcher(app_handle, canonical.clone())?;
+    let _ = trash::purge_expired_entries(&canonical);
     Ok(Some(canonical.to_string_lossy().to_string()))
 }
 
@@ -544,6 +558,7 @@ pub fn clear_working_folder() -> Result<()> {
 pub fn set_working_folder(path: String, app_handle: tauri::AppHandle) -> Result<String> {
     let canonical = set_active_workspace(&path)?;
     workspace_watch::start_workspace_watcher(app_handle, canonical.clone())?;
+    let _ = trash::purge_expired_entries(&canonical);
     Ok(canonical.to_string_lossy().to_string())
 }
 
@@ -1606,7 +1621,8 @@ pub fn create_entry(
     name: String,
     kind: EntryKind,
     conflict_strategy: ConflictStrategy,
-) -> Result<String> {
+    template: Option<NoteTemplateRequest>,
+) -> Result<CreatedEntry> {
     let root = active_workspace_root()?;
     let parent = normalize_existing_dir(&parent_path)?;
     ensure_within_root(&root, &parent)?;
@@ -1618,15 +1634,28 @@ pub fn create_entry(
     let is_dir = matches!(kind, EntryKind::Folder);
     let destination = resolve_destination(base_path, conflict_strategy, is_dir)?;
 
+    let initial_content = if is_dir {
+        None
+    } else {
+        note_templates::initial_note_content(&root, &destination, template.as_ref())?
+    };
+
+    let mut cursor_offset = None;
     if is_dir {
         fs::create_dir_all(&destination)?;
+    } else if let Some(rendered) = initial_content {
+        fs::write(&destination, rendered.content)?;
+        cursor_offset = rendered.cursor_offset;
     } else if destination.exists() {
         fs::write(&destination, "")?;
     } else {
         fs::File::create(&destination)?;
     }
 
-    Ok(destination.to_string_lossy().to_string())
+    Ok(CreatedEntry {
+        path: destination.to_string_lossy().to_string(),
+        cursor_offset,
+    })
 }
 
 #[tauri::command]
@@ -1658,7 +1687,9 @@ pub fn create_extracted_note(source_path: String, content: String) -> Result<Ext
         base_name,
         EntryKind::File,
         ConflictStrategy::Rename,
-    )?;
+        None,
+    )?
+    .path;
     let created = PathBuf::from(&created_path);
 
     if let Err(error) = fs::write(&created, &note_content) {
@@ -1706,9 +1737,26 @@ pub fn rename_entry(
     if destination.is_file() {
         record_workspace_mutation_write_from_disk(&destination);
     }
+    // An operation that cannot be undone is reverted rather than kept silently.
+    if let Err(err) =
+        operation_journal::record_move(&root, OperationKind::Rename, &source, &destination)
+    {
+        fs::rename(&destination, &source)?;
+        return Err(err);
+    }
     Ok(destination.to_string_lossy().to_string())
 }
 
+/// Deletes a copy this command just created when it cannot be recorded for undo.
+fn remove_created_copy(path: &Path) -> Result<()> {
+    if path.is_dir() {
+        fs::remove_dir_all(path)?;
+    } else {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
 #[tauri::command]
 pub fn duplicate_entry(path: String, conflict_strategy: ConflictStrategy) -> Result<String> {
     let root = active_workspace_root()?;
@@ -1732,6 +1780,12 @@ pub fn duplicate_entry(path: String, conflict_strategy: ConflictStrategy) -> Res
         fs::copy(&source, &destination)?;
     }
 
+    if let Err(err) =
+        operation_journal::record_copy(&root, OperationKind::Duplicate, &source, &destination)
+    {
+        remove_created_copy(&destination)?;
+        return Err(err);
+    }
     Ok(destination.to_string_lossy().to_string())
 }
 
@@ -1779,6 +1833,12 @@ pub fn move_entry(
     if destination.is_file() {
         record_workspace_mutation_write_from_disk(&destination);
     }
+    if let Err(err) =
+        operation_journal::record_move(&root, OperationKind::Move, &source, &destination)
+    {
+        fs::rename(&destination, &source)?;
+        return Err(err);
+    }
     Ok(destination.to_string_lossy().to_string())
 }
 
@@ -1820,17 +1880,27 @@ pub fn copy_entry(
         fs::copy(&source, &destination)?;
     }
 
+    if let Err(err) =
+        operation_journal::record_copy(&root, OperationKind::Copy, &source, &destination)
+    {
+        remove_created_copy(&destination)?;
+        return Err(err);
+    }
     Ok(destination.to_string_lossy().to_string())
 }
 
-#[tauri::command]
-pub fn trash_entry(path: String) -> Result<String> {
-    let root = active_workspace_root()?;
-    let source = normalize_existing_path(&path)?;
-    ensure_within_root(&root, &source)?;
+/// Entry moved to the workspace trash by [`move_to_trash`].
+pub(crate) struct TrashedEntry {
+    pub path: PathBuf,
+    pub size_bytes: u64,
+    pub is_dir: bool,
+}
 
-    let root_canonical = fs::canonicalize(&root)?;
-    let source_canonical = fs::canonicalize(&source)?;
+/// Moves `source` to the workspace trash and records its original path in the trash
+/// manifest, without adding an undo operation.
+pub(crate) fn move_to_trash(root: &Path, source: &Path) -> Result<TrashedEntry> {
+    let root_canonical = fs::canonicalize(root)?;
+    let source_canonical = fs::canonicalize(source)?;
     if source_canonical == root_canonical {
         return Err(AppError::InvalidOperation(
             "Cannot move the working folder to trash.".to_string(),
@@ -1857,8 +1927,48 @@ pub fn trash_entry(path: String) -> Result<String> {
         destination
     };
 
-    fs::rename(&source, &final_destination)?;
-    Ok(final_destination.to_string_lossy().to_string())
+    let original_path = source_canonical
+        .strip_prefix(&root_canonical)
+        .map(|relative| relative.to_string_lossy().replace('\\', "/"))
+        .unwrap_or_else(|_| file_name.to_string());
+    let is_dir = source.is_dir();
+    let size_bytes = trash::trashed_entry_size(source);
+
+    fs::rename(source, &final_destination)?;
+    // Without a manifest record the entry could not be restored to its folder.
+    if let Err(err) =
+        trash::record_trashed_entry(root, &final_destination, &original_path, size_bytes, is_dir)
+    {
+        fs::rename(&final_destination, source)?;
+        return Err(err);
+    }
+    Ok(TrashedEntry {
+        path: final_destination,
+        size_bytes,
+        is_dir,
+    })
+}
+
+#[tauri::command]
+pub fn trash_entry(path: String) -> Result<String> {
+    let root = active_workspace_root()?;
+    let source = normalize_existing_path(&path)?;
+    ensure_within_root(&root, &source)?;
+
+    let trashed = move_to_trash(&root, &source)?;
+    // The manifest record left behind is dropped the next time the trash is listed.
+    if let Err(err) = operation_journal::record_trash(
+        &root,
+        &source,
+        &trashed.path,
+        trashed.size_bytes,
+        trashed.is_dir,
+    ) {
+        fs::rename(&trashed.path, &source)?;
+        return Err(err);
+    }
+    let _ = trash::purge_expired_entries(&root);
+    Ok(trashed.path.to_string_lossy().to_string())
 }
 
 #[tauri::command]
@@ -1940,6 +2050,7 @@ mod tests {
 
     use calamine::Data;
     use crate::editor_sync::recent_internal_write_for;
+    use crate::note_templates::NoteTemplateRequest;
 
     use super::{
         copy_entry, create_entry, create_extracted_note, duplicate_entry, list_children,
@@ -1977,6 +2088,7 @@ mod tests {
             "note.md".to_string(),
             EntryKind::File,
             ConflictStrategy::Rename,
+            None,
         )
         .expect("create first");
 
@@ -1985,11 +2097,42 @@ mod tests {
             "note.md".to_string(),
             EntryKind::File,
             ConflictStrategy::Rename,
+            None,
         )
         .expect("create second");
 
-        assert!(first.ends_with("note.md"));
-        assert!(second.ends_with("note (1).md"));
+        assert!(first.path.ends_with("note.md"));
+        assert!(second.path.ends_with("note (1).md"));
+        assert_eq!(first.cursor_offset, None);
+        fs::remove_dir_all(dir).expect("cleanup");
+    }
+
+    #[test]
+    fn create_entry_returns_the_template_cursor_offset() {
+        let dir = make_temp_dir();
+        let _guard = activate_workspace(&dir);
+        fs::create_dir_all(dir.join("_templates")).expect("create templates dir");
+        fs::write(
+            dir.join("_templates/_default.md"),
+            "# {{title}}\n\n{{cursor}}\n",
+        )
+        .expect("write default template");
+
+        let created = create_entry(
+            dir.to_string_lossy().to_string(),
+            "meeting.md".to_string(),
+            EntryKind::File,
+            ConflictStrategy::Fail,
+            Some(NoteTemplateRequest {
+                use_folder_default: true,
+                ..Default::default()
+            }),
+        )
+        .expect("create templated note");
+
+        let content = fs::read_to_string(&created.path).expect("read created note");
+        assert_eq!(content, "# meeting\n\n\n");
+        assert_eq!(created.cursor_offset, Some("# meeting\n\n".len()));
         fs::remove_dir_all(dir).expect("cleanup");
     }
 
@@ -2166,6 +2309,51 @@ mod tests {
         fs::remove_dir_all(dir).expect("cleanup");
     }
 
+    #[test]
+    fn trash_entry_puts_the_file_back_when_the_manifest_cannot_be_written() {
+        let dir = make_temp_dir();
+        let _guard = activate_workspace(&dir);
+        let source = dir.join("keep-me.md");
+        fs::write(&source, "keep me").expect("write source");
+        fs::create_dir_all(dir.join(".tomosona").join("trash.json")).expect("block manifest");
+
+        assert!(trash_entry(source.to_string_lossy().to_string()).is_err());
+
+        assert_eq!(fs::read_to_string(&source).expect("read source"), "keep me");
+        let trashed = fs::read_dir(dir.join(".tomosona-trash"))
+            .expect("trash dir")
+            .count();
+        assert_eq!(trashed, 0);
+        fs::remove_dir_all(dir).expect("cleanup");
+    }
+
+    #[test]
+    fn rename_and_copy_are_reverted_when_the_journal_cannot_be_written() {
+        let dir = make_temp_dir();
+        let _guard = activate_workspace(&dir);
+        let source = dir.join("keep-me.md");
+        fs::write(&source, "keep me").expect("write source");
+        fs::create_dir_all(dir.join(".tomosona").join("operation-journal.json"))
+            .expect("block journal");
+
+        assert!(rename_entry(
+            source.to_string_lossy().to_string(),
+            "renamed.md".to_string(),
+            ConflictStrategy::Fail,
+        )
+        .is_err());
+        assert!(source.exists());
+        assert!(!dir.join("renamed.md").exists());
+
+        assert!(duplicate_entry(
+            source.to_string_lossy().to_string(),
+            ConflictStrategy::Rename
+        )
+        .is_err());
+        assert_eq!(fs::read_dir(&dir).expect("read dir").count(), 2);
+        fs::remove_dir_all(dir).expect("cleanup");
+    }
+
     #[test]
     fn list_tree_excludes_internal_files() {
         let dir = make_temp_dir();
@@ -2320,6 +2508,7 @@ mod tests {
             "CON".to_string(),
             EntryKind::File,
             ConflictStrategy::Fail,
+            None,
         );
 
         assert!(result.is_err());
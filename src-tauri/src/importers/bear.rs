@@ -0,0 +1,330 @@
+//! Bear export reader.
+//!
+//! Bear exports each note as a TextBundle: a `Title.textbundle` folder holding
+//! `text.md` (or `text.markdown`), `info.json` and an `assets/` folder. Bundles can
+//! arrive as folders, as a `.textpack` (zipped bundle) or as a zip of bundles. Notes
+//! already link to each other with `[[Title]]` and carry inline `#tags`.
+
+use std::{collections::BTreeMap, fs::File, path::Path};
+
+use serde_json::Value;
+
+use super::{
+    normalize_date, percent_decode, read_dir_entries, read_zip_entries, ImportBatch, ImportSkip,
+    ImportedAttachment, ImportedNote, PropertyValue, ATTACHMENT_LINK_PREFIX,
+};
+use crate::Result;
+
+const BUNDLE_EXTENSION: &str = ".textbundle";
+const TEXT_FILES: [&str; 3] = ["text.md", "text.markdown", "text.txt"];
+const ASSETS_PREFIX: &str = "assets/";
+const BEAR_INFO_KEY: &str = "net.shinyfrog.bear";
+
+#[derive(Default)]
+struct Bundle {
+    text: Option<Vec<u8>>,
+    info: Option<Vec<u8>>,
+    assets: BTreeMap<String, Vec<u8>>,
+}
+
+/// Splits an entry path into `(bundle path, path inside the bundle)`.
+fn split_bundle_path(path: &str) -> (String, String) {
+    let segments = path.split('/').collect::<Vec<_>>();
+    match segments
+        .iter()
+        .position(|segment| segment.to_lowercase().ends_with(BUNDLE_EXTENSION))
+    {
+        Some(index) => (
+            segments[..=index].join("/"),
+            segments[index + 1..].join("/"),
+        ),
+        None => (String::new(), path.to_string()),
+    }
+}
+
+fn group_bundles(entries: Vec<(String, Vec<u8>)>) -> BTreeMap<String, Bundle> {
+    let mut bundles = BTreeMap::<String, Bundle>::new();
+    for (path, bytes) in entries {
+        let (bundle_path, inner) = split_bundle_path(&path);
+        let bundle = bundles.entry(bundle_path).or_default();
+        if TEXT_FILES.contains(&inner.to_lowercase().as_str()) {
+            bundle.text.get_or_insert(bytes);
+        } else if inner.eq_ignore_ascii_case("info.json") {
+            bundle.info = Some(bytes);
+        } else if inner.starts_with(ASSETS_PREFIX) {
+            bundle.assets.insert(inner, bytes);
+        }
+    }
+    bundles
+}
+
+/// Collects Bear `#tags`, including nested `#a/b` and multi-word `#a b#` tags.
+pub(super) fn extract_tags(markdown: &str) -> Vec<String> {
+    let mut tags = Vec::new();
+    let mut in_fence = false;
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        let chars = line.char_indices().collect::<Vec<_>>();
+        let mut index = 0usize;
+        while index < chars.len() {
+            let (offset, ch) = chars[index];
+            let at_boundary = index == 0 || chars[index - 1].1.is_whitespace();
+            let next = chars.get(index + 1).map(|(_, next)| *next);
+            if ch != '#'
+                || !at_boundary
+                || next
+                    .map(|next| next.is_whitespace() || next == '#')
+                    .unwrap_or(true)
+            {
+                index += 1;
+                continue;
+            }
+            let rest = &line[offset + 1..];
+            let simple_end = rest
+                .find(|ch: char| ch.is_whitespace() || ch == '#')
+                .unwrap_or(rest.len());
+            let multi_end = rest.find('#').filter(|end| {
+                *end > simple_end
+                    && !rest[..*end].ends_with(char::is_whitespace)
+                    && rest[*end + 1..]
+                        .chars()
+                        .next()
+                        .map(char::is_whitespace)
+                        .unwrap_or(true)
+            });
+            let (tag, consumed) = match multi_end {
+                Some(end) => (&rest[..end], end + 1),
+                None => (&rest[..simple_end], simple_end),
+            };
+            let tag = tag.trim_end_matches(['.', ',', ';', ':', '!', '?', ')']);
+            if !tag.is_empty() && !tags.iter().any(|known: &String| known == tag) {
+                tags.push(tag.to_string());
+            }
+            let end_offset = offset + 1 + consumed;
+            while index < chars.len() && chars[index].0 < end_offset {
+                index += 1;
+            }
+        }
+    }
+    tags
+}
+
+/// Points `](assets/…)` links at attachments and returns the rewritten body.
+fn convert_asset_links(
+    body: &str,
+    assets: &BTreeMap<String, Vec<u8>>,
+    attachments: &mut Vec<ImportedAttachment>,
+) -> String {
+    let mut output = String::with_capacity(body.len());
+    let mut rest = body;
+    while let Some(start) = rest.find("](") {
+        let target_start = start + 2;
+        let Some(end_rel) = rest[target_start..].find(')') else {
+            break;
+        };
+        let end = target_start + end_rel;
+        let raw_target = rest[target_start..end].trim().trim_matches(['<', '>']);
+        let decoded = percent_decode(raw_target);
+        output.push_str(&rest[..target_start]);
+        match assets.get(&decoded) {
+            Some(bytes) => {
+                let name = decoded[ASSETS_PREFIX.len()..].replace('/', " ");
+                if !attachments.iter().any(|item| item.file_name == name) {
+                    attachments.push(ImportedAttachment {
+                        file_name: name.clone(),
+                        bytes: bytes.clone(),
+                    });
+                }
+                output.push_str(&format!("{ATTACHMENT_LINK_PREFIX}{name})"));
+            }
+            None => output.push_str(&rest[target_start..=end]),
+        }
+        rest = &rest[end + 1..];
+    }
+    output.push_str(rest);
+    output
+}
+
+fn info_dates(info: &[u8]) -> Vec<(String, PropertyValue)> {
+    let Ok(value) = serde_json::from_slice::<Value>(info) else {
+        return Vec::new();
+    };
+    let bear = value.get(BEAR_INFO_KEY).unwrap_or(&value);
+    [("created", "creationDate"), ("updated", "modificationDate")]
+        .into_iter()
+        .filter_map(|(key, field)| {
+            bear.get(field)
+                .and_then(Value::as_str)
+                .and_then(normalize_date)
+                .map(|date| (key.to_string(), PropertyValue::Date(date)))
+        })
+        .collect()
+}
+
+fn bundle_title(bundle_path: &str, fallback: &str) -> String {
+    let name = bundle_path.rsplit('/').next().unwrap_or_default();
+    let stem = name
+        .get(..name.len().saturating_sub(BUNDLE_EXTENSION.len()))
+        .unwrap_or_default();
+    if stem.is_empty() {
+        fallback.to_string()
+    } else {
+        stem.to_string()
+    }
+}
+
+fn convert_bundle(
+    bundle_path: &str,
+    bundle: Bundle,
+    fallback_title: &str,
+) -> std::result::Result<ImportedNote, ImportSkip> {
+    let source = if bundle_path.is_empty() {
+        fallback_title.to_string()
+    } else {
+        bundle_path.to_string()
+    };
+    let Some(text) = bundle.text else {
+        return Err(ImportSkip {
+            source,
+            reason: "Bundle has no text.md file.".to_string(),
+        });
+    };
+    let Ok(markdown) = String::from_utf8(text) else {
+        return Err(ImportSkip {
+            source,
+            reason: "Note is not valid UTF-8.".to_string(),
+        });
+    };
+    let trimmed = markdown.trim_start_matches('\u{feff}').trim_start();
+    let (title, body) = match trimmed.split_once('\n').unwrap_or((trimmed, "")) {
+        (first, rest) if first.starts_with("# ") => (first[2..].trim().to_string(), rest),
+        _ => (bundle_title(bundle_path, fallback_title), trimmed),
+    };
+
+    let mut properties = bundle.info.as_deref().map(info_dates).unwrap_or_default();
+    let tags = extract_tags(body);
+    if !tags.is_empty() {
+        properties.push(("tags".to_string(), PropertyValue::List(tags)));
+    }
+    let mut attachments = Vec::new();
+    let body = convert_asset_links(body, &bundle.assets, &mut attachments);
+    let folder = bundle_path
+        .rsplit_once('/')
+        .map(|(dir, _)| dir.to_string())
+        .unwrap_or_default();
+    let mut link_keys = vec![title.clone()];
+    let bundle_name = bundle_title(bundle_path, fallback_title);
+    if bundle_name != title {
+        link_keys.push(bundle_name);
+    }
+    Ok(ImportedNote {
+        folder,
+        title,
+        body: body.trim().to_string(),
+        properties,
+        attachments,
+        link_keys,
+    })
+}
+
+/// Reads Bear TextBundles from a folder, a single bundle, a `.textpack` or a zip.
+pub(super) fn read_bear_export(source: &Path) -> Result<ImportBatch> {
+    let entries = if source.is_dir() {
+        read_dir_entries(source)?
+    } else {
+        read_zip_entries(File::open(source)?)?
+    };
+    let fallback_title = source
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| "Untitled".to_string());
+
+    let mut batch = ImportBatch::default();
+    for (bundle_path, bundle) in group_bundles(entries) {
+        if bundle_path.is_empty() && bundle.text.is_none() {
+            continue;
+        }
+        match convert_bundle(&bundle_path, bundle, &fallback_title) {
+            Ok(note) => batch.notes.push(note),
+            Err(skip) => batch.skipped.push(skip),
+        }
+    }
+    Ok(batch)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    use super::*;
+
+    #[test]
+    fn extracts_bear_tags() {
+        let markdown = "Intro #work and #project/alpha.\n# Heading\n#multi word tag# end\n```\n#not-a-tag\n```\nissue #12x";
+        assert_eq!(
+            extract_tags(markdown),
+            vec!["work", "project/alpha", "multi word tag", "12x"]
+        );
+    }
+
+    #[test]
+    fn reads_textbundle_folders() {
+        let nonce = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|value| value.as_nanos())
+            .unwrap_or(0);
+        let export = std::env::temp_dir().join(format!("tomosona-bear-export-{nonce}"));
+        let bundle = export.join("Trip.textbundle");
+        fs::create_dir_all(bundle.join("assets")).expect("create bundle");
+        fs::write(
+            bundle.join("text.md"),
+            "# Trip plan\n\nSee [[Packing list]] #travel\n\n![](assets/map%201.png)\n",
+        )
+        .expect("write text");
+        fs::write(
+            bundle.join("info.json"),
+            r#"{"version":2,"net.shinyfrog.bear":{"creationDate":"2024-02-03T10:00:00Z"}}"#,
+        )
+        .expect("write info");
+        fs::write(bundle.join("assets/map 1.png"), [1u8, 2]).expect("write asset");
+        fs::create_dir_all(export.join("Empty.textbundle")).expect("create empty bundle");
+        fs::write(export.join("Empty.textbundle/info.json"), "{}").expect("write empty info");
+
+        let batch = read_bear_export(&export).expect("read export");
+        assert_eq!(batch.notes.len(), 1);
+        assert_eq!(batch.skipped.len(), 1);
+        let note = &batch.notes[0];
+        assert_eq!(note.title, "Trip plan");
+        assert_eq!(note.folder, "");
+        assert_eq!(
+            note.body,
+            "See [[Packing list]] #travel\n\n![](attachment:map 1.png)"
+        );
+        assert_eq!(note.attachments[0].file_name, "map 1.png");
+        assert_eq!(
+            note.properties,
+            vec![
+                (
+                    "created".to_string(),
+                    PropertyValue::Date("2024-02-03".to_string())
+                ),
+                (
+                    "tags".to_string(),
+                    PropertyValue::List(vec!["travel".to_string()])
+                ),
+            ]
+        );
+
+        fs::remove_dir_all(export).expect("cleanup export");
+    }
+}
@@ -0,0 +1,785 @@
+//! Evernote `.enex` export reader.
+//!
+//! ENEX is XML with one `<note>` per note; its body is ENML (XHTML) inside a CDATA
+//! section and attachments are base64 `<resource>` elements referenced from the body
+//! by the MD5 hash of their data (`<en-media hash="…">`). The export structure is
+//! read with `quick-xml`; ENML bodies go through a lenient tag scanner because notes
+//! clipped from the web often carry HTML that is not well-formed XML.
+
+use std::{fs, path::Path};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use md5::{Digest, Md5};
+use quick_xml::{
+    escape::resolve_predefined_entity,
+    events::{BytesRef, Event},
+    Reader,
+};
+
+use super::{
+    normalize_date, ImportBatch, ImportSkip, ImportedAttachment, ImportedNote, PropertyValue,
+    ATTACHMENT_LINK_PREFIX,
+};
+use crate::{AppError, Result};
+
+const ENEX_EXTENSION: &str = "enex";
+
+/// Decodes the XML/HTML entities that appear in ENML.
+fn decode_entities(value: &str) -> String {
+    let mut output = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find('&') {
+        output.push_str(&rest[..start]);
+        let tail = &rest[start..];
+        let Some(end) = tail.find(';').filter(|end| *end <= 10) else {
+            output.push('&');
+            rest = &tail[1..];
+            continue;
+        };
+        let entity = &tail[1..end];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            _ => entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(ch) => {
+                output.push(ch);
+                rest = &tail[end + 1..];
+            }
+            None => {
+                output.push('&');
+                rest = &tail[1..];
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let mut rest = tag;
+    while let Some(index) = rest.find(name) {
+        let before_ok = index == 0
+            || rest[..index]
+                .chars()
+                .last()
+                .map(char::is_whitespace)
+                .unwrap_or(true);
+        let after = rest[index + name.len()..].trim_start();
+        if before_ok {
+            if let Some(value) = after.strip_prefix('=') {
+                let value = value.trim_start();
+                let quote = value.chars().next()?;
+                if quote == '"' || quote == '\'' {
+                    let inner = &value[1..];
+                    let end = inner.find(quote)?;
+                    return Some(decode_entities(&inner[..end]));
+                }
+            }
+        }
+        rest = &rest[index + name.len()..];
+    }
+    None
+}
+
+/// Attachment referenced from ENML, already given its final file name.
+pub(super) struct MediaResource {
+    pub hash: String,
+    pub mime: String,
+    pub file_name: String,
+}
+
+#[derive(Default)]
+struct EnmlWriter {
+    output: String,
+    quote_depth: usize,
+    lists: Vec<Option<usize>>,
+    pre_depth: usize,
+    code_divs: Vec<bool>,
+    links: Vec<(usize, Option<String>)>,
+    table_row: Option<usize>,
+    table_cells: usize,
+    in_cell: bool,
+    media_index: usize,
+}
+
+impl EnmlWriter {
+    fn at_line_start(&self) -> bool {
+        self.output.is_empty() || self.output.ends_with('\n')
+    }
+
+    fn push_raw(&mut self, text: &str) {
+        if self.at_line_start() && self.quote_depth > 0 && !text.is_empty() {
+            self.output.push_str(&"> ".repeat(self.quote_depth));
+        }
+        self.output.push_str(text);
+    }
+
+    fn trim_trailing_spaces(&mut self) {
+        let trimmed = self.output.trim_end_matches([' ', '\t']).len();
+        self.output.truncate(trimmed);
+    }
+
+    fn newline(&mut self) {
+        if self.in_cell {
+            self.push_raw(" ");
+            return;
+        }
+        self.trim_trailing_spaces();
+        if !self.at_line_start() {
+            self.output.push('\n');
+        }
+    }
+
+    fn blank_line(&mut self) {
+        if self.in_cell {
+            self.push_raw(" ");
+            return;
+        }
+        self.newline();
+        if !self.output.is_empty() && !self.output.ends_with("\n\n") {
+            if self.quote_depth > 0 {
+                self.output.push('>');
+            }
+            self.output.push('\n');
+        }
+    }
+
+    fn push_text(&mut self, text: &str) {
+        if self.pre_depth > 0 {
+            self.push_raw(text);
+            return;
+        }
+        let mut collapsed = String::with_capacity(text.len());
+        let mut last_space = self.at_line_start() || self.output.ends_with(' ');
+        for ch in text.chars() {
+            if ch.is_whitespace() {
+                if !last_space {
+                    collapsed.push(' ');
+                }
+                last_space = true;
+            } else {
+                collapsed.push(ch);
+                last_space = false;
+            }
+        }
+        self.push_raw(&collapsed);
+    }
+
+    fn open_list_item(&mut self) {
+        self.newline();
+        let depth = self.lists.len().saturating_sub(1);
+        let marker = match self.lists.last_mut() {
+            Some(Some(counter)) => {
+                *counter += 1;
+                format!("{counter}. ")
+            }
+            _ => "- ".to_string(),
+        };
+        self.push_raw(&format!("{}{marker}", "  ".repeat(depth)));
+    }
+
+    fn close_link(&mut self) {
+        let Some((start, href)) = self.links.pop() else {
+            return;
+        };
+        let label = self.output[start..].trim().to_string();
+        self.output.truncate(start);
+        match href {
+            Some(href) if href.starts_with("evernote:") && !label.is_empty() => {
+                self.push_raw(&format!("[[{label}|{label}]]"));
+            }
+            Some(href) if !label.is_empty() => self.push_raw(&format!("[{label}]({href})")),
+            Some(href) => self.push_raw(&format!("<{href}>")),
+            None => self.push_raw(&label),
+        }
+    }
+
+    fn handle_tag(&mut self, raw_tag: &str, resources: &[MediaResource]) {
+        let closing = raw_tag.starts_with('/');
+        let body = raw_tag.trim_start_matches('/').trim_end_matches('/');
+        let name = body
+            .split(|ch: char| ch.is_whitespace())
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let heading_level = name
+            .strip_prefix('h')
+            .and_then(|level| level.parse::<usize>().ok())
+            .filter(|level| (1..=6).contains(level));
+        if let Some(level) = heading_level {
+            self.blank_line();
+            if !closing {
+                self.push_raw(&format!("{} ", "#".repeat(level)));
+            }
+            return;
+        }
+        match (name.as_str(), closing) {
+            ("p" | "section" | "article", _) => self.blank_line(),
+            ("div", false) => {
+                let is_code = attribute(body, "style")
+                    .map(|style| style.contains("-en-codeblock"))
+                    .unwrap_or(false);
+                self.code_divs.push(is_code);
+                if is_code {
+                    self.blank_line();
+                    self.push_raw("```\n");
+                    self.pre_depth += 1;
+                } else {
+                    self.newline();
+                }
+            }
+            ("div", true) => {
+                if self.code_divs.pop().unwrap_or(false) {
+                    self.pre_depth = self.pre_depth.saturating_sub(1);
+                    self.newline();
+                    self.push_raw("```");
+                    self.blank_line();
+                } else if self.pre_depth > 0 {
+                    self.output.push('\n');
+                } else {
+                    self.newline();
+                }
+            }
+            ("br", _) => {
+                if self.pre_depth > 0 {
+                    self.output.push('\n');
+                } else {
+                    self.newline();
+                }
+            }
+            ("hr", _) => {
+                self.blank_line();
+                self.push_raw("---");
+                self.blank_line();
+            }
+            ("b" | "strong", _) => self.push_raw("**"),
+            ("i" | "em", _) => self.push_raw("*"),
+            ("s" | "strike" | "del", _) => self.push_raw("~~"),
+            ("code", _) if self.pre_depth == 0 => self.push_raw("`"),
+            ("pre", false) => {
+                self.blank_line();
+                self.push_raw("```\n");
+                self.pre_depth += 1;
+            }
+            ("pre", true) => {
+                self.pre_depth = self.pre_depth.saturating_sub(1);
+                self.newline();
+                self.push_raw("```");
+                self.blank_line();
+            }
+            ("blockquote", false) => {
+                self.blank_line();
+                self.quote_depth += 1;
+            }
+            ("blockquote", true) => {
+                self.quote_depth = self.quote_depth.saturating_sub(1);
+                self.blank_line();
+            }
+            ("ul", false) | ("ol", false) => {
+                if self.lists.is_empty() {
+                    self.blank_line();
+                }
+                self.lists.push((name == "ol").then_some(0));
+            }
+            ("ul", true) | ("ol", true) => {
+                self.lists.pop();
+                if self.lists.is_empty() {
+                    self.blank_line();
+                }
+            }
+            ("li", false) => self.open_list_item(),
+            ("li", true) => self.newline(),
+            ("a", false) => {
+                let href = attribute(body, "href");
+                self.links.push((self.output.len(), href));
+            }
+            ("a", true) => self.close_link(),
+            ("en-todo", false) => {
+                let checked = attribute(body, "checked")
+                    .map(|value| value == "true")
+                    .unwrap_or(false);
+                if self.lists.is_empty() && self.at_line_start() {
+                    self.push_raw("- ");
+                }
+                self.push_raw(if checked { "[x] " } else { "[ ] " });
+            }
+            ("en-media", false) => {
+                let hash = attribute(body, "hash").unwrap_or_default().to_lowercase();
+                let resource = resources
+                    .iter()
+                    .find(|resource| resource.hash == hash)
+                    .or_else(|| resources.get(self.media_index));
+                self.media_index += 1;
+                if let Some(resource) = resource {
+                    let name = &resource.file_name;
+                    if resource.mime.starts_with("image/") {
+                        self.push_raw(&format!("![{name}]({ATTACHMENT_LINK_PREFIX}{name})"));
+                    } else {
+                        self.push_raw(&format!("[{name}]({ATTACHMENT_LINK_PREFIX}{name})"));
+                    }
+                }
+            }
+            ("img", false) => {
+                if let Some(src) = attribute(body, "src").filter(|src| src.contains("://")) {
+                    let alt = attribute(body, "alt").unwrap_or_default();
+                    self.push_raw(&format!("![{alt}]({src})"));
+                }
+            }
+            ("table", false) => {
+                self.blank_line();
+                self.table_row = Some(0);
+            }
+            ("table", true) => {
+                self.table_row = None;
+                self.blank_line();
+            }
+            ("tr", false) => {
+                self.newline();
+                self.table_cells = 0;
+            }
+            ("tr", true) => {
+                if self.table_cells > 0 {
+                    self.push_raw(" |");
+                }
+                self.newline();
+                if self.table_row == Some(0) && self.table_cells > 0 {
+                    let separator = vec!["---"; self.table_cells].join(" | ");
+                    self.push_raw(&format!("| {separator} |"));
+                    self.newline();
+                }
+                self.table_row = self.table_row.map(|row| row + 1);
+            }
+            ("td" | "th", false) => {
+                self.push_raw(if self.table_cells == 0 { "| " } else { " | " });
+                self.table_cells += 1;
+                self.in_cell = true;
+            }
+            ("td" | "th", true) => {
+                self.trim_trailing_spaces();
+                self.in_cell = false;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Converts an ENML document to Markdown, resolving `<en-media>` against `resources`.
+pub(super) fn enml_to_markdown(enml: &str, resources: &[MediaResource]) -> String {
+    let mut writer = EnmlWriter::default();
+    let mut rest = enml;
+    while let Some(start) = rest.find('<') {
+        if start > 0 {
+            writer.push_text(&decode_entities(&rest[..start]));
+        }
+        let tail = &rest[start..];
+        if let Some(comment) = tail.strip_prefix("<!--") {
+            rest = comment
+                .find("-->")
+                .map(|end| &comment[end + 3..])
+                .unwrap_or("");
+            continue;
+        }
+        let Some(end) = tail.find('>') else {
+            rest = "";
+            break;
+        };
+        let raw_tag = &tail[1..end];
+        if !raw_tag.starts_with(['?', '!']) {
+            writer.handle_tag(raw_tag, resources);
+        }
+        rest = &tail[end + 1..];
+    }
+    if !rest.is_empty() {
+        writer.push_text(&decode_entities(rest));
+    }
+    let markdown = writer
+        .output
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut collapsed = String::with_capacity(markdown.len());
+    for line in markdown.split('\n') {
+        if line.is_empty() && (collapsed.is_empty() || collapsed.ends_with("\n\n")) {
+            continue;
+        }
+        collapsed.push_str(line);
+        collapsed.push('\n');
+    }
+    collapsed.trim().to_string()
+}
+
+/// MD5 digest as lowercase hex; ENML references resources by this hash.
+fn md5_hex(data: &[u8]) -> String {
+    Md5::digest(data)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+fn extension_for_mime(mime: &str) -> &str {
+    match mime {
+        "image/jpeg" => "jpg",
+        "image/svg+xml" => "svg",
+        "application/pdf" => "pdf",
+        "text/plain" => "txt",
+        other => other
+            .rsplit('/')
+            .next()
+            .filter(|ext| ext.chars().all(|ch| ch.is_ascii_alphanumeric()))
+            .unwrap_or("bin"),
+    }
+}
+
+/// `<resource>` of a note, with its base64 data still encoded.
+#[derive(Default)]
+struct EnexResource {
+    data: String,
+    mime: String,
+    file_name: String,
+}
+
+/// Fields of one `<note>`; empty strings stand for missing elements.
+#[derive(Default)]
+struct EnexNote {
+    title: String,
+    content: Option<String>,
+    created: String,
+    updated: String,
+    tags: Vec<String>,
+    source_url: String,
+    author: String,
+    resources: Vec<EnexResource>,
+}
+
+/// Appends the text of an `&…;` reference read by `quick-xml`; unknown entities are kept
+/// as written.
+pub(super) fn push_entity(
+    text: &mut String,
+    entity: &BytesRef<'_>,
+) -> std::result::Result<(), quick_xml::Error> {
+    if let Some(ch) = entity.resolve_char_ref()? {
+        text.push(ch);
+        return Ok(());
+    }
+    let name = entity.decode()?;
+    match resolve_predefined_entity(&name) {
+        Some(value) => text.push_str(value),
+        None => text.push_str(&format!("&{name};")),
+    }
+    Ok(())
+}
+
+/// Reads the notes of an ENEX document.
+fn read_notes(xml: &str) -> std::result::Result<Vec<EnexNote>, quick_xml::Error> {
+    let mut reader = Reader::from_str(xml);
+    let mut notes: Vec<EnexNote> = Vec::new();
+    let mut path: Vec<String> = Vec::new();
+    let mut text = String::new();
+    loop {
+        match reader.read_event()? {
+            Event::Start(tag) => {
+                let name = String::from_utf8_lossy(tag.name().as_ref()).into_owned();
+                match (path.last().map(String::as_str), name.as_str()) {
+                    (_, "note") => notes.push(EnexNote::default()),
+                    (Some("note"), "resource") => {
+                        if let Some(note) = notes.last_mut() {
+                            note.resources.push(EnexResource::default());
+                        }
+                    }
+                    _ => {}
+                }
+                path.push(name);
+                text.clear();
+            }
+            Event::Text(value) => text.push_str(&value.decode()?),
+            Event::CData(value) => text.push_str(&value.decode()?),
+            Event::GeneralRef(entity) => push_entity(&mut text, &entity)?,
+            Event::End(_) => {
+                let name = path.pop().unwrap_or_default();
+                let parent = path.last().map(String::as_str);
+                let value = std::mem::take(&mut text);
+                let Some(note) = notes.last_mut() else {
+                    continue;
+                };
+                match (parent, name.as_str()) {
+                    (Some("note"), "title") => note.title = value.trim().to_string(),
+                    (Some("note"), "content") => note.content = Some(value),
+                    (Some("note"), "created") => note.created = value.trim().to_string(),
+                    (Some("note"), "updated") => note.updated = value.trim().to_string(),
+                    (Some("note"), "tag") => note.tags.push(value.trim().to_string()),
+                    (Some("note-attributes"), "source-url") => {
+                        note.source_url = value.trim().to_string();
+                    }
+                    (Some("note-attributes"), "author") => note.author = value.trim().to_string(),
+                    _ => {
+                        let Some(resource) = note.resources.last_mut() else {
+                            continue;
+                        };
+                        match (parent, name.as_str()) {
+                            (Some("resource"), "data") => resource.data = value,
+                            (Some("resource"), "mime") => resource.mime = value.trim().to_string(),
+                            (Some("resource-attributes"), "file-name") => {
+                                resource.file_name = value.trim().to_string();
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(notes)
+}
+
+fn read_resources(
+    note: &EnexNote,
+    title: &str,
+    warnings: &mut Vec<String>,
+) -> (Vec<MediaResource>, Vec<ImportedAttachment>) {
+    let mut resources = Vec::new();
+    let mut attachments: Vec<ImportedAttachment> = Vec::new();
+    for (index, resource) in note.resources.iter().enumerate() {
+        let encoded = resource
+            .data
+            .chars()
+            .filter(|ch| !ch.is_whitespace())
+            .collect::<String>();
+        let Ok(bytes) = STANDARD.decode(encoded.as_bytes()) else {
+            warnings.push(format!(
+                "{title}: attachment {} could not be decoded",
+                index + 1
+            ));
+            continue;
+        };
+        let mime = resource.mime.clone();
+        let mut file_name = if resource.file_name.is_empty() {
+            format!("attachment-{}.{}", index + 1, extension_for_mime(&mime))
+        } else {
+            resource.file_name.clone()
+        };
+        if attachments.iter().any(|item| item.file_name == file_name) {
+            let (stem, ext) = file_name
+                .rsplit_once('.')
+                .unwrap_or((file_name.as_str(), ""));
+            file_name = format!("{stem} ({}).{ext}", index + 1);
+        }
+        resources.push(MediaResource {
+            hash: md5_hex(&bytes),
+            mime,
+            file_name: file_name.clone(),
+        });
+        attachments.push(ImportedAttachment { file_name, bytes });
+    }
+    (resources, attachments)
+}
+
+fn convert_note(note: EnexNote, folder: &str, batch: &mut ImportBatch, source: &str) {
+    let title = if note.title.is_empty() {
+        "Untitled".to_string()
+    } else {
+        note.title.clone()
+    };
+    let Some(content) = note.content.as_deref() else {
+        batch.skipped.push(ImportSkip {
+            source: format!("{source}: {title}"),
+            reason: "Note has no content.".to_string(),
+        });
+        return;
+    };
+    let (resources, attachments) = read_resources(&note, &title, &mut batch.warnings);
+    let body = enml_to_markdown(content, &resources);
+
+    let mut properties = Vec::new();
+    for (key, value) in [("created", &note.created), ("updated", &note.updated)] {
+        if let Some(date) = normalize_date(value) {
+            properties.push((key.to_string(), PropertyValue::Date(date)));
+        }
+    }
+    let tags = note
+        .tags
+        .into_iter()
+        .filter(|tag| !tag.is_empty())
+        .collect::<Vec<_>>();
+    if !tags.is_empty() {
+        properties.push(("tags".to_string(), PropertyValue::List(tags)));
+    }
+    for (key, value) in [("source", note.source_url), ("author", note.author)] {
+        if !value.is_empty() {
+            properties.push((key.to_string(), PropertyValue::Text(value)));
+        }
+    }
+
+    batch.notes.push(ImportedNote {
+        folder: folder.to_string(),
+        title: title.clone(),
+        body,
+        properties,
+        attachments,
+        link_keys: vec![title],
+    });
+}
+
+fn read_enex_file(path: &Path, folder: &str, batch: &mut ImportBatch) -> Result<()> {
+    let source = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let bytes = fs::read(path)?;
+    let Ok(xml) = String::from_utf8(bytes) else {
+        batch.skipped.push(ImportSkip {
+            source,
+            reason: "Export is not valid UTF-8.".to_string(),
+        });
+        return Ok(());
+    };
+    if !xml.contains("<en-export") {
+        batch.skipped.push(ImportSkip {
+            source,
+            reason: "Not an Evernote export.".to_string(),
+        });
+        return Ok(());
+    }
+    match read_notes(&xml) {
+        Ok(notes) => {
+            for note in notes {
+                convert_note(note, folder, batch, &source);
+            }
+        }
+        Err(err) => batch.skipped.push(ImportSkip {
+            source,
+            reason: format!("Export is not valid XML: {err}"),
+        }),
+    }
+    Ok(())
+}
+
+/// Reads one `.enex` file, or every `.enex` file of a folder (one subfolder per notebook).
+pub(super) fn read_enex_export(source: &Path) -> Result<ImportBatch> {
+    let mut batch = ImportBatch::default();
+    if source.is_file() {
+        read_enex_file(source, "", &mut batch)?;
+        return Ok(batch);
+    }
+    let mut files = fs::read_dir(source)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .map(|ext| ext.eq_ignore_ascii_case(ENEX_EXTENSION))
+                .unwrap_or(false)
+        })
+        .collect::<Vec<_>>();
+    if files.is_empty() {
+        return Err(AppError::InvalidOperation(
+            "No .enex file found in the selected folder.".to_string(),
+        ));
+    }
+    files.sort();
+    for path in files {
+        let notebook = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        read_enex_file(&path, &notebook, &mut batch)?;
+    }
+    Ok(batch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_md5_digests() {
+        assert_eq!(md5_hex(b""), "d41d8cd98f00b204e9800998ecf8427e");
+        assert_eq!(
+            md5_hex(b"The quick brown fox jumps over the lazy dog"),
+            "9e107d9d372bb6826bd81d3542a419d6"
+        );
+    }
+
+    #[test]
+    fn converts_enml_to_markdown() {
+        let resources = vec![MediaResource {
+            hash: "abc".to_string(),
+            mime: "image/png".to_string(),
+            file_name: "chart.png".to_string(),
+        }];
+        let enml = concat!(
+            "<?xml version=\"1.0\"?><!DOCTYPE en-note SYSTEM \"x\"><en-note>",
+            "<h1>Plan</h1><div>Hello <b>bold</b> &amp; <i>soft</i></div>",
+            "<ul><li>one</li><li><en-todo checked=\"true\"/>done</li></ul>",
+            "<div><a href=\"https://example.com\">site</a> and ",
+            "<a href=\"evernote:///view/1/s1/abc/abc/\">Other note</a></div>",
+            "<div><en-media hash=\"ABC\" type=\"image/png\"/></div>",
+            "<table><tr><th>A</th><th>B</th></tr><tr><td>1</td><td>2</td></tr></table>",
+            "</en-note>"
+        );
+        assert_eq!(
+            enml_to_markdown(enml, &resources),
+            "# Plan\n\nHello **bold** & *soft*\n\n- one\n- [x] done\n\n[site](https://example.com) and [[Other note|Other note]]\n![chart.png](attachment:chart.png)\n\n| A | B |\n| --- | --- |\n| 1 | 2 |"
+        );
+    }
+
+    #[test]
+    fn reads_notes_with_metadata_and_resources() {
+        let data = STANDARD.encode(b"png-bytes");
+        let hash = md5_hex(b"png-bytes");
+        let xml = format!(
+            "<?xml version=\"1.0\"?><en-export><note><title>Trip &amp; plan</title>\
+             <content><![CDATA[<en-note><div>See <en-media hash=\"{hash}\" type=\"image/png\"/></div></en-note>]]></content>\
+             <created>20240105T101500Z</created><tag>travel</tag><tag>2024</tag>\
+             <note-attributes><source-url>https://example.com/a</source-url></note-attributes>\
+             <resource><data encoding=\"base64\">{data}</data><mime>image/png</mime>\
+             <resource-attributes><file-name>map.png</file-name></resource-attributes></resource>\
+             </note></en-export>"
+        );
+        let mut batch = ImportBatch::default();
+        for note in read_notes(&xml).unwrap() {
+            convert_note(note, "", &mut batch, "test.enex");
+        }
+        let note = &batch.notes[0];
+        assert_eq!(note.title, "Trip & plan");
+        assert_eq!(note.body, "See ![map.png](attachment:map.png)");
+        assert_eq!(note.attachments[0].bytes, b"png-bytes".to_vec());
+        assert_eq!(
+            note.properties,
+            vec![
+                (
+                    "created".to_string(),
+                    PropertyValue::Date("2024-01-05".to_string())
+                ),
+                (
+                    "tags".to_string(),
+                    PropertyValue::List(vec!["travel".to_string(), "2024".to_string()])
+                ),
+                (
+                    "source".to_string(),
+                    PropertyValue::Text("https://example.com/a".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_exports_that_are_not_well_formed() {
+        assert!(read_notes("<en-export><note><title>A</note></en-export>").is_err());
+        let notes =
+            read_notes("<en-export><note><title>A &#x26; B</title></note></en-export>").unwrap();
+        assert_eq!(notes[0].title, "A & B");
+        assert!(notes[0].content.is_none());
+    }
+}
@@ -0,0 +1,904 @@
+//! Importers that bring notes from other tools into a workspace subfolder.
+//!
+//! Each source module turns its export format into [`ImportedNote`] values:
+//! - `notion`: Markdown + CSV export zip;
+//! - `evernote`: `.enex` XML export;
+//! - `bear`: `.textbundle` folders or a `.textpack` archive;
+//! - `word`: Word `.docx` documents, also importable one at a time next to
+//!   the source file.
+//!
+//! This module owns everything shared: note placement with `fs_ops` conflict
+//! strategies, internal link conversion to `[[wikilinks]]`, frontmatter typed with
+//! the workspace property type schema, attachment copies and the import report.
+
+mod bear;
+mod evernote;
+mod notion;
+mod word;
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    io::{Read, Seek, Write},
+    path::{Component, Path, PathBuf},
+};
+
+use atomicwrites::{AllowOverwrite, AtomicFile};
+use serde::{Deserialize, Serialize};
+use zip::ZipArchive;
+
+use crate::editor_sync::record_workspace_mutation_write;
+use crate::fs_ops::{resolve_destination, ConflictStrategy};
+use crate::search_index::{read_property_type_schema, write_property_type_schema};
+use crate::workspace_paths::{relative_from, split_wikilink_target_suffix};
+use crate::{
+    active_workspace_root, ensure_within_root, normalize_key_text, normalize_workspace_path,
+    note_link_target, reindex_markdown_file_now_sync, AppError, Result,
+};
+
+const ATTACHMENTS_DIR_NAME: &str = "attachments";
+const MAX_FILE_STEM_CHARS: usize = 120;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportSource {
+    Notion,
+    Evernote,
+    Bear,
+    Word,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ImportNotesPayload {
+    pub source: ImportSource,
+    /// Export file or folder on disk, outside or inside the workspace.
+    pub source_path: String,
+    /// Workspace-relative folder receiving the notes; created when missing.
+    pub target_folder: String,
+    pub conflict_strategy: ConflictStrategy,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportSkip {
+    pub source: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportReport {
+    pub source: ImportSource,
+    pub target_folder: String,
+    pub created_notes: Vec<String>,
+    pub copied_attachments: usize,
+    pub converted_links: usize,
+    pub unresolved_links: usize,
+    /// Property keys added to the workspace property type schema.
+    pub new_property_types: Vec<String>,
+    pub skipped: Vec<ImportSkip>,
+    pub warnings: Vec<String>,
+}
+
+/// Frontmatter value before it is typed against the property schema.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum PropertyValue {
+    Text(String),
+    List(Vec<String>),
+    Number(f64),
+    Checkbox(bool),
+    Date(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ImportedAttachment {
+    pub file_name: String,
+    pub bytes: Vec<u8>,
+}
+
+/// One note produced by a source importer.
+///
+/// `body` may link to other notes of the same import as `[[key|label]]`, where `key`
+/// is one of their `link_keys`; links to attachments use `attachment:<file_name>`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ImportedNote {
+    pub folder: String,
+    pub title: String,
+    pub body: String,
+    pub properties: Vec<(String, PropertyValue)>,
+    pub attachments: Vec<ImportedAttachment>,
+    pub link_keys: Vec<String>,
+}
+
+/// Output of a source importer: notes plus entries it could not convert.
+#[derive(Debug, Default)]
+pub(crate) struct ImportBatch {
+    pub notes: Vec<ImportedNote>,
+    pub skipped: Vec<ImportSkip>,
+    pub warnings: Vec<String>,
+}
+
+pub(crate) const ATTACHMENT_LINK_PREFIX: &str = "attachment:";
+
+/// Reads every file entry of a zip archive as `(path, bytes)`, directories excluded.
+pub(crate) fn read_zip_entries<R: Read + Seek>(reader: R) -> Result<Vec<(String, Vec<u8>)>> {
+    let mut archive = ZipArchive::new(reader)
+        .map_err(|err| AppError::InvalidOperation(format!("Import archive open failed: {err}")))?;
+    let mut entries = Vec::new();
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index).map_err(|err| {
+            AppError::InvalidOperation(format!("Import archive entry read failed: {err}"))
+        })?;
+        let name = entry.name().replace('\\', "/");
+        // Skip folders and metadata added by archivers (`__MACOSX/`, `.DS_Store`).
+        if entry.is_dir()
+            || name
+                .split('/')
+                .any(|segment| segment.starts_with('.') || segment == "__MACOSX")
+        {
+            continue;
+        }
+        let mut bytes = Vec::new();
+        entry.read_to_end(&mut bytes)?;
+        entries.push((name, bytes));
+    }
+    Ok(entries)
+}
+
+/// Reads every file below `dir` as `(relative path, bytes)`, skipping hidden entries.
+pub(crate) fn read_dir_entries(dir: &Path) -> Result<Vec<(String, Vec<u8>)>> {
+    let mut entries = Vec::new();
+    let mut stack = vec![dir.to_path_buf()];
+    while let Some(current) = stack.pop() {
+        for entry in fs::read_dir(&current)? {
+            let path = entry?.path();
+            let hidden = path
+                .file_name()
+                .map(|name| name.to_string_lossy().starts_with('.'))
+                .unwrap_or(true);
+            if hidden {
+                continue;
+            }
+            if path.is_dir() {
+                stack.push(path);
+            } else if let Ok(relative) = path.strip_prefix(dir) {
+                let name = relative.to_string_lossy().replace('\\', "/");
+                entries.push((name, fs::read(&path)?));
+            }
+        }
+    }
+    entries.sort_by(|left, right| left.0.cmp(&right.0));
+    Ok(entries)
+}
+
+/// Decodes `%XX` escapes used by exported relative links.
+pub(crate) fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0usize;
+    while index < bytes.len() {
+        if bytes[index] == b'%' {
+            if let Some(byte) = value
+                .get(index + 1..index + 3)
+                .filter(|hex| hex.chars().all(|ch| ch.is_ascii_hexdigit()))
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                decoded.push(byte);
+                index += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[index]);
+        index += 1;
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+/// Makes a title safe to use as a file name on every platform.
+pub(crate) fn sanitize_file_stem(title: &str) -> String {
+    let cleaned = title
+        .chars()
+        .map(|ch| match ch {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' | '#' | '^' | '[' | ']' => ' ',
+            ch if ch.is_control() => ' ',
+            ch => ch,
+        })
+        .collect::<String>();
+    let collapsed = cleaned.split_whitespace().collect::<Vec<_>>().join(" ");
+    let trimmed = collapsed.trim_matches('.').trim();
+    let stem = trimmed
+        .chars()
+        .take(MAX_FILE_STEM_CHARS)
+        .collect::<String>();
+    if stem.is_empty() {
+        "Untitled".to_string()
+    } else {
+        stem
+    }
+}
+
+/// Normalizes a folder path coming from an export, dropping unsafe segments.
+pub(crate) fn sanitize_folder(folder: &str) -> String {
+    folder
+        .split(['/', '\\'])
+        .map(str::trim)
+        .filter(|segment| !segment.is_empty() && *segment != "." && *segment != "..")
+        .map(sanitize_file_stem)
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Converts common export date formats to `YYYY-MM-DD`.
+pub(crate) fn normalize_date(raw: &str) -> Option<String> {
+    let value = raw.trim();
+    if let Some(prefix) = value.get(..10) {
+        let bytes = prefix.as_bytes();
+        if bytes[4] == b'-'
+            && bytes[7] == b'-'
+            && prefix
+                .chars()
+                .enumerate()
+                .all(|(idx, ch)| idx == 4 || idx == 7 || ch.is_ascii_digit())
+        {
+            return Some(prefix.to_string());
+        }
+    }
+    if let Some(digits) = value
+        .get(..8)
+        .filter(|digits| digits.chars().all(|ch| ch.is_ascii_digit()))
+    {
+        if value.len() == 8 || value[8..].starts_with('T') {
+            return Some(format!(
+                "{}-{}-{}",
+                &digits[..4],
+                &digits[4..6],
+                &digits[6..8]
+            ));
+        }
+    }
+
+    const MONTHS: [&str; 12] = [
+        "january",
+        "february",
+        "march",
+        "april",
+        "may",
+        "june",
+        "july",
+        "august",
+        "september",
+        "october",
+        "november",
+        "december",
+    ];
+    let mut parts = value.split([' ', ',']).filter(|part| !part.is_empty());
+    let month_name = parts.next()?.to_lowercase();
+    let month = MONTHS
+        .iter()
+        .position(|name| month_name.len() >= 3 && name.starts_with(&month_name))?;
+    let day = parts.next()?.parse::<u32>().ok()?;
+    let year = parts.next()?.parse::<u32>().ok()?;
+    if !(1..=31).contains(&day) || year < 1000 {
+        return None;
+    }
+    Some(format!("{year:04}-{:02}-{day:02}", month + 1))
+}
+
+fn yaml_scalar(value: &str) -> String {
+    let needs_quotes = value.is_empty()
+        || value.trim() != value
+        || value.contains([':', '#', '"', '\'', '\n', '[', ']', '{', '}', ','])
+        || value.starts_with(['-', '?', '!', '&', '*', '>', '|', '%', '@', '`'])
+        || matches!(
+            value.to_ascii_lowercase().as_str(),
+            "true" | "false" | "yes" | "no" | "null" | "~"
+        )
+        || value.parse::<f64>().is_ok();
+    if !needs_quotes {
+        return value.to_string();
+    }
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("\"{escaped}\"")
+}
+
+fn property_type(value: &PropertyValue) -> &'static str {
+    match value {
+        PropertyValue::Text(_) => "text",
+        PropertyValue::List(_) => "list",
+        PropertyValue::Number(_) => "number",
+        PropertyValue::Checkbox(_) => "checkbox",
+        PropertyValue::Date(_) => "date",
+    }
+}
+
+fn value_as_text(value: &PropertyValue) -> String {
+    match value {
+        PropertyValue::Text(text) | PropertyValue::Date(text) => text.clone(),
+        PropertyValue::List(items) => items.join(", "),
+        PropertyValue::Number(number) => number.to_string(),
+        PropertyValue::Checkbox(flag) => flag.to_string(),
+    }
+}
+
+/// Renders one property using the schema type when the key already has one.
+fn render_property(key: &str, value: &PropertyValue, schema_type: &str) -> Option<String> {
+    let line = match (schema_type, value) {
+        ("list" | "tags", value) => {
+            let items = match value {
+                PropertyValue::List(items) => items.clone(),
+                other => value_as_text(other)
+                    .split(',')
+                    .map(|item| item.trim().to_string())
+                    .filter(|item| !item.is_empty())
+                    .collect(),
+            };
+            if items.is_empty() {
+                return None;
+            }
+            let rendered = items
+                .iter()
+                .map(|item| format!("  - {}", yaml_scalar(item)))
+                .collect::<Vec<_>>()
+                .join("\n");
+            format!("{key}:\n{rendered}")
+        }
+        ("number", PropertyValue::Number(number)) => format!("{key}: {number}"),
+        ("checkbox", PropertyValue::Checkbox(flag)) => format!("{key}: {flag}"),
+        ("date", PropertyValue::Date(date)) => format!("{key}: {date}"),
+        ("date", other) => match normalize_date(&value_as_text(other)) {
+            Some(date) => format!("{key}: {date}"),
+            None => format!("{key}: {}", yaml_scalar(&value_as_text(other))),
+        },
+        (_, other) => format!("{key}: {}", yaml_scalar(&value_as_text(other))),
+    };
+    Some(line)
+}
+
+/// Builds the frontmatter block and records the type of keys new to the schema.
+fn build_frontmatter(
+    properties: &[(String, PropertyValue)],
+    schema: &HashMap<String, String>,
+    new_types: &mut HashMap<String, String>,
+) -> String {
+    let mut lines = Vec::new();
+    let mut seen = HashSet::new();
+    for (raw_key, value) in properties {
+        let key = raw_key.trim().to_lowercase().replace(' ', "_");
+        if key.is_empty() || !seen.insert(key.clone()) {
+            continue;
+        }
+        let schema_type = match schema.get(&key).or_else(|| new_types.get(&key)) {
+            Some(existing) => existing.clone(),
+            None => {
+                let inferred = if key == "tags" {
+                    "tags"
+                } else {
+                    property_type(value)
+                };
+                new_types.insert(key.clone(), inferred.to_string());
+                inferred.to_string()
+            }
+        };
+        if let Some(line) = render_property(&key, value, &schema_type) {
+            lines.push(line);
+        }
+    }
+    if lines.is_empty() {
+        return String::new();
+    }
+    format!("---\n{}\n---\n\n", lines.join("\n"))
+}
+
+/// Rewrites `[[key|label]]` links between imported notes to their final targets.
+fn rewrite_import_links(
+    body: &str,
+    targets: &HashMap<String, String>,
+    converted: &mut usize,
+    unresolved: &mut usize,
+) -> String {
+    let mut output = String::with_capacity(body.len());
+    let mut offset = 0usize;
+    while let Some(start_rel) = body[offset..].find("[[") {
+        let start = offset + start_rel;
+        let Some(end_rel) = body[start + 2..].find("]]") else {
+            break;
+        };
+        let end = start + 2 + end_rel;
+        let content = &body[start + 2..end];
+        let (key, suffix) = split_wikilink_target_suffix(content);
+        output.push_str(&body[offset..start + 2]);
+        match targets.get(&normalize_key_text(key.trim())) {
+            Some(target) => {
+                output.push_str(target);
+                output.push_str(suffix);
+                *converted += 1;
+            }
+            None => {
+                output.push_str(content);
+                *unresolved += 1;
+            }
+        }
+        output.push_str("]]");
+        offset = end + 2;
+    }
+    output.push_str(&body[offset..]);
+    output
+}
+
+/// Points `](attachment:<name>)` links at the copied files, relative to the note.
+fn rewrite_attachment_links(body: &str, note_dir: &str, copied: &[(String, String)]) -> String {
+    let mut output = body.to_string();
+    for (name, path) in copied {
+        let target = relative_from(note_dir, path);
+        let link = if target.contains([' ', '(', ')']) {
+            format!("<{target}>")
+        } else {
+            target
+        };
+        output = output.replace(
+            &format!("]({ATTACHMENT_LINK_PREFIX}{name})"),
+            &format!("]({link})"),
+        );
+    }
+    output
+}
+
+fn workspace_relative(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .map(|relative| relative.to_string_lossy().replace('\\', "/"))
+        .unwrap_or_else(|_| path.to_string_lossy().to_string())
+}
+
+fn validate_target_folder(raw: &str) -> Result<PathBuf> {
+    let trimmed = raw.trim().replace('\\', "/");
+    let relative = PathBuf::from(trimmed.trim_matches('/'));
+    if relative.as_os_str().is_empty()
+        || relative
+            .components()
+            .any(|component| !matches!(component, Component::Normal(_)))
+    {
+        return Err(AppError::InvalidPath);
+    }
+    Ok(relative)
+}
+
+/// Writes a converted batch below `target_root` and returns the report.
+fn write_batch(
+    root: &Path,
+    target_root: &Path,
+    batch: ImportBatch,
+    strategy: ConflictStrategy,
+    schema: &HashMap<String, String>,
+    report: &mut ImportReport,
+) -> Result<HashMap<String, String>> {
+    fs::create_dir_all(target_root)?;
+    let mut planned = Vec::new();
+    let mut reserved = HashSet::new();
+    for note in &batch.notes {
+        let folder = sanitize_folder(&note.folder);
+        let dir = if folder.is_empty() {
+            target_root.to_path_buf()
+        } else {
+            target_root.join(&folder)
+        };
+        let base = dir.join(format!("{}.md", sanitize_file_stem(&note.title)));
+        if matches!(strategy, ConflictStrategy::Fail) && (base.exists() || reserved.contains(&base))
+        {
+            return Err(AppError::AlreadyExists);
+        }
+        planned.push(base.clone());
+        reserved.insert(base);
+    }
+
+    let mut final_paths = Vec::new();
+    let mut assigned = HashSet::new();
+    let mut targets = HashMap::new();
+    for (note, base) in batch.notes.iter().zip(planned) {
+        if let Some(parent) = base.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Two notes of the same batch never overwrite each other.
+        let note_strategy = if assigned.contains(&base) {
+            ConflictStrategy::Rename
+        } else {
+            strategy
+        };
+        let destination = resolve_destination(base, note_strategy, false)?;
+        assigned.insert(destination.clone());
+        if !destination.exists() {
+            // Reserves the name so later notes of the batch pick another one.
+            fs::File::create(&destination)?;
+        }
+        let target = note_link_target(root, &destination)?;
+        for key in &note.link_keys {
+            targets.insert(normalize_key_text(key.trim()), target.clone());
+        }
+        final_paths.push(destination);
+    }
+
+    let mut new_types = HashMap::new();
+    for (note, destination) in batch.notes.into_iter().zip(final_paths) {
+        let relative = workspace_relative(root, &destination);
+        let note_dir = relative
+            .rsplit_once('/')
+            .map(|(dir, _)| dir.to_string())
+            .unwrap_or_default();
+        let stem = destination
+            .file_stem()
+            .map(|value| value.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let target_relative = workspace_relative(root, target_root);
+        let attachments_dir = if target_relative.is_empty() {
+            format!("{ATTACHMENTS_DIR_NAME}/{stem}")
+        } else {
+            format!("{target_relative}/{ATTACHMENTS_DIR_NAME}/{stem}")
+        };
+
+        let mut copied = Vec::new();
+        if !note.attachments.is_empty() {
+            let dir = root.join(&attachments_dir);
+            fs::create_dir_all(&dir)?;
+            for attachment in &note.attachments {
+                let safe_name = sanitize_attachment_name(&attachment.file_name);
+                fs::write(dir.join(&safe_name), &attachment.bytes)?;
+                copied.push((
+                    attachment.file_name.clone(),
+                    format!("{attachments_dir}/{safe_name}"),
+                ));
+                report.copied_attachments += 1;
+            }
+        }
+
+        let body = rewrite_import_links(
+            &note.body,
+            &targets,
+            &mut report.converted_links,
+            &mut report.unresolved_links,
+        );
+        let body = rewrite_attachment_links(&body, &note_dir, &copied);
+        let frontmatter = build_frontmatter(&note.properties, schema, &mut new_types);
+        let content = format!("{frontmatter}{}\n", body.trim_end());
+        write_note_atomically(&destination, &content)?;
+        record_workspace_mutation_write(&destination, &content);
+        report.created_notes.push(relative);
+    }
+    Ok(new_types)
+}
+
+/// Replaces a note in one step, so an overwritten note is never left truncated.
+fn write_note_atomically(path: &Path, content: &str) -> Result<()> {
+    let atomic = AtomicFile::new(path, AllowOverwrite);
+    atomic
+        .write(|file| {
+            file.write_all(content.as_bytes())?;
+            file.flush()?;
+            file.sync_all()?;
+            Ok(())
+        })
+        .map_err(|err| match err {
+            atomicwrites::Error::Internal(error) | atomicwrites::Error::User(error) => {
+                AppError::Io(error)
+            }
+        })
+}
+
+/// Keeps attachment names flat and safe while preserving their extension.
+pub(crate) fn sanitize_attachment_name(name: &str) -> String {
+    let base = name.rsplit(['/', '\\']).next().unwrap_or(name);
+    match base.rsplit_once('.') {
+        Some((stem, ext)) if !ext.is_empty() && !stem.is_empty() => {
+            format!("{}.{}", sanitize_file_stem(stem), sanitize_file_stem(ext))
+        }
+        _ => sanitize_file_stem(base),
+    }
+}
+
+fn import_notes_sync(root: &Path, payload: ImportNotesPayload) -> Result<ImportReport> {
+    let source_path = PathBuf::from(payload.source_path.trim());
+    if !source_path.exists() {
+        return Err(AppError::InvalidPath);
+    }
+    let target_relative = validate_target_folder(&payload.target_folder)?;
+    let target_root = root.join(&target_relative);
+
+    let batch = match payload.source {
+        ImportSource::Notion => notion::read_notion_export(&source_path)?,
+        ImportSource::Evernote => evernote::read_enex_export(&source_path)?,
+        ImportSource::Bear => bear::read_bear_export(&source_path)?,
+        ImportSource::Word => word::read_docx_export(&source_path)?,
+    };
+    write_import(
+        root,
+        payload.source,
+        &target_root,
+        batch,
+        payload.conflict_strategy,
+    )
+}
+
+/// Writes a converted batch, extends the property type schema and reindexes
+/// the created notes.
+fn write_import(
+    root: &Path,
+    source: ImportSource,
+    target_root: &Path,
+    batch: ImportBatch,
+    strategy: ConflictStrategy,
+) -> Result<ImportReport> {
+    let mut report = ImportReport {
+        source,
+        target_folder: workspace_relative(root, target_root),
+        created_notes: Vec::new(),
+        copied_attachments: 0,
+        converted_links: 0,
+        unresolved_links: 0,
+        new_property_types: Vec::new(),
+        skipped: Vec::new(),
+        warnings: Vec::new(),
+    };
+    report.skipped.extend(batch.skipped.iter().cloned());
+    report.warnings.extend(batch.warnings.iter().cloned());
+
+    let schema = read_property_type_schema()?;
+    let new_types = write_batch(root, target_root, batch, strategy, &schema, &mut report)?;
+    if !new_types.is_empty() {
+        let mut merged = schema;
+        let mut keys = new_types.keys().cloned().collect::<Vec<_>>();
+        keys.sort();
+        merged.extend(new_types);
+        write_property_type_schema(merged)?;
+        report.new_property_types = keys;
+    }
+
+    for note in &report.created_notes {
+        if let Err(err) =
+            reindex_markdown_file_now_sync(root.join(note).to_string_lossy().to_string())
+        {
+            report
+                .warnings
+                .push(format!("{note}: indexing failed ({err})"));
+        }
+    }
+    Ok(report)
+}
+
+/// Converts a workspace `.docx` file to a note written next to it. An existing
+/// note with the same name is kept and the new one gets a suffix.
+fn import_docx_note_sync(root: &Path, path: &str) -> Result<ImportReport> {
+    let source_path = normalize_workspace_path(root, path)?;
+    ensure_within_root(root, &source_path)?;
+    if !source_path.is_file() || !word::is_docx_path(&source_path) {
+        return Err(AppError::InvalidPath);
+    }
+    let target_root = source_path.parent().ok_or(AppError::InvalidPath)?;
+
+    let batch = word::read_docx_export(&source_path)?;
+    if let Some(skip) = batch.skipped.first() {
+        return Err(AppError::InvalidOperation(format!(
+            "{}: {}",
+            skip.source, skip.reason
+        )));
+    }
+    write_import(
+        root,
+        ImportSource::Word,
+        target_root,
+        batch,
+        ConflictStrategy::Rename,
+    )
+}
+
+/// Imports a Notion, Evernote, Bear or Word export into a workspace subfolder.
+#[tauri::command]
+pub async fn import_notes(payload: ImportNotesPayload) -> Result<ImportReport> {
+    let root = active_workspace_root()?;
+    tauri::async_runtime::spawn_blocking(move || import_notes_sync(&root, payload))
+        .await
+        .map_err(|_| AppError::OperationFailed)?
+}
+
+/// Converts a `.docx` file of the workspace to a Markdown note beside it.
+#[tauri::command]
+pub async fn import_docx_note(path: String) -> Result<ImportReport> {
+    let root = active_workspace_root()?;
+    tauri::async_runtime::spawn_blocking(move || import_docx_note_sync(&root, &path))
+        .await
+        .map_err(|_| AppError::OperationFailed)?
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::*;
+
+    fn create_temp_workspace(prefix: &str) -> PathBuf {
+        let nonce = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|value| value.as_nanos())
+            .unwrap_or(0);
+        let dir = std::env::temp_dir().join(format!("{prefix}-{nonce}"));
+        fs::create_dir_all(&dir).expect("create temp workspace");
+        fs::canonicalize(dir).expect("canonical workspace")
+    }
+
+    fn empty_report() -> ImportReport {
+        ImportReport {
+            source: ImportSource::Bear,
+            target_folder: "Imported".to_string(),
+            created_notes: Vec::new(),
+            copied_attachments: 0,
+            converted_links: 0,
+            unresolved_links: 0,
+            new_property_types: Vec::new(),
+            skipped: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn normalizes_export_dates() {
+        assert_eq!(
+            normalize_date("2024-03-05T10:00:00Z").as_deref(),
+            Some("2024-03-05")
+        );
+        assert_eq!(
+            normalize_date("20240305T100000Z").as_deref(),
+            Some("2024-03-05")
+        );
+        assert_eq!(
+            normalize_date("March 5, 2024 10:00 AM").as_deref(),
+            Some("2024-03-05")
+        );
+        assert_eq!(
+            normalize_date("Sept 5, 2024").as_deref(),
+            Some("2024-09-05")
+        );
+        assert_eq!(normalize_date("soon"), None);
+    }
+
+    #[test]
+    fn sanitizes_titles_and_folders() {
+        assert_eq!(sanitize_file_stem("a/b: c?"), "a b c");
+        assert_eq!(sanitize_file_stem("..."), "Untitled");
+        assert_eq!(sanitize_folder("../Projects//Q1: plan"), "Projects/Q1 plan");
+        assert_eq!(
+            sanitize_attachment_name("dir/My image?.png"),
+            "My image.png"
+        );
+    }
+
+    #[test]
+    fn frontmatter_follows_existing_schema_and_records_new_types() {
+        let schema = HashMap::from([("status".to_string(), "list".to_string())]);
+        let mut new_types = HashMap::new();
+        let frontmatter = build_frontmatter(
+            &[
+                (
+                    "Status".to_string(),
+                    PropertyValue::Text("Done".to_string()),
+                ),
+                (
+                    "Created".to_string(),
+                    PropertyValue::Date("2024-01-02".to_string()),
+                ),
+                (
+                    "tags".to_string(),
+                    PropertyValue::List(vec!["a".to_string(), "b c".to_string()]),
+                ),
+                ("Score".to_string(), PropertyValue::Number(3.0)),
+                (
+                    "Source".to_string(),
+                    PropertyValue::Text("https://x.y".to_string()),
+                ),
+            ],
+            &schema,
+            &mut new_types,
+        );
+        assert_eq!(
+            frontmatter,
+            "---\nstatus:\n  - Done\ncreated: 2024-01-02\ntags:\n  - a\n  - b c\nscore: 3\nsource: \"https://x.y\"\n---\n\n"
+        );
+        assert_eq!(new_types.get("created").map(String::as_str), Some("date"));
+        assert_eq!(new_types.get("tags").map(String::as_str), Some("tags"));
+        assert!(!new_types.contains_key("status"));
+    }
+
+    #[test]
+    fn writes_notes_with_links_and_attachments() {
+        let workspace = create_temp_workspace("tomosona-import-write");
+        let target_root = workspace.join("Imported");
+        let batch = ImportBatch {
+            notes: vec![
+                ImportedNote {
+                    folder: "Projects".to_string(),
+                    title: "Plan".to_string(),
+                    body: "See [[home-key|Home]] and ![chart](attachment:chart 1.png) [[gone]]"
+                        .to_string(),
+                    properties: Vec::new(),
+                    attachments: vec![ImportedAttachment {
+                        file_name: "chart 1.png".to_string(),
+                        bytes: vec![1, 2, 3],
+                    }],
+                    link_keys: vec!["plan-key".to_string()],
+                },
+                ImportedNote {
+                    folder: String::new(),
+                    title: "Home".to_string(),
+                    body: "Back to [[plan-key]]".to_string(),
+                    properties: Vec::new(),
+                    attachments: Vec::new(),
+                    link_keys: vec!["home-key".to_string()],
+                },
+            ],
+            ..Default::default()
+        };
+        let mut report = empty_report();
+        write_batch(
+            &workspace,
+            &target_root,
+            batch,
+            ConflictStrategy::Rename,
+            &HashMap::new(),
+            &mut report,
+        )
+        .expect("write batch");
+
+        assert_eq!(
+            fs::read_to_string(target_root.join("Projects/Plan.md")).expect("read plan"),
+            "See [[Imported/Home|Home]] and ![chart](<../attachments/Plan/chart 1.png>) [[gone]]\n"
+        );
+        assert_eq!(
+            fs::read_to_string(target_root.join("Home.md")).expect("read home"),
+            "Back to [[Imported/Projects/Plan]]\n"
+        );
+        assert!(target_root.join("attachments/Plan/chart 1.png").exists());
+        assert_eq!(report.converted_links, 2);
+        assert_eq!(report.unresolved_links, 1);
+        assert_eq!(report.copied_attachments, 1);
+
+        fs::remove_dir_all(workspace).expect("cleanup workspace");
+    }
+
+    #[test]
+    fn overwrite_keeps_the_existing_note_when_the_import_fails() {
+        let workspace = create_temp_workspace("tomosona-import-overwrite");
+        let target_root = workspace.join("Imported");
+        fs::create_dir_all(&target_root).expect("create target");
+        fs::write(target_root.join("Plan.md"), "keep me").expect("write existing note");
+        fs::write(target_root.join(ATTACHMENTS_DIR_NAME), "not a folder")
+            .expect("block attachments");
+        let batch = ImportBatch {
+            notes: vec![ImportedNote {
+                folder: String::new(),
+                title: "Plan".to_string(),
+                body: "![chart](attachment:chart.png)".to_string(),
+                properties: Vec::new(),
+                attachments: vec![ImportedAttachment {
+                    file_name: "chart.png".to_string(),
+                    bytes: vec![1],
+                }],
+                link_keys: Vec::new(),
+            }],
+            ..Default::default()
+        };
+
+        let mut report = empty_report();
+        assert!(write_batch(
+            &workspace,
+            &target_root,
+            batch,
+            ConflictStrategy::Overwrite,
+            &HashMap::new(),
+            &mut report,
+        )
+        .is_err());
+        assert_eq!(
+            fs::read_to_string(target_root.join("Plan.md")).expect("read existing note"),
+            "keep me"
+        );
+
+        fs::remove_dir_all(workspace).expect("cleanup workspace");
+    }
+}
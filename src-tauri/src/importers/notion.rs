@@ -0,0 +1,460 @@
+//! Notion "Markdown & CSV" export reader.
+//!
+//! Pages are `Title <32 hex id>.md` files whose children live in a sibling folder of
+//! the same name. Databases are exported as `Name <id>.csv` (plus an `_all.csv`
+//! variant) with one page per row; row values become typed frontmatter.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs::File,
+    io::Cursor,
+    path::Path,
+};
+
+use super::{
+    normalize_date, percent_decode, read_dir_entries, read_zip_entries, ImportBatch, ImportSkip,
+    ImportedAttachment, ImportedNote, PropertyValue, ATTACHMENT_LINK_PREFIX,
+};
+use crate::workspace_paths::join_relative;
+use crate::Result;
+
+const NOTION_ID_LEN: usize = 32;
+const MAX_NESTED_ARCHIVES: usize = 2;
+
+/// Removes the trailing Notion id from one path segment (`Plan 0a1b…` -> `Plan`).
+pub(super) fn strip_notion_id(segment: &str) -> String {
+    let (stem, ext) = match segment.rsplit_once('.') {
+        Some((stem, ext)) if !ext.contains(' ') => (stem, Some(ext)),
+        _ => (segment, None),
+    };
+    let stripped = stem
+        .rsplit_once(' ')
+        .filter(|(_, id)| id.len() == NOTION_ID_LEN && id.chars().all(|ch| ch.is_ascii_hexdigit()))
+        .map(|(name, _)| name.trim_end())
+        .unwrap_or(stem);
+    match ext {
+        Some(ext) => format!("{stripped}.{ext}"),
+        None => stripped.to_string(),
+    }
+}
+
+fn strip_notion_ids(path: &str) -> String {
+    path.split('/')
+        .map(strip_notion_id)
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn parent_dir(path: &str) -> &str {
+    path.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("")
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+/// Parses RFC 4180 CSV, including quoted fields spanning several lines.
+pub(super) fn parse_csv(input: &str) -> Vec<Vec<String>> {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = input.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if in_quotes {
+            match ch {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                other => field.push(other),
+            }
+            continue;
+        }
+        match ch {
+            '"' => in_quotes = true,
+            ',' => row.push(std::mem::take(&mut field)),
+            '\r' => {}
+            '\n' => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            other => field.push(other),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows.retain(|row| row.iter().any(|value| !value.trim().is_empty()));
+    rows
+}
+
+/// Infers a frontmatter value from a Notion database cell.
+fn infer_property(key: &str, raw: &str) -> Option<PropertyValue> {
+    let value = raw.trim();
+    if value.is_empty() {
+        return None;
+    }
+    let lowered_key = key.to_lowercase();
+    if lowered_key == "tags" || lowered_key == "tag" {
+        return Some(PropertyValue::List(
+            value
+                .split(',')
+                .map(|item| item.trim().to_string())
+                .filter(|item| !item.is_empty())
+                .collect(),
+        ));
+    }
+    match value {
+        "Yes" | "No" => return Some(PropertyValue::Checkbox(value == "Yes")),
+        _ => {}
+    }
+    if let Ok(number) = value.parse::<f64>() {
+        if number.is_finite() {
+            return Some(PropertyValue::Number(number));
+        }
+    }
+    if let Some(date) = normalize_date(value) {
+        return Some(PropertyValue::Date(date));
+    }
+    Some(PropertyValue::Text(value.to_string()))
+}
+
+/// Database rows keyed by `(rows folder, row title)`.
+type DatabaseRows = HashMap<(String, String), Vec<(String, PropertyValue)>>;
+
+fn collect_database_rows(
+    entries: &[(String, Vec<u8>)],
+    warnings: &mut Vec<String>,
+) -> DatabaseRows {
+    let csv_paths = entries
+        .iter()
+        .map(|(path, _)| path.as_str())
+        .filter(|path| path.to_lowercase().ends_with(".csv"))
+        .collect::<HashSet<_>>();
+    let mut rows_by_page = DatabaseRows::new();
+    for (path, bytes) in entries {
+        if !path.to_lowercase().ends_with(".csv") {
+            continue;
+        }
+        let stem = &path[..path.len() - 4];
+        // Prefer the `_all` variant, which also lists rows hidden by the view.
+        let rows_folder = match stem.strip_suffix("_all") {
+            Some(base) => base,
+            None if csv_paths.contains(format!("{stem}_all.csv").as_str()) => continue,
+            None => stem,
+        };
+        let Ok(text) = String::from_utf8(bytes.clone()) else {
+            warnings.push(format!("{path}: database is not valid UTF-8"));
+            continue;
+        };
+        let mut rows = parse_csv(&text).into_iter();
+        let Some(headers) = rows.next() else {
+            continue;
+        };
+        for row in rows {
+            let Some(title) = row.first().map(|value| value.trim().to_string()) else {
+                continue;
+            };
+            let properties = headers
+                .iter()
+                .zip(row.iter())
+                .skip(1)
+                .filter_map(|(key, value)| {
+                    infer_property(key, value).map(|parsed| (key.trim().to_string(), parsed))
+                })
+                .collect::<Vec<_>>();
+            rows_by_page.insert((strip_notion_ids(rows_folder), title), properties);
+        }
+    }
+    rows_by_page
+}
+
+/// Splits the leading `# Title` line off a page body.
+fn split_title(markdown: &str) -> (Option<String>, &str) {
+    let trimmed = markdown.trim_start_matches('\u{feff}').trim_start();
+    match trimmed.split_once('\n') {
+        Some((first, rest)) if first.starts_with("# ") => {
+            (Some(first[2..].trim().to_string()), rest)
+        }
+        None if trimmed.starts_with("# ") => (Some(trimmed[2..].trim().to_string()), ""),
+        _ => (None, trimmed),
+    }
+}
+
+/// Drops the `Key: value` lines Notion repeats under a database row title.
+fn strip_property_block<'a>(body: &'a str, keys: &[(String, PropertyValue)]) -> &'a str {
+    if keys.is_empty() {
+        return body;
+    }
+    let mut rest = body.trim_start_matches('\n');
+    loop {
+        let (line, tail) = rest.split_once('\n').unwrap_or((rest, ""));
+        let is_property = line
+            .split_once(": ")
+            .map(|(key, _)| keys.iter().any(|(name, _)| name == key.trim()))
+            .unwrap_or(false);
+        if !is_property {
+            return rest;
+        }
+        rest = tail;
+    }
+}
+
+struct LinkContext<'a> {
+    note_dir: &'a str,
+    pages: &'a HashSet<String>,
+    assets: &'a HashMap<String, Vec<u8>>,
+}
+
+/// Rewrites Markdown links: pages become `[[key|label]]`, files become attachments.
+fn convert_links(
+    body: &str,
+    context: &LinkContext<'_>,
+    attachments: &mut Vec<ImportedAttachment>,
+) -> String {
+    let mut output = String::with_capacity(body.len());
+    let mut names_by_source = HashMap::<String, String>::new();
+    let mut rest = body;
+    while let Some(open) = rest.find('[') {
+        let Some(close_rel) = rest[open..].find("](") else {
+            break;
+        };
+        let close = open + close_rel;
+        let label = &rest[open + 1..close];
+        let end = match rest[close + 2..].find(')') {
+            Some(end_rel) if !label.contains(['[', ']']) => close + 2 + end_rel,
+            _ => {
+                output.push_str(&rest[..=open]);
+                rest = &rest[open + 1..];
+                continue;
+            }
+        };
+        let raw_target = rest[close + 2..end].trim();
+        let is_image = open > 0 && rest.as_bytes()[open - 1] == b'!';
+        let resolved = if raw_target.contains("://") || raw_target.starts_with("mailto:") {
+            None
+        } else {
+            let decoded = percent_decode(raw_target.trim_matches(['<', '>']));
+            let path = decoded.split('#').next().unwrap_or_default().to_string();
+            join_relative(context.note_dir, &path)
+        };
+
+        output.push_str(&rest[..open]);
+        match resolved {
+            Some(path) if !is_image && context.pages.contains(&path) => {
+                output.push_str(&format!("[[{path}|{label}]]"));
+            }
+            Some(path) if context.assets.contains_key(&path) => {
+                let name = match names_by_source.get(&path) {
+                    Some(name) => name.clone(),
+                    None => {
+                        let name = attachment_name(file_name(&path), attachments);
+                        attachments.push(ImportedAttachment {
+                            file_name: name.clone(),
+                            bytes: context.assets[&path].clone(),
+                        });
+                        names_by_source.insert(path, name.clone());
+                        name
+                    }
+                };
+                output.push_str(&format!("[{label}]({ATTACHMENT_LINK_PREFIX}{name})"));
+            }
+            _ => output.push_str(&rest[open..=end]),
+        }
+        rest = &rest[end + 1..];
+    }
+    output.push_str(rest);
+    output
+}
+
+/// Returns the attachment name for `name`, suffixing it when another file already uses it.
+fn attachment_name(name: &str, attachments: &[ImportedAttachment]) -> String {
+    let name = strip_notion_id(name);
+    if !attachments.iter().any(|item| item.file_name == name) {
+        return name;
+    }
+    let (stem, ext) = name.rsplit_once('.').unwrap_or((name.as_str(), ""));
+    (1..)
+        .map(|index| {
+            if ext.is_empty() {
+                format!("{stem} ({index})")
+            } else {
+                format!("{stem} ({index}).{ext}")
+            }
+        })
+        .find(|candidate| !attachments.iter().any(|item| &item.file_name == candidate))
+        .unwrap_or(name)
+}
+
+fn expand_nested_archives(
+    entries: Vec<(String, Vec<u8>)>,
+    depth: usize,
+) -> Result<Vec<(String, Vec<u8>)>> {
+    let mut expanded = Vec::new();
+    for (path, bytes) in entries {
+        if depth < MAX_NESTED_ARCHIVES && path.to_lowercase().ends_with(".zip") {
+            let nested = read_zip_entries(Cursor::new(bytes))?;
+            expanded.extend(expand_nested_archives(nested, depth + 1)?);
+        } else {
+            expanded.push((path, bytes));
+        }
+    }
+    Ok(expanded)
+}
+
+/// Reads a Notion export zip, or an already extracted export folder.
+pub(super) fn read_notion_export(source: &Path) -> Result<ImportBatch> {
+    let entries = if source.is_dir() {
+        read_dir_entries(source)?
+    } else {
+        read_zip_entries(File::open(source)?)?
+    };
+    let entries = expand_nested_archives(entries, 0)?;
+    Ok(convert_entries(entries))
+}
+
+fn convert_entries(entries: Vec<(String, Vec<u8>)>) -> ImportBatch {
+    let mut batch = ImportBatch::default();
+    let rows = collect_database_rows(&entries, &mut batch.warnings);
+    let pages = entries
+        .iter()
+        .filter(|(path, _)| path.to_lowercase().ends_with(".md"))
+        .map(|(path, _)| path.clone())
+        .collect::<HashSet<_>>();
+    let assets = entries
+        .iter()
+        .filter(|(path, _)| {
+            let lowered = path.to_lowercase();
+            !lowered.ends_with(".md") && !lowered.ends_with(".csv")
+        })
+        .map(|(path, bytes)| (path.clone(), bytes.clone()))
+        .collect::<HashMap<_, _>>();
+
+    for (path, bytes) in &entries {
+        if !pages.contains(path) {
+            continue;
+        }
+        let Ok(markdown) = String::from_utf8(bytes.clone()) else {
+            batch.skipped.push(ImportSkip {
+                source: path.clone(),
+                reason: "Page is not valid UTF-8.".to_string(),
+            });
+            continue;
+        };
+        let note_dir = parent_dir(path);
+        let folder = strip_notion_ids(note_dir);
+        let (heading, body) = split_title(&markdown);
+        let title = heading.unwrap_or_else(|| {
+            strip_notion_id(file_name(path))
+                .trim_end_matches(".md")
+                .to_string()
+        });
+        let properties = rows
+            .get(&(folder.clone(), title.clone()))
+            .cloned()
+            .unwrap_or_default();
+        let body = strip_property_block(body, &properties);
+        let mut attachments = Vec::new();
+        let context = LinkContext {
+            note_dir,
+            pages: &pages,
+            assets: &assets,
+        };
+        let body = convert_links(body, &context, &mut attachments);
+        batch.notes.push(ImportedNote {
+            folder,
+            title,
+            body: body.trim().to_string(),
+            properties,
+            attachments,
+            link_keys: vec![path.clone()],
+        });
+    }
+    batch
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID_A: &str = "0123456789abcdef0123456789abcdef";
+    const ID_B: &str = "fedcba9876543210fedcba9876543210";
+
+    #[test]
+    fn strips_notion_ids_from_segments() {
+        assert_eq!(strip_notion_id(&format!("Plan {ID_A}.md")), "Plan.md");
+        assert_eq!(strip_notion_id(&format!("Road map {ID_A}")), "Road map");
+        assert_eq!(strip_notion_id("Notes 2024.md"), "Notes 2024.md");
+    }
+
+    #[test]
+    fn parses_quoted_csv() {
+        let rows = parse_csv(
+            "\u{feff}Name,Tags\n\"Plan, v2\",\"a, b\"\n\"Multi\nline\",\"say \"\"hi\"\"\"\n",
+        );
+        assert_eq!(
+            rows,
+            vec![
+                vec!["Name".to_string(), "Tags".to_string()],
+                vec!["Plan, v2".to_string(), "a, b".to_string()],
+                vec!["Multi\nline".to_string(), "say \"hi\"".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn converts_pages_rows_and_assets() {
+        let entries = vec![
+            (
+                format!("Home {ID_A}.md"),
+                format!("# Home\n\nSee [Tasks](Home%20{ID_A}/Tasks%20{ID_B}.csv) and [Plan](Home%20{ID_A}/Plan%20{ID_B}.md).\n\n![chart](Home%20{ID_A}/chart.png)\n[site](https://notion.so)\n").into_bytes(),
+            ),
+            (
+                format!("Home {ID_A}/Plan {ID_B}.md"),
+                b"# Plan\n\nStatus: Doing\nTags: a, b\n\nBody text\n".to_vec(),
+            ),
+            (format!("Home {ID_A}/chart.png"), vec![7, 8]),
+            (
+                format!("Home {ID_A}.csv"),
+                b"Name,Status,Tags,Done,Due\nPlan,Doing,\"a, b\",Yes,\"March 5, 2024\"\n".to_vec(),
+            ),
+        ];
+        let batch = convert_entries(entries);
+        assert_eq!(batch.notes.len(), 2);
+
+        let home = &batch.notes[0];
+        assert_eq!(home.title, "Home");
+        assert_eq!(home.folder, "");
+        assert_eq!(
+            home.body,
+            format!("See [Tasks](Home%20{ID_A}/Tasks%20{ID_B}.csv) and [[Home {ID_A}/Plan {ID_B}.md|Plan]].\n\n![chart](attachment:chart.png)\n[site](https://notion.so)")
+        );
+        assert_eq!(home.attachments.len(), 1);
+
+        let plan = &batch.notes[1];
+        assert_eq!(plan.folder, "Home");
+        assert_eq!(plan.body, "Body text");
+        assert_eq!(
+            plan.properties,
+            vec![
+                (
+                    "Status".to_string(),
+                    PropertyValue::Text("Doing".to_string())
+                ),
+                (
+                    "Tags".to_string(),
+                    PropertyValue::List(vec!["a".to_string(), "b".to_string()])
+                ),
+                ("Done".to_string(), PropertyValue::Checkbox(true)),
+                (
+                    "Due".to_string(),
+                    PropertyValue::Date("2024-03-05".to_string())
+                ),
+            ]
+        );
+    }
+}
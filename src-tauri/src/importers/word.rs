@@ -0,0 +1,1119 @@
+//! Word `.docx` reader.
+//!
+//! A DOCX file is a zip of WordprocessingML parts: the body lives in
+//! `word/document.xml`, paragraph styles in `word/styles.xml`, list formats in
+//! `word/numbering.xml`, link and image targets in the document relationships
+//! and document properties in `docProps/`. The parts are small enough to be read
+//! with `quick-xml` into a minimal element tree; only the elements that carry
+//! Markdown structure are interpreted and everything else is walked through for
+//! its text.
+
+use std::{collections::HashMap, fs, fs::File, path::Path};
+
+use quick_xml::{
+    events::{BytesStart, Event},
+    Reader,
+};
+
+use super::{
+    evernote::push_entity, normalize_date, read_zip_entries, ImportBatch, ImportSkip,
+    ImportedAttachment, ImportedNote, PropertyValue, ATTACHMENT_LINK_PREFIX,
+};
+use crate::docx::style_from_docx::heading_style_candidates;
+use crate::{AppError, Result};
+
+const DOCX_EXTENSION: &str = "docx";
+const MAX_HEADING_LEVEL: u8 = 6;
+// Styles are resolved through `w:basedOn`; real documents rarely nest deeper.
+const MAX_STYLE_DEPTH: usize = 8;
+const QUOTE_STYLES: [&str; 4] = ["quote", "intensequote", "citation", "citationintense"];
+
+#[derive(Debug, Default)]
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Node>,
+}
+
+#[derive(Debug)]
+enum Node {
+    Element(Element),
+    Text(String),
+}
+
+impl Element {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|child| match child {
+            Node::Element(element) => Some(element),
+            Node::Text(_) => None,
+        })
+    }
+
+    fn child(&self, name: &str) -> Option<&Element> {
+        self.elements().find(|element| element.name == name)
+    }
+
+    /// First element named `name` below this one, in document order.
+    fn find(&self, name: &str) -> Option<&Element> {
+        self.elements().find_map(|element| {
+            (element.name == name)
+                .then_some(element)
+                .or_else(|| element.find(name))
+        })
+    }
+
+    fn find_all<'a>(&'a self, name: &str, found: &mut Vec<&'a Element>) {
+        for element in self.elements() {
+            if element.name == name {
+                found.push(element);
+            } else {
+                element.find_all(name, found);
+            }
+        }
+    }
+
+    fn text(&self) -> String {
+        let mut text = String::new();
+        for child in &self.children {
+            match child {
+                Node::Text(value) => text.push_str(value),
+                Node::Element(element) => text.push_str(&element.text()),
+            }
+        }
+        text
+    }
+
+    /// `w:val` of the child `name`, for the many `<w:x w:val="…"/>` properties.
+    fn child_val(&self, name: &str) -> Option<&str> {
+        self.child(name).and_then(|child| child.attr("w:val"))
+    }
+}
+
+/// Parses an XML part into a synthetic root element holding its top-level nodes.
+///
+/// Parsing stops at the first malformed construct and keeps what was read so far.
+fn parse_xml(xml: &str) -> Element {
+    let mut stack = vec![Element::default()];
+    let mut text = String::new();
+    let _ = read_nodes(&mut Reader::from_str(xml), &mut stack, &mut text);
+
+    // Close whatever a truncated part left open.
+    flush_text(&mut stack, &mut text);
+    while stack.len() > 1 {
+        let element = stack.pop().unwrap_or_default();
+        if let Some(parent) = stack.last_mut() {
+            parent.children.push(Node::Element(element));
+        }
+    }
+    stack.pop().unwrap_or_default()
+}
+
+fn read_nodes(
+    reader: &mut Reader<&[u8]>,
+    stack: &mut Vec<Element>,
+    text: &mut String,
+) -> std::result::Result<(), quick_xml::Error> {
+    loop {
+        match reader.read_event()? {
+            Event::Text(value) => text.push_str(&value.decode()?),
+            Event::CData(value) => text.push_str(&value.decode()?),
+            Event::GeneralRef(entity) => push_entity(text, &entity)?,
+            Event::Start(tag) => {
+                flush_text(stack, text);
+                stack.push(element_from_tag(&tag));
+            }
+            Event::Empty(tag) => {
+                flush_text(stack, text);
+                if let Some(parent) = stack.last_mut() {
+                    parent.children.push(Node::Element(element_from_tag(&tag)));
+                }
+            }
+            Event::End(_) => {
+                flush_text(stack, text);
+                if stack.len() > 1 {
+                    let element = stack.pop().unwrap_or_default();
+                    if let Some(parent) = stack.last_mut() {
+                        parent.children.push(Node::Element(element));
+                    }
+                }
+            }
+            Event::Eof => return Ok(()),
+            _ => {}
+        }
+    }
+}
+
+fn flush_text(stack: &mut [Element], text: &mut String) {
+    if text.is_empty() {
+        return;
+    }
+    if let Some(parent) = stack.last_mut() {
+        parent.children.push(Node::Text(std::mem::take(text)));
+    }
+}
+
+fn element_from_tag(tag: &BytesStart<'_>) -> Element {
+    let attributes = tag
+        .attributes()
+        .flatten()
+        .map(|attribute| {
+            let key = String::from_utf8_lossy(attribute.key.as_ref()).into_owned();
+            let value = attribute
+                .unescape_value()
+                .map(|value| value.into_owned())
+                .unwrap_or_else(|_| String::from_utf8_lossy(&attribute.value).into_owned());
+            (key, value)
+        })
+        .collect();
+    Element {
+        name: String::from_utf8_lossy(tag.name().as_ref()).into_owned(),
+        attributes,
+        children: Vec::new(),
+    }
+}
+
+#[derive(Debug, Default)]
+struct ParagraphStyleInfo {
+    name: String,
+    based_on: Option<String>,
+    outline_level: Option<u8>,
+    numbering: Option<(String, u8)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Fragment {
+    text: String,
+    bold: bool,
+    italic: bool,
+    link: Option<String>,
+    /// Markdown emitted as is, e.g. an image.
+    raw: bool,
+}
+
+enum ParagraphKind {
+    Body,
+    Heading(u8),
+    Quote,
+    ListItem { level: u8, ordered: bool },
+}
+
+/// Converts the parts of one DOCX file to a Markdown body.
+struct WordConverter<'a> {
+    entries: &'a HashMap<String, Vec<u8>>,
+    styles: HashMap<String, ParagraphStyleInfo>,
+    /// `(numId, ilvl)` → ordered list.
+    ordered_levels: HashMap<(String, u8), bool>,
+    relationships: HashMap<String, (String, bool)>,
+    attachments: Vec<ImportedAttachment>,
+    attachment_names: HashMap<String, String>,
+}
+
+impl<'a> WordConverter<'a> {
+    fn new(entries: &'a HashMap<String, Vec<u8>>) -> Self {
+        let part = |name: &str| {
+            entries
+                .get(name)
+                .map(|bytes| parse_xml(&String::from_utf8_lossy(bytes)))
+        };
+        Self {
+            entries,
+            styles: part("word/styles.xml")
+                .map(|styles| read_paragraph_styles(&styles))
+                .unwrap_or_default(),
+            ordered_levels: part("word/numbering.xml")
+                .map(|numbering| read_ordered_levels(&numbering))
+                .unwrap_or_default(),
+            relationships: part("word/_rels/document.xml.rels")
+                .map(|rels| read_relationships(&rels))
+                .unwrap_or_default(),
+            attachments: Vec::new(),
+            attachment_names: HashMap::new(),
+        }
+    }
+
+    fn convert(&mut self, document: &Element) -> String {
+        let Some(body) = document.find("w:body") else {
+            return String::new();
+        };
+        let mut blocks = Vec::new();
+        self.collect_blocks(body, &mut blocks);
+
+        let mut markdown = String::new();
+        let mut previous_list = false;
+        for (block, is_list) in blocks {
+            if !markdown.is_empty() {
+                markdown.push_str(if previous_list && is_list {
+                    "\n"
+                } else {
+                    "\n\n"
+                });
+            }
+            markdown.push_str(&block);
+            previous_list = is_list;
+        }
+        markdown
+    }
+
+    fn collect_blocks(&mut self, parent: &Element, blocks: &mut Vec<(String, bool)>) {
+        for element in parent.elements() {
+            match element.name.as_str() {
+                "w:p" => {
+                    if let Some(block) = self.paragraph(element) {
+                        blocks.push(block);
+                    }
+                }
+                "w:tbl" => {
+                    if let Some(table) = self.table(element) {
+                        blocks.push((table, false));
+                    }
+                }
+                "w:sectPr" | "w:del" | "w:moveFrom" => {}
+                _ => self.collect_blocks(element, blocks),
+            }
+        }
+    }
+
+    fn paragraph(&mut self, paragraph: &Element) -> Option<(String, bool)> {
+        let kind = self.paragraph_kind(paragraph);
+        let mut fragments = Vec::new();
+        self.collect_inline(paragraph, None, &mut fragments);
+        let text = render_fragments(&fragments);
+        let text = text.trim();
+        if text.is_empty() {
+            return None;
+        }
+
+        let block = match kind {
+            ParagraphKind::Heading(level) => {
+                format!("{} {}", "#".repeat(level as usize), text.replace('\n', " "))
+            }
+            ParagraphKind::ListItem { level, ordered } => {
+                let marker = if ordered { "1." } else { "-" };
+                let indent = "    ".repeat(level as usize);
+                return Some((
+                    format!("{indent}{marker} {}", text.replace('\n', " ")),
+                    true,
+                ));
+            }
+            ParagraphKind::Quote => text
+                .lines()
+                .map(|line| format!("> {line}"))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            ParagraphKind::Body => {
+                let escaped = if text.starts_with(['#', '>', '-', '+', '=']) {
+                    format!("\\{text}")
+                } else {
+                    text.to_string()
+                };
+                escaped.replace('\n', "\\\n")
+            }
+        };
+        Some((block, false))
+    }
+
+    fn paragraph_kind(&self, paragraph: &Element) -> ParagraphKind {
+        let properties = paragraph.child("w:pPr");
+        let style_id = properties.and_then(|properties| properties.child_val("w:pStyle"));
+
+        if let Some(level) = style_id.and_then(|style_id| self.heading_level(style_id)) {
+            return ParagraphKind::Heading(level);
+        }
+        if let Some(level) = properties
+            .and_then(|properties| properties.child_val("w:outlineLvl"))
+            .and_then(outline_heading_level)
+        {
+            return ParagraphKind::Heading(level);
+        }
+
+        let numbering = properties
+            .and_then(|properties| properties.child("w:numPr"))
+            .map(|numbering| {
+                (
+                    numbering.child_val("w:numId").unwrap_or("0").to_string(),
+                    numbering
+                        .child_val("w:ilvl")
+                        .and_then(|level| level.parse().ok())
+                        .unwrap_or(0),
+                )
+            })
+            .or_else(|| style_id.and_then(|style_id| self.style_numbering(style_id)));
+        if let Some((num_id, level)) = numbering.filter(|(num_id, _)| num_id != "0") {
+            let ordered = self
+                .ordered_levels
+                .get(&(num_id, level))
+                .copied()
+                .unwrap_or(false);
+            return ParagraphKind::ListItem { level, ordered };
+        }
+
+        let is_quote = |name: &str| {
+            QUOTE_STYLES
+                .iter()
+                .any(|quote| name.eq_ignore_ascii_case(quote))
+        };
+        let quote = style_id.is_some_and(|style_id| {
+            is_quote(style_id)
+                || self
+                    .style_chain(style_id)
+                    .any(|(id, style)| is_quote(id) || is_quote(&compact_style_name(&style.name)))
+        });
+        if quote {
+            ParagraphKind::Quote
+        } else {
+            ParagraphKind::Body
+        }
+    }
+
+    /// The style and the styles it is based on, nearest first.
+    fn style_chain<'s>(
+        &'s self,
+        style_id: &'s str,
+    ) -> impl Iterator<Item = (&'s str, &'s ParagraphStyleInfo)> {
+        let mut next = Some(style_id);
+        std::iter::from_fn(move || {
+            let id = next?;
+            let style = self.styles.get(id)?;
+            next = style.based_on.as_deref();
+            Some((id, style))
+        })
+        .take(MAX_STYLE_DEPTH)
+    }
+
+    /// Matches the style id or name against the heading styles the exporter
+    /// knows, including localized ones, then falls back to the outline level.
+    fn heading_level(&self, style_id: &str) -> Option<u8> {
+        let matches_heading = |candidate: &str| {
+            let candidate = compact_style_name(candidate);
+            if candidate.eq_ignore_ascii_case("title") || candidate.eq_ignore_ascii_case("titre") {
+                return Some(1);
+            }
+            (1..=MAX_HEADING_LEVEL).find(|level| {
+                heading_style_candidates(*level)
+                    .iter()
+                    .any(|known| known.eq_ignore_ascii_case(&candidate))
+            })
+        };
+
+        if let Some(level) = matches_heading(style_id) {
+            return Some(level);
+        }
+        for (id, style) in self.style_chain(style_id) {
+            if let Some(level) = matches_heading(id).or_else(|| matches_heading(&style.name)) {
+                return Some(level);
+            }
+            if let Some(level) = style.outline_level {
+                return Some(level);
+            }
+        }
+        None
+    }
+
+    fn style_numbering(&self, style_id: &str) -> Option<(String, u8)> {
+        self.style_chain(style_id)
+            .find_map(|(_, style)| style.numbering.clone())
+    }
+
+    fn collect_inline(
+        &mut self,
+        parent: &Element,
+        link: Option<&str>,
+        fragments: &mut Vec<Fragment>,
+    ) {
+        for element in parent.elements() {
+            match element.name.as_str() {
+                "w:r" => self.run(element, link, fragments),
+                "w:hyperlink" => {
+                    let target = element
+                        .attr("r:id")
+                        .and_then(|id| self.relationships.get(id))
+                        .map(|(target, _)| target.clone());
+                    self.collect_inline(element, target.as_deref().or(link), fragments);
+                }
+                "w:pPr" | "w:del" | "w:moveFrom" | "w:bookmarkStart" | "w:bookmarkEnd" => {}
+                "mc:AlternateContent" => {
+                    if let Some(choice) = element.child("mc:Choice") {
+                        self.collect_inline(choice, link, fragments);
+                    }
+                }
+                _ => self.collect_inline(element, link, fragments),
+            }
+        }
+    }
+
+    fn run(&mut self, run: &Element, link: Option<&str>, fragments: &mut Vec<Fragment>) {
+        let properties = run.child("w:rPr");
+        let character_style = properties
+            .and_then(|properties| properties.child_val("w:rStyle"))
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let bold = properties.is_some_and(|properties| toggle_property(properties, "w:b"))
+            || character_style == "strong";
+        let italic = properties.is_some_and(|properties| toggle_property(properties, "w:i"))
+            || character_style == "emphasis";
+
+        let mut push = |text: String, raw: bool| {
+            fragments.push(Fragment {
+                text,
+                bold: bold && !raw,
+                italic: italic && !raw,
+                link: link.map(str::to_string),
+                raw,
+            });
+        };
+        for element in run.elements() {
+            match element.name.as_str() {
+                "w:t" => push(element.text(), false),
+                "w:tab" | "w:ptab" => push(" ".to_string(), false),
+                "w:br" | "w:cr" if element.attr("w:type") != Some("page") => {
+                    push("\n".to_string(), false)
+                }
+                "w:noBreakHyphen" => push("-".to_string(), false),
+                "w:drawing" | "w:pict" | "mc:AlternateContent" | "w:object" => {
+                    if let Some(image) = self.image(element) {
+                        push(image, true);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Markdown for the first picture below `element`, extracting its bytes as
+    /// an attachment.
+    fn image(&mut self, element: &Element) -> Option<String> {
+        let relationship_id = element
+            .find("a:blip")
+            .and_then(|blip| blip.attr("r:embed").or_else(|| blip.attr("r:link")))
+            .or_else(|| {
+                element
+                    .find("v:imagedata")
+                    .and_then(|image| image.attr("r:id"))
+            })?;
+        let alt = element
+            .find("wp:docPr")
+            .and_then(|properties| {
+                properties
+                    .attr("descr")
+                    .or_else(|| properties.attr("title"))
+            })
+            .unwrap_or_default()
+            .replace(['[', ']', '\n'], " ");
+        let (target, external) = self.relationships.get(relationship_id)?.clone();
+        if external {
+            return Some(format!("![{}]({})", alt.trim(), markdown_url(&target)));
+        }
+
+        let part = resolve_part_path("word", &target);
+        if let Some(name) = self.attachment_names.get(&part) {
+            return Some(format!("![{}]({ATTACHMENT_LINK_PREFIX}{name})", alt.trim()));
+        }
+        let bytes = self.entries.get(&part)?.clone();
+        let mut name = part.rsplit('/').next().unwrap_or(&part).to_string();
+        if self
+            .attachments
+            .iter()
+            .any(|attachment| attachment.file_name == name)
+        {
+            name = format!("{}-{name}", self.attachments.len() + 1);
+        }
+        self.attachment_names.insert(part, name.clone());
+        self.attachments.push(ImportedAttachment {
+            file_name: name.clone(),
+            bytes,
+        });
+        Some(format!("![{}]({ATTACHMENT_LINK_PREFIX}{name})", alt.trim()))
+    }
+
+    fn table(&mut self, table: &Element) -> Option<String> {
+        let mut rows = Vec::new();
+        for row in table.elements().filter(|element| element.name == "w:tr") {
+            let mut cells = Vec::new();
+            for cell in row.elements().filter(|element| element.name == "w:tc") {
+                let mut paragraphs = Vec::new();
+                cell.find_all("w:p", &mut paragraphs);
+                let text = paragraphs
+                    .into_iter()
+                    .map(|paragraph| {
+                        let mut fragments = Vec::new();
+                        self.collect_inline(paragraph, None, &mut fragments);
+                        render_fragments(&fragments).trim().to_string()
+                    })
+                    .filter(|text| !text.is_empty())
+                    .collect::<Vec<_>>()
+                    .join("<br>")
+                    .replace('\n', "<br>")
+                    .replace('|', "\\|");
+                let span = cell
+                    .child("w:tcPr")
+                    .and_then(|properties| properties.child_val("w:gridSpan"))
+                    .and_then(|span| span.parse::<usize>().ok())
+                    .unwrap_or(1)
+                    .max(1);
+                cells.push(text);
+                cells.extend(std::iter::repeat_n(String::new(), span - 1));
+            }
+            rows.push(cells);
+        }
+
+        let columns = rows.iter().map(Vec::len).max().filter(|count| *count > 0)?;
+        let render_row = |cells: &[String]| {
+            let mut padded = cells.to_vec();
+            padded.resize(columns, String::new());
+            format!("| {} |", padded.join(" | "))
+        };
+        let mut lines = vec![
+            render_row(&rows[0]),
+            format!("|{}", " --- |".repeat(columns)),
+        ];
+        lines.extend(rows[1..].iter().map(|row| render_row(row)));
+        Some(lines.join("\n"))
+    }
+}
+
+/// `<w:b/>` and friends are on unless their value turns them off.
+fn toggle_property(properties: &Element, name: &str) -> bool {
+    properties.child(name).is_some_and(|property| {
+        !matches!(property.attr("w:val"), Some("0" | "false" | "off" | "none"))
+    })
+}
+
+fn outline_heading_level(value: &str) -> Option<u8> {
+    value
+        .parse::<u8>()
+        .ok()
+        .filter(|level| *level < MAX_HEADING_LEVEL)
+        .map(|level| level + 1)
+}
+
+fn compact_style_name(name: &str) -> String {
+    name.chars().filter(|ch| !ch.is_whitespace()).collect()
+}
+
+fn read_paragraph_styles(styles: &Element) -> HashMap<String, ParagraphStyleInfo> {
+    let mut found = Vec::new();
+    styles.find_all("w:style", &mut found);
+    found
+        .into_iter()
+        .filter(|style| style.attr("w:type") == Some("paragraph"))
+        .filter_map(|style| {
+            let id = style.attr("w:styleId")?.to_string();
+            let properties = style.child("w:pPr");
+            let info = ParagraphStyleInfo {
+                name: style.child_val("w:name").unwrap_or_default().to_string(),
+                based_on: style.child_val("w:basedOn").map(str::to_string),
+                outline_level: properties
+                    .and_then(|properties| properties.child_val("w:outlineLvl"))
+                    .and_then(outline_heading_level),
+                numbering: properties
+                    .and_then(|properties| properties.child("w:numPr"))
+                    .and_then(|numbering| {
+                        let num_id = numbering.child_val("w:numId")?.to_string();
+                        let level = numbering
+                            .child_val("w:ilvl")
+                            .and_then(|level| level.parse().ok())
+                            .unwrap_or(0);
+                        Some((num_id, level))
+                    }),
+            };
+            Some((id, info))
+        })
+        .collect()
+}
+
+fn read_ordered_levels(numbering: &Element) -> HashMap<(String, u8), bool> {
+    let mut abstract_formats = HashMap::new();
+    let mut nums = Vec::new();
+    for element in numbering
+        .find("w:numbering")
+        .unwrap_or(numbering)
+        .elements()
+    {
+        match element.name.as_str() {
+            "w:abstractNum" => {
+                let Some(id) = element.attr("w:abstractNumId") else {
+                    continue;
+                };
+                for level in element.elements().filter(|child| child.name == "w:lvl") {
+                    let Some(index) = level.attr("w:ilvl").and_then(|index| index.parse().ok())
+                    else {
+                        continue;
+                    };
+                    let ordered = !matches!(level.child_val("w:numFmt"), Some("bullet" | "none"));
+                    abstract_formats.insert((id.to_string(), index), ordered);
+                }
+            }
+            "w:num" => {
+                if let (Some(num_id), Some(abstract_id)) = (
+                    element.attr("w:numId"),
+                    element.child_val("w:abstractNumId"),
+                ) {
+                    nums.push((num_id.to_string(), abstract_id.to_string()));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut ordered_levels = HashMap::new();
+    for (num_id, abstract_id) in nums {
+        for ((id, level), ordered) in &abstract_formats {
+            if *id == abstract_id {
+                ordered_levels.insert((num_id.clone(), *level), *ordered);
+            }
+        }
+    }
+    ordered_levels
+}
+
+/// `Id` → `(Target, external)` of a relationships part.
+fn read_relationships(rels: &Element) -> HashMap<String, (String, bool)> {
+    let mut found = Vec::new();
+    rels.find_all("Relationship", &mut found);
+    found
+        .into_iter()
+        .filter_map(|relationship| {
+            let id = relationship.attr("Id")?.to_string();
+            let target = relationship.attr("Target")?.to_string();
+            let external = relationship.attr("TargetMode") == Some("External");
+            Some((id, (target, external)))
+        })
+        .collect()
+}
+
+/// Resolves a relationship target against the folder of the part declaring it.
+fn resolve_part_path(base_dir: &str, target: &str) -> String {
+    if let Some(absolute) = target.strip_prefix('/') {
+        return absolute.to_string();
+    }
+    let mut segments = base_dir
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>();
+    for segment in target.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            other => segments.push(other),
+        }
+    }
+    segments.join("/")
+}
+
+fn markdown_url(url: &str) -> String {
+    if url.contains([' ', '(', ')']) {
+        format!("<{url}>")
+    } else {
+        url.to_string()
+    }
+}
+
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        if matches!(ch, '\\' | '*' | '_' | '`' | '[' | ']') {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
+    }
+    escaped
+}
+
+/// Wraps `text` in an emphasis marker, keeping surrounding spaces outside it.
+fn emphasize(text: &str, marker: &str) -> String {
+    let trimmed = text.trim();
+    if marker.is_empty() || trimmed.is_empty() {
+        return text.to_string();
+    }
+    let leading = &text[..text.len() - text.trim_start().len()];
+    let trailing = &text[text.trim_end().len()..];
+    format!("{leading}{marker}{trimmed}{marker}{trailing}")
+}
+
+fn render_fragments(fragments: &[Fragment]) -> String {
+    let mut output = String::new();
+    let mut index = 0;
+    while index < fragments.len() {
+        let link = &fragments[index].link;
+        let end = fragments[index..]
+            .iter()
+            .position(|fragment| fragment.link != *link)
+            .map_or(fragments.len(), |offset| index + offset);
+        let inner = render_styled(&fragments[index..end]);
+        match link {
+            Some(url) if !inner.trim().is_empty() => {
+                let leading = &inner[..inner.len() - inner.trim_start().len()];
+                let trailing = &inner[inner.trim_end().len()..];
+                output.push_str(&format!(
+                    "{leading}[{}]({}){trailing}",
+                    inner.trim(),
+                    markdown_url(url)
+                ));
+            }
+            _ => output.push_str(&inner),
+        }
+        index = end;
+    }
+    output
+}
+
+fn render_styled(fragments: &[Fragment]) -> String {
+    let mut output = String::new();
+    let mut pending = String::new();
+    let mut pending_style = (false, false);
+
+    let flush = |pending: &mut String, style: (bool, bool), output: &mut String| {
+        let marker = match style {
+            (true, true) => "***",
+            (true, false) => "**",
+            (false, true) => "*",
+            (false, false) => "",
+        };
+        output.push_str(&emphasize(&escape_markdown(pending), marker));
+        pending.clear();
+    };
+    for fragment in fragments {
+        if fragment.raw {
+            flush(&mut pending, pending_style, &mut output);
+            output.push_str(&fragment.text);
+            continue;
+        }
+        let style = (fragment.bold, fragment.italic);
+        if style != pending_style {
+            flush(&mut pending, pending_style, &mut output);
+            pending_style = style;
+        }
+        pending.push_str(&fragment.text);
+    }
+    flush(&mut pending, pending_style, &mut output);
+    output
+}
+
+/// Frontmatter properties from the core and custom document properties.
+fn read_properties(entries: &HashMap<String, Vec<u8>>) -> Vec<(String, PropertyValue)> {
+    let mut properties = Vec::new();
+    if let Some(core) = entries.get("docProps/core.xml") {
+        let core = parse_xml(&String::from_utf8_lossy(core));
+        let value = |name: &str| {
+            core.find(name)
+                .map(|element| element.text().trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        for (key, name) in [
+            ("title", "dc:title"),
+            ("author", "dc:creator"),
+            ("subject", "dc:subject"),
+            ("description", "dc:description"),
+        ] {
+            if let Some(text) = value(name) {
+                properties.push((key.to_string(), PropertyValue::Text(text)));
+            }
+        }
+        if let Some(keywords) = value("cp:keywords") {
+            let tags = keywords
+                .split([',', ';'])
+                .map(|tag| tag.trim().to_string())
+                .filter(|tag| !tag.is_empty())
+                .collect::<Vec<_>>();
+            if !tags.is_empty() {
+                properties.push(("tags".to_string(), PropertyValue::List(tags)));
+            }
+        }
+        for (key, name) in [
+            ("created", "dcterms:created"),
+            ("updated", "dcterms:modified"),
+        ] {
+            if let Some(date) = value(name).and_then(|value| normalize_date(&value)) {
+                properties.push((key.to_string(), PropertyValue::Date(date)));
+            }
+        }
+    }
+
+    if let Some(custom) = entries.get("docProps/custom.xml") {
+        let custom = parse_xml(&String::from_utf8_lossy(custom));
+        let mut found = Vec::new();
+        custom.find_all("property", &mut found);
+        for property in found {
+            let (Some(name), Some(value)) = (property.attr("name"), property.elements().next())
+            else {
+                continue;
+            };
+            let text = value.text().trim().to_string();
+            let value = match value.name.as_str() {
+                "vt:bool" => PropertyValue::Checkbox(matches!(text.as_str(), "true" | "1")),
+                "vt:i4" | "vt:i8" | "vt:r8" | "vt:int" => match text.parse() {
+                    Ok(number) => PropertyValue::Number(number),
+                    Err(_) => PropertyValue::Text(text),
+                },
+                "vt:filetime" => match normalize_date(&text) {
+                    Some(date) => PropertyValue::Date(date),
+                    None => PropertyValue::Text(text),
+                },
+                _ => PropertyValue::Text(text),
+            };
+            properties.push((name.to_string(), value));
+        }
+    }
+    properties
+}
+
+/// Converts the zip entries of a DOCX file to a note titled `title`.
+fn convert_docx(
+    entries: Vec<(String, Vec<u8>)>,
+    title: &str,
+    folder: &str,
+) -> std::result::Result<ImportedNote, String> {
+    let entries = entries.into_iter().collect::<HashMap<_, _>>();
+    let Some(document) = entries.get("word/document.xml") else {
+        return Err("Not a Word document.".to_string());
+    };
+    let document = parse_xml(&String::from_utf8_lossy(document));
+
+    let mut converter = WordConverter::new(&entries);
+    let body = converter.convert(&document);
+    Ok(ImportedNote {
+        folder: folder.to_string(),
+        title: title.to_string(),
+        body,
+        properties: read_properties(&entries),
+        attachments: converter.attachments,
+        link_keys: vec![title.to_string()],
+    })
+}
+
+fn read_docx_file(path: &Path, folder: &str, batch: &mut ImportBatch) -> Result<()> {
+    let source = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let title = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| "Untitled".to_string());
+    let converted = read_zip_entries(File::open(path)?)
+        .map_err(|_| "Not a valid .docx archive.".to_string())
+        .and_then(|entries| convert_docx(entries, &title, folder));
+    match converted {
+        Ok(note) => batch.notes.push(note),
+        Err(reason) => batch.skipped.push(ImportSkip { source, reason }),
+    }
+    Ok(())
+}
+
+pub(super) fn is_docx_path(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.eq_ignore_ascii_case(DOCX_EXTENSION))
+        .unwrap_or(false)
+}
+
+/// Reads one `.docx` file, or every `.docx` file of a folder.
+pub(super) fn read_docx_export(source: &Path) -> Result<ImportBatch> {
+    let mut batch = ImportBatch::default();
+    if source.is_file() {
+        read_docx_file(source, "", &mut batch)?;
+        return Ok(batch);
+    }
+    let mut files = fs::read_dir(source)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| is_docx_path(path))
+        // Word keeps `~$name.docx` lock files next to open documents.
+        .filter(|path| {
+            !path
+                .file_name()
+                .map(|name| name.to_string_lossy().starts_with("~$"))
+                .unwrap_or(true)
+        })
+        .collect::<Vec<_>>();
+    if files.is_empty() {
+        return Err(AppError::InvalidOperation(
+            "No .docx file found in the selected folder.".to_string(),
+        ));
+    }
+    files.sort();
+    for path in files {
+        read_docx_file(&path, "", &mut batch)?;
+    }
+    Ok(batch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const W: &str = r#"xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main""#;
+
+    fn entry(name: &str, xml: &str) -> (String, Vec<u8>) {
+        (name.to_string(), xml.as_bytes().to_vec())
+    }
+
+    fn paragraph(style: Option<&str>, runs: &str) -> String {
+        let properties = style
+            .map(|style| format!(r#"<w:pPr><w:pStyle w:val="{style}"/></w:pPr>"#))
+            .unwrap_or_default();
+        format!("<w:p>{properties}{runs}</w:p>")
+    }
+
+    fn list_item(num_id: u32, level: u32, text: &str) -> String {
+        format!(
+            r#"<w:p><w:pPr><w:numPr><w:ilvl w:val="{level}"/><w:numId w:val="{num_id}"/></w:numPr></w:pPr><w:r><w:t>{text}</w:t></w:r></w:p>"#
+        )
+    }
+
+    #[test]
+    fn converts_structure_formatting_links_and_images() {
+        let body = [
+            paragraph(Some("Titre1"), "<w:r><w:t>Rapport</w:t></w:r>"),
+            paragraph(Some("Custom2"), "<w:r><w:t>Section</w:t></w:r>"),
+            paragraph(
+                None,
+                concat!(
+                    r#"<w:r><w:t xml:space="preserve">Plain </w:t></w:r>"#,
+                    r#"<w:r><w:rPr><w:b/></w:rPr><w:t xml:space="preserve">bold </w:t></w:r>"#,
+                    r#"<w:r><w:rPr><w:i/><w:b w:val="0"/></w:rPr><w:t>soft</w:t></w:r>"#,
+                    r#"<w:r><w:t xml:space="preserve"> &amp; 2*3, see </w:t></w:r>"#,
+                    r#"<w:hyperlink r:id="rIdLink"><w:r><w:t>the site</w:t></w:r></w:hyperlink>"#,
+                    "<w:r><w:br/><w:t>next line</w:t></w:r>"
+                ),
+            ),
+            list_item(1, 0, "first"),
+            list_item(1, 1, "nested"),
+            list_item(2, 0, "step"),
+            paragraph(Some("Quote"), "<w:r><w:t>Quoted</w:t></w:r>"),
+            paragraph(
+                None,
+                concat!(
+                    r#"<w:r><w:drawing><wp:inline><wp:docPr id="1" name="Picture 1" descr="A chart"/>"#,
+                    r#"<a:graphic><a:graphicData><pic:pic><pic:blipFill><a:blip r:embed="rIdImage"/>"#,
+                    "</pic:blipFill></pic:pic></a:graphicData></a:graphic></wp:inline></w:drawing></w:r>"
+                ),
+            ),
+            concat!(
+                "<w:tbl><w:tr><w:tc><w:p><w:r><w:t>Name</w:t></w:r></w:p></w:tc>",
+                "<w:tc><w:p><w:r><w:t>Value</w:t></w:r></w:p></w:tc></w:tr>",
+                r#"<w:tr><w:tc><w:tcPr><w:gridSpan w:val="2"/></w:tcPr><w:p><w:r><w:t>a|b</w:t></w:r></w:p>"#,
+                "<w:p><w:r><w:t>c</w:t></w:r></w:p></w:tc></w:tr></w:tbl>"
+            )
+            .to_string(),
+            paragraph(None, "<w:r><w:t>  </w:t></w:r>"),
+        ]
+        .concat();
+        let entries = vec![
+            entry(
+                "word/document.xml",
+                &format!(r#"<?xml version="1.0"?><w:document {W}><w:body>{body}<w:sectPr/></w:body></w:document>"#),
+            ),
+            entry(
+                "word/styles.xml",
+                &format!(
+                    concat!(
+                        r#"<w:styles {}><w:style w:type="paragraph" w:styleId="Custom2">"#,
+                        r#"<w:name w:val="My Section"/><w:basedOn w:val="Titre2"/></w:style>"#,
+                        r#"<w:style w:type="paragraph" w:styleId="Titre2"><w:name w:val="heading 2"/></w:style>"#,
+                        "</w:styles>"
+                    ),
+                    W
+                ),
+            ),
+            entry(
+                "word/numbering.xml",
+                &format!(
+                    concat!(
+                        r#"<w:numbering {}><w:abstractNum w:abstractNumId="10">"#,
+                        r#"<w:lvl w:ilvl="0"><w:numFmt w:val="bullet"/></w:lvl>"#,
+                        r#"<w:lvl w:ilvl="1"><w:numFmt w:val="bullet"/></w:lvl></w:abstractNum>"#,
+                        r#"<w:abstractNum w:abstractNumId="20"><w:lvl w:ilvl="0"><w:numFmt w:val="decimal"/></w:lvl></w:abstractNum>"#,
+                        r#"<w:num w:numId="1"><w:abstractNumId w:val="10"/></w:num>"#,
+                        r#"<w:num w:numId="2"><w:abstractNumId w:val="20"/></w:num></w:numbering>"#
+                    ),
+                    W
+                ),
+            ),
+            entry(
+                "word/_rels/document.xml.rels",
+                concat!(
+                    r#"<Relationships><Relationship Id="rIdLink" Type="hyperlink" "#,
+                    r#"Target="https://example.com/a?b=1&amp;c=2" TargetMode="External"/>"#,
+                    r#"<Relationship Id="rIdImage" Type="image" Target="media/image1.png"/></Relationships>"#
+                ),
+            ),
+            ("word/media/image1.png".to_string(), vec![1, 2, 3]),
+            entry(
+                "docProps/core.xml",
+                concat!(
+                    "<cp:coreProperties><dc:title>Quarterly report</dc:title><dc:creator>Ada</dc:creator>",
+                    "<cp:keywords>finance; q3</cp:keywords>",
+                    r#"<dcterms:created xsi:type="dcterms:W3CDTF">2024-07-01T09:00:00Z</dcterms:created>"#,
+                    "</cp:coreProperties>"
+                ),
+            ),
+            entry(
+                "docProps/custom.xml",
+                r#"<Properties><property fmtid="x" pid="2" name="Reviewed"><vt:bool>true</vt:bool></property></Properties>"#,
+            ),
+        ];
+
+        let note = convert_docx(entries, "Report", "").expect("convert");
+        assert_eq!(
+            note.body,
+            concat!(
+                "# Rapport\n\n",
+                "## Section\n\n",
+                "Plain **bold** *soft* & 2\\*3, see [the site](https://example.com/a?b=1&c=2)\\\nnext line\n\n",
+                "- first\n    - nested\n",
+                "1. step\n\n",
+                "> Quoted\n\n",
+                "![A chart](attachment:image1.png)\n\n",
+                "| Name | Value |\n| --- | --- |\n| a\\|b<br>c |  |"
+            )
+        );
+        assert_eq!(
+            note.attachments,
+            vec![ImportedAttachment {
+                file_name: "image1.png".to_string(),
+                bytes: vec![1, 2, 3],
+            }]
+        );
+        assert_eq!(
+            note.properties,
+            vec![
+                (
+                    "title".to_string(),
+                    PropertyValue::Text("Quarterly report".to_string())
+                ),
+                ("author".to_string(), PropertyValue::Text("Ada".to_string())),
+                (
+                    "tags".to_string(),
+                    PropertyValue::List(vec!["finance".to_string(), "q3".to_string()])
+                ),
+                (
+                    "created".to_string(),
+                    PropertyValue::Date("2024-07-01".to_string())
+                ),
+                ("Reviewed".to_string(), PropertyValue::Checkbox(true)),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_archives_without_a_document() {
+        let result = convert_docx(vec![entry("word/styles.xml", "<w:styles/>")], "x", "");
+        assert_eq!(result, Err("Not a Word document.".to_string()));
+    }
+
+    #[test]
+    fn keeps_what_precedes_a_malformed_part() {
+        let root = parse_xml(r#"<w:p><w:t a="1 &amp; 2">A &#x26; B</w:t><w:t>C</w:b></w:p>"#);
+        let paragraph = root.child("w:p").unwrap();
+        assert_eq!(paragraph.child("w:t").unwrap().attr("a"), Some("1 & 2"));
+        assert_eq!(paragraph.text(), "A & BC");
+    }
+}
@@ -114,6 +114,40 @@ fn schema_shape_needs_reset(conn: &Connection, current_version: i64) -> bool {
     !table_has_column(conn, "second_brain_sessions", "alter_id")
 }
 
+/// Adds message branching columns to Second Brain tables created before branching.
+///
+/// Existing conversations are linear, so each message is chained to the previous one
+/// of its session; history is kept instead of resetting the schema.
+fn ensure_second_brain_branch_columns(conn: &Connection) -> Result<()> {
+    if !table_has_column(conn, "second_brain_sessions", "active_message_id") {
+        conn.execute(
+            "ALTER TABLE second_brain_sessions ADD COLUMN active_message_id TEXT NOT NULL DEFAULT ''",
+            [],
+        )?;
+    }
+    if !table_has_column(conn, "second_brain_messages", "parent_id") {
+        conn.execute_batch(
+            r#"
+      ALTER TABLE second_brain_messages ADD COLUMN parent_id TEXT NOT NULL DEFAULT '';
+      UPDATE second_brain_messages SET parent_id = COALESCE((
+        SELECT previous.id FROM second_brain_messages previous
+        WHERE previous.session_id = second_brain_messages.session_id
+          AND (previous.created_at_ms < second_brain_messages.created_at_ms
+            OR (previous.created_at_ms = second_brain_messages.created_at_ms
+              AND previous.rowid < second_brain_messages.rowid))
+        ORDER BY previous.created_at_ms DESC, previous.rowid DESC
+        LIMIT 1
+      ), '');
+    "#,
+        )?;
+    }
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_second_brain_messages_parent ON second_brain_messages(session_id, parent_id)",
+        [],
+    )?;
+    Ok(())
+}
+
 pub(crate) fn ensure_index_schema(conn: &Connection) -> Result<()> {
     conn.execute_batch(
         r#"
@@ -154,6 +188,9 @@ pub(crate) fn ensure_index_schema(conn: &Connection) -> Result<()> {
       DROP TABLE IF EXISTS note_links;
       DROP TABLE IF EXISTS note_properties;
       DROP TABLE IF EXISTS semantic_edges;
+      DROP TABLE IF EXISTS conversation_search_fts;
+      DROP TABLE IF EXISTS conversation_search_items;
+      DROP TABLE IF EXISTS second_brain_history_summaries;
       DROP TABLE IF EXISTS second_brain_session_targets;
       DROP TABLE IF EXISTS second_brain_drafts;
       DROP TABLE IF EXISTS second_brain_messages;
@@ -161,6 +198,7 @@ pub(crate) fn ensure_index_schema(conn: &Connection) -> Result<()> {
       DROP TABLE IF EXISTS second_brain_sessions;
       DELETE FROM internal_meta WHERE key IN ('last_index_run_finished_at_ms', 'last_index_run_title', 'last_index_run_duration_ms');
       DELETE FROM internal_meta WHERE key = 'index_schema_version';
+      DELETE FROM internal_meta WHERE key = 'conversation_search_backfilled';
     "#,
         )?;
     }
@@ -260,6 +298,7 @@ pub(crate) fn ensure_index_schema(conn: &Connection) -> Result<()> {
       provider TEXT NOT NULL DEFAULT '',
       model TEXT NOT NULL DEFAULT '',
       alter_id TEXT NOT NULL DEFAULT '',
+      active_message_id TEXT NOT NULL DEFAULT '',
       created_at_ms INTEGER NOT NULL DEFAULT 0,
       updated_at_ms INTEGER NOT NULL DEFAULT 0
     );
@@ -278,6 +317,7 @@ pub(crate) fn ensure_index_schema(conn: &Connection) -> Result<()> {
     CREATE TABLE IF NOT EXISTS second_brain_messages (
       id TEXT PRIMARY KEY,
       session_id TEXT NOT NULL,
+      parent_id TEXT NOT NULL DEFAULT '',
       role TEXT NOT NULL,
       mode TEXT NOT NULL DEFAULT 'freestyle',
       content_md TEXT NOT NULL DEFAULT '',
@@ -300,8 +340,52 @@ pub(crate) fn ensure_index_schema(conn: &Connection) -> Result<()> {
       updated_at_ms INTEGER NOT NULL DEFAULT 0
     );
 
+    CREATE TABLE IF NOT EXISTS second_brain_history_summaries (
+      session_id TEXT PRIMARY KEY,
+      content_md TEXT NOT NULL DEFAULT '',
+      covered_until_message_id TEXT NOT NULL DEFAULT '',
+      covered_until_ms INTEGER NOT NULL DEFAULT 0,
+      covered_message_count INTEGER NOT NULL DEFAULT 0,
+      edited_by_user INTEGER NOT NULL DEFAULT 0,
+      updated_at_ms INTEGER NOT NULL DEFAULT 0
+    );
+
+    CREATE TABLE IF NOT EXISTS conversation_search_items (
+      id INTEGER PRIMARY KEY,
+      source_kind TEXT NOT NULL,
+      session_id TEXT NOT NULL,
+      item_id TEXT NOT NULL,
+      role TEXT NOT NULL DEFAULT '',
+      alter_ids TEXT NOT NULL DEFAULT '',
+      mode TEXT NOT NULL DEFAULT '',
+      context_paths TEXT NOT NULL DEFAULT '',
+      created_at_ms INTEGER NOT NULL DEFAULT 0,
+      content TEXT NOT NULL DEFAULT '',
+      UNIQUE(source_kind, session_id, item_id)
+    );
+    CREATE INDEX IF NOT EXISTS idx_conversation_search_items_created
+      ON conversation_search_items(created_at_ms);
+
+    CREATE VIRTUAL TABLE IF NOT EXISTS conversation_search_fts USING fts5(
+      content,
+      content='conversation_search_items',
+      content_rowid='id'
+    );
+
+    CREATE TRIGGER IF NOT EXISTS conversation_search_ai AFTER INSERT ON conversation_search_items BEGIN
+      INSERT INTO conversation_search_fts(rowid, content) VALUES (new.id, new.content);
+    END;
+    CREATE TRIGGER IF NOT EXISTS conversation_search_ad AFTER DELETE ON conversation_search_items BEGIN
+      INSERT INTO conversation_search_fts(conversation_search_fts, rowid, content) VALUES('delete', old.id, old.content);
+    END;
+    CREATE TRIGGER IF NOT EXISTS conversation_search_au AFTER UPDATE ON conversation_search_items BEGIN
+      INSERT INTO conversation_search_fts(conversation_search_fts, rowid, content) VALUES('delete', old.id, old.content);
+      INSERT INTO conversation_search_fts(rowid, content) VALUES (new.id, new.content);
+    END;
+
   "#,
     )?;
+    ensure_second_brain_branch_columns(conn)?;
 
     conn.execute(
         "INSERT OR REPLACE INTO internal_meta(key, value) VALUES ('index_schema_version', ?1)",
@@ -3,20 +3,29 @@
 
 mod alters;
 mod alter_exploration;
+mod alter_packs;
 mod app_meta;
+mod conversation_search;
 mod db;
 mod docx;
 mod echoes;
 mod editor_sync;
 mod favorites;
 mod fs_ops;
+mod importers;
 mod index_schema;
 mod markdown_index;
 pub(crate) mod note_history;
+mod note_templates;
+mod obsidian_import;
+mod operation_journal;
 mod search_index;
 mod second_brain;
+mod secrets;
 mod semantic;
 mod settings;
+mod site_export;
+mod trash;
 mod wikilink_graph;
 mod workspace_paths;
 mod workspace_runtime;
@@ -35,7 +44,7 @@ use std::{
 };
 use thiserror::Error;
 
-use docx::convert_markdown_to_docx;
+use docx::{compile_notes_to_docx, convert_markdown_to_docx, convert_markdown_to_pdf};
 use editor_sync::{read_note_snapshot, save_note_buffer};
 use fs_ops::{
     clear_working_folder, copy_entry, create_entry, create_extracted_note, duplicate_entry,
@@ -387,6 +396,9 @@ pub fn run() {
         log_index("sqlite_runtime:init_failed");
     }
     semantic::set_index_logger(log_index);
+    if let Err(message) = settings::migrate_plaintext_keys() {
+        eprintln!("[settings] {message}");
+    }
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .invoke_handler(tauri::generate_handler![
@@ -405,6 +417,8 @@ pub fn run() {
             render_spreadsheet_preview_html,
             write_text_file,
             convert_markdown_to_docx,
+            compile_notes_to_docx,
+            convert_markdown_to_pdf,
             read_note_snapshot,
             save_note_buffer,
             list_note_history,
@@ -448,11 +462,28 @@ pub fn run() {
             favorites::add_favorite,
             favorites::remove_favorite,
             favorites::rename_favorite,
+            trash::list_trash_entries,
+            trash::restore_trash_entry,
+            trash::purge_trash_entry,
+            trash::set_trash_auto_purge,
+            operation_journal::list_operation_journal,
+            operation_journal::undo_last_operation,
+            operation_journal::redo_operation,
+            note_templates::render_note_template,
+            note_templates::list_template_prompts,
+            obsidian_import::analyze_obsidian_vault,
+            importers::import_notes,
+            importers::import_docx_note,
+            site_export::export_static_site,
             compute_echoes_pack,
             settings::read_app_settings,
             settings::write_app_settings,
             settings::discover_llm_models,
             settings::discover_embedding_models,
+            secrets::read_secret_store_status,
+            secrets::unlock_secret_store,
+            secrets::lock_secret_store,
+            secrets::set_secret_store_passphrase,
             alters::list_alters,
             alters::create_alter,
             alters::load_alter,
@@ -463,24 +494,40 @@ pub fn run() {
             alters::load_alter_revision,
             alters::preview_alter,
             alters::generate_alter_draft,
+            alter_packs::export_alters,
+            alter_packs::import_alter_pack,
+            alter_packs::sync_built_in_alters,
             alter_exploration::create_alter_exploration_session,
             alter_exploration::load_alter_exploration_session,
             alter_exploration::list_alter_exploration_sessions,
             alter_exploration::run_alter_exploration_session,
             alter_exploration::cancel_alter_exploration_session,
+            conversation_search::search_conversations,
             second_brain::read_second_brain_config_status,
+            second_brain::list_second_brain_prompts,
             second_brain::generate_frontmatter_properties,
             second_brain::discover_codex_models,
+            second_brain::check_local_model_server,
+            second_brain::list_local_models,
+            second_brain::pull_local_model,
+            second_brain::delete_local_model,
+            second_brain::read_llm_usage_summary,
             second_brain::write_second_brain_global_config,
             second_brain::list_second_brain_sessions,
             second_brain::create_second_brain_session,
             second_brain::load_second_brain_session,
             second_brain::delete_second_brain_session,
             second_brain::update_second_brain_context,
+            second_brain::read_second_brain_history_summary,
+            second_brain::update_second_brain_history_summary,
             second_brain::cancel_second_brain_stream,
             second_brain::cancel_pulse_stream,
             second_brain::run_pulse_transformation,
             second_brain::send_second_brain_message,
+            second_brain::regenerate_second_brain_reply,
+            second_brain::edit_second_brain_message,
+            second_brain::list_second_brain_message_branches,
+            second_brain::switch_second_brain_branch,
             second_brain::set_second_brain_session_alter,
             second_brain::set_second_brain_session_target_note,
             second_brain::insert_second_brain_assistant_into_target_note,
@@ -566,13 +613,20 @@ mod tests {
                     provider: "openai-codex".to_string(),
                     model: "gpt-5.2-codex".to_string(),
                     api_key: None,
+                    key_source: Default::default(),
                     default_temperature: 0.15,
                     system_prompt: String::new(),
                     preserve_existing_api_key: false,
                     base_url: None,
                     default_mode: Some("freestyle".to_string()),
                     capabilities: second_brain::config::ProfileCapabilities::default(),
+                    mock: None,
+                    budget: None,
                 }],
+                prompt_language: None,
+                model_roles: None,
+                model_fallbacks: None,
+                request_policy: None,
             },
             embeddings: settings::SaveEmbeddingsInput {
                 mode: "internal".to_string(),
@@ -1325,6 +1379,69 @@ mod tests {
         fs::remove_dir_all(&workspace).expect("cleanup workspace");
     }
 
+    #[test]
+    fn load_second_brain_session_chains_messages_created_before_branching() {
+        let _guard = workspace_test_guard();
+        let workspace = create_temp_workspace("tomosona-second-brain-branch-migrate");
+        let root = workspace.to_string_lossy().to_string();
+        let internal_dir = workspace.join(INTERNAL_DIR_NAME);
+        fs::create_dir_all(&internal_dir).expect("create internal dir");
+
+        let db_path = internal_dir.join(DB_FILE_NAME);
+        let conn = rusqlite::Connection::open(&db_path).expect("open legacy db");
+        conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS internal_meta (
+              key TEXT PRIMARY KEY,
+              value TEXT NOT NULL
+            );
+            INSERT OR REPLACE INTO internal_meta(key, value) VALUES ('index_schema_version', '3');
+            CREATE TABLE IF NOT EXISTS second_brain_sessions (
+              id TEXT PRIMARY KEY,
+              title TEXT NOT NULL DEFAULT '',
+              provider TEXT NOT NULL DEFAULT '',
+              model TEXT NOT NULL DEFAULT '',
+              alter_id TEXT NOT NULL DEFAULT '',
+              created_at_ms INTEGER NOT NULL DEFAULT 0,
+              updated_at_ms INTEGER NOT NULL DEFAULT 0
+            );
+            CREATE TABLE IF NOT EXISTS second_brain_messages (
+              id TEXT PRIMARY KEY,
+              session_id TEXT NOT NULL,
+              role TEXT NOT NULL,
+              mode TEXT NOT NULL DEFAULT 'freestyle',
+              content_md TEXT NOT NULL DEFAULT '',
+              citations_json TEXT NOT NULL DEFAULT '[]',
+              attachments_json TEXT NOT NULL DEFAULT '[]',
+              created_at_ms INTEGER NOT NULL DEFAULT 0
+            );
+            INSERT INTO second_brain_sessions(id, title) VALUES ('sb-legacy', 'Legacy');
+            INSERT INTO second_brain_messages(id, session_id, role, content_md, created_at_ms)
+              VALUES ('u1', 'sb-legacy', 'user', 'hello', 10),
+                     ('a1', 'sb-legacy', 'assistant', 'hi', 20),
+                     ('u2', 'sb-legacy', 'user', 'again', 30);
+            "#,
+        )
+        .expect("seed pre-branching schema");
+        drop(conn);
+
+        set_active_workspace(&root).expect("set workspace");
+
+        let session = second_brain::load_second_brain_session("sb-legacy".to_string())
+            .expect("load legacy session");
+        let chain = session
+            .messages
+            .iter()
+            .map(|item| (item.id.as_str(), item.parent_id.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(chain, vec![("u1", ""), ("a1", "u1"), ("u2", "a1")]);
+        assert_eq!(session.active_message_id, "u2");
+        assert!(session.branch_points.is_empty());
+
+        clear_active_workspace().expect("clear workspace");
+        fs::remove_dir_all(&workspace).expect("cleanup workspace");
+    }
+
     #[test]
     fn lexical_reindex_updates_fts_data_without_embedding_rows() {
         let _guard = workspace_test_guard();
@@ -165,7 +165,7 @@ pub(crate) fn normalize_wikilink_target(raw: &str) -> Option<String> {
     Some(key)
 }
 
-fn parse_wikilink_targets(markdown: &str) -> Vec<String> {
+pub(crate) fn parse_wikilink_targets(markdown: &str) -> Vec<String> {
     let mut targets = Vec::new();
     let mut offset = 0usize;
 
@@ -259,7 +259,7 @@ pub(crate) fn strip_yaml_frontmatter(markdown: &str) -> &str {
     markdown
 }
 
-fn extract_yaml_frontmatter(markdown: &str) -> Option<&str> {
+pub(crate) fn extract_yaml_frontmatter(markdown: &str) -> Option<&str> {
     if !markdown.starts_with("---\n") {
         return None;
     }
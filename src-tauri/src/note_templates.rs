@@ -0,0 +1,696 @@
+//! Note templates stored under `_templates/`.
+//!
+//! Templates are Markdown files with optional frontmatter. Rendering supports:
+//! - variables: `{{title}}`, `{{folder}}`, `{{date}}`, `{{date:YYYY-MM-DD}}`, `{{time}}`;
+//! - `{{cursor}}`, removed from the output and reported as a byte offset;
+//! - `{{include:path}}`, which inlines another template and merges its frontmatter;
+//! - user prompts declared in the template `prompts:` frontmatter list and referenced
+//!   as `{{key}}`.
+//!
+//! `_default.md` in a `_templates/` subfolder mirroring a workspace folder is the
+//! default template for notes created there; the closest one wins. It is only applied
+//! when the caller of `create_entry` asks for it.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::markdown_index::{
+    extract_yaml_frontmatter, strip_yaml_frontmatter, unquote_yaml_scalar,
+};
+use crate::{active_workspace_root, now_ms, AppError, Result};
+
+const TEMPLATES_DIR_NAME: &str = "_templates";
+const DEFAULT_TEMPLATE_FILE_NAME: &str = "_default.md";
+const PROMPTS_KEY: &str = "prompts";
+const CURSOR_MARKER: &str = "{{cursor}}";
+const MAX_INCLUDE_DEPTH: usize = 8;
+
+/// Template choice sent with `create_entry`.
+///
+/// `template_path` selects a template explicitly and an empty path creates a blank
+/// note. Without a path the note is blank unless `use_folder_default` is set.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct NoteTemplateRequest {
+    #[serde(default)]
+    pub template_path: Option<String>,
+    /// Applies the closest folder `_default.md` when no template is selected.
+    #[serde(default)]
+    pub use_folder_default: bool,
+    #[serde(default)]
+    pub values: HashMap<String, String>,
+    /// Local offset from UTC used for date variables; the frontend knows the user zone.
+    #[serde(default)]
+    pub utc_offset_minutes: Option<i32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RenderNoteTemplatePayload {
+    pub template_path: String,
+    pub note_path: String,
+    #[serde(default)]
+    pub values: HashMap<String, String>,
+    #[serde(default)]
+    pub utc_offset_minutes: Option<i32>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct TemplatePrompt {
+    pub key: String,
+    pub label: String,
+    pub default_value: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RenderedNoteTemplate {
+    pub content: String,
+    pub cursor_offset: Option<usize>,
+    pub prompts: Vec<TemplatePrompt>,
+}
+
+struct TemplateContext<'a> {
+    title: String,
+    folder: String,
+    values: &'a HashMap<String, String>,
+    now_ms: u64,
+    utc_offset_minutes: i32,
+}
+
+/// Frontmatter as ordered top-level entries, each keeping its raw YAML lines.
+#[derive(Default)]
+struct Frontmatter {
+    entries: Vec<(String, String)>,
+}
+
+impl Frontmatter {
+    fn parse(raw: &str) -> Self {
+        let mut entries: Vec<(String, String)> = Vec::new();
+        for line in raw.lines() {
+            let is_top_level = !line.starts_with([' ', '\t', '-']) && !line.trim().is_empty();
+            match line.split_once(':').filter(|_| is_top_level) {
+                Some((key, _)) => entries.push((key.trim().to_string(), line.to_string())),
+                None => {
+                    if let Some((_, block)) = entries.last_mut() {
+                        block.push('\n');
+                        block.push_str(line);
+                    }
+                }
+            }
+        }
+        Self { entries }
+    }
+
+    fn get(&self, key: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(entry_key, _)| entry_key == key)
+            .map(|(_, block)| block.as_str())
+    }
+
+    /// Adds entries missing from `self`; existing keys keep their value.
+    fn merge_missing(&mut self, other: Frontmatter) {
+        for (key, block) in other.entries {
+            if self.get(&key).is_none() {
+                self.entries.push((key, block));
+            }
+        }
+    }
+
+    fn render(&self) -> String {
+        let blocks = self
+            .entries
+            .iter()
+            .filter(|(key, _)| key != PROMPTS_KEY)
+            .map(|(_, block)| block.as_str())
+            .collect::<Vec<_>>();
+        if blocks.is_empty() {
+            return String::new();
+        }
+        format!("---\n{}\n---\n", blocks.join("\n"))
+    }
+}
+
+fn templates_root(root: &Path) -> PathBuf {
+    root.join(TEMPLATES_DIR_NAME)
+}
+
+/// Resolves a template reference inside `_templates/`, with or without `.md`.
+fn resolve_template_path(root: &Path, raw: &str) -> Result<PathBuf> {
+    let templates = templates_root(root);
+    let raw = raw.trim().replace('\\', "/");
+    let candidate = PathBuf::from(&raw);
+    let relative = if candidate.is_absolute() {
+        candidate
+            .strip_prefix(&templates)
+            .map_err(|_| AppError::InvalidPath)?
+            .to_path_buf()
+    } else {
+        PathBuf::from(raw.strip_prefix("_templates/").unwrap_or(&raw))
+    };
+    if relative
+        .components()
+        .any(|component| !matches!(component, Component::Normal(_)))
+    {
+        return Err(AppError::InvalidPath);
+    }
+
+    let path = templates.join(&relative);
+    if path.is_file() {
+        return Ok(path);
+    }
+    let with_extension = templates.join(format!("{}.md", relative.to_string_lossy()));
+    if with_extension.is_file() {
+        return Ok(with_extension);
+    }
+    Err(AppError::InvalidOperation(format!(
+        "Template not found: {}",
+        relative.to_string_lossy()
+    )))
+}
+
+/// Returns the closest `_default.md` for a note created in `folder`.
+///
+/// Notes created inside `_templates/` never get a default template.
+fn default_template_for_folder(root: &Path, folder: &Path) -> Option<PathBuf> {
+    if folder.starts_with(templates_root(root)) {
+        return None;
+    }
+    let relative = folder.strip_prefix(root).ok()?;
+    let mut current = templates_root(root).join(relative);
+    loop {
+        let candidate = current.join(DEFAULT_TEMPLATE_FILE_NAME);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+        if current == templates_root(root) || !current.pop() {
+            return None;
+        }
+    }
+}
+
+fn parse_prompts(frontmatter: &Frontmatter) -> Vec<TemplatePrompt> {
+    let Some(block) = frontmatter.get(PROMPTS_KEY) else {
+        return Vec::new();
+    };
+    let mut prompts: Vec<TemplatePrompt> = Vec::new();
+    let mut lines = block.lines();
+    let inline = lines
+        .next()
+        .and_then(|line| line.split_once(':'))
+        .map(|(_, value)| value.trim())
+        .unwrap_or("");
+    if let Some(items) = inline
+        .strip_prefix('[')
+        .and_then(|value| value.strip_suffix(']'))
+    {
+        for item in items.split(',') {
+            prompts.push(TemplatePrompt::default());
+            apply_prompt_field(prompts.last_mut(), "key", unquote_yaml_scalar(item));
+        }
+    }
+
+    for line in lines {
+        let trimmed = line.trim();
+        let field = match trimmed.strip_prefix('-') {
+            Some(item) => {
+                prompts.push(TemplatePrompt::default());
+                item.trim()
+            }
+            None => trimmed,
+        };
+        match field.split_once(':') {
+            Some((name, value)) => {
+                apply_prompt_field(prompts.last_mut(), name.trim(), unquote_yaml_scalar(value))
+            }
+            None if !field.is_empty() => {
+                apply_prompt_field(prompts.last_mut(), "key", unquote_yaml_scalar(field))
+            }
+            None => {}
+        }
+    }
+
+    prompts.retain(|prompt| !prompt.key.is_empty());
+    for prompt in &mut prompts {
+        if prompt.label.is_empty() {
+            prompt.label = prompt.key.clone();
+        }
+    }
+    prompts
+}
+
+fn apply_prompt_field(prompt: Option<&mut TemplatePrompt>, name: &str, value: String) {
+    let Some(prompt) = prompt else {
+        return;
+    };
+    match name {
+        "key" => prompt.key = value,
+        "label" => prompt.label = value,
+        "default" => prompt.default_value = value,
+        _ => {}
+    }
+}
+
+/// Converts days since 1970-01-01 to a civil date.
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+fn format_date(now_ms: u64, utc_offset_minutes: i32, pattern: &str) -> String {
+    let seconds = (now_ms / 1000) as i64 + i64::from(utc_offset_minutes) * 60;
+    let (year, month, day) = civil_from_days(seconds.div_euclid(86_400));
+    let second_of_day = seconds.rem_euclid(86_400);
+    let tokens: [(&str, String); 7] = [
+        ("YYYY", format!("{year:04}")),
+        ("YY", format!("{:02}", year.rem_euclid(100))),
+        ("MM", format!("{month:02}")),
+        ("DD", format!("{day:02}")),
+        ("HH", format!("{:02}", second_of_day / 3600)),
+        ("mm", format!("{:02}", second_of_day % 3600 / 60)),
+        ("ss", format!("{:02}", second_of_day % 60)),
+    ];
+
+    let mut output = String::new();
+    let mut rest = pattern;
+    'outer: while !rest.is_empty() {
+        for (token, value) in &tokens {
+            if let Some(after) = rest.strip_prefix(token) {
+                output.push_str(value);
+                rest = after;
+                continue 'outer;
+            }
+        }
+        let mut chars = rest.chars();
+        if let Some(ch) = chars.next() {
+            output.push(ch);
+        }
+        rest = chars.as_str();
+    }
+    output
+}
+
+fn variable_value(name: &str, context: &TemplateContext<'_>) -> Option<String> {
+    let name = name.trim();
+    if let Some(value) = context.values.get(name) {
+        return Some(value.clone());
+    }
+    match name {
+        "title" => Some(context.title.clone()),
+        "folder" => Some(context.folder.clone()),
+        "date" => Some(format_date(
+            context.now_ms,
+            context.utc_offset_minutes,
+            "YYYY-MM-DD",
+        )),
+        "time" => Some(format_date(
+            context.now_ms,
+            context.utc_offset_minutes,
+            "HH:mm",
+        )),
+        _ => name
+            .strip_prefix("date:")
+            .map(|pattern| format_date(context.now_ms, context.utc_offset_minutes, pattern.trim())),
+    }
+}
+
+/// Replaces known `{{...}}` placeholders; unknown ones and `{{cursor}}` are kept.
+fn substitute_variables(text: &str, context: &TemplateContext<'_>) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let after_open = &rest[start + 2..];
+        let Some(end) = after_open.find("}}") else {
+            output.push_str(&rest[start..]);
+            return output;
+        };
+        let name = &after_open[..end];
+        match variable_value(name, context) {
+            Some(value) => output.push_str(&value),
+            None => output.push_str(&rest[start..start + 2 + end + 2]),
+        }
+        rest = &after_open[end + 2..];
+    }
+    output.push_str(rest);
+    output
+}
+
+/// Loads a template, inlines its includes and returns merged frontmatter and body.
+fn expand_template(
+    root: &Path,
+    path: &Path,
+    visiting: &mut HashSet<PathBuf>,
+) -> Result<(Frontmatter, String)> {
+    if visiting.len() >= MAX_INCLUDE_DEPTH || !visiting.insert(path.to_path_buf()) {
+        return Err(AppError::InvalidOperation(format!(
+            "Template includes form a cycle or are nested too deeply: {}",
+            path.to_string_lossy()
+        )));
+    }
+    let raw = fs::read_to_string(path)?.replace("\r\n", "\n");
+    let mut frontmatter = extract_yaml_frontmatter(&raw)
+        .map(Frontmatter::parse)
+        .unwrap_or_default();
+    let mut body = String::new();
+    let mut rest = strip_yaml_frontmatter(&raw);
+    while let Some(start) = rest.find("{{include:") {
+        let after_open = &rest[start + "{{include:".len()..];
+        let Some(end) = after_open.find("}}") else {
+            break;
+        };
+        body.push_str(&rest[..start]);
+        let included_path = resolve_template_path(root, &after_open[..end])?;
+        let (included_frontmatter, included_body) =
+            expand_template(root, &included_path, visiting)?;
+        frontmatter.merge_missing(included_frontmatter);
+        body.push_str(included_body.trim_end_matches('\n'));
+        rest = &after_open[end + 2..];
+    }
+    body.push_str(rest);
+    visiting.remove(path);
+    Ok((frontmatter, body))
+}
+
+fn render_template_file(
+    root: &Path,
+    template_path: &Path,
+    context: &TemplateContext<'_>,
+) -> Result<RenderedNoteTemplate> {
+    let (frontmatter, body) = expand_template(root, template_path, &mut HashSet::new())?;
+    let prompts = parse_prompts(&frontmatter);
+    let mut values = context.values.clone();
+    for prompt in &prompts {
+        values
+            .entry(prompt.key.clone())
+            .or_insert_with(|| prompt.default_value.clone());
+    }
+    let context = TemplateContext {
+        title: context.title.clone(),
+        folder: context.folder.clone(),
+        values: &values,
+        now_ms: context.now_ms,
+        utc_offset_minutes: context.utc_offset_minutes,
+    };
+
+    let rendered = format!(
+        "{}{}",
+        substitute_variables(&frontmatter.render(), &context),
+        substitute_variables(&body, &context)
+    );
+    let cursor_offset = rendered.find(CURSOR_MARKER);
+    Ok(RenderedNoteTemplate {
+        content: rendered.replace(CURSOR_MARKER, ""),
+        cursor_offset,
+        prompts,
+    })
+}
+
+fn template_context<'a>(
+    root: &Path,
+    note_path: &Path,
+    values: &'a HashMap<String, String>,
+    utc_offset_minutes: Option<i32>,
+) -> TemplateContext<'a> {
+    let title = note_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let folder = note_path
+        .parent()
+        .and_then(|parent| parent.strip_prefix(root).ok())
+        .map(|relative| relative.to_string_lossy().replace('\\', "/"))
+        .unwrap_or_default();
+    TemplateContext {
+        title,
+        folder,
+        values,
+        now_ms: now_ms(),
+        utc_offset_minutes: utc_offset_minutes.unwrap_or(0),
+    }
+}
+
+fn is_markdown_note(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown"))
+}
+
+/// Renders the initial content and cursor position of a note created by `create_entry`.
+///
+/// Returns `None` when the note should stay empty: non-Markdown files, no template
+/// request, an explicit blank choice, or no folder default template.
+pub(crate) fn initial_note_content(
+    root: &Path,
+    note_path: &Path,
+    request: Option<&NoteTemplateRequest>,
+) -> Result<Option<RenderedNoteTemplate>> {
+    if !is_markdown_note(note_path) {
+        return Ok(None);
+    }
+    let note_path = &note_path
+        .parent()
+        .and_then(|parent| fs::canonicalize(parent).ok())
+        .zip(note_path.file_name())
+        .map(|(parent, name)| parent.join(name))
+        .unwrap_or_else(|| note_path.to_path_buf());
+    let Some(request) = request else {
+        return Ok(None);
+    };
+    let template_path = match request.template_path.as_deref().map(str::trim) {
+        Some("") => return Ok(None),
+        Some(raw) => resolve_template_path(root, raw)?,
+        None if !request.use_folder_default => return Ok(None),
+        None => {
+            let folder = note_path.parent().unwrap_or(root);
+            match default_template_for_folder(root, folder) {
+                Some(path) => path,
+                None => return Ok(None),
+            }
+        }
+    };
+
+    let context = template_context(root, note_path, &request.values, request.utc_offset_minutes);
+    render_template_file(root, &template_path, &context).map(Some)
+}
+
+/// Renders a template for a note path without writing it, e.g. for daily notes.
+#[tauri::command]
+pub fn render_note_template(payload: RenderNoteTemplatePayload) -> Result<RenderedNoteTemplate> {
+    let root = active_workspace_root()?;
+    let template_path = resolve_template_path(&root, &payload.template_path)?;
+    let note_path = PathBuf::from(payload.note_path.trim());
+    let note_path = if note_path.is_absolute() {
+        note_path
+    } else {
+        root.join(note_path)
+    };
+    let context = template_context(
+        &root,
+        &note_path,
+        &payload.values,
+        payload.utc_offset_minutes,
+    );
+    render_template_file(&root, &template_path, &context)
+}
+
+/// Lists the prompts a template declares so the frontend can ask for their values.
+#[tauri::command]
+pub fn list_template_prompts(template_path: String) -> Result<Vec<TemplatePrompt>> {
+    let root = active_workspace_root()?;
+    let path = resolve_template_path(&root, &template_path)?;
+    let (frontmatter, _) = expand_template(&root, &path, &mut HashSet::new())?;
+    Ok(parse_prompts(&frontmatter))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::*;
+
+    fn create_temp_workspace(prefix: &str) -> PathBuf {
+        let nonce = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|value| value.as_nanos())
+            .unwrap_or(0);
+        let dir = std::env::temp_dir().join(format!("{prefix}-{nonce}"));
+        fs::create_dir_all(dir.join(TEMPLATES_DIR_NAME)).expect("create templates dir");
+        fs::canonicalize(dir).expect("canonical workspace")
+    }
+
+    fn context<'a>(values: &'a HashMap<String, String>) -> TemplateContext<'a> {
+        TemplateContext {
+            title: "Weekly sync".to_string(),
+            folder: "meetings".to_string(),
+            values,
+            // 2024-02-29T23:30:00Z
+            now_ms: 1_709_249_400_000,
+            utc_offset_minutes: 0,
+        }
+    }
+
+    #[test]
+    fn formats_date_tokens_with_offset() {
+        assert_eq!(
+            format_date(1_709_249_400_000, 0, "YYYY-MM-DD HH:mm"),
+            "2024-02-29 23:30"
+        );
+        assert_eq!(format_date(1_709_249_400_000, 60, "DD/MM/YY"), "01/03/24");
+        assert_eq!(format_date(0, -60, "YYYY-MM-DD"), "1969-12-31");
+    }
+
+    #[test]
+    fn substitutes_known_variables_and_keeps_unknown_ones() {
+        let values = HashMap::new();
+        let rendered = substitute_variables(
+            "# {{title}} ({{folder}}) {{date:YYYY}} {{unknown}} {{cursor}}",
+            &context(&values),
+        );
+        assert_eq!(
+            rendered,
+            "# Weekly sync (meetings) 2024 {{unknown}} {{cursor}}"
+        );
+    }
+
+    #[test]
+    fn parses_prompts_in_both_list_forms() {
+        let frontmatter = Frontmatter::parse(
+            "tags: [meeting]\nprompts:\n  - key: client\n    label: Client name\n    default: ACME\n  - topic",
+        );
+        assert_eq!(
+            parse_prompts(&frontmatter),
+            vec![
+                TemplatePrompt {
+                    key: "client".to_string(),
+                    label: "Client name".to_string(),
+                    default_value: "ACME".to_string(),
+                },
+                TemplatePrompt {
+                    key: "topic".to_string(),
+                    label: "topic".to_string(),
+                    default_value: String::new(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn renders_includes_prompts_frontmatter_and_cursor() {
+        let workspace = create_temp_workspace("tomosona-templates-render");
+        let templates = workspace.join(TEMPLATES_DIR_NAME);
+        fs::write(
+            templates.join("header.md"),
+            "---\ntype: meeting\ntags: [base]\n---\n## Attendees\n",
+        )
+        .expect("write header");
+        fs::write(
+            templates.join("meeting.md"),
+            "---\ntags: [meeting]\nprompts:\n  - key: client\n    default: ACME\n---\n# {{title}} with {{client}}\n{{include:header}}\n{{cursor}}\n",
+        )
+        .expect("write meeting");
+
+        let values = HashMap::new();
+        let rendered =
+            render_template_file(&workspace, &templates.join("meeting.md"), &context(&values))
+                .expect("render template");
+        assert_eq!(
+            rendered.content,
+            "---\ntags: [meeting]\ntype: meeting\n---\n# Weekly sync with ACME\n## Attendees\n\n"
+        );
+        assert_eq!(rendered.cursor_offset, Some(rendered.content.len() - 1));
+        assert_eq!(rendered.prompts.len(), 1);
+
+        fs::remove_dir_all(workspace).expect("cleanup workspace");
+    }
+
+    #[test]
+    fn rejects_include_cycles() {
+        let workspace = create_temp_workspace("tomosona-templates-cycle");
+        let templates = workspace.join(TEMPLATES_DIR_NAME);
+        fs::write(templates.join("a.md"), "{{include:b}}").expect("write a");
+        fs::write(templates.join("b.md"), "{{include:a}}").expect("write b");
+
+        let values = HashMap::new();
+        assert!(
+            render_template_file(&workspace, &templates.join("a.md"), &context(&values)).is_err()
+        );
+
+        fs::remove_dir_all(workspace).expect("cleanup workspace");
+    }
+
+    #[test]
+    fn uses_closest_folder_default_template() {
+        let workspace = create_temp_workspace("tomosona-templates-default");
+        let templates = workspace.join(TEMPLATES_DIR_NAME);
+        fs::create_dir_all(templates.join("journal")).expect("create journal templates");
+        fs::create_dir_all(workspace.join("journal/2024")).expect("create journal dir");
+        fs::write(templates.join(DEFAULT_TEMPLATE_FILE_NAME), "root").expect("write root default");
+        fs::write(
+            templates.join("journal").join(DEFAULT_TEMPLATE_FILE_NAME),
+            "# {{title}}",
+        )
+        .expect("write journal default");
+
+        let note = workspace.join("journal/2024/today.md");
+        let folder_default = NoteTemplateRequest {
+            use_folder_default: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            initial_note_content(&workspace, &note, None).expect("no template request"),
+            None
+        );
+        assert_eq!(
+            initial_note_content(&workspace, &note, Some(&NoteTemplateRequest::default()))
+                .expect("default not requested"),
+            None
+        );
+        assert_eq!(
+            initial_note_content(&workspace, &note, Some(&folder_default))
+                .expect("render default")
+                .map(|rendered| rendered.content),
+            Some("# today".to_string())
+        );
+        let blank = NoteTemplateRequest {
+            template_path: Some(String::new()),
+            ..Default::default()
+        };
+        assert_eq!(
+            initial_note_content(&workspace, &note, Some(&blank)).expect("blank note"),
+            None
+        );
+        assert_eq!(
+            initial_note_content(
+                &workspace,
+                &workspace.join("data.csv"),
+                Some(&folder_default)
+            )
+            .expect("non markdown"),
+            None
+        );
+        assert_eq!(
+            initial_note_content(
+                &workspace,
+                &workspace.join("other.md"),
+                Some(&folder_default)
+            )
+            .expect("root default")
+            .map(|rendered| rendered.content),
+            Some("root".to_string())
+        );
+
+        fs::remove_dir_all(workspace).expect("cleanup workspace");
+    }
+}
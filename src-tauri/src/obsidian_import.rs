@@ -0,0 +1,809 @@
+//! Obsidian vault compatibility analysis and normalization.
+//!
+//! A vault opened as a workspace is mostly plain Markdown, but a few Obsidian
+//! conventions do not survive `markdown_index` and the editor:
+//! - `![[image.png]]` attachment embeds, resolved through Obsidian's attachment folder;
+//! - `%%comments%%`, which Tomosona renders as text;
+//! - relative, partial-path or ambiguous `[[links]]` that only Obsidian resolves;
+//! - escaped `\|` aliases in tables, block references and plugin code blocks.
+//!
+//! `analyze_obsidian_vault` always reports what it found per file. With
+//! `normalize: true` and `dry_run: false` it also rewrites the fixable cases:
+//! embeds become Markdown images, comments become HTML comments and links get the
+//! full workspace path. Rewrites are journaled so they can be undone.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::editor_sync::record_workspace_mutation_write;
+use crate::markdown_index::normalize_wikilink_target;
+use crate::operation_journal::record_link_rewrites;
+use crate::workspace_paths::{
+    join_relative, relative_from, should_skip_workspace_walk_dir, should_skip_workspace_walk_file,
+    split_wikilink_target_suffix,
+};
+use crate::{
+    active_workspace_root, list_markdown_files_via_find, normalize_key_text, note_key_basename,
+    reindex_markdown_file_now_sync, AppError, Result,
+};
+
+const OBSIDIAN_DIR_NAME: &str = ".obsidian";
+const TOMOSONA_TEMPLATES_DIR_NAME: &str = "_templates";
+const TOMOSONA_JOURNAL_DIR_NAME: &str = "journal";
+const PLUGIN_CODE_BLOCK_LANGUAGES: [&str; 4] = ["dataview", "dataviewjs", "tasks", "query"];
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AnalyzeObsidianVaultPayload {
+    #[serde(default)]
+    pub normalize: bool,
+    #[serde(default = "default_dry_run")]
+    pub dry_run: bool,
+}
+
+fn default_dry_run() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ObsidianIssueKind {
+    AttachmentEmbed,
+    InlineEmbed,
+    Comment,
+    RelativeLink,
+    AmbiguousLink,
+    UnresolvedLink,
+    EscapedAliasPipe,
+    BlockReference,
+    PluginCodeBlock,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ObsidianIssue {
+    pub kind: ObsidianIssueKind,
+    pub line: usize,
+    pub excerpt: String,
+    /// True when normalization rewrites this occurrence.
+    pub fixable: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ObsidianFileReport {
+    pub path: String,
+    pub issues: Vec<ObsidianIssue>,
+    /// True when normalization rewrites the file, or would in a dry run.
+    pub changed: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ObsidianVaultSettings {
+    pub found: bool,
+    pub attachment_folder: Option<String>,
+    pub new_note_location: Option<String>,
+    pub new_note_folder: Option<String>,
+    pub link_format: Option<String>,
+    pub use_markdown_links: bool,
+    pub daily_notes_folder: Option<String>,
+    pub templates_folder: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ObsidianImportReport {
+    pub settings: ObsidianVaultSettings,
+    /// Human-readable notes on how Obsidian settings map to Tomosona conventions.
+    pub mapping_notes: Vec<String>,
+    pub files: Vec<ObsidianFileReport>,
+    pub scanned_files: usize,
+    pub issue_count: usize,
+    pub changed_files: usize,
+    pub dry_run: bool,
+}
+
+struct VaultCatalog {
+    note_keys: HashSet<String>,
+    note_targets: HashMap<String, String>,
+    keys_by_basename: HashMap<String, Vec<String>>,
+    attachments: Vec<String>,
+}
+
+enum LinkResolution {
+    Supported,
+    Rewrite(String),
+    Ambiguous,
+    Missing,
+}
+
+fn read_json(path: &Path) -> Option<Value> {
+    let raw = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+fn json_string(value: &Value, key: &str) -> Option<String> {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .map(|text| text.trim().trim_matches('/').to_string())
+        .filter(|text| !text.is_empty())
+}
+
+fn read_obsidian_settings(root: &Path) -> ObsidianVaultSettings {
+    let dir = root.join(OBSIDIAN_DIR_NAME);
+    if !dir.is_dir() {
+        return ObsidianVaultSettings::default();
+    }
+    let app = read_json(&dir.join("app.json")).unwrap_or(Value::Null);
+    let daily = read_json(&dir.join("daily-notes.json")).unwrap_or(Value::Null);
+    let templates = read_json(&dir.join("templates.json")).unwrap_or(Value::Null);
+    ObsidianVaultSettings {
+        found: true,
+        attachment_folder: json_string(&app, "attachmentFolderPath"),
+        new_note_location: json_string(&app, "newFileLocation"),
+        new_note_folder: json_string(&app, "newFileFolderPath"),
+        link_format: json_string(&app, "newLinkFormat"),
+        use_markdown_links: app
+            .get("useMarkdownLinks")
+            .and_then(Value::as_bool)
+            .unwrap_or(false),
+        daily_notes_folder: json_string(&daily, "folder"),
+        templates_folder: json_string(&templates, "folder"),
+    }
+}
+
+fn mapping_notes(settings: &ObsidianVaultSettings) -> Vec<String> {
+    let mut notes = Vec::new();
+    if !settings.found {
+        notes.push("No .obsidian/ folder found; default link rules apply.".to_string());
+        return notes;
+    }
+    match settings.attachment_folder.as_deref() {
+        None => notes.push(
+            "Attachments live at the vault root; embeds are resolved by file name.".to_string(),
+        ),
+        Some(folder) if folder.starts_with('.') => notes.push(format!(
+            "Attachments are stored next to each note ({folder}); images keep relative paths."
+        )),
+        Some(folder) => notes.push(format!(
+            "Attachments are stored in {folder}/; embeds become Markdown images pointing there."
+        )),
+    }
+    match (
+        settings.new_note_location.as_deref(),
+        settings.new_note_folder.as_deref(),
+    ) {
+        (Some("folder"), Some(folder)) => notes.push(format!(
+            "New notes were created in {folder}/; create Tomosona notes there too."
+        )),
+        (Some("current"), _) => {
+            notes.push("New notes were created next to the current note.".to_string())
+        }
+        _ => notes.push("New notes were created at the vault root.".to_string()),
+    }
+    if let Some(folder) = settings.templates_folder.as_deref() {
+        if folder != TOMOSONA_TEMPLATES_DIR_NAME {
+            notes.push(format!(
+                "Templates live in {folder}/; Tomosona reads {TOMOSONA_TEMPLATES_DIR_NAME}/."
+            ));
+        }
+    }
+    if let Some(folder) = settings.daily_notes_folder.as_deref() {
+        if folder != TOMOSONA_JOURNAL_DIR_NAME {
+            notes.push(format!(
+                "Daily notes live in {folder}/; Tomosona links dates to {TOMOSONA_JOURNAL_DIR_NAME}/."
+            ));
+        }
+    }
+    if settings.use_markdown_links {
+        notes.push(
+            "The vault uses Markdown links for new notes; only [[wikilinks]] are checked."
+                .to_string(),
+        );
+    }
+    notes
+}
+
+fn relative_string(root: &Path, path: &Path) -> Option<String> {
+    path.strip_prefix(root)
+        .ok()
+        .map(|relative| relative.to_string_lossy().replace('\\', "/"))
+}
+
+fn list_attachment_files(root: &Path) -> Result<Vec<String>> {
+    fn walk(root: &Path, dir: &Path, out: &mut Vec<String>) -> Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            if path.is_dir() {
+                if !should_skip_workspace_walk_dir(&name, &path) && !name.starts_with('.') {
+                    walk(root, &path, out)?;
+                }
+                continue;
+            }
+            if !path.is_file() || should_skip_workspace_walk_file(&path) || is_markdown_name(&name)
+            {
+                continue;
+            }
+            if let Some(relative) = relative_string(root, &path) {
+                out.push(relative);
+            }
+        }
+        Ok(())
+    }
+
+    let mut files = Vec::new();
+    walk(root, root, &mut files)?;
+    files.sort();
+    Ok(files)
+}
+
+fn is_markdown_name(name: &str) -> bool {
+    let lower = name.to_ascii_lowercase();
+    lower.ends_with(".md") || lower.ends_with(".markdown")
+}
+
+fn build_catalog(root: &Path, markdown_files: &[PathBuf]) -> Result<VaultCatalog> {
+    let mut note_keys = HashSet::new();
+    let mut note_targets = HashMap::new();
+    let mut keys_by_basename: HashMap<String, Vec<String>> = HashMap::new();
+    for path in markdown_files {
+        let Some(relative) = relative_string(root, path) else {
+            continue;
+        };
+        let Some(key) = normalize_wikilink_target(&relative) else {
+            continue;
+        };
+        let target = strip_markdown_suffix(&relative).to_string();
+        keys_by_basename
+            .entry(note_key_basename(&key))
+            .or_default()
+            .push(key.clone());
+        note_targets.insert(key.clone(), target);
+        note_keys.insert(key);
+    }
+    Ok(VaultCatalog {
+        note_keys,
+        note_targets,
+        keys_by_basename,
+        attachments: list_attachment_files(root)?,
+    })
+}
+
+fn strip_markdown_suffix(path: &str) -> &str {
+    let lower = path.to_ascii_lowercase();
+    if lower.ends_with(".markdown") {
+        &path[..path.len() - ".markdown".len()]
+    } else if lower.ends_with(".md") {
+        &path[..path.len() - ".md".len()]
+    } else {
+        path
+    }
+}
+
+fn resolve_link(target: &str, note_dir: &str, catalog: &VaultCatalog) -> LinkResolution {
+    let trimmed = target.trim();
+    if trimmed.is_empty() {
+        return LinkResolution::Supported;
+    }
+    if let Some(key) = normalize_wikilink_target(trimmed) {
+        if catalog.note_keys.contains(&key) {
+            return LinkResolution::Supported;
+        }
+        let basename_matches = catalog
+            .keys_by_basename
+            .get(&note_key_basename(&key))
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        if !key.contains('/') {
+            return match basename_matches.len() {
+                0 => LinkResolution::Missing,
+                1 => LinkResolution::Supported,
+                _ => LinkResolution::Ambiguous,
+            };
+        }
+        let suffix = format!("/{key}");
+        let partial = basename_matches
+            .iter()
+            .filter(|candidate| candidate.ends_with(&suffix))
+            .collect::<Vec<_>>();
+        if let [only] = partial.as_slice() {
+            return LinkResolution::Rewrite(catalog.note_targets[*only].clone());
+        }
+    }
+
+    let Some(joined) = join_relative(note_dir, &trimmed.replace('\\', "/")) else {
+        return LinkResolution::Missing;
+    };
+    match normalize_wikilink_target(&joined) {
+        Some(key) if catalog.note_keys.contains(&key) => {
+            LinkResolution::Rewrite(catalog.note_targets[&key].clone())
+        }
+        _ => LinkResolution::Missing,
+    }
+}
+
+/// Finds an attachment the way Obsidian does: exact path, note-relative, then file name.
+fn resolve_attachment(
+    target: &str,
+    note_dir: &str,
+    settings: &ObsidianVaultSettings,
+    catalog: &VaultCatalog,
+) -> Option<String> {
+    let target = target.trim().trim_start_matches('/').replace('\\', "/");
+    let lower = normalize_key_text(&target);
+    let find = |candidate: &str| {
+        let candidate = normalize_key_text(candidate);
+        catalog
+            .attachments
+            .iter()
+            .find(|path| normalize_key_text(path) == candidate)
+            .cloned()
+    };
+
+    if let Some(found) = find(&target) {
+        return Some(found);
+    }
+    if let Some(found) = join_relative(note_dir, &target).and_then(|joined| find(&joined)) {
+        return Some(found);
+    }
+    if let Some(folder) = settings.attachment_folder.as_deref() {
+        let base = if folder.starts_with('.') {
+            join_relative(note_dir, folder)
+        } else {
+            Some(folder.to_string())
+        };
+        if let Some(found) = base
+            .and_then(|base| join_relative(&base, &target))
+            .and_then(|joined| find(&joined))
+        {
+            return Some(found);
+        }
+    }
+    let by_name = catalog
+        .attachments
+        .iter()
+        .filter(|path| {
+            let name = path.rsplit('/').next().unwrap_or(path);
+            normalize_key_text(name) == lower
+        })
+        .collect::<Vec<_>>();
+    match by_name.as_slice() {
+        [only] => Some((*only).clone()),
+        _ => None,
+    }
+}
+
+fn excerpt(text: &str) -> String {
+    const MAX_EXCERPT_CHARS: usize = 80;
+    let trimmed = text.trim();
+    if trimmed.chars().count() <= MAX_EXCERPT_CHARS {
+        return trimmed.to_string();
+    }
+    let mut cut = trimmed.chars().take(MAX_EXCERPT_CHARS).collect::<String>();
+    cut.push('…');
+    cut
+}
+
+fn markdown_image(alt: &str, path: &str) -> String {
+    if path.contains(' ') {
+        format!("![{alt}](<{path}>)")
+    } else {
+        format!("![{alt}]({path})")
+    }
+}
+
+struct FileContext<'a> {
+    note_dir: String,
+    settings: &'a ObsidianVaultSettings,
+    catalog: &'a VaultCatalog,
+}
+
+/// Rewrites `%%comments%%` on one line, tracking comments spanning several lines.
+fn convert_comments(
+    line: &str,
+    line_number: usize,
+    in_comment: &mut bool,
+    issues: &mut Vec<ObsidianIssue>,
+) -> String {
+    if !line.contains("%%") && !*in_comment {
+        return line.to_string();
+    }
+    let mut output = String::with_capacity(line.len() + 8);
+    let mut parts = line.split("%%").peekable();
+    if let Some(first) = parts.next() {
+        output.push_str(first);
+    }
+    while let Some(part) = parts.next() {
+        if *in_comment {
+            output.push_str(" -->");
+            *in_comment = false;
+        } else {
+            output.push_str("<!-- ");
+            *in_comment = true;
+            issues.push(ObsidianIssue {
+                kind: ObsidianIssueKind::Comment,
+                line: line_number,
+                excerpt: excerpt(line),
+                fixable: true,
+            });
+        }
+        output.push_str(part);
+        if parts.peek().is_none() {
+            break;
+        }
+    }
+    output
+}
+
+/// Checks and rewrites `[[links]]` and `![[embeds]]` on one line.
+fn convert_links(
+    line: &str,
+    line_number: usize,
+    context: &FileContext<'_>,
+    issues: &mut Vec<ObsidianIssue>,
+) -> String {
+    let mut output = String::with_capacity(line.len());
+    let mut offset = 0usize;
+    let standalone_embed = line.trim().starts_with("![[") && line.trim().ends_with("]]");
+    while let Some(start_rel) = line[offset..].find("[[") {
+        let start = offset + start_rel;
+        let Some(end_rel) = line[start + 2..].find("]]") else {
+            break;
+        };
+        let end = start + 2 + end_rel;
+        let is_embed = start > 0 && line.as_bytes()[start - 1] == b'!';
+        let content = &line[start + 2..end];
+        let (target, suffix) = split_wikilink_target_suffix(content);
+        let issue = |kind, fixable| ObsidianIssue {
+            kind,
+            line: line_number,
+            excerpt: excerpt(&line[start.saturating_sub(usize::from(is_embed))..end + 2]),
+            fixable,
+        };
+
+        if target.ends_with('\\') && suffix.starts_with('|') {
+            issues.push(issue(ObsidianIssueKind::EscapedAliasPipe, false));
+        }
+        if suffix.starts_with("#^") {
+            issues.push(issue(ObsidianIssueKind::BlockReference, false));
+        }
+
+        let is_attachment = is_embed && !is_markdown_name(target) && target.contains('.');
+        if is_attachment {
+            let attachment =
+                resolve_attachment(target, &context.note_dir, context.settings, context.catalog);
+            issues.push(issue(
+                ObsidianIssueKind::AttachmentEmbed,
+                attachment.is_some(),
+            ));
+            if let Some(path) = attachment {
+                let alt = suffix
+                    .strip_prefix('|')
+                    .filter(|text| !text.chars().all(|ch| ch.is_ascii_digit() || ch == 'x'))
+                    .unwrap_or_else(|| path.rsplit('/').next().unwrap_or(&path));
+                output.push_str(&line[offset..start - 1]);
+                output.push_str(&markdown_image(
+                    alt,
+                    &relative_from(&context.note_dir, &path),
+                ));
+                offset = end + 2;
+                continue;
+            }
+            output.push_str(&line[offset..end + 2]);
+            offset = end + 2;
+            continue;
+        }
+
+        if is_embed && !standalone_embed {
+            issues.push(issue(ObsidianIssueKind::InlineEmbed, false));
+        }
+        output.push_str(&line[offset..start + 2]);
+        match resolve_link(
+            target.trim_end_matches('\\'),
+            &context.note_dir,
+            context.catalog,
+        ) {
+            LinkResolution::Supported => output.push_str(content),
+            LinkResolution::Rewrite(new_target) => {
+                issues.push(issue(ObsidianIssueKind::RelativeLink, true));
+                output.push_str(&new_target);
+                output.push_str(suffix);
+            }
+            LinkResolution::Ambiguous => {
+                issues.push(issue(ObsidianIssueKind::AmbiguousLink, false));
+                output.push_str(content);
+            }
+            LinkResolution::Missing => {
+                issues.push(issue(ObsidianIssueKind::UnresolvedLink, false));
+                output.push_str(content);
+            }
+        }
+        output.push_str("]]");
+        offset = end + 2;
+    }
+    output.push_str(&line[offset..]);
+    output
+}
+
+/// Analyzes one note and returns its issues with the normalized content.
+fn convert_note(markdown: &str, context: &FileContext<'_>) -> (Vec<ObsidianIssue>, String) {
+    let mut issues = Vec::new();
+    let mut output = Vec::new();
+    let mut fence: Option<String> = None;
+    let mut in_comment = false;
+
+    for (index, line) in markdown.split('\n').enumerate() {
+        let line_number = index + 1;
+        let trimmed = line.trim_start();
+        if let Some(marker) = fence.as_deref() {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            output.push(line.to_string());
+            continue;
+        }
+        if !in_comment && (trimmed.starts_with("```") || trimmed.starts_with("~~~")) {
+            let marker = &trimmed[..3];
+            let language = trimmed[3..].trim().to_ascii_lowercase();
+            if PLUGIN_CODE_BLOCK_LANGUAGES.contains(&language.as_str()) {
+                issues.push(ObsidianIssue {
+                    kind: ObsidianIssueKind::PluginCodeBlock,
+                    line: line_number,
+                    excerpt: excerpt(line),
+                    fixable: false,
+                });
+            }
+            fence = Some(marker.to_string());
+            output.push(line.to_string());
+            continue;
+        }
+
+        let uncommented = convert_comments(line, line_number, &mut in_comment, &mut issues);
+        output.push(convert_links(
+            &uncommented,
+            line_number,
+            context,
+            &mut issues,
+        ));
+    }
+
+    (issues, output.join("\n"))
+}
+
+fn analyze_vault(
+    root: &Path,
+    payload: &AnalyzeObsidianVaultPayload,
+) -> Result<ObsidianImportReport> {
+    let settings = read_obsidian_settings(root);
+    let mut markdown_files = list_markdown_files_via_find(root)?;
+    markdown_files.sort();
+    let catalog = build_catalog(root, &markdown_files)?;
+    let apply = payload.normalize && !payload.dry_run;
+
+    let mut files = Vec::new();
+    let mut rewrites = Vec::new();
+    for path in &markdown_files {
+        let Ok(markdown) = fs::read_to_string(path) else {
+            continue;
+        };
+        let relative = relative_string(root, path).unwrap_or_default();
+        let note_dir = relative
+            .rsplit_once('/')
+            .map(|(dir, _)| dir.to_string())
+            .unwrap_or_default();
+        let context = FileContext {
+            note_dir,
+            settings: &settings,
+            catalog: &catalog,
+        };
+        let (issues, normalized) = convert_note(&markdown, &context);
+        let changed = payload.normalize && normalized != markdown;
+        if apply && changed {
+            fs::write(path, &normalized)?;
+            record_workspace_mutation_write(path, &normalized);
+            rewrites.push((path.clone(), markdown, normalized));
+        }
+        if !issues.is_empty() || changed {
+            files.push(ObsidianFileReport {
+                path: relative,
+                issues,
+                changed,
+            });
+        }
+    }
+
+    for (path, _, _) in &rewrites {
+        reindex_markdown_file_now_sync(path.to_string_lossy().to_string())?;
+    }
+    let changed_files = files.iter().filter(|file| file.changed).count();
+    if !rewrites.is_empty() {
+        let _ = record_link_rewrites(root, &[], rewrites);
+    }
+
+    Ok(ObsidianImportReport {
+        mapping_notes: mapping_notes(&settings),
+        settings,
+        issue_count: files.iter().map(|file| file.issues.len()).sum(),
+        scanned_files: markdown_files.len(),
+        changed_files,
+        files,
+        dry_run: !apply,
+    })
+}
+
+/// Reports Obsidian-specific syntax in the active workspace and optionally fixes it.
+#[tauri::command]
+pub async fn analyze_obsidian_vault(
+    payload: Option<AnalyzeObsidianVaultPayload>,
+) -> Result<ObsidianImportReport> {
+    let root = active_workspace_root()?;
+    let payload = payload.unwrap_or(AnalyzeObsidianVaultPayload {
+        normalize: false,
+        dry_run: true,
+    });
+    tauri::async_runtime::spawn_blocking(move || analyze_vault(&root, &payload))
+        .await
+        .map_err(|_| AppError::OperationFailed)?
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::*;
+
+    fn create_temp_workspace(prefix: &str) -> PathBuf {
+        let nonce = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|value| value.as_nanos())
+            .unwrap_or(0);
+        let dir = std::env::temp_dir().join(format!("{prefix}-{nonce}"));
+        fs::create_dir_all(&dir).expect("create temp workspace");
+        fs::canonicalize(dir).expect("canonical workspace")
+    }
+
+    fn catalog(notes: &[&str], attachments: &[&str]) -> VaultCatalog {
+        let mut note_keys = HashSet::new();
+        let mut note_targets = HashMap::new();
+        let mut keys_by_basename: HashMap<String, Vec<String>> = HashMap::new();
+        for note in notes {
+            let key = normalize_wikilink_target(note).expect("note key");
+            keys_by_basename
+                .entry(note_key_basename(&key))
+                .or_default()
+                .push(key.clone());
+            note_targets.insert(key.clone(), strip_markdown_suffix(note).to_string());
+            note_keys.insert(key);
+        }
+        VaultCatalog {
+            note_keys,
+            note_targets,
+            keys_by_basename,
+            attachments: attachments.iter().map(|value| value.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn computes_relative_paths_between_folders() {
+        assert_eq!(
+            relative_from("notes/sub", "assets/a.png"),
+            "../../assets/a.png"
+        );
+        assert_eq!(relative_from("", "assets/a.png"), "assets/a.png");
+        assert_eq!(relative_from("assets", "assets/a.png"), "a.png");
+        assert_eq!(
+            join_relative("notes/sub", "../x"),
+            Some("notes/x".to_string())
+        );
+        assert_eq!(join_relative("", "../x"), None);
+    }
+
+    #[test]
+    fn classifies_links_with_tomosona_resolution_rules() {
+        let catalog = catalog(
+            &[
+                "projects/alpha/plan.md",
+                "a/todo.md",
+                "b/todo.md",
+                "home.md",
+            ],
+            &[],
+        );
+        assert!(matches!(
+            resolve_link("home", "", &catalog),
+            LinkResolution::Supported
+        ));
+        assert!(matches!(
+            resolve_link("plan", "", &catalog),
+            LinkResolution::Supported
+        ));
+        assert!(matches!(
+            resolve_link("todo", "", &catalog),
+            LinkResolution::Ambiguous
+        ));
+        assert!(matches!(
+            resolve_link("alpha/plan", "", &catalog),
+            LinkResolution::Rewrite(target) if target == "projects/alpha/plan"
+        ));
+        assert!(matches!(
+            resolve_link("../alpha/plan", "projects/beta", &catalog),
+            LinkResolution::Rewrite(target) if target == "projects/alpha/plan"
+        ));
+        assert!(matches!(
+            resolve_link("missing", "", &catalog),
+            LinkResolution::Missing
+        ));
+    }
+
+    #[test]
+    fn converts_comments_embeds_and_links() {
+        let catalog = catalog(
+            &["projects/alpha/plan.md", "home.md"],
+            &["assets/diagram one.png"],
+        );
+        let settings = ObsidianVaultSettings {
+            found: true,
+            attachment_folder: Some("assets".to_string()),
+            ..Default::default()
+        };
+        let context = FileContext {
+            note_dir: "projects".to_string(),
+            settings: &settings,
+            catalog: &catalog,
+        };
+        let markdown = "See [[alpha/plan|the plan]] %%todo%%\n![[diagram one.png|400]]\n%%start\nhidden%%\n```dataview\n[[not a link]]\n```\nText ![[home]] and [[a\\|b]]";
+        let (issues, normalized) = convert_note(markdown, &context);
+
+        assert_eq!(
+            normalized,
+            "See [[projects/alpha/plan|the plan]] <!-- todo -->\n![diagram one.png](<../assets/diagram one.png>)\n<!-- start\nhidden -->\n```dataview\n[[not a link]]\n```\nText ![[home]] and [[a\\|b]]"
+        );
+        let kinds = issues.iter().map(|issue| issue.kind).collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                ObsidianIssueKind::Comment,
+                ObsidianIssueKind::RelativeLink,
+                ObsidianIssueKind::AttachmentEmbed,
+                ObsidianIssueKind::Comment,
+                ObsidianIssueKind::PluginCodeBlock,
+                ObsidianIssueKind::InlineEmbed,
+                ObsidianIssueKind::EscapedAliasPipe,
+                ObsidianIssueKind::UnresolvedLink,
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_settings_and_normalizes_only_when_not_dry_run() {
+        let workspace = create_temp_workspace("tomosona-obsidian-import");
+        fs::create_dir_all(workspace.join(".obsidian")).expect("create obsidian dir");
+        fs::write(
+            workspace.join(".obsidian/app.json"),
+            r#"{"attachmentFolderPath":"assets","newFileLocation":"folder","newFileFolderPath":"inbox"}"#,
+        )
+        .expect("write app settings");
+        fs::write(workspace.join("note.md"), "%%draft%% body").expect("write note");
+
+        let dry_run = AnalyzeObsidianVaultPayload {
+            normalize: true,
+            dry_run: true,
+        };
+        let report = analyze_vault(&workspace, &dry_run).expect("dry run");
+        assert!(report.dry_run);
+        assert_eq!(report.settings.attachment_folder.as_deref(), Some("assets"));
+        assert_eq!(report.settings.new_note_folder.as_deref(), Some("inbox"));
+        assert_eq!(report.changed_files, 1);
+        assert_eq!(
+            fs::read_to_string(workspace.join("note.md")).expect("read note"),
+            "%%draft%% body"
+        );
+
+        fs::remove_dir_all(workspace).expect("cleanup workspace");
+    }
+}
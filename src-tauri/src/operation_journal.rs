@@ -0,0 +1,1060 @@
+//! Undo/redo journal for workspace file operations.
+//!
+//! Rename, move, duplicate, copy and trash in `fs_ops` record one operation each in
+//! `.tomosona/operation-journal.json`. Wikilink rewrites that follow a move are
+//! attached to that move, so undoing a folder drag restores both the files and every
+//! note whose links were rewritten. Undo and redo check every step before touching
+//! the disk and roll back already applied steps if one of them fails. Files replaced
+//! with the `overwrite` conflict strategy are not kept, so undo cannot bring them back.
+//! Undoing a copy moves it to the workspace trash, and is refused once the copy changed.
+//!
+//! A link rewrite keeps only the span of the note it changed and hashes of the whole
+//! note before and after. Spans larger than `JOURNAL_MAX_EDIT_BYTES` are not kept;
+//! the operation stays in the journal but undo refuses it instead of guessing.
+
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use atomicwrites::{AllowOverwrite, AtomicFile};
+use serde::{Deserialize, Serialize};
+
+use crate::editor_sync::{
+    record_workspace_mutation_write, record_workspace_mutation_write_from_disk,
+};
+use crate::fs_ops::{copy_dir_recursive, move_to_trash};
+use crate::markdown_index::{
+    reindex_markdown_file_lexical_sync, reindex_markdown_file_semantic_sync,
+    remove_markdown_file_from_index_sync,
+};
+use crate::{
+    active_workspace_root, list_markdown_files_via_find, normalize_workspace_relative_path, now_ms,
+    refresh_semantic_edges_cache_now_sync, trash, AppError, Result,
+};
+
+const INTERNAL_DIR_NAME: &str = ".tomosona";
+const JOURNAL_FILE_NAME: &str = "operation-journal.json";
+const JOURNAL_VERSION: u8 = 1;
+const JOURNAL_KEEP_LAST: usize = 50;
+const JOURNAL_MAX_EDIT_BYTES: usize = 64 * 1024;
+
+static JOURNAL_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationKind {
+    Rename,
+    Move,
+    Duplicate,
+    Copy,
+    Trash,
+    LinkRewrite,
+}
+
+/// One reversible change. Paths are workspace-relative with `/` separators.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum JournalStep {
+    Move {
+        from: String,
+        to: String,
+    },
+    Copy {
+        from: String,
+        to: String,
+        /// `entry_hash` of the copy when it was made.
+        #[serde(default)]
+        content_hash: String,
+    },
+    Trash {
+        from: String,
+        to: String,
+        size_bytes: u64,
+        is_dir: bool,
+    },
+    Write {
+        path: String,
+        before_hash: String,
+        after_hash: String,
+        /// `None` when the changed span was too large to keep.
+        edit: Option<TextEdit>,
+    },
+}
+
+/// Span of a note replaced by a rewrite; the text around it is left unchanged.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct TextEdit {
+    /// Byte offset of the span in both versions of the note.
+    offset: usize,
+    before: String,
+    after: String,
+}
+
+impl TextEdit {
+    /// Smallest span turning `before` into `after`, found by trimming the common
+    /// prefix and suffix.
+    fn between(before: &str, after: &str) -> Self {
+        let mut prefix = before
+            .bytes()
+            .zip(after.bytes())
+            .take_while(|(left, right)| left == right)
+            .count();
+        while !before.is_char_boundary(prefix) {
+            prefix -= 1;
+        }
+        let max_suffix = before.len().min(after.len()) - prefix;
+        let mut suffix = before
+            .bytes()
+            .rev()
+            .zip(after.bytes().rev())
+            .take(max_suffix)
+            .take_while(|(left, right)| left == right)
+            .count();
+        while !before.is_char_boundary(before.len() - suffix) {
+            suffix -= 1;
+        }
+        TextEdit {
+            offset: prefix,
+            before: before[prefix..before.len() - suffix].to_string(),
+            after: after[prefix..after.len() - suffix].to_string(),
+        }
+    }
+
+    fn size(&self) -> usize {
+        self.before.len() + self.after.len()
+    }
+
+    /// Rebuilds the other version of a note from `current`, which holds `from`.
+    fn apply(&self, current: &str, from: &str, to: &str) -> Option<String> {
+        let end = self.offset.checked_add(from.len())?;
+        if current.get(self.offset..end)? != from {
+            return None;
+        }
+        Some(format!(
+            "{}{to}{}",
+            &current[..self.offset],
+            &current[end..]
+        ))
+    }
+}
+
+fn content_hash(content: &str) -> String {
+    blake3::hash(content.as_bytes()).to_hex().to_string()
+}
+
+/// Hash of a file, or of every path and file content below a folder.
+fn entry_hash(path: &Path) -> Result<String> {
+    fn feed(hasher: &mut blake3::Hasher, base: &Path, path: &Path) -> Result<()> {
+        let relative = path.strip_prefix(base).unwrap_or(path);
+        hasher.update(relative.to_string_lossy().as_bytes());
+        hasher.update(&[0]);
+        if path.is_dir() {
+            let mut children = fs::read_dir(path)?
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<std::io::Result<Vec<_>>>()?;
+            children.sort();
+            for child in children {
+                feed(hasher, base, &child)?;
+            }
+        } else {
+            hasher.update(&fs::read(path)?);
+            hasher.update(&[0]);
+        }
+        Ok(())
+    }
+
+    let mut hasher = blake3::Hasher::new();
+    feed(&mut hasher, path, path)?;
+    Ok(hasher.finalize().to_hex().to_string())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct JournalOperation {
+    id: String,
+    kind: OperationKind,
+    label: String,
+    created_at_ms: u64,
+    steps: Vec<JournalStep>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct OperationJournal {
+    version: u8,
+    #[serde(default)]
+    undo: Vec<JournalOperation>,
+    #[serde(default)]
+    redo: Vec<JournalOperation>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OperationSummary {
+    pub id: String,
+    pub kind: OperationKind,
+    pub label: String,
+    pub created_at_ms: u64,
+    pub moved_paths: usize,
+    pub rewritten_files: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OperationJournalState {
+    pub undo: Vec<OperationSummary>,
+    pub redo: Vec<OperationSummary>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UndoRedoResult {
+    pub operation: Option<OperationSummary>,
+    /// Absolute paths touched by the operation, for editor refresh.
+    pub changed_paths: Vec<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Undo,
+    Redo,
+}
+
+fn lock_journal() -> Result<std::sync::MutexGuard<'static, ()>> {
+    JOURNAL_LOCK.lock().map_err(|_| AppError::OperationFailed)
+}
+
+fn journal_path(root: &Path) -> Result<PathBuf> {
+    let dir = root.join(INTERNAL_DIR_NAME);
+    fs::create_dir_all(&dir)?;
+    Ok(dir.join(JOURNAL_FILE_NAME))
+}
+
+fn read_journal(root: &Path) -> Result<OperationJournal> {
+    let path = journal_path(root)?;
+    if !path.exists() {
+        return Ok(OperationJournal {
+            version: JOURNAL_VERSION,
+            ..Default::default()
+        });
+    }
+
+    let raw = fs::read_to_string(path)?;
+    let parsed: OperationJournal = serde_json::from_str(&raw)
+        .map_err(|_| AppError::InvalidOperation("Operation journal is invalid.".to_string()))?;
+    if parsed.version != JOURNAL_VERSION {
+        return Err(AppError::InvalidOperation(
+            "Operation journal version is not supported.".to_string(),
+        ));
+    }
+    Ok(parsed)
+}
+
+/// Replaces `path` through a temp file, so a crash mid-write never truncates it.
+fn write_atomically(path: &Path, content: &str) -> Result<()> {
+    AtomicFile::new(path, AllowOverwrite)
+        .write(|file| {
+            file.write_all(content.as_bytes())?;
+            file.flush()?;
+            file.sync_all()?;
+            Ok(())
+        })
+        .map_err(|err| match err {
+            atomicwrites::Error::Internal(error) | atomicwrites::Error::User(error) => {
+                AppError::Io(error)
+            }
+        })
+}
+
+fn write_journal(root: &Path, journal: &OperationJournal) -> Result<()> {
+    let path = journal_path(root)?;
+    let content = serde_json::to_string(journal).map_err(|_| AppError::OperationFailed)?;
+    write_atomically(&path, &format!("{content}\n"))
+}
+
+/// Returns the workspace-relative form of a path that may no longer exist.
+fn journal_relative_path(root: &Path, path: &Path) -> Result<String> {
+    let resolved = match fs::canonicalize(path) {
+        Ok(value) => value,
+        Err(_) => {
+            let parent = path.parent().ok_or(AppError::InvalidPath)?;
+            let file_name = path.file_name().ok_or(AppError::InvalidPath)?;
+            fs::canonicalize(parent)?.join(file_name)
+        }
+    };
+    let relative = normalize_workspace_relative_path(root, &resolved)?;
+    if relative.is_empty() {
+        return Err(AppError::InvalidPath);
+    }
+    Ok(relative)
+}
+
+fn summarize(operation: &JournalOperation) -> OperationSummary {
+    let moved_paths = operation
+        .steps
+        .iter()
+        .filter(|step| !matches!(step, JournalStep::Write { .. }))
+        .count();
+    OperationSummary {
+        id: operation.id.clone(),
+        kind: operation.kind,
+        label: operation.label.clone(),
+        created_at_ms: operation.created_at_ms,
+        moved_paths,
+        rewritten_files: operation.steps.len() - moved_paths,
+    }
+}
+
+fn operation_label(kind: OperationKind, steps: &[JournalStep]) -> String {
+    let subject = steps
+        .iter()
+        .find_map(|step| match step {
+            JournalStep::Move { from, .. }
+            | JournalStep::Copy { from, .. }
+            | JournalStep::Trash { from, .. } => Some(from.as_str()),
+            JournalStep::Write { .. } => None,
+        })
+        .and_then(|path| path.rsplit('/').next())
+        .unwrap_or("");
+    let verb = match kind {
+        OperationKind::Rename => "Rename",
+        OperationKind::Move => "Move",
+        OperationKind::Duplicate => "Duplicate",
+        OperationKind::Copy => "Copy",
+        OperationKind::Trash => "Move to trash",
+        OperationKind::LinkRewrite => return format!("Update links in {} notes", steps.len()),
+    };
+    format!("{verb} {subject}").trim_end().to_string()
+}
+
+fn push_operation(root: &Path, kind: OperationKind, steps: Vec<JournalStep>) -> Result<()> {
+    if steps.is_empty() {
+        return Ok(());
+    }
+    let _lock = lock_journal()?;
+    let mut journal = read_journal(root)?;
+    append_operation(&mut journal, kind, steps);
+    write_journal(root, &journal)
+}
+
+/// Adds an operation to the undo stack of a journal read under the journal lock.
+fn append_operation(journal: &mut OperationJournal, kind: OperationKind, steps: Vec<JournalStep>) {
+    let created_at_ms = now_ms();
+    journal.undo.push(JournalOperation {
+        id: format!("op-{created_at_ms}-{}", journal.undo.len()),
+        kind,
+        label: operation_label(kind, &steps),
+        created_at_ms,
+        steps,
+    });
+    if journal.undo.len() > JOURNAL_KEEP_LAST {
+        let overflow = journal.undo.len() - JOURNAL_KEEP_LAST;
+        journal.undo.drain(..overflow);
+    }
+    journal.redo.clear();
+}
+
+/// Records a rename or move of `from` to `to`, called after the rename succeeded.
+pub(crate) fn record_move(root: &Path, kind: OperationKind, from: &Path, to: &Path) -> Result<()> {
+    let step = JournalStep::Move {
+        from: journal_relative_path(root, from)?,
+        to: journal_relative_path(root, to)?,
+    };
+    push_operation(root, kind, vec![step])
+}
+
+/// Records a duplicate or copy of `from` to the newly created `to`.
+pub(crate) fn record_copy(root: &Path, kind: OperationKind, from: &Path, to: &Path) -> Result<()> {
+    let step = JournalStep::Copy {
+        from: journal_relative_path(root, from)?,
+        to: journal_relative_path(root, to)?,
+        content_hash: entry_hash(to)?,
+    };
+    push_operation(root, kind, vec![step])
+}
+
+/// Records an entry moved to the workspace trash.
+pub(crate) fn record_trash(
+    root: &Path,
+    from: &Path,
+    to: &Path,
+    size_bytes: u64,
+    is_dir: bool,
+) -> Result<()> {
+    let step = JournalStep::Trash {
+        from: journal_relative_path(root, from)?,
+        to: journal_relative_path(root, to)?,
+        size_bytes,
+        is_dir,
+    };
+    push_operation(root, OperationKind::Trash, vec![step])
+}
+
+/// Records note contents rewritten after `moved_to` paths were renamed or moved.
+///
+/// The writes join the latest operation when it moved one of those paths, so undo
+/// reverts the move and its link updates together.
+pub(crate) fn record_link_rewrites(
+    root: &Path,
+    moved_to: &[PathBuf],
+    writes: Vec<(PathBuf, String, String)>,
+) -> Result<()> {
+    let mut steps = Vec::new();
+    for (path, before, after) in writes {
+        let edit = TextEdit::between(&before, &after);
+        steps.push(JournalStep::Write {
+            path: journal_relative_path(root, &path)?,
+            before_hash: content_hash(&before),
+            after_hash: content_hash(&after),
+            edit: (edit.size() <= JOURNAL_MAX_EDIT_BYTES).then_some(edit),
+        });
+    }
+    if steps.is_empty() {
+        return Ok(());
+    }
+    let moved_to = moved_to
+        .iter()
+        .filter_map(|path| journal_relative_path(root, path).ok())
+        .collect::<Vec<_>>();
+
+    let _lock = lock_journal()?;
+    let mut journal = read_journal(root)?;
+    let last_move = journal.undo.last_mut().filter(|operation| {
+        operation.steps.iter().any(|step| match step {
+            JournalStep::Move { to, .. } => moved_to.iter().any(|path| path == to),
+            _ => false,
+        })
+    });
+    match last_move {
+        Some(operation) => operation.steps.extend(steps),
+        None => append_operation(&mut journal, OperationKind::LinkRewrite, steps),
+    }
+    write_journal(root, &journal)
+}
+
+fn absolute(root: &Path, relative: &str) -> PathBuf {
+    root.join(relative)
+}
+
+fn path_taken(path: &Path) -> bool {
+    fs::symlink_metadata(path).is_ok()
+}
+
+fn conflict(message: &str, path: &str) -> AppError {
+    AppError::InvalidOperation(format!("{message}: {path}"))
+}
+
+/// Where `path` is before the steps recorded in `moved` run.
+///
+/// `moved` holds `(after, before)` pairs of the steps preceding the checked one, so a
+/// note rewritten after its folder moved is looked up where it still is.
+fn located(path: &str, moved: &[(&str, &str)]) -> String {
+    let mut current = path.to_string();
+    for (after, before) in moved.iter().rev() {
+        if current == *after {
+            current = before.to_string();
+        } else if let Some(rest) = current
+            .strip_prefix(after)
+            .and_then(|rest| rest.strip_prefix('/'))
+        {
+            current = format!("{before}/{rest}");
+        }
+    }
+    current
+}
+
+/// Path a step moves in `direction`, as `(before, after)`.
+fn moved_path(step: &JournalStep, direction: Direction) -> Option<(&str, &str)> {
+    match step {
+        JournalStep::Move { from, to } | JournalStep::Trash { from, to, .. } => match direction {
+            Direction::Undo => Some((to, from)),
+            Direction::Redo => Some((from, to)),
+        },
+        JournalStep::Copy { .. } | JournalStep::Write { .. } => None,
+    }
+}
+
+/// Checks that a step can be applied in `direction` without overwriting anything,
+/// once the steps recorded in `moved` ran.
+fn check_step(
+    root: &Path,
+    step: &JournalStep,
+    direction: Direction,
+    moved: &[(&str, &str)],
+) -> Result<()> {
+    let at = |path: &str| absolute(root, &located(path, moved));
+    match step {
+        JournalStep::Move { from, to } | JournalStep::Trash { from, to, .. } => {
+            let (source, target) = match direction {
+                Direction::Undo => (to, from),
+                Direction::Redo => (from, to),
+            };
+            if !path_taken(&at(source)) {
+                return Err(conflict("Path no longer exists", source));
+            }
+            if path_taken(&at(target)) {
+                return Err(conflict("Path is already taken", target));
+            }
+        }
+        JournalStep::Copy {
+            from,
+            to,
+            content_hash,
+        } => {
+            // Undo trashes the copy and redo copies the source again, so either one
+            // must still hold the content the copy was made with.
+            let (source, target) = match direction {
+                Direction::Undo => (to, None),
+                Direction::Redo => (from, Some(to)),
+            };
+            if !path_taken(&at(source)) {
+                return Err(conflict("Path no longer exists", source));
+            }
+            if entry_hash(&at(source)).ok().as_ref() != Some(content_hash) {
+                return Err(conflict("Path changed since the operation", source));
+            }
+            if let Some(target) = target.filter(|target| path_taken(&at(target))) {
+                return Err(conflict("Path is already taken", target));
+            }
+        }
+        JournalStep::Write {
+            path,
+            before_hash,
+            after_hash,
+            edit,
+        } => {
+            let expected = match direction {
+                Direction::Undo => after_hash,
+                Direction::Redo => before_hash,
+            };
+            let current = fs::read_to_string(at(path)).unwrap_or_default();
+            if &content_hash(&current) != expected {
+                return Err(conflict("Note changed since the operation", path));
+            }
+            if edit.is_none() {
+                return Err(conflict("Link changes are too large to revert", path));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn move_path(source: &Path, target: &Path) -> Result<()> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::rename(source, target)?;
+    if target.is_file() {
+        record_workspace_mutation_write_from_disk(target);
+    }
+    Ok(())
+}
+
+fn write_note(path: &Path, content: &str) -> Result<()> {
+    write_atomically(path, content)?;
+    record_workspace_mutation_write(path, content);
+    Ok(())
+}
+
+fn apply_step(root: &Path, step: &JournalStep, direction: Direction) -> Result<()> {
+    match step {
+        JournalStep::Move { from, to } => match direction {
+            Direction::Undo => move_path(&absolute(root, to), &absolute(root, from)),
+            Direction::Redo => move_path(&absolute(root, from), &absolute(root, to)),
+        },
+        JournalStep::Trash {
+            from,
+            to,
+            size_bytes,
+            is_dir,
+        } => match direction {
+            Direction::Undo => move_path(&absolute(root, to), &absolute(root, from)),
+            Direction::Redo => {
+                let target = absolute(root, to);
+                let source = absolute(root, from);
+                move_path(&source, &target)?;
+                if let Err(err) =
+                    trash::record_trashed_entry(root, &target, from, *size_bytes, *is_dir)
+                {
+                    move_path(&target, &source)?;
+                    return Err(err);
+                }
+                Ok(())
+            }
+        },
+        JournalStep::Copy { from, to, .. } => match direction {
+            Direction::Undo => move_to_trash(root, &absolute(root, to)).map(|_| ()),
+            Direction::Redo => {
+                let source = absolute(root, from);
+                let target = absolute(root, to);
+                if source.is_dir() {
+                    copy_dir_recursive(&source, &target)
+                } else {
+                    fs::copy(&source, &target)?;
+                    Ok(())
+                }
+            }
+        },
+        JournalStep::Write { path, edit, .. } => {
+            let edit = edit
+                .as_ref()
+                .ok_or_else(|| conflict("Link changes are too large to revert", path))?;
+            let (from, to) = match direction {
+                Direction::Undo => (&edit.after, &edit.before),
+                Direction::Redo => (&edit.before, &edit.after),
+            };
+            let note = absolute(root, path);
+            let current = fs::read_to_string(&note)?;
+            let content = edit
+                .apply(&current, from, to)
+                .ok_or_else(|| conflict("Note changed since the operation", path))?;
+            write_note(&note, &content)
+        }
+    }
+}
+
+fn reverse(direction: Direction) -> Direction {
+    match direction {
+        Direction::Undo => Direction::Redo,
+        Direction::Redo => Direction::Undo,
+    }
+}
+
+/// Applies all steps of an operation or none of them.
+///
+/// Every step is checked before the first one runs. Undo walks the steps backwards so
+/// link edits are reverted before their notes move back; if a step still fails, the
+/// steps applied so far are replayed in the other direction.
+fn apply_operation(root: &Path, operation: &JournalOperation, direction: Direction) -> Result<()> {
+    let ordered: Vec<&JournalStep> = match direction {
+        Direction::Undo => operation.steps.iter().rev().collect(),
+        Direction::Redo => operation.steps.iter().collect(),
+    };
+    let mut moved = Vec::new();
+    for step in &ordered {
+        check_step(root, step, direction, &moved)?;
+        if let Some((before, after)) = moved_path(step, direction) {
+            moved.push((after, before));
+        }
+    }
+
+    for (index, step) in ordered.iter().enumerate() {
+        if let Err(err) = apply_step(root, step, direction) {
+            for applied in ordered[..index].iter().rev() {
+                let _ = apply_step(root, applied, reverse(direction));
+            }
+            return Err(err);
+        }
+    }
+    Ok(())
+}
+
+fn is_markdown_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown"))
+}
+
+fn markdown_files_under(path: &Path) -> Vec<PathBuf> {
+    if path.is_dir() {
+        list_markdown_files_via_find(path).unwrap_or_default()
+    } else if is_markdown_path(path) {
+        vec![path.to_path_buf()]
+    } else {
+        Vec::new()
+    }
+}
+
+/// Brings the index in line with the files an operation touched.
+///
+/// Indexing is best effort: the files are already restored and the workspace
+/// watcher reports the same changes to the frontend.
+fn refresh_index(root: &Path, operation: &JournalOperation, direction: Direction) -> Vec<String> {
+    let mut removed = Vec::new();
+    let mut present = Vec::new();
+    for step in &operation.steps {
+        match step {
+            JournalStep::Move { from, to } | JournalStep::Trash { from, to, .. } => {
+                let (gone, now) = match direction {
+                    Direction::Undo => (to, from),
+                    Direction::Redo => (from, to),
+                };
+                let (gone_path, now_path) = (absolute(root, gone), absolute(root, now));
+                for note in markdown_files_under(&now_path) {
+                    match note.strip_prefix(&now_path) {
+                        Ok(relative) if !relative.as_os_str().is_empty() => {
+                            removed.push(gone_path.join(relative))
+                        }
+                        _ => removed.push(gone_path.clone()),
+                    }
+                    present.push(note);
+                }
+            }
+            JournalStep::Copy { to, .. } => match direction {
+                Direction::Undo => removed.push(absolute(root, to)),
+                Direction::Redo => present.extend(markdown_files_under(&absolute(root, to))),
+            },
+            JournalStep::Write { path, .. } => present.push(absolute(root, path)),
+        }
+    }
+
+    let is_internal = |path: &Path| {
+        path.strip_prefix(root)
+            .ok()
+            .and_then(|relative| relative.components().next())
+            .is_some_and(|first| first.as_os_str().to_string_lossy().starts_with('.'))
+    };
+    for path in &removed {
+        let _ = remove_markdown_file_from_index_sync(path.to_string_lossy().to_string());
+    }
+    present.retain(|path| !is_internal(path));
+    present.sort();
+    present.dedup();
+    for path in &present {
+        let value = path.to_string_lossy().to_string();
+        let _ = reindex_markdown_file_lexical_sync(value.clone());
+        let _ = reindex_markdown_file_semantic_sync(value);
+    }
+    if !present.is_empty() || !removed.is_empty() {
+        let _ = refresh_semantic_edges_cache_now_sync();
+    }
+
+    let mut changed = removed
+        .into_iter()
+        .chain(present)
+        .map(|path| path.to_string_lossy().to_string())
+        .collect::<Vec<_>>();
+    changed.sort();
+    changed.dedup();
+    changed
+}
+
+fn run_journal_step(root: &Path, direction: Direction) -> Result<UndoRedoResult> {
+    let lock = lock_journal()?;
+    let mut journal = read_journal(root)?;
+    let source = match direction {
+        Direction::Undo => &mut journal.undo,
+        Direction::Redo => &mut journal.redo,
+    };
+    let Some(operation) = source.pop() else {
+        return Ok(UndoRedoResult {
+            operation: None,
+            changed_paths: Vec::new(),
+        });
+    };
+
+    apply_operation(root, &operation, direction)?;
+    let summary = summarize(&operation);
+    match direction {
+        Direction::Undo => journal.redo.push(operation.clone()),
+        Direction::Redo => journal.undo.push(operation.clone()),
+    }
+    write_journal(root, &journal)?;
+    drop(lock);
+
+    Ok(UndoRedoResult {
+        operation: Some(summary),
+        changed_paths: refresh_index(root, &operation, direction),
+    })
+}
+
+#[tauri::command]
+pub fn list_operation_journal() -> Result<OperationJournalState> {
+    let root = active_workspace_root()?;
+    let _lock = lock_journal()?;
+    let journal = read_journal(&root)?;
+    Ok(OperationJournalState {
+        undo: journal.undo.iter().rev().map(summarize).collect(),
+        redo: journal.redo.iter().rev().map(summarize).collect(),
+    })
+}
+
+/// Reverts the latest file operation together with its link rewrites.
+#[tauri::command]
+pub fn undo_last_operation() -> Result<UndoRedoResult> {
+    let root = active_workspace_root()?;
+    run_journal_step(&root, Direction::Undo)
+}
+
+/// Re-applies the latest undone file operation.
+#[tauri::command]
+pub fn redo_operation() -> Result<UndoRedoResult> {
+    let root = active_workspace_root()?;
+    run_journal_step(&root, Direction::Redo)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::*;
+    use crate::fs_ops::{copy_entry, move_entry, rename_entry, trash_entry, ConflictStrategy};
+    use crate::{clear_active_workspace, set_active_workspace, workspace_test_guard};
+
+    fn create_temp_workspace(prefix: &str) -> PathBuf {
+        let nonce = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|value| value.as_nanos())
+            .unwrap_or(0);
+        let dir = std::env::temp_dir().join(format!("{prefix}-{nonce}"));
+        fs::create_dir_all(&dir).expect("create temp workspace");
+        fs::canonicalize(dir).expect("canonical workspace")
+    }
+
+    #[test]
+    fn undo_and_redo_move_with_link_rewrites() {
+        let _guard = workspace_test_guard();
+        let workspace = create_temp_workspace("tomosona-journal-move");
+        fs::create_dir_all(workspace.join("archive")).expect("create archive");
+        fs::write(workspace.join("plan.txt"), "plan").expect("write plan");
+        fs::write(workspace.join("index.txt"), "[[plan]]").expect("write index");
+        set_active_workspace(&workspace.to_string_lossy()).expect("set workspace");
+
+        let moved = move_entry(
+            workspace.join("plan.txt").to_string_lossy().to_string(),
+            workspace.join("archive").to_string_lossy().to_string(),
+            ConflictStrategy::Fail,
+        )
+        .expect("move entry");
+        fs::write(workspace.join("index.txt"), "[[archive/plan]]").expect("rewrite index");
+        record_link_rewrites(
+            &workspace,
+            &[PathBuf::from(&moved)],
+            vec![(
+                workspace.join("index.txt"),
+                "[[plan]]".to_string(),
+                "[[archive/plan]]".to_string(),
+            )],
+        )
+        .expect("record rewrites");
+
+        let state = list_operation_journal().expect("journal state");
+        assert_eq!(state.undo.len(), 1);
+        assert_eq!(state.undo[0].kind, OperationKind::Move);
+        assert_eq!(state.undo[0].rewritten_files, 1);
+
+        let undone = undo_last_operation().expect("undo");
+        assert!(undone.operation.is_some());
+        assert!(workspace.join("plan.txt").exists());
+        assert!(!workspace.join("archive/plan.txt").exists());
+        assert_eq!(
+            fs::read_to_string(workspace.join("index.txt")).expect("read index"),
+            "[[plan]]"
+        );
+
+        redo_operation().expect("redo");
+        assert!(workspace.join("archive/plan.txt").exists());
+        assert_eq!(
+            fs::read_to_string(workspace.join("index.txt")).expect("read index"),
+            "[[archive/plan]]"
+        );
+
+        clear_active_workspace().expect("clear workspace");
+        fs::remove_dir_all(workspace).expect("cleanup workspace");
+    }
+
+    #[test]
+    fn undo_reverts_rename_copy_and_trash_in_order() {
+        let _guard = workspace_test_guard();
+        let workspace = create_temp_workspace("tomosona-journal-stack");
+        fs::create_dir_all(workspace.join("target")).expect("create target");
+        fs::write(workspace.join("a.txt"), "a").expect("write a");
+        set_active_workspace(&workspace.to_string_lossy()).expect("set workspace");
+
+        let renamed = rename_entry(
+            workspace.join("a.txt").to_string_lossy().to_string(),
+            "b.txt".to_string(),
+            ConflictStrategy::Fail,
+        )
+        .expect("rename");
+        copy_entry(
+            renamed.clone(),
+            workspace.join("target").to_string_lossy().to_string(),
+            ConflictStrategy::Fail,
+        )
+        .expect("copy");
+        trash_entry(renamed).expect("trash");
+        assert_eq!(list_operation_journal().expect("state").undo.len(), 3);
+
+        undo_last_operation().expect("undo trash");
+        assert!(workspace.join("b.txt").exists());
+        undo_last_operation().expect("undo copy");
+        assert!(!workspace.join("target/b.txt").exists());
+        undo_last_operation().expect("undo rename");
+        assert!(workspace.join("a.txt").exists());
+        assert!(undo_last_operation()
+            .expect("empty undo")
+            .operation
+            .is_none());
+        assert_eq!(list_operation_journal().expect("state").redo.len(), 3);
+
+        clear_active_workspace().expect("clear workspace");
+        fs::remove_dir_all(workspace).expect("cleanup workspace");
+    }
+
+    #[test]
+    fn link_rewrites_keep_only_the_changed_span() {
+        let edit = TextEdit::between(
+            "Voir [[été]] puis [[b]].",
+            "Voir [[archive/été]] puis [[b]].",
+        );
+        assert_eq!(edit.offset, 7);
+        assert_eq!(edit.before, "");
+        assert_eq!(edit.after, "archive/");
+        assert_eq!(
+            edit.apply(
+                "Voir [[archive/été]] puis [[b]].",
+                &edit.after,
+                &edit.before
+            ),
+            Some("Voir [[été]] puis [[b]].".to_string())
+        );
+
+        let edit = TextEdit::between("[[é]]", "[[è]]");
+        assert_eq!((edit.before.as_str(), edit.after.as_str()), ("é", "è"));
+        assert_eq!(TextEdit::between("same", "same").size(), 0);
+    }
+
+    #[test]
+    fn undo_refuses_link_rewrites_too_large_to_keep() {
+        let _guard = workspace_test_guard();
+        let workspace = create_temp_workspace("tomosona-journal-large");
+        fs::write(workspace.join("a.txt"), "a").expect("write a");
+        let before = format!("[[a]]{}[[a]]", "x".repeat(JOURNAL_MAX_EDIT_BYTES));
+        let after = format!("[[b]]{}[[b]]", "x".repeat(JOURNAL_MAX_EDIT_BYTES));
+        fs::write(workspace.join("index.txt"), &after).expect("write index");
+        set_active_workspace(&workspace.to_string_lossy()).expect("set workspace");
+
+        let renamed = rename_entry(
+            workspace.join("a.txt").to_string_lossy().to_string(),
+            "b.txt".to_string(),
+            ConflictStrategy::Fail,
+        )
+        .expect("rename");
+        record_link_rewrites(
+            &workspace,
+            &[PathBuf::from(renamed)],
+            vec![(workspace.join("index.txt"), before, after.clone())],
+        )
+        .expect("record rewrites");
+
+        let journal = fs::read_to_string(workspace.join(INTERNAL_DIR_NAME).join(JOURNAL_FILE_NAME))
+            .expect("read journal");
+        assert!(journal.len() < JOURNAL_MAX_EDIT_BYTES);
+        assert!(undo_last_operation().is_err());
+        assert!(workspace.join("b.txt").exists());
+        assert_eq!(
+            fs::read_to_string(workspace.join("index.txt")).expect("read index"),
+            after
+        );
+
+        clear_active_workspace().expect("clear workspace");
+        fs::remove_dir_all(workspace).expect("cleanup workspace");
+    }
+
+    #[test]
+    fn undo_refuses_when_a_rewritten_note_changed() {
+        let _guard = workspace_test_guard();
+        let workspace = create_temp_workspace("tomosona-journal-conflict");
+        fs::write(workspace.join("a.txt"), "a").expect("write a");
+        fs::write(workspace.join("index.txt"), "[[b]]").expect("write index");
+        set_active_workspace(&workspace.to_string_lossy()).expect("set workspace");
+
+        let renamed = rename_entry(
+            workspace.join("a.txt").to_string_lossy().to_string(),
+            "b.txt".to_string(),
+            ConflictStrategy::Fail,
+        )
+        .expect("rename");
+        record_link_rewrites(
+            &workspace,
+            &[PathBuf::from(renamed)],
+            vec![(
+                workspace.join("index.txt"),
+                "[[a]]".to_string(),
+                "[[b]]".to_string(),
+            )],
+        )
+        .expect("record rewrites");
+        fs::write(workspace.join("index.txt"), "[[b]] edited").expect("edit index");
+
+        assert!(undo_last_operation().is_err());
+        assert!(workspace.join("b.txt").exists());
+        assert_eq!(list_operation_journal().expect("state").undo.len(), 1);
+
+        clear_active_workspace().expect("clear workspace");
+        fs::remove_dir_all(workspace).expect("cleanup workspace");
+    }
+
+    #[test]
+    fn undo_trashes_an_unchanged_copy_and_refuses_an_edited_one() {
+        let _guard = workspace_test_guard();
+        let workspace = create_temp_workspace("tomosona-journal-copy");
+        fs::create_dir_all(workspace.join("target")).expect("create target");
+        fs::create_dir_all(workspace.join("folder")).expect("create folder");
+        fs::write(workspace.join("folder/a.txt"), "a").expect("write a");
+        set_active_workspace(&workspace.to_string_lossy()).expect("set workspace");
+
+        copy_entry(
+            workspace.join("folder").to_string_lossy().to_string(),
+            workspace.join("target").to_string_lossy().to_string(),
+            ConflictStrategy::Fail,
+        )
+        .expect("copy");
+        undo_last_operation().expect("undo unchanged copy");
+        assert!(!workspace.join("target/folder").exists());
+        let trashed = trash::list_trash_entries().expect("trash entries");
+        assert_eq!(trashed.entries.len(), 1);
+        assert_eq!(trashed.entries[0].original_path, "target/folder");
+
+        redo_operation().expect("redo copy");
+        fs::write(workspace.join("target/folder/a.txt"), "edited").expect("edit copy");
+        assert!(undo_last_operation().is_err());
+        assert_eq!(
+            fs::read_to_string(workspace.join("target/folder/a.txt")).expect("read copy"),
+            "edited"
+        );
+
+        clear_active_workspace().expect("clear workspace");
+        fs::remove_dir_all(workspace).expect("cleanup workspace");
+    }
+
+    #[test]
+    fn redo_checks_a_rewritten_note_where_its_move_puts_it() {
+        let _guard = workspace_test_guard();
+        let workspace = create_temp_workspace("tomosona-journal-moved-rewrite");
+        fs::create_dir_all(workspace.join("archive")).expect("create archive");
+        fs::write(workspace.join("plan.txt"), "[[plan]]").expect("write plan");
+        set_active_workspace(&workspace.to_string_lossy()).expect("set workspace");
+
+        let moved = move_entry(
+            workspace.join("plan.txt").to_string_lossy().to_string(),
+            workspace.join("archive").to_string_lossy().to_string(),
+            ConflictStrategy::Fail,
+        )
+        .expect("move entry");
+        fs::write(&moved, "[[archive/plan]]").expect("rewrite plan");
+        record_link_rewrites(
+            &workspace,
+            &[PathBuf::from(&moved)],
+            vec![(
+                PathBuf::from(&moved),
+                "[[plan]]".to_string(),
+                "[[archive/plan]]".to_string(),
+            )],
+        )
+        .expect("record rewrites");
+
+        undo_last_operation().expect("undo");
+        assert_eq!(
+            fs::read_to_string(workspace.join("plan.txt")).expect("read plan"),
+            "[[plan]]"
+        );
+        redo_operation().expect("redo");
+        assert_eq!(
+            fs::read_to_string(&moved).expect("read moved plan"),
+            "[[archive/plan]]"
+        );
+
+        clear_active_workspace().expect("clear workspace");
+        fs::remove_dir_all(workspace).expect("cleanup workspace");
+    }
+}
@@ -1,5 +1,9 @@
 use serde::{Deserialize, Serialize};
 
+use super::local_models::LocalServerKind;
+use super::prompt_packs::PromptLocale;
+use crate::secrets::ApiKeySource;
+
 fn default_temperature() -> f64 {
     0.15
 }
@@ -24,7 +28,12 @@ pub struct ProviderProfile {
     pub label: String,
     pub provider: String,
     pub model: String,
+    /// Key used for requests. Resolved at runtime from `key_source` and never written
+    /// back; a plaintext value read from an older `conf.json` is migrated to the vault.
+    #[serde(default, skip_serializing)]
     pub api_key: String,
+    #[serde(flatten)]
+    pub key_source: ApiKeySource,
     #[serde(default = "default_temperature")]
     pub default_temperature: f64,
     #[serde(default)]
@@ -35,12 +44,264 @@ pub struct ProviderProfile {
     pub default_mode: Option<String>,
     #[serde(default)]
     pub capabilities: ProfileCapabilities,
+    /// Replies of the offline `mock` provider; ignored by other providers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mock: Option<MockProviderSettings>,
+    /// Pricing and monthly limits checked before each request of this profile.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget: Option<ProfileBudget>,
+}
+
+/// What happens to requests once a monthly limit of a [`ProfileBudget`] is reached.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetAction {
+    /// Requests still run; the limit is reported as a warning.
+    #[default]
+    Warn,
+    /// Requests fail until the next month or a higher limit.
+    Block,
+}
+
+/// Per-profile pricing, used to cost recorded usage, and optional monthly limits.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProfileBudget {
+    #[serde(default)]
+    pub input_usd_per_million_tokens: Option<f64>,
+    #[serde(default)]
+    pub output_usd_per_million_tokens: Option<f64>,
+    #[serde(default)]
+    pub monthly_limit_usd: Option<f64>,
+    #[serde(default)]
+    pub monthly_limit_tokens: Option<u64>,
+    #[serde(default)]
+    pub on_exceed: BudgetAction,
+}
+
+impl ProfileBudget {
+    /// Whether no price or limit is set, so the budget has no effect.
+    pub fn is_empty(&self) -> bool {
+        self.input_usd_per_million_tokens.is_none()
+            && self.output_usd_per_million_tokens.is_none()
+            && self.monthly_limit_usd.is_none()
+            && self.monthly_limit_tokens.is_none()
+    }
+}
+
+/// Behavior of a `mock` profile, which answers without any network access.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MockProviderSettings {
+    /// Replies served in order and wrapping around; empty echoes the user prompt.
+    #[serde(default)]
+    pub responses: Vec<MockResponse>,
+    /// Characters per streamed chunk; 0 sends each reply as a single chunk.
+    #[serde(default)]
+    pub chunk_chars: usize,
+    /// Delay before each streamed chunk.
+    #[serde(default)]
+    pub latency_ms: u64,
+}
+
+/// One scripted reply: plain text, or text with an injected error.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MockResponse {
+    Text(String),
+    Scripted {
+        #[serde(default)]
+        text: String,
+        /// Error raised instead of completing the reply.
+        #[serde(default)]
+        error: Option<String>,
+        /// Chunks of `text` streamed before `error` is raised.
+        #[serde(default)]
+        error_after_chunks: usize,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SecondBrainConfig {
     pub active_profile: String,
     pub profiles: Vec<ProviderProfile>,
+    /// Forced prompt pack language; `None` picks the pack from the material language.
+    #[serde(default)]
+    pub prompt_language: Option<PromptLocale>,
+    /// Profiles assigned to named model roles; unassigned roles use the active profile.
+    #[serde(default)]
+    pub model_roles: ModelRoles,
+    /// Profiles tried, in order, when the profile serving a role fails.
+    #[serde(default)]
+    pub model_fallbacks: ModelFallbacks,
+    /// Timeouts and retries applied to every request.
+    #[serde(default)]
+    pub request_policy: RequestPolicy,
+}
+
+/// Workload a profile can be assigned to, independently of the active chat profile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModelRole {
+    /// Chat replies, always served by the active profile or the one picked for a
+    /// regenerate; only fallbacks can be configured for it.
+    Chat,
+    /// Cheap intermediate steps such as exploration digests and later rounds.
+    Fast,
+    /// Steps where reasoning quality matters, such as first rounds and syntheses.
+    Deep,
+    /// Rolling conversation history summaries.
+    Summarizer,
+    /// Session titles; without an assignment titles come from the first message.
+    Titler,
+    /// Frontmatter property suggestions.
+    Frontmatter,
+    /// Pulse transformations.
+    Pulse,
+}
+
+/// Profile ids assigned to each [`ModelRole`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ModelRoles {
+    #[serde(default)]
+    pub fast: Option<String>,
+    #[serde(default)]
+    pub deep: Option<String>,
+    #[serde(default)]
+    pub summarizer: Option<String>,
+    #[serde(default)]
+    pub titler: Option<String>,
+    #[serde(default)]
+    pub frontmatter: Option<String>,
+    #[serde(default)]
+    pub pulse: Option<String>,
+}
+
+impl ModelRoles {
+    /// Returns the trimmed profile id assigned to `role`, if any.
+    pub fn get(&self, role: ModelRole) -> Option<&str> {
+        let value = match role {
+            ModelRole::Chat => return None,
+            ModelRole::Fast => &self.fast,
+            ModelRole::Deep => &self.deep,
+            ModelRole::Summarizer => &self.summarizer,
+            ModelRole::Titler => &self.titler,
+            ModelRole::Frontmatter => &self.frontmatter,
+            ModelRole::Pulse => &self.pulse,
+        };
+        value
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    }
+
+    fn assigned(&self) -> impl Iterator<Item = (&'static str, &str)> {
+        [
+            ("fast", ModelRole::Fast),
+            ("deep", ModelRole::Deep),
+            ("summarizer", ModelRole::Summarizer),
+            ("titler", ModelRole::Titler),
+            ("frontmatter", ModelRole::Frontmatter),
+            ("pulse", ModelRole::Pulse),
+        ]
+        .into_iter()
+        .filter_map(|(name, role)| self.get(role).map(|id| (name, id)))
+    }
+}
+
+/// Ordered fallback profile ids of each [`ModelRole`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ModelFallbacks {
+    #[serde(default)]
+    pub chat: Vec<String>,
+    #[serde(default)]
+    pub fast: Vec<String>,
+    #[serde(default)]
+    pub deep: Vec<String>,
+    #[serde(default)]
+    pub summarizer: Vec<String>,
+    #[serde(default)]
+    pub titler: Vec<String>,
+    #[serde(default)]
+    pub frontmatter: Vec<String>,
+    #[serde(default)]
+    pub pulse: Vec<String>,
+}
+
+impl ModelFallbacks {
+    pub fn get(&self, role: ModelRole) -> &[String] {
+        match role {
+            ModelRole::Chat => &self.chat,
+            ModelRole::Fast => &self.fast,
+            ModelRole::Deep => &self.deep,
+            ModelRole::Summarizer => &self.summarizer,
+            ModelRole::Titler => &self.titler,
+            ModelRole::Frontmatter => &self.frontmatter,
+            ModelRole::Pulse => &self.pulse,
+        }
+    }
+
+    fn assigned(&self) -> impl Iterator<Item = (&'static str, &str)> {
+        [
+            ("chat", ModelRole::Chat),
+            ("fast", ModelRole::Fast),
+            ("deep", ModelRole::Deep),
+            ("summarizer", ModelRole::Summarizer),
+            ("titler", ModelRole::Titler),
+            ("frontmatter", ModelRole::Frontmatter),
+            ("pulse", ModelRole::Pulse),
+        ]
+        .into_iter()
+        .flat_map(move |(name, role)| self.get(role).iter().map(move |id| (name, id.trim())))
+    }
+}
+
+fn default_request_timeout_secs() -> u64 {
+    120
+}
+
+fn default_max_retries() -> u32 {
+    2
+}
+
+fn default_initial_backoff_ms() -> u64 {
+    1_000
+}
+
+fn default_max_backoff_ms() -> u64 {
+    15_000
+}
+
+/// Timeouts and retries of LLM requests.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RequestPolicy {
+    /// Seconds to wait for a response, or for the next chunk of a streamed one.
+    #[serde(default = "default_request_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Extra attempts on the same profile after a rate limit, timeout or transient
+    /// server error.
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    /// Delay before the first retry; each later retry doubles it.
+    #[serde(default = "default_initial_backoff_ms")]
+    pub initial_backoff_ms: u64,
+    #[serde(default = "default_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+}
+
+impl Default for RequestPolicy {
+    fn default() -> Self {
+        Self {
+            timeout_secs: default_request_timeout_secs(),
+            max_retries: default_max_retries(),
+            initial_backoff_ms: default_initial_backoff_ms(),
+            max_backoff_ms: default_max_backoff_ms(),
+        }
+    }
+}
+
+/// Profiles tried in order for one request, and the policy applied to each attempt.
+#[derive(Debug, Clone)]
+pub struct LlmRoute {
+    pub profiles: Vec<ProviderProfile>,
+    pub policy: RequestPolicy,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -53,6 +314,9 @@ pub struct ConfigStatus {
     pub supports_image_input: bool,
     pub supports_audio_input: bool,
     pub error: Option<String>,
+    /// Set when the active profile is past a monthly budget that only warns.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget_warning: Option<String>,
 }
 
 fn default_true() -> bool {
@@ -79,6 +343,20 @@ pub fn validate_config(config: &SecondBrainConfig) -> Result<(), String> {
         if !(0.0..=1.0).contains(&profile.default_temperature) {
             return Err("profile.default_temperature must be between 0 and 1.".to_string());
         }
+        if let Some(budget) = &profile.budget {
+            let amounts = [
+                budget.input_usd_per_million_tokens,
+                budget.output_usd_per_million_tokens,
+                budget.monthly_limit_usd,
+            ];
+            if amounts
+                .into_iter()
+                .flatten()
+                .any(|amount| !amount.is_finite() || amount < 0.0)
+            {
+                return Err("profile.budget amounts must be zero or positive.".to_string());
+            }
+        }
         let provider = profile.provider.trim().to_lowercase();
         let has_base_url = profile
             .base_url
@@ -102,12 +380,19 @@ pub fn validate_config(config: &SecondBrainConfig) -> Result<(), String> {
                 | "mimo"
                 | "zai"
                 | "bigmodel"
+                | "mock"
         );
         if !is_native_provider && !has_base_url {
             return Err("profile.base_url is required for custom provider.".to_string());
         }
-        if provider != "openai-codex" && profile.api_key.trim().is_empty() {
-            return Err("profile.api_key is required.".to_string());
+        let is_local_server = LocalServerKind::from_provider(&provider).is_some();
+        if provider != "openai-codex"
+            && provider != "mock"
+            && !is_local_server
+            && profile.api_key.trim().is_empty()
+            && !profile.key_source.is_configured()
+        {
+            return Err("profile.api_key or a key source is required.".to_string());
         }
         if let Some(base_url) = &profile.base_url {
             let trimmed = base_url.trim();
@@ -127,6 +412,39 @@ pub fn validate_config(config: &SecondBrainConfig) -> Result<(), String> {
     {
         return Err("active_profile was not found in profiles.".to_string());
     }
+    for (role, profile_id) in config.model_roles.assigned() {
+        let Some(profile) = config
+            .profiles
+            .iter()
+            .find(|profile| profile.id.trim() == profile_id)
+        else {
+            return Err(format!("model_roles.{role} was not found in profiles."));
+        };
+        if !profile.capabilities.text {
+            return Err(format!(
+                "model_roles.{role} must use a text-capable profile."
+            ));
+        }
+    }
+    for (role, profile_id) in config.model_fallbacks.assigned() {
+        let Some(profile) = config
+            .profiles
+            .iter()
+            .find(|profile| profile.id.trim() == profile_id)
+        else {
+            return Err(format!(
+                "model_fallbacks.{role} contains unknown profile {profile_id}."
+            ));
+        };
+        if !profile.capabilities.text {
+            return Err(format!(
+                "model_fallbacks.{role} must only use text-capable profiles."
+            ));
+        }
+    }
+    if config.request_policy.timeout_secs == 0 {
+        return Err("request_policy.timeout_secs must be at least 1.".to_string());
+    }
 
     Ok(())
 }
@@ -136,6 +454,54 @@ pub fn active_profile<'a>(config: &'a SecondBrainConfig) -> Option<&'a ProviderP
     config.profiles.iter().find(|item| item.id.trim() == active)
 }
 
+/// Returns the text-capable profile explicitly assigned to `role`.
+pub fn assigned_role_profile(
+    config: &SecondBrainConfig,
+    role: ModelRole,
+) -> Option<&ProviderProfile> {
+    let profile_id = config.model_roles.get(role)?;
+    config
+        .profiles
+        .iter()
+        .find(|item| item.id.trim() == profile_id)
+        .filter(|item| item.capabilities.text)
+}
+
+/// Resolves the profile for `role`: its assignment, then the active profile, then any
+/// text-capable profile.
+pub fn role_profile(config: &SecondBrainConfig, role: ModelRole) -> Option<&ProviderProfile> {
+    assigned_role_profile(config, role)
+        .or_else(|| active_profile(config).filter(|item| item.capabilities.text))
+        .or_else(|| config.profiles.iter().find(|item| item.capabilities.text))
+}
+
+/// Builds the request route of `role`: `primary`, then the configured fallbacks that
+/// exist, are text-capable and are not already in the route.
+pub fn role_route(
+    config: &SecondBrainConfig,
+    primary: &ProviderProfile,
+    role: ModelRole,
+) -> LlmRoute {
+    let mut profiles = vec![primary.clone()];
+    for profile_id in config.model_fallbacks.get(role) {
+        let profile_id = profile_id.trim();
+        if profiles.iter().any(|item| item.id.trim() == profile_id) {
+            continue;
+        }
+        if let Some(profile) = config
+            .profiles
+            .iter()
+            .find(|item| item.id.trim() == profile_id && item.capabilities.text)
+        {
+            profiles.push(profile.clone());
+        }
+    }
+    LlmRoute {
+        profiles,
+        policy: config.request_policy.clone(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -149,12 +515,19 @@ mod tests {
                 provider: "openai_compatible".to_string(),
                 model: "gpt-oss".to_string(),
                 api_key: "abc".to_string(),
+                key_source: Default::default(),
                 default_temperature: 0.15,
                 system_prompt: String::new(),
                 base_url: Some("http://localhost:11434/v1".to_string()),
                 default_mode: Some("freestyle".to_string()),
                 capabilities: ProfileCapabilities::default(),
+                mock: None,
+                budget: None,
             }],
+            prompt_language: None,
+            model_roles: ModelRoles::default(),
+            model_fallbacks: ModelFallbacks::default(),
+            request_policy: RequestPolicy::default(),
         }
     }
 
@@ -188,12 +561,19 @@ mod tests {
                 provider: "openai-codex".to_string(),
                 model: "gpt-5.2-codex".to_string(),
                 api_key: "".to_string(),
+                key_source: Default::default(),
                 default_temperature: 0.15,
                 system_prompt: String::new(),
                 base_url: None,
                 default_mode: Some("freestyle".to_string()),
                 capabilities: ProfileCapabilities::default(),
+                mock: None,
+                budget: None,
             }],
+            prompt_language: None,
+            model_roles: ModelRoles::default(),
+            model_fallbacks: ModelFallbacks::default(),
+            request_policy: RequestPolicy::default(),
         };
         assert!(validate_config(&config).is_ok());
     }
@@ -211,6 +591,43 @@ mod tests {
         config.profiles[0].provider = "openai".to_string();
         config.profiles[0].api_key = String::new();
         assert!(validate_config(&config).is_err());
+
+        config.profiles[0].key_source.api_key_env = Some("OPENAI_API_KEY".to_string());
+        assert!(validate_config(&config).is_ok());
+    }
+
+    #[test]
+    fn allows_local_servers_without_api_key() {
+        let mut config = base_config();
+        config.profiles[0].provider = "ollama".to_string();
+        config.profiles[0].api_key = String::new();
+        config.profiles[0].base_url = None;
+        assert!(validate_config(&config).is_ok());
+
+        config.profiles[0].provider = "llama_cpp".to_string();
+        assert!(validate_config(&config).is_err());
+        config.profiles[0].base_url = Some("http://127.0.0.1:8080/v1".to_string());
+        assert!(validate_config(&config).is_ok());
+    }
+
+    #[test]
+    fn rejects_negative_budget_amounts() {
+        let mut config = base_config();
+        config.profiles[0].budget = Some(ProfileBudget {
+            input_usd_per_million_tokens: Some(2.5),
+            monthly_limit_usd: Some(20.0),
+            ..ProfileBudget::default()
+        });
+        assert!(validate_config(&config).is_ok());
+
+        config.profiles[0].budget = Some(ProfileBudget {
+            monthly_limit_usd: Some(-1.0),
+            ..ProfileBudget::default()
+        });
+        assert_eq!(
+            validate_config(&config).unwrap_err(),
+            "profile.budget amounts must be zero or positive."
+        );
     }
 
     #[test]
@@ -220,4 +637,71 @@ mod tests {
         config.profiles[0].base_url = None;
         assert!(validate_config(&config).is_err());
     }
+
+    #[test]
+    fn validates_model_role_assignments() {
+        let mut config = base_config();
+        config.profiles[0].capabilities.text = true;
+        config.model_roles.deep = Some(" p1 ".to_string());
+        assert!(validate_config(&config).is_ok());
+
+        config.model_roles.fast = Some("missing".to_string());
+        assert_eq!(
+            validate_config(&config).unwrap_err(),
+            "model_roles.fast was not found in profiles."
+        );
+    }
+
+    #[test]
+    fn resolves_role_profiles_with_fallbacks() {
+        let mut config = base_config();
+        config.profiles[0].capabilities.text = true;
+        let mut deep = config.profiles[0].clone();
+        deep.id = "p2".to_string();
+        config.profiles.push(deep);
+        config.model_roles.deep = Some("p2".to_string());
+
+        assert_eq!(role_profile(&config, ModelRole::Deep).unwrap().id, "p2");
+        assert_eq!(role_profile(&config, ModelRole::Fast).unwrap().id, "p1");
+        assert!(assigned_role_profile(&config, ModelRole::Titler).is_none());
+
+        config.profiles[0].capabilities.text = false;
+        assert_eq!(role_profile(&config, ModelRole::Fast).unwrap().id, "p2");
+    }
+
+    #[test]
+    fn builds_role_routes_from_fallbacks() {
+        let mut config = base_config();
+        config.profiles[0].capabilities.text = true;
+        for id in ["p2", "p3"] {
+            let mut profile = config.profiles[0].clone();
+            profile.id = id.to_string();
+            config.profiles.push(profile);
+        }
+        config.model_fallbacks.chat = vec![" p3 ".to_string(), "p1".to_string(), "p2".to_string()];
+        assert!(validate_config(&config).is_ok());
+
+        let route = role_route(&config, &config.profiles[0], ModelRole::Chat);
+        let ids = route
+            .profiles
+            .iter()
+            .map(|profile| profile.id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["p1", "p3", "p2"]);
+        assert_eq!(
+            role_route(&config, &config.profiles[1], ModelRole::Pulse)
+                .profiles
+                .len(),
+            1
+        );
+
+        config.model_fallbacks.deep = vec!["missing".to_string()];
+        assert_eq!(
+            validate_config(&config).unwrap_err(),
+            "model_fallbacks.deep contains unknown profile missing."
+        );
+        config.model_fallbacks.deep.clear();
+        config.request_policy.timeout_secs = 0;
+        assert!(validate_config(&config).is_err());
+    }
 }
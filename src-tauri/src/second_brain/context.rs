@@ -1,8 +1,8 @@
 //! Context loading and mention prioritization for Second Brain prompts.
 //!
-//! This module stays close to the persistence layer: it reads session context rows,
-//! session messages, and the referenced markdown files, then prepares deterministic
-//! prompt inputs for downstream prompt construction.
+//! This module stays close to the persistence layer: it reads session context rows
+//! and the referenced markdown files, then prepares deterministic prompt inputs for
+//! downstream prompt construction.
 
 use std::{collections::HashSet, fs};
 
@@ -11,7 +11,7 @@ use rusqlite::params;
 use super::{
     super::{active_workspace_root, open_db, Result},
     paths::normalize_markdown_path,
-    session_store::{estimate_tokens, ContextItem, MessageRow},
+    session_store::{estimate_tokens, ContextItem},
 };
 
 #[derive(Debug, Clone)]
@@ -56,34 +56,6 @@ pub(super) fn read_session_context(
     Ok(items)
 }
 
-pub(super) fn read_session_messages(
-    conn: &rusqlite::Connection,
-    session_id: &str,
-) -> Result<Vec<MessageRow>> {
-    let mut stmt = conn.prepare(
-        "SELECT id, role, mode, content_md, citations_json, attachments_json, created_at_ms
-         FROM second_brain_messages
-         WHERE session_id = ?1
-         ORDER BY created_at_ms ASC",
-    )?;
-    let rows = stmt.query_map(params![session_id], |row| {
-        Ok(MessageRow {
-            id: row.get::<_, String>(0)?,
-            role: row.get::<_, String>(1)?,
-            mode: row.get::<_, String>(2)?,
-            content_md: row.get::<_, String>(3)?,
-            citations_json: row.get::<_, String>(4)?,
-            attachments_json: row.get::<_, String>(5)?,
-            created_at_ms: row.get::<_, i64>(6)? as u64,
-        })
-    })?;
-    let mut messages = Vec::new();
-    for row in rows {
-        messages.push(row?);
-    }
-    Ok(messages)
-}
-
 /// Reads prioritized markdown context entries from persisted session context paths.
 pub(super) fn load_prioritized_session_entries(
     session_id: &str,
@@ -4,12 +4,20 @@
 //! can request structured suggestions without depending on the `alters` system.
 
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 
 use super::prompt_builder::{
     build_frontmatter_generation_prompt, frontmatter_generation_system_prompt,
-    FrontmatterGenerationPromptInput,
+    select_frontmatter_pack, FrontmatterGenerationPromptInput,
+};
+use super::{
+    config::{role_profile, role_route, ModelRole},
+    llm::run_llm_json,
+    load_config,
+    structured_output::JsonOutput,
+    usage::LlmFeature,
+    AppError, Result,
 };
-use super::{active_profile, llm::run_llm, load_config, AppError, Result};
 use crate::second_brain::config::SecondBrainConfig;
 
 /// How the frontend is asking the model to generate properties.
@@ -77,17 +85,43 @@ struct GeneratedFrontmatterPayload {
     properties: Vec<GeneratedFrontmatterProperty>,
 }
 
-fn extract_json_object(raw: &str) -> Option<&str> {
-    let trimmed = raw.trim();
-    if trimmed.starts_with('{') && trimmed.ends_with('}') {
-        return Some(trimmed);
-    }
-    let start = trimmed.find('{')?;
-    let end = trimmed.rfind('}')?;
-    if end <= start {
-        return None;
+/// Schema of [`GeneratedFrontmatterPayload`] replies.
+fn frontmatter_output() -> JsonOutput {
+    JsonOutput {
+        name: "frontmatter_properties",
+        description: "Frontmatter properties suggested for the note.",
+        schema: json!({
+            "type": "object",
+            "properties": {
+                "language": {"type": "string"},
+                "properties": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "key": {"type": "string"},
+                            "type": {
+                                "type": "string",
+                                "enum": ["text", "number", "checkbox", "date", "list", "tags"]
+                            },
+                            "value": {
+                                "anyOf": [
+                                    {"type": "string"},
+                                    {"type": "number"},
+                                    {"type": "boolean"},
+                                    {"type": "array", "items": {"type": "string"}}
+                                ]
+                            }
+                        },
+                        "required": ["key", "type", "value"],
+                        "additionalProperties": false
+                    }
+                }
+            },
+            "required": ["language", "properties"],
+            "additionalProperties": false
+        }),
     }
-    Some(&trimmed[start..=end])
 }
 
 fn sanitize_property_key(value: &str) -> Option<String> {
@@ -118,35 +152,27 @@ fn normalize_existing_fields(
         .collect()
 }
 
-fn parse_generated_frontmatter_response(raw: &str) -> Result<GeneratedFrontmatterPayload> {
-    let json = extract_json_object(raw).ok_or_else(|| {
-        AppError::InvalidOperation("Model response was not valid JSON.".to_string())
-    })?;
-    serde_json::from_str::<GeneratedFrontmatterPayload>(json)
-        .map_err(|_| AppError::InvalidOperation("Model response was not valid JSON.".to_string()))
-}
-
 fn load_active_second_brain_config() -> Result<SecondBrainConfig> {
     load_config()
 }
 
-fn active_profile_for_frontmatter(
-    config: &SecondBrainConfig,
-) -> Result<super::config::ProviderProfile> {
-    active_profile(config).cloned().ok_or_else(|| {
-        AppError::InvalidOperation("Second Brain configuration is unavailable.".to_string())
-    })
+fn profile_for_frontmatter(config: &SecondBrainConfig) -> Result<super::config::ProviderProfile> {
+    role_profile(config, ModelRole::Frontmatter)
+        .cloned()
+        .ok_or_else(|| {
+            AppError::InvalidOperation("Second Brain configuration is unavailable.".to_string())
+        })
 }
 
-/// Generates frontmatter suggestions from the active Second Brain LLM profile.
+/// Generates frontmatter suggestions from the profile assigned to the frontmatter role.
 ///
-/// This workflow keeps prompt assembly isolated from the transport and parses the
-/// model response as strict JSON so the frontend only receives structured output.
+/// This workflow keeps prompt assembly isolated from the transport and requests the
+/// reply through [`run_llm_json`], so the frontend only receives schema-checked output.
 pub async fn generate_frontmatter_properties(
     payload: GenerateFrontmatterPropertiesPayload,
 ) -> Result<GenerateFrontmatterPropertiesResult> {
     let config = load_active_second_brain_config()?;
-    let profile = active_profile_for_frontmatter(&config)?;
+    let profile = profile_for_frontmatter(&config)?;
     let prompt_input = FrontmatterGenerationPromptInput {
         path: payload.path.trim().to_string(),
         title: payload.title.trim().to_string(),
@@ -160,17 +186,20 @@ pub async fn generate_frontmatter_properties(
         language_hint: payload
             .language_hint
             .and_then(|value| sanitize_property_key(&value)),
+        prompt_language: config.prompt_language,
     };
     let built_prompt = build_frontmatter_generation_prompt(&prompt_input);
-    let raw = run_llm(
-        &profile,
-        frontmatter_generation_system_prompt(),
+    let pack = select_frontmatter_pack(config.prompt_language, &built_prompt.language_hint);
+    let parsed: GeneratedFrontmatterPayload = run_llm_json(
+        &role_route(&config, &profile, ModelRole::Frontmatter),
+        LlmFeature::Frontmatter,
+        &frontmatter_output(),
+        frontmatter_generation_system_prompt(pack),
         &built_prompt.user_prompt,
         Some(0.2),
     )
     .await
     .map_err(|message| AppError::InvalidOperation(message))?;
-    let parsed = parse_generated_frontmatter_response(&raw)?;
     Ok(GenerateFrontmatterPropertiesResult {
         language: if parsed.language.trim().is_empty() {
             built_prompt.language_hint
@@ -184,20 +213,30 @@ pub async fn generate_frontmatter_properties(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::second_brain::structured_output;
 
     #[test]
-    fn extracts_json_object_from_wrapped_content() {
-        let raw = "```json\n{\"language\":\"fr\",\"properties\":[]}\n```";
-        let parsed = parse_generated_frontmatter_response(raw).expect("parse response");
-        assert_eq!(parsed.language, "fr");
-        assert!(parsed.properties.is_empty());
-    }
-
-    #[test]
-    fn rejects_invalid_json_content() {
-        let raw = "not json";
-        let err = parse_generated_frontmatter_response(raw).expect_err("invalid json should fail");
-        assert!(format!("{err}").contains("JSON"));
+    fn schema_accepts_generated_properties_only() {
+        let schema = frontmatter_output().schema;
+        let valid = json!({
+            "language": "fr",
+            "properties": [
+                {"key": "tags", "type": "list", "value": ["a", "b"]},
+                {"key": "draft", "type": "checkbox", "value": true}
+            ]
+        });
+        assert!(structured_output::validate(&schema, &valid).is_empty());
+        let parsed: GeneratedFrontmatterPayload = serde_json::from_value(valid).expect("parse");
+        assert_eq!(parsed.properties.len(), 2);
+
+        let invalid = json!({"language": "fr", "properties": [{"key": "tags", "value": {}}]});
+        assert_eq!(
+            structured_output::validate(&schema, &invalid),
+            vec![
+                "$.properties[0].type: is required",
+                "$.properties[0].value: does not match any allowed shape"
+            ]
+        );
     }
 
     #[test]
@@ -0,0 +1,189 @@
+//! Rolling summarization of Second Brain history that overflows the prompt window.
+//!
+//! `prompt_builder` only keeps the most recent turns. Before each new message this
+//! module folds the turns that are about to be dropped into a persisted per-session
+//! summary, using the active model, so long research sessions keep their earlier
+//! conclusions instead of silently forgetting them.
+
+use rusqlite::Connection;
+
+use super::{
+    config::LlmRoute,
+    llm::run_llm,
+    prompt_builder::{
+        build_history_summary_prompt, history_overflow_len, history_summary_system_prompt,
+    },
+    prompt_packs::PromptPack,
+    session_store::{read_history_summary, upsert_history_summary, HistorySummary, MessageRow},
+    usage::LlmFeature,
+    Result,
+};
+use crate::now_ms;
+
+const HISTORY_SUMMARY_TEMPERATURE: f64 = 0.1;
+/// Summarization calls allowed before one message; later turns wait for the next one.
+const MAX_SUMMARY_ROUNDS: usize = 3;
+
+/// Refreshes the session summary with overflowing turns and returns the text to inject.
+///
+/// Turns that do not fit one summarization prompt are folded in further rounds, up
+/// to [`MAX_SUMMARY_ROUNDS`] per message; coverage only advances past turns a prompt
+/// actually included. Summarization failures never block the user message: the
+/// latest summary is reused and the uncovered turns are retried on the next message.
+pub(super) async fn refresh_history_summary(
+    conn: &Connection,
+    route: &LlmRoute,
+    session_id: &str,
+    message: &str,
+    history_messages: &[MessageRow],
+    pack: &PromptPack,
+) -> Result<Option<String>> {
+    let existing = read_history_summary(conn, session_id)?
+        .filter(|summary| summary_matches_branch(summary, history_messages));
+    let overflow_len = history_overflow_len(message, history_messages);
+    let dropped = &history_messages[..overflow_len];
+    let mut pending = pending_summary_messages(dropped, existing.as_ref());
+    let mut content = existing
+        .map(|summary| summary.content_md)
+        .unwrap_or_default();
+
+    for _ in 0..MAX_SUMMARY_ROUNDS {
+        if pending.is_empty() {
+            break;
+        }
+        let (prompt, included) = build_history_summary_prompt(&content, pending, pack);
+        let Ok(reply) = run_llm(
+            route,
+            LlmFeature::HistorySummary,
+            history_summary_system_prompt(pack),
+            &prompt,
+            Some(HISTORY_SUMMARY_TEMPERATURE),
+        )
+        .await
+        else {
+            break;
+        };
+
+        let (covered, rest) = pending.split_at(included);
+        let last_covered = covered.last().expect("summary prompts include a turn");
+        content = reply.text.trim().to_string();
+        pending = rest;
+        upsert_history_summary(
+            conn,
+            &HistorySummary {
+                session_id: session_id.to_string(),
+                content_md: content.clone(),
+                covered_until_message_id: last_covered.id.clone(),
+                covered_until_ms: last_covered.created_at_ms,
+                covered_message_count: overflow_len - pending.len(),
+                edited_by_user: false,
+                updated_at_ms: now_ms(),
+            },
+        )?;
+    }
+    Ok(Some(content).filter(|value| !value.trim().is_empty()))
+}
+
+/// Tells whether a stored summary was built from the branch being answered.
+///
+/// After switching branches the summary may cover turns the prompt no longer
+/// includes; it is then rebuilt from this branch. User-edited summaries are kept.
+fn summary_matches_branch(summary: &HistorySummary, history_messages: &[MessageRow]) -> bool {
+    summary.edited_by_user
+        || summary.covered_until_message_id.is_empty()
+        || history_messages
+            .iter()
+            .any(|item| item.id == summary.covered_until_message_id)
+}
+
+/// Returns the dropped turns that the stored summary does not cover yet.
+///
+/// Coverage is tracked by the last summarized message id; the timestamp is only a
+/// fallback when that message no longer belongs to the dropped range.
+fn pending_summary_messages<'a>(
+    dropped: &'a [MessageRow],
+    existing: Option<&HistorySummary>,
+) -> &'a [MessageRow] {
+    let Some(summary) = existing else {
+        return dropped;
+    };
+    if summary.covered_until_message_id.is_empty() {
+        return dropped;
+    }
+    if let Some(index) = dropped
+        .iter()
+        .position(|item| item.id == summary.covered_until_message_id)
+    {
+        return &dropped[index + 1..];
+    }
+    let start = dropped
+        .iter()
+        .position(|item| item.created_at_ms > summary.covered_until_ms)
+        .unwrap_or(dropped.len());
+    &dropped[start..]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(id: &str, created_at_ms: u64) -> MessageRow {
+        MessageRow {
+            id: id.to_string(),
+            parent_id: String::new(),
+            role: "user".to_string(),
+            mode: "freestyle".to_string(),
+            content_md: format!("content {id}"),
+            citations_json: "[]".to_string(),
+            attachments_json: "[]".to_string(),
+            created_at_ms,
+        }
+    }
+
+    fn summary(covered_id: &str, covered_ms: u64) -> HistorySummary {
+        HistorySummary {
+            session_id: "s1".to_string(),
+            content_md: "resume".to_string(),
+            covered_until_message_id: covered_id.to_string(),
+            covered_until_ms: covered_ms,
+            covered_message_count: 0,
+            edited_by_user: false,
+            updated_at_ms: 0,
+        }
+    }
+
+    #[test]
+    fn pending_messages_start_after_last_covered_id() {
+        let dropped = vec![message("m1", 10), message("m2", 20), message("m3", 30)];
+        let pending = pending_summary_messages(&dropped, Some(&summary("m2", 20)));
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].id, "m3");
+
+        assert_eq!(pending_summary_messages(&dropped, None).len(), 3);
+        assert!(pending_summary_messages(&dropped, Some(&summary("m3", 30))).is_empty());
+    }
+
+    #[test]
+    fn summaries_from_another_branch_are_rebuilt() {
+        let history = vec![message("m1", 10), message("m2", 20)];
+        assert!(summary_matches_branch(&summary("m2", 20), &history));
+        assert!(!summary_matches_branch(&summary("other", 15), &history));
+
+        let mut edited = summary("other", 15);
+        edited.edited_by_user = true;
+        assert!(summary_matches_branch(&edited, &history));
+    }
+
+    #[test]
+    fn pending_messages_fall_back_to_timestamp_when_id_is_unknown() {
+        let dropped = vec![message("m1", 10), message("m2", 20), message("m3", 30)];
+        let pending = pending_summary_messages(&dropped, Some(&summary("gone", 15)));
+        assert_eq!(
+            pending
+                .iter()
+                .map(|item| item.id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["m2", "m3"]
+        );
+    }
+}
@@ -1,13 +1,32 @@
+use std::borrow::Cow;
+use std::time::{Duration, Instant};
+
 use futures_util::StreamExt;
 use genai::{
-	chat::{ChatMessage, ChatOptions, ChatRequest, ChatStreamEvent, MessageContent},
+	chat::{
+		ChatMessage, ChatOptions, ChatRequest, ChatResponseFormat, ChatStreamEvent, JsonSpec,
+		MessageContent, Tool, Usage,
+	},
 	resolver::{AuthData, Endpoint, ServiceTargetResolver},
 	Client,
 	ServiceTarget,
 };
 
-use super::config::ProviderProfile;
+use serde::de::DeserializeOwned;
+
+use super::config::{LlmRoute, ProviderProfile, RequestPolicy};
+use super::mock_llm::{is_mock_provider, run_mock, run_mock_stream};
 use super::openai_codex::{run_codex, run_codex_stream};
+use super::structured_output::{self, JsonOutput};
+use super::usage::{
+	check_budget, classify_error, insert_usage, open_usage_db, status_in_message, ErrorClass,
+	LlmFeature, TokenUsage, UsageRecord,
+};
+use crate::secrets::resolve_api_key;
+
+const NO_PROFILE: &str = "No LLM profile is configured.";
+/// Repair rounds of [`run_llm_json`] after the first invalid reply.
+const JSON_REPAIR_ATTEMPTS: u32 = 2;
 
 fn is_openai_codex(profile: &ProviderProfile) -> bool {
 	profile.provider.trim().eq_ignore_ascii_case("openai-codex")
@@ -47,6 +66,31 @@ fn is_openai_compatible_provider(profile: &ProviderProfile) -> bool {
 		.unwrap_or(false)
 }
 
+/// How a profile is asked for JSON matching a [`JsonOutput`] schema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum JsonOutputMode {
+	/// Provider-side JSON schema (`response_format` or its native equivalent).
+	Native,
+	/// A single tool whose arguments follow the schema.
+	Tool,
+	/// Schema spelled out in the system prompt.
+	Prompt,
+}
+
+fn json_output_mode(profile: &ProviderProfile) -> JsonOutputMode {
+	if is_openai_codex(profile) || is_mock_provider(profile) {
+		return JsonOutputMode::Prompt;
+	}
+	let provider = profile.provider.trim().to_lowercase();
+	if is_openai_compatible_provider(profile) || provider == "gemini" || provider == "ollama" {
+		JsonOutputMode::Native
+	} else if profile.capabilities.tool_calling {
+		JsonOutputMode::Tool
+	} else {
+		JsonOutputMode::Prompt
+	}
+}
+
 fn llm_log(event: &str, profile: &ProviderProfile, detail: &str) {
 	let provider = profile.provider.trim();
 	let model = profile.model.trim();
@@ -110,7 +154,9 @@ fn build_client(profile: &ProviderProfile) -> Client {
 fn chat_options_for_temperature(temperature: f64, capture_content: bool) -> ChatOptions {
     let mut options = ChatOptions::default().with_temperature(temperature);
     if capture_content {
-        options = options.with_capture_content(true);
+        options = options
+            .with_capture_content(true)
+            .with_capture_usage(true);
     }
     options
 }
@@ -127,26 +173,358 @@ fn apply_profile_system_prompt(profile: &ProviderProfile, system_prompt: &str) -
     format!("{global}\n\n{local}")
 }
 
+fn token_count(value: Option<i32>) -> Option<u64> {
+	value.and_then(|count| u64::try_from(count).ok())
+}
+
+fn usage_from_genai(usage: &Usage) -> TokenUsage {
+	TokenUsage {
+		input_tokens: token_count(usage.prompt_tokens),
+		output_tokens: token_count(usage.completion_tokens),
+	}
+}
+
+/// Fails when the monthly budget of `profile` blocks requests; warnings are only logged.
+fn ensure_budget(profile: &ProviderProfile) -> Result<(), AttemptError> {
+	let budget = check_budget(profile)
+		.map_err(|message| AttemptError::new(ErrorClass::Budget, message))?;
+	if let Some(warning) = budget {
+		llm_log("budget_warning", profile, &warning);
+	}
+	Ok(())
+}
+
+/// `profile` with its API key, resolving the key source again when loading the config
+/// could not, e.g. because the secret store was still locked.
+fn with_api_key(profile: &ProviderProfile) -> Result<Cow<'_, ProviderProfile>, AttemptError> {
+	if !profile.api_key.trim().is_empty() || !profile.key_source.is_configured() {
+		return Ok(Cow::Borrowed(profile));
+	}
+	let api_key = resolve_api_key(&profile.api_key, &profile.key_source)
+		.map_err(|err| AttemptError::new(ErrorClass::Auth, err.to_string()))?;
+	Ok(Cow::Owned(ProviderProfile {
+		api_key,
+		..profile.clone()
+	}))
+}
+
+/// Appends the outcome of a request to the local usage store; failures are only logged.
+fn record_usage(
+	profile: &ProviderProfile,
+	feature: LlmFeature,
+	started: Instant,
+	result: &Result<(String, TokenUsage), AttemptError>,
+) {
+	let (usage, error) = match result {
+		Ok((_, usage)) => (*usage, None),
+		Err(error) => (TokenUsage::default(), Some(error.class)),
+	};
+	let record = UsageRecord::new(profile, feature, usage, started.elapsed(), error);
+	if let Err(err) = open_usage_db().and_then(|conn| insert_usage(&conn, &record)) {
+		llm_log("usage_record_error", profile, &err.to_string());
+	}
+}
+
+/// Reply of a routed Second Brain LLM request.
+#[derive(Debug, Clone)]
+pub struct LlmReply {
+	pub text: String,
+	/// Profile of the route that produced `text`.
+	pub profile_id: String,
+	/// Error that cut a streamed reply short; `text` then holds the partial answer.
+	pub interrupted: Option<String>,
+}
+
+/// Message of a request that exceeded the route timeout.
+pub(super) fn timed_out(timeout: Duration) -> String {
+	format!("Model request timed out after {}s.", timeout.as_secs())
+}
+
+/// Failure of one attempt on a profile, classified where it happened.
+#[derive(Debug)]
+struct AttemptError {
+	class: ErrorClass,
+	message: String,
+}
+
+impl AttemptError {
+	fn new(class: ErrorClass, message: String) -> Self {
+		Self { class, message }
+	}
+
+	/// Error of a provider that only reports a message (genai, Codex, mock scripts);
+	/// an HTTP status written in it still decides the class.
+	fn from_message(message: String) -> Self {
+		Self::new(classify_error(&message), message)
+	}
+
+	/// Error returned by the caller's chunk handler, e.g. a canceled generation.
+	fn aborted(message: String) -> Self {
+		Self::new(ErrorClass::Canceled, message)
+	}
+
+	fn timed_out(timeout: Duration) -> Self {
+		Self::new(ErrorClass::Timeout, timed_out(timeout))
+	}
+}
+
+/// Exponential backoff before retry number `attempt` (0-based), capped by the policy.
+fn retry_delay(policy: &RequestPolicy, attempt: u32) -> Duration {
+	let factor = 1u64.checked_shl(attempt).unwrap_or(u64::MAX);
+	let delay = policy.initial_backoff_ms.saturating_mul(factor);
+	Duration::from_millis(delay.min(policy.max_backoff_ms))
+}
+
+/// Sleeps before retrying `profile` after `error`; returns false when the next
+/// profile of the route should be tried instead.
+async fn wait_for_retry(
+	profile: &ProviderProfile,
+	policy: &RequestPolicy,
+	attempt: &mut u32,
+	error: &AttemptError,
+) -> bool {
+	let message = &error.message;
+	if !error.class.is_retryable() || *attempt >= policy.max_retries {
+		llm_log("fallback", profile, message);
+		return false;
+	}
+	let delay = retry_delay(policy, *attempt);
+	*attempt += 1;
+	llm_log(
+		"retry",
+		profile,
+		&format!("attempt={} delay_ms={} error={message}", *attempt, delay.as_millis()),
+	);
+	tokio::time::sleep(delay).await;
+	true
+}
+
+/// Runs one attempt of a non-streaming request on `profile`.
+async fn attempt_llm(
+	profile: &ProviderProfile,
+	feature: LlmFeature,
+	timeout: Duration,
+	output: Option<&JsonOutput>,
+	system_prompt: &str,
+	user_prompt: &str,
+	temperature: Option<f64>,
+) -> Result<String, AttemptError> {
+	if is_mock_provider(profile) {
+		return run_mock(profile, user_prompt)
+			.await
+			.map_err(AttemptError::from_message);
+	}
+	let started = Instant::now();
+	let result = match ensure_budget(profile).and_then(|()| with_api_key(profile)) {
+		Ok(profile) => tokio::time::timeout(
+			timeout,
+			request_llm(&profile, timeout, output, system_prompt, user_prompt, temperature),
+		)
+		.await
+		.unwrap_or_else(|_| Err(AttemptError::timed_out(timeout))),
+		Err(message) => Err(message),
+	};
+	record_usage(profile, feature, started, &result);
+	result.map(|(text, _)| text)
+}
+
+/// Runs one attempt of a streaming request on `profile`; profiles without streaming
+/// deliver their whole reply as a single chunk.
+async fn attempt_llm_stream<F>(
+	profile: &ProviderProfile,
+	feature: LlmFeature,
+	timeout: Duration,
+	system_prompt: &str,
+	user_prompt: &str,
+	temperature: Option<f64>,
+	mut on_chunk: F,
+) -> Result<String, AttemptError>
+where
+	F: FnMut(&str) -> Result<(), String>,
+{
+	if !profile.capabilities.streaming {
+		let text = attempt_llm(
+			profile,
+			feature,
+			timeout,
+			None,
+			system_prompt,
+			user_prompt,
+			temperature,
+		)
+		.await?;
+		on_chunk(&text).map_err(AttemptError::aborted)?;
+		return Ok(text);
+	}
+	// Mock and Codex streams return a handler error as their own message; the flag
+	// keeps it classified as an abort rather than by its text.
+	let mut aborted = false;
+	let on_chunk = |chunk: &str| on_chunk(chunk).inspect_err(|_| aborted = true);
+	if is_mock_provider(profile) {
+		let result = run_mock_stream(profile, user_prompt, on_chunk).await;
+		return result.map_err(|message| {
+			if aborted {
+				AttemptError::aborted(message)
+			} else {
+				AttemptError::from_message(message)
+			}
+		});
+	}
+	let started = Instant::now();
+	let result = match ensure_budget(profile).and_then(|()| with_api_key(profile)) {
+		Ok(profile) => {
+			request_llm_stream(&profile, timeout, system_prompt, user_prompt, temperature, on_chunk)
+				.await
+		}
+		Err(message) => Err(message),
+	};
+	let result = result.map_err(|error| {
+		if aborted {
+			AttemptError::aborted(error.message)
+		} else {
+			error
+		}
+	});
+	record_usage(profile, feature, started, &result);
+	result.map(|(text, _)| text)
+}
+
 /// Runs a single Second Brain LLM request.
 ///
 /// Callers pass an optional temperature so alter-scoped tuning can be applied
-/// without changing the provider default for other generation paths.
+/// without changing the provider default for other generation paths. Each profile of
+/// the route is retried with exponential backoff on rate limits, timeouts and
+/// transient server errors before the next one is tried. Every attempt is recorded
+/// under `feature` in the usage store, except for the offline mock provider, and
+/// refused when the profile budget blocks it.
 pub async fn run_llm(
+	route: &LlmRoute,
+	feature: LlmFeature,
+	system_prompt: &str,
+	user_prompt: &str,
+	temperature: Option<f64>,
+) -> Result<LlmReply, String> {
+	route_llm(route, feature, None, system_prompt, user_prompt, temperature).await
+}
+
+/// Runs a Second Brain LLM request whose reply must be a JSON object matching
+/// `output`, deserialized into `T`.
+///
+/// The schema is passed natively to providers with JSON schema support, as the
+/// arguments of a single tool to other tool-calling profiles, and spelled out in the
+/// system prompt otherwise. Every reply is validated against the schema; an invalid
+/// one is sent back with the problems found, up to [`JSON_REPAIR_ATTEMPTS`] times,
+/// before the request fails. Retries and fallbacks follow [`run_llm`].
+pub async fn run_llm_json<T: DeserializeOwned>(
+	route: &LlmRoute,
+	feature: LlmFeature,
+	output: &JsonOutput,
+	system_prompt: &str,
+	user_prompt: &str,
+	temperature: Option<f64>,
+) -> Result<T, String> {
+	let mut prompt = user_prompt.to_string();
+	let mut repairs = 0;
+	loop {
+		let reply =
+			route_llm(route, feature, Some(output), system_prompt, &prompt, temperature).await?;
+		let problems = match structured_output::parse_reply(&reply.text) {
+			Ok(value) => {
+				let problems = structured_output::validate(&output.schema, &value);
+				if !problems.is_empty() {
+					problems
+				} else {
+					match serde_json::from_value(value) {
+						Ok(parsed) => return Ok(parsed),
+						Err(err) => vec![err.to_string()],
+					}
+				}
+			}
+			Err(problem) => vec![problem],
+		};
+		if repairs >= JSON_REPAIR_ATTEMPTS {
+			return Err(format!(
+				"Model reply did not match the expected JSON: {}",
+				problems.join("; ")
+			));
+		}
+		repairs += 1;
+		if let Some(profile) = route.profiles.iter().find(|item| item.id == reply.profile_id) {
+			llm_log("json_repair", profile, &problems.join("; "));
+		}
+		prompt = structured_output::repair_prompt(user_prompt, &reply.text, &problems);
+	}
+}
+
+async fn route_llm(
+	route: &LlmRoute,
+	feature: LlmFeature,
+	output: Option<&JsonOutput>,
+	system_prompt: &str,
+	user_prompt: &str,
+	temperature: Option<f64>,
+) -> Result<LlmReply, String> {
+	let timeout = Duration::from_secs(route.policy.timeout_secs);
+	let mut last_error = NO_PROFILE.to_string();
+	for profile in &route.profiles {
+		let mut attempt = 0;
+		loop {
+			match attempt_llm(
+				profile,
+				feature,
+				timeout,
+				output,
+				system_prompt,
+				user_prompt,
+				temperature,
+			)
+			.await
+			{
+				Ok(text) => {
+					return Ok(LlmReply {
+						text,
+						profile_id: profile.id.clone(),
+						interrupted: None,
+					})
+				}
+				Err(error) => {
+					let retry = wait_for_retry(profile, &route.policy, &mut attempt, &error).await;
+					last_error = error.message;
+					if !retry {
+						break;
+					}
+				}
+			}
+		}
+	}
+	Err(last_error)
+}
+
+async fn request_llm(
 	profile: &ProviderProfile,
+	timeout: Duration,
+	output: Option<&JsonOutput>,
 	system_prompt: &str,
 	user_prompt: &str,
 	temperature: Option<f64>,
-) -> Result<String, String> {
+) -> Result<(String, TokenUsage), AttemptError> {
 	let effective_temperature = temperature.unwrap_or(profile.default_temperature);
-    let effective_system_prompt = apply_profile_system_prompt(profile, system_prompt);
+    let mut effective_system_prompt = apply_profile_system_prompt(profile, system_prompt);
+	let output = output.map(|output| (output, json_output_mode(profile)));
+	if let Some((output, JsonOutputMode::Prompt)) = output {
+		effective_system_prompt =
+			structured_output::prompt_with_schema(&effective_system_prompt, output);
+	}
 	if is_openai_codex(profile) {
 		return run_codex(
 			&profile.model,
             &effective_system_prompt,
 			user_prompt,
 			Some(effective_temperature),
+			timeout,
 		)
-		.await;
+		.await
+		.map_err(AttemptError::from_message);
 	}
 
 	let model = normalize_model_name(profile);
@@ -157,43 +535,138 @@ pub async fn run_llm(
 		ChatMessage::user(MessageContent::from(user_prompt)),
     ];
 
-    let request = ChatRequest::new(messages);
-    let chat_options = Some(chat_options_for_temperature(effective_temperature, false));
+    let mut request = ChatRequest::new(messages);
+    let mut chat_options = chat_options_for_temperature(effective_temperature, false);
+    match output {
+        Some((output, JsonOutputMode::Native)) => {
+            chat_options = chat_options.with_response_format(ChatResponseFormat::JsonSpec(
+                JsonSpec::new(output.name, output.schema.clone()),
+            ));
+        }
+        Some((output, JsonOutputMode::Tool)) => {
+            request = request.with_tools(vec![Tool::new(output.name)
+                .with_description(output.description)
+                .with_schema(output.schema.clone())]);
+        }
+        _ => {}
+    }
+    let chat_options = Some(chat_options);
     match client
         .exec_chat(&model, request, chat_options.as_ref())
         .await
     {
         Ok(response) => {
-            let text = response
-                .first_text()
-                .map(str::trim)
-                .unwrap_or("")
-                .to_string();
+            let tool_arguments = match output {
+                Some((_, JsonOutputMode::Tool)) => response
+                    .tool_calls()
+                    .first()
+                    .map(|call| call.fn_arguments.to_string()),
+                _ => None,
+            };
+            let text = tool_arguments.unwrap_or_else(|| {
+                response
+                    .first_text()
+                    .map(str::trim)
+                    .unwrap_or("")
+                    .to_string()
+            });
             let final_text = if text.is_empty() {
                 "(Empty assistant response)".to_string()
             } else {
                 text
             };
-            Ok(final_text)
+            Ok((final_text, usage_from_genai(&response.usage)))
         }
         Err(err) => {
             let message = format!("Model request failed: {err}");
             llm_log("request_error", profile, &message);
-            Err(message)
+            Err(AttemptError::from_message(message))
         }
     }
 }
 
 /// Runs a streaming Second Brain LLM request.
 ///
-/// The optional temperature follows the same rules as [`run_llm`].
+/// Chunks reach `on_chunk` with the profile that produced them. Retries and
+/// fallbacks follow [`run_llm`] as long as nothing was streamed yet; a failure after
+/// the first chunk keeps the partial answer and reports the error in
+/// [`LlmReply::interrupted`]. An error returned by `on_chunk` stops the request.
 pub async fn run_llm_stream<F>(
+	route: &LlmRoute,
+	feature: LlmFeature,
+	system_prompt: &str,
+	user_prompt: &str,
+	temperature: Option<f64>,
+	mut on_chunk: F,
+) -> Result<LlmReply, String>
+where
+	F: FnMut(&ProviderProfile, &str) -> Result<(), String>,
+{
+	let timeout = Duration::from_secs(route.policy.timeout_secs);
+	let mut last_error = NO_PROFILE.to_string();
+	for profile in &route.profiles {
+		let mut attempt = 0;
+		loop {
+			let mut streamed = String::new();
+			let mut aborted = None;
+			let result = attempt_llm_stream(
+				profile,
+				feature,
+				timeout,
+				system_prompt,
+				user_prompt,
+				temperature,
+				|chunk| match on_chunk(profile, chunk) {
+					Ok(()) => {
+						streamed.push_str(chunk);
+						Ok(())
+					}
+					Err(err) => {
+						aborted = Some(err.clone());
+						Err(err)
+					}
+				},
+			)
+			.await;
+			if let Some(err) = aborted {
+				return Err(err);
+			}
+			let error = match result {
+				Ok(text) => {
+					return Ok(LlmReply {
+						text,
+						profile_id: profile.id.clone(),
+						interrupted: None,
+					})
+				}
+				Err(error) => error,
+			};
+			if !streamed.is_empty() {
+				llm_log("stream_interrupted", profile, &error.message);
+				return Ok(LlmReply {
+					text: streamed,
+					profile_id: profile.id.clone(),
+					interrupted: Some(error.message),
+				});
+			}
+			let retry = wait_for_retry(profile, &route.policy, &mut attempt, &error).await;
+			last_error = error.message;
+			if !retry {
+				break;
+			}
+		}
+	}
+	Err(last_error)
+}
+
+async fn request_llm_stream<F>(
 	profile: &ProviderProfile,
+	timeout: Duration,
 	system_prompt: &str,
 	user_prompt: &str,
 	temperature: Option<f64>,
     mut on_chunk: F,
-) -> Result<String, String>
+) -> Result<(String, TokenUsage), AttemptError>
 where
 	F: FnMut(&str) -> Result<(), String>,
 {
@@ -205,9 +678,11 @@ where
             &effective_system_prompt,
 			user_prompt,
 			Some(effective_temperature),
+			timeout,
 			on_chunk,
 		)
-		.await;
+		.await
+		.map_err(AttemptError::from_message);
 	}
 
 	let model = normalize_model_name(profile);
@@ -220,25 +695,35 @@ where
 
     let request = ChatRequest::new(messages);
     let options = Some(chat_options_for_temperature(effective_temperature, true));
-    let mut response = client
-        .exec_chat_stream(&model, request, options.as_ref())
-        .await
-        .map_err(|err| {
-            let message = format!("Model request failed: {err}");
-            llm_log("stream_start_error", profile, &message);
-            message
-        })?;
+    let mut response = tokio::time::timeout(
+        timeout,
+        client.exec_chat_stream(&model, request, options.as_ref()),
+    )
+    .await
+    .map_err(|_| AttemptError::timed_out(timeout))?
+    .map_err(|err| {
+        let message = format!("Model request failed: {err}");
+        llm_log("stream_start_error", profile, &message);
+        AttemptError::from_message(message)
+    })?;
 
     let mut full_text = String::new();
-    while let Some(next) = response.stream.next().await {
+    let mut usage = TokenUsage::default();
+    while let Some(next) = tokio::time::timeout(timeout, response.stream.next())
+        .await
+        .map_err(|_| AttemptError::timed_out(timeout))?
+    {
         match next {
             Ok(ChatStreamEvent::Chunk(chunk)) => {
                 if !chunk.content.is_empty() {
                     full_text.push_str(&chunk.content);
-                    on_chunk(&chunk.content)?;
+                    on_chunk(&chunk.content).map_err(AttemptError::aborted)?;
                 }
             }
             Ok(ChatStreamEvent::End(end)) => {
+                if let Some(captured) = end.captured_usage.as_ref() {
+                    usage = usage_from_genai(captured);
+                }
                 if full_text.trim().is_empty() {
                     if let Some(captured) = end.captured_first_text() {
                         full_text = captured.to_string();
@@ -249,21 +734,24 @@ where
             Err(err) => {
                 let message = format!("Model stream failed: {err}");
                 llm_log("stream_error", profile, &message);
-                return Err(message);
+                let class = status_in_message(&message)
+                    .map_or(ErrorClass::Stream, ErrorClass::from_status);
+                return Err(AttemptError::new(class, message));
             }
         }
     }
 
     if full_text.trim().is_empty() {
-        Ok("(Empty assistant response)".to_string())
+        Ok(("(Empty assistant response)".to_string(), usage))
     } else {
-        Ok(full_text)
+        Ok((full_text, usage))
     }
 }
 
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::second_brain::config::ProfileCapabilities;
 
 	#[test]
 	fn normalizes_openai_compatible_model() {
@@ -273,11 +761,14 @@ mod tests {
             provider: "openai_compatible".to_string(),
             model: "gpt-oss".to_string(),
             api_key: "x".to_string(),
+            key_source: Default::default(),
             default_temperature: 0.15,
             system_prompt: String::new(),
             base_url: Some("http://localhost:11434/v1".to_string()),
             default_mode: None,
             capabilities: Default::default(),
+            mock: None,
+            budget: None,
 		};
 		assert_eq!(normalize_model_name(&profile), "openai::gpt-oss");
 	}
@@ -290,11 +781,14 @@ mod tests {
 			provider: "custom".to_string(),
 			model: "openweight-medium".to_string(),
 			api_key: "x".to_string(),
+			key_source: Default::default(),
 			default_temperature: 0.15,
             system_prompt: String::new(),
 			base_url: Some("https://albert.api.etalab.gouv.fr/v1/".to_string()),
 			default_mode: None,
 			capabilities: Default::default(),
+			mock: None,
+			budget: None,
 		};
 		assert_eq!(normalize_model_name(&profile), "openai::openweight-medium");
 	}
@@ -307,11 +801,14 @@ mod tests {
             provider: "OpenAI-Codex".to_string(),
             model: "gpt-5.2-codex".to_string(),
             api_key: String::new(),
+            key_source: Default::default(),
             default_temperature: 0.15,
             system_prompt: String::new(),
             base_url: None,
             default_mode: None,
             capabilities: Default::default(),
+            mock: None,
+            budget: None,
         };
         assert!(is_openai_codex(&profile));
     }
@@ -324,11 +821,14 @@ mod tests {
 			provider: "openai".to_string(),
             model: "gpt-4.1".to_string(),
             api_key: "x".to_string(),
+            key_source: Default::default(),
             default_temperature: 0.15,
             system_prompt: String::new(),
             base_url: None,
             default_mode: None,
             capabilities: Default::default(),
+            mock: None,
+            budget: None,
 		};
 		assert!(!is_openai_codex(&profile));
 	}
@@ -341,11 +841,14 @@ mod tests {
 			provider: "custom".to_string(),
 			model: "openweight-medium".to_string(),
 			api_key: "secret".to_string(),
+			key_source: Default::default(),
 			default_temperature: 0.15,
             system_prompt: String::new(),
 			base_url: Some("https://albert.api.etalab.gouv.fr/v1/".to_string()),
 			default_mode: None,
 			capabilities: Default::default(),
+			mock: None,
+			budget: None,
 		};
 		let client = build_client(&profile);
 		let target = tauri::async_runtime::block_on(async {
@@ -372,11 +875,14 @@ mod tests {
             provider: "openai".to_string(),
             model: "gpt-4.1".to_string(),
             api_key: "x".to_string(),
+            key_source: Default::default(),
             default_temperature: 0.15,
             system_prompt: "Global instruction.".to_string(),
             base_url: None,
             default_mode: None,
             capabilities: Default::default(),
+            mock: None,
+            budget: None,
         };
 
         assert_eq!(
@@ -384,4 +890,231 @@ mod tests {
             "Global instruction.\n\nLocal instruction."
         );
     }
+
+	fn mock_route(scripts: &[(&str, &str)]) -> LlmRoute {
+		let profiles = scripts
+			.iter()
+			.map(|(id, script)| ProviderProfile {
+				id: id.to_string(),
+				label: "Mock".to_string(),
+				provider: "mock".to_string(),
+				model: "scripted".to_string(),
+				api_key: String::new(),
+				key_source: Default::default(),
+				default_temperature: 0.15,
+				system_prompt: String::new(),
+				base_url: None,
+				default_mode: None,
+				capabilities: ProfileCapabilities {
+					text: true,
+					streaming: true,
+					..ProfileCapabilities::default()
+				},
+				mock: Some(serde_json::from_str(script).expect("mock settings")),
+				budget: None,
+			})
+			.collect();
+		LlmRoute {
+			profiles,
+			policy: RequestPolicy {
+				initial_backoff_ms: 0,
+				..RequestPolicy::default()
+			},
+		}
+	}
+
+	fn stream(route: &LlmRoute) -> (Result<LlmReply, String>, Vec<String>) {
+		let mut chunks = Vec::new();
+		let result = tauri::async_runtime::block_on(run_llm_stream(
+			route,
+			LlmFeature::Chat,
+			"",
+			"prompt",
+			None,
+			|profile, chunk| {
+				chunks.push(format!("{}:{chunk}", profile.id));
+				Ok(())
+			},
+		));
+		(result, chunks)
+	}
+
+	#[test]
+	fn backs_off_exponentially_up_to_the_cap() {
+		let policy = RequestPolicy {
+			initial_backoff_ms: 500,
+			max_backoff_ms: 3_000,
+			..RequestPolicy::default()
+		};
+		let delays = (0..4)
+			.map(|attempt| retry_delay(&policy, attempt).as_millis())
+			.collect::<Vec<_>>();
+		assert_eq!(delays, vec![500, 1_000, 2_000, 3_000]);
+		assert_eq!(retry_delay(&policy, 80).as_millis(), 3_000);
+		assert!(AttemptError::timed_out(Duration::from_secs(5)).class.is_retryable());
+		assert!(!AttemptError::aborted("Generation canceled.".to_string()).class.is_retryable());
+	}
+
+	#[test]
+	fn retries_transient_errors_on_the_same_profile() {
+		let route = mock_route(&[(
+			"llm-retry",
+			r#"{"responses": [{"error": "503 Service Unavailable"}, "recovered"]}"#,
+		)]);
+		let reply = tauri::async_runtime::block_on(run_llm(
+			&route,
+			LlmFeature::Chat,
+			"",
+			"prompt",
+			None,
+		))
+		.expect("reply");
+		assert_eq!(reply.text, "recovered");
+		assert_eq!(reply.profile_id, "llm-retry");
+	}
+
+	#[test]
+	fn retries_only_errors_with_a_transient_status() {
+		let route = mock_route(&[
+			(
+				"llm-status",
+				r#"{"responses": [{"error": "status: 429"}, {"error": "model gpt-503 was not found"}, "not retried"]}"#,
+			),
+			("llm-status-fallback", r#"{"responses": ["from fallback"]}"#),
+		]);
+		let reply = tauri::async_runtime::block_on(run_llm(
+			&route,
+			LlmFeature::Chat,
+			"",
+			"prompt",
+			None,
+		))
+		.expect("reply");
+		assert_eq!(reply.text, "from fallback");
+		assert_eq!(reply.profile_id, "llm-status-fallback");
+	}
+
+	#[test]
+	fn falls_back_to_the_next_profile_and_reports_it() {
+		let route = mock_route(&[
+			("llm-primary", r#"{"responses": [{"error": "401 Unauthorized"}]}"#),
+			("llm-fallback", r#"{"responses": ["from fallback"], "chunk_chars": 0}"#),
+		]);
+		let (result, chunks) = stream(&route);
+		let reply = result.expect("reply");
+		assert_eq!(reply.text, "from fallback");
+		assert_eq!(reply.profile_id, "llm-fallback");
+		assert_eq!(chunks, vec!["llm-fallback:from fallback"]);
+	}
+
+	#[test]
+	fn keeps_partial_output_when_the_stream_breaks() {
+		let route = mock_route(&[
+			(
+				"llm-partial",
+				r#"{"responses": [{"text": "partial answer", "error": "connection reset", "error_after_chunks": 1}], "chunk_chars": 7}"#,
+			),
+			("llm-unused", r#"{"responses": ["unused"]}"#),
+		]);
+		let (result, chunks) = stream(&route);
+		let reply = result.expect("reply");
+		assert_eq!(reply.text, "partial");
+		assert_eq!(reply.profile_id, "llm-partial");
+		assert_eq!(
+			reply.interrupted.as_deref(),
+			Some("Model stream failed: connection reset")
+		);
+		assert_eq!(chunks, vec!["llm-partial:partial"]);
+	}
+
+	#[test]
+	fn stops_when_the_caller_rejects_a_chunk() {
+		let route = mock_route(&[
+			("llm-canceled", r#"{"responses": ["first"]}"#),
+			("llm-after-cancel", r#"{"responses": ["second"]}"#),
+		]);
+		let result = tauri::async_runtime::block_on(run_llm_stream(
+			&route,
+			LlmFeature::Chat,
+			"",
+			"prompt",
+			None,
+			|_, _| Err("Generation canceled.".to_string()),
+		));
+		assert_eq!(result.unwrap_err(), "Generation canceled.");
+	}
+
+	#[test]
+	fn classifies_a_rejected_chunk_as_canceled_whatever_its_message() {
+		let route = mock_route(&[("llm-stopped", r#"{"responses": ["first"]}"#)]);
+		let error = tauri::async_runtime::block_on(attempt_llm_stream(
+			&route.profiles[0],
+			LlmFeature::Chat,
+			Duration::from_secs(5),
+			"",
+			"prompt",
+			None,
+			|_| Err("Stopped by the user.".to_string()),
+		))
+		.expect_err("rejected chunk");
+		assert_eq!(error.class, ErrorClass::Canceled);
+		assert_eq!(error.message, "Stopped by the user.");
+	}
+
+	fn name_output() -> JsonOutput {
+		JsonOutput {
+			name: "named",
+			description: "A name.",
+			schema: serde_json::json!({
+				"type": "object",
+				"properties": {"name": {"type": "string"}},
+				"required": ["name"],
+				"additionalProperties": false
+			}),
+		}
+	}
+
+	#[derive(Debug, serde::Deserialize)]
+	struct Named {
+		name: String,
+	}
+
+	#[test]
+	fn repairs_json_replies_until_they_match_the_schema() {
+		let route = mock_route(&[(
+			"llm-json-repair",
+			r#"{"responses": ["not json", "{\"name\": 3}", "```json\n{\"name\": \"Ada\"}\n```"]}"#,
+		)]);
+		let named: Named = tauri::async_runtime::block_on(run_llm_json(
+			&route,
+			LlmFeature::Frontmatter,
+			&name_output(),
+			"",
+			"prompt",
+			None,
+		))
+		.expect("repaired reply");
+		assert_eq!(named.name, "Ada");
+	}
+
+	#[test]
+	fn fails_when_repairs_do_not_produce_valid_json() {
+		let route = mock_route(&[(
+			"llm-json-invalid",
+			r#"{"responses": ["{\"name\": null}"]}"#,
+		)]);
+		let result = tauri::async_runtime::block_on(run_llm_json::<Named>(
+			&route,
+			LlmFeature::Frontmatter,
+			&name_output(),
+			"",
+			"prompt",
+			None,
+		));
+		assert_eq!(
+			result.unwrap_err(),
+			"Model reply did not match the expected JSON: $.name: expected string"
+		);
+		assert_eq!(json_output_mode(&route.profiles[0]), JsonOutputMode::Prompt);
+	}
 }
@@ -0,0 +1,657 @@
+//! Native APIs of local model servers: Ollama and llama.cpp (`llama-server`).
+//!
+//! Chat requests keep going through the OpenAI-compatible path in `llm.rs`. This
+//! module covers what `/models` discovery cannot: health checks, installed model
+//! details (size, quantization, context length) and, for Ollama, pulling and
+//! deleting models.
+
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use super::model_discovery::{summarize_response_body, DiscoveredModel};
+
+const OLLAMA_DEFAULT_BASE_URL: &str = "http://localhost:11434";
+const HEALTH_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LocalServerKind {
+    Ollama,
+    LlamaCpp,
+}
+
+impl LocalServerKind {
+    pub fn from_provider(provider: &str) -> Option<Self> {
+        match provider.trim().to_lowercase().as_str() {
+            "ollama" => Some(Self::Ollama),
+            "llama_cpp" | "llama.cpp" | "llamacpp" => Some(Self::LlamaCpp),
+            _ => None,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Ollama => "Ollama",
+            Self::LlamaCpp => "llama.cpp server",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct LocalModel {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size_bytes: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parameter_size: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quantization: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub family: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_length: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LocalServerStatus {
+    pub reachable: bool,
+    pub base_url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct LocalModelPullProgress {
+    pub model: String,
+    pub status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed_bytes: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_bytes: Option<u64>,
+}
+
+/// Root of the native API: the profile base URL without a trailing `/v1` or `/api`.
+pub fn server_root(kind: LocalServerKind, base_url: Option<&str>) -> Result<String, String> {
+    let base_url = base_url
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(|value| value.trim_end_matches('/'));
+    match (kind, base_url) {
+        (_, Some(url)) => {
+            let root = url
+                .strip_suffix("/v1")
+                .or_else(|| url.strip_suffix("/api"))
+                .unwrap_or(url);
+            Ok(root.to_string())
+        }
+        (LocalServerKind::Ollama, None) => Ok(OLLAMA_DEFAULT_BASE_URL.to_string()),
+        (LocalServerKind::LlamaCpp, None) => {
+            Err("llama.cpp server base_url is required.".to_string())
+        }
+    }
+}
+
+fn unreachable_message(kind: LocalServerKind, root: &str) -> String {
+    match kind {
+        LocalServerKind::Ollama => {
+            format!("Ollama is not running at {root}. Start it with `ollama serve`.")
+        }
+        LocalServerKind::LlamaCpp => {
+            format!("llama.cpp server is not reachable at {root}. Start it with `llama-server`.")
+        }
+    }
+}
+
+fn request_error(kind: LocalServerKind, root: &str, err: reqwest::Error) -> String {
+    if err.is_connect() || err.is_timeout() {
+        unreachable_message(kind, root)
+    } else {
+        format!("{} request failed at {root}: {err}", kind.label())
+    }
+}
+
+/// Error text of an Ollama (`{"error": "..."}`) or llama.cpp
+/// (`{"error": {"message": "..."}}`) error payload.
+fn payload_error(payload: &Value) -> Option<String> {
+    let error = payload.get("error")?;
+    error
+        .as_str()
+        .or_else(|| error.get("message").and_then(Value::as_str))
+        .map(|message| message.trim().to_string())
+        .filter(|message| !message.is_empty())
+}
+
+fn client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .connect_timeout(HEALTH_TIMEOUT)
+        .build()
+        .unwrap_or_default()
+}
+
+async fn send_json(
+    kind: LocalServerKind,
+    root: &str,
+    request: reqwest::RequestBuilder,
+) -> Result<Value, String> {
+    let response = request
+        .send()
+        .await
+        .map_err(|err| request_error(kind, root, err))?;
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .map_err(|err| request_error(kind, root, err))?;
+    let payload = serde_json::from_str::<Value>(&body).ok();
+    if !status.is_success() {
+        let detail = payload
+            .as_ref()
+            .and_then(payload_error)
+            .unwrap_or_else(|| summarize_response_body(&body));
+        return Err(format!("{} returned HTTP {status}: {detail}", kind.label()));
+    }
+    payload.ok_or_else(|| {
+        format!(
+            "{} returned invalid JSON: {}",
+            kind.label(),
+            summarize_response_body(&body)
+        )
+    })
+}
+
+fn optional_string(value: Option<&Value>) -> Option<String> {
+    value
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToString::to_string)
+}
+
+fn parse_ollama_tags(payload: &Value) -> Vec<LocalModel> {
+    let Some(models) = payload.get("models").and_then(Value::as_array) else {
+        return Vec::new();
+    };
+    models
+        .iter()
+        .filter_map(|model| {
+            let id = optional_string(model.get("name"))
+                .or_else(|| optional_string(model.get("model")))?;
+            let details = model.get("details");
+            Some(LocalModel {
+                id,
+                size_bytes: model.get("size").and_then(Value::as_u64),
+                parameter_size: optional_string(details.and_then(|d| d.get("parameter_size"))),
+                quantization: optional_string(details.and_then(|d| d.get("quantization_level"))),
+                family: optional_string(details.and_then(|d| d.get("family"))),
+                context_length: None,
+            })
+        })
+        .collect()
+}
+
+/// Trained context length from an `/api/show` payload (`model_info["<arch>.context_length"]`).
+fn parse_ollama_context_length(payload: &Value) -> Option<u64> {
+    let info = payload.get("model_info")?.as_object()?;
+    if let Some(architecture) = info.get("general.architecture").and_then(Value::as_str) {
+        if let Some(length) = info
+            .get(&format!("{architecture}.context_length"))
+            .and_then(Value::as_u64)
+        {
+            return Some(length);
+        }
+    }
+    info.iter()
+        .find(|(key, _)| key.ends_with(".context_length"))
+        .and_then(|(_, value)| value.as_u64())
+}
+
+/// Quantization tag embedded in a GGUF file name, e.g. `Q4_K_M` or `F16`.
+fn quantization_from_name(name: &str) -> Option<String> {
+    let stem = name.rsplit(['/', '\\']).next().unwrap_or(name);
+    let stem = stem.strip_suffix(".gguf").unwrap_or(stem);
+    stem.split(['-', '.']).rev().find_map(|part| {
+        let tag = part.to_uppercase();
+        let digits = tag
+            .strip_prefix("IQ")
+            .or_else(|| tag.strip_prefix('Q'))
+            .filter(|rest| rest.starts_with(|c: char| c.is_ascii_digit()));
+        let is_float = matches!(tag.as_str(), "F16" | "F32" | "BF16");
+        (digits.is_some() || is_float).then_some(tag)
+    })
+}
+
+fn format_parameter_count(count: u64) -> String {
+    if count >= 1_000_000_000 {
+        format!("{:.1}B", count as f64 / 1_000_000_000.0)
+    } else {
+        format!("{}M", count / 1_000_000)
+    }
+}
+
+fn parse_llama_cpp_models(payload: &Value) -> Vec<LocalModel> {
+    let Some(models) = payload
+        .get("data")
+        .or_else(|| payload.get("models"))
+        .and_then(Value::as_array)
+    else {
+        return Vec::new();
+    };
+    models
+        .iter()
+        .filter_map(|model| {
+            let id =
+                optional_string(model.get("id")).or_else(|| optional_string(model.get("model")))?;
+            let meta = model.get("meta");
+            Some(LocalModel {
+                quantization: quantization_from_name(&id),
+                size_bytes: meta.and_then(|m| m.get("size")).and_then(Value::as_u64),
+                parameter_size: meta
+                    .and_then(|m| m.get("n_params"))
+                    .and_then(Value::as_u64)
+                    .map(format_parameter_count),
+                family: None,
+                context_length: meta
+                    .and_then(|m| m.get("n_ctx_train"))
+                    .and_then(Value::as_u64),
+                id,
+            })
+        })
+        .collect()
+}
+
+/// Parses one NDJSON line of `/api/pull`; errors reported by Ollama become `Err`.
+fn parse_pull_line(model: &str, line: &str) -> Result<Option<LocalModelPullProgress>, String> {
+    let line = line.trim();
+    if line.is_empty() {
+        return Ok(None);
+    }
+    let Ok(payload) = serde_json::from_str::<Value>(line) else {
+        return Ok(None);
+    };
+    if let Some(error) = payload_error(&payload) {
+        return Err(format!("Ollama could not pull `{model}`: {error}"));
+    }
+    let Some(status) = optional_string(payload.get("status")) else {
+        return Ok(None);
+    };
+    Ok(Some(LocalModelPullProgress {
+        model: model.to_string(),
+        status,
+        completed_bytes: payload.get("completed").and_then(Value::as_u64),
+        total_bytes: payload.get("total").and_then(Value::as_u64),
+    }))
+}
+
+/// Whether an installed Ollama tag matches a configured model name; a missing tag means `latest`.
+fn same_ollama_model(installed: &str, wanted: &str) -> bool {
+    let normalize = |value: &str| {
+        let value = value.trim();
+        let value = value.strip_prefix("ollama::").unwrap_or(value);
+        if value.contains(':') {
+            value.to_string()
+        } else {
+            format!("{value}:latest")
+        }
+    };
+    normalize(installed) == normalize(wanted)
+}
+
+fn format_size(bytes: u64) -> String {
+    if bytes >= 1_000_000_000 {
+        format!("{:.1} GB", bytes as f64 / 1_000_000_000.0)
+    } else {
+        format!("{} MB", bytes / 1_000_000)
+    }
+}
+
+/// Model picker entry: the id followed by its parameter size, quantization, size and context.
+pub fn discovered_model(model: &LocalModel) -> DiscoveredModel {
+    let mut details = Vec::new();
+    if let Some(parameter_size) = model.parameter_size.as_deref() {
+        details.push(parameter_size.to_string());
+    }
+    if let Some(quantization) = model.quantization.as_deref() {
+        details.push(quantization.to_string());
+    }
+    if let Some(size_bytes) = model.size_bytes {
+        details.push(format_size(size_bytes));
+    }
+    if let Some(context_length) = model.context_length {
+        details.push(format!("{context_length} ctx"));
+    }
+    let display_name = if details.is_empty() {
+        model.id.clone()
+    } else {
+        format!("{} ({})", model.id, details.join(", "))
+    };
+    DiscoveredModel {
+        id: model.id.clone(),
+        display_name,
+        group: model.family.clone(),
+    }
+}
+
+/// Probes the server: `/api/version` for Ollama, `/health` for llama.cpp.
+pub async fn server_status(kind: LocalServerKind, base_url: Option<&str>) -> LocalServerStatus {
+    let root = match server_root(kind, base_url) {
+        Ok(root) => root,
+        Err(error) => {
+            return LocalServerStatus {
+                reachable: false,
+                base_url: String::new(),
+                version: None,
+                error: Some(error),
+            }
+        }
+    };
+    let path = match kind {
+        LocalServerKind::Ollama => "/api/version",
+        LocalServerKind::LlamaCpp => "/health",
+    };
+    let request = client()
+        .get(format!("{root}{path}"))
+        .timeout(HEALTH_TIMEOUT);
+    match send_json(kind, &root, request).await {
+        Ok(payload) => LocalServerStatus {
+            reachable: true,
+            version: optional_string(payload.get("version")),
+            base_url: root,
+            error: None,
+        },
+        Err(error) => LocalServerStatus {
+            reachable: false,
+            base_url: root,
+            version: None,
+            error: Some(error),
+        },
+    }
+}
+
+async fn ollama_tags(root: &str) -> Result<Vec<LocalModel>, String> {
+    let request = client().get(format!("{root}/api/tags"));
+    let payload = send_json(LocalServerKind::Ollama, root, request).await?;
+    Ok(parse_ollama_tags(&payload))
+}
+
+/// Lists installed models with their size, quantization and context length.
+pub async fn list_models(
+    kind: LocalServerKind,
+    base_url: Option<&str>,
+) -> Result<Vec<LocalModel>, String> {
+    let root = server_root(kind, base_url)?;
+    match kind {
+        LocalServerKind::Ollama => {
+            let mut models = ollama_tags(&root).await?;
+            for model in &mut models {
+                let request = client()
+                    .post(format!("{root}/api/show"))
+                    .json(&json!({ "model": model.id }));
+                if let Ok(payload) = send_json(kind, &root, request).await {
+                    model.context_length = parse_ollama_context_length(&payload);
+                }
+            }
+            Ok(models)
+        }
+        LocalServerKind::LlamaCpp => {
+            let request = client().get(format!("{root}/v1/models"));
+            let payload = send_json(kind, &root, request).await?;
+            Ok(parse_llama_cpp_models(&payload))
+        }
+    }
+}
+
+/// Checks that the server is up and, for Ollama, that `model` is installed.
+pub async fn check_profile_model(
+    kind: LocalServerKind,
+    base_url: Option<&str>,
+    model: &str,
+) -> Result<(), String> {
+    let status = server_status(kind, base_url).await;
+    if let Some(error) = status.error {
+        return Err(error);
+    }
+    if kind == LocalServerKind::Ollama && !model.trim().is_empty() {
+        let installed = ollama_tags(&status.base_url).await?;
+        if !installed
+            .iter()
+            .any(|candidate| same_ollama_model(&candidate.id, model))
+        {
+            return Err(format!(
+                "Model `{}` is not installed in Ollama. Pull it from the model settings or run `ollama pull {}`.",
+                model.trim(),
+                model.trim()
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn ensure_ollama(kind: LocalServerKind, action: &str) -> Result<(), String> {
+    if kind == LocalServerKind::Ollama {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} cannot {action} models. Manage GGUF files on the server host instead.",
+            kind.label()
+        ))
+    }
+}
+
+fn required_model_name(model: &str) -> Result<&str, String> {
+    let model = model.trim();
+    if model.is_empty() {
+        return Err("Model name is required.".to_string());
+    }
+    Ok(model)
+}
+
+/// Pulls a model through Ollama, reporting each progress line to `on_progress`.
+pub async fn pull_model<F>(
+    kind: LocalServerKind,
+    base_url: Option<&str>,
+    model: &str,
+    mut on_progress: F,
+) -> Result<(), String>
+where
+    F: FnMut(LocalModelPullProgress),
+{
+    ensure_ollama(kind, "pull")?;
+    let model = required_model_name(model)?;
+    let root = server_root(kind, base_url)?;
+    let response = client()
+        .post(format!("{root}/api/pull"))
+        .json(&json!({ "model": model, "stream": true }))
+        .send()
+        .await
+        .map_err(|err| request_error(kind, &root, err))?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        let detail = serde_json::from_str::<Value>(&body)
+            .ok()
+            .as_ref()
+            .and_then(payload_error)
+            .unwrap_or_else(|| summarize_response_body(&body));
+        return Err(format!(
+            "Ollama could not pull `{model}`: HTTP {status}: {detail}"
+        ));
+    }
+
+    let mut stream = response.bytes_stream();
+    let mut buffer = String::new();
+    let mut completed = false;
+    while let Some(next) = stream.next().await {
+        let bytes = next.map_err(|_| format!("Ollama pull stream for `{model}` failed."))?;
+        buffer.push_str(&String::from_utf8_lossy(&bytes));
+        while let Some(position) = buffer.find('\n') {
+            let line = buffer[..position].to_string();
+            buffer = buffer[position + 1..].to_string();
+            if let Some(progress) = parse_pull_line(model, &line)? {
+                completed |= progress.status == "success";
+                on_progress(progress);
+            }
+        }
+    }
+    if let Some(progress) = parse_pull_line(model, &buffer)? {
+        completed |= progress.status == "success";
+        on_progress(progress);
+    }
+    if !completed {
+        return Err(format!(
+            "Ollama stopped pulling `{model}` before it completed."
+        ));
+    }
+    Ok(())
+}
+
+/// Deletes an installed model from Ollama.
+pub async fn delete_model(
+    kind: LocalServerKind,
+    base_url: Option<&str>,
+    model: &str,
+) -> Result<(), String> {
+    ensure_ollama(kind, "delete")?;
+    let model = required_model_name(model)?;
+    let root = server_root(kind, base_url)?;
+    let response = client()
+        .delete(format!("{root}/api/delete"))
+        .json(&json!({ "model": model }))
+        .send()
+        .await
+        .map_err(|err| request_error(kind, &root, err))?;
+    let status = response.status();
+    if status == reqwest::StatusCode::NOT_FOUND {
+        return Err(format!("Model `{model}` is not installed in Ollama."));
+    }
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!(
+            "Ollama could not delete `{model}`: HTTP {status}: {}",
+            summarize_response_body(&body)
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_server_roots() {
+        assert_eq!(
+            server_root(LocalServerKind::Ollama, None).as_deref(),
+            Ok("http://localhost:11434")
+        );
+        assert_eq!(
+            server_root(LocalServerKind::Ollama, Some("http://gpu-box:11434/v1/")).as_deref(),
+            Ok("http://gpu-box:11434")
+        );
+        assert_eq!(
+            server_root(LocalServerKind::LlamaCpp, Some("http://127.0.0.1:8080/v1")).as_deref(),
+            Ok("http://127.0.0.1:8080")
+        );
+        assert!(server_root(LocalServerKind::LlamaCpp, Some("  ")).is_err());
+        assert_eq!(
+            LocalServerKind::from_provider("llama.cpp"),
+            Some(LocalServerKind::LlamaCpp)
+        );
+        assert_eq!(LocalServerKind::from_provider("openai"), None);
+    }
+
+    #[test]
+    fn parses_ollama_tags_and_context_length() {
+        let tags = json!({
+            "models": [{
+                "name": "llama3.1:8b",
+                "size": 4920753328u64,
+                "details": { "family": "llama", "parameter_size": "8.0B", "quantization_level": "Q4_K_M" }
+            }, { "details": {} }]
+        });
+        let models = parse_ollama_tags(&tags);
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0].quantization.as_deref(), Some("Q4_K_M"));
+        assert_eq!(models[0].size_bytes, Some(4_920_753_328));
+
+        let show = json!({
+            "model_info": {
+                "general.architecture": "llama",
+                "llama.context_length": 131072,
+                "llama.embedding_length": 4096
+            }
+        });
+        assert_eq!(parse_ollama_context_length(&show), Some(131_072));
+        assert_eq!(parse_ollama_context_length(&json!({})), None);
+
+        let mut model = models[0].clone();
+        model.context_length = Some(131_072);
+        let entry = discovered_model(&model);
+        assert_eq!(entry.id, "llama3.1:8b");
+        assert_eq!(
+            entry.display_name,
+            "llama3.1:8b (8.0B, Q4_K_M, 4.9 GB, 131072 ctx)"
+        );
+        assert_eq!(entry.group.as_deref(), Some("llama"));
+    }
+
+    #[test]
+    fn parses_llama_cpp_models_and_gguf_quantization() {
+        let payload = json!({
+            "data": [{
+                "id": "models/Qwen2.5-7B-Instruct-Q5_K_M.gguf",
+                "meta": { "n_ctx_train": 32768, "n_params": 7615616512u64, "size": 5444831232u64 }
+            }]
+        });
+        let models = parse_llama_cpp_models(&payload);
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0].quantization.as_deref(), Some("Q5_K_M"));
+        assert_eq!(models[0].context_length, Some(32_768));
+        assert_eq!(models[0].parameter_size.as_deref(), Some("7.6B"));
+        assert_eq!(
+            quantization_from_name("mistral-7b-instruct.f16.gguf").as_deref(),
+            Some("F16")
+        );
+        assert_eq!(quantization_from_name("phi-3-mini"), None);
+    }
+
+    #[test]
+    fn parses_pull_progress_and_errors() {
+        let progress = parse_pull_line(
+            "llama3",
+            r#"{"status":"pulling 6a0746a1ec1a","digest":"sha256:6a07","total":4661211424,"completed":1048576}"#,
+        )
+        .expect("progress line");
+        assert_eq!(
+            progress,
+            Some(LocalModelPullProgress {
+                model: "llama3".to_string(),
+                status: "pulling 6a0746a1ec1a".to_string(),
+                completed_bytes: Some(1_048_576),
+                total_bytes: Some(4_661_211_424),
+            })
+        );
+        assert_eq!(parse_pull_line("llama3", "  "), Ok(None));
+        assert_eq!(
+            parse_pull_line(
+                "nope",
+                r#"{"error":"pull model manifest: file does not exist"}"#
+            ),
+            Err(
+                "Ollama could not pull `nope`: pull model manifest: file does not exist"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn matches_ollama_models_with_implicit_latest_tag() {
+        assert!(same_ollama_model("llama3:latest", "llama3"));
+        assert!(same_ollama_model("llama3:latest", "ollama::llama3"));
+        assert!(same_ollama_model("qwen2.5:7b", "qwen2.5:7b"));
+        assert!(!same_ollama_model("qwen2.5:7b", "qwen2.5"));
+    }
+}
@@ -1,31 +1,68 @@
 //! Assistant message workflow for Second Brain sessions.
 //!
 //! The command wrapper stays in `mod.rs`, while this module owns the concrete flow:
-//! validate payload, persist the user message, build the prompt, run the LLM, persist
-//! the assistant response, and emit streaming lifecycle events.
+//! validate payload, persist the user message, fold overflowing history into the rolling
+//! summary, build the prompt, run the LLM, persist the assistant response, and emit
+//! streaming lifecycle events.
+//!
+//! Messages form a tree: editing a user message or regenerating a reply adds a sibling
+//! branch, and prompts only include the branch being answered.
 
-use rusqlite::params;
+use rusqlite::{params, Connection};
 use tauri::{AppHandle, Emitter};
 
 use super::{
-    config::active_profile,
-    context::{load_prioritized_session_entries, read_session_messages},
-    llm::{run_llm, run_llm_stream},
+    config::{
+        active_profile, assigned_role_profile, role_route, LlmRoute, ModelRole, ProviderProfile,
+        SecondBrainConfig,
+    },
+    context::load_prioritized_session_entries,
+    history_summary::refresh_history_summary,
+    llm::{run_llm, run_llm_stream, LlmReply},
     load_config,
-    modes::resolve_mode_prompt,
+    modes::resolve_mode,
     next_id,
-    prompt_builder::{build_user_prompt, normalize_title_from_first_message},
+    prompt_builder::{
+        build_session_title_prompt, build_user_prompt, normalize_generated_title,
+        normalize_title_from_first_message, session_title_system_prompt,
+    },
+    prompt_library::load_prompt_catalog,
+    prompt_packs::{select_prompt_pack, PromptLocale},
     session_exists,
-    session_store::{insert_message, update_session_title, MessageRow},
+    session_store::{
+        active_message_id, insert_message, read_branch_messages, read_message,
+        update_session_title, MessageRow,
+    },
     stream_control::consume_stream_cancel,
-    AppError, Result, SendMessagePayload, SendMessageResult, StreamEvent,
+    usage::LlmFeature,
+    AppError, AttachmentMeta, EditMessagePayload, RegenerateReplyPayload, Result,
+    SendMessagePayload, SendMessageResult, StreamEvent,
 };
 use crate::alters::{
     effective_generation_temperature, resolve_invocation_prompt, resolve_invocation_temperature,
 };
+use crate::conversation_search::index_second_brain_message;
 use crate::ensure_index_schema;
 
+const SESSION_TITLE_TEMPERATURE: f64 = 0.2;
+
+/// Alter applied to one reply, resolved before anything is persisted.
+struct ReplyAlter {
+    id: String,
+    temperature: Option<f64>,
+}
+
+/// User message to answer and the id reserved for the assistant reply.
+struct ReplyTarget<'a> {
+    session_id: &'a str,
+    user_message: &'a MessageRow,
+    alter: ReplyAlter,
+    assistant_message_id: String,
+}
+
 /// Runs the complete assistant message flow while preserving the existing IPC events.
+///
+/// The user message continues the active branch.
 pub(super) async fn send_message(
     app: AppHandle,
     payload: SendMessagePayload,
@@ -39,32 +76,176 @@ pub(super) async fn send_message(
 
     validate_send_message(&payload, &active.capabilities)?;
 
-    let conn = super::super::open_db()?;
-    ensure_index_schema(&conn)?;
-    if !session_exists(&conn, &payload.session_id)? {
+    let conn = open_session_db(&payload.session_id)?;
+    let alter = resolve_reply_alter(&conn, &payload.session_id, payload.alter_id.as_deref())?;
+
+    let user_message_id = next_id("sbm-user");
+    let assistant_message_id = next_id("sbm-assistant");
+    if consume_stream_cancel(&payload.session_id, &assistant_message_id) {
         return Err(AppError::InvalidOperation(
-            "Second Brain session not found.".to_string(),
+            "Generation canceled.".to_string(),
         ));
     }
 
-    let session_alter_id: String = conn
-        .query_row(
-            "SELECT COALESCE(alter_id, '') FROM second_brain_sessions WHERE id = ?1",
-            params![payload.session_id.clone()],
-            |row| row.get(0),
-        )
-        .unwrap_or_default();
-    let effective_alter_id = payload
-        .alter_id
+    let user_message = persist_user_message(
+        &conn,
+        &payload.session_id,
+        &user_message_id,
+        &active_message_id(&conn, &payload.session_id)?,
+        &payload.mode,
+        &payload.message,
+        serde_json::to_string(&payload.attachments).unwrap_or_else(|_| "[]".to_string()),
+    )?;
+    let _ = index_second_brain_message(&conn, &payload.session_id, &user_message, &alter.id);
+    let is_first_message =
+        maybe_update_title_from_first_user_message(&conn, &payload.session_id, &payload.message)?;
+
+    let result = generate_reply(
+        &app,
+        &conn,
+        &config,
+        &active,
+        ReplyTarget {
+            session_id: &payload.session_id,
+            user_message: &user_message,
+            alter,
+            assistant_message_id,
+        },
+    )
+    .await?;
+    if is_first_message {
+        if let Some(titler) = assigned_role_profile(&config, ModelRole::Titler) {
+            generate_session_title(
+                &conn,
+                &config,
+                titler,
+                &payload.session_id,
+                &payload.message,
+            )
+            .await;
+        }
+    }
+    Ok(result)
+}
+
+/// Sends an edited copy of a user message as a new branch next to the original.
+///
+/// The original message and the replies that followed it stay reachable through
+/// branch switching.
+pub(super) async fn edit_and_resend_message(
+    app: AppHandle,
+    payload: EditMessagePayload,
+) -> Result<SendMessageResult> {
+    let config = load_config()?;
+    let active = active_profile(&config)
+        .ok_or_else(|| {
+            AppError::InvalidOperation("active profile is missing from config.".to_string())
+        })?
+        .clone();
+
+    let conn = open_session_db(&payload.session_id)?;
+    let original = read_message(&conn, &payload.session_id, &payload.message_id)?;
+    if original.role != "user" {
+        return Err(AppError::InvalidOperation(
+            "Only user messages can be edited.".to_string(),
+        ));
+    }
+    validate_message_input(
+        &payload.message,
+        has_attachments(&original),
+        &active.capabilities,
+    )?;
+    let alter = resolve_reply_alter(&conn, &payload.session_id, payload.alter_id.as_deref())?;
+
+    let user_message_id = next_id("sbm-user");
+    let assistant_message_id = next_id("sbm-assistant");
+    if consume_stream_cancel(&payload.session_id, &assistant_message_id) {
+        return Err(AppError::InvalidOperation(
+            "Generation canceled.".to_string(),
+        ));
+    }
+    let mode = payload
+        .mode
         .as_deref()
-        .unwrap_or(&session_alter_id)
-        .trim()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .unwrap_or(&original.mode)
         .to_string();
-    let effective_temperature = effective_generation_temperature(resolve_invocation_temperature(
-        Some(&effective_alter_id),
-    )?);
+    let user_message = persist_user_message(
+        &conn,
+        &payload.session_id,
+        &user_message_id,
+        &original.parent_id,
+        &mode,
+        &payload.message,
+        original.attachments_json.clone(),
+    )?;
+    let _ = index_second_brain_message(&conn, &payload.session_id, &user_message, &alter.id);
+
+    generate_reply(
+        &app,
+        &conn,
+        &config,
+        &active,
+        ReplyTarget {
+            session_id: &payload.session_id,
+            user_message: &user_message,
+            alter,
+            assistant_message_id,
+        },
+    )
+    .await
+}
+
+/// Generates another reply to a user message, stored as a sibling of the previous ones.
+///
+/// `message_id` may be the reply to replace or the user message itself. The optional
+/// profile and Alter only apply to the new reply.
+pub(super) async fn regenerate_reply(
+    app: AppHandle,
+    payload: RegenerateReplyPayload,
+) -> Result<SendMessageResult> {
+    let config = load_config()?;
+    let profile = match payload
+        .profile_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+    {
+        Some(profile_id) => config
+            .profiles
+            .iter()
+            .find(|item| item.id.trim() == profile_id)
+            .ok_or_else(|| AppError::InvalidOperation("Provider profile not found.".to_string()))?,
+        None => active_profile(&config).ok_or_else(|| {
+            AppError::InvalidOperation("active profile is missing from config.".to_string())
+        })?,
+    }
+    .clone();
+
+    let conn = open_session_db(&payload.session_id)?;
+    let target = read_message(&conn, &payload.session_id, &payload.message_id)?;
+    let user_message = if target.role == "user" {
+        target
+    } else {
+        read_message(&conn, &payload.session_id, &target.parent_id).map_err(|_| {
+            AppError::InvalidOperation(
+                "Only replies to a user message can be regenerated.".to_string(),
+            )
+        })?
+    };
+    if user_message.role != "user" {
+        return Err(AppError::InvalidOperation(
+            "Only replies to a user message can be regenerated.".to_string(),
+        ));
+    }
+    validate_message_input(
+        &user_message.content_md,
+        has_attachments(&user_message),
+        &profile.capabilities,
+    )?;
+    let alter = resolve_reply_alter(&conn, &payload.session_id, payload.alter_id.as_deref())?;
 
-    let user_message_id = next_id("sbm-user");
     let assistant_message_id = next_id("sbm-assistant");
     if consume_stream_cancel(&payload.session_id, &assistant_message_id) {
         return Err(AppError::InvalidOperation(
@@ -72,61 +253,156 @@ pub(super) async fn send_message(
         ));
     }
 
-    persist_user_message(&conn, &payload, &user_message_id)?;
-    maybe_update_title_from_first_user_message(&conn, &payload.session_id, &payload.message)?;
+    generate_reply(
+        &app,
+        &conn,
+        &config,
+        &profile,
+        ReplyTarget {
+            session_id: &payload.session_id,
+            user_message: &user_message,
+            alter,
+            assistant_message_id,
+        },
+    )
+    .await
+}
 
-    let context_entries = load_prioritized_session_entries(&payload.session_id, &payload.message)?;
-    let history_messages = read_session_messages(&conn, &payload.session_id)?
-        .into_iter()
-        .filter(|item| item.id != user_message_id)
-        .collect::<Vec<_>>();
-    let mode_prompt = resolve_mode_prompt(&payload.mode);
+/// Answers a persisted user message using only the messages of its own branch.
+async fn generate_reply(
+    app: &AppHandle,
+    conn: &Connection,
+    config: &SecondBrainConfig,
+    profile: &ProviderProfile,
+    target: ReplyTarget<'_>,
+) -> Result<SendMessageResult> {
+    let session_id = target.session_id;
+    let message = target.user_message.content_md.as_str();
+    let context_entries = load_prioritized_session_entries(session_id, message)?;
+    let pack = select_prompt_pack(
+        config.prompt_language,
+        &std::iter::once(message)
+            .chain(context_entries.iter().map(|entry| entry.content.as_str()))
+            .collect::<Vec<_>>(),
+        PromptLocale::Fr,
+    );
+    let mode = resolve_mode(
+        &target.user_message.mode,
+        &load_prompt_catalog(pack).modes,
+        pack,
+    );
+    let effective_temperature =
+        effective_generation_temperature(target.alter.temperature.or(mode.temperature));
+    let history_messages = read_branch_messages(conn, session_id, &target.user_message.parent_id)?;
+    let summary_profile = assigned_role_profile(config, ModelRole::Summarizer).unwrap_or(profile);
+    let history_summary = refresh_history_summary(
+        conn,
+        &role_route(config, summary_profile, ModelRole::Summarizer),
+        session_id,
+        message,
+        &history_messages,
+        pack,
+    )
+    .await?;
     let built_prompt = build_user_prompt(
-        &payload.session_id,
-        &payload.message,
+        session_id,
+        message,
         &history_messages,
         &context_entries,
-        resolve_invocation_prompt(&conn, Some(&effective_alter_id))?.as_deref(),
+        resolve_invocation_prompt(conn, Some(&target.alter.id))?.as_deref(),
+        history_summary.as_deref(),
+        mode.output_format,
+        pack,
     );
 
-    emit_assistant_start(&app, &payload.session_id, &assistant_message_id);
+    let assistant_message_id = target.assistant_message_id;
+    emit_assistant_start(app, session_id, &assistant_message_id);
 
-    let answer = run_assistant_generation(
-        &app,
-        &active,
-        &payload.session_id,
+    let reply = run_assistant_generation(
+        app,
+        &role_route(config, profile, ModelRole::Chat),
+        session_id,
         &assistant_message_id,
-        &mode_prompt,
+        &mode.prompt_template,
         &built_prompt.user_prompt,
         effective_temperature,
     )
     .await?;
 
     let citations = build_citations(&built_prompt.included_context_paths);
-    persist_assistant_message(&conn, &payload, &assistant_message_id, &answer, &citations)?;
-    emit_assistant_complete(&app, &payload.session_id, &assistant_message_id, &answer);
+    let assistant_message = persist_assistant_message(
+        conn,
+        session_id,
+        target.user_message,
+        &assistant_message_id,
+        &reply.text,
+        &citations,
+    )?;
+    let _ = index_second_brain_message(conn, session_id, &assistant_message, &target.alter.id);
+    emit_assistant_complete(app, session_id, &assistant_message_id, &reply);
 
     Ok(SendMessageResult {
-        user_message_id,
+        user_message_id: target.user_message.id.clone(),
         assistant_message_id,
     })
 }
 
+fn open_session_db(session_id: &str) -> Result<Connection> {
+    let conn = super::super::open_db()?;
+    ensure_index_schema(&conn)?;
+    if !session_exists(&conn, session_id)? {
+        return Err(AppError::InvalidOperation(
+            "Second Brain session not found.".to_string(),
+        ));
+    }
+    Ok(conn)
+}
+
+/// Resolves the Alter of a reply: the explicit one when given, otherwise the session Alter.
+fn resolve_reply_alter(
+    conn: &Connection,
+    session_id: &str,
+    alter_id: Option<&str>,
+) -> Result<ReplyAlter> {
+    let session_alter_id: String = conn
+        .query_row(
+            "SELECT COALESCE(alter_id, '') FROM second_brain_sessions WHERE id = ?1",
+            params![session_id],
+            |row| row.get(0),
+        )
+        .unwrap_or_default();
+    let id = alter_id.unwrap_or(&session_alter_id).trim().to_string();
+    let temperature = resolve_invocation_temperature(Some(&id))?;
+    Ok(ReplyAlter { id, temperature })
+}
+
 fn validate_send_message(
     payload: &SendMessagePayload,
     capabilities: &super::config::ProfileCapabilities,
+) -> Result<()> {
+    validate_message_input(
+        &payload.message,
+        !payload.attachments.is_empty(),
+        capabilities,
+    )
+}
+
+fn validate_message_input(
+    message: &str,
+    has_attachments: bool,
+    capabilities: &super::config::ProfileCapabilities,
 ) -> Result<()> {
     if !capabilities.text {
         return Err(AppError::InvalidOperation(
             "The active profile does not support text generation.".to_string(),
         ));
     }
-    if payload.message.trim().is_empty() {
+    if message.trim().is_empty() {
         return Err(AppError::InvalidOperation(
             "Message must not be empty.".to_string(),
         ));
     }
-    if !payload.attachments.is_empty() && !capabilities.image_input && !capabilities.audio_input {
+    if has_attachments && !capabilities.image_input && !capabilities.audio_input {
         return Err(AppError::InvalidOperation(
             "Attachments are not supported by the active profile.".to_string(),
         ));
@@ -134,29 +410,41 @@ fn validate_send_message(
     Ok(())
 }
 
+fn has_attachments(message: &MessageRow) -> bool {
+    serde_json::from_str::<Vec<AttachmentMeta>>(&message.attachments_json)
+        .map(|items| !items.is_empty())
+        .unwrap_or(false)
+}
+
 fn persist_user_message(
-    conn: &rusqlite::Connection,
-    payload: &SendMessagePayload,
+    conn: &Connection,
+    session_id: &str,
     user_message_id: &str,
-) -> Result<()> {
+    parent_id: &str,
+    mode: &str,
+    message: &str,
+    attachments_json: String,
+) -> Result<MessageRow> {
     let user_message = MessageRow {
         id: user_message_id.to_string(),
+        parent_id: parent_id.to_string(),
         role: "user".to_string(),
-        mode: payload.mode.clone(),
-        content_md: payload.message.clone(),
+        mode: mode.to_string(),
+        content_md: message.to_string(),
         citations_json: "[]".to_string(),
-        attachments_json: serde_json::to_string(&payload.attachments)
-            .unwrap_or_else(|_| "[]".to_string()),
+        attachments_json,
         created_at_ms: super::super::now_ms(),
     };
-    insert_message(conn, &user_message, &payload.session_id)
+    insert_message(conn, &user_message, session_id)?;
+    Ok(user_message)
 }
 
+/// Titles the session from its first user message and reports whether it was the first.
 fn maybe_update_title_from_first_user_message(
-    conn: &rusqlite::Connection,
+    conn: &Connection,
     session_id: &str,
     message: &str,
-) -> Result<()> {
+) -> Result<bool> {
     let user_count: i64 = conn.query_row(
         "SELECT COUNT(*) FROM second_brain_messages WHERE session_id = ?1 AND role = 'user'",
         params![session_id],
@@ -166,50 +454,79 @@ fn maybe_update_title_from_first_user_message(
         let inferred_title = normalize_title_from_first_message(message);
         let _ = update_session_title(conn, session_id, &inferred_title);
     }
-    Ok(())
+    Ok(user_count <= 1)
 }
 
+/// Replaces the inferred title with one from the titler model role.
+///
+/// Failures keep the title inferred from the first message.
+async fn generate_session_title(
+    conn: &Connection,
+    config: &SecondBrainConfig,
+    titler: &ProviderProfile,
+    session_id: &str,
+    message: &str,
+) {
+    let pack = select_prompt_pack(config.prompt_language, &[message], PromptLocale::Fr);
+    let generated = run_llm(
+        &role_route(config, titler, ModelRole::Titler),
+        LlmFeature::SessionTitle,
+        session_title_system_prompt(pack),
+        &build_session_title_prompt(message, pack),
+        Some(SESSION_TITLE_TEMPERATURE),
+    )
+    .await;
+    if let Some(title) = generated
+        .ok()
+        .and_then(|reply| normalize_generated_title(&reply.text))
+    {
+        let _ = update_session_title(conn, session_id, &title);
+    }
+}
+
+/// Streams the reply over the chat route.
+///
+/// A stream that breaks after the first chunk still yields the partial answer, with
+/// the error kept in [`LlmReply::interrupted`] for the completion event.
 async fn run_assistant_generation(
     app: &AppHandle,
-    active: &super::config::ProviderProfile,
+    route: &LlmRoute,
     session_id: &str,
     assistant_message_id: &str,
     system_prompt: &str,
     user_prompt: &str,
     temperature: f64,
-) -> Result<String> {
+) -> Result<LlmReply> {
     let stream_session_id = session_id.to_string();
     let stream_message_id = assistant_message_id.to_string();
     let app_for_stream = app.clone();
-    let llm_result = if active.capabilities.streaming {
-        run_llm_stream(
-            active,
-            system_prompt,
-            user_prompt,
-            Some(temperature),
-            move |chunk| {
-                if consume_stream_cancel(&stream_session_id, &stream_message_id) {
-                    return Err("Generation canceled.".to_string());
-                }
-                let _ = app_for_stream.emit(
-                    "second-brain://assistant-delta",
-                    StreamEvent {
-                        session_id: stream_session_id.clone(),
-                        message_id: stream_message_id.clone(),
-                        chunk: chunk.to_string(),
-                        done: false,
-                        error: None,
-                    },
-                );
-                Ok(())
-            },
-        )
-        .await
-    } else {
-        run_llm(active, system_prompt, user_prompt, Some(temperature)).await
-    };
+    let llm_result = run_llm_stream(
+        route,
+        LlmFeature::Chat,
+        system_prompt,
+        user_prompt,
+        Some(temperature),
+        move |profile, chunk| {
+            if consume_stream_cancel(&stream_session_id, &stream_message_id) {
+                return Err("Generation canceled.".to_string());
+            }
+            let _ = app_for_stream.emit(
+                "second-brain://assistant-delta",
+                StreamEvent {
+                    session_id: stream_session_id.clone(),
+                    message_id: stream_message_id.clone(),
+                    chunk: chunk.to_string(),
+                    done: false,
+                    error: None,
+                    profile_id: Some(profile.id.clone()),
+                },
+            );
+            Ok(())
+        },
+    )
+    .await;
 
-    let answer = match llm_result {
+    let reply = match llm_result {
         Ok(value) => value,
         Err(err) => {
             emit_assistant_error(app, session_id, assistant_message_id, &err);
@@ -229,39 +546,29 @@ async fn run_assistant_generation(
         ));
     }
 
-    if !active.capabilities.streaming {
-        let _ = app.emit(
-            "second-brain://assistant-delta",
-            StreamEvent {
-                session_id: session_id.to_string(),
-                message_id: assistant_message_id.to_string(),
-                chunk: answer.clone(),
-                done: false,
-                error: None,
-            },
-        );
-    }
-
-    Ok(answer)
+    Ok(reply)
 }
 
 fn persist_assistant_message(
-    conn: &rusqlite::Connection,
-    payload: &SendMessagePayload,
+    conn: &Connection,
+    session_id: &str,
+    user_message: &MessageRow,
     assistant_message_id: &str,
     answer: &str,
     citations: &[String],
-) -> Result<()> {
+) -> Result<MessageRow> {
     let assistant_message = MessageRow {
         id: assistant_message_id.to_string(),
+        parent_id: user_message.id.clone(),
         role: "assistant".to_string(),
-        mode: payload.mode.clone(),
+        mode: user_message.mode.clone(),
         content_md: answer.to_string(),
         citations_json: serde_json::to_string(citations).unwrap_or_else(|_| "[]".to_string()),
         attachments_json: "[]".to_string(),
         created_at_ms: super::super::now_ms(),
     };
-    insert_message(conn, &assistant_message, &payload.session_id)
+    insert_message(conn, &assistant_message, session_id)?;
+    Ok(assistant_message)
 }
 
 fn build_citations(paths: &[String]) -> Vec<String> {
@@ -277,6 +584,7 @@ fn emit_assistant_start(app: &AppHandle, session_id: &str, message_id: &str) {
             chunk: String::new(),
             done: false,
             error: None,
+            profile_id: None,
         },
     );
 }
@@ -290,19 +598,22 @@ fn emit_assistant_error(app: &AppHandle, session_id: &str, message_id: &str, err
             chunk: String::new(),
             done: true,
             error: Some(error.to_string()),
+            profile_id: None,
         },
     );
 }
 
-fn emit_assistant_complete(app: &AppHandle, session_id: &str, message_id: &str, answer: &str) {
+/// Completes the reply; an interrupted stream keeps its error next to the partial answer.
+fn emit_assistant_complete(app: &AppHandle, session_id: &str, message_id: &str, reply: &LlmReply) {
     let _ = app.emit(
         "second-brain://assistant-complete",
         StreamEvent {
             session_id: session_id.to_string(),
             message_id: message_id.to_string(),
-            chunk: answer.to_string(),
+            chunk: reply.text.clone(),
             done: true,
-            error: None,
+            error: reply.interrupted.clone(),
+            profile_id: Some(reply.profile_id.clone()),
         },
     );
 }
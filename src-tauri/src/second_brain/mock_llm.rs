@@ -0,0 +1,270 @@
+//! Offline `mock` provider for deterministic tests, CI runs and demos.
+//!
+//! A profile with `provider: "mock"` never touches the network. Its
+//! [`MockProviderSettings`] pick the reply: scripted responses served in order per
+//! profile, or an echo of the user prompt when no script is set. Replies stream in
+//! fixed-size chunks with an optional delay, and scripted entries can inject an error
+//! before the first chunk or part-way through the stream.
+
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+    sync::{Mutex, OnceLock},
+    time::Duration,
+};
+
+use super::config::{MockProviderSettings, MockResponse, ProviderProfile};
+
+pub const MOCK_PROVIDER: &str = "mock";
+const EMPTY_RESPONSE: &str = "(Empty assistant response)";
+
+/// Position in the script of one profile; a changed script restarts from the top.
+struct ScriptCursor {
+    fingerprint: u64,
+    next: usize,
+}
+
+struct MockReply {
+    text: String,
+    error: Option<String>,
+    error_after_chunks: usize,
+}
+
+fn script_cursors() -> &'static Mutex<HashMap<String, ScriptCursor>> {
+    static CURSORS: OnceLock<Mutex<HashMap<String, ScriptCursor>>> = OnceLock::new();
+    CURSORS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+pub fn is_mock_provider(profile: &ProviderProfile) -> bool {
+    profile.provider.trim().eq_ignore_ascii_case(MOCK_PROVIDER)
+}
+
+fn script_fingerprint(responses: &[MockResponse]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    responses.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Next scripted response of `profile_id`, wrapping around at the end of the script.
+fn next_scripted_response(profile_id: &str, responses: &[MockResponse]) -> MockResponse {
+    let fingerprint = script_fingerprint(responses);
+    let mut cursors = script_cursors()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let cursor = cursors
+        .entry(profile_id.to_string())
+        .or_insert(ScriptCursor {
+            fingerprint,
+            next: 0,
+        });
+    if cursor.fingerprint != fingerprint {
+        cursor.fingerprint = fingerprint;
+        cursor.next = 0;
+    }
+    let response = responses[cursor.next % responses.len()].clone();
+    cursor.next += 1;
+    response
+}
+
+fn next_reply(
+    profile: &ProviderProfile,
+    settings: &MockProviderSettings,
+    user_prompt: &str,
+) -> MockReply {
+    if settings.responses.is_empty() {
+        return MockReply {
+            text: user_prompt.to_string(),
+            error: None,
+            error_after_chunks: 0,
+        };
+    }
+    match next_scripted_response(profile.id.trim(), &settings.responses) {
+        MockResponse::Text(text) => MockReply {
+            text,
+            error: None,
+            error_after_chunks: 0,
+        },
+        MockResponse::Scripted {
+            text,
+            error,
+            error_after_chunks,
+        } => MockReply {
+            text,
+            error,
+            error_after_chunks,
+        },
+    }
+}
+
+fn split_chunks(text: &str, chunk_chars: usize) -> Vec<String> {
+    if text.is_empty() {
+        return Vec::new();
+    }
+    if chunk_chars == 0 {
+        return vec![text.to_string()];
+    }
+    text.chars()
+        .collect::<Vec<_>>()
+        .chunks(chunk_chars)
+        .map(|chunk| chunk.iter().collect())
+        .collect()
+}
+
+fn injected_error(error: &str, streamed_chunks: usize) -> String {
+    if streamed_chunks == 0 {
+        format!("Model request failed: {error}")
+    } else {
+        format!("Model stream failed: {error}")
+    }
+}
+
+async fn simulate_latency(latency_ms: u64) {
+    if latency_ms == 0 {
+        return;
+    }
+    tokio::time::sleep(Duration::from_millis(latency_ms)).await;
+}
+
+/// Answers a request with the next reply of the mock profile.
+pub async fn run_mock(profile: &ProviderProfile, user_prompt: &str) -> Result<String, String> {
+    run_mock_stream(profile, user_prompt, |_| Ok(())).await
+}
+
+/// Streams the next reply of the mock profile, chunk by chunk.
+pub async fn run_mock_stream<F>(
+    profile: &ProviderProfile,
+    user_prompt: &str,
+    mut on_chunk: F,
+) -> Result<String, String>
+where
+    F: FnMut(&str) -> Result<(), String>,
+{
+    let settings = profile.mock.clone().unwrap_or_default();
+    let reply = next_reply(profile, &settings, user_prompt);
+    let mut full_text = String::new();
+    for (index, chunk) in split_chunks(&reply.text, settings.chunk_chars)
+        .iter()
+        .enumerate()
+    {
+        if let Some(error) = reply.error.as_deref() {
+            if index >= reply.error_after_chunks {
+                return Err(injected_error(error, index));
+            }
+        }
+        simulate_latency(settings.latency_ms).await;
+        full_text.push_str(chunk);
+        on_chunk(chunk)?;
+    }
+    if let Some(error) = reply.error.as_deref() {
+        return Err(injected_error(error, reply.error_after_chunks));
+    }
+
+    if full_text.trim().is_empty() {
+        Ok(EMPTY_RESPONSE.to_string())
+    } else {
+        Ok(full_text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::second_brain::config::ProfileCapabilities;
+
+    fn mock_profile(id: &str, settings: MockProviderSettings) -> ProviderProfile {
+        ProviderProfile {
+            id: id.to_string(),
+            label: "Mock".to_string(),
+            provider: "Mock".to_string(),
+            model: "scripted".to_string(),
+            api_key: String::new(),
+            key_source: Default::default(),
+            default_temperature: 0.15,
+            system_prompt: String::new(),
+            base_url: None,
+            default_mode: None,
+            capabilities: ProfileCapabilities::default(),
+            mock: Some(settings),
+            budget: None,
+        }
+    }
+
+    fn stream(profile: &ProviderProfile, prompt: &str) -> (Result<String, String>, Vec<String>) {
+        let mut chunks = Vec::new();
+        let result = tauri::async_runtime::block_on(run_mock_stream(profile, prompt, |chunk| {
+            chunks.push(chunk.to_string());
+            Ok(())
+        }));
+        (result, chunks)
+    }
+
+    #[test]
+    fn echoes_the_user_prompt_in_chunks() {
+        let profile = mock_profile(
+            "mock-echo",
+            MockProviderSettings {
+                chunk_chars: 4,
+                ..MockProviderSettings::default()
+            },
+        );
+        assert!(is_mock_provider(&profile));
+        let (result, chunks) = stream(&profile, "hello world");
+        assert_eq!(result.as_deref(), Ok("hello world"));
+        assert_eq!(chunks, vec!["hell", "o wo", "rld"]);
+    }
+
+    #[test]
+    fn serves_scripted_responses_in_order_and_restarts_on_new_script() {
+        let mut profile = mock_profile(
+            "mock-script",
+            MockProviderSettings {
+                responses: vec![
+                    MockResponse::Text("first".to_string()),
+                    MockResponse::Text("second".to_string()),
+                ],
+                ..MockProviderSettings::default()
+            },
+        );
+        let run = |profile: &ProviderProfile| {
+            tauri::async_runtime::block_on(run_mock(profile, "ignored")).expect("mock reply")
+        };
+        assert_eq!(run(&profile), "first");
+        assert_eq!(run(&profile), "second");
+        assert_eq!(run(&profile), "first");
+
+        profile.mock = Some(MockProviderSettings {
+            responses: vec![MockResponse::Text("other".to_string())],
+            ..MockProviderSettings::default()
+        });
+        assert_eq!(run(&profile), "other");
+    }
+
+    #[test]
+    fn injects_errors_before_or_during_the_stream() {
+        let settings: MockProviderSettings = serde_json::from_str(
+            r#"{
+                "responses": [
+                    {"error": "rate limited"},
+                    {"text": "partial answer", "error": "connection reset", "error_after_chunks": 2}
+                ],
+                "chunk_chars": 5
+            }"#,
+        )
+        .expect("mock settings");
+        let profile = mock_profile("mock-errors", settings);
+
+        let (result, chunks) = stream(&profile, "prompt");
+        assert_eq!(
+            result,
+            Err("Model request failed: rate limited".to_string())
+        );
+        assert!(chunks.is_empty());
+
+        let (result, chunks) = stream(&profile, "prompt");
+        assert_eq!(
+            result,
+            Err("Model stream failed: connection reset".to_string())
+        );
+        assert_eq!(chunks, vec!["parti", "al an"]);
+    }
+}
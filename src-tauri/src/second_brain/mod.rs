@@ -2,25 +2,32 @@ use std::sync::atomic::{AtomicU64, Ordering};
 
 use rusqlite::params;
 use serde::{Deserialize, Serialize};
-use tauri::AppHandle;
+use tauri::{AppHandle, Emitter};
 
-use super::{ensure_index_schema, now_ms, open_db, settings, AppError, Result};
+use super::{ensure_index_schema, now_ms, open_db, secrets, settings, AppError, Result};
 
 pub mod config;
 mod context;
 pub mod draft;
 mod draft_publish;
 mod frontmatter_generation;
+mod history_summary;
 pub mod model_discovery;
 pub mod llm;
+pub mod local_models;
 mod message_flow;
+pub mod mock_llm;
 pub mod modes;
 pub mod openai_codex;
 mod paths;
 mod prompt_builder;
+pub mod prompt_library;
+pub mod prompt_packs;
 mod pulse_flow;
 pub mod session_store;
 mod stream_control;
+pub mod structured_output;
+pub mod usage;
 
 use config::{active_profile, validate_config, ConfigStatus, SecondBrainConfig};
 use context::load_context_items;
@@ -34,14 +41,19 @@ use frontmatter_generation::{
     generate_frontmatter_properties as generate_frontmatter_properties_impl,
     GenerateFrontmatterPropertiesPayload, GenerateFrontmatterPropertiesResult,
 };
-use message_flow::send_message;
+use local_models::{LocalModel, LocalServerKind, LocalServerStatus};
+use message_flow::{edit_and_resend_message, regenerate_reply, send_message};
 use openai_codex::{discover_models, has_codex_tokens, CodexDiscoveredModel};
+use prompt_library::{load_prompt_catalog, PromptCatalog};
+use prompt_packs::prompt_pack;
 use pulse_flow::run_pulse;
 use session_store::{
-    create_session, delete_session, list_sessions, load_session, set_session_alter_id,
-    upsert_context,
+    create_session, delete_session, list_message_branches, list_sessions, load_session,
+    read_history_summary, set_session_alter_id, switch_active_branch, upsert_context,
+    upsert_history_summary, HistorySummary, MessageBranch,
 };
 use stream_control::request_stream_cancel;
+use usage::{budget_statuses, check_budget, open_usage_db, summarize_usage, UsageSummary};
 
 const SESSION_PREFIX: &str = "sb";
 static ID_SEQ: AtomicU64 = AtomicU64::new(1);
@@ -89,6 +101,35 @@ pub struct SendMessagePayload {
     pub attachments: Vec<AttachmentMeta>,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct RegenerateReplyPayload {
+    pub session_id: String,
+    pub message_id: String,
+    pub profile_id: Option<String>,
+    pub alter_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct EditMessagePayload {
+    pub session_id: String,
+    pub message_id: String,
+    pub message: String,
+    pub mode: Option<String>,
+    pub alter_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MessageBranchPayload {
+    pub session_id: String,
+    pub message_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateHistorySummaryPayload {
+    pub session_id: String,
+    pub content_md: String,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct SetSessionAlterPayload {
     pub session_id: String,
@@ -113,6 +154,9 @@ pub struct StreamEvent {
     pub chunk: String,
     pub done: bool,
     pub error: Option<String>,
+    /// Profile that produced the chunk or reply, which may be a fallback profile.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -155,6 +199,9 @@ pub struct PulseStreamEvent {
     pub error: Option<String>,
     pub title: Option<String>,
     pub provenance_paths: Vec<String>,
+    /// Profile that produced the chunk or output, which may be a fallback profile.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -237,6 +284,30 @@ pub struct WriteGlobalConfigResult {
     pub path: String,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct LocalServerPayload {
+    pub provider: String,
+    #[serde(default)]
+    pub base_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LocalModelPayload {
+    pub provider: String,
+    #[serde(default)]
+    pub base_url: Option<String>,
+    pub model: String,
+}
+
+/// Time range of a usage summary, in epoch milliseconds; both bounds are optional.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct UsageSummaryPayload {
+    #[serde(default)]
+    pub since_ms: Option<u64>,
+    #[serde(default)]
+    pub until_ms: Option<u64>,
+}
+
 /// Loads the user-facing Second Brain config and normalizes backend failures to safe IPC errors.
 pub(super) fn load_config() -> Result<SecondBrainConfig> {
     settings::load_llm_for_runtime().map_err(|err| {
@@ -263,7 +334,7 @@ pub(super) fn session_exists(conn: &rusqlite::Connection, session_id: &str) -> R
 }
 
 #[tauri::command]
-pub fn read_second_brain_config_status() -> Result<ConfigStatus> {
+pub async fn read_second_brain_config_status() -> Result<ConfigStatus> {
     match load_config() {
         Ok(config) => {
             let active = active_profile(&config).ok_or_else(|| {
@@ -281,17 +352,57 @@ pub fn read_second_brain_config_status() -> Result<ConfigStatus> {
                     error: Some(
                         "OpenAI Codex is not authenticated. Run `codex auth login`.".to_string(),
                     ),
+                    budget_warning: None,
                 });
             }
+            if let Err(err) = secrets::resolve_api_key(&active.api_key, &active.key_source) {
+                return Ok(ConfigStatus {
+                    configured: false,
+                    provider: Some(active.provider.clone()),
+                    model: Some(active.model.clone()),
+                    profile_id: Some(active.id.clone()),
+                    supports_streaming: false,
+                    supports_image_input: false,
+                    supports_audio_input: false,
+                    error: Some(err.to_string()),
+                    budget_warning: None,
+                });
+            }
+            if let Some(kind) = LocalServerKind::from_provider(&active.provider) {
+                if let Err(error) = local_models::check_profile_model(
+                    kind,
+                    active.base_url.as_deref(),
+                    &active.model,
+                )
+                .await
+                {
+                    return Ok(ConfigStatus {
+                        configured: false,
+                        provider: Some(active.provider.clone()),
+                        model: Some(active.model.clone()),
+                        profile_id: Some(active.id.clone()),
+                        supports_streaming: false,
+                        supports_image_input: false,
+                        supports_audio_input: false,
+                        error: Some(error),
+                        budget_warning: None,
+                    });
+                }
+            }
+            let (budget_error, budget_warning) = match check_budget(active) {
+                Ok(warning) => (None, warning),
+                Err(error) => (Some(error), None),
+            };
             Ok(ConfigStatus {
-                configured: true,
+                configured: budget_error.is_none(),
                 provider: Some(active.provider.clone()),
                 model: Some(active.model.clone()),
                 profile_id: Some(active.id.clone()),
                 supports_streaming: active.capabilities.streaming,
                 supports_image_input: active.capabilities.image_input,
                 supports_audio_input: active.capabilities.audio_input,
-                error: None,
+                error: budget_error.or_else(settings::key_migration_error),
+                budget_warning,
             })
         }
         Err(err) => Ok(ConfigStatus {
@@ -303,10 +414,22 @@ pub fn read_second_brain_config_status() -> Result<ConfigStatus> {
             supports_image_input: false,
             supports_audio_input: false,
             error: Some(err.to_string()),
+            budget_warning: None,
         }),
     }
 }
 
+/// Lists built-in and workspace-defined modes and Pulse actions.
+///
+/// Built-in texts follow the configured prompt language, or the default pack when the
+/// language is detected per request. Invalid `_prompts/` files are returned as issues
+/// so the settings UI can show them.
+#[tauri::command]
+pub fn list_second_brain_prompts() -> Result<PromptCatalog> {
+    let locale = settings::configured_prompt_locale().unwrap_or_default();
+    Ok(load_prompt_catalog(prompt_pack(locale)))
+}
+
 #[tauri::command]
 pub async fn generate_frontmatter_properties(
     payload: GenerateFrontmatterPropertiesPayload,
@@ -319,6 +442,68 @@ pub async fn discover_codex_models() -> Result<Vec<CodexDiscoveredModel>> {
     discover_models().await.map_err(AppError::InvalidOperation)
 }
 
+/// Summarizes recorded LLM usage by day, feature and model, with the current
+/// month status of every profile budget.
+#[tauri::command]
+pub fn read_llm_usage_summary(payload: UsageSummaryPayload) -> Result<UsageSummary> {
+    let conn = open_usage_db()?;
+    let rows = summarize_usage(&conn, payload.since_ms, payload.until_ms)?;
+    let budgets = match load_config() {
+        Ok(config) => budget_statuses(&conn, &config.profiles)?,
+        Err(_) => Vec::new(),
+    };
+    Ok(UsageSummary { rows, budgets })
+}
+
+fn local_server_kind(provider: &str) -> Result<LocalServerKind> {
+    LocalServerKind::from_provider(provider).ok_or_else(|| {
+        AppError::InvalidOperation(format!(
+            "Provider {} is not a local model server.",
+            provider.trim()
+        ))
+    })
+}
+
+/// Reports whether the Ollama or llama.cpp server of a profile is reachable.
+#[tauri::command]
+pub async fn check_local_model_server(payload: LocalServerPayload) -> Result<LocalServerStatus> {
+    let kind = local_server_kind(&payload.provider)?;
+    Ok(local_models::server_status(kind, payload.base_url.as_deref()).await)
+}
+
+/// Lists the models installed on a local server with size, quantization and context length.
+#[tauri::command]
+pub async fn list_local_models(payload: LocalServerPayload) -> Result<Vec<LocalModel>> {
+    let kind = local_server_kind(&payload.provider)?;
+    local_models::list_models(kind, payload.base_url.as_deref())
+        .await
+        .map_err(AppError::InvalidOperation)
+}
+
+/// Pulls a model through Ollama, emitting `second-brain://local-model-pull-progress` events.
+#[tauri::command]
+pub async fn pull_local_model(app: AppHandle, payload: LocalModelPayload) -> Result<()> {
+    let kind = local_server_kind(&payload.provider)?;
+    local_models::pull_model(
+        kind,
+        payload.base_url.as_deref(),
+        &payload.model,
+        |progress| {
+            let _ = app.emit("second-brain://local-model-pull-progress", progress);
+        },
+    )
+    .await
+    .map_err(AppError::InvalidOperation)
+}
+
+#[tauri::command]
+pub async fn delete_local_model(payload: LocalModelPayload) -> Result<()> {
+    let kind = local_server_kind(&payload.provider)?;
+    local_models::delete_model(kind, payload.base_url.as_deref(), &payload.model)
+        .await
+        .map_err(AppError::InvalidOperation)
+}
+
 #[tauri::command]
 pub fn write_second_brain_global_config(
     payload: WriteGlobalConfigPayload,
@@ -436,6 +621,56 @@ pub fn update_second_brain_context(payload: UpdateContextPayload) -> Result<Upda
     Ok(UpdateContextResult { token_estimate })
 }
 
+#[tauri::command]
+pub fn read_second_brain_history_summary(session_id: String) -> Result<Option<HistorySummary>> {
+    let conn = open_db()?;
+    ensure_index_schema(&conn)?;
+    if !session_exists(&conn, &session_id)? {
+        return Err(AppError::InvalidOperation(
+            "Second Brain session not found.".to_string(),
+        ));
+    }
+    read_history_summary(&conn, &session_id)
+}
+
+/// Saves a user-edited rolling summary while keeping its coverage marker.
+///
+/// The next overflow folds newly dropped turns into the edited text instead of
+/// replacing it.
+#[tauri::command]
+pub fn update_second_brain_history_summary(
+    payload: UpdateHistorySummaryPayload,
+) -> Result<HistorySummary> {
+    let conn = open_db()?;
+    ensure_index_schema(&conn)?;
+    if !session_exists(&conn, &payload.session_id)? {
+        return Err(AppError::InvalidOperation(
+            "Second Brain session not found.".to_string(),
+        ));
+    }
+    let existing = read_history_summary(&conn, &payload.session_id)?;
+    let summary = HistorySummary {
+        session_id: payload.session_id.clone(),
+        content_md: payload.content_md.trim().to_string(),
+        covered_until_message_id: existing
+            .as_ref()
+            .map(|item| item.covered_until_message_id.clone())
+            .unwrap_or_default(),
+        covered_until_ms: existing
+            .as_ref()
+            .map(|item| item.covered_until_ms)
+            .unwrap_or(0),
+        covered_message_count: existing
+            .as_ref()
+            .map(|item| item.covered_message_count)
+            .unwrap_or(0),
+        edited_by_user: true,
+        updated_at_ms: now_ms(),
+    };
+    upsert_history_summary(&conn, &summary)?;
+    Ok(summary)
+}
+
 #[tauri::command]
 pub fn cancel_second_brain_stream(payload: CancelStreamPayload) -> Result<()> {
     if payload.session_id.trim().is_empty() {
@@ -474,6 +709,55 @@ pub async fn send_second_brain_message(
     send_message(app, payload).await
 }
 
+/// Regenerates a reply as a new branch, optionally with another profile or Alter.
+#[tauri::command]
+pub async fn regenerate_second_brain_reply(
+    app: AppHandle,
+    payload: RegenerateReplyPayload,
+) -> Result<SendMessageResult> {
+    regenerate_reply(app, payload).await
+}
+
+/// Sends an edited user message as a new branch and answers it.
+#[tauri::command]
+pub async fn edit_second_brain_message(
+    app: AppHandle,
+    payload: EditMessagePayload,
+) -> Result<SendMessageResult> {
+    edit_and_resend_message(app, payload).await
+}
+
+#[tauri::command]
+pub fn list_second_brain_message_branches(
+    payload: MessageBranchPayload,
+) -> Result<Vec<MessageBranch>> {
+    let conn = open_db()?;
+    ensure_index_schema(&conn)?;
+    if !session_exists(&conn, &payload.session_id)? {
+        return Err(AppError::InvalidOperation(
+            "Second Brain session not found.".to_string(),
+        ));
+    }
+    list_message_branches(&conn, &payload.session_id, &payload.message_id)
+}
+
+/// Activates the branch containing a message and returns the session as displayed.
+#[tauri::command]
+pub fn switch_second_brain_branch(
+    payload: MessageBranchPayload,
+) -> Result<session_store::SessionPayload> {
+    let conn = open_db()?;
+    ensure_index_schema(&conn)?;
+    if !session_exists(&conn, &payload.session_id)? {
+        return Err(AppError::InvalidOperation(
+            "Second Brain session not found.".to_string(),
+        ));
+    }
+    switch_active_branch(&conn, &payload.session_id, &payload.message_id)?;
+    let draft_content = read_draft(&payload.session_id)?;
+    load_session(&conn, &payload.session_id, draft_content)
+}
+
 #[tauri::command]
 pub fn set_second_brain_session_target_note(
     payload: SetSessionTargetNotePayload,
@@ -11,7 +11,7 @@ pub struct DiscoveredModel {
     pub group: Option<String>,
 }
 
-fn summarize_response_body(body: &str) -> String {
+pub(super) fn summarize_response_body(body: &str) -> String {
     let trimmed = body.trim();
     if trimmed.is_empty() {
         return "<empty body>".to_string();
@@ -1,5 +1,9 @@
 use serde::{Deserialize, Serialize};
 
+use super::prompt_packs::{BuiltinPromptText, PromptPack};
+
+const FREESTYLE_MODE_ID: &str = "freestyle";
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum ModeKind {
@@ -8,6 +12,25 @@ pub enum ModeKind {
     SkillRef,
 }
 
+/// Response shape requested from the model by a mode or Pulse action.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputFormat {
+    #[default]
+    Markdown,
+    Plain,
+    Json,
+}
+
+/// Where a mode or Pulse action definition comes from.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptSource {
+    #[default]
+    Builtin,
+    Workspace,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModeSpec {
     pub id: String,
@@ -16,100 +39,154 @@ pub struct ModeSpec {
     pub prompt_template: String,
     pub agent_id: Option<String>,
     pub skill_ref: Option<String>,
+    #[serde(default)]
+    pub temperature: Option<f64>,
+    #[serde(default)]
+    pub output_format: OutputFormat,
+    #[serde(default)]
+    pub source: PromptSource,
+    #[serde(default)]
+    pub source_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PulseActionSpec {
+    pub id: String,
+    pub label: String,
+    pub prompt_template: String,
+    #[serde(default)]
+    pub temperature: Option<f64>,
+    #[serde(default)]
+    pub output_format: OutputFormat,
+    #[serde(default)]
+    pub source: PromptSource,
+    #[serde(default)]
+    pub source_path: Option<String>,
 }
 
-pub fn default_mode_specs() -> Vec<ModeSpec> {
-    vec![
-        ModeSpec {
-            id: "freestyle".to_string(),
-            label: "Freestyle".to_string(),
-            kind: ModeKind::PromptTemplate,
-            prompt_template: "Tu es un assistant polyvalent. Execute la demande utilisateur avec precision. Utilise le contexte fourni s'il existe et signale clairement les incertitudes lorsqu'il manque des informations. Reponds en markdown. Ne cite des sources que si l'utilisateur le demande explicitement."
-                .to_string(),
-            agent_id: None,
-            skill_ref: None,
-        },
-        ModeSpec {
-            id: "synthese".to_string(),
-            label: "Synthese".to_string(),
-            kind: ModeKind::PromptTemplate,
-            prompt_template: "Fournis une synthese structuree, concise, avec limites et points d'incertitude. Cite les sources de contexte utilisees."
-                .to_string(),
-            agent_id: None,
-            skill_ref: None,
-        },
-        ModeSpec {
-            id: "plan_action".to_string(),
-            label: "Plan d'action".to_string(),
-            kind: ModeKind::PromptTemplate,
-            prompt_template: "Construis un plan d'action concret, ordonne et verifiable. Cite les sources de contexte pour chaque decision."
-                .to_string(),
-            agent_id: None,
-            skill_ref: None,
-        },
-        ModeSpec {
-            id: "diagnostic".to_string(),
-            label: "Diagnostic".to_string(),
-            kind: ModeKind::AgentBuiltin,
-            prompt_template: "Etablis un diagnostic: problemes, hypotheses, evidences, tests de validation. Cite les sources de contexte."
-                .to_string(),
-            agent_id: Some("builtin.diagnostic.v1".to_string()),
-            skill_ref: None,
-        },
-        ModeSpec {
-            id: "fusion_notes".to_string(),
-            label: "Fusion de notes".to_string(),
-            kind: ModeKind::PromptTemplate,
-            prompt_template: "Fusionne les notes en eliminant doublons et contradictions. Preserve les faits traces aux sources."
-                .to_string(),
-            agent_id: None,
-            skill_ref: None,
-        },
-        ModeSpec {
-            id: "extraction_concepts".to_string(),
-            label: "Extraction de concepts".to_string(),
-            kind: ModeKind::SkillRef,
-            prompt_template: "Extrait les concepts, definitons, relations et ambiguities restantes. Cite les sources."
-                .to_string(),
-            agent_id: None,
-            skill_ref: Some("second_brain.extract_concepts".to_string()),
-        },
-    ]
+fn builtin_mode(text: &BuiltinPromptText) -> ModeSpec {
+    let (kind, agent_id, skill_ref) = builtin_mode_wiring(text.id);
+    ModeSpec {
+        id: text.id.to_string(),
+        label: text.label.to_string(),
+        kind,
+        prompt_template: text.prompt.to_string(),
+        agent_id: agent_id.map(ToOwned::to_owned),
+        skill_ref: skill_ref.map(ToOwned::to_owned),
+        temperature: None,
+        output_format: OutputFormat::Markdown,
+        source: PromptSource::Builtin,
+        source_path: None,
+    }
+}
+
+/// Returns the kind and agent/skill references of a built-in mode; texts come from the pack.
+fn builtin_mode_wiring(id: &str) -> (ModeKind, Option<&'static str>, Option<&'static str>) {
+    match id {
+        "diagnostic" => (ModeKind::AgentBuiltin, Some("builtin.diagnostic.v1"), None),
+        "extraction_concepts" => (
+            ModeKind::SkillRef,
+            None,
+            Some("second_brain.extract_concepts"),
+        ),
+        _ => (ModeKind::PromptTemplate, None, None),
+    }
 }
 
-pub fn resolve_mode_prompt(mode: &str) -> String {
+fn builtin_pulse_action(text: &BuiltinPromptText) -> PulseActionSpec {
+    PulseActionSpec {
+        id: text.id.to_string(),
+        label: text.label.to_string(),
+        prompt_template: text.prompt.to_string(),
+        temperature: None,
+        output_format: OutputFormat::Markdown,
+        source: PromptSource::Builtin,
+        source_path: None,
+    }
+}
+
+pub fn default_mode_specs(pack: &PromptPack) -> Vec<ModeSpec> {
+    pack.modes.iter().map(builtin_mode).collect()
+}
+
+pub fn default_pulse_action_specs(pack: &PromptPack) -> Vec<PulseActionSpec> {
+    pack.pulse_actions
+        .iter()
+        .map(builtin_pulse_action)
+        .collect()
+}
+
+/// Resolves a mode id against the available specs, falling back to freestyle.
+pub fn resolve_mode(mode: &str, modes: &[ModeSpec], pack: &PromptPack) -> ModeSpec {
     let mode_trimmed = mode.trim().to_lowercase();
-    default_mode_specs()
-        .into_iter()
+    modes
+        .iter()
         .find(|item| item.id == mode_trimmed)
-        .map(|item| item.prompt_template)
+        .or_else(|| modes.iter().find(|item| item.id == FREESTYLE_MODE_ID))
+        .cloned()
         .unwrap_or_else(|| {
-            "Tu es un assistant polyvalent. Execute la demande utilisateur avec precision. Utilise le contexte fourni s'il existe et signale clairement les incertitudes lorsqu'il manque des informations. Reponds en markdown. Ne cite des sources que si l'utilisateur le demande explicitement."
-                .to_string()
+            builtin_mode(
+                pack.mode(FREESTYLE_MODE_ID)
+                    .expect("every prompt pack defines the freestyle mode"),
+            )
         })
 }
 
+pub fn resolve_mode_prompt(mode: &str, modes: &[ModeSpec], pack: &PromptPack) -> String {
+    resolve_mode(mode, modes, pack).prompt_template
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::second_brain::prompt_packs::{prompt_pack, PromptLocale};
+
+    fn french() -> &'static PromptPack {
+        prompt_pack(PromptLocale::Fr)
+    }
 
     #[test]
     fn resolves_known_mode_prompt() {
-        let prompt = resolve_mode_prompt("diagnostic");
+        let prompt = resolve_mode_prompt("diagnostic", &default_mode_specs(french()), french());
         assert!(prompt.contains("diagnostic"));
     }
 
     #[test]
     fn has_freestyle_mode() {
-        let modes = default_mode_specs();
+        let modes = default_mode_specs(french());
         assert!(modes.iter().any(|item| item.id == "freestyle"));
     }
 
     #[test]
     fn freestyle_prompt_is_markdown_without_forced_citations() {
-        let prompt = resolve_mode_prompt("freestyle");
+        let prompt = resolve_mode_prompt("freestyle", &default_mode_specs(french()), french());
         assert!(prompt.contains("Reponds en markdown"));
         assert!(prompt.contains("Ne cite des sources que si l'utilisateur le demande"));
         assert!(!prompt.contains("assistant de deliberation"));
     }
+
+    #[test]
+    fn english_pack_keeps_mode_ids_and_wiring() {
+        let english = prompt_pack(PromptLocale::En);
+        let modes = default_mode_specs(english);
+        let diagnostic = resolve_mode("diagnostic", &modes, english);
+        assert_eq!(diagnostic.kind, ModeKind::AgentBuiltin);
+        assert_eq!(
+            diagnostic.agent_id.as_deref(),
+            Some("builtin.diagnostic.v1")
+        );
+        assert!(resolve_mode_prompt("freestyle", &modes, english).contains("Respond in markdown"));
+    }
+
+    #[test]
+    fn unknown_mode_falls_back_to_freestyle_even_without_specs() {
+        assert_eq!(
+            resolve_mode("missing", &default_mode_specs(french()), french()).id,
+            "freestyle"
+        );
+        assert_eq!(
+            resolve_mode("missing", &[], french()).prompt_template,
+            french().mode("freestyle").unwrap().prompt
+        );
+    }
 }
@@ -1,10 +1,13 @@
-use std::{collections::HashSet, fs, path::PathBuf};
+use std::{collections::HashSet, fs, path::PathBuf, time::Duration};
 
 use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 use futures_util::StreamExt;
 use serde::Serialize;
 use serde_json::Value;
 
+use super::llm::timed_out;
+use super::usage::TokenUsage;
+
 #[derive(Debug, Clone)]
 struct CodexTokens {
     access_token: String,
@@ -334,10 +337,27 @@ fn parse_sse_line(line: &str) -> Option<&str> {
     trimmed.strip_prefix("data: ")
 }
 
+/// Token counts of a `response.completed` event.
+fn parse_codex_usage(event: &Value) -> TokenUsage {
+    let usage = event
+        .get("response")
+        .and_then(|response| response.get("usage"));
+    let count = |key: &str| {
+        usage
+            .and_then(|value| value.get(key))
+            .and_then(Value::as_u64)
+    };
+    TokenUsage {
+        input_tokens: count("input_tokens"),
+        output_tokens: count("output_tokens"),
+    }
+}
+
 async fn stream_codex_text<F>(
     response: reqwest::Response,
+    timeout: Duration,
     mut on_chunk: F,
-) -> Result<String, String>
+) -> Result<(String, TokenUsage), String>
 where
     F: FnMut(&str) -> Result<(), String>,
 {
@@ -345,7 +365,10 @@ where
     let mut buffer = String::new();
     let mut full_text = String::new();
 
-    while let Some(next) = stream.next().await {
+    while let Some(next) = tokio::time::timeout(timeout, stream.next())
+        .await
+        .map_err(|_| timed_out(timeout))?
+    {
         let bytes = next.map_err(|_| "OpenAI Codex stream failed.".to_string())?;
         buffer.push_str(&String::from_utf8_lossy(&bytes));
 
@@ -357,7 +380,7 @@ where
                 continue;
             };
             if data == "[DONE]" {
-                return Ok(full_text);
+                return Ok((full_text, TokenUsage::default()));
             }
 
             let event: Value = match serde_json::from_str(data) {
@@ -386,13 +409,13 @@ where
                         .unwrap_or("OpenAI Codex returned an error.");
                     return Err(message.to_string());
                 }
-                "response.completed" => return Ok(full_text),
+                "response.completed" => return Ok((full_text, parse_codex_usage(&event))),
                 _ => {}
             }
         }
     }
 
-    Ok(full_text)
+    Ok((full_text, TokenUsage::default()))
 }
 
 pub async fn run_codex(
@@ -400,8 +423,17 @@ pub async fn run_codex(
     system_prompt: &str,
     user_prompt: &str,
     temperature: Option<f64>,
-) -> Result<String, String> {
-    run_codex_stream(model, system_prompt, user_prompt, temperature, |_| Ok(())).await
+    timeout: Duration,
+) -> Result<(String, TokenUsage), String> {
+    run_codex_stream(
+        model,
+        system_prompt,
+        user_prompt,
+        temperature,
+        timeout,
+        |_| Ok(()),
+    )
+    .await
 }
 
 pub async fn run_codex_stream<F>(
@@ -409,20 +441,26 @@ pub async fn run_codex_stream<F>(
     system_prompt: &str,
     user_prompt: &str,
     temperature: Option<f64>,
+    timeout: Duration,
     on_chunk: F,
-) -> Result<String, String>
+) -> Result<(String, TokenUsage), String>
 where
     F: FnMut(&str) -> Result<(), String>,
 {
     let (access_token, account_id) = load_credentials()?;
     let body = codex_request_body(model, system_prompt, user_prompt, temperature);
-    let response = post_codex_request(&access_token, &account_id, &body).await?;
-    let full_text = stream_codex_text(response, on_chunk).await?;
+    let response = tokio::time::timeout(
+        timeout,
+        post_codex_request(&access_token, &account_id, &body),
+    )
+    .await
+    .map_err(|_| timed_out(timeout))??;
+    let (full_text, usage) = stream_codex_text(response, timeout, on_chunk).await?;
     let trimmed = full_text.trim();
     if trimmed.is_empty() {
-        Ok("(Empty assistant response)".to_string())
+        Ok(("(Empty assistant response)".to_string(), usage))
     } else {
-        Ok(full_text)
+        Ok((full_text, usage))
     }
 }
 
@@ -485,6 +523,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_codex_usage_reads_completed_event() {
+        let event: Value = serde_json::from_str(
+            r#"{"type":"response.completed","response":{"usage":{"input_tokens":120,"output_tokens":45,"total_tokens":165}}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            parse_codex_usage(&event),
+            TokenUsage {
+                input_tokens: Some(120),
+                output_tokens: Some(45),
+            }
+        );
+        assert_eq!(
+            parse_codex_usage(&serde_json::json!({"type": "response.completed"})),
+            TokenUsage::default()
+        );
+    }
+
     #[test]
     fn parse_sse_line_works() {
         assert_eq!(parse_sse_line("data: hello"), Some("hello"));
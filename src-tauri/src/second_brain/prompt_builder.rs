@@ -6,6 +6,8 @@
 use super::{
     context::ContextPromptEntry,
     frontmatter_generation::{FrontmatterGenerationExistingField, FrontmatterGenerationMode},
+    modes::{OutputFormat, PulseActionSpec},
+    prompt_packs::{detect_note_language, prompt_pack, PromptLocale, PromptPack},
     session_store::{estimate_tokens, MessageRow},
     AppError, PulseSourceKind, Result, RunPulseTransformationPayload,
 };
@@ -20,6 +22,7 @@ pub(super) struct FrontmatterGenerationPromptInput {
     pub mode: FrontmatterGenerationMode,
     pub target_key: Option<String>,
     pub language_hint: Option<String>,
+    pub prompt_language: Option<PromptLocale>,
 }
 
 const SB_HISTORY_WINDOW: usize = 12;
@@ -28,9 +31,12 @@ const SB_HISTORY_BUDGET_TOKENS: usize = 3_000;
 const SB_CONTEXT_BUDGET_TOKENS: usize = 6_500;
 const SB_MAX_FILE_TOKENS: usize = 1_200;
 const SB_PROMPT_OVERHEAD_TOKENS: usize = 500;
+const SB_HISTORY_SUMMARY_BUDGET_TOKENS: usize = 800;
+const SB_SUMMARY_INPUT_BUDGET_TOKENS: usize = 6_000;
+const SB_SUMMARY_MAX_TURN_TOKENS: usize = 1_200;
+const SB_TITLE_INPUT_BUDGET_TOKENS: usize = 1_000;
 const FRONTMATTER_BODY_BUDGET_TOKENS: usize = 3_500;
 const FRONTMATTER_RAW_YAML_BUDGET_TOKENS: usize = 1_200;
-const TRUNCATION_MARKER: &str = "\n[CONTENU TRONQUE]\n";
 
 #[derive(Debug, Clone)]
 pub(super) struct BuiltPrompt {
@@ -40,7 +46,7 @@ pub(super) struct BuiltPrompt {
 }
 
 /// Truncates oversized text while preserving both the beginning and the end of the source.
-pub(super) fn truncate_text_for_tokens(text: &str, max_tokens: usize) -> String {
+pub(super) fn truncate_text_for_tokens(text: &str, max_tokens: usize, marker: &str) -> String {
     if max_tokens == 0 {
         return String::new();
     }
@@ -49,22 +55,74 @@ pub(super) fn truncate_text_for_tokens(text: &str, max_tokens: usize) -> String
     }
 
     let max_chars = max_tokens.saturating_mul(4);
-    let marker_len = TRUNCATION_MARKER.chars().count();
+    let marker_len = marker.chars().count();
     if max_chars <= marker_len + 32 {
-        return TRUNCATION_MARKER.trim().to_string();
+        return marker.trim().to_string();
     }
 
     let keep_each = (max_chars - marker_len) / 2;
     let start: String = text.chars().take(keep_each).collect();
     let end_vec: Vec<char> = text.chars().rev().take(keep_each).collect();
     let end: String = end_vec.into_iter().rev().collect();
-    format!("{start}{TRUNCATION_MARKER}{end}")
+    format!("{start}{marker}{end}")
+}
+
+/// Returns how many of the oldest history messages fall outside the prompt window.
+///
+/// The count mirrors [`build_history_section`] so the rolling summary covers exactly
+/// the turns that the next prompt is about to drop.
+pub(super) fn history_overflow_len(message: &str, history_messages: &[MessageRow]) -> usize {
+    let (history_budget, _) = prompt_budgets(message);
+    history_window_start(history_messages, history_budget, SB_HISTORY_WINDOW)
+}
+
+fn prompt_budgets(message: &str) -> (usize, usize) {
+    let user_tokens = estimate_tokens(message);
+    let available_budget = SB_PROMPT_BUDGET_TOKENS
+        .saturating_sub(SB_PROMPT_OVERHEAD_TOKENS)
+        .saturating_sub(user_tokens);
+    let history_budget = available_budget.min(SB_HISTORY_BUDGET_TOKENS);
+    let context_budget = available_budget
+        .saturating_sub(history_budget)
+        .min(SB_CONTEXT_BUDGET_TOKENS);
+    (history_budget, context_budget)
+}
+
+fn history_entry(item: &MessageRow) -> String {
+    format!("[{}]\n{}\n", item.role, item.content_md.trim())
+}
+
+fn history_window_start(
+    history_messages: &[MessageRow],
+    history_budget_tokens: usize,
+    max_messages: usize,
+) -> usize {
+    let mut kept = 0usize;
+    let mut consumed = 0usize;
+    for item in history_messages.iter().rev() {
+        if kept >= max_messages {
+            break;
+        }
+        let entry_tokens = estimate_tokens(&history_entry(item));
+        let remaining = history_budget_tokens.saturating_sub(consumed);
+        if entry_tokens <= remaining {
+            kept += 1;
+            consumed = consumed.saturating_add(entry_tokens);
+            continue;
+        }
+        if kept == 0 && remaining >= 32 {
+            kept += 1;
+        }
+        break;
+    }
+    history_messages.len() - kept
 }
 
 fn build_history_section(
     history_messages: &[MessageRow],
     history_budget_tokens: usize,
     max_messages: usize,
+    pack: &PromptPack,
 ) -> String {
     let mut selected: Vec<String> = Vec::new();
     let mut consumed = 0usize;
@@ -72,7 +130,7 @@ fn build_history_section(
         if selected.len() >= max_messages {
             break;
         }
-        let entry = format!("[{}]\n{}\n", item.role, item.content_md.trim());
+        let entry = history_entry(item);
         let entry_tokens = estimate_tokens(&entry);
         let remaining = history_budget_tokens.saturating_sub(consumed);
         if entry_tokens <= remaining {
@@ -81,7 +139,11 @@ fn build_history_section(
             continue;
         }
         if selected.is_empty() && remaining >= 32 {
-            selected.push(truncate_text_for_tokens(&entry, remaining));
+            selected.push(truncate_text_for_tokens(
+                &entry,
+                remaining,
+                pack.truncation_marker,
+            ));
         }
         break;
     }
@@ -93,12 +155,16 @@ fn build_context_section(
     session_id: &str,
     context_entries: &[ContextPromptEntry],
     context_budget_tokens: usize,
+    pack: &PromptPack,
 ) -> (String, Vec<String>) {
     if context_entries.is_empty() || context_budget_tokens == 0 {
         return (String::new(), Vec::new());
     }
 
-    let mut section = format!("Session: {session_id}\n\nContexte fourni:\n");
+    let mut section = format!(
+        "Session: {session_id}\n\n{}\n",
+        pack.second_brain.context_heading
+    );
     let mut consumed = estimate_tokens(&section);
     let mut included_paths = Vec::new();
     for entry in context_entries {
@@ -117,7 +183,8 @@ fn build_context_section(
         if content_budget < 32 {
             break;
         }
-        let content = truncate_text_for_tokens(&entry.content, content_budget);
+        let content =
+            truncate_text_for_tokens(&entry.content, content_budget, pack.truncation_marker);
         section.push_str(&header);
         section.push_str(&content);
         section.push('\n');
@@ -133,33 +200,48 @@ fn build_context_section(
 }
 
 /// Builds the assistant request prompt from explicit context, recent history and the new message.
+///
+/// When a rolling history summary exists it stands in for the turns that no longer fit
+/// in the recent-history window. Its budget is taken from the context share so the
+/// window itself stays aligned with [`history_overflow_len`]. Headings and response
+/// instructions come from `pack`, whose locale is reported as the `language_hint`.
 pub(super) fn build_user_prompt(
     session_id: &str,
     message: &str,
     history_messages: &[MessageRow],
     context_entries: &[ContextPromptEntry],
     alter_prompt: Option<&str>,
+    history_summary: Option<&str>,
+    output_format: OutputFormat,
+    pack: &PromptPack,
 ) -> BuiltPrompt {
-    let user_tokens = estimate_tokens(message);
-    let available_budget = SB_PROMPT_BUDGET_TOKENS
-        .saturating_sub(SB_PROMPT_OVERHEAD_TOKENS)
-        .saturating_sub(user_tokens);
-    let history_budget = available_budget.min(SB_HISTORY_BUDGET_TOKENS);
-    let context_budget = available_budget
-        .saturating_sub(history_budget)
-        .min(SB_CONTEXT_BUDGET_TOKENS);
+    let texts = &pack.second_brain;
+    let (history_budget, context_budget) = prompt_budgets(message);
+    let summary_section = history_summary
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(|value| {
+            truncate_text_for_tokens(
+                value,
+                SB_HISTORY_SUMMARY_BUDGET_TOKENS,
+                pack.truncation_marker,
+            )
+        })
+        .unwrap_or_default();
+    let context_budget = context_budget.saturating_sub(estimate_tokens(&summary_section));
 
     let (context_section, included_context_paths) =
-        build_context_section(session_id, context_entries, context_budget);
+        build_context_section(session_id, context_entries, context_budget, pack);
     let history_section =
-        build_history_section(history_messages, history_budget, SB_HISTORY_WINDOW);
+        build_history_section(history_messages, history_budget, SB_HISTORY_WINDOW, pack);
 
     let mut prompt = String::new();
     if let Some(alter_prompt) = alter_prompt
         .map(str::trim)
         .filter(|value| !value.is_empty())
     {
-        prompt.push_str("Alter actif:\n");
+        prompt.push_str(texts.active_alter_heading);
+        prompt.push('\n');
         prompt.push_str(alter_prompt);
         prompt.push_str("\n\n");
     }
@@ -167,20 +249,110 @@ pub(super) fn build_user_prompt(
         prompt.push_str(&context_section);
         prompt.push_str("\n\n");
     }
+    if !summary_section.is_empty() {
+        prompt.push_str(texts.history_summary_heading);
+        prompt.push('\n');
+        prompt.push_str(&summary_section);
+        prompt.push_str("\n\n");
+    }
     if !history_section.is_empty() {
-        prompt.push_str("Historique recent:\n");
+        prompt.push_str(texts.recent_history_heading);
+        prompt.push('\n');
         prompt.push_str(&history_section);
         prompt.push_str("\n\n");
     }
-    prompt.push_str("Demande utilisateur:\n");
+    prompt.push_str(texts.user_request_heading);
+    prompt.push('\n');
     prompt.push_str(message.trim());
-    prompt.push_str("\n\nReponds en markdown.");
+    prompt.push_str("\n\n");
+    prompt.push_str(response_format_instruction(output_format, pack));
 
     BuiltPrompt {
         user_prompt: prompt,
         included_context_paths,
-        language_hint: String::new(),
+        language_hint: pack.locale.code().to_string(),
+    }
+}
+
+/// Returns the system instruction used to condense overflowing history.
+pub(super) fn history_summary_system_prompt(pack: &PromptPack) -> &'static str {
+    pack.second_brain.summary_system
+}
+
+/// Builds the prompt that folds newly dropped turns into the existing rolling summary.
+///
+/// Turns are added oldest first while they fit the input budget; the returned count
+/// tells the caller how many of `dropped_messages` the prompt actually covers.
+pub(super) fn build_history_summary_prompt(
+    previous_summary: &str,
+    dropped_messages: &[MessageRow],
+    pack: &PromptPack,
+) -> (String, usize) {
+    let mut turns = Vec::new();
+    let mut consumed = 0usize;
+    for item in dropped_messages {
+        let entry = truncate_text_for_tokens(
+            &history_entry(item),
+            SB_SUMMARY_MAX_TURN_TOKENS,
+            pack.truncation_marker,
+        );
+        let tokens = estimate_tokens(&entry);
+        if !turns.is_empty() && consumed.saturating_add(tokens) > SB_SUMMARY_INPUT_BUDGET_TOKENS {
+            break;
+        }
+        consumed = consumed.saturating_add(tokens);
+        turns.push(entry);
+    }
+    let included = turns.len();
+
+    let texts = &pack.second_brain;
+    let mut prompt = String::new();
+    let previous = previous_summary.trim();
+    if !previous.is_empty() {
+        prompt.push_str(texts.summary_existing_heading);
+        prompt.push('\n');
+        prompt.push_str(previous);
+        prompt.push_str("\n\n");
+    }
+    prompt.push_str(texts.summary_new_turns_heading);
+    prompt.push('\n');
+    prompt.push_str(&turns.join("\n"));
+    prompt.push_str("\n\n");
+    prompt.push_str(texts.task_heading);
+    prompt.push('\n');
+    prompt.push_str(texts.summary_task);
+    (prompt, included)
+}
+
+/// Returns the system prompt used by the titler model role.
+pub(super) fn session_title_system_prompt(pack: &PromptPack) -> &'static str {
+    pack.second_brain.title_system
+}
+
+/// Builds the prompt asking the titler model role to name a session from its first message.
+pub(super) fn build_session_title_prompt(message: &str, pack: &PromptPack) -> String {
+    let texts = &pack.second_brain;
+    let excerpt = truncate_text_for_tokens(
+        message.trim(),
+        SB_TITLE_INPUT_BUDGET_TOKENS,
+        pack.truncation_marker,
+    );
+    format!(
+        "{}\n{}\n\n{}\n{}",
+        texts.user_request_heading, excerpt, texts.task_heading, texts.title_task
+    )
+}
+
+/// Cleans a model generated title; returns `None` when nothing usable remains.
+pub(super) fn normalize_generated_title(raw: &str) -> Option<String> {
+    let line = raw.lines().map(str::trim).find(|line| !line.is_empty())?;
+    let cleaned = line
+        .trim_matches(|ch: char| matches!(ch, '"' | '\'' | '`' | '#' | '*') || ch.is_whitespace())
+        .trim_end_matches(['.', ':', ';']);
+    if cleaned.is_empty() {
+        return None;
     }
+    Some(normalize_title_from_first_message(cleaned))
 }
 
 pub(super) fn normalize_title_from_first_message(raw: &str) -> String {
@@ -198,21 +370,10 @@ pub(super) fn normalize_title_from_first_message(raw: &str) -> String {
     compact
 }
 
-pub(super) fn normalize_pulse_action_id(raw: &str) -> Result<String> {
+/// Normalizes a Pulse action id and checks it against the built-in and workspace actions.
+pub(super) fn normalize_pulse_action_id(raw: &str, actions: &[PulseActionSpec]) -> Result<String> {
     let normalized = raw.trim().to_lowercase().replace('-', "_");
-    let allowed = [
-        "format",
-        "rewrite",
-        "condense",
-        "expand",
-        "change_tone",
-        "synthesize",
-        "outline",
-        "brief",
-        "extract_themes",
-        "identify_tensions",
-    ];
-    if allowed.contains(&normalized.as_str()) {
+    if actions.iter().any(|action| action.id == normalized) {
         Ok(normalized)
     } else {
         Err(AppError::InvalidOperation(
@@ -221,48 +382,24 @@ pub(super) fn normalize_pulse_action_id(raw: &str) -> Result<String> {
     }
 }
 
-pub(super) fn pulse_action_prompt(action_id: &str) -> &'static str {
-    match action_id {
-        "format" => {
-            "Reformate la matiere fournie en changeant uniquement sa forme (structure, longueur, presentation), sans jugement ni ajout de contenu. Reponds en markdown."
-        }
-        "rewrite" => {
-            "Reecris la matiere fournie pour la rendre plus claire et plus fluide sans changer le fond. Reponds en markdown."
-        }
-        "condense" => {
-            "Condense la matiere fournie en conservant les informations essentielles. Reponds en markdown."
-        }
-        "expand" => {
-            "Developpe la matiere fournie avec plus de structure et de details utiles, sans inventer de faits. Reponds en markdown."
-        }
-        "change_tone" => {
-            "Reformule la matiere fournie en adaptant le ton selon l'instruction utilisateur. Si aucun ton n'est precise, choisis un ton sobre et professionnel. Reponds en markdown."
-        }
-        "synthesize" => {
-            "Produis une synthese structuree de la matiere fournie. Fais ressortir les idees principales, les limites et les incertitudes. Reponds en markdown."
-        }
-        "outline" => {
-            "Transforme la matiere fournie en plan structure et exploitable. Reponds en markdown."
-        }
-        "brief" => {
-            "Transforme la matiere fournie en brief de travail clair: objectif, points saillants, tensions, prochaines questions si necessaire. Reponds en markdown."
-        }
-        "extract_themes" => {
-            "Fais emerger les themes dominants de la matiere fournie, avec une formulation concise et exploitable. Reponds en markdown."
-        }
-        "identify_tensions" => {
-            "Identifie les tensions, contradictions, angles morts ou arbitrages visibles dans la matiere fournie. Reponds en markdown."
-        }
-        _ => "Transforme la matiere fournie en sortie utile et structuree. Reponds en markdown.",
+/// Returns the closing response instruction matching a mode or Pulse output format.
+pub(super) fn response_format_instruction(
+    output_format: OutputFormat,
+    pack: &PromptPack,
+) -> &'static str {
+    match output_format {
+        OutputFormat::Markdown => pack.second_brain.markdown_instruction,
+        OutputFormat::Plain => pack.second_brain.plain_instruction,
+        OutputFormat::Json => pack.second_brain.json_instruction,
     }
 }
 
-fn pulse_source_label(kind: &PulseSourceKind) -> &'static str {
+fn pulse_source_label(kind: &PulseSourceKind, pack: &PromptPack) -> &'static str {
     match kind {
-        PulseSourceKind::EditorSelection => "Selection editeur",
-        PulseSourceKind::EditorNote => "Note editeur",
-        PulseSourceKind::SecondBrainContext => "Contexte Second Brain",
-        PulseSourceKind::CosmosFocus => "Focus Cosmos",
+        PulseSourceKind::EditorSelection => pack.pulse.editor_selection,
+        PulseSourceKind::EditorNote => pack.pulse.editor_note,
+        PulseSourceKind::SecondBrainContext => pack.pulse.second_brain_context,
+        PulseSourceKind::CosmosFocus => pack.pulse.cosmos_focus,
     }
 }
 
@@ -281,85 +418,6 @@ fn frontmatter_candidates() -> &'static [(&'static str, &'static str)] {
     ]
 }
 
-fn count_hints(text: &str, hints: &[&str]) -> usize {
-    let lowered = text.to_lowercase();
-    hints.iter().map(|hint| lowered.matches(hint).count()).sum()
-}
-
-fn detect_note_language(text: &str) -> &'static str {
-    let cleaned = text.trim();
-    if cleaned.is_empty() {
-        return "unknown";
-    }
-
-    let french_score = count_hints(
-        cleaned,
-        &[
-            " le ",
-            " la ",
-            " les ",
-            " des ",
-            " une ",
-            " un ",
-            " et ",
-            " pour ",
-            " avec ",
-            " dans ",
-            " que ",
-            " est ",
-            " être ",
-            "sur ",
-            " à ",
-            " du ",
-            "de ",
-            "note ",
-            "projet ",
-            "brouillon ",
-        ],
-    ) + cleaned
-        .chars()
-        .filter(|ch| {
-            matches!(
-                ch,
-                'à' | 'â' | 'ç' | 'é' | 'è' | 'ê' | 'ë' | 'î' | 'ï' | 'ô' | 'ù' | 'û' | 'ü'
-            )
-        })
-        .count();
-    let english_score = count_hints(
-        cleaned,
-        &[
-            " the ",
-            " and ",
-            " with ",
-            " for ",
-            " from ",
-            " note ",
-            " project ",
-            " draft ",
-            " should ",
-            " this ",
-            " that ",
-            " are ",
-            " is ",
-            " to ",
-            " of ",
-            " in ",
-        ],
-    );
-
-    if french_score == 0 && english_score == 0 {
-        return "unknown";
-    }
-    if (french_score as i64 - english_score as i64).abs() <= 2 {
-        return "mixed";
-    }
-    if french_score > english_score {
-        "fr"
-    } else {
-        "en"
-    }
-}
-
 fn language_display_name(code: &str) -> &'static str {
     match code {
         "fr" => "French",
@@ -369,15 +427,17 @@ fn language_display_name(code: &str) -> &'static str {
     }
 }
 
-fn summarize_existing_fields(fields: &[FrontmatterGenerationExistingField]) -> String {
+fn summarize_existing_fields(fields: &[FrontmatterGenerationExistingField]) -> Option<String> {
     if fields.is_empty() {
-        return "(aucune)".to_string();
+        return None;
     }
-    fields
-        .iter()
-        .map(|field| format!("- {} ({}) = {}", field.key, field.field_type, field.value))
-        .collect::<Vec<_>>()
-        .join("\n")
+    Some(
+        fields
+            .iter()
+            .map(|field| format!("- {} ({}) = {}", field.key, field.field_type, field.value))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    )
 }
 
 fn summarize_candidates() -> String {
@@ -413,13 +473,25 @@ fn build_frontmatter_generation_output_schema(target_key: Option<&str>) -> Strin
 }
 
 /// Builds the prompt used by frontmatter auto-generation and sparkle actions.
+///
+/// The pack follows the `prompt_language` setting when present, otherwise the detected
+/// note language; mixed or unknown notes use the default pack.
 pub(super) fn build_frontmatter_generation_prompt(
     input: &FrontmatterGenerationPromptInput,
 ) -> BuiltPrompt {
-    let body_excerpt =
-        truncate_text_for_tokens(&input.body_markdown, FRONTMATTER_BODY_BUDGET_TOKENS);
-    let raw_yaml_excerpt =
-        truncate_text_for_tokens(&input.raw_yaml, FRONTMATTER_RAW_YAML_BUDGET_TOKENS);
+    let existing_fields = summarize_existing_fields(&input.existing_fields);
+    // Excerpts are cut before detection, so they use the configured or default marker.
+    let excerpt_marker = select_frontmatter_pack(input.prompt_language, "").truncation_marker;
+    let body_excerpt = truncate_text_for_tokens(
+        &input.body_markdown,
+        FRONTMATTER_BODY_BUDGET_TOKENS,
+        excerpt_marker,
+    );
+    let raw_yaml_excerpt = truncate_text_for_tokens(
+        &input.raw_yaml,
+        FRONTMATTER_RAW_YAML_BUDGET_TOKENS,
+        excerpt_marker,
+    );
     let detected_language_hint = input
         .language_hint
         .as_deref()
@@ -431,56 +503,69 @@ pub(super) fn build_frontmatter_generation_prompt(
                 input.title,
                 body_excerpt,
                 raw_yaml_excerpt,
-                summarize_existing_fields(&input.existing_fields)
+                existing_fields.as_deref().unwrap_or_default()
             ))
         });
+    let pack = select_frontmatter_pack(input.prompt_language, detected_language_hint);
+    let texts = &pack.frontmatter;
 
     let mut prompt = String::new();
-    prompt.push_str("Tu generes des properties frontmatter pour Tomosona.\n");
-    prompt.push_str("Retourne uniquement du JSON valide, sans bloc de code, sans explication, sans texte autour.\n");
-    prompt.push_str(
-        "Les clefs doivent rester canoniques et stables. Ne traduis pas les clefs systeme.\n",
-    );
-    prompt.push_str("Adapte les valeurs textuelles a la langue dominante de la note.\n");
-    prompt.push_str("N'ecrase pas silencieusement une valeur non vide en mode auto.\n");
-    prompt.push_str("Pour un sparkle sur une property, ne renvoie que cette clef.\n\n");
+    for line in texts.instructions {
+        prompt.push_str(line);
+        prompt.push('\n');
+    }
+    prompt.push('\n');
     prompt.push_str(&format!(
-        "Mode: {}\nLangue detectee: {} ({})\n",
+        "Mode: {}\n{}: {} ({})\n",
         frontmatter_generation_mode_label(&input.mode),
+        texts.detected_language_label,
         detected_language_hint,
         language_display_name(detected_language_hint)
     ));
-    prompt.push_str(&format!("Chemin: {}\n", input.path));
-    prompt.push_str(&format!("Titre: {}\n\n", input.title.trim()));
+    prompt.push_str(&format!("{}: {}\n", texts.path_label, input.path));
+    prompt.push_str(&format!(
+        "{}: {}\n\n",
+        texts.title_label,
+        input.title.trim()
+    ));
 
     if !body_excerpt.trim().is_empty() {
-        prompt.push_str("Corps de la note:\n");
+        prompt.push_str(texts.body_heading);
+        prompt.push('\n');
         prompt.push_str(&body_excerpt);
         prompt.push_str("\n\n");
     }
 
     if !raw_yaml_excerpt.trim().is_empty() {
-        prompt.push_str("Frontmatter YAML actuel:\n");
+        prompt.push_str(texts.yaml_heading);
+        prompt.push('\n');
         prompt.push_str(&raw_yaml_excerpt);
         prompt.push_str("\n\n");
     }
 
-    prompt.push_str("Properties deja presentes:\n");
-    prompt.push_str(&summarize_existing_fields(&input.existing_fields));
-    prompt.push_str("\n\nProprietes canoniques candidates:\n");
+    prompt.push_str(texts.existing_heading);
+    prompt.push('\n');
+    prompt.push_str(existing_fields.as_deref().unwrap_or(texts.empty_list));
+    prompt.push_str("\n\n");
+    prompt.push_str(texts.candidates_heading);
+    prompt.push('\n');
     prompt.push_str(&summarize_candidates());
-    prompt.push_str("\n\nRegles:\n");
-    prompt.push_str("- En mode auto, propose seulement les properties les plus pertinentes.\n");
-    prompt.push_str("- Ne propose pas une clef deja remplie sauf si sa valeur est vide.\n");
-    prompt.push_str("- Si aucune property pertinente n'existe, retourne un tableau vide.\n");
+    prompt.push_str("\n\n");
+    prompt.push_str(texts.rules_heading);
+    prompt.push('\n');
+    for rule in texts.rules {
+        prompt.push_str(&format!("- {rule}\n"));
+    }
     if let Some(target_key) = input
         .target_key
         .as_deref()
         .filter(|value| !value.trim().is_empty())
     {
-        prompt.push_str(&format!("- Sparkle cible: {target_key}\n"));
+        prompt.push_str(&format!("- {}: {target_key}\n", texts.target_label));
     }
-    prompt.push_str("\nFormat JSON attendu:\n");
+    prompt.push('\n');
+    prompt.push_str(texts.schema_heading);
+    prompt.push('\n');
     prompt.push_str(&build_frontmatter_generation_output_schema(
         input.target_key.as_deref(),
     ));
@@ -493,25 +578,39 @@ pub(super) fn build_frontmatter_generation_prompt(
     }
 }
 
+/// Returns the frontmatter pack: the configured locale, else the detected note language,
+/// else French like the frontmatter prompts that predate packs.
+pub(super) fn select_frontmatter_pack(
+    preferred: Option<PromptLocale>,
+    detected_language_hint: &str,
+) -> &'static PromptPack {
+    prompt_pack(
+        preferred
+            .or_else(|| PromptLocale::from_language_hint(detected_language_hint))
+            .unwrap_or(PromptLocale::Fr),
+    )
+}
+
 /// Returns the system instruction used for frontmatter generation.
-pub(super) fn frontmatter_generation_system_prompt() -> &'static str {
-    "Tu es un générateur de properties frontmatter. Retourne uniquement un objet JSON valide, fidèle à la note, avec des clefs canoniques et des valeurs dans la langue dominante de la note."
+pub(super) fn frontmatter_generation_system_prompt(pack: &PromptPack) -> &'static str {
+    pack.frontmatter.system
 }
 
 /// Builds the Pulse prompt while keeping action-specific guidance and context budgeting explicit.
 pub(super) fn build_pulse_user_prompt(
     payload: &RunPulseTransformationPayload,
-    action_id: &str,
+    action: &PulseActionSpec,
     context_entries: &[ContextPromptEntry],
+    pack: &PromptPack,
 ) -> BuiltPrompt {
+    let texts = &pack.pulse;
     let mut prompt = String::new();
-    prompt.push_str("Pulse est un moteur de transformation redactionnelle.\n");
-    prompt.push_str("Travaille uniquement a partir de la matiere fournie. ");
-    prompt.push_str("Ne fais pas de retrieval implicite et ne presente pas le resultat comme une validation de verite.\n\n");
+    prompt.push_str(texts.intro);
+    prompt.push_str("\n\n");
     prompt.push_str(&format!(
         "Source: {}\nAction: {}\n",
-        pulse_source_label(&payload.source_kind),
-        action_id
+        pulse_source_label(&payload.source_kind, pack),
+        action.id
     ));
     if let Some(label) = payload
         .selection_label
@@ -519,7 +618,7 @@ pub(super) fn build_pulse_user_prompt(
         .map(str::trim)
         .filter(|value| !value.is_empty())
     {
-        prompt.push_str(&format!("Libelle: {label}\n"));
+        prompt.push_str(&format!("{}: {label}\n", texts.label_field));
     }
     if let Some(session_id) = payload
         .session_id
@@ -549,7 +648,9 @@ pub(super) fn build_pulse_user_prompt(
         .map(str::trim)
         .filter(|value| !value.is_empty())
     {
-        prompt.push_str("\nInstruction supplementaire:\n");
+        prompt.push('\n');
+        prompt.push_str(texts.instructions_heading);
+        prompt.push('\n');
         prompt.push_str(instructions);
         prompt.push('\n');
     }
@@ -559,7 +660,9 @@ pub(super) fn build_pulse_user_prompt(
         .map(str::trim)
         .filter(|value| !value.is_empty())
     {
-        prompt.push_str("\nMatiere source explicite:\n");
+        prompt.push('\n');
+        prompt.push_str(texts.source_text_heading);
+        prompt.push('\n');
         prompt.push_str(source_text);
         prompt.push('\n');
     }
@@ -567,7 +670,7 @@ pub(super) fn build_pulse_user_prompt(
     let mut included_context_paths = Vec::new();
     if !context_entries.is_empty() {
         let (context_section, paths) =
-            build_context_section("pulse", context_entries, SB_CONTEXT_BUDGET_TOKENS);
+            build_context_section("pulse", context_entries, SB_CONTEXT_BUDGET_TOKENS, pack);
         if !context_section.is_empty() {
             prompt.push('\n');
             prompt.push_str(&context_section);
@@ -576,27 +679,41 @@ pub(super) fn build_pulse_user_prompt(
         }
     }
 
-    prompt.push_str("\nTache:\n");
-    prompt.push_str(pulse_action_prompt(action_id));
-    prompt.push_str("\n\nExigences:\n");
-    prompt.push_str("- Reponds en markdown.\n");
-    prompt.push_str("- Signale les incertitudes lorsque la matiere est incomplete.\n");
-    prompt.push_str("- Reste fidele a la matiere fournie.\n");
+    prompt.push('\n');
+    prompt.push_str(pack.second_brain.task_heading);
+    prompt.push('\n');
+    prompt.push_str(&action.prompt_template);
+    prompt.push_str("\n\n");
+    prompt.push_str(texts.requirements_heading);
+    prompt.push('\n');
+    prompt.push_str(&format!(
+        "- {}\n",
+        response_format_instruction(action.output_format, pack)
+    ));
+    for requirement in texts.requirements {
+        prompt.push_str(&format!("- {requirement}\n"));
+    }
 
     BuiltPrompt {
         user_prompt: prompt,
         included_context_paths,
-        language_hint: String::new(),
+        language_hint: pack.locale.code().to_string(),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::second_brain::modes::{default_pulse_action_specs, PromptSource};
+
+    fn french() -> &'static PromptPack {
+        prompt_pack(PromptLocale::Fr)
+    }
 
     fn message(id: &str, role: &str, content: &str) -> MessageRow {
         MessageRow {
             id: id.to_string(),
+            parent_id: String::new(),
             role: role.to_string(),
             mode: "freestyle".to_string(),
             content_md: content.to_string(),
@@ -609,7 +726,7 @@ mod tests {
     #[test]
     fn truncates_text_with_marker_when_budget_is_small() {
         let long_text = "x".repeat(20_000);
-        let truncated = truncate_text_for_tokens(&long_text, 50);
+        let truncated = truncate_text_for_tokens(&long_text, 50, french().truncation_marker);
         assert!(truncated.contains("[CONTENU TRONQUE]"));
         assert!(estimate_tokens(&truncated) <= 70);
     }
@@ -619,7 +736,7 @@ mod tests {
         let history = (0..20)
             .map(|idx| message(&format!("m{idx}"), "user", &format!("msg-{idx}")))
             .collect::<Vec<_>>();
-        let section = build_history_section(&history, 10_000, 12);
+        let section = build_history_section(&history, 10_000, 12, french());
         assert!(!section.contains("msg-0"));
         assert!(section.contains("msg-19"));
         assert!(section.contains("msg-8"));
@@ -628,13 +745,128 @@ mod tests {
     #[test]
     fn user_prompt_without_context_is_valid() {
         let history = vec![message("m1", "assistant", "old answer")];
-        let built = build_user_prompt("s1", "nouvelle demande", &history, &[], None);
+        let built = build_user_prompt(
+            "s1",
+            "nouvelle demande",
+            &history,
+            &[],
+            None,
+            None,
+            OutputFormat::Markdown,
+            french(),
+        );
         assert!(built.user_prompt.contains("Historique recent"));
         assert!(built.user_prompt.contains("Demande utilisateur"));
         assert!(built.user_prompt.contains("Reponds en markdown."));
         assert!(built.included_context_paths.is_empty());
     }
 
+    #[test]
+    fn english_pack_localizes_headings_and_marker() {
+        let english = prompt_pack(PromptLocale::En);
+        let history = vec![message("m1", "assistant", "old answer")];
+        let contexts = vec![ContextPromptEntry {
+            path: "a.md".to_string(),
+            content: "a".repeat(8_000),
+        }];
+        let built = build_user_prompt(
+            "s1",
+            "new request",
+            &history,
+            &contexts,
+            None,
+            None,
+            OutputFormat::Plain,
+            english,
+        );
+        assert!(built.user_prompt.contains("Recent history:"));
+        assert!(built.user_prompt.contains("User request:"));
+        assert!(built.user_prompt.contains("[CONTENT TRUNCATED]"));
+        assert!(built
+            .user_prompt
+            .ends_with("Respond in plain text, without markdown."));
+        assert_eq!(built.language_hint, "en");
+    }
+
+    #[test]
+    fn overflow_len_matches_history_window() {
+        let history = (0..20)
+            .map(|idx| message(&format!("m{idx}"), "user", &format!("msg-{idx}")))
+            .collect::<Vec<_>>();
+        assert_eq!(history_overflow_len("question", &history), 8);
+        assert_eq!(history_overflow_len("question", &history[..5]), 0);
+    }
+
+    #[test]
+    fn user_prompt_injects_history_summary_before_recent_history() {
+        let history = vec![message("m1", "assistant", "old answer")];
+        let built = build_user_prompt(
+            "s1",
+            "question",
+            &history,
+            &[],
+            None,
+            Some("On a compare trois offres de prix."),
+            OutputFormat::Markdown,
+            french(),
+        );
+        let summary_at = built
+            .user_prompt
+            .find("Resume des echanges precedents")
+            .expect("summary section");
+        let history_at = built
+            .user_prompt
+            .find("Historique recent")
+            .expect("history");
+        assert!(summary_at < history_at);
+        assert!(built.user_prompt.contains("trois offres de prix"));
+    }
+
+    #[test]
+    fn history_summary_prompt_folds_previous_summary_and_new_turns() {
+        let dropped = vec![
+            message("m1", "user", "Quel prix pour l'offre pro ?"),
+            message("m2", "assistant", "49 euros par mois."),
+        ];
+        let (prompt, included) =
+            build_history_summary_prompt("Sujet: tarification.", &dropped, french());
+        assert_eq!(included, 2);
+        assert!(prompt.contains("Resume existant:\nSujet: tarification."));
+        assert!(prompt.contains("[user]\nQuel prix pour l'offre pro ?"));
+        assert!(prompt.contains("[assistant]\n49 euros par mois."));
+    }
+
+    #[test]
+    fn history_summary_prompt_stops_at_the_input_budget() {
+        let dropped = (0..10)
+            .map(|idx| {
+                let content = format!("tour-{idx} {}", "x".repeat(4_000));
+                message(&format!("m{idx}"), "user", &content)
+            })
+            .collect::<Vec<_>>();
+        let (prompt, included) = build_history_summary_prompt("", &dropped, french());
+        assert_eq!(included, 5);
+        assert!(prompt.contains("tour-4 "));
+        assert!(!prompt.contains("tour-5 "));
+        assert!(!prompt.contains(french().truncation_marker.trim()));
+    }
+
+    #[test]
+    fn session_title_prompt_and_generated_title_cleanup() {
+        let prompt = build_session_title_prompt("  Comment fixer le prix ?  ", french());
+        assert!(prompt.starts_with("Demande utilisateur:\nComment fixer le prix ?\n\nTache:"));
+
+        assert_eq!(
+            normalize_generated_title("\n\"Strategie de prix.\"\nExplication"),
+            Some("Strategie de prix".to_string())
+        );
+        assert_eq!(
+            normalize_generated_title("## **Pricing**"),
+            Some("Pricing".to_string())
+        );
+        assert_eq!(normalize_generated_title(" \"\" "), None);
+    }
+
     #[test]
     fn user_prompt_includes_only_context_that_fits_budget() {
         let history = vec![message("m1", "assistant", "ok")];
@@ -648,7 +880,16 @@ mod tests {
                 content: "b".repeat(8_000),
             },
         ];
-        let built = build_user_prompt("s1", "question", &history, &contexts, None);
+        let built = build_user_prompt(
+            "s1",
+            "question",
+            &history,
+            &contexts,
+            None,
+            None,
+            OutputFormat::Markdown,
+            french(),
+        );
         assert!(built.user_prompt.contains("--- SOURCE: a.md ---"));
         assert!(built.user_prompt.contains("[CONTENU TRONQUE]"));
         assert!(!built.included_context_paths.is_empty());
@@ -656,13 +897,53 @@ mod tests {
 
     #[test]
     fn normalizes_supported_pulse_actions() {
-        assert_eq!(normalize_pulse_action_id("format").unwrap(), "format");
-        assert_eq!(normalize_pulse_action_id("rewrite").unwrap(), "rewrite");
+        let actions = default_pulse_action_specs(french());
         assert_eq!(
-            normalize_pulse_action_id("identify-tensions").unwrap(),
+            normalize_pulse_action_id("format", &actions).unwrap(),
+            "format"
+        );
+        assert_eq!(
+            normalize_pulse_action_id("rewrite", &actions).unwrap(),
+            "rewrite"
+        );
+        assert_eq!(
+            normalize_pulse_action_id("identify-tensions", &actions).unwrap(),
             "identify_tensions"
         );
-        assert!(normalize_pulse_action_id("freestyle").is_err());
+        assert!(normalize_pulse_action_id("freestyle", &actions).is_err());
+    }
+
+    #[test]
+    fn accepts_workspace_pulse_actions_and_their_output_format() {
+        let mut actions = default_pulse_action_specs(french());
+        actions.push(PulseActionSpec {
+            id: "release_notes".to_string(),
+            label: "Release notes".to_string(),
+            prompt_template: "Ecris des notes de version.".to_string(),
+            temperature: Some(0.4),
+            output_format: OutputFormat::Plain,
+            source: PromptSource::Workspace,
+            source_path: Some("_prompts/release-notes.md".to_string()),
+        });
+        assert_eq!(
+            normalize_pulse_action_id("release-notes", &actions).unwrap(),
+            "release_notes"
+        );
+        let payload = RunPulseTransformationPayload {
+            request_id: None,
+            source_kind: PulseSourceKind::EditorNote,
+            action_id: "release_notes".to_string(),
+            instructions: None,
+            context_paths: Vec::new(),
+            source_text: Some("v1.2".to_string()),
+            selection_label: None,
+            session_id: None,
+            cosmos_selected_node_id: None,
+            cosmos_neighbor_paths: Vec::new(),
+        };
+        let built = build_pulse_user_prompt(&payload, &actions[actions.len() - 1], &[], french());
+        assert!(built.user_prompt.contains("Ecris des notes de version."));
+        assert!(built.user_prompt.contains("sans markdown"));
     }
 
     #[test]
@@ -680,7 +961,12 @@ mod tests {
             cosmos_neighbor_paths: Vec::new(),
         };
 
-        let built = build_pulse_user_prompt(&payload, "rewrite", &[]);
+        let actions = default_pulse_action_specs(french());
+        let rewrite = actions
+            .iter()
+            .find(|action| action.id == "rewrite")
+            .expect("rewrite action");
+        let built = build_pulse_user_prompt(&payload, rewrite, &[], french());
         assert!(built.user_prompt.contains("Pulse est un moteur"));
         assert!(built.user_prompt.contains("Original paragraph"));
         assert!(built.user_prompt.contains("Use a diplomatic tone."));
@@ -690,7 +976,12 @@ mod tests {
 
     #[test]
     fn format_prompt_emphasizes_shape_without_judgment() {
-        assert!(pulse_action_prompt("format").contains("sans ajouter de jugement"));
+        let actions = default_pulse_action_specs(french());
+        let format = actions
+            .iter()
+            .find(|action| action.id == "format")
+            .expect("format action");
+        assert!(format.prompt_template.contains("sans ajouter de jugement"));
     }
 
     #[test]
@@ -708,6 +999,7 @@ mod tests {
             mode: FrontmatterGenerationMode::Auto,
             target_key: None,
             language_hint: Some("fr".to_string()),
+            prompt_language: None,
         };
 
         let built = build_frontmatter_generation_prompt(&input);
@@ -721,4 +1013,32 @@ mod tests {
             .user_prompt
             .contains("Proprietes canoniques candidates"));
     }
+
+    #[test]
+    fn frontmatter_prompt_follows_setting_over_detected_language() {
+        let input = FrontmatterGenerationPromptInput {
+            path: "notes/a.md".to_string(),
+            title: "Meeting notes".to_string(),
+            body_markdown: "This is the summary of the meeting and the next steps.".to_string(),
+            raw_yaml: String::new(),
+            existing_fields: Vec::new(),
+            mode: FrontmatterGenerationMode::Auto,
+            target_key: None,
+            language_hint: None,
+            prompt_language: None,
+        };
+        let built = build_frontmatter_generation_prompt(&input);
+        assert_eq!(built.language_hint, "en");
+        assert!(built.user_prompt.contains("Existing properties:\n(none)"));
+
+        let forced = FrontmatterGenerationPromptInput {
+            prompt_language: Some(PromptLocale::Fr),
+            ..input
+        };
+        let built = build_frontmatter_generation_prompt(&forced);
+        assert_eq!(built.language_hint, "en");
+        assert!(built
+            .user_prompt
+            .contains("Properties deja presentes:\n(aucune)"));
+    }
 }
@@ -0,0 +1,511 @@
+//! Workspace prompt library for user-defined Second Brain modes and Pulse actions.
+//!
+//! Teams drop Markdown files into `_prompts/` at the workspace root. The YAML
+//! frontmatter declares the prompt metadata and the body is the prompt template:
+//!
+//! ```text
+//! ---
+//! id: decision_memo
+//! label: Decision memo
+//! kind: mode            # mode | pulse_action
+//! temperature: 0.3      # optional, between 0 and 1
+//! output: markdown      # markdown | plain | json
+//! ---
+//! Write a one-page decision memo from the provided material...
+//! ```
+//!
+//! Workspace prompts are plain prompt templates: `agent_id` and `skill_ref` are
+//! rejected because nothing dispatches agent or skill modes declared in a file.
+//!
+//! Workspace entries override built-in entries with the same id. A mode override
+//! keeps the agent or skill wiring of the built-in mode it replaces.
+//! Invalid files are skipped and reported as issues instead of failing the whole
+//! catalog.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+
+use super::modes::{
+    default_mode_specs, default_pulse_action_specs, ModeKind, ModeSpec, OutputFormat, PromptSource,
+    PulseActionSpec,
+};
+use super::prompt_packs::PromptPack;
+use crate::markdown_index::unquote_yaml_scalar;
+use crate::{active_workspace_root, normalize_workspace_relative_path};
+
+const PROMPTS_DIR_NAME: &str = "_prompts";
+
+/// One prompt file that could not be loaded, with a user-facing reason.
+#[derive(Debug, Clone, Serialize)]
+pub struct PromptFileIssue {
+    pub path: String,
+    pub message: String,
+}
+
+/// Modes and Pulse actions available for the active workspace.
+#[derive(Debug, Clone, Serialize)]
+pub struct PromptCatalog {
+    pub modes: Vec<ModeSpec>,
+    pub pulse_actions: Vec<PulseActionSpec>,
+    pub issues: Vec<PromptFileIssue>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum WorkspacePromptKind {
+    Mode,
+    PulseAction,
+}
+
+#[derive(Debug, Clone)]
+struct WorkspacePrompt {
+    id: String,
+    label: String,
+    kind: WorkspacePromptKind,
+    temperature: Option<f64>,
+    output_format: OutputFormat,
+    prompt_template: String,
+    source_path: String,
+}
+
+/// Loads built-in prompts of `pack` merged with the `_prompts/` folder of the active workspace.
+///
+/// Without an active workspace or prompt folder the built-in catalog is returned.
+pub(super) fn load_prompt_catalog(pack: &PromptPack) -> PromptCatalog {
+    let Ok(root) = active_workspace_root() else {
+        return merge_catalog(pack, Vec::new(), Vec::new());
+    };
+    let (prompts, issues) = load_workspace_prompts(&root);
+    merge_catalog(pack, prompts, issues)
+}
+
+fn load_workspace_prompts(root: &Path) -> (Vec<WorkspacePrompt>, Vec<PromptFileIssue>) {
+    let dir = root.join(PROMPTS_DIR_NAME);
+    let mut files = Vec::new();
+    if dir.is_dir() {
+        collect_prompt_files(&dir, &mut files);
+    }
+    files.sort();
+
+    let mut prompts: Vec<WorkspacePrompt> = Vec::new();
+    let mut issues = Vec::new();
+    for path in files {
+        let relative = normalize_workspace_relative_path(root, &path)
+            .unwrap_or_else(|_| path.to_string_lossy().to_string());
+        let parsed = fs::read_to_string(&path)
+            .map_err(|_| "File could not be read.".to_string())
+            .and_then(|content| parse_prompt_file(&relative, &content));
+        match parsed {
+            Ok(prompt) => {
+                let duplicate = prompts
+                    .iter()
+                    .any(|item| item.kind == prompt.kind && item.id == prompt.id);
+                if duplicate {
+                    issues.push(PromptFileIssue {
+                        path: relative,
+                        message: format!("Duplicate id `{}`.", prompt.id),
+                    });
+                } else {
+                    prompts.push(prompt);
+                }
+            }
+            Err(message) => issues.push(PromptFileIssue {
+                path: relative,
+                message,
+            }),
+        }
+    }
+    (prompts, issues)
+}
+
+fn collect_prompt_files(dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            collect_prompt_files(&path, out);
+            continue;
+        }
+        let is_markdown = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown"))
+            .unwrap_or(false);
+        if is_markdown {
+            out.push(path);
+        }
+    }
+}
+
+fn split_frontmatter(content: &str) -> Option<(Vec<(String, String)>, String)> {
+    let normalized = content.replace("\r\n", "\n");
+    let rest = normalized.strip_prefix("---\n")?;
+    let end = rest.find("\n---")?;
+    let yaml = &rest[..end];
+    let body = rest[end + 4..].trim().to_string();
+
+    let mut fields = Vec::new();
+    for line in yaml.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = trimmed.split_once(':') else {
+            continue;
+        };
+        let value = value.split(" #").next().unwrap_or("");
+        fields.push((key.trim().to_lowercase(), unquote_yaml_scalar(value)));
+    }
+    Some((fields, body))
+}
+
+fn frontmatter_field<'a>(fields: &'a [(String, String)], key: &str) -> Option<&'a str> {
+    fields
+        .iter()
+        .find(|(name, _)| name == key)
+        .map(|(_, value)| value.as_str())
+}
+
+fn normalize_prompt_id(raw: &str) -> Result<String, String> {
+    let id = raw.trim().to_lowercase().replace('-', "_");
+    if id.is_empty() {
+        return Err("Frontmatter `id` is required.".to_string());
+    }
+    if !id
+        .chars()
+        .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '.')
+    {
+        return Err(
+            "Frontmatter `id` may only contain letters, digits, `_`, `-` or `.`.".to_string(),
+        );
+    }
+    Ok(id)
+}
+
+fn parse_output_format(raw: &str) -> Result<OutputFormat, String> {
+    match raw.trim().to_lowercase().as_str() {
+        "" | "markdown" | "md" => Ok(OutputFormat::Markdown),
+        "plain" | "text" => Ok(OutputFormat::Plain),
+        "json" => Ok(OutputFormat::Json),
+        other => Err(format!(
+            "Unsupported output format `{other}`. Use markdown, plain or json."
+        )),
+    }
+}
+
+fn parse_prompt_file(relative_path: &str, content: &str) -> Result<WorkspacePrompt, String> {
+    let (fields, body) = split_frontmatter(content)
+        .ok_or_else(|| "Prompt file must start with a YAML frontmatter block.".to_string())?;
+    let field = |key: &str| frontmatter_field(&fields, key);
+
+    let id = normalize_prompt_id(field("id").unwrap_or(""))?;
+    let kind = match field("kind")
+        .unwrap_or("mode")
+        .trim()
+        .to_lowercase()
+        .as_str()
+    {
+        "" | "mode" => WorkspacePromptKind::Mode,
+        "pulse" | "pulse_action" | "pulse-action" => WorkspacePromptKind::PulseAction,
+        other => {
+            return Err(format!(
+                "Unsupported kind `{other}`. Use mode or pulse_action."
+            ))
+        }
+    };
+    let label = field("label")
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToOwned::to_owned)
+        .unwrap_or_else(|| id.clone());
+    let temperature = match field("temperature")
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+    {
+        Some(raw) => {
+            let value = raw
+                .parse::<f64>()
+                .map_err(|_| "Frontmatter `temperature` must be a number.".to_string())?;
+            if !(0.0..=1.0).contains(&value) {
+                return Err("Frontmatter `temperature` must be between 0 and 1.".to_string());
+            }
+            Some(value)
+        }
+        None => None,
+    };
+    let output_format = parse_output_format(
+        field("output")
+            .or_else(|| field("output_format"))
+            .unwrap_or(""),
+    )?;
+    if ["agent_id", "skill_ref"]
+        .into_iter()
+        .any(|key| field(key).is_some_and(|value| !value.trim().is_empty()))
+    {
+        return Err("Workspace prompts cannot set `agent_id` or `skill_ref`.".to_string());
+    }
+    if body.is_empty() {
+        return Err("Prompt body must not be empty.".to_string());
+    }
+
+    Ok(WorkspacePrompt {
+        id,
+        label,
+        kind,
+        temperature,
+        output_format,
+        prompt_template: body,
+        source_path: relative_path.to_string(),
+    })
+}
+
+fn merge_catalog(
+    pack: &PromptPack,
+    prompts: Vec<WorkspacePrompt>,
+    issues: Vec<PromptFileIssue>,
+) -> PromptCatalog {
+    let mut modes = default_mode_specs(pack);
+    let mut pulse_actions = default_pulse_action_specs(pack);
+
+    for prompt in prompts {
+        match prompt.kind {
+            WorkspacePromptKind::Mode => {
+                let existing = modes.iter().position(|item| item.id == prompt.id);
+                let (kind, agent_id, skill_ref) = match existing {
+                    Some(index) => (
+                        modes[index].kind.clone(),
+                        modes[index].agent_id.clone(),
+                        modes[index].skill_ref.clone(),
+                    ),
+                    None => (ModeKind::PromptTemplate, None, None),
+                };
+                let spec = ModeSpec {
+                    id: prompt.id,
+                    label: prompt.label,
+                    kind,
+                    prompt_template: prompt.prompt_template,
+                    agent_id,
+                    skill_ref,
+                    temperature: prompt.temperature,
+                    output_format: prompt.output_format,
+                    source: PromptSource::Workspace,
+                    source_path: Some(prompt.source_path),
+                };
+                match existing {
+                    Some(index) => modes[index] = spec,
+                    None => modes.push(spec),
+                }
+            }
+            WorkspacePromptKind::PulseAction => {
+                let spec = PulseActionSpec {
+                    id: prompt.id,
+                    label: prompt.label,
+                    prompt_template: prompt.prompt_template,
+                    temperature: prompt.temperature,
+                    output_format: prompt.output_format,
+                    source: PromptSource::Workspace,
+                    source_path: Some(prompt.source_path),
+                };
+                match pulse_actions.iter_mut().find(|item| item.id == spec.id) {
+                    Some(existing) => *existing = spec,
+                    None => pulse_actions.push(spec),
+                }
+            }
+        }
+    }
+
+    PromptCatalog {
+        modes,
+        pulse_actions,
+        issues,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::second_brain::prompt_packs::{prompt_pack, PromptLocale};
+
+    fn make_temp_dir(label: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "tomosona-prompt-library-{label}-{}-{}",
+            std::process::id(),
+            crate::now_ms()
+        ));
+        fs::create_dir_all(&path).expect("temp dir");
+        path
+    }
+
+    #[test]
+    fn parses_prompt_file_frontmatter_and_body() {
+        let prompt = parse_prompt_file(
+            "_prompts/memo.md",
+            "---\nid: Decision-Memo\nlabel: \"Decision memo\"\nkind: mode\ntemperature: 0.3\noutput: plain\n---\n\nWrite a decision memo.\n",
+        )
+        .expect("valid prompt");
+        assert_eq!(prompt.id, "decision_memo");
+        assert_eq!(prompt.label, "Decision memo");
+        assert_eq!(prompt.kind, WorkspacePromptKind::Mode);
+        assert_eq!(prompt.temperature, Some(0.3));
+        assert_eq!(prompt.output_format, OutputFormat::Plain);
+        assert_eq!(prompt.prompt_template, "Write a decision memo.");
+    }
+
+    #[test]
+    fn rejects_invalid_prompt_files() {
+        assert!(parse_prompt_file("a.md", "no frontmatter").is_err());
+        assert!(parse_prompt_file("a.md", "---\nlabel: x\n---\nbody").is_err());
+        assert!(parse_prompt_file("a.md", "---\nid: x\ntemperature: 3\n---\nbody").is_err());
+        assert!(parse_prompt_file("a.md", "---\nid: x\noutput: html\n---\nbody").is_err());
+        assert!(parse_prompt_file("a.md", "---\nid: x\nkind: agent\n---\nbody").is_err());
+        assert!(parse_prompt_file("a.md", "---\nid: x\n---\n   ").is_err());
+        assert!(parse_prompt_file("a.md", "---\nid: x\nagent_id: a.v1\n---\nbody").is_err());
+        assert!(parse_prompt_file("a.md", "---\nid: x\nskill_ref: b\n---\nbody").is_err());
+        assert!(parse_prompt_file(
+            "a.md",
+            "---\nid: x\nkind: pulse_action\nskill_ref: b\n---\nbody"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn reads_crlf_frontmatter_and_inline_comments() {
+        let prompt = parse_prompt_file(
+            "_prompts/release.md",
+            "---\r\n# release helper\r\nid: release  # shown in Pulse\r\nkind: pulse   # action\r\noutput: text\r\n---\r\nList the changes.\r\n",
+        )
+        .expect("valid prompt");
+        assert_eq!(prompt.id, "release");
+        assert_eq!(prompt.label, "release");
+        assert_eq!(prompt.kind, WorkspacePromptKind::PulseAction);
+        assert_eq!(prompt.temperature, None);
+        assert_eq!(prompt.output_format, OutputFormat::Plain);
+        assert_eq!(prompt.prompt_template, "List the changes.");
+        assert_eq!(prompt.source_path, "_prompts/release.md");
+    }
+
+    #[test]
+    fn workspace_modes_are_appended_after_the_builtins_of_the_pack() {
+        let prompt = parse_prompt_file(
+            "_prompts/memo.md",
+            "---\nid: memo\ntemperature: 0.2\n---\nWrite a memo.",
+        )
+        .expect("valid prompt");
+        let english = prompt_pack(PromptLocale::En);
+        let catalog = merge_catalog(english, vec![prompt], Vec::new());
+
+        assert_eq!(catalog.modes.len(), english.modes.len() + 1);
+        let memo = catalog.modes.last().expect("memo mode");
+        assert_eq!(memo.id, "memo");
+        assert_eq!(memo.kind, ModeKind::PromptTemplate);
+        assert_eq!(memo.temperature, Some(0.2));
+        assert_eq!(memo.source_path.as_deref(), Some("_prompts/memo.md"));
+        let format = catalog
+            .pulse_actions
+            .iter()
+            .find(|item| item.id == "format")
+            .expect("format action");
+        assert_eq!(format.source, PromptSource::Builtin);
+        assert!(format.prompt_template.contains("without judgment"));
+        assert!(catalog.issues.is_empty());
+    }
+
+    #[test]
+    fn workspace_mode_overrides_keep_builtin_agent_and_skill_wiring() {
+        let prompts = [
+            "---\nid: diagnostic\n---\nTeam diagnostic.",
+            "---\nid: extraction_concepts\n---\nTeam concepts.",
+            "---\nid: review\n---\nReview the material.",
+        ]
+        .iter()
+        .map(|content| parse_prompt_file("_prompts/x.md", content).expect("valid prompt"))
+        .collect();
+        let catalog = merge_catalog(prompt_pack(PromptLocale::Fr), prompts, Vec::new());
+        let mode = |id: &str| {
+            catalog
+                .modes
+                .iter()
+                .find(|item| item.id == id)
+                .cloned()
+                .expect("mode")
+        };
+
+        let diagnostic = mode("diagnostic");
+        assert_eq!(diagnostic.kind, ModeKind::AgentBuiltin);
+        assert_eq!(
+            diagnostic.agent_id.as_deref(),
+            Some("builtin.diagnostic.v1")
+        );
+        assert_eq!(diagnostic.prompt_template, "Team diagnostic.");
+        let concepts = mode("extraction_concepts");
+        assert_eq!(concepts.kind, ModeKind::SkillRef);
+        assert_eq!(
+            concepts.skill_ref.as_deref(),
+            Some("second_brain.extract_concepts")
+        );
+        let review = mode("review");
+        assert_eq!(review.kind, ModeKind::PromptTemplate);
+        assert_eq!(review.agent_id, None);
+        assert_eq!(review.skill_ref, None);
+    }
+
+    #[test]
+    fn workspace_prompts_override_builtins_and_report_duplicates() {
+        let root = make_temp_dir("merge");
+        let dir = root.join(PROMPTS_DIR_NAME);
+        fs::create_dir_all(dir.join("pulse")).expect("prompt dir");
+        fs::write(
+            dir.join("a-synthese.md"),
+            "---\nid: synthese\nlabel: Team synthesis\n---\nTeam synthesis prompt.",
+        )
+        .expect("write mode");
+        fs::write(
+            dir.join("b-synthese-copy.md"),
+            "---\nid: synthese\n---\nDuplicate.",
+        )
+        .expect("write duplicate");
+        fs::write(
+            dir.join("pulse").join("release.md"),
+            "---\nid: release_notes\nkind: pulse_action\noutput: json\n---\nRelease notes.",
+        )
+        .expect("write pulse");
+        fs::write(dir.join("broken.md"), "no frontmatter").expect("write broken");
+
+        let (prompts, issues) = load_workspace_prompts(&root);
+        let catalog = merge_catalog(prompt_pack(PromptLocale::Fr), prompts, issues);
+
+        let synthese = catalog
+            .modes
+            .iter()
+            .find(|item| item.id == "synthese")
+            .expect("synthese mode");
+        assert_eq!(synthese.label, "Team synthesis");
+        assert_eq!(synthese.source, PromptSource::Workspace);
+        assert_eq!(
+            catalog
+                .modes
+                .iter()
+                .filter(|item| item.id == "synthese")
+                .count(),
+            1
+        );
+        let release = catalog
+            .pulse_actions
+            .iter()
+            .find(|item| item.id == "release_notes")
+            .expect("release action");
+        assert_eq!(release.output_format, OutputFormat::Json);
+        assert_eq!(catalog.issues.len(), 2);
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,764 @@
+//! Locale prompt packs for Second Brain, Pulse, frontmatter generation and Alters.
+//!
+//! Every built-in instruction sent to a model lives here, grouped per locale, so the
+//! prompt builders only decide ordering and budgets. The pack is either forced by the
+//! `prompt_language` setting or picked from the dominant language of the material.
+
+use serde::{Deserialize, Serialize};
+
+/// Characters sampled per text when detecting the prompt language.
+const DETECTION_SAMPLE_CHARS: usize = 4_000;
+
+/// Language of a built-in prompt pack.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptLocale {
+    #[default]
+    Fr,
+    En,
+}
+
+impl PromptLocale {
+    /// Returns the short language code used in prompts and `language_hint` values.
+    pub fn code(self) -> &'static str {
+        match self {
+            PromptLocale::Fr => "fr",
+            PromptLocale::En => "en",
+        }
+    }
+
+    /// Maps a detected or user-provided language hint to a pack locale.
+    pub fn from_language_hint(hint: &str) -> Option<Self> {
+        match hint.trim().to_lowercase().as_str() {
+            "fr" => Some(PromptLocale::Fr),
+            "en" => Some(PromptLocale::En),
+            _ => None,
+        }
+    }
+}
+
+/// Built-in mode or Pulse action text for one locale.
+#[derive(Debug)]
+pub struct BuiltinPromptText {
+    pub id: &'static str,
+    pub label: &'static str,
+    pub prompt: &'static str,
+}
+
+#[derive(Debug)]
+pub struct SecondBrainPromptText {
+    pub active_alter_heading: &'static str,
+    pub context_heading: &'static str,
+    pub history_summary_heading: &'static str,
+    pub recent_history_heading: &'static str,
+    pub user_request_heading: &'static str,
+    pub markdown_instruction: &'static str,
+    pub plain_instruction: &'static str,
+    pub json_instruction: &'static str,
+    pub summary_system: &'static str,
+    pub summary_existing_heading: &'static str,
+    pub summary_new_turns_heading: &'static str,
+    pub task_heading: &'static str,
+    pub summary_task: &'static str,
+    pub title_system: &'static str,
+    pub title_task: &'static str,
+}
+
+#[derive(Debug)]
+pub struct PulsePromptText {
+    pub intro: &'static str,
+    pub label_field: &'static str,
+    pub instructions_heading: &'static str,
+    pub source_text_heading: &'static str,
+    pub requirements_heading: &'static str,
+    pub requirements: &'static [&'static str],
+    pub editor_selection: &'static str,
+    pub editor_note: &'static str,
+    pub second_brain_context: &'static str,
+    pub cosmos_focus: &'static str,
+}
+
+#[derive(Debug)]
+pub struct FrontmatterPromptText {
+    pub system: &'static str,
+    pub instructions: &'static [&'static str],
+    pub detected_language_label: &'static str,
+    pub path_label: &'static str,
+    pub title_label: &'static str,
+    pub body_heading: &'static str,
+    pub yaml_heading: &'static str,
+    pub existing_heading: &'static str,
+    pub candidates_heading: &'static str,
+    pub rules_heading: &'static str,
+    pub rules: &'static [&'static str],
+    pub target_label: &'static str,
+    pub schema_heading: &'static str,
+    pub empty_list: &'static str,
+}
+
+#[derive(Debug)]
+pub struct AlterPromptText {
+    pub contract_heading: &'static str,
+    pub identity_label: &'static str,
+    pub description_label: &'static str,
+    pub mission_label: &'static str,
+    pub inspirations_label: &'static str,
+    /// Introduces the excerpt of a note inspiration; followed by the note path.
+    pub note_excerpt_label: &'static str,
+    pub principles_label: &'static str,
+    pub reflexes_label: &'static str,
+    pub values_label: &'static str,
+    pub critiques_label: &'static str,
+    pub blind_spots_label: &'static str,
+    pub style_label: &'static str,
+    pub hints_label: &'static str,
+    pub cite_hypotheses: &'static str,
+    pub signal_biases: &'static str,
+    pub closing: &'static str,
+    pub draft_intro: &'static str,
+    pub draft_shape_heading: &'static str,
+    pub draft_constraints: &'static str,
+    /// Quick start user prompt; `{brief}` is replaced by the user brief.
+    pub draft_user_template: &'static str,
+}
+
+/// Exploration texts. Templates use `{name}` placeholders filled by [`fill_template`].
+#[derive(Debug)]
+pub struct ExplorationPromptText {
+    pub context_heading: &'static str,
+    pub challenge_guidance: &'static str,
+    pub explore_guidance: &'static str,
+    pub decide_guidance: &'static str,
+    pub refine_guidance: &'static str,
+    pub summary_format: &'static str,
+    pub tension_map_format: &'static str,
+    pub decision_brief_format: &'static str,
+    pub refined_proposal_format: &'static str,
+    pub round1_template: &'static str,
+    pub round2_template: &'static str,
+    pub round3_template: &'static str,
+    pub synthesis_template: &'static str,
+    pub round1_digest_system: &'static str,
+    pub round2_digest_system: &'static str,
+    pub synthesis_system: &'static str,
+    pub round_heading_template: &'static str,
+}
+
+/// Complete set of built-in prompts for one locale.
+#[derive(Debug)]
+pub struct PromptPack {
+    pub locale: PromptLocale,
+    pub truncation_marker: &'static str,
+    pub modes: &'static [BuiltinPromptText],
+    pub pulse_actions: &'static [BuiltinPromptText],
+    pub second_brain: SecondBrainPromptText,
+    pub pulse: PulsePromptText,
+    pub frontmatter: FrontmatterPromptText,
+    pub alter: AlterPromptText,
+    pub exploration: ExplorationPromptText,
+}
+
+impl PromptPack {
+    /// Returns the built-in mode text for `id`, if this pack defines it.
+    pub fn mode(&self, id: &str) -> Option<&'static BuiltinPromptText> {
+        self.modes.iter().find(|item| item.id == id)
+    }
+}
+
+static FRENCH_PACK: PromptPack = PromptPack {
+    locale: PromptLocale::Fr,
+    truncation_marker: "\n[CONTENU TRONQUE]\n",
+    modes: &[
+        BuiltinPromptText {
+            id: "freestyle",
+            label: "Freestyle",
+            prompt: "Tu es un assistant polyvalent. Execute la demande utilisateur avec precision. Utilise le contexte fourni s'il existe et signale clairement les incertitudes lorsqu'il manque des informations. Reponds en markdown. Ne cite des sources que si l'utilisateur le demande explicitement.",
+        },
+        BuiltinPromptText {
+            id: "synthese",
+            label: "Synthese",
+            prompt: "Fournis une synthese structuree, concise, avec limites et points d'incertitude. Cite les sources de contexte utilisees.",
+        },
+        BuiltinPromptText {
+            id: "plan_action",
+            label: "Plan d'action",
+            prompt: "Construis un plan d'action concret, ordonne et verifiable. Cite les sources de contexte pour chaque decision.",
+        },
+        BuiltinPromptText {
+            id: "diagnostic",
+            label: "Diagnostic",
+            prompt: "Etablis un diagnostic: problemes, hypotheses, evidences, tests de validation. Cite les sources de contexte.",
+        },
+        BuiltinPromptText {
+            id: "fusion_notes",
+            label: "Fusion de notes",
+            prompt: "Fusionne les notes en eliminant doublons et contradictions. Preserve les faits traces aux sources.",
+        },
+        BuiltinPromptText {
+            id: "extraction_concepts",
+            label: "Extraction de concepts",
+            prompt: "Extrait les concepts, definitons, relations et ambiguities restantes. Cite les sources.",
+        },
+    ],
+    pulse_actions: &[
+        BuiltinPromptText {
+            id: "format",
+            label: "Format",
+            prompt: "Reformate la matiere fournie en changeant uniquement sa forme (structure, longueur, presentation), sans ajouter de jugement ni de contenu. Reponds en markdown.",
+        },
+        BuiltinPromptText {
+            id: "rewrite",
+            label: "Rewrite",
+            prompt: "Reecris la matiere fournie pour la rendre plus claire et plus fluide sans changer le fond. Reponds en markdown.",
+        },
+        BuiltinPromptText {
+            id: "condense",
+            label: "Condense",
+            prompt: "Condense la matiere fournie en conservant les informations essentielles. Reponds en markdown.",
+        },
+        BuiltinPromptText {
+            id: "expand",
+            label: "Expand",
+            prompt: "Developpe la matiere fournie avec plus de structure et de details utiles, sans inventer de faits. Reponds en markdown.",
+        },
+        BuiltinPromptText {
+            id: "change_tone",
+            label: "Change tone",
+            prompt: "Reformule la matiere fournie en adaptant le ton selon l'instruction utilisateur. Si aucun ton n'est precise, choisis un ton sobre et professionnel. Reponds en markdown.",
+        },
+        BuiltinPromptText {
+            id: "synthesize",
+            label: "Synthesize",
+            prompt: "Produis une synthese structuree de la matiere fournie. Fais ressortir les idees principales, les limites et les incertitudes. Reponds en markdown.",
+        },
+        BuiltinPromptText {
+            id: "outline",
+            label: "Outline",
+            prompt: "Transforme la matiere fournie en plan structure et exploitable. Reponds en markdown.",
+        },
+        BuiltinPromptText {
+            id: "brief",
+            label: "Brief",
+            prompt: "Transforme la matiere fournie en brief de travail clair: objectif, points saillants, tensions, prochaines questions si necessaire. Reponds en markdown.",
+        },
+        BuiltinPromptText {
+            id: "extract_themes",
+            label: "Extract themes",
+            prompt: "Fais emerger les themes dominants de la matiere fournie, avec une formulation concise et exploitable. Reponds en markdown.",
+        },
+        BuiltinPromptText {
+            id: "identify_tensions",
+            label: "Identify tensions",
+            prompt: "Identifie les tensions, contradictions, angles morts ou arbitrages visibles dans la matiere fournie. Reponds en markdown.",
+        },
+    ],
+    second_brain: SecondBrainPromptText {
+        active_alter_heading: "Alter actif:",
+        context_heading: "Contexte fourni:",
+        history_summary_heading: "Resume des echanges precedents:",
+        recent_history_heading: "Historique recent:",
+        user_request_heading: "Demande utilisateur:",
+        markdown_instruction: "Reponds en markdown.",
+        plain_instruction: "Reponds en texte brut, sans markdown.",
+        json_instruction: "Reponds uniquement avec un objet JSON valide, sans texte autour.",
+        summary_system: "Tu condenses l'historique d'une conversation de recherche. Produis un resume factuel et dense en markdown: sujets abordes, decisions, conclusions, questions ouvertes et references aux notes citees. N'invente rien et ne reponds pas aux demandes de la conversation.",
+        summary_existing_heading: "Resume existant:",
+        summary_new_turns_heading: "Nouveaux echanges a integrer:",
+        task_heading: "Tache:",
+        summary_task: "Reecris un resume unique qui integre le resume existant et les nouveaux echanges. Conserve les informations encore utiles, retire les redites et reste sous 400 mots.",
+        title_system: "Tu donnes un titre court a une conversation de recherche a partir de son premier message. Reponds uniquement avec le titre, sans guillemets, markdown ni ponctuation finale.",
+        title_task: "Propose un titre de 3 a 8 mots, dans la langue du message, qui resume son sujet.",
+    },
+    pulse: PulsePromptText {
+        intro: "Pulse est un moteur de transformation redactionnelle.\nTravaille uniquement a partir de la matiere fournie. Ne fais pas de retrieval implicite et ne presente pas le resultat comme une validation de verite.",
+        label_field: "Libelle",
+        instructions_heading: "Instruction supplementaire:",
+        source_text_heading: "Matiere source explicite:",
+        requirements_heading: "Exigences:",
+        requirements: &[
+            "Signale les incertitudes lorsque la matiere est incomplete.",
+            "Reste fidele a la matiere fournie.",
+        ],
+        editor_selection: "Selection editeur",
+        editor_note: "Note editeur",
+        second_brain_context: "Contexte Second Brain",
+        cosmos_focus: "Focus Cosmos",
+    },
+    frontmatter: FrontmatterPromptText {
+        system: "Tu es un générateur de properties frontmatter. Retourne uniquement un objet JSON valide, fidèle à la note, avec des clefs canoniques et des valeurs dans la langue dominante de la note.",
+        instructions: &[
+            "Tu generes des properties frontmatter pour Tomosona.",
+            "Retourne uniquement du JSON valide, sans bloc de code, sans explication, sans texte autour.",
+            "Les clefs doivent rester canoniques et stables. Ne traduis pas les clefs systeme.",
+            "Adapte les valeurs textuelles a la langue dominante de la note.",
+            "N'ecrase pas silencieusement une valeur non vide en mode auto.",
+            "Pour un sparkle sur une property, ne renvoie que cette clef.",
+        ],
+        detected_language_label: "Langue detectee",
+        path_label: "Chemin",
+        title_label: "Titre",
+        body_heading: "Corps de la note:",
+        yaml_heading: "Frontmatter YAML actuel:",
+        existing_heading: "Properties deja presentes:",
+        candidates_heading: "Proprietes canoniques candidates:",
+        rules_heading: "Regles:",
+        rules: &[
+            "En mode auto, propose seulement les properties les plus pertinentes.",
+            "Ne propose pas une clef deja remplie sauf si sa valeur est vide.",
+            "Si aucune property pertinente n'existe, retourne un tableau vide.",
+        ],
+        target_label: "Sparkle cible",
+        schema_heading: "Format JSON attendu:",
+        empty_list: "(aucune)",
+    },
+    alter: AlterPromptText {
+        contract_heading: "Contrat d'invocation de l'Alter.",
+        identity_label: "Identite",
+        description_label: "Description",
+        mission_label: "Mission",
+        inspirations_label: "Inspirations",
+        note_excerpt_label: "Extrait de la note",
+        principles_label: "Principes",
+        reflexes_label: "Reflexes",
+        values_label: "Valeurs",
+        critiques_label: "Critiques",
+        blind_spots_label: "Angles morts",
+        style_label: "Style",
+        hints_label: "Indications",
+        cite_hypotheses: "Cite toujours explicitement les hypotheses.",
+        signal_biases: "Signale les biais et angles morts potentiels dans la reponse.",
+        closing: "Reponds en markdown et garde le cadrage de l'Alter explicite sans etre theatral.",
+        draft_intro: "Tu concois des personas Alter structures pour un outil de reflexion centre sur l'espace de travail.\n\nRetourne exactement un objet JSON et rien d'autre.\nNe l'entoure pas de blocs markdown.\nGenere une configuration d'Alter pragmatique et utilisable a partir du brief utilisateur.\nRedige les valeurs textuelles en francais.",
+        draft_shape_heading: "Forme JSON requise:",
+        draft_constraints: "Contraintes:\n- Donne un nom compact et pret a l'emploi.\n- Genere automatiquement category, description et mission.\n- Prefere 3 a 6 elements par liste lorsque c'est pertinent.\n- Garde des inspirations concretes et utilise reference_figure/manual sauf si l'utilisateur evoque explicitement une note.\n- Utilise null pour les valeurs optionnelles inconnues.\n- Garde temperature entre 0 et 1.\n- Garde contradiction_level et exploration_level entre 0 et 100.",
+        draft_user_template: "Brief utilisateur pour le demarrage rapide de l'Alter:\n{brief}\n\nGenere maintenant le JSON complet de l'Alter.",
+    },
+    exploration: ExplorationPromptText {
+        context_heading: "Contextes fournis:",
+        challenge_guidance: "Expose les faiblesses, contradictions et hypotheses fragiles.",
+        explore_guidance: "Elargis l'espace d'interpretation et fais emerger des alternatives.",
+        decide_guidance: "Fais ressortir les arbitrages et converge vers une direction.",
+        refine_guidance: "Ameliore le brouillon par la confrontation et des ajustements cibles.",
+        summary_format: "Fournis une synthese narrative concise avec les accords, desaccords et arbitrages cles, et une prochaine etape recommandee.",
+        tension_map_format: "Retourne une carte des tensions structuree avec: Accords, Desaccords, Questions ouvertes, Arbitrages et Prochaine etape.",
+        decision_brief_format: "Retourne une note de decision avec: Options, Risques, Voie privilegiee et Justification.",
+        refined_proposal_format: "Reecris le sujet en proposition affinee, puis liste les changements cles appliques.",
+        round1_template: "Mode Exploration Alter (Tour 1)\nOrientation du mode: {mode_guidance}\n\nSujet ({subject_type}):\n{subject}\n\n{context}\n\nInstructions:\n- Donne ta lecture du sujet.\n- Enonce ta preoccupation principale.\n- Enonce ta recommandation principale.\n\nContraintes:\n- Garde chaque section courte et dense.\n- Ne fais pas de jeu de role.\n\nFormat de reponse:\nLecture: ...\nPreoccupation: ...\nRecommandation: ...",
+        round2_template: "Mode Exploration Alter (Tour 2)\nOrientation du mode: {mode_guidance}\n\nSujet ({subject_type}):\n{subject}\n\n{context}\n\nSynthese du tour 1:\n{digest}\n\nTu dois reagir a la position de {target} ci-dessous. Reference-la explicitement.\n\n--- Position de {target} ---\n{target_content}\n\nInstructions:\n- Reponds par un accord, un desaccord ou un affinement.\n- Apporte quelque chose de nouveau; ne repete pas ton propre tour 1.\n\nFormat de reponse:\nReaction (a {target}): ...\nAccord/Desaccord: ...\nAjustement: ...",
+        round3_template: "Mode Exploration Alter (Tour 3)\nOrientation du mode: {mode_guidance}\n\nSujet ({subject_type}):\n{subject}\n\n{context}\n\nTensions du tour 2:\n{digest}\n\nInstructions:\n- Enonce ce que tu vois maintenant comme le point le plus solide.\n- Enonce ce qui reste non resolu.\n- Enonce ce qui devrait se passer ensuite.\n\nFormat de reponse:\nPoint le plus solide: ...\nNon resolu: ...\nProchaine etape: ...",
+        synthesis_template: "Tu es le moderateur silencieux. Produis l'artefact final.\nOrientation du mode: {mode_guidance}\nFormat de sortie: {output_format}\n\nSujet ({subject_type}):\n{subject}\n\n{context}\n\nResultats des tours:\n{rounds}\n\nContraintes:\n- Sois actionnable.\n- Reste concis et dense.\n- Ne fais pas de jeu de role.\n\n{format_guidance}",
+        round1_digest_system: "Tu es un moderateur silencieux. Resume le tour 1 en puces concises qui font ressortir les recoupements et les differences.",
+        round2_digest_system: "Tu es un moderateur silencieux. Resume le tour 2 en tensions et convergences cles.",
+        synthesis_system: "Tu es le moderateur de l'exploration.",
+        round_heading_template: "Tour {round}:",
+    },
+};
+
+static ENGLISH_PACK: PromptPack = PromptPack {
+    locale: PromptLocale::En,
+    truncation_marker: "\n[CONTENT TRUNCATED]\n",
+    modes: &[
+        BuiltinPromptText {
+            id: "freestyle",
+            label: "Freestyle",
+            prompt: "You are a versatile assistant. Carry out the user request precisely. Use the provided context when it exists and clearly flag uncertainties when information is missing. Respond in markdown. Only cite sources when the user explicitly asks for them.",
+        },
+        BuiltinPromptText {
+            id: "synthese",
+            label: "Synthesis",
+            prompt: "Provide a structured, concise synthesis with limits and points of uncertainty. Cite the context sources you used.",
+        },
+        BuiltinPromptText {
+            id: "plan_action",
+            label: "Action plan",
+            prompt: "Build a concrete, ordered and verifiable action plan. Cite the context sources for each decision.",
+        },
+        BuiltinPromptText {
+            id: "diagnostic",
+            label: "Diagnostic",
+            prompt: "Establish a diagnostic: problems, hypotheses, evidence, validation tests. Cite the context sources.",
+        },
+        BuiltinPromptText {
+            id: "fusion_notes",
+            label: "Merge notes",
+            prompt: "Merge the notes by removing duplicates and contradictions. Preserve facts traced to their sources.",
+        },
+        BuiltinPromptText {
+            id: "extraction_concepts",
+            label: "Concept extraction",
+            prompt: "Extract concepts, definitions, relations and remaining ambiguities. Cite the sources.",
+        },
+    ],
+    pulse_actions: &[
+        BuiltinPromptText {
+            id: "format",
+            label: "Format",
+            prompt: "Reformat the provided material by changing only its shape (structure, length, presentation), without judgment or added content. Respond in markdown.",
+        },
+        BuiltinPromptText {
+            id: "rewrite",
+            label: "Rewrite",
+            prompt: "Rewrite the provided material to make it clearer and smoother without changing its substance. Respond in markdown.",
+        },
+        BuiltinPromptText {
+            id: "condense",
+            label: "Condense",
+            prompt: "Condense the provided material while keeping the essential information. Respond in markdown.",
+        },
+        BuiltinPromptText {
+            id: "expand",
+            label: "Expand",
+            prompt: "Expand the provided material with more structure and useful detail, without inventing facts. Respond in markdown.",
+        },
+        BuiltinPromptText {
+            id: "change_tone",
+            label: "Change tone",
+            prompt: "Rephrase the provided material, adapting the tone to the user instruction. If no tone is specified, choose a sober, professional tone. Respond in markdown.",
+        },
+        BuiltinPromptText {
+            id: "synthesize",
+            label: "Synthesize",
+            prompt: "Produce a structured synthesis of the provided material. Bring out the main ideas, limits and uncertainties. Respond in markdown.",
+        },
+        BuiltinPromptText {
+            id: "outline",
+            label: "Outline",
+            prompt: "Turn the provided material into a structured, usable outline. Respond in markdown.",
+        },
+        BuiltinPromptText {
+            id: "brief",
+            label: "Brief",
+            prompt: "Turn the provided material into a clear working brief: goal, key points, tensions, next questions if needed. Respond in markdown.",
+        },
+        BuiltinPromptText {
+            id: "extract_themes",
+            label: "Extract themes",
+            prompt: "Surface the dominant themes of the provided material, phrased concisely and usably. Respond in markdown.",
+        },
+        BuiltinPromptText {
+            id: "identify_tensions",
+            label: "Identify tensions",
+            prompt: "Identify the tensions, contradictions, blind spots or trade-offs visible in the provided material. Respond in markdown.",
+        },
+    ],
+    second_brain: SecondBrainPromptText {
+        active_alter_heading: "Active Alter:",
+        context_heading: "Provided context:",
+        history_summary_heading: "Summary of earlier exchanges:",
+        recent_history_heading: "Recent history:",
+        user_request_heading: "User request:",
+        markdown_instruction: "Respond in markdown.",
+        plain_instruction: "Respond in plain text, without markdown.",
+        json_instruction: "Respond only with a valid JSON object, with no surrounding text.",
+        summary_system: "You condense the history of a research conversation. Produce a factual, dense markdown summary: topics covered, decisions, conclusions, open questions and references to cited notes. Do not invent anything and do not answer the requests in the conversation.",
+        summary_existing_heading: "Existing summary:",
+        summary_new_turns_heading: "New exchanges to integrate:",
+        task_heading: "Task:",
+        summary_task: "Rewrite a single summary that integrates the existing summary and the new exchanges. Keep information that is still useful, remove repetition and stay under 400 words.",
+        title_system: "You give a short title to a research conversation based on its first message. Respond only with the title, without quotes, markdown or trailing punctuation.",
+        title_task: "Suggest a title of 3 to 8 words, in the language of the message, that captures its subject.",
+    },
+    pulse: PulsePromptText {
+        intro: "Pulse is an editorial transformation engine.\nWork only from the provided material. Do not perform implicit retrieval and do not present the result as a validation of truth.",
+        label_field: "Label",
+        instructions_heading: "Additional instruction:",
+        source_text_heading: "Explicit source material:",
+        requirements_heading: "Requirements:",
+        requirements: &[
+            "Flag uncertainties when the material is incomplete.",
+            "Stay faithful to the provided material.",
+        ],
+        editor_selection: "Editor selection",
+        editor_note: "Editor note",
+        second_brain_context: "Second Brain context",
+        cosmos_focus: "Cosmos focus",
+    },
+    frontmatter: FrontmatterPromptText {
+        system: "You generate frontmatter properties. Return only a valid JSON object, faithful to the note, with canonical keys and values in the dominant language of the note.",
+        instructions: &[
+            "You generate frontmatter properties for Tomosona.",
+            "Return only valid JSON, without code fences, explanation or surrounding text.",
+            "Keys must stay canonical and stable. Do not translate system keys.",
+            "Adapt text values to the dominant language of the note.",
+            "Do not silently overwrite a non-empty value in auto mode.",
+            "For a sparkle on a property, only return that key.",
+        ],
+        detected_language_label: "Detected language",
+        path_label: "Path",
+        title_label: "Title",
+        body_heading: "Note body:",
+        yaml_heading: "Current YAML frontmatter:",
+        existing_heading: "Existing properties:",
+        candidates_heading: "Candidate canonical properties:",
+        rules_heading: "Rules:",
+        rules: &[
+            "In auto mode, only suggest the most relevant properties.",
+            "Do not suggest a key that is already filled unless its value is empty.",
+            "If no relevant property exists, return an empty array.",
+        ],
+        target_label: "Target sparkle",
+        schema_heading: "Expected JSON format:",
+        empty_list: "(none)",
+    },
+    alter: AlterPromptText {
+        contract_heading: "Alter invocation contract.",
+        identity_label: "Identity",
+        description_label: "Description",
+        mission_label: "Mission",
+        inspirations_label: "Inspirations",
+        note_excerpt_label: "Note excerpt",
+        principles_label: "Principles",
+        reflexes_label: "Reflexes",
+        values_label: "Values",
+        critiques_label: "Critiques",
+        blind_spots_label: "Blind spots",
+        style_label: "Style",
+        hints_label: "Hints",
+        cite_hypotheses: "Always cite hypotheses explicitly.",
+        signal_biases: "Signal potential biases and blind spots in the answer.",
+        closing: "Respond in markdown and keep the Alter framing explicit but not theatrical.",
+        draft_intro: "You design structured Alter personas for a workspace-centric thinking tool.\n\nReturn exactly one JSON object and nothing else.\nDo not wrap in markdown fences.\nGenerate a pragmatic, usable Alter configuration from the user's brief.",
+        draft_shape_heading: "Required JSON shape:",
+        draft_constraints: "Constraints:\n- Make the name compact and product-ready.\n- Generate category, description, and mission automatically.\n- Prefer 3 to 6 items for each list when relevant.\n- Keep inspirations concrete and use reference_figure/manual unless the user explicitly implies a note.\n- Use null for unknown optional values.\n- Keep temperature between 0 and 1.\n- Keep contradiction_level and exploration_level between 0 and 100.",
+        draft_user_template: "User brief for the Alter quick start:\n{brief}\n\nGenerate the full Alter JSON now.",
+    },
+    exploration: ExplorationPromptText {
+        context_heading: "Provided context:",
+        challenge_guidance: "Expose weaknesses, contradictions, and fragile assumptions.",
+        explore_guidance: "Widen the space of interpretation and surface alternatives.",
+        decide_guidance: "Surface trade-offs and converge on a direction.",
+        refine_guidance: "Improve the draft through confrontation and targeted adjustments.",
+        summary_format: "Provide a concise narrative summary with key agreements, disagreements, trade-offs, and a recommended next step.",
+        tension_map_format: "Return a structured tension map with: Agreements, Disagreements, Unresolved Issues, Trade-offs, and Next Step.",
+        decision_brief_format: "Return a decision brief with: Options, Risks, Preferred Path, and Rationale.",
+        refined_proposal_format: "Rewrite the subject into a refined proposal, then list the key changes applied.",
+        round1_template: "Alter Exploration Mode (Round 1)\nMode guidance: {mode_guidance}\n\nSubject ({subject_type}):\n{subject}\n\n{context}\n\nInstructions:\n- Provide your reading of the subject.\n- State your main concern.\n- State your main recommendation.\n\nConstraints:\n- Keep each section short and dense.\n- Do not roleplay.\n\nResponse format:\nReading: ...\nConcern: ...\nRecommendation: ...",
+        round2_template: "Alter Exploration Mode (Round 2)\nMode guidance: {mode_guidance}\n\nSubject ({subject_type}):\n{subject}\n\n{context}\n\nRound 1 digest:\n{digest}\n\nYou must react to {target}'s position below. Reference them explicitly.\n\n--- {target} position ---\n{target_content}\n\nInstructions:\n- Respond with agreement, disagreement, or refinement.\n- Add something new; no restating your own Round 1.\n\nResponse format:\nReaction (to {target}): ...\nAgreement/Disagreement: ...\nAdjustment: ...",
+        round3_template: "Alter Exploration Mode (Round 3)\nMode guidance: {mode_guidance}\n\nSubject ({subject_type}):\n{subject}\n\n{context}\n\nRound 2 tension digest:\n{digest}\n\nInstructions:\n- State what you now see as the strongest point.\n- State what remains unresolved.\n- State what should happen next.\n\nResponse format:\nStrongest point: ...\nUnresolved: ...\nNext step: ...",
+        synthesis_template: "You are the silent moderator. Produce the final artifact.\nMode guidance: {mode_guidance}\nOutput format: {output_format}\n\nSubject ({subject_type}):\n{subject}\n\n{context}\n\nRound results:\n{rounds}\n\nConstraints:\n- Be actionable.\n- Keep it concise and dense.\n- Do not roleplay.\n\n{format_guidance}",
+        round1_digest_system: "You are a silent moderator. Summarize Round 1 into concise bullets highlighting overlaps and differences.",
+        round2_digest_system: "You are a silent moderator. Summarize Round 2 into the key tensions and convergences.",
+        synthesis_system: "You are the exploration moderator.",
+        round_heading_template: "Round {round}:",
+    },
+};
+
+/// Returns the built-in pack for a locale.
+pub fn prompt_pack(locale: PromptLocale) -> &'static PromptPack {
+    match locale {
+        PromptLocale::Fr => &FRENCH_PACK,
+        PromptLocale::En => &ENGLISH_PACK,
+    }
+}
+
+/// Picks the pack forced by settings, or the one matching the dominant language of `samples`.
+///
+/// Mixed, short or undetectable material gets `fallback`, the language the calling
+/// surface used before prompt packs existed.
+pub fn select_prompt_pack(
+    preferred: Option<PromptLocale>,
+    samples: &[&str],
+    fallback: PromptLocale,
+) -> &'static PromptPack {
+    prompt_pack(
+        preferred
+            .or_else(|| detect_prompt_locale(samples))
+            .unwrap_or(fallback),
+    )
+}
+
+/// Detects the pack locale from the dominant language of `samples`, if there is one.
+pub fn detect_prompt_locale(samples: &[&str]) -> Option<PromptLocale> {
+    let sample = samples
+        .iter()
+        .map(|text| {
+            text.chars()
+                .take(DETECTION_SAMPLE_CHARS)
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    PromptLocale::from_language_hint(detect_note_language(&sample))
+}
+
+fn count_hints(text: &str, hints: &[&str]) -> usize {
+    let lowered = text.to_lowercase();
+    hints.iter().map(|hint| lowered.matches(hint).count()).sum()
+}
+
+/// Returns `fr`, `en`, `mixed` or `unknown` from simple lexical hints.
+pub fn detect_note_language(text: &str) -> &'static str {
+    let cleaned = text.trim();
+    if cleaned.is_empty() {
+        return "unknown";
+    }
+
+    let french_score = count_hints(
+        cleaned,
+        &[
+            " le ",
+            " la ",
+            " les ",
+            " des ",
+            " une ",
+            " un ",
+            " et ",
+            " pour ",
+            " avec ",
+            " dans ",
+            " que ",
+            " est ",
+            " être ",
+            "sur ",
+            " à ",
+            " du ",
+            "de ",
+            "note ",
+            "projet ",
+            "brouillon ",
+        ],
+    ) + cleaned
+        .chars()
+        .filter(|ch| {
+            matches!(
+                ch,
+                'à' | 'â' | 'ç' | 'é' | 'è' | 'ê' | 'ë' | 'î' | 'ï' | 'ô' | 'ù' | 'û' | 'ü'
+            )
+        })
+        .count();
+    let english_score = count_hints(
+        cleaned,
+        &[
+            " the ",
+            " and ",
+            " with ",
+            " for ",
+            " from ",
+            " note ",
+            " project ",
+            " draft ",
+            " should ",
+            " this ",
+            " that ",
+            " are ",
+            " is ",
+            " to ",
+            " of ",
+            " in ",
+        ],
+    );
+
+    if french_score == 0 && english_score == 0 {
+        return "unknown";
+    }
+    if (french_score as i64 - english_score as i64).abs() <= 2 {
+        return "mixed";
+    }
+    if french_score > english_score {
+        "fr"
+    } else {
+        "en"
+    }
+}
+
+/// Replaces `{name}` placeholders in a single pass.
+///
+/// Inserted values are never rescanned, so user text containing braces is kept as-is.
+/// Unknown placeholders are left untouched.
+pub fn fill_template(template: &str, values: &[(&str, &str)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let replacement = after.find('}').and_then(|end| {
+            let key = &after[..end];
+            values
+                .iter()
+                .find(|(name, _)| *name == key)
+                .map(|(_, value)| (*value, end))
+        });
+        match replacement {
+            Some((value, end)) => {
+                out.push_str(value);
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(items: &[BuiltinPromptText]) -> Vec<&str> {
+        items.iter().map(|item| item.id).collect()
+    }
+
+    #[test]
+    fn packs_define_the_same_builtin_ids() {
+        assert_eq!(ids(FRENCH_PACK.modes), ids(ENGLISH_PACK.modes));
+        assert_eq!(
+            ids(FRENCH_PACK.pulse_actions),
+            ids(ENGLISH_PACK.pulse_actions)
+        );
+        assert!(FRENCH_PACK.mode("freestyle").is_some());
+    }
+
+    #[test]
+    fn setting_overrides_detected_language() {
+        let english = "This is the draft of the project and the plan for the team.";
+        assert_eq!(
+            select_prompt_pack(None, &[english], PromptLocale::Fr).locale,
+            PromptLocale::En
+        );
+        assert_eq!(
+            select_prompt_pack(Some(PromptLocale::Fr), &[english], PromptLocale::En).locale,
+            PromptLocale::Fr
+        );
+    }
+
+    #[test]
+    fn undetected_material_keeps_the_surface_fallback() {
+        let mixed = "Le plan et the draft pour the team.";
+        for samples in [&["42"][..], &[mixed][..], &[][..]] {
+            assert_eq!(detect_prompt_locale(samples), None);
+            assert_eq!(
+                select_prompt_pack(None, samples, PromptLocale::En).locale,
+                PromptLocale::En
+            );
+            assert_eq!(
+                select_prompt_pack(None, samples, PromptLocale::Fr).locale,
+                PromptLocale::Fr
+            );
+        }
+    }
+
+    #[test]
+    fn detects_french_and_english_notes() {
+        assert_eq!(
+            detect_note_language("Le projet est prêt pour la revue et les tests."),
+            "fr"
+        );
+        assert_eq!(
+            detect_note_language("The project is ready for the review and the tests."),
+            "en"
+        );
+        assert_eq!(detect_note_language("   "), "unknown");
+    }
+
+    #[test]
+    fn fill_template_does_not_rescan_inserted_values() {
+        let filled = fill_template(
+            "Subject: {subject} / {digest} / {missing}",
+            &[("subject", "use {digest} here"), ("digest", "ok")],
+        );
+        assert_eq!(filled, "Subject: use {digest} here / ok / {missing}");
+    }
+}
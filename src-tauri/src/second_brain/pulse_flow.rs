@@ -7,12 +7,17 @@
 use tauri::{AppHandle, Emitter};
 
 use super::{
-    config::active_profile,
+    config::{role_profile, role_route, LlmRoute, ModelRole},
     context::load_context_entries_from_paths,
-    llm::{run_llm, run_llm_stream},
-    load_config, next_id,
-    prompt_builder::{build_pulse_user_prompt, normalize_pulse_action_id, pulse_action_prompt},
+    llm::{run_llm_stream, LlmReply},
+    load_config,
+    modes::PulseActionSpec,
+    next_id,
+    prompt_builder::{build_pulse_user_prompt, normalize_pulse_action_id},
+    prompt_library::load_prompt_catalog,
+    prompt_packs::{select_prompt_pack, PromptLocale},
     stream_control::consume_stream_cancel,
+    usage::LlmFeature,
     AppError, PulseStreamEvent, Result, RunPulseTransformationPayload,
     RunPulseTransformationResult,
 };
@@ -23,19 +28,31 @@ pub(super) async fn run_pulse(
     payload: RunPulseTransformationPayload,
 ) -> Result<RunPulseTransformationResult> {
     let config = load_config()?;
-    let active = active_profile(&config)
+    let active = role_profile(&config, ModelRole::Pulse)
         .ok_or_else(|| {
-            AppError::InvalidOperation("active profile is missing from config.".to_string())
+            AppError::InvalidOperation("No text-capable model profile available.".to_string())
         })?
         .clone();
 
-    if !active.capabilities.text {
-        return Err(AppError::InvalidOperation(
-            "The active profile does not support text generation.".to_string(),
-        ));
-    }
-
-    let action_id = normalize_pulse_action_id(&payload.action_id)?;
+    let context_entries = load_context_entries_from_paths(&payload.context_paths)?;
+    let pack = select_prompt_pack(
+        config.prompt_language,
+        &payload
+            .source_text
+            .iter()
+            .map(String::as_str)
+            .chain(context_entries.iter().map(|entry| entry.content.as_str()))
+            .collect::<Vec<_>>(),
+        PromptLocale::Fr,
+    );
+    let catalog = load_prompt_catalog(pack);
+    let action_id = normalize_pulse_action_id(&payload.action_id, &catalog.pulse_actions)?;
+    let action = catalog
+        .pulse_actions
+        .iter()
+        .find(|item| item.id == action_id)
+        .cloned()
+        .ok_or_else(|| AppError::InvalidOperation("Pulse action is not supported.".to_string()))?;
     validate_pulse_payload(&payload)?;
 
     let request_id = payload
@@ -52,18 +69,17 @@ pub(super) async fn run_pulse(
         ));
     }
 
-    let context_entries = load_context_entries_from_paths(&payload.context_paths)?;
-    let built_prompt = build_pulse_user_prompt(&payload, &action_id, &context_entries);
+    let built_prompt = build_pulse_user_prompt(&payload, &action, &context_entries, pack);
     let provenance_paths = built_prompt.included_context_paths.clone();
 
     emit_pulse_start(&app, &request_id, &output_id, &provenance_paths);
 
-    let answer = run_pulse_generation(
+    let reply = run_pulse_generation(
         &app,
-        &active,
+        &role_route(&config, &active, ModelRole::Pulse),
         &request_id,
         &output_id,
-        &action_id,
+        &action,
         &built_prompt.user_prompt,
     )
     .await?;
@@ -74,11 +90,12 @@ pub(super) async fn run_pulse(
         PulseStreamEvent {
             request_id: request_id.clone(),
             output_id: output_id.clone(),
-            chunk: answer,
+            chunk: reply.text,
             done: true,
-            error: None,
+            error: reply.interrupted,
             title: Some(title),
             provenance_paths: built_prompt.included_context_paths,
+            profile_id: Some(reply.profile_id),
         },
     );
 
@@ -105,52 +122,45 @@ fn validate_pulse_payload(payload: &RunPulseTransformationPayload) -> Result<()>
 
 async fn run_pulse_generation(
     app: &AppHandle,
-    active: &super::config::ProviderProfile,
+    route: &LlmRoute,
     request_id: &str,
     output_id: &str,
-    action_id: &str,
+    action: &PulseActionSpec,
     user_prompt: &str,
-) -> Result<String> {
+) -> Result<LlmReply> {
+    let system_prompt = action.prompt_template.as_str();
     let request_id_for_stream = request_id.to_string();
     let output_id_for_stream = output_id.to_string();
     let app_for_stream = app.clone();
-    let llm_result = if active.capabilities.streaming {
-        run_llm_stream(
-            active,
-            pulse_action_prompt(action_id),
-            user_prompt,
-            None,
-            move |chunk| {
-                if consume_stream_cancel(&request_id_for_stream, &output_id_for_stream) {
-                    return Err("Generation canceled.".to_string());
-                }
-                let _ = app_for_stream.emit(
-                    "pulse://delta",
-                    PulseStreamEvent {
-                        request_id: request_id_for_stream.clone(),
-                        output_id: output_id_for_stream.clone(),
-                        chunk: chunk.to_string(),
-                        done: false,
-                        error: None,
-                        title: None,
-                        provenance_paths: Vec::new(),
-                    },
-                );
-                Ok(())
-            },
-        )
-        .await
-    } else {
-        run_llm(
-            active,
-            pulse_action_prompt(action_id),
-            user_prompt,
-            None,
-        )
-        .await
-    };
+    let llm_result = run_llm_stream(
+        route,
+        LlmFeature::Pulse,
+        system_prompt,
+        user_prompt,
+        action.temperature,
+        move |profile, chunk| {
+            if consume_stream_cancel(&request_id_for_stream, &output_id_for_stream) {
+                return Err("Generation canceled.".to_string());
+            }
+            let _ = app_for_stream.emit(
+                "pulse://delta",
+                PulseStreamEvent {
+                    request_id: request_id_for_stream.clone(),
+                    output_id: output_id_for_stream.clone(),
+                    chunk: chunk.to_string(),
+                    done: false,
+                    error: None,
+                    title: None,
+                    provenance_paths: Vec::new(),
+                    profile_id: Some(profile.id.clone()),
+                },
+            );
+            Ok(())
+        },
+    )
+    .await;
 
-    let answer = match llm_result {
+    let reply = match llm_result {
         Ok(value) => value,
         Err(err) => {
             emit_pulse_error(app, request_id, output_id, &err, Vec::new());
@@ -171,22 +181,7 @@ async fn run_pulse_generation(
         ));
     }
 
-    if !active.capabilities.streaming {
-        let _ = app.emit(
-            "pulse://delta",
-            PulseStreamEvent {
-                request_id: request_id.to_string(),
-                output_id: output_id.to_string(),
-                chunk: answer.clone(),
-                done: false,
-                error: None,
-                title: None,
-                provenance_paths: Vec::new(),
-            },
-        );
-    }
-
-    Ok(answer)
+    Ok(reply)
 }
 
 fn pulse_completion_title(action_id: &str) -> String {
@@ -209,6 +204,7 @@ fn emit_pulse_start(
             error: None,
             title: None,
             provenance_paths: provenance_paths.to_vec(),
+            profile_id: None,
         },
     );
 }
@@ -230,6 +226,7 @@ fn emit_pulse_error(
             error: Some(error.to_string()),
             title: None,
             provenance_paths,
+            profile_id: None,
         },
     );
 }
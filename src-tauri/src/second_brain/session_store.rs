@@ -1,7 +1,10 @@
-use rusqlite::{params, Connection};
+use std::collections::{HashMap, HashSet};
+
+use rusqlite::{params, Connection, OptionalExtension, Row};
 use serde::Serialize;
 
 use super::super::{now_ms, AppError, Result};
+use crate::conversation_search::delete_second_brain_session_items;
 
 #[derive(Debug, Clone, Serialize)]
 pub struct SessionSummary {
@@ -24,6 +27,8 @@ pub struct ContextItem {
 #[derive(Debug, Clone, Serialize)]
 pub struct MessageRow {
     pub id: String,
+    /// Previous message in the conversation tree; empty for the first message.
+    pub parent_id: String,
     pub role: String,
     pub mode: String,
     pub content_md: String,
@@ -32,6 +37,37 @@ pub struct MessageRow {
     pub created_at_ms: u64,
 }
 
+/// Alternative message sharing the same parent, e.g. a regenerated reply or an edited prompt.
+#[derive(Debug, Clone, Serialize)]
+pub struct MessageBranch {
+    pub message_id: String,
+    pub role: String,
+    pub preview: String,
+    pub created_at_ms: u64,
+    /// Leaf reached when switching to this branch.
+    pub leaf_message_id: String,
+    pub active: bool,
+}
+
+/// Message of the active branch that has alternatives the user can switch to.
+#[derive(Debug, Clone, Serialize)]
+pub struct BranchPoint {
+    pub message_id: String,
+    pub sibling_ids: Vec<String>,
+}
+
+/// Rolling summary of history turns that no longer fit in the prompt window.
+#[derive(Debug, Clone, Serialize)]
+pub struct HistorySummary {
+    pub session_id: String,
+    pub content_md: String,
+    pub covered_until_message_id: String,
+    pub covered_until_ms: u64,
+    pub covered_message_count: usize,
+    pub edited_by_user: bool,
+    pub updated_at_ms: u64,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct SessionPayload {
     pub session_id: String,
@@ -44,6 +80,9 @@ pub struct SessionPayload {
     pub alter_id: String,
     pub context_items: Vec<ContextItem>,
     pub messages: Vec<MessageRow>,
+    pub active_message_id: String,
+    pub branch_points: Vec<BranchPoint>,
+    pub history_summary: Option<HistorySummary>,
     pub draft_content: String,
 }
 
@@ -138,13 +177,15 @@ pub fn upsert_context(
     Ok(context_items.iter().map(|item| item.token_estimate).sum())
 }
 
+/// Persists a message and makes it the tip of the active branch.
 pub fn insert_message(conn: &Connection, msg: &MessageRow, session_id: &str) -> Result<()> {
     conn.execute(
-        "INSERT INTO second_brain_messages (id, session_id, role, mode, content_md, citations_json, attachments_json, created_at_ms)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        "INSERT INTO second_brain_messages (id, session_id, parent_id, role, mode, content_md, citations_json, attachments_json, created_at_ms)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
         params![
             msg.id,
             session_id,
+            msg.parent_id,
             msg.role,
             msg.mode,
             msg.content_md,
@@ -153,13 +194,200 @@ pub fn insert_message(conn: &Connection, msg: &MessageRow, session_id: &str) ->
             msg.created_at_ms as i64
         ],
     )?;
+    set_active_message_id(conn, session_id, &msg.id)
+}
+
+pub fn set_active_message_id(conn: &Connection, session_id: &str, message_id: &str) -> Result<()> {
     conn.execute(
-        "UPDATE second_brain_sessions SET updated_at_ms = ?2 WHERE id = ?1",
-        params![session_id, now_ms() as i64],
+        "UPDATE second_brain_sessions SET active_message_id = ?2, updated_at_ms = ?3 WHERE id = ?1",
+        params![session_id, message_id, now_ms() as i64],
     )?;
     Ok(())
 }
 
+/// Reads every message of a session, all branches included, in creation order.
+pub fn read_all_messages(conn: &Connection, session_id: &str) -> Result<Vec<MessageRow>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, parent_id, role, mode, content_md, citations_json, attachments_json, created_at_ms
+         FROM second_brain_messages
+         WHERE session_id = ?1
+         ORDER BY created_at_ms ASC, rowid ASC",
+    )?;
+    let rows = stmt.query_map(params![session_id], message_from_row)?;
+    let mut messages = Vec::new();
+    for row in rows {
+        messages.push(row?);
+    }
+    Ok(messages)
+}
+
+fn message_from_row(row: &Row<'_>) -> rusqlite::Result<MessageRow> {
+    Ok(MessageRow {
+        id: row.get::<_, String>(0)?,
+        parent_id: row.get::<_, String>(1)?,
+        role: row.get::<_, String>(2)?,
+        mode: row.get::<_, String>(3)?,
+        content_md: row.get::<_, String>(4)?,
+        citations_json: row.get::<_, String>(5)?,
+        attachments_json: row.get::<_, String>(6)?,
+        created_at_ms: row.get::<_, i64>(7)? as u64,
+    })
+}
+
+pub fn read_message(conn: &Connection, session_id: &str, message_id: &str) -> Result<MessageRow> {
+    conn.query_row(
+        "SELECT id, parent_id, role, mode, content_md, citations_json, attachments_json, created_at_ms
+         FROM second_brain_messages
+         WHERE session_id = ?1 AND id = ?2",
+        params![session_id, message_id],
+        message_from_row,
+    )
+    .optional()?
+    .ok_or_else(|| AppError::InvalidOperation("Second Brain message not found.".to_string()))
+}
+
+/// Returns the tip of the active branch.
+///
+/// Sessions created before branching have no stored tip; their latest message is used.
+pub fn active_message_id(conn: &Connection, session_id: &str) -> Result<String> {
+    let stored = stored_active_message_id(conn, session_id);
+    let messages = read_all_messages(conn, session_id)?;
+    Ok(resolve_active_message_id(&messages, &stored))
+}
+
+/// Reads the conversation from the first message down to `leaf_message_id`.
+pub fn read_branch_messages(
+    conn: &Connection,
+    session_id: &str,
+    leaf_message_id: &str,
+) -> Result<Vec<MessageRow>> {
+    let messages = read_all_messages(conn, session_id)?;
+    Ok(branch_path(&messages, leaf_message_id))
+}
+
+/// Lists the alternatives of a message, itself included, oldest first.
+pub fn list_message_branches(
+    conn: &Connection,
+    session_id: &str,
+    message_id: &str,
+) -> Result<Vec<MessageBranch>> {
+    let messages = read_all_messages(conn, session_id)?;
+    let Some(message) = messages.iter().find(|item| item.id == message_id) else {
+        return Err(AppError::InvalidOperation(
+            "Second Brain message not found.".to_string(),
+        ));
+    };
+    let stored = stored_active_message_id(conn, session_id);
+    let active_ids = branch_path(&messages, &resolve_active_message_id(&messages, &stored))
+        .into_iter()
+        .map(|item| item.id)
+        .collect::<HashSet<_>>();
+    Ok(messages
+        .iter()
+        .filter(|item| item.parent_id == message.parent_id)
+        .map(|item| MessageBranch {
+            message_id: item.id.clone(),
+            role: item.role.clone(),
+            preview: message_preview(&item.content_md),
+            created_at_ms: item.created_at_ms,
+            leaf_message_id: latest_descendant(&messages, &item.id),
+            active: active_ids.contains(&item.id),
+        })
+        .collect())
+}
+
+/// Makes the branch containing `message_id` active, following its most recent replies.
+pub fn switch_active_branch(
+    conn: &Connection,
+    session_id: &str,
+    message_id: &str,
+) -> Result<String> {
+    let messages = read_all_messages(conn, session_id)?;
+    if !messages.iter().any(|item| item.id == message_id) {
+        return Err(AppError::InvalidOperation(
+            "Second Brain message not found.".to_string(),
+        ));
+    }
+    let leaf = latest_descendant(&messages, message_id);
+    set_active_message_id(conn, session_id, &leaf)?;
+    Ok(leaf)
+}
+
+fn stored_active_message_id(conn: &Connection, session_id: &str) -> String {
+    conn.query_row(
+        "SELECT COALESCE(active_message_id, '') FROM second_brain_sessions WHERE id = ?1",
+        params![session_id],
+        |row| row.get(0),
+    )
+    .unwrap_or_default()
+}
+
+fn resolve_active_message_id(messages: &[MessageRow], stored: &str) -> String {
+    if messages.iter().any(|item| item.id == stored) {
+        return stored.to_string();
+    }
+    messages
+        .last()
+        .map(|item| item.id.clone())
+        .unwrap_or_default()
+}
+
+fn branch_path(messages: &[MessageRow], leaf_message_id: &str) -> Vec<MessageRow> {
+    let by_id = messages
+        .iter()
+        .map(|item| (item.id.as_str(), item))
+        .collect::<HashMap<_, _>>();
+    let mut path = Vec::new();
+    let mut seen = HashSet::new();
+    let mut cursor = by_id.get(leaf_message_id).copied();
+    while let Some(message) = cursor {
+        if !seen.insert(message.id.as_str()) {
+            break;
+        }
+        path.push(message.clone());
+        cursor = by_id.get(message.parent_id.as_str()).copied();
+    }
+    path.reverse();
+    path
+}
+
+fn latest_descendant(messages: &[MessageRow], message_id: &str) -> String {
+    let mut current = message_id.to_string();
+    let mut seen = HashSet::new();
+    while seen.insert(current.clone()) {
+        match messages.iter().rev().find(|item| item.parent_id == current) {
+            Some(child) => current = child.id.clone(),
+            None => break,
+        }
+    }
+    current
+}
+
+fn branch_points(messages: &[MessageRow], active_path: &[MessageRow]) -> Vec<BranchPoint> {
+    active_path
+        .iter()
+        .filter_map(|message| {
+            let sibling_ids = messages
+                .iter()
+                .filter(|item| item.parent_id == message.parent_id)
+                .map(|item| item.id.clone())
+                .collect::<Vec<_>>();
+            (sibling_ids.len() > 1).then(|| BranchPoint {
+                message_id: message.id.clone(),
+                sibling_ids,
+            })
+        })
+        .collect()
+}
+
+fn message_preview(content: &str) -> String {
+    let flattened = content.split_whitespace().collect::<Vec<_>>().join(" ");
+    if flattened.chars().count() <= 120 {
+        return flattened;
+    }
+    format!("{}...", flattened.chars().take(120).collect::<String>())
+}
+
 pub fn update_session_title(conn: &Connection, session_id: &str, title: &str) -> Result<()> {
     conn.execute(
         "UPDATE second_brain_sessions SET title = ?2, updated_at_ms = ?3 WHERE id = ?1",
@@ -237,29 +465,12 @@ pub fn load_session(
         context_items.push(item?);
     }
 
-    let mut message_stmt = conn.prepare(
-        "SELECT id, role, mode, content_md, citations_json, attachments_json, created_at_ms
-         FROM second_brain_messages
-         WHERE session_id = ?1
-         ORDER BY created_at_ms ASC",
-    )?;
-
-    let message_rows = message_stmt.query_map(params![session_id], |row| {
-        Ok(MessageRow {
-            id: row.get::<_, String>(0)?,
-            role: row.get::<_, String>(1)?,
-            mode: row.get::<_, String>(2)?,
-            content_md: row.get::<_, String>(3)?,
-            citations_json: row.get::<_, String>(4)?,
-            attachments_json: row.get::<_, String>(5)?,
-            created_at_ms: row.get::<_, i64>(6)? as u64,
-        })
-    })?;
-
-    let mut messages = Vec::new();
-    for item in message_rows {
-        messages.push(item?);
-    }
+    let all_messages = read_all_messages(conn, session_id)?;
+    let stored_active_id = stored_active_message_id(conn, session_id);
+    let active_message_id = resolve_active_message_id(&all_messages, &stored_active_id);
+    let messages = branch_path(&all_messages, &active_message_id);
+    let branch_points = branch_points(&all_messages, &messages);
+    let history_summary = read_history_summary(conn, session_id)?;
 
     Ok(SessionPayload {
         session_id: id,
@@ -272,10 +483,65 @@ pub fn load_session(
         alter_id,
         context_items,
         messages,
+        active_message_id,
+        branch_points,
+        history_summary,
         draft_content,
     })
 }
 
+pub fn read_history_summary(conn: &Connection, session_id: &str) -> Result<Option<HistorySummary>> {
+    let row = conn.query_row(
+        "SELECT content_md, covered_until_message_id, covered_until_ms, covered_message_count, edited_by_user, updated_at_ms
+         FROM second_brain_history_summaries
+         WHERE session_id = ?1",
+        params![session_id],
+        |row| {
+            Ok(HistorySummary {
+                session_id: session_id.to_string(),
+                content_md: row.get::<_, String>(0)?,
+                covered_until_message_id: row.get::<_, String>(1)?,
+                covered_until_ms: row.get::<_, i64>(2)? as u64,
+                covered_message_count: row.get::<_, i64>(3)? as usize,
+                edited_by_user: row.get::<_, i64>(4)? != 0,
+                updated_at_ms: row.get::<_, i64>(5)? as u64,
+            })
+        },
+    );
+    match row {
+        Ok(summary) => Ok(Some(summary)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+pub fn upsert_history_summary(conn: &Connection, summary: &HistorySummary) -> Result<()> {
+    conn.execute(
+        "INSERT INTO second_brain_history_summaries(
+            session_id, content_md, covered_until_message_id, covered_until_ms,
+            covered_message_count, edited_by_user, updated_at_ms
+         )
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+         ON CONFLICT(session_id) DO UPDATE SET
+            content_md = excluded.content_md,
+            covered_until_message_id = excluded.covered_until_message_id,
+            covered_until_ms = excluded.covered_until_ms,
+            covered_message_count = excluded.covered_message_count,
+            edited_by_user = excluded.edited_by_user,
+            updated_at_ms = excluded.updated_at_ms",
+        params![
+            summary.session_id,
+            summary.content_md,
+            summary.covered_until_message_id,
+            summary.covered_until_ms as i64,
+            summary.covered_message_count as i64,
+            if summary.edited_by_user { 1 } else { 0 },
+            summary.updated_at_ms as i64
+        ],
+    )?;
+    Ok(())
+}
+
 pub fn set_session_alter_id(conn: &Connection, session_id: &str, alter_id: &str) -> Result<()> {
     conn.execute(
         "UPDATE second_brain_sessions SET alter_id = ?2, updated_at_ms = ?3 WHERE id = ?1",
@@ -302,6 +568,11 @@ pub fn delete_session(conn: &Connection, session_id: &str) -> Result<()> {
         "DELETE FROM second_brain_session_targets WHERE session_id = ?1",
         params![session_id],
     )?;
+    tx.execute(
+        "DELETE FROM second_brain_history_summaries WHERE session_id = ?1",
+        params![session_id],
+    )?;
+    delete_second_brain_session_items(&tx, session_id)?;
     tx.execute(
         "DELETE FROM second_brain_sessions WHERE id = ?1",
         params![session_id],
@@ -320,4 +591,100 @@ mod tests {
         assert_eq!(estimate_tokens("abcd"), 1);
         assert_eq!(estimate_tokens("abcdefgh"), 2);
     }
+
+    fn message(id: &str, parent_id: &str, role: &str) -> MessageRow {
+        MessageRow {
+            id: id.to_string(),
+            parent_id: parent_id.to_string(),
+            role: role.to_string(),
+            mode: "freestyle".to_string(),
+            content_md: format!("content {id}"),
+            citations_json: "[]".to_string(),
+            attachments_json: "[]".to_string(),
+            created_at_ms: 0,
+        }
+    }
+
+    fn branched_conversation() -> Vec<MessageRow> {
+        vec![
+            message("u1", "", "user"),
+            message("a1", "u1", "assistant"),
+            message("u2", "a1", "user"),
+            message("a2", "u2", "assistant"),
+            message("a2b", "u2", "assistant"),
+            message("u2b", "a1", "user"),
+            message("a3", "u2b", "assistant"),
+        ]
+    }
+
+    fn ids(messages: &[MessageRow]) -> Vec<&str> {
+        messages.iter().map(|item| item.id.as_str()).collect()
+    }
+
+    #[test]
+    fn branch_path_follows_parents_only() {
+        let messages = branched_conversation();
+        assert_eq!(
+            ids(&branch_path(&messages, "a2b")),
+            vec!["u1", "a1", "u2", "a2b"]
+        );
+        assert_eq!(
+            ids(&branch_path(&messages, "a3")),
+            vec!["u1", "a1", "u2b", "a3"]
+        );
+        assert!(branch_path(&messages, "missing").is_empty());
+    }
+
+    #[test]
+    fn branch_path_stops_on_parent_cycles() {
+        let messages = vec![
+            message("m1", "m2", "user"),
+            message("m2", "m1", "assistant"),
+        ];
+        assert_eq!(ids(&branch_path(&messages, "m2")), vec!["m1", "m2"]);
+    }
+
+    #[test]
+    fn switching_follows_the_latest_reply() {
+        let messages = branched_conversation();
+        assert_eq!(latest_descendant(&messages, "u2"), "a2b");
+        assert_eq!(latest_descendant(&messages, "a1"), "a3");
+        assert_eq!(latest_descendant(&messages, "a3"), "a3");
+    }
+
+    #[test]
+    fn active_message_falls_back_to_latest_message() {
+        let messages = branched_conversation();
+        assert_eq!(resolve_active_message_id(&messages, "a2"), "a2");
+        assert_eq!(resolve_active_message_id(&messages, ""), "a3");
+        assert_eq!(resolve_active_message_id(&[], "a2"), "");
+    }
+
+    #[test]
+    fn branch_points_list_alternatives_on_active_path() {
+        let messages = branched_conversation();
+        let path = branch_path(&messages, "a2");
+        let points = branch_points(&messages, &path);
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[0].message_id, "u2");
+        assert_eq!(points[0].sibling_ids, vec!["u2", "u2b"]);
+        assert_eq!(points[1].message_id, "a2");
+        assert_eq!(points[1].sibling_ids, vec!["a2", "a2b"]);
+    }
+
+    #[test]
+    fn reads_one_message_of_its_own_session() {
+        let conn = Connection::open_in_memory().expect("open memory db");
+        crate::ensure_index_schema(&conn).expect("create schema");
+        conn.execute_batch("INSERT INTO second_brain_sessions(id) VALUES ('s1'), ('s2');")
+            .expect("insert sessions");
+        insert_message(&conn, &message("u1", "", "user"), "s1").expect("insert u1");
+        insert_message(&conn, &message("a1", "u1", "assistant"), "s1").expect("insert a1");
+
+        let reply = read_message(&conn, "s1", "a1").expect("read a1");
+        assert_eq!(reply.parent_id, "u1");
+        assert_eq!(reply.content_md, "content a1");
+        assert!(read_message(&conn, "s2", "a1").is_err());
+        assert!(read_message(&conn, "s1", "missing").is_err());
+    }
 }
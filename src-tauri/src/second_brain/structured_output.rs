@@ -0,0 +1,230 @@
+//! JSON replies checked against a schema, used by [`super::llm::run_llm_json`].
+//!
+//! Schemas are plain JSON Schema values written in the subset every provider
+//! accepts in strict mode: `type` (a name or a list of names), `properties`,
+//! `required`, `additionalProperties: false`, `items`, `enum` and `anyOf`. The same
+//! schema is sent to the provider and checked locally, so a reply that slips past a
+//! provider without native JSON support is still caught and repaired.
+
+use serde_json::Value;
+
+/// Schema a structured reply must match.
+#[derive(Debug, Clone)]
+pub struct JsonOutput {
+    /// Schema or tool name sent to providers; letters, digits, `_` and `-` only.
+    pub name: &'static str,
+    pub description: &'static str,
+    pub schema: Value,
+}
+
+/// Parses the JSON object of a model reply, tolerating code fences and text around it.
+pub(super) fn parse_reply(raw: &str) -> Result<Value, String> {
+    let trimmed = raw.trim();
+    if let Ok(value) = serde_json::from_str(trimmed) {
+        return Ok(value);
+    }
+    let json = extract_json_object(trimmed).ok_or_else(|| "no JSON object found".to_string())?;
+    serde_json::from_str(json).map_err(|err| format!("invalid JSON ({err})"))
+}
+
+/// First balanced `{...}` block of `raw`, ignoring braces inside strings.
+fn extract_json_object(raw: &str) -> Option<&str> {
+    let start = raw.find('{')?;
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (offset, ch) in raw[start..].char_indices() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if ch == '\\' {
+                escaped = true;
+            } else if ch == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match ch {
+            '"' => in_string = true,
+            '{' => depth += 1,
+            '}' => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    let end = start + offset + ch.len_utf8();
+                    return Some(&raw[start..end]);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn type_matches(name: &str, value: &Value) -> bool {
+    match name {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "number" => value.is_number(),
+        "integer" => {
+            value.is_i64()
+                || value.is_u64()
+                || value.as_f64().is_some_and(|number| number.fract() == 0.0)
+        }
+        _ => true,
+    }
+}
+
+/// Checks `value` against `schema` and returns one message per mismatch, each
+/// prefixed with the JSON path of the offending value.
+pub fn validate(schema: &Value, value: &Value) -> Vec<String> {
+    let mut errors = Vec::new();
+    validate_at(schema, value, "$", &mut errors);
+    errors
+}
+
+fn validate_at(schema: &Value, value: &Value, path: &str, errors: &mut Vec<String>) {
+    if let Some(options) = schema.get("anyOf").and_then(Value::as_array) {
+        if !options
+            .iter()
+            .any(|option| validate(option, value).is_empty())
+        {
+            errors.push(format!("{path}: does not match any allowed shape"));
+        }
+        return;
+    }
+
+    let types = match schema.get("type") {
+        Some(Value::String(name)) => vec![name.as_str()],
+        Some(Value::Array(names)) => names.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    };
+    if !types.is_empty() && !types.iter().any(|name| type_matches(name, value)) {
+        errors.push(format!("{path}: expected {}", types.join(" or ")));
+        return;
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(value) {
+            let names = allowed
+                .iter()
+                .map(Value::to_string)
+                .collect::<Vec<_>>()
+                .join(", ");
+            errors.push(format!("{path}: must be one of {names}"));
+        }
+    }
+
+    if let Some(object) = value.as_object() {
+        let properties = schema.get("properties").and_then(Value::as_object);
+        for key in schema
+            .get("required")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+        {
+            if !object.contains_key(key) {
+                errors.push(format!("{path}.{key}: is required"));
+            }
+        }
+        for (key, item) in object {
+            match properties.and_then(|properties| properties.get(key)) {
+                Some(property) => validate_at(property, item, &format!("{path}.{key}"), errors),
+                None if schema.get("additionalProperties") == Some(&Value::Bool(false)) => {
+                    errors.push(format!("{path}.{key}: is not allowed"));
+                }
+                None => {}
+            }
+        }
+    }
+
+    if let (Some(items), Some(array)) = (schema.get("items"), value.as_array()) {
+        for (index, item) in array.iter().enumerate() {
+            validate_at(items, item, &format!("{path}[{index}]"), errors);
+        }
+    }
+}
+
+/// User prompt of a repair attempt: the original request, the rejected reply and
+/// what was wrong with it.
+pub(super) fn repair_prompt(user_prompt: &str, reply: &str, problems: &[String]) -> String {
+    format!(
+        "{user_prompt}\n\n---\nYour previous reply was rejected:\n{reply}\n\nProblems:\n- {}\n\nReply again with only the corrected JSON object.",
+        problems.join("\n- ")
+    )
+}
+
+/// System prompt for providers without native JSON output: the schema is spelled out.
+pub(super) fn prompt_with_schema(system_prompt: &str, output: &JsonOutput) -> String {
+    let schema = serde_json::to_string_pretty(&output.schema).unwrap_or_default();
+    format!(
+        "{system_prompt}\n\nReply with a single JSON object and nothing else. It must match this JSON schema:\n{schema}"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn parses_fenced_or_prefixed_replies() {
+        let fenced = "```json\n{\"language\":\"fr\",\"properties\":[]}\n```";
+        assert_eq!(
+            parse_reply(fenced).expect("fenced"),
+            json!({"language": "fr", "properties": []})
+        );
+        let prefixed = "Voici : {\"name\":\"A {b}\"} et voila.";
+        assert_eq!(
+            parse_reply(prefixed).expect("prefixed"),
+            json!({"name": "A {b}"})
+        );
+        assert!(parse_reply("not json").is_err());
+    }
+
+    #[test]
+    fn reports_schema_mismatches_with_their_path() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "tone": {"type": "string", "enum": ["neutral", "direct"]},
+                "level": {"type": ["integer", "null"]},
+                "tags": {"type": "array", "items": {"type": "string"}},
+                "value": {"anyOf": [{"type": "string"}, {"type": "number"}]}
+            },
+            "required": ["tone", "level", "tags", "value"],
+            "additionalProperties": false
+        });
+
+        let valid = json!({"tone": "direct", "level": null, "tags": ["a"], "value": 2.5});
+        assert!(validate(&schema, &valid).is_empty());
+
+        let invalid =
+            json!({"tone": "loud", "level": 1.5, "tags": ["a", 3], "value": true, "extra": 1});
+        let mut errors = validate(&schema, &invalid);
+        errors.sort();
+        assert_eq!(
+            errors,
+            vec![
+                "$.extra: is not allowed",
+                "$.level: expected integer or null",
+                "$.tags[1]: expected string",
+                "$.tone: must be one of \"neutral\", \"direct\"",
+                "$.value: does not match any allowed shape",
+            ]
+        );
+        assert_eq!(
+            validate(&schema, &json!({"tone": "direct"})),
+            vec![
+                "$.level: is required",
+                "$.tags: is required",
+                "$.value: is required"
+            ]
+        );
+    }
+}
@@ -0,0 +1,671 @@
+//! Local accounting of LLM requests: tokens, latency, error class and cost.
+//!
+//! Every provider attempt made by `run_llm` / `run_llm_stream` appends one row to
+//! `~/.tomosona/usage.sqlite`. The store is global rather than per workspace
+//! because provider bills and [`ProfileBudget`] limits follow the profile, not
+//! the vault. Summaries group rows by day, feature and model.
+
+use std::{fs, path::PathBuf, time::Duration};
+
+use directories::BaseDirs;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+use super::config::{BudgetAction, ProfileBudget, ProviderProfile};
+use crate::{now_ms, AppError, Result};
+
+const USAGE_DB_FILE_NAME: &str = "usage.sqlite";
+
+/// Product surface a request was made for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LlmFeature {
+    Chat,
+    SessionTitle,
+    HistorySummary,
+    Pulse,
+    Exploration,
+    Frontmatter,
+    AlterDraft,
+}
+
+impl LlmFeature {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Chat => "chat",
+            Self::SessionTitle => "session_title",
+            Self::HistorySummary => "history_summary",
+            Self::Pulse => "pulse",
+            Self::Exploration => "exploration",
+            Self::Frontmatter => "frontmatter",
+            Self::AlterDraft => "alter_draft",
+        }
+    }
+}
+
+/// Token counts reported by the provider; `None` when the provider did not report them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TokenUsage {
+    pub input_tokens: Option<u64>,
+    pub output_tokens: Option<u64>,
+}
+
+impl TokenUsage {
+    fn total(self) -> Option<u64> {
+        match (self.input_tokens, self.output_tokens) {
+            (None, None) => None,
+            (input, output) => Some(input.unwrap_or(0) + output.unwrap_or(0)),
+        }
+    }
+}
+
+/// One finished (or failed) request.
+#[derive(Debug, Clone)]
+pub struct UsageRecord {
+    pub created_at_ms: u64,
+    pub profile_id: String,
+    pub provider: String,
+    pub model: String,
+    pub feature: LlmFeature,
+    pub usage: TokenUsage,
+    pub latency_ms: u64,
+    pub error_class: Option<&'static str>,
+    pub cost_usd: Option<f64>,
+}
+
+impl UsageRecord {
+    pub fn new(
+        profile: &ProviderProfile,
+        feature: LlmFeature,
+        usage: TokenUsage,
+        latency: Duration,
+        error: Option<ErrorClass>,
+    ) -> Self {
+        Self {
+            created_at_ms: now_ms(),
+            profile_id: profile.id.trim().to_string(),
+            provider: profile.provider.trim().to_string(),
+            model: profile.model.trim().to_string(),
+            feature,
+            usage,
+            latency_ms: u64::try_from(latency.as_millis()).unwrap_or(u64::MAX),
+            error_class: error.map(ErrorClass::as_str),
+            cost_usd: request_cost(profile.budget.as_ref(), usage),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct UsageSummaryRow {
+    /// Local calendar day, `YYYY-MM-DD`.
+    pub day: String,
+    pub feature: String,
+    pub provider: String,
+    pub model: String,
+    pub requests: u64,
+    pub errors: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub avg_latency_ms: u64,
+    /// Sum of the costs known for this group; `None` when no request had pricing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_usd: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ProfileBudgetStatus {
+    pub profile_id: String,
+    pub label: String,
+    pub month_tokens: u64,
+    pub month_cost_usd: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monthly_limit_tokens: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monthly_limit_usd: Option<f64>,
+    pub on_exceed: BudgetAction,
+    /// Set once a monthly limit is reached.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exceeded: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageSummary {
+    pub rows: Vec<UsageSummaryRow>,
+    pub budgets: Vec<ProfileBudgetStatus>,
+}
+
+/// Coarse error class stored instead of the full provider message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorClass {
+    Canceled,
+    Budget,
+    Auth,
+    RateLimit,
+    Timeout,
+    Server,
+    Network,
+    Stream,
+    Provider,
+}
+
+impl ErrorClass {
+    /// Name stored in the `error_class` column.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Canceled => "canceled",
+            Self::Budget => "budget",
+            Self::Auth => "auth",
+            Self::RateLimit => "rate_limit",
+            Self::Timeout => "timeout",
+            Self::Server => "server",
+            Self::Network => "network",
+            Self::Stream => "stream",
+            Self::Provider => "provider",
+        }
+    }
+
+    /// Class of a failed HTTP response status.
+    pub fn from_status(status: u16) -> Self {
+        match status {
+            401 | 403 => Self::Auth,
+            408 => Self::Timeout,
+            429 => Self::RateLimit,
+            500 | 502 | 503 | 504 | 529 => Self::Server,
+            _ => Self::Provider,
+        }
+    }
+
+    /// Rate limits, timeouts, dropped connections and transient server errors are
+    /// worth another attempt on the same profile.
+    pub fn is_retryable(self) -> bool {
+        matches!(
+            self,
+            Self::RateLimit | Self::Timeout | Self::Network | Self::Server | Self::Stream
+        )
+    }
+}
+
+/// HTTP status written as `status: NNN`, `status code NNN` or `HTTP NNN` in `message`.
+///
+/// Bare numbers are ignored, so ports, ids or counts in a message never pass for a
+/// status.
+pub fn status_in_message(message: &str) -> Option<u16> {
+    let lower = message.to_ascii_lowercase();
+    for keyword in ["status code", "status", "http"] {
+        let mut from = 0;
+        while let Some(found) = lower[from..].find(keyword) {
+            let start = from + found;
+            from = start + keyword.len();
+            let preceded_by_word = lower[..start]
+                .chars()
+                .next_back()
+                .is_some_and(|ch| ch.is_alphanumeric() || ch == '_');
+            if preceded_by_word {
+                continue;
+            }
+            let rest = lower[from..].trim_start_matches([' ', ':', '=']);
+            let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+            let followed_by_word = rest[digits..]
+                .chars()
+                .next()
+                .is_some_and(|ch| ch.is_alphanumeric() || ch == '_');
+            if digits == 3 && !followed_by_word {
+                let status: u16 = rest[..3].parse().unwrap_or(0);
+                if (100..600).contains(&status) {
+                    return Some(status);
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Classifies an error known only by its message: a written HTTP status first, then
+/// known phrases.
+///
+/// Never returns [`ErrorClass::Canceled`]: a cancellation is classified where the
+/// chunk handler stops the stream, not from words in a provider message.
+pub fn classify_error(message: &str) -> ErrorClass {
+    let lower = message.to_lowercase();
+    let has = |needles: &[&str]| needles.iter().any(|needle| lower.contains(needle));
+    if let Some(status) = status_in_message(message) {
+        ErrorClass::from_status(status)
+    } else if has(&["monthly budget"]) {
+        ErrorClass::Budget
+    } else if has(&["unauthorized", "forbidden", "api key", "not authenticated"]) {
+        ErrorClass::Auth
+    } else if has(&["rate limit", "rate_limit", "too many requests"]) {
+        ErrorClass::RateLimit
+    } else if has(&["timed out", "timeout"]) {
+        ErrorClass::Timeout
+    } else if has(&[
+        "internal server error",
+        "bad gateway",
+        "service unavailable",
+        "overloaded",
+    ]) {
+        ErrorClass::Server
+    } else if has(&["connect", "dns", "not running", "not reachable"]) {
+        ErrorClass::Network
+    } else if has(&["stream failed"]) {
+        ErrorClass::Stream
+    } else {
+        ErrorClass::Provider
+    }
+}
+
+/// Cost of one request from the profile pricing; `None` without pricing or token counts.
+pub fn request_cost(budget: Option<&ProfileBudget>, usage: TokenUsage) -> Option<f64> {
+    let budget = budget?;
+    if budget.input_usd_per_million_tokens.is_none()
+        && budget.output_usd_per_million_tokens.is_none()
+    {
+        return None;
+    }
+    usage.total()?;
+    let input =
+        usage.input_tokens.unwrap_or(0) as f64 * budget.input_usd_per_million_tokens.unwrap_or(0.0);
+    let output = usage.output_tokens.unwrap_or(0) as f64
+        * budget.output_usd_per_million_tokens.unwrap_or(0.0);
+    Some((input + output) / 1_000_000.0)
+}
+
+fn usage_db_path() -> Result<PathBuf> {
+    let base_dirs = BaseDirs::new().ok_or_else(|| {
+        AppError::InvalidOperation("Could not resolve user home directory.".to_string())
+    })?;
+    Ok(base_dirs
+        .home_dir()
+        .join(".tomosona")
+        .join(USAGE_DB_FILE_NAME))
+}
+
+fn ensure_usage_schema(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+    CREATE TABLE IF NOT EXISTS llm_usage (
+      id INTEGER PRIMARY KEY AUTOINCREMENT,
+      created_at_ms INTEGER NOT NULL,
+      profile_id TEXT NOT NULL,
+      provider TEXT NOT NULL,
+      model TEXT NOT NULL,
+      feature TEXT NOT NULL,
+      input_tokens INTEGER,
+      output_tokens INTEGER,
+      latency_ms INTEGER NOT NULL,
+      error_class TEXT,
+      cost_usd REAL
+    );
+    CREATE INDEX IF NOT EXISTS idx_llm_usage_profile_time ON llm_usage(profile_id, created_at_ms);
+  "#,
+    )?;
+    Ok(())
+}
+
+pub fn open_usage_db() -> Result<Connection> {
+    let path = usage_db_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let conn = Connection::open(path)?;
+    let _ = conn.busy_timeout(Duration::from_millis(3_000));
+    ensure_usage_schema(&conn)?;
+    Ok(conn)
+}
+
+pub fn insert_usage(conn: &Connection, record: &UsageRecord) -> Result<()> {
+    conn.execute(
+        "INSERT INTO llm_usage(created_at_ms, profile_id, provider, model, feature, input_tokens, output_tokens, latency_ms, error_class, cost_usd)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        params![
+            record.created_at_ms as i64,
+            record.profile_id,
+            record.provider,
+            record.model,
+            record.feature.as_str(),
+            record.usage.input_tokens.map(|value| value as i64),
+            record.usage.output_tokens.map(|value| value as i64),
+            record.latency_ms as i64,
+            record.error_class,
+            record.cost_usd,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Start of the current local calendar month, in epoch milliseconds.
+fn current_month_start_ms(conn: &Connection) -> Result<u64> {
+    let seconds: i64 = conn.query_row(
+        "SELECT CAST(strftime('%s', 'now', 'localtime', 'start of month', 'utc') AS INTEGER)",
+        [],
+        |row| row.get(0),
+    )?;
+    Ok(seconds.max(0) as u64 * 1000)
+}
+
+/// Usage rows grouped by local day, feature and model, newest day first.
+pub fn summarize_usage(
+    conn: &Connection,
+    since_ms: Option<u64>,
+    until_ms: Option<u64>,
+) -> Result<Vec<UsageSummaryRow>> {
+    let mut stmt = conn.prepare(
+        "SELECT date(created_at_ms / 1000, 'unixepoch', 'localtime') AS day,
+                feature, provider, model,
+                COUNT(*),
+                SUM(CASE WHEN error_class IS NULL THEN 0 ELSE 1 END),
+                COALESCE(SUM(input_tokens), 0),
+                COALESCE(SUM(output_tokens), 0),
+                CAST(AVG(latency_ms) AS INTEGER),
+                SUM(cost_usd)
+         FROM llm_usage
+         WHERE created_at_ms >= ?1 AND created_at_ms < ?2
+         GROUP BY day, feature, provider, model
+         ORDER BY day DESC, feature, provider, model",
+    )?;
+    let rows = stmt.query_map(
+        params![
+            since_ms.unwrap_or(0) as i64,
+            until_ms.map(|value| value as i64).unwrap_or(i64::MAX)
+        ],
+        |row| {
+            Ok(UsageSummaryRow {
+                day: row.get(0)?,
+                feature: row.get(1)?,
+                provider: row.get(2)?,
+                model: row.get(3)?,
+                requests: row.get::<_, i64>(4)?.max(0) as u64,
+                errors: row.get::<_, i64>(5)?.max(0) as u64,
+                input_tokens: row.get::<_, i64>(6)?.max(0) as u64,
+                output_tokens: row.get::<_, i64>(7)?.max(0) as u64,
+                avg_latency_ms: row.get::<_, i64>(8)?.max(0) as u64,
+                cost_usd: row.get(9)?,
+            })
+        },
+    )?;
+    Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+}
+
+fn budget_status(
+    conn: &Connection,
+    profile: &ProviderProfile,
+    budget: &ProfileBudget,
+    month_start_ms: u64,
+) -> Result<ProfileBudgetStatus> {
+    let (tokens, cost): (i64, f64) = conn.query_row(
+        "SELECT COALESCE(SUM(COALESCE(input_tokens, 0) + COALESCE(output_tokens, 0)), 0),
+                COALESCE(SUM(cost_usd), 0.0)
+         FROM llm_usage
+         WHERE profile_id = ?1 AND created_at_ms >= ?2",
+        params![profile.id.trim(), month_start_ms as i64],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    let month_tokens = tokens.max(0) as u64;
+    let label = if profile.label.trim().is_empty() {
+        profile.id.trim()
+    } else {
+        profile.label.trim()
+    };
+    let exceeded = match (budget.monthly_limit_usd, budget.monthly_limit_tokens) {
+        (Some(limit), _) if cost >= limit => Some(format!(
+            "Monthly budget of {label} reached: ${cost:.2} of ${limit:.2} spent."
+        )),
+        (_, Some(limit)) if month_tokens >= limit => Some(format!(
+            "Monthly budget of {label} reached: {month_tokens} of {limit} tokens used."
+        )),
+        _ => None,
+    };
+    Ok(ProfileBudgetStatus {
+        profile_id: profile.id.trim().to_string(),
+        label: label.to_string(),
+        month_tokens,
+        month_cost_usd: cost,
+        monthly_limit_tokens: budget.monthly_limit_tokens,
+        monthly_limit_usd: budget.monthly_limit_usd,
+        on_exceed: budget.on_exceed,
+        exceeded,
+    })
+}
+
+/// Current-month status of every profile with a budget.
+pub fn budget_statuses(
+    conn: &Connection,
+    profiles: &[ProviderProfile],
+) -> Result<Vec<ProfileBudgetStatus>> {
+    let month_start_ms = current_month_start_ms(conn)?;
+    profiles
+        .iter()
+        .filter_map(|profile| profile.budget.as_ref().map(|budget| (profile, budget)))
+        .map(|(profile, budget)| budget_status(conn, profile, budget, month_start_ms))
+        .collect()
+}
+
+fn check_budget_in(conn: &Connection, profile: &ProviderProfile) -> Result<Option<String>> {
+    let Some(budget) = profile.budget.as_ref() else {
+        return Ok(None);
+    };
+    if budget.monthly_limit_usd.is_none() && budget.monthly_limit_tokens.is_none() {
+        return Ok(None);
+    }
+    let month_start_ms = current_month_start_ms(conn)?;
+    Ok(budget_status(conn, profile, budget, month_start_ms)?.exceeded)
+}
+
+/// Checks the monthly budget of `profile` before a request.
+///
+/// Returns the warning of an exceeded `warn` budget, or an error for an exceeded
+/// `block` budget. An unavailable usage store never blocks requests.
+pub fn check_budget(profile: &ProviderProfile) -> std::result::Result<Option<String>, String> {
+    if profile.budget.is_none() {
+        return Ok(None);
+    }
+    let exceeded = open_usage_db()
+        .and_then(|conn| check_budget_in(&conn, profile))
+        .unwrap_or(None);
+    match (
+        exceeded,
+        profile.budget.as_ref().map(|budget| budget.on_exceed),
+    ) {
+        (Some(message), Some(BudgetAction::Block)) => Err(format!(
+            "{message} Raise the limit or switch profile to continue."
+        )),
+        (exceeded, _) => Ok(exceeded),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::second_brain::config::ProfileCapabilities;
+
+    fn profile(budget: Option<ProfileBudget>) -> ProviderProfile {
+        ProviderProfile {
+            id: "paid".to_string(),
+            label: "Paid".to_string(),
+            provider: "openai".to_string(),
+            model: "gpt-4.1".to_string(),
+            api_key: "k".to_string(),
+            key_source: Default::default(),
+            default_temperature: 0.15,
+            system_prompt: String::new(),
+            base_url: None,
+            default_mode: None,
+            capabilities: ProfileCapabilities::default(),
+            mock: None,
+            budget,
+        }
+    }
+
+    fn memory_db() -> Connection {
+        let conn = Connection::open_in_memory().expect("memory db");
+        ensure_usage_schema(&conn).expect("usage schema");
+        conn
+    }
+
+    fn record(
+        feature: LlmFeature,
+        input: u64,
+        output: u64,
+        error: Option<ErrorClass>,
+    ) -> UsageRecord {
+        let budget = ProfileBudget {
+            input_usd_per_million_tokens: Some(2.0),
+            output_usd_per_million_tokens: Some(8.0),
+            ..ProfileBudget::default()
+        };
+        UsageRecord::new(
+            &profile(Some(budget)),
+            feature,
+            TokenUsage {
+                input_tokens: Some(input),
+                output_tokens: Some(output),
+            },
+            Duration::from_millis(100 + input),
+            error,
+        )
+    }
+
+    #[test]
+    fn classifies_provider_errors() {
+        let class = |message: &str| classify_error(message).as_str();
+        assert_eq!(class("Generation canceled."), "provider");
+        assert_eq!(
+            class("Model request failed: status: 429, body: \"request canceled\""),
+            "rate_limit"
+        );
+        assert_eq!(
+            class("Model request failed: HTTP 429 Too Many Requests"),
+            "rate_limit"
+        );
+        assert_eq!(class("Model request failed: 401 Unauthorized"), "auth");
+        assert_eq!(
+            class("Ollama is not running at http://localhost:11434."),
+            "network"
+        );
+        assert_eq!(class("Model stream failed: unexpected EOF"), "stream");
+        assert_eq!(
+            class("Model request failed: 503 Service Unavailable"),
+            "server"
+        );
+        assert_eq!(class("Model request failed: bad model"), "provider");
+    }
+
+    #[test]
+    fn reads_statuses_only_next_to_status_or_http() {
+        assert_eq!(
+            status_in_message("ResponseFailedStatus { status: 503, body: \"\" }"),
+            Some(503)
+        );
+        assert_eq!(
+            status_in_message("OpenAI Codex returned HTTP 429 Too Many Requests."),
+            Some(429)
+        );
+        assert_eq!(status_in_message("status code 401"), Some(401));
+        assert_eq!(
+            status_in_message("model gpt-500 at http://localhost:5000"),
+            None
+        );
+        assert_eq!(status_in_message("HTTP/1.1 error 503"), None);
+        assert_eq!(status_in_message("substatus: 429"), None);
+        assert_eq!(status_in_message("status: 5031"), None);
+
+        let class = |message: &str| classify_error(message).as_str();
+        assert_eq!(
+            class("Model request failed: unknown model llama-429b"),
+            "provider"
+        );
+        assert_eq!(
+            class("Model request failed: request 5003 rejected"),
+            "provider"
+        );
+        assert_eq!(
+            class(
+                "Web call failed. Cause: ResponseFailedStatus { status: 429, body: \"slow down\" }"
+            ),
+            "rate_limit"
+        );
+        assert_eq!(
+            class("Model request failed: status: 400, body: \"timeout must be a number\""),
+            "provider"
+        );
+    }
+
+    #[test]
+    fn costs_requests_from_profile_pricing() {
+        let usage = TokenUsage {
+            input_tokens: Some(1_000),
+            output_tokens: Some(500),
+        };
+        let budget = ProfileBudget {
+            input_usd_per_million_tokens: Some(2.0),
+            output_usd_per_million_tokens: Some(8.0),
+            ..ProfileBudget::default()
+        };
+        let cost = request_cost(Some(&budget), usage).expect("cost");
+        assert!((cost - 0.006).abs() < 1e-12);
+        assert_eq!(request_cost(None, usage), None);
+        assert_eq!(request_cost(Some(&budget), TokenUsage::default()), None);
+    }
+
+    #[test]
+    fn summarizes_usage_by_day_feature_and_model() {
+        let conn = memory_db();
+        insert_usage(&conn, &record(LlmFeature::Chat, 1_000, 500, None)).expect("insert");
+        insert_usage(
+            &conn,
+            &record(LlmFeature::Chat, 3_000, 500, Some(ErrorClass::RateLimit)),
+        )
+        .expect("insert");
+        insert_usage(&conn, &record(LlmFeature::Pulse, 10, 20, None)).expect("insert");
+
+        let rows = summarize_usage(&conn, None, None).expect("summary");
+        assert_eq!(rows.len(), 2);
+        let chat = &rows[0];
+        assert_eq!(chat.feature, "chat");
+        assert_eq!(chat.model, "gpt-4.1");
+        assert_eq!(chat.requests, 2);
+        assert_eq!(chat.errors, 1);
+        assert_eq!(chat.input_tokens, 4_000);
+        assert_eq!(chat.avg_latency_ms, 2_100);
+        assert!((chat.cost_usd.expect("cost") - 0.016).abs() < 1e-12);
+        assert_eq!(rows[1].feature, "pulse");
+
+        assert!(summarize_usage(&conn, Some(u64::MAX / 2), None)
+            .expect("empty summary")
+            .is_empty());
+    }
+
+    #[test]
+    fn reports_exceeded_monthly_budgets() {
+        let conn = memory_db();
+        insert_usage(&conn, &record(LlmFeature::Chat, 600_000, 100_000, None)).expect("insert");
+
+        let mut limited = profile(Some(ProfileBudget {
+            monthly_limit_usd: Some(5.0),
+            monthly_limit_tokens: Some(1_000_000),
+            on_exceed: BudgetAction::Block,
+            ..ProfileBudget::default()
+        }));
+        assert_eq!(check_budget_in(&conn, &limited).expect("budget"), None);
+
+        limited.budget.as_mut().expect("budget").monthly_limit_usd = Some(2.0);
+        assert_eq!(
+            check_budget_in(&conn, &limited).expect("budget").as_deref(),
+            Some("Monthly budget of Paid reached: $2.00 of $2.00 spent.")
+        );
+
+        limited.budget.as_mut().expect("budget").monthly_limit_usd = None;
+        limited
+            .budget
+            .as_mut()
+            .expect("budget")
+            .monthly_limit_tokens = Some(700_000);
+        let statuses = budget_statuses(&conn, &[limited, profile(None)]).expect("statuses");
+        assert_eq!(statuses.len(), 1);
+        assert_eq!(statuses[0].month_tokens, 700_000);
+        assert_eq!(
+            statuses[0].exceeded.as_deref(),
+            Some("Monthly budget of Paid reached: 700000 of 700000 tokens used.")
+        );
+    }
+}
@@ -0,0 +1,681 @@
+//! Secret store for provider API keys.
+//!
+//! `conf.json` never holds keys. A profile points at its key through an
+//! [`ApiKeySource`]: an environment variable, a command printing the key
+//! (`api_key_cmd`, e.g. `pass show openai`), or an entry of the encrypted vault in
+//! `~/.tomosona/secrets.vault.json`.
+//!
+//! Vault entries are sealed with ChaCha20-Poly1305 under a key derived with
+//! PBKDF2-HMAC-SHA256 from either a passphrase or a machine-bound key. The machine
+//! key is a random file in the user's local data directory, mixed with the OS
+//! machine id where one exists, so syncing or backing up `~/.tomosona` alone does
+//! not carry what decrypts the vault. It lives under the same home directory, so it
+//! does not protect against anyone who can read that whole directory; use a
+//! passphrase for that.
+//! A passphrase vault stays locked until `unlock_secret_store` is called; the derived
+//! key then lives in process memory only.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    num::NonZeroU32,
+    path::PathBuf,
+    process::Command,
+    sync::{Mutex, OnceLock},
+};
+
+use atomicwrites::{AllowOverwrite, AtomicFile};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use directories::BaseDirs;
+use ring::{
+    aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN},
+    pbkdf2,
+    rand::{SecureRandom, SystemRandom},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{AppError, Result};
+
+const VAULT_FILE: &str = "secrets.vault.json";
+const MACHINE_KEY_FILE: &str = "secrets.key";
+const VAULT_VERSION: u32 = 1;
+const KEY_LEN: usize = 32;
+const PASSPHRASE_ITERATIONS: u32 = 600_000;
+/// The machine key is already random, so stretching it buys nothing.
+const MACHINE_ITERATIONS: u32 = 1;
+const MIN_PASSPHRASE_CHARS: usize = 8;
+const CHECK_AAD: &[u8] = b"tomosona-vault-check";
+const CHECK_PLAINTEXT: &[u8] = b"tomosona";
+const LOCKED_MESSAGE: &str = "The secret store is locked. Unlock it in Settings.";
+
+/// Where a profile reads its API key from, in priority order: environment variable,
+/// command, then vault entry.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiKeySource {
+    /// Id of the vault entry holding the key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key_secret: Option<String>,
+    /// Environment variable holding the key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key_env: Option<String>,
+    /// Shell command printing the key on stdout.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key_cmd: Option<String>,
+}
+
+impl ApiKeySource {
+    pub fn is_configured(&self) -> bool {
+        self.env().is_some() || self.cmd().is_some() || self.secret().is_some()
+    }
+
+    /// Whether the key comes from outside the vault, so a typed key is not stored.
+    pub fn is_external(&self) -> bool {
+        self.env().is_some() || self.cmd().is_some()
+    }
+
+    fn secret(&self) -> Option<&str> {
+        non_empty(self.api_key_secret.as_deref())
+    }
+
+    fn env(&self) -> Option<&str> {
+        non_empty(self.api_key_env.as_deref())
+    }
+
+    fn cmd(&self) -> Option<&str> {
+        non_empty(self.api_key_cmd.as_deref())
+    }
+}
+
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|value| !value.is_empty())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VaultProtection {
+    Machine,
+    Passphrase,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct VaultFile {
+    version: u32,
+    protection: VaultProtection,
+    salt: String,
+    iterations: u32,
+    /// Known value sealed with the vault key, used to verify passphrases.
+    check: String,
+    #[serde(default)]
+    entries: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SecretStoreStatus {
+    pub path: String,
+    pub exists: bool,
+    pub protection: VaultProtection,
+    pub locked: bool,
+    pub secret_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UnlockSecretStorePayload {
+    pub passphrase: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SetSecretStorePassphrasePayload {
+    /// New passphrase; `None` switches back to the machine-bound key.
+    #[serde(default)]
+    pub passphrase: Option<String>,
+}
+
+/// Vault key derived in this process, tied to the salt it was derived with.
+struct UnlockedKey {
+    salt: String,
+    key: [u8; KEY_LEN],
+}
+
+fn unlocked_key() -> &'static Mutex<Option<UnlockedKey>> {
+    static KEY: OnceLock<Mutex<Option<UnlockedKey>>> = OnceLock::new();
+    KEY.get_or_init(|| Mutex::new(None))
+}
+
+fn command_cache() -> &'static Mutex<HashMap<String, String>> {
+    static CACHE: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn home_dirs() -> Result<BaseDirs> {
+    BaseDirs::new().ok_or_else(|| {
+        AppError::InvalidOperation("Could not resolve user home directory.".to_string())
+    })
+}
+
+fn vault_path() -> Result<PathBuf> {
+    Ok(home_dirs()?.home_dir().join(".tomosona").join(VAULT_FILE))
+}
+
+fn machine_key_path() -> Result<PathBuf> {
+    Ok(home_dirs()?
+        .data_local_dir()
+        .join("tomosona")
+        .join(MACHINE_KEY_FILE))
+}
+
+fn random_bytes<const N: usize>() -> Result<[u8; N]> {
+    let mut bytes = [0u8; N];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| AppError::OperationFailed)?;
+    Ok(bytes)
+}
+
+fn decode(value: &str) -> Result<Vec<u8>> {
+    STANDARD
+        .decode(value)
+        .map_err(|_| AppError::InvalidOperation("The secret store is corrupted.".to_string()))
+}
+
+fn derive_key(secret: &[u8], salt: &[u8], iterations: u32) -> [u8; KEY_LEN] {
+    let mut key = [0u8; KEY_LEN];
+    let iterations = NonZeroU32::new(iterations).unwrap_or(NonZeroU32::MIN);
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        iterations,
+        salt,
+        secret,
+        &mut key,
+    );
+    key
+}
+
+fn aead_key(key: &[u8; KEY_LEN]) -> LessSafeKey {
+    LessSafeKey::new(UnboundKey::new(&CHACHA20_POLY1305, key).expect("32-byte key"))
+}
+
+/// Encrypts `plaintext` bound to `aad`, as base64 of nonce followed by ciphertext.
+fn seal(key: &[u8; KEY_LEN], aad: &[u8], plaintext: &[u8]) -> Result<String> {
+    let nonce_bytes = random_bytes::<NONCE_LEN>()?;
+    let mut buffer = plaintext.to_vec();
+    aead_key(key)
+        .seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce_bytes),
+            Aad::from(aad),
+            &mut buffer,
+        )
+        .map_err(|_| AppError::OperationFailed)?;
+    let mut sealed = nonce_bytes.to_vec();
+    sealed.extend_from_slice(&buffer);
+    Ok(STANDARD.encode(sealed))
+}
+
+/// Decrypts a value produced by [`seal`]; `None` when the key or `aad` do not match.
+fn open(key: &[u8; KEY_LEN], aad: &[u8], sealed: &str) -> Result<Option<Vec<u8>>> {
+    let sealed = decode(sealed)?;
+    if sealed.len() < NONCE_LEN {
+        return Ok(None);
+    }
+    let (nonce_bytes, ciphertext) = sealed.split_at(NONCE_LEN);
+    let nonce =
+        Nonce::try_assume_unique_for_key(nonce_bytes).map_err(|_| AppError::OperationFailed)?;
+    let mut buffer = ciphertext.to_vec();
+    Ok(aead_key(key)
+        .open_in_place(nonce, Aad::from(aad), &mut buffer)
+        .ok()
+        .map(|plaintext| plaintext.to_vec()))
+}
+
+impl VaultFile {
+    fn create(
+        protection: VaultProtection,
+        secret: &[u8],
+        iterations: u32,
+    ) -> Result<(Self, [u8; KEY_LEN])> {
+        let salt = random_bytes::<16>()?;
+        let key = derive_key(secret, &salt, iterations);
+        let vault = Self {
+            version: VAULT_VERSION,
+            protection,
+            salt: STANDARD.encode(salt),
+            iterations,
+            check: seal(&key, CHECK_AAD, CHECK_PLAINTEXT)?,
+            entries: BTreeMap::new(),
+        };
+        Ok((vault, key))
+    }
+
+    /// Derives the vault key from `secret` and verifies it against the check value.
+    fn unlock_with(&self, secret: &[u8]) -> Result<Option<[u8; KEY_LEN]>> {
+        let key = derive_key(secret, &decode(&self.salt)?, self.iterations);
+        let verified = open(&key, CHECK_AAD, &self.check)?.as_deref() == Some(CHECK_PLAINTEXT);
+        Ok(verified.then_some(key))
+    }
+
+    fn read_entry(&self, key: &[u8; KEY_LEN], id: &str) -> Result<Option<String>> {
+        let Some(sealed) = self.entries.get(id) else {
+            return Ok(None);
+        };
+        let plaintext = open(key, id.as_bytes(), sealed)?.ok_or_else(|| {
+            AppError::InvalidOperation(format!("Secret {id} could not be decrypted."))
+        })?;
+        String::from_utf8(plaintext)
+            .map(Some)
+            .map_err(|_| AppError::InvalidOperation(format!("Secret {id} is not valid text.")))
+    }
+
+    fn write_entry(&mut self, key: &[u8; KEY_LEN], id: &str, value: &str) -> Result<()> {
+        let sealed = seal(key, id.as_bytes(), value.as_bytes())?;
+        self.entries.insert(id.to_string(), sealed);
+        Ok(())
+    }
+
+    /// Re-encrypts every entry under a new protection.
+    fn rekey(
+        &self,
+        key: &[u8; KEY_LEN],
+        protection: VaultProtection,
+        secret: &[u8],
+        iterations: u32,
+    ) -> Result<(Self, [u8; KEY_LEN])> {
+        let (mut next, next_key) = Self::create(protection, secret, iterations)?;
+        for id in self.entries.keys() {
+            if let Some(value) = self.read_entry(key, id)? {
+                next.write_entry(&next_key, id, &value)?;
+            }
+        }
+        Ok((next, next_key))
+    }
+}
+
+fn read_vault() -> Result<Option<VaultFile>> {
+    let path = vault_path()?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let raw = fs::read_to_string(path)?;
+    let vault: VaultFile = serde_json::from_str(&raw)
+        .map_err(|_| AppError::InvalidOperation("The secret store is corrupted.".to_string()))?;
+    if vault.version > VAULT_VERSION {
+        return Err(AppError::InvalidOperation(
+            "The secret store was written by a newer version of Tomosona.".to_string(),
+        ));
+    }
+    Ok(Some(vault))
+}
+
+fn write_private_file(path: &PathBuf, bytes: &[u8]) -> Result<()> {
+    let parent = path.parent().ok_or(AppError::InvalidPath)?;
+    fs::create_dir_all(parent)?;
+    AtomicFile::new(path, AllowOverwrite)
+        .write(|file| {
+            use std::io::Write;
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                file.set_permissions(fs::Permissions::from_mode(0o600))?;
+            }
+            file.write_all(bytes)?;
+            file.sync_all()
+        })
+        .map_err(|_: atomicwrites::Error<std::io::Error>| AppError::OperationFailed)
+}
+
+fn write_vault(vault: &VaultFile) -> Result<()> {
+    let json = serde_json::to_string_pretty(vault).map_err(|_| AppError::OperationFailed)?;
+    write_private_file(&vault_path()?, format!("{json}\n").as_bytes())
+}
+
+/// Reads the machine id of the OS where one is exposed as a file.
+fn machine_id() -> Option<String> {
+    ["/etc/machine-id", "/var/lib/dbus/machine-id"]
+        .iter()
+        .find_map(|path| fs::read_to_string(path).ok())
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// Secret behind the machine-bound key, creating the random key file on first use.
+fn machine_secret() -> Result<Vec<u8>> {
+    let path = machine_key_path()?;
+    let mut secret = match fs::read(&path) {
+        Ok(bytes) if bytes.len() == KEY_LEN => bytes,
+        Ok(_) => {
+            return Err(AppError::InvalidOperation(
+                "The machine key of the secret store is corrupted.".to_string(),
+            ))
+        }
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            let machine_vault =
+                read_vault()?.is_some_and(|vault| vault.protection == VaultProtection::Machine);
+            if machine_vault {
+                return Err(AppError::InvalidOperation(
+                    "The machine key of the secret store is missing.".to_string(),
+                ));
+            }
+            let bytes = random_bytes::<KEY_LEN>()?;
+            write_private_file(&path, &bytes)?;
+            bytes.to_vec()
+        }
+        Err(err) => return Err(err.into()),
+    };
+    if let Some(id) = machine_id() {
+        secret.extend_from_slice(id.as_bytes());
+    }
+    Ok(secret)
+}
+
+/// Key of `vault`: derived from the machine key, or the one cached by an unlock.
+fn vault_key(vault: &VaultFile) -> Result<[u8; KEY_LEN]> {
+    match vault.protection {
+        VaultProtection::Machine => vault.unlock_with(&machine_secret()?)?.ok_or_else(|| {
+            AppError::InvalidOperation(
+                "The secret store was created on another machine.".to_string(),
+            )
+        }),
+        VaultProtection::Passphrase => {
+            let guard = unlocked_key()
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            guard
+                .as_ref()
+                .filter(|unlocked| unlocked.salt == vault.salt)
+                .map(|unlocked| unlocked.key)
+                .ok_or_else(|| AppError::InvalidOperation(LOCKED_MESSAGE.to_string()))
+        }
+    }
+}
+
+fn remember_key(vault: &VaultFile, key: [u8; KEY_LEN]) {
+    let mut guard = unlocked_key()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    *guard = Some(UnlockedKey {
+        salt: vault.salt.clone(),
+        key,
+    });
+}
+
+/// Opens the vault for writing, creating a machine-bound one when none exists yet.
+fn load_or_create_vault() -> Result<(VaultFile, [u8; KEY_LEN])> {
+    match read_vault()? {
+        Some(vault) => {
+            let key = vault_key(&vault)?;
+            Ok((vault, key))
+        }
+        None => VaultFile::create(
+            VaultProtection::Machine,
+            &machine_secret()?,
+            MACHINE_ITERATIONS,
+        ),
+    }
+}
+
+/// Stores `value` under `id`, replacing any previous value.
+pub fn store_secret(id: &str, value: &str) -> Result<()> {
+    let (mut vault, key) = load_or_create_vault()?;
+    if vault.read_entry(&key, id)?.as_deref() == Some(value) {
+        return Ok(());
+    }
+    vault.write_entry(&key, id, value)?;
+    write_vault(&vault)
+}
+
+/// Reads the value stored under `id`.
+pub fn read_secret(id: &str) -> Result<String> {
+    let vault = read_vault()?;
+    let vault = vault
+        .as_ref()
+        .filter(|vault| vault.entries.contains_key(id))
+        .ok_or_else(|| {
+            AppError::InvalidOperation(format!("Secret {id} is missing from the secret store."))
+        })?;
+    let key = vault_key(vault)?;
+    vault.read_entry(&key, id)?.ok_or(AppError::OperationFailed)
+}
+
+/// Deletes the entries `keep` rejects; entries are independent, so this works locked.
+pub fn retain_secrets(keep: impl Fn(&str) -> bool) -> Result<()> {
+    let Some(mut vault) = read_vault()? else {
+        return Ok(());
+    };
+    let before = vault.entries.len();
+    vault.entries.retain(|id, _| keep(id));
+    if vault.entries.len() == before {
+        return Ok(());
+    }
+    write_vault(&vault)
+}
+
+fn run_key_command(command: &str) -> Result<String> {
+    if let Some(cached) = command_cache()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get(command)
+    {
+        return Ok(cached.clone());
+    }
+    #[cfg(windows)]
+    let output = Command::new("cmd").args(["/C", command]).output();
+    #[cfg(not(windows))]
+    let output = Command::new("sh").args(["-c", command]).output();
+    let output = output.map_err(|err| {
+        AppError::InvalidOperation(format!("api_key_cmd could not be started: {err}"))
+    })?;
+    if !output.status.success() {
+        return Err(AppError::InvalidOperation(format!(
+            "api_key_cmd exited with {}.",
+            output.status
+        )));
+    }
+    let key = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if key.is_empty() {
+        return Err(AppError::InvalidOperation(
+            "api_key_cmd printed no key.".to_string(),
+        ));
+    }
+    command_cache()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .insert(command.to_string(), key.clone());
+    Ok(key)
+}
+
+/// Resolves the key of a profile: a plaintext `api_key` still held in memory wins,
+/// then the sources of `source` in priority order. Command output is cached for the
+/// process lifetime so password managers are not prompted on every request.
+pub fn resolve_api_key(api_key: &str, source: &ApiKeySource) -> Result<String> {
+    let api_key = api_key.trim();
+    if !api_key.is_empty() {
+        return Ok(api_key.to_string());
+    }
+    if let Some(name) = source.env() {
+        return std::env::var(name)
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .ok_or_else(|| {
+                AppError::InvalidOperation(format!("Environment variable {name} is not set."))
+            });
+    }
+    if let Some(command) = source.cmd() {
+        return run_key_command(command);
+    }
+    if let Some(id) = source.secret() {
+        return read_secret(id);
+    }
+    Ok(String::new())
+}
+
+#[tauri::command]
+pub fn read_secret_store_status() -> Result<SecretStoreStatus> {
+    let path = vault_path()?;
+    let vault = read_vault()?;
+    let protection = vault
+        .as_ref()
+        .map(|vault| vault.protection)
+        .unwrap_or(VaultProtection::Machine);
+    let locked = vault.as_ref().is_some_and(|vault| {
+        vault.protection == VaultProtection::Passphrase && vault_key(vault).is_err()
+    });
+    Ok(SecretStoreStatus {
+        path: path.to_string_lossy().to_string(),
+        exists: vault.is_some(),
+        protection,
+        locked,
+        secret_ids: vault
+            .map(|vault| vault.entries.into_keys().collect())
+            .unwrap_or_default(),
+    })
+}
+
+#[tauri::command]
+pub fn unlock_secret_store(payload: UnlockSecretStorePayload) -> Result<SecretStoreStatus> {
+    let vault = read_vault()?
+        .filter(|vault| vault.protection == VaultProtection::Passphrase)
+        .ok_or_else(|| {
+            AppError::InvalidOperation(
+                "The secret store is not protected by a passphrase.".to_string(),
+            )
+        })?;
+    let key = vault
+        .unlock_with(payload.passphrase.as_bytes())?
+        .ok_or_else(|| AppError::InvalidOperation("Wrong passphrase.".to_string()))?;
+    remember_key(&vault, key);
+    read_secret_store_status()
+}
+
+/// Forgets the unlocked passphrase key and the cached `api_key_cmd` output.
+#[tauri::command]
+pub fn lock_secret_store() -> Result<SecretStoreStatus> {
+    *unlocked_key()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+    command_cache()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clear();
+    read_secret_store_status()
+}
+
+/// Protects the vault with a passphrase, or with the machine-bound key when none is
+/// given. The vault must be readable, so a passphrase vault has to be unlocked first.
+#[tauri::command]
+pub fn set_secret_store_passphrase(
+    payload: SetSecretStorePassphrasePayload,
+) -> Result<SecretStoreStatus> {
+    let passphrase = payload.passphrase.filter(|value| !value.is_empty());
+    if passphrase
+        .as_deref()
+        .is_some_and(|value| value.chars().count() < MIN_PASSPHRASE_CHARS)
+    {
+        return Err(AppError::InvalidOperation(format!(
+            "The passphrase must have at least {MIN_PASSPHRASE_CHARS} characters."
+        )));
+    }
+    let (vault, key) = load_or_create_vault()?;
+    let (next, next_key) = match passphrase.as_deref() {
+        Some(passphrase) => vault.rekey(
+            &key,
+            VaultProtection::Passphrase,
+            passphrase.as_bytes(),
+            PASSPHRASE_ITERATIONS,
+        )?,
+        None => vault.rekey(
+            &key,
+            VaultProtection::Machine,
+            &machine_secret()?,
+            MACHINE_ITERATIONS,
+        )?,
+    };
+    write_vault(&next)?;
+    remember_key(&next, next_key);
+    read_secret_store_status()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seals_entries_bound_to_their_id() {
+        let (mut vault, key) =
+            VaultFile::create(VaultProtection::Passphrase, b"correct horse", 2).expect("vault");
+        vault
+            .write_entry(&key, "llm/openai", "sk-test")
+            .expect("write");
+        assert_eq!(
+            vault
+                .read_entry(&key, "llm/openai")
+                .expect("read")
+                .as_deref(),
+            Some("sk-test")
+        );
+        assert!(!vault.entries["llm/openai"].contains("sk-test"));
+
+        let copied = vault.entries["llm/openai"].clone();
+        vault.entries.insert("llm/other".to_string(), copied);
+        assert!(vault.read_entry(&key, "llm/other").is_err());
+        assert_eq!(vault.read_entry(&key, "missing").expect("read"), None);
+    }
+
+    #[test]
+    fn verifies_passphrases_and_rekeys_entries() {
+        let (mut vault, key) =
+            VaultFile::create(VaultProtection::Passphrase, b"correct horse", 2).expect("vault");
+        vault
+            .write_entry(&key, "llm/openai", "sk-test")
+            .expect("write");
+        assert_eq!(
+            vault.unlock_with(b"correct horse").expect("unlock"),
+            Some(key)
+        );
+        assert_eq!(vault.unlock_with(b"wrong horse").expect("unlock"), None);
+
+        let (machine, machine_key) = vault
+            .rekey(&key, VaultProtection::Machine, b"machine secret", 1)
+            .expect("rekey");
+        assert_eq!(machine.protection, VaultProtection::Machine);
+        assert!(machine
+            .unlock_with(b"correct horse")
+            .expect("unlock")
+            .is_none());
+        assert_eq!(
+            machine
+                .read_entry(&machine_key, "llm/openai")
+                .expect("read")
+                .as_deref(),
+            Some("sk-test")
+        );
+    }
+
+    #[test]
+    fn resolves_keys_from_memory_env_and_command() {
+        let source = ApiKeySource {
+            api_key_env: Some("TOMOSONA_TEST_SECRET_KEY".to_string()),
+            ..ApiKeySource::default()
+        };
+        assert_eq!(
+            resolve_api_key(" sk-inline ", &source).expect("inline"),
+            "sk-inline"
+        );
+        std::env::set_var("TOMOSONA_TEST_SECRET_KEY", "sk-env");
+        assert_eq!(resolve_api_key("", &source).expect("env"), "sk-env");
+        std::env::remove_var("TOMOSONA_TEST_SECRET_KEY");
+        assert!(resolve_api_key("", &source).is_err());
+        assert!(source.is_external());
+
+        #[cfg(unix)]
+        {
+            let source = ApiKeySource {
+                api_key_cmd: Some("printf 'sk-cmd\\n'".to_string()),
+                ..ApiKeySource::default()
+            };
+            assert_eq!(resolve_api_key("", &source).expect("cmd"), "sk-cmd");
+        }
+        assert_eq!(
+            resolve_api_key("", &ApiKeySource::default()).expect("none"),
+            ""
+        );
+    }
+}
@@ -22,7 +22,7 @@ use rusqlite::{ffi::sqlite3_auto_extension, params, Connection, OptionalExtensio
 use serde::Serialize;
 use sqlite_vec::sqlite3_vec_init;
 
-use crate::settings;
+use crate::{secrets, settings};
 
 const EMBEDDING_MODEL_NAME: &str = "lightonai/modernbert-embed-large";
 #[derive(Default)]
@@ -289,9 +289,11 @@ pub fn embed_texts(texts: &[String]) -> Result<Vec<Vec<f32>>, String> {
     let embeddings = settings::load_embeddings_for_runtime()
         .map_err(|_| "Semantic embedding settings are invalid.".to_string())?;
     if embeddings.mode.trim().eq_ignore_ascii_case("external") {
-        let profile = embeddings
+        let mut profile = embeddings
             .external
             .ok_or_else(|| "Semantic embedding settings are invalid.".to_string())?;
+        profile.api_key = secrets::resolve_api_key(&profile.api_key, &profile.key_source)
+            .map_err(|err| err.to_string())?;
         return embed_texts_external(texts, &profile);
     }
     embed_texts_internal(texts)
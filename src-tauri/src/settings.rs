@@ -3,16 +3,26 @@
 //! This module centralizes validation, readback, and save semantics for:
 //! - `llm` provider profiles used by second-brain chat features.
 //! - `embeddings` runtime configuration used by semantic indexing/search.
+//!
+//! API keys never reach `conf.json`: typed keys are moved into the secret store
+//! (see [`crate::secrets`]) and profiles only keep their [`ApiKeySource`]. Keys still
+//! found in plain text in an older file are migrated once at startup by
+//! [`migrate_plaintext_keys`].
 
-use std::{fs, path::PathBuf};
+use std::{fs, path::PathBuf, sync::OnceLock};
 
 use directories::BaseDirs;
 use serde::{Deserialize, Serialize};
 
 use crate::second_brain::config::{
-    validate_config as validate_llm_config, ProfileCapabilities, ProviderProfile, SecondBrainConfig,
+    validate_config as validate_llm_config, MockProviderSettings, ModelFallbacks, ModelRoles,
+    ProfileBudget, ProfileCapabilities, ProviderProfile, RequestPolicy, SecondBrainConfig,
 };
+use crate::second_brain::local_models::{self, LocalServerKind};
+use crate::second_brain::mock_llm::MOCK_PROVIDER;
 use crate::second_brain::model_discovery::{discover_models as discover_compatible_models, DiscoveredModel};
+use crate::second_brain::prompt_packs::PromptLocale;
+use crate::secrets::{self, ApiKeySource};
 use crate::{AppError, Result};
 
 const SETTINGS_FILE: &str = "conf.json";
@@ -24,6 +34,11 @@ const ALTER_DEFAULT_MODE_LAST_USED: &str = "last_used";
 const ALTER_DEFAULT_INTENSITY_LIGHT: &str = "light";
 const ALTER_DEFAULT_INTENSITY_BALANCED: &str = "balanced";
 const ALTER_DEFAULT_INTENSITY_STRONG: &str = "strong";
+const LLM_SECRET_PREFIX: &str = "llm/";
+const EMBEDDINGS_SECRET_PREFIX: &str = "embeddings/";
+
+/// Outcome of the startup key migration, kept to report a failure in Settings.
+static KEY_MIGRATION: OnceLock<std::result::Result<(), String>> = OnceLock::new();
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EmbeddingProviderProfile {
@@ -31,7 +46,11 @@ pub struct EmbeddingProviderProfile {
     pub label: String,
     pub provider: String,
     pub model: String,
+    /// Runtime-only key, like [`ProviderProfile::api_key`].
+    #[serde(default, skip_serializing)]
     pub api_key: String,
+    #[serde(flatten)]
+    pub key_source: ApiKeySource,
     #[serde(default)]
     pub base_url: Option<String>,
 }
@@ -83,18 +102,27 @@ pub struct LlmProfileView {
     pub label: String,
     pub provider: String,
     pub model: String,
-    pub api_key: String,
+    /// Whether a key is saved or referenced; the key itself is never sent back.
+    pub has_api_key: bool,
+    #[serde(flatten)]
+    pub key_source: ApiKeySource,
     pub default_temperature: f64,
     pub system_prompt: String,
     pub base_url: Option<String>,
     pub default_mode: Option<String>,
     pub capabilities: ProfileCapabilities,
+    pub mock: Option<MockProviderSettings>,
+    pub budget: Option<ProfileBudget>,
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct LlmConfigView {
     pub active_profile: String,
     pub profiles: Vec<LlmProfileView>,
+    pub prompt_language: Option<PromptLocale>,
+    pub model_roles: ModelRoles,
+    pub model_fallbacks: ModelFallbacks,
+    pub request_policy: RequestPolicy,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -103,7 +131,9 @@ pub struct EmbeddingProfileView {
     pub label: String,
     pub provider: String,
     pub model: String,
-    pub api_key: String,
+    pub has_api_key: bool,
+    #[serde(flatten)]
+    pub key_source: ApiKeySource,
     pub base_url: Option<String>,
 }
 
@@ -120,6 +150,8 @@ pub struct AppSettingsView {
     pub llm: Option<LlmConfigView>,
     pub embeddings: EmbeddingsSettingsView,
     pub alters: AltersSettings,
+    /// Why plaintext keys could not be moved into the secret store, while some remain.
+    pub key_migration_error: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -129,9 +161,13 @@ pub struct SaveLlmProfileInput {
     pub provider: String,
     pub model: String,
     pub api_key: Option<String>,
+    /// Environment variable or command to read the key from instead of a typed key.
+    #[serde(flatten)]
+    pub key_source: ApiKeySource,
     pub default_temperature: f64,
     #[serde(default)]
     pub system_prompt: String,
+    /// Kept for older clients: the saved key is kept whenever no new key is given.
     #[serde(default)]
     pub preserve_existing_api_key: bool,
     #[serde(default)]
@@ -140,12 +176,50 @@ pub struct SaveLlmProfileInput {
     pub default_mode: Option<String>,
     #[serde(default)]
     pub capabilities: ProfileCapabilities,
+    /// `None` keeps the saved script of a mock profile.
+    #[serde(default)]
+    pub mock: Option<MockProviderSettings>,
+    /// `None` keeps the saved budget; a budget without prices or limits removes it.
+    #[serde(default)]
+    pub budget: Option<ProfileBudget>,
+}
+
+/// Prompt language picked in Settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptLanguageChoice {
+    /// Follow the language of the material sent to the model.
+    Auto,
+    Fr,
+    En,
+}
+
+impl PromptLanguageChoice {
+    fn locale(self) -> Option<PromptLocale> {
+        match self {
+            Self::Auto => None,
+            Self::Fr => Some(PromptLocale::Fr),
+            Self::En => Some(PromptLocale::En),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct SaveLlmConfigInput {
     pub active_profile: String,
     pub profiles: Vec<SaveLlmProfileInput>,
+    /// `None` keeps the saved language, like `model_roles`.
+    #[serde(default)]
+    pub prompt_language: Option<PromptLanguageChoice>,
+    /// `None` keeps the saved roles, so clients unaware of roles do not clear them.
+    #[serde(default)]
+    pub model_roles: Option<ModelRoles>,
+    /// `None` keeps the saved fallbacks, like `model_roles`.
+    #[serde(default)]
+    pub model_fallbacks: Option<ModelFallbacks>,
+    /// `None` keeps the saved timeouts and retries.
+    #[serde(default)]
+    pub request_policy: Option<RequestPolicy>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -155,6 +229,8 @@ pub struct SaveEmbeddingProfileInput {
     pub provider: String,
     pub model: String,
     pub api_key: Option<String>,
+    #[serde(flatten)]
+    pub key_source: ApiKeySource,
     #[serde(default)]
     pub preserve_existing_api_key: bool,
     #[serde(default)]
@@ -181,6 +257,9 @@ pub struct DiscoverLlmModelsInput {
     pub provider: String,
     #[serde(default)]
     pub api_key: Option<String>,
+    /// Environment variable or command to test with before the profile is saved.
+    #[serde(flatten)]
+    pub key_source: ApiKeySource,
     #[serde(default)]
     pub preserve_existing_api_key: bool,
     #[serde(default)]
@@ -192,6 +271,9 @@ pub struct DiscoverEmbeddingModelsInput {
     pub profile_id: String,
     #[serde(default)]
     pub api_key: Option<String>,
+    /// Environment variable or command to test with before the profile is saved.
+    #[serde(flatten)]
+    pub key_source: ApiKeySource,
     #[serde(default)]
     pub preserve_existing_api_key: bool,
     #[serde(default)]
@@ -243,24 +325,27 @@ fn validate_base_url(base_url: &Option<String>, field_name: &str) -> Result<()>
     )))
 }
 
-fn resolve_api_key(
+/// Picks the key of a saved profile: a typed key, then an environment variable or
+/// command from the input, then the key or source already saved for the profile.
+fn resolve_key_source(
     input_value: Option<&str>,
-    preserve_existing: bool,
-    existing_value: Option<&str>,
+    input_source: &ApiKeySource,
+    existing: Option<(&str, &ApiKeySource)>,
     label: &str,
-) -> Result<String> {
-    if let Some(value) = input_value {
-        let trimmed = value.trim();
-        if !trimmed.is_empty() {
-            return Ok(trimmed.to_string());
-        }
+) -> Result<(String, ApiKeySource)> {
+    if let Some(value) = input_value.map(str::trim).filter(|value| !value.is_empty()) {
+        return Ok((value.to_string(), ApiKeySource::default()));
     }
-    if preserve_existing {
-        if let Some(existing) = existing_value {
-            let trimmed = existing.trim();
-            if !trimmed.is_empty() {
-                return Ok(trimmed.to_string());
-            }
+    let source = ApiKeySource {
+        api_key_secret: None,
+        ..input_source.clone()
+    };
+    if source.is_external() {
+        return Ok((String::new(), source));
+    }
+    if let Some((api_key, source)) = existing {
+        if !api_key.trim().is_empty() || source.is_configured() {
+            return Ok((api_key.trim().to_string(), source.clone()));
         }
     }
     Err(AppError::InvalidOperation(format!(
@@ -303,7 +388,7 @@ fn validate_embeddings(settings: &EmbeddingsSettings) -> Result<()> {
             "Embeddings model is required.".to_string(),
         ));
     }
-    if profile.api_key.trim().is_empty() {
+    if profile.api_key.trim().is_empty() && !profile.key_source.is_configured() {
         return Err(AppError::InvalidOperation(
             "Embeddings API key is required.".to_string(),
         ));
@@ -355,6 +440,119 @@ fn read_settings_file() -> Result<AppSettings> {
     Ok(settings)
 }
 
+/// Moves the API keys older versions wrote in plain text into the secret store.
+///
+/// Runs once per process, at startup. Until it succeeds the plaintext keys keep
+/// working and the next settings save retries the move; the error is reported by
+/// [`read_app_settings`] and the Second Brain config status meanwhile.
+pub fn migrate_plaintext_keys() -> std::result::Result<(), String> {
+    KEY_MIGRATION
+        .get_or_init(|| {
+            let Ok(mut settings) = read_settings_file() else {
+                // Nothing readable to migrate; read errors are reported on their own.
+                return Ok(());
+            };
+            if !has_plaintext_keys(&settings) {
+                return Ok(());
+            }
+            write_settings_file(&mut settings)
+                .map(|_| ())
+                .map_err(|err| format!("Could not move API keys into the secret store: {err}"))
+        })
+        .clone()
+}
+
+/// Why the startup key migration failed, while `settings` still holds plaintext keys.
+fn pending_key_migration_error(settings: &AppSettings) -> Option<String> {
+    match KEY_MIGRATION.get() {
+        Some(Err(message)) if has_plaintext_keys(settings) => Some(message.clone()),
+        _ => None,
+    }
+}
+
+/// Reports a failed startup key migration that still applies to `conf.json`.
+pub fn key_migration_error() -> Option<String> {
+    read_settings_file()
+        .ok()
+        .and_then(|settings| pending_key_migration_error(&settings))
+}
+
+fn has_plaintext_keys(settings: &AppSettings) -> bool {
+    settings
+        .llm
+        .profiles
+        .iter()
+        .map(|profile| (&profile.api_key, &profile.key_source))
+        .chain(
+            settings
+                .embeddings
+                .external
+                .iter()
+                .map(|profile| (&profile.api_key, &profile.key_source)),
+        )
+        .any(|(api_key, source)| !api_key.trim().is_empty() && !source.is_external())
+}
+
+/// Moves a typed key into the secret store under `secret_id` and points `source` at it.
+fn seal_api_key(secret_id: String, api_key: &mut String, source: &mut ApiKeySource) -> Result<()> {
+    if source.is_external() {
+        source.api_key_secret = None;
+        return Ok(());
+    }
+    let value = api_key.trim();
+    if value.is_empty() {
+        return Ok(());
+    }
+    secrets::store_secret(&secret_id, value)?;
+    source.api_key_secret = Some(secret_id);
+    api_key.clear();
+    Ok(())
+}
+
+/// Writes `conf.json` after sealing typed keys, then drops the profile secrets no
+/// profile references any more.
+fn write_settings_file(settings: &mut AppSettings) -> Result<PathBuf> {
+    for profile in &mut settings.llm.profiles {
+        let secret_id = format!("{LLM_SECRET_PREFIX}{}", profile.id.trim());
+        seal_api_key(secret_id, &mut profile.api_key, &mut profile.key_source)?;
+    }
+    if let Some(profile) = settings.embeddings.external.as_mut() {
+        let secret_id = format!("{EMBEDDINGS_SECRET_PREFIX}{}", profile.id.trim());
+        seal_api_key(secret_id, &mut profile.api_key, &mut profile.key_source)?;
+    }
+
+    let path = conf_path()?;
+    ensure_conf_parent(&path)?;
+    let raw = serde_json::to_string_pretty(&settings).map_err(|_| AppError::OperationFailed)?;
+    fs::write(&path, format!("{raw}\n"))?;
+
+    let referenced = settings
+        .llm
+        .profiles
+        .iter()
+        .map(|profile| &profile.key_source)
+        .chain(
+            settings
+                .embeddings
+                .external
+                .iter()
+                .map(|profile| &profile.key_source),
+        )
+        .filter_map(|source| source.api_key_secret.clone())
+        .collect::<Vec<_>>();
+    secrets::retain_secrets(|id| {
+        let owned = id.starts_with(LLM_SECRET_PREFIX) || id.starts_with(EMBEDDINGS_SECRET_PREFIX);
+        !owned || referenced.iter().any(|item| item == id)
+    })?;
+    Ok(path)
+}
+
+/// Fills the runtime `api_key` of a profile from its key source.
+fn resolve_runtime_key(api_key: &mut String, source: &ApiKeySource) -> Result<()> {
+    *api_key = secrets::resolve_api_key(api_key, source)?;
+    Ok(())
+}
+
 fn view_llm(config: &SecondBrainConfig) -> LlmConfigView {
     LlmConfigView {
         active_profile: config.active_profile.clone(),
@@ -366,14 +564,22 @@ fn view_llm(config: &SecondBrainConfig) -> LlmConfigView {
                 label: profile.label.clone(),
                 provider: profile.provider.clone(),
                 model: profile.model.clone(),
-                api_key: profile.api_key.clone(),
+                has_api_key: !profile.api_key.trim().is_empty()
+                    || profile.key_source.is_configured(),
+                key_source: profile.key_source.clone(),
                 default_temperature: profile.default_temperature,
                 system_prompt: profile.system_prompt.clone(),
                 base_url: profile.base_url.clone(),
                 default_mode: profile.default_mode.clone(),
                 capabilities: profile.capabilities.clone(),
+                mock: profile.mock.clone(),
+                budget: profile.budget.clone(),
             })
             .collect(),
+        prompt_language: config.prompt_language,
+        model_roles: config.model_roles.clone(),
+        model_fallbacks: config.model_fallbacks.clone(),
+        request_policy: config.request_policy.clone(),
     }
 }
 
@@ -388,7 +594,9 @@ fn view_embeddings(settings: &EmbeddingsSettings) -> EmbeddingsSettingsView {
                 label: profile.label.clone(),
                 provider: profile.provider.clone(),
                 model: profile.model.clone(),
-                api_key: profile.api_key.clone(),
+                has_api_key: !profile.api_key.trim().is_empty()
+                    || profile.key_source.is_configured(),
+                key_source: profile.key_source.clone(),
                 base_url: profile.base_url.clone(),
             }),
     }
@@ -422,6 +630,84 @@ fn embedding_identity(settings: &EmbeddingsSettings) -> EmbeddingIdentity {
     }
 }
 
+fn normalize_model_roles(roles: &ModelRoles) -> ModelRoles {
+    let normalize = |value: &Option<String>| {
+        value
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    };
+    ModelRoles {
+        fast: normalize(&roles.fast),
+        deep: normalize(&roles.deep),
+        summarizer: normalize(&roles.summarizer),
+        titler: normalize(&roles.titler),
+        frontmatter: normalize(&roles.frontmatter),
+        pulse: normalize(&roles.pulse),
+    }
+}
+
+/// Saved roles whose profile is still part of `profiles`.
+fn kept_model_roles(roles: &ModelRoles, profiles: &[ProviderProfile]) -> ModelRoles {
+    let known = |value: &Option<String>| {
+        value
+            .clone()
+            .filter(|id| profiles.iter().any(|profile| profile.id == id.trim()))
+    };
+    ModelRoles {
+        fast: known(&roles.fast),
+        deep: known(&roles.deep),
+        summarizer: known(&roles.summarizer),
+        titler: known(&roles.titler),
+        frontmatter: known(&roles.frontmatter),
+        pulse: known(&roles.pulse),
+    }
+}
+
+fn normalize_model_fallbacks(fallbacks: &ModelFallbacks) -> ModelFallbacks {
+    let normalize = |ids: &[String]| {
+        let mut out: Vec<String> = Vec::new();
+        for id in ids.iter().map(|id| id.trim()).filter(|id| !id.is_empty()) {
+            if !out.iter().any(|item| item == id) {
+                out.push(id.to_string());
+            }
+        }
+        out
+    };
+    ModelFallbacks {
+        chat: normalize(&fallbacks.chat),
+        fast: normalize(&fallbacks.fast),
+        deep: normalize(&fallbacks.deep),
+        summarizer: normalize(&fallbacks.summarizer),
+        titler: normalize(&fallbacks.titler),
+        frontmatter: normalize(&fallbacks.frontmatter),
+        pulse: normalize(&fallbacks.pulse),
+    }
+}
+
+/// Saved fallbacks without the profiles that are no longer part of `profiles`.
+fn kept_model_fallbacks(
+    fallbacks: &ModelFallbacks,
+    profiles: &[ProviderProfile],
+) -> ModelFallbacks {
+    let known = |ids: &[String]| {
+        ids.iter()
+            .filter(|id| profiles.iter().any(|profile| profile.id == id.trim()))
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+    ModelFallbacks {
+        chat: known(&fallbacks.chat),
+        fast: known(&fallbacks.fast),
+        deep: known(&fallbacks.deep),
+        summarizer: known(&fallbacks.summarizer),
+        titler: known(&fallbacks.titler),
+        frontmatter: known(&fallbacks.frontmatter),
+        pulse: known(&fallbacks.pulse),
+    }
+}
+
 fn apply_save_payload(
     payload: SaveAppSettingsPayload,
     existing: Option<&AppSettings>,
@@ -435,23 +721,33 @@ fn apply_save_payload(
         .iter()
         .map(|profile| {
             let provider = profile.provider.trim().to_lowercase();
-            let existing_api_key = existing_llm.and_then(|cfg| {
+            let existing_profile = existing_llm.and_then(|cfg| {
                 cfg.profiles
                     .iter()
                     .find(|item| item.id.trim() == profile.id.trim())
-                    .map(|item| item.api_key.as_str())
             });
-            let api_key = if provider == "openai-codex" {
-                String::new()
+            let existing_key =
+                existing_profile.map(|item| (item.api_key.as_str(), &item.key_source));
+            let is_mock = provider == MOCK_PROVIDER;
+            let (api_key, key_source) = if provider == "openai-codex" || is_mock {
+                (String::new(), ApiKeySource::default())
+            } else if LocalServerKind::from_provider(&provider).is_some() {
+                resolve_key_source(
+                    profile.api_key.as_deref(),
+                    &profile.key_source,
+                    existing_key,
+                    "LLM profile",
+                )
+                .unwrap_or_default()
             } else {
-                resolve_api_key(
+                resolve_key_source(
                     profile.api_key.as_deref(),
-                    profile.preserve_existing_api_key,
-                    existing_api_key,
+                    &profile.key_source,
+                    existing_key,
                     "LLM profile",
                 )?
             };
-            if provider != "openai-codex" {
+            if provider != "openai-codex" && !is_mock {
                 validate_base_url(&profile.base_url, "LLM base_url")?;
             }
             Ok(ProviderProfile {
@@ -460,9 +756,10 @@ fn apply_save_payload(
                 provider: profile.provider.trim().to_string(),
                 model: profile.model.trim().to_string(),
                 api_key,
+                key_source,
                 default_temperature: profile.default_temperature,
                 system_prompt: profile.system_prompt.trim().to_string(),
-                base_url: if provider == "openai-codex" {
+                base_url: if provider == "openai-codex" || is_mock {
                     None
                 } else {
                     profile
@@ -475,13 +772,58 @@ fn apply_save_payload(
                     .as_ref()
                     .map(|item| item.trim().to_string()),
                 capabilities: profile.capabilities.clone(),
+                mock: if is_mock {
+                    Some(
+                        profile
+                            .mock
+                            .clone()
+                            .or_else(|| existing_profile.and_then(|item| item.mock.clone()))
+                            .unwrap_or_default(),
+                    )
+                } else {
+                    None
+                },
+                budget: match &profile.budget {
+                    Some(budget) if budget.is_empty() => None,
+                    Some(budget) => Some(budget.clone()),
+                    None => existing_profile.and_then(|item| item.budget.clone()),
+                },
             })
         })
         .collect::<Result<Vec<_>>>()?;
 
+    let model_roles = match &payload.llm.model_roles {
+        Some(roles) => normalize_model_roles(roles),
+        None => existing_llm
+            .map(|cfg| kept_model_roles(&normalize_model_roles(&cfg.model_roles), &llm_profiles))
+            .unwrap_or_default(),
+    };
+    let model_fallbacks = match &payload.llm.model_fallbacks {
+        Some(fallbacks) => normalize_model_fallbacks(fallbacks),
+        None => existing_llm
+            .map(|cfg| {
+                kept_model_fallbacks(
+                    &normalize_model_fallbacks(&cfg.model_fallbacks),
+                    &llm_profiles,
+                )
+            })
+            .unwrap_or_default(),
+    };
+    let request_policy = payload
+        .llm
+        .request_policy
+        .or_else(|| existing_llm.map(|cfg| cfg.request_policy.clone()))
+        .unwrap_or_default();
     let llm = SecondBrainConfig {
         active_profile: payload.llm.active_profile.trim().to_string(),
         profiles: llm_profiles,
+        prompt_language: match payload.llm.prompt_language {
+            Some(choice) => choice.locale(),
+            None => existing_llm.and_then(|cfg| cfg.prompt_language),
+        },
+        model_roles,
+        model_fallbacks,
+        request_policy,
     };
 
     let mode = payload.embeddings.mode.trim().to_lowercase();
@@ -494,10 +836,10 @@ fn apply_save_payload(
         })?;
         let existing_key = existing_embeddings
             .filter(|item| item.id.trim() == profile.id.trim())
-            .map(|item| item.api_key.as_str());
-        let api_key = resolve_api_key(
+            .map(|item| (item.api_key.as_str(), &item.key_source));
+        let (api_key, key_source) = resolve_key_source(
             profile.api_key.as_deref(),
-            profile.preserve_existing_api_key,
+            &profile.key_source,
             existing_key,
             "Embeddings",
         )?;
@@ -508,6 +850,7 @@ fn apply_save_payload(
             provider: profile.provider.trim().to_string(),
             model: profile.model.trim().to_string(),
             api_key,
+            key_source,
             base_url: profile
                 .base_url
                 .as_ref()
@@ -534,14 +877,31 @@ fn apply_save_payload(
     Ok(settings)
 }
 
+/// Loads the LLM config with keys resolved; a key that cannot be resolved is left
+/// empty and its error is reported when the profile is used.
 pub fn load_llm_for_runtime() -> Result<SecondBrainConfig> {
-    let settings = read_settings_file()?;
+    let mut settings = read_settings_file()?;
+    for profile in &mut settings.llm.profiles {
+        let _ = resolve_runtime_key(&mut profile.api_key, &profile.key_source);
+    }
     Ok(settings.llm)
 }
 
+/// Returns the prompt language forced in settings, `None` when it follows the material.
+pub fn configured_prompt_locale() -> Option<PromptLocale> {
+    read_settings_file()
+        .ok()
+        .and_then(|settings| settings.llm.prompt_language)
+}
+
 pub fn load_embeddings_for_runtime() -> std::result::Result<EmbeddingsSettings, String> {
     match read_settings_file() {
-        Ok(settings) => Ok(settings.embeddings),
+        Ok(mut settings) => {
+            if let Some(profile) = settings.embeddings.external.as_mut() {
+                let _ = resolve_runtime_key(&mut profile.api_key, &profile.key_source);
+            }
+            Ok(settings.embeddings)
+        }
         Err(AppError::InvalidOperation(message))
             if message.contains("not found (.tomosona/conf.json)") =>
         {
@@ -556,7 +916,7 @@ pub fn write_llm_only(config: SecondBrainConfig) -> Result<PathBuf> {
         AppError::InvalidOperation(format!("LLM configuration error: {message}"))
     })?;
     let existing = read_settings_file().ok();
-    let settings = AppSettings {
+    let mut settings = AppSettings {
         llm: config,
         embeddings: existing
             .as_ref()
@@ -568,11 +928,7 @@ pub fn write_llm_only(config: SecondBrainConfig) -> Result<PathBuf> {
             .unwrap_or_default(),
     };
     validate_settings(&settings)?;
-    let path = conf_path()?;
-    ensure_conf_parent(&path)?;
-    let raw = serde_json::to_string_pretty(&settings).map_err(|_| AppError::OperationFailed)?;
-    fs::write(&path, format!("{raw}\n"))?;
-    Ok(path)
+    write_settings_file(&mut settings)
 }
 
 #[tauri::command]
@@ -585,15 +941,18 @@ pub fn read_app_settings() -> Result<AppSettingsView> {
             llm: None,
             embeddings: view_embeddings(&EmbeddingsSettings::default()),
             alters: AltersSettings::default(),
+            key_migration_error: None,
         });
     }
     let settings = read_settings_file()?;
+    let key_migration_error = pending_key_migration_error(&settings);
     Ok(AppSettingsView {
         exists: true,
         path: path.to_string_lossy().to_string(),
         llm: Some(view_llm(&settings.llm)),
         embeddings: view_embeddings(&settings.embeddings),
         alters: settings.alters,
+        key_migration_error,
     })
 }
 
@@ -604,12 +963,9 @@ pub fn write_app_settings(payload: SaveAppSettingsPayload) -> Result<WriteAppSet
         .as_ref()
         .map(|item| embedding_identity(&item.embeddings))
         .unwrap_or_else(|| embedding_identity(&EmbeddingsSettings::default()));
-    let settings = apply_save_payload(payload, existing.as_ref())?;
+    let mut settings = apply_save_payload(payload, existing.as_ref())?;
     let next_identity = embedding_identity(&settings.embeddings);
-    let path = conf_path()?;
-    ensure_conf_parent(&path)?;
-    let raw = serde_json::to_string_pretty(&settings).map_err(|_| AppError::OperationFailed)?;
-    fs::write(&path, format!("{raw}\n"))?;
+    let path = write_settings_file(&mut settings)?;
     Ok(WriteAppSettingsResult {
         path: path.to_string_lossy().to_string(),
         embeddings_changed: previous_identity != next_identity,
@@ -620,22 +976,40 @@ pub fn write_app_settings(payload: SaveAppSettingsPayload) -> Result<WriteAppSet
 fn resolve_discovery_api_key(
     profile_id: &str,
     input_value: Option<&str>,
-    preserve_existing: bool,
+    input_source: &ApiKeySource,
 ) -> Result<String> {
-    let existing_api_key = read_settings_file().ok().and_then(|settings| {
+    if let Some(value) = input_value.map(str::trim).filter(|value| !value.is_empty()) {
+        return Ok(value.to_string());
+    }
+    if input_source.is_external() {
+        return secrets::resolve_api_key("", input_source);
+    }
+    let settings = read_settings_file().ok();
+    let saved = settings.as_ref().and_then(|settings| {
         settings
             .llm
             .profiles
             .iter()
-            .find(|profile| profile.id.trim() == profile_id.trim())
-            .map(|profile| profile.api_key.clone())
+            .map(|profile| (&profile.id, &profile.api_key, &profile.key_source))
+            .chain(
+                settings
+                    .embeddings
+                    .external
+                    .iter()
+                    .map(|profile| (&profile.id, &profile.api_key, &profile.key_source)),
+            )
+            .find(|(id, _, _)| id.trim() == profile_id.trim())
     });
-    resolve_api_key(
-        input_value,
-        preserve_existing,
-        existing_api_key.as_deref(),
-        "LLM profile",
-    )
+    let api_key = match saved {
+        Some((_, api_key, source)) => secrets::resolve_api_key(api_key, source)?,
+        None => String::new(),
+    };
+    if api_key.is_empty() {
+        return Err(AppError::InvalidOperation(
+            "LLM profile API key is required.".to_string(),
+        ));
+    }
+    Ok(api_key)
 }
 
 fn discovery_endpoint(provider: &str, base_url: Option<&str>) -> Result<String> {
@@ -661,11 +1035,18 @@ pub async fn discover_llm_models(payload: DiscoverLlmModelsInput) -> Result<Vec<
         ));
     }
 
+    if let Some(kind) = LocalServerKind::from_provider(&payload.provider) {
+        let models = local_models::list_models(kind, payload.base_url.as_deref())
+            .await
+            .map_err(AppError::InvalidOperation)?;
+        return Ok(models.iter().map(local_models::discovered_model).collect());
+    }
+
     let endpoint = discovery_endpoint(&payload.provider, payload.base_url.as_deref())?;
     let api_key = resolve_discovery_api_key(
         &payload.profile_id,
         payload.api_key.as_deref(),
-        payload.preserve_existing_api_key,
+        &payload.key_source,
     )?;
     discover_compatible_models(&endpoint, &api_key)
         .await
@@ -680,7 +1061,7 @@ pub async fn discover_embedding_models(
     let api_key = resolve_discovery_api_key(
         &payload.profile_id,
         payload.api_key.as_deref(),
-        payload.preserve_existing_api_key,
+        &payload.key_source,
     )?;
     discover_compatible_models(&endpoint, &api_key)
         .await
@@ -698,12 +1079,15 @@ mod tests {
             provider: "openai".to_string(),
             model: "gpt-4.1".to_string(),
             api_key: Some("secret".to_string()),
+            key_source: Default::default(),
             default_temperature: 0.15,
             system_prompt: String::new(),
             preserve_existing_api_key: false,
             base_url: None,
             default_mode: Some("freestyle".to_string()),
             capabilities: ProfileCapabilities::default(),
+            mock: None,
+            budget: None,
         }
     }
 
@@ -718,12 +1102,19 @@ mod tests {
                 provider: "openai".to_string(),
                 model: "gpt-4.1".to_string(),
                 api_key: "k".to_string(),
+                key_source: Default::default(),
                 default_temperature: 0.15,
                 system_prompt: String::new(),
                 base_url: None,
                 default_mode: Some("freestyle".to_string()),
                 capabilities: ProfileCapabilities::default(),
+                mock: None,
+                budget: None,
                 }],
+                prompt_language: None,
+                model_roles: ModelRoles::default(),
+                model_fallbacks: ModelFallbacks::default(),
+                request_policy: RequestPolicy::default(),
             },
             embeddings: EmbeddingsSettings {
                 mode: EMBEDDINGS_MODE_EXTERNAL.to_string(),
@@ -733,6 +1124,7 @@ mod tests {
                     provider: "anthropic".to_string(),
                     model: "text-embedding-3-small".to_string(),
                     api_key: "k".to_string(),
+                    key_source: Default::default(),
                     base_url: None,
                 }),
             },
@@ -751,6 +1143,10 @@ mod tests {
                     preserve_existing_api_key: true,
                     ..base_profile()
                 }],
+                prompt_language: None,
+                model_roles: None,
+                model_fallbacks: None,
+                request_policy: None,
             },
             embeddings: SaveEmbeddingsInput {
                 mode: EMBEDDINGS_MODE_INTERNAL.to_string(),
@@ -765,6 +1161,72 @@ mod tests {
         assert!(apply_save_payload(payload, None).is_err());
     }
 
+    #[test]
+    fn keeps_saved_key_source_and_never_serializes_keys() {
+        let payload = |api_key: Option<&str>, key_source: ApiKeySource| SaveAppSettingsPayload {
+            llm: SaveLlmConfigInput {
+                active_profile: "openai-profile".to_string(),
+                profiles: vec![SaveLlmProfileInput {
+                    api_key: api_key.map(str::to_string),
+                    key_source,
+                    ..base_profile()
+                }],
+                prompt_language: None,
+                model_roles: None,
+                model_fallbacks: None,
+                request_policy: None,
+            },
+            embeddings: SaveEmbeddingsInput {
+                mode: EMBEDDINGS_MODE_INTERNAL.to_string(),
+                external: None,
+            },
+            alters: SaveAltersInput {
+                default_mode: ALTER_DEFAULT_MODE_NEUTRAL.to_string(),
+                show_badge_in_chat: true,
+                default_influence_intensity: ALTER_DEFAULT_INTENSITY_BALANCED.to_string(),
+            },
+        };
+        let mut existing =
+            apply_save_payload(payload(Some("secret"), ApiKeySource::default()), None)
+                .expect("settings");
+        existing.llm.profiles[0].api_key.clear();
+        existing.llm.profiles[0].key_source.api_key_secret = Some("llm/openai-profile".to_string());
+
+        let kept = apply_save_payload(payload(None, ApiKeySource::default()), Some(&existing))
+            .expect("kept settings");
+        assert_eq!(
+            kept.llm.profiles[0].key_source.api_key_secret.as_deref(),
+            Some("llm/openai-profile")
+        );
+
+        let from_env = ApiKeySource {
+            api_key_env: Some("OPENAI_API_KEY".to_string()),
+            api_key_secret: Some("llm/other".to_string()),
+            ..ApiKeySource::default()
+        };
+        let switched =
+            apply_save_payload(payload(None, from_env), Some(&existing)).expect("env settings");
+        let profile = &switched.llm.profiles[0];
+        assert_eq!(
+            profile.key_source.api_key_env.as_deref(),
+            Some("OPENAI_API_KEY")
+        );
+        assert_eq!(profile.key_source.api_key_secret, None);
+
+        let mut typed = apply_save_payload(payload(Some("secret"), ApiKeySource::default()), None)
+            .expect("typed settings");
+        typed.llm.profiles[0].key_source.api_key_secret = Some("llm/openai-profile".to_string());
+        let raw = serde_json::to_string(&typed).expect("serialize settings");
+        assert!(!raw.contains("\"secret\""));
+        assert!(raw.contains("\"api_key_secret\":\"llm/openai-profile\""));
+        let legacy: AppSettings = serde_json::from_str(&raw.replace(
+            "\"api_key_secret\":\"llm/openai-profile\"",
+            "\"api_key\":\"legacy\"",
+        ))
+        .expect("legacy settings");
+        assert!(has_plaintext_keys(&legacy));
+    }
+
     #[test]
     fn embeddings_identity_changes_when_model_changes() {
         let left = EmbeddingsSettings {
@@ -775,6 +1237,7 @@ mod tests {
                 provider: "openai".to_string(),
                 model: "text-embedding-3-small".to_string(),
                 api_key: "k".to_string(),
+                key_source: Default::default(),
                 base_url: None,
             }),
         };
@@ -786,6 +1249,7 @@ mod tests {
                 provider: "openai".to_string(),
                 model: "text-embedding-3-large".to_string(),
                 api_key: "k".to_string(),
+                key_source: Default::default(),
                 base_url: None,
             }),
         };
@@ -803,13 +1267,20 @@ mod tests {
                     provider: "openai-codex".to_string(),
                     model: "gpt-5.2-codex".to_string(),
                     api_key: None,
+                    key_source: Default::default(),
                     default_temperature: 0.15,
                     system_prompt: String::new(),
                     preserve_existing_api_key: false,
                     base_url: Some("https://ignored.example".to_string()),
                     default_mode: Some("freestyle".to_string()),
                     capabilities: ProfileCapabilities::default(),
+                    mock: None,
+                    budget: None,
                 }],
+                prompt_language: None,
+                model_roles: None,
+                model_fallbacks: None,
+                request_policy: None,
             },
             embeddings: SaveEmbeddingsInput {
                 mode: EMBEDDINGS_MODE_INTERNAL.to_string(),
@@ -825,4 +1296,301 @@ mod tests {
         assert_eq!(settings.llm.profiles[0].api_key, "");
         assert_eq!(settings.llm.profiles[0].base_url, None);
     }
+
+    #[test]
+    fn saves_mock_profile_without_api_key_or_network_settings() {
+        let payload = |mock: Option<MockProviderSettings>| SaveAppSettingsPayload {
+            llm: SaveLlmConfigInput {
+                active_profile: "openai-profile".to_string(),
+                profiles: vec![SaveLlmProfileInput {
+                    provider: "mock".to_string(),
+                    api_key: None,
+                    base_url: Some("not a url".to_string()),
+                    mock,
+                    ..base_profile()
+                }],
+                prompt_language: None,
+                model_roles: None,
+                model_fallbacks: None,
+                request_policy: None,
+            },
+            embeddings: SaveEmbeddingsInput {
+                mode: EMBEDDINGS_MODE_INTERNAL.to_string(),
+                external: None,
+            },
+            alters: SaveAltersInput {
+                default_mode: ALTER_DEFAULT_MODE_NEUTRAL.to_string(),
+                show_badge_in_chat: true,
+                default_influence_intensity: ALTER_DEFAULT_INTENSITY_BALANCED.to_string(),
+            },
+        };
+        let settings = apply_save_payload(payload(None), None).expect("mock settings");
+        let profile = &settings.llm.profiles[0];
+        assert_eq!(profile.api_key, "");
+        assert_eq!(profile.base_url, None);
+        assert_eq!(profile.mock, Some(MockProviderSettings::default()));
+
+        let scripted = MockProviderSettings {
+            chunk_chars: 4,
+            latency_ms: 10,
+            ..MockProviderSettings::default()
+        };
+        let existing =
+            apply_save_payload(payload(Some(scripted.clone())), None).expect("scripted settings");
+        let kept = apply_save_payload(payload(None), Some(&existing)).expect("kept settings");
+        assert_eq!(kept.llm.profiles[0].mock, Some(scripted));
+    }
+
+    #[test]
+    fn saves_trimmed_model_roles_for_known_profiles() {
+        let payload = |deep: &str| SaveAppSettingsPayload {
+            llm: SaveLlmConfigInput {
+                active_profile: "openai-profile".to_string(),
+                profiles: vec![SaveLlmProfileInput {
+                    capabilities: ProfileCapabilities {
+                        text: true,
+                        ..ProfileCapabilities::default()
+                    },
+                    ..base_profile()
+                }],
+                prompt_language: None,
+                model_roles: Some(ModelRoles {
+                    deep: Some(deep.to_string()),
+                    titler: Some("  ".to_string()),
+                    ..ModelRoles::default()
+                }),
+                model_fallbacks: None,
+                request_policy: None,
+            },
+            embeddings: SaveEmbeddingsInput {
+                mode: EMBEDDINGS_MODE_INTERNAL.to_string(),
+                external: None,
+            },
+            alters: SaveAltersInput {
+                default_mode: ALTER_DEFAULT_MODE_NEUTRAL.to_string(),
+                show_badge_in_chat: true,
+                default_influence_intensity: ALTER_DEFAULT_INTENSITY_BALANCED.to_string(),
+            },
+        };
+
+        let settings = apply_save_payload(payload(" openai-profile "), None).expect("settings");
+        assert_eq!(
+            settings.llm.model_roles.deep.as_deref(),
+            Some("openai-profile")
+        );
+        assert_eq!(settings.llm.model_roles.titler, None);
+        assert!(apply_save_payload(payload("removed-profile"), None).is_err());
+    }
+
+    #[test]
+    fn keeps_saved_model_roles_when_the_payload_omits_them() {
+        let payload = |ids: &[&str], model_roles: Option<ModelRoles>| SaveAppSettingsPayload {
+            llm: SaveLlmConfigInput {
+                active_profile: "openai-profile".to_string(),
+                profiles: ids
+                    .iter()
+                    .map(|id| SaveLlmProfileInput {
+                        id: id.to_string(),
+                        capabilities: ProfileCapabilities {
+                            text: true,
+                            ..ProfileCapabilities::default()
+                        },
+                        ..base_profile()
+                    })
+                    .collect(),
+                prompt_language: None,
+                model_roles,
+                model_fallbacks: None,
+                request_policy: None,
+            },
+            embeddings: SaveEmbeddingsInput {
+                mode: EMBEDDINGS_MODE_INTERNAL.to_string(),
+                external: None,
+            },
+            alters: SaveAltersInput {
+                default_mode: ALTER_DEFAULT_MODE_NEUTRAL.to_string(),
+                show_badge_in_chat: true,
+                default_influence_intensity: ALTER_DEFAULT_INTENSITY_BALANCED.to_string(),
+            },
+        };
+        let existing = apply_save_payload(
+            payload(
+                &["openai-profile", "local-profile"],
+                Some(ModelRoles {
+                    deep: Some("openai-profile".to_string()),
+                    fast: Some("local-profile".to_string()),
+                    ..ModelRoles::default()
+                }),
+            ),
+            None,
+        )
+        .expect("existing settings");
+
+        let kept = apply_save_payload(
+            payload(&["openai-profile", "local-profile"], None),
+            Some(&existing),
+        )
+        .expect("kept settings");
+        assert_eq!(kept.llm.model_roles, existing.llm.model_roles);
+
+        let pruned = apply_save_payload(payload(&["openai-profile"], None), Some(&existing))
+            .expect("pruned settings");
+        assert_eq!(
+            pruned.llm.model_roles.deep.as_deref(),
+            Some("openai-profile")
+        );
+        assert_eq!(pruned.llm.model_roles.fast, None);
+
+        let cleared = apply_save_payload(
+            payload(&["openai-profile"], Some(ModelRoles::default())),
+            Some(&existing),
+        )
+        .expect("cleared settings");
+        assert_eq!(cleared.llm.model_roles, ModelRoles::default());
+    }
+
+    #[test]
+    fn keeps_saved_fallbacks_and_request_policy_when_the_payload_omits_them() {
+        let payload =
+            |ids: &[&str],
+             model_fallbacks: Option<ModelFallbacks>,
+             request_policy: Option<RequestPolicy>| SaveAppSettingsPayload {
+                llm: SaveLlmConfigInput {
+                    active_profile: "openai-profile".to_string(),
+                    profiles: ids
+                        .iter()
+                        .map(|id| SaveLlmProfileInput {
+                            id: id.to_string(),
+                            capabilities: ProfileCapabilities {
+                                text: true,
+                                ..ProfileCapabilities::default()
+                            },
+                            ..base_profile()
+                        })
+                        .collect(),
+                    prompt_language: None,
+                    model_roles: None,
+                    model_fallbacks,
+                    request_policy,
+                },
+                embeddings: SaveEmbeddingsInput {
+                    mode: EMBEDDINGS_MODE_INTERNAL.to_string(),
+                    external: None,
+                },
+                alters: SaveAltersInput {
+                    default_mode: ALTER_DEFAULT_MODE_NEUTRAL.to_string(),
+                    show_badge_in_chat: true,
+                    default_influence_intensity: ALTER_DEFAULT_INTENSITY_BALANCED.to_string(),
+                },
+            };
+        let policy = RequestPolicy {
+            timeout_secs: 12,
+            max_retries: 0,
+            ..RequestPolicy::default()
+        };
+        let existing = apply_save_payload(
+            payload(
+                &["openai-profile", "backup-a", "backup-b"],
+                Some(ModelFallbacks {
+                    chat: vec!["backup-a".to_string(), "backup-b".to_string()],
+                    ..ModelFallbacks::default()
+                }),
+                Some(policy.clone()),
+            ),
+            None,
+        )
+        .expect("existing settings");
+
+        let kept = apply_save_payload(
+            payload(&["openai-profile", "backup-b"], None, None),
+            Some(&existing),
+        )
+        .expect("kept settings");
+        assert_eq!(kept.llm.model_fallbacks.chat, vec!["backup-b".to_string()]);
+        assert_eq!(kept.llm.request_policy, policy);
+
+        let reset = apply_save_payload(
+            payload(
+                &["openai-profile"],
+                Some(ModelFallbacks::default()),
+                Some(RequestPolicy::default()),
+            ),
+            Some(&existing),
+        )
+        .expect("reset settings");
+        assert!(reset.llm.model_fallbacks.chat.is_empty());
+        assert_eq!(reset.llm.request_policy, RequestPolicy::default());
+    }
+
+    #[test]
+    fn keeps_saved_budget_when_the_profile_omits_it() {
+        let payload = |budget: Option<ProfileBudget>| SaveAppSettingsPayload {
+            llm: SaveLlmConfigInput {
+                active_profile: "openai-profile".to_string(),
+                profiles: vec![SaveLlmProfileInput {
+                    budget,
+                    ..base_profile()
+                }],
+                prompt_language: None,
+                model_roles: None,
+                model_fallbacks: None,
+                request_policy: None,
+            },
+            embeddings: SaveEmbeddingsInput {
+                mode: EMBEDDINGS_MODE_INTERNAL.to_string(),
+                external: None,
+            },
+            alters: SaveAltersInput {
+                default_mode: ALTER_DEFAULT_MODE_NEUTRAL.to_string(),
+                show_badge_in_chat: true,
+                default_influence_intensity: ALTER_DEFAULT_INTENSITY_BALANCED.to_string(),
+            },
+        };
+        let budget = ProfileBudget {
+            monthly_limit_usd: Some(5.0),
+            ..ProfileBudget::default()
+        };
+        let existing =
+            apply_save_payload(payload(Some(budget.clone())), None).expect("existing settings");
+
+        let kept = apply_save_payload(payload(None), Some(&existing)).expect("kept settings");
+        assert_eq!(kept.llm.profiles[0].budget, Some(budget));
+
+        let cleared = apply_save_payload(payload(Some(ProfileBudget::default())), Some(&existing))
+            .expect("cleared settings");
+        assert_eq!(cleared.llm.profiles[0].budget, None);
+    }
+
+    #[test]
+    fn keeps_saved_prompt_language_when_the_payload_omits_it() {
+        let payload = |prompt_language: Option<PromptLanguageChoice>| SaveAppSettingsPayload {
+            llm: SaveLlmConfigInput {
+                active_profile: "openai-profile".to_string(),
+                profiles: vec![base_profile()],
+                prompt_language,
+                model_roles: None,
+                model_fallbacks: None,
+                request_policy: None,
+            },
+            embeddings: SaveEmbeddingsInput {
+                mode: EMBEDDINGS_MODE_INTERNAL.to_string(),
+                external: None,
+            },
+            alters: SaveAltersInput {
+                default_mode: ALTER_DEFAULT_MODE_NEUTRAL.to_string(),
+                show_badge_in_chat: true,
+                default_influence_intensity: ALTER_DEFAULT_INTENSITY_BALANCED.to_string(),
+            },
+        };
+        let existing = apply_save_payload(payload(Some(PromptLanguageChoice::En)), None)
+            .expect("existing settings");
+        assert_eq!(existing.llm.prompt_language, Some(PromptLocale::En));
+
+        let kept = apply_save_payload(payload(None), Some(&existing)).expect("kept settings");
+        assert_eq!(kept.llm.prompt_language, Some(PromptLocale::En));
+
+        let auto = apply_save_payload(payload(Some(PromptLanguageChoice::Auto)), Some(&existing))
+            .expect("auto settings");
+        assert_eq!(auto.llm.prompt_language, None);
+    }
 }
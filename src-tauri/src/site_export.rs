@@ -0,0 +1,1214 @@
+//! Static HTML site export for published notes.
+//!
+//! Notes whose frontmatter has `publish: true` (or every note, on request) are
+//! rendered with comrak into one HTML page each. Wikilinks resolve with the same
+//! key rules as the wikilink graph; links to notes left out of the site degrade to
+//! plain text. The export also writes backlinks sections, a tag index, Mermaid
+//! diagrams as SVG or PNG files and a client-side search index.
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use comrak::{markdown_to_html, Options};
+use serde::{Deserialize, Serialize};
+
+use crate::docx::conversion::{is_mermaid_code_block, render_mermaid_png, render_mermaid_svg};
+use crate::importers::percent_decode;
+use crate::markdown_index::{
+    extract_yaml_frontmatter, normalize_wikilink_target, parse_yaml_frontmatter_properties,
+    strip_yaml_frontmatter, unquote_yaml_scalar,
+};
+use crate::wikilink_graph::NoteKeyResolver;
+use crate::workspace_paths::{
+    join_relative, relative_from, should_skip_workspace_walk_dir, should_skip_workspace_walk_file,
+    split_wikilink_target_suffix,
+};
+use crate::{
+    active_workspace_root, list_markdown_files_via_find, normalize_workspace_relative_path,
+    AppError, Result,
+};
+
+const PUBLISH_KEY: &str = "publish";
+const EXPORT_MARKER_FILE: &str = ".tomosona-site";
+const ASSETS_DIR: &str = "assets";
+const DIAGRAMS_DIR: &str = "assets/diagrams";
+const TAGS_DIR: &str = "tags";
+const SEARCH_TEXT_MAX_CHARS: usize = 4000;
+const SEARCH_EXCERPT_MAX_CHARS: usize = 180;
+const IMAGE_EXTENSIONS: [&str; 8] = ["png", "jpg", "jpeg", "gif", "svg", "webp", "bmp", "avif"];
+
+const STYLE_CSS: &str = r#":root { color-scheme: light dark; --accent: #3867d6; --muted: #6b7280; }
+body { margin: 0; font: 16px/1.6 system-ui, -apple-system, "Segoe UI", sans-serif; }
+.site-header { display: flex; gap: 1rem; align-items: center; padding: 0.75rem 1.5rem; border-bottom: 1px solid #8883; position: relative; }
+.site-header a { color: inherit; text-decoration: none; font-weight: 600; }
+.site-search { margin-left: auto; position: relative; }
+.site-search input { padding: 0.3rem 0.6rem; border: 1px solid #8886; border-radius: 6px; min-width: 14rem; }
+.site-search ul { position: absolute; right: 0; z-index: 10; list-style: none; margin: 0.25rem 0 0; padding: 0; width: 24rem; max-width: 80vw; background: Canvas; border: 1px solid #8884; border-radius: 6px; }
+.site-search li a { display: block; padding: 0.4rem 0.6rem; font-weight: 400; }
+.site-search li small { display: block; color: var(--muted); }
+main { max-width: 46rem; margin: 0 auto; padding: 1.5rem; }
+a { color: var(--accent); }
+img { max-width: 100%; }
+pre { overflow-x: auto; padding: 0.75rem; border-radius: 6px; background: #8881; }
+table { border-collapse: collapse; }
+td, th { border: 1px solid #8884; padding: 0.3rem 0.6rem; }
+.tags a { display: inline-block; margin-right: 0.4rem; font-size: 0.85rem; }
+.backlinks { margin-top: 3rem; padding-top: 1rem; border-top: 1px solid #8883; }
+.backlinks h2 { font-size: 1rem; color: var(--muted); }
+"#;
+
+const SEARCH_JS: &str = r#"(function () {
+  var input = document.getElementById("site-search-input");
+  var results = document.getElementById("site-search-results");
+  var index = window.TOMOSONA_SEARCH_INDEX || [];
+  var root = document.body.getAttribute("data-root") || "";
+  if (!input || !results) return;
+  function render(matches) {
+    results.innerHTML = "";
+    matches.slice(0, 20).forEach(function (entry) {
+      var item = document.createElement("li");
+      var link = document.createElement("a");
+      link.href = root + entry.url;
+      link.textContent = entry.title;
+      var excerpt = document.createElement("small");
+      excerpt.textContent = entry.excerpt;
+      link.appendChild(excerpt);
+      item.appendChild(link);
+      results.appendChild(item);
+    });
+  }
+  input.addEventListener("input", function () {
+    var terms = input.value.toLowerCase().split(/\s+/).filter(Boolean);
+    if (!terms.length) { render([]); return; }
+    var scored = [];
+    index.forEach(function (entry) {
+      var title = entry.title.toLowerCase();
+      var haystack = title + " " + entry.tags.join(" ") + " " + entry.text;
+      if (!terms.every(function (term) { return haystack.indexOf(term) !== -1; })) return;
+      var score = terms.reduce(function (sum, term) { return sum + (title.indexOf(term) !== -1 ? 2 : 1); }, 0);
+      scored.push({ entry: entry, score: score });
+    });
+    scored.sort(function (a, b) { return b.score - a.score || a.entry.title.localeCompare(b.entry.title); });
+    render(scored.map(function (item) { return item.entry; }));
+  });
+})();
+"#;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagramFormat {
+    #[default]
+    Svg,
+    Png,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct StaticSiteExportPayload {
+    /// Workspace-relative folder to export; the whole workspace when empty.
+    #[serde(default)]
+    pub folder: Option<String>,
+    /// Absolute output folder. It must be empty or hold a previous site export.
+    pub output_dir: String,
+    /// Export every note instead of only notes with `publish: true`.
+    #[serde(default)]
+    pub include_unpublished: bool,
+    #[serde(default)]
+    pub site_title: Option<String>,
+    #[serde(default)]
+    pub diagram_format: DiagramFormat,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StaticSiteExportResult {
+    pub output_dir: String,
+    pub pages: usize,
+    pub tags: usize,
+    pub copied_assets: usize,
+    pub rendered_diagrams: usize,
+    pub unresolved_links: usize,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct SearchEntry {
+    title: String,
+    url: String,
+    tags: Vec<String>,
+    excerpt: String,
+    text: String,
+}
+
+struct SitePage {
+    source: String,
+    title: String,
+    tags: Vec<String>,
+    url: String,
+    markdown: String,
+    starts_with_title: bool,
+}
+
+/// Mutable state shared while the pages of one export are rendered.
+struct SiteBuilder<'a> {
+    root: &'a Path,
+    output: &'a Path,
+    resolver: NoteKeyResolver,
+    url_by_source: HashMap<String, String>,
+    asset_urls: HashMap<String, String>,
+    attachments_by_name: HashMap<String, Option<String>>,
+    diagram_format: DiagramFormat,
+    copied_assets: usize,
+    rendered_diagrams: usize,
+    unresolved_links: usize,
+    warnings: Vec<String>,
+}
+
+/// Context of the page currently being rendered.
+struct PageContext<'a> {
+    source: &'a str,
+    note_dir: &'a str,
+    page_dir: &'a str,
+    diagram_prefix: String,
+    diagram_count: usize,
+    outgoing: HashSet<String>,
+}
+
+fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            other => escaped.push(other),
+        }
+    }
+    escaped
+}
+
+/// Escapes Markdown punctuation in text inserted into a note.
+fn escape_markdown_text(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        if matches!(
+            ch,
+            '\\' | '[' | ']' | '*' | '_' | '`' | '<' | '>' | '#' | '!'
+        ) {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
+    }
+    escaped
+}
+
+/// Percent-encodes a relative URL path, keeping `/` separators.
+fn encode_url_path(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'/' | b'-' | b'_' | b'.' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+/// Lowercase, dash-separated slug used for page and tag file names.
+fn slugify(value: &str) -> String {
+    let mut slug = String::with_capacity(value.len());
+    let mut previous_dash = true;
+    for ch in value.chars().flat_map(char::to_lowercase) {
+        if ch.is_alphanumeric() {
+            slug.push(ch);
+            previous_dash = false;
+        } else if !previous_dash {
+            slug.push('-');
+            previous_dash = true;
+        }
+    }
+    let slug = slug.trim_matches('-').to_string();
+    if slug.is_empty() {
+        "page".to_string()
+    } else {
+        slug
+    }
+}
+
+/// Heading id as generated by comrak's `header_ids` extension.
+fn heading_anchor(heading: &str) -> String {
+    heading
+        .trim()
+        .to_lowercase()
+        .chars()
+        .filter(|ch| ch.is_alphanumeric() || matches!(ch, '_' | '-' | ' '))
+        .map(|ch| if ch == ' ' { '-' } else { ch })
+        .collect()
+}
+
+fn parent_dir(path: &str) -> &str {
+    path.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("")
+}
+
+fn is_external_url(target: &str) -> bool {
+    target.contains("://")
+        || target.starts_with("mailto:")
+        || target.starts_with("data:")
+        || target.starts_with('#')
+}
+
+fn is_markdown_target(path: &str) -> bool {
+    let lowered = path.to_lowercase();
+    lowered.ends_with(".md") || lowered.ends_with(".markdown")
+}
+
+fn is_image_target(path: &str) -> bool {
+    path.rsplit_once('.')
+        .map(|(_, ext)| IMAGE_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// Reads `title` and `tags` from frontmatter, keeping the author's casing.
+fn frontmatter_title_and_tags(markdown: &str) -> (Option<String>, Vec<String>) {
+    let Some(yaml) = extract_yaml_frontmatter(markdown) else {
+        return (None, Vec::new());
+    };
+    let mut title = None;
+    let mut tags = Vec::new();
+    let mut in_tags = false;
+    for line in yaml.lines() {
+        if in_tags {
+            if let Some(item) = line.trim_start().strip_prefix("- ") {
+                tags.push(unquote_yaml_scalar(item));
+                continue;
+            }
+            if line.starts_with([' ', '\t']) || line.trim().is_empty() {
+                continue;
+            }
+            in_tags = false;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        match key.trim().to_lowercase().as_str() {
+            "title" => {
+                let value = unquote_yaml_scalar(value);
+                if !value.is_empty() {
+                    title = Some(value);
+                }
+            }
+            "tags" => {
+                let value = value.trim();
+                if value.is_empty() {
+                    in_tags = true;
+                } else {
+                    tags.extend(
+                        value
+                            .trim_start_matches('[')
+                            .trim_end_matches(']')
+                            .split(',')
+                            .map(unquote_yaml_scalar),
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+    let tags = tags
+        .into_iter()
+        .map(|tag| tag.trim_start_matches('#').trim().to_string())
+        .filter(|tag| !tag.is_empty())
+        .fold(Vec::<String>::new(), |mut unique, tag| {
+            if !unique.iter().any(|known| known.eq_ignore_ascii_case(&tag)) {
+                unique.push(tag);
+            }
+            unique
+        });
+    (title, tags)
+}
+
+fn is_published(markdown: &str) -> bool {
+    parse_yaml_frontmatter_properties(markdown)
+        .iter()
+        .any(|property| property.key == PUBLISH_KEY && property.value_bool == Some(1))
+}
+
+fn first_heading(body: &str) -> Option<String> {
+    body.lines()
+        .find(|line| !line.trim().is_empty())
+        .and_then(|line| line.strip_prefix("# "))
+        .map(|heading| heading.trim().to_string())
+}
+
+/// Applies `transform` to the parts of `line` outside inline code spans.
+fn map_outside_inline_code(line: &str, mut transform: impl FnMut(&str) -> String) -> String {
+    let mut output = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(start) = rest.find('`') {
+        output.push_str(&transform(&rest[..start]));
+        let run = rest[start..].chars().take_while(|ch| *ch == '`').count();
+        let fence = "`".repeat(run);
+        let after = &rest[start + run..];
+        match after.find(&fence) {
+            Some(end) => {
+                output.push_str(&rest[start..start + run + end + run]);
+                rest = &after[end + run..];
+            }
+            None => {
+                output.push_str(&rest[start..start + run]);
+                rest = after;
+            }
+        }
+    }
+    output.push_str(&transform(rest));
+    output
+}
+
+fn plain_text_from_html(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for ch in html.chars() {
+        match ch {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                text.push(' ');
+            }
+            other if !in_tag => text.push(other),
+            _ => {}
+        }
+    }
+    let decoded = text
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+    decoded.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+impl SiteBuilder<'_> {
+    fn link_from(&self, page_dir: &str, target_url: &str) -> String {
+        encode_url_path(&relative_from(page_dir, target_url))
+    }
+
+    /// Copies a workspace file into `assets/` once and returns its site URL.
+    fn copy_asset(&mut self, workspace_path: &str) -> Option<String> {
+        if let Some(url) = self.asset_urls.get(workspace_path) {
+            return Some(url.clone());
+        }
+        let source = self.root.join(workspace_path);
+        if !source.is_file() {
+            return None;
+        }
+        let url = format!("{ASSETS_DIR}/{workspace_path}");
+        let destination = self.output.join(&url);
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent).ok()?;
+        }
+        if let Err(err) = fs::copy(&source, &destination) {
+            self.warnings
+                .push(format!("{workspace_path}: asset copy failed ({err})"));
+            return None;
+        }
+        self.copied_assets += 1;
+        self.asset_urls
+            .insert(workspace_path.to_string(), url.clone());
+        Some(url)
+    }
+
+    /// Finds an attachment referenced from a note: note-relative, workspace-relative,
+    /// then by file name when a single workspace file has it.
+    fn resolve_attachment(&self, note_dir: &str, target: &str) -> Option<String> {
+        [join_relative(note_dir, target), join_relative("", target)]
+            .into_iter()
+            .flatten()
+            .find(|candidate| self.root.join(candidate).is_file())
+            .or_else(|| {
+                let name = target.rsplit('/').next()?.to_lowercase();
+                self.attachments_by_name.get(&name).cloned().flatten()
+            })
+    }
+
+    fn render_wikilink(
+        &mut self,
+        content: &str,
+        embed: bool,
+        page: &mut PageContext<'_>,
+    ) -> String {
+        let (target, suffix) = split_wikilink_target_suffix(content);
+        // Inside tables the alias pipe is written `\|`.
+        let target = target.trim_end_matches('\\').trim();
+        let alias = suffix
+            .split_once('|')
+            .map(|(_, alias)| alias.trim().to_string())
+            .filter(|alias| !alias.is_empty());
+        let heading = suffix
+            .strip_prefix('#')
+            .map(|rest| {
+                rest.split('|')
+                    .next()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_string()
+            })
+            .filter(|heading| !heading.is_empty());
+
+        if embed && is_image_target(target) {
+            return match self
+                .resolve_attachment(page.note_dir, target)
+                .and_then(|path| self.copy_asset(&path))
+            {
+                Some(url) => {
+                    let alt = escape_markdown_text(alias.as_deref().unwrap_or(target));
+                    format!("![{alt}]({})", self.link_from(page.page_dir, &url))
+                }
+                None => {
+                    self.unresolved_links += 1;
+                    escape_markdown_text(target)
+                }
+            };
+        }
+
+        let label = alias.unwrap_or_else(|| match (&heading, target.is_empty()) {
+            (Some(heading), true) => heading.clone(),
+            (Some(heading), false) => format!("{target} > {heading}"),
+            (None, _) => target.to_string(),
+        });
+        let label = escape_markdown_text(&label);
+        let anchor = heading
+            .as_deref()
+            .map(|heading| format!("#{}", heading_anchor(heading)))
+            .unwrap_or_default();
+        if target.is_empty() {
+            return format!("[{label}]({anchor})");
+        }
+
+        let resolved = normalize_wikilink_target(target)
+            .and_then(|key| self.resolver.resolve(&key).map(str::to_string));
+        let Some(source) = resolved else {
+            self.unresolved_links += 1;
+            return label;
+        };
+        match self.url_by_source.get(&source) {
+            Some(url) => {
+                if source != page.source {
+                    page.outgoing.insert(source);
+                }
+                format!("[{label}]({}{anchor})", self.link_from(page.page_dir, url))
+            }
+            // Notes left out of the site must not leak; keep the label only.
+            None => label,
+        }
+    }
+
+    /// Rewrites a `[label](target)` destination; returns `None` to keep it unchanged.
+    fn render_markdown_link(
+        &mut self,
+        label: &str,
+        target: &str,
+        page: &mut PageContext<'_>,
+    ) -> Option<String> {
+        let target = target.trim();
+        let (destination, title) = match target.split_once(char::is_whitespace) {
+            Some((destination, title)) if !target.starts_with('<') => (destination, Some(title)),
+            _ => (target, None),
+        };
+        let destination = destination.trim_start_matches('<').trim_end_matches('>');
+        if destination.is_empty() || is_external_url(destination) {
+            return None;
+        }
+        let decoded = percent_decode(destination);
+        let (path, fragment) = match decoded.split_once('#') {
+            Some((path, fragment)) => (path.to_string(), format!("#{fragment}")),
+            None => (decoded.clone(), String::new()),
+        };
+        let title = title.map(|title| format!(" {title}")).unwrap_or_default();
+
+        if is_markdown_target(&path) {
+            let resolved = join_relative(page.note_dir, &path)?;
+            return match self.url_by_source.get(&resolved) {
+                Some(url) => {
+                    if resolved != page.source {
+                        page.outgoing.insert(resolved);
+                    }
+                    let href = self.link_from(page.page_dir, url);
+                    Some(format!("[{label}]({href}{fragment}{title})"))
+                }
+                None => Some(label.to_string()),
+            };
+        }
+        let attachment = self.resolve_attachment(page.note_dir, &path)?;
+        let url = self.copy_asset(&attachment)?;
+        let href = self.link_from(page.page_dir, &url);
+        Some(format!("[{label}]({href}{fragment}{title})"))
+    }
+
+    fn rewrite_inline(&mut self, text: &str, page: &mut PageContext<'_>) -> String {
+        let mut output = String::with_capacity(text.len());
+        let mut rest = text;
+        loop {
+            let wiki = rest.find("[[");
+            let link = rest.find("](");
+            match (wiki, link) {
+                (Some(start), other) if other.map(|link| start < link).unwrap_or(true) => {
+                    let Some(end_rel) = rest[start + 2..].find("]]") else {
+                        break;
+                    };
+                    let end = start + 2 + end_rel;
+                    let embed = rest[..start].ends_with('!');
+                    let prefix_end = if embed { start - 1 } else { start };
+                    output.push_str(&rest[..prefix_end]);
+                    let content = &rest[start + 2..end];
+                    output.push_str(&self.render_wikilink(content, embed, page));
+                    rest = &rest[end + 2..];
+                }
+                (_, Some(middle)) => {
+                    let Some(open) = rest[..middle].rfind('[') else {
+                        output.push_str(&rest[..middle + 2]);
+                        rest = &rest[middle + 2..];
+                        continue;
+                    };
+                    let Some(close_rel) = rest[middle + 2..].find(')') else {
+                        break;
+                    };
+                    let close = middle + 2 + close_rel;
+                    let label = &rest[open + 1..middle];
+                    let target = &rest[middle + 2..close];
+                    match self.render_markdown_link(label, target, page) {
+                        Some(rendered) => {
+                            output.push_str(&rest[..open]);
+                            output.push_str(&rendered);
+                        }
+                        None => output.push_str(&rest[..=close]),
+                    }
+                    rest = &rest[close + 1..];
+                }
+                _ => break,
+            }
+        }
+        output.push_str(rest);
+        output
+    }
+
+    fn render_diagram(&mut self, code: &str, page: &mut PageContext<'_>) -> Option<String> {
+        let (extension, bytes) = match self.diagram_format {
+            DiagramFormat::Svg => ("svg", render_mermaid_svg(code)?.into_bytes()),
+            DiagramFormat::Png => ("png", render_mermaid_png(code)?),
+        };
+        page.diagram_count += 1;
+        let url = format!(
+            "{DIAGRAMS_DIR}/{}-{}.{extension}",
+            page.diagram_prefix, page.diagram_count
+        );
+        let destination = self.output.join(&url);
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent).ok()?;
+        }
+        fs::write(&destination, bytes).ok()?;
+        self.rendered_diagrams += 1;
+        Some(format!(
+            "![Diagram {}]({})",
+            page.diagram_count,
+            self.link_from(page.page_dir, &url)
+        ))
+    }
+
+    /// Rewrites links, embeds and Mermaid fences of a note body outside code.
+    fn rewrite_markdown(&mut self, markdown: &str, page: &mut PageContext<'_>) -> String {
+        let mut output = Vec::new();
+        let mut fence: Option<(String, bool, Vec<String>)> = None;
+        for line in markdown.lines() {
+            let trimmed = line.trim_start();
+            if let Some((marker, is_mermaid, collected)) = fence.as_mut() {
+                if trimmed.starts_with(marker.as_str()) && trimmed.trim_end() == marker.as_str() {
+                    let rendered = if *is_mermaid {
+                        self.render_diagram(&collected.join("\n"), page)
+                    } else {
+                        None
+                    };
+                    match rendered {
+                        Some(image) => output.push(image),
+                        None => {
+                            if *is_mermaid {
+                                self.warnings.push(format!(
+                                    "{}: a Mermaid diagram could not be rendered",
+                                    page.source
+                                ));
+                            }
+                            output.push(format!(
+                                "{marker}{}",
+                                if *is_mermaid { "mermaid" } else { "" }
+                            ));
+                            output.append(collected);
+                            output.push(line.to_string());
+                        }
+                    }
+                    fence = None;
+                } else if *is_mermaid {
+                    collected.push(line.to_string());
+                } else {
+                    output.push(line.to_string());
+                }
+                continue;
+            }
+            let marker_len = trimmed
+                .chars()
+                .take_while(|ch| *ch == '`' || *ch == '~')
+                .count();
+            if marker_len >= 3 {
+                let marker = trimmed[..marker_len].to_string();
+                let is_mermaid = is_mermaid_code_block(&trimmed[marker_len..]);
+                if !is_mermaid {
+                    output.push(line.to_string());
+                }
+                fence = Some((marker, is_mermaid, Vec::new()));
+                continue;
+            }
+            output.push(map_outside_inline_code(line, |segment| {
+                self.rewrite_inline(segment, page)
+            }));
+        }
+        if let Some((marker, is_mermaid, collected)) = fence {
+            if is_mermaid {
+                output.push(format!("{marker}mermaid"));
+                output.extend(collected);
+            }
+        }
+        output.join("\n")
+    }
+}
+
+/// Indexes non-note workspace files by lowercase file name; `None` marks duplicates.
+fn index_attachments(root: &Path, output: &Path) -> Result<HashMap<String, Option<String>>> {
+    let mut by_name = HashMap::new();
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            if path.starts_with(output) {
+                continue;
+            }
+            if path.is_dir() {
+                if !should_skip_workspace_walk_dir(&name, &path) {
+                    stack.push(path);
+                }
+                continue;
+            }
+            if should_skip_workspace_walk_file(&path) || is_markdown_target(&name) {
+                continue;
+            }
+            let Ok(relative) = normalize_workspace_relative_path(root, &path) else {
+                continue;
+            };
+            by_name
+                .entry(name.to_lowercase())
+                .and_modify(|existing: &mut Option<String>| *existing = None)
+                .or_insert(Some(relative));
+        }
+    }
+    Ok(by_name)
+}
+
+fn page_html(
+    site_title: &str,
+    page: &SitePage,
+    body_html: &str,
+    backlinks: &[(&str, &str)],
+) -> String {
+    let page_dir = parent_dir(&page.url);
+    let prefix = relative_from(page_dir, "");
+    let prefix = if prefix.is_empty() {
+        String::new()
+    } else {
+        format!("{prefix}/")
+    };
+    let heading = if page.starts_with_title {
+        String::new()
+    } else {
+        format!("<h1>{}</h1>\n", escape_html(&page.title))
+    };
+    let tags = if page.tags.is_empty() {
+        String::new()
+    } else {
+        let links = page
+            .tags
+            .iter()
+            .map(|tag| {
+                format!(
+                    "<a href=\"{prefix}{TAGS_DIR}/{}.html\">#{}</a>",
+                    slugify(tag),
+                    escape_html(tag)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("");
+        format!("<p class=\"tags\">{links}</p>\n")
+    };
+    let backlinks_html = if backlinks.is_empty() {
+        String::new()
+    } else {
+        let items = backlinks
+            .iter()
+            .map(|(title, url)| {
+                format!(
+                    "<li><a href=\"{}\">{}</a></li>",
+                    encode_url_path(&relative_from(page_dir, url)),
+                    escape_html(title)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        format!("<aside class=\"backlinks\">\n<h2>Backlinks</h2>\n<ul>\n{items}\n</ul>\n</aside>\n")
+    };
+    layout_html(
+        site_title,
+        &page.title,
+        &prefix,
+        &format!("<article>\n{heading}{tags}{body_html}</article>\n{backlinks_html}"),
+    )
+}
+
+fn layout_html(site_title: &str, title: &str, prefix: &str, main: &str) -> String {
+    let site = escape_html(site_title);
+    let document_title = if title == site_title {
+        site.clone()
+    } else {
+        format!("{} · {site}", escape_html(title))
+    };
+    format!(
+        "<!doctype html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{document_title}</title>\n<link rel=\"stylesheet\" href=\"{prefix}style.css\">\n\
+         </head>\n<body data-root=\"{prefix}\">\n<header class=\"site-header\">\n\
+         <a href=\"{prefix}index.html\">{site}</a>\n<a href=\"{prefix}{TAGS_DIR}/index.html\">Tags</a>\n\
+         <div class=\"site-search\"><input id=\"site-search-input\" type=\"search\" placeholder=\"Search\">\
+         <ul id=\"site-search-results\"></ul></div>\n</header>\n<main>\n{main}</main>\n\
+         <script src=\"{prefix}search-index.js\"></script>\n<script src=\"{prefix}search.js\"></script>\n\
+         </body>\n</html>\n"
+    )
+}
+
+fn page_list_html(pages: &[&SitePage], page_dir: &str) -> String {
+    let items = pages
+        .iter()
+        .map(|page| {
+            format!(
+                "<li><a href=\"{}\">{}</a></li>",
+                encode_url_path(&relative_from(page_dir, &page.url)),
+                escape_html(&page.title)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!("<ul>\n{items}\n</ul>\n")
+}
+
+/// Clears a previous export or refuses to write into an unrelated, non-empty folder.
+fn prepare_output_dir(output: &Path) -> Result<()> {
+    if output.exists() {
+        if !output.is_dir() {
+            return Err(AppError::InvalidPath);
+        }
+        if output.join(EXPORT_MARKER_FILE).is_file() {
+            fs::remove_dir_all(output)?;
+        } else if fs::read_dir(output)?.next().is_some() {
+            return Err(AppError::InvalidOperation(
+                "The output folder is not empty and does not contain a previous site export."
+                    .to_string(),
+            ));
+        }
+    }
+    fs::create_dir_all(output)?;
+    fs::write(output.join(EXPORT_MARKER_FILE), "")?;
+    Ok(())
+}
+
+fn collect_pages(
+    root: &Path,
+    paths: &[String],
+    folder: &str,
+    include_unpublished: bool,
+) -> Result<Vec<SitePage>> {
+    let mut pages = Vec::new();
+    let mut used_urls = HashSet::new();
+    for source in paths {
+        if !folder.is_empty() && !source.starts_with(&format!("{folder}/")) {
+            continue;
+        }
+        let markdown = fs::read_to_string(root.join(source))?;
+        if !include_unpublished && !is_published(&markdown) {
+            continue;
+        }
+        let (frontmatter_title, tags) = frontmatter_title_and_tags(&markdown);
+        let body = strip_yaml_frontmatter(&markdown).to_string();
+        let heading = first_heading(&body);
+        let stem = source
+            .rsplit('/')
+            .next()
+            .unwrap_or(source)
+            .rsplit_once('.')
+            .map(|(stem, _)| stem.to_string())
+            .unwrap_or_else(|| source.clone());
+        let title = frontmatter_title
+            .clone()
+            .or_else(|| heading.clone())
+            .unwrap_or(stem);
+        let starts_with_title =
+            heading.is_some() && (frontmatter_title.is_none() || heading == frontmatter_title);
+
+        let relative = source.strip_prefix(&format!("{folder}/")).unwrap_or(source);
+        let segments = relative.split('/').collect::<Vec<_>>();
+        let (dirs, file) = segments.split_at(segments.len() - 1);
+        let file_stem = file[0]
+            .rsplit_once('.')
+            .map(|(stem, _)| stem)
+            .unwrap_or(file[0]);
+        let mut base = dirs.iter().map(|dir| slugify(dir)).collect::<Vec<_>>();
+        base.push(slugify(file_stem));
+        let mut base = base.join("/");
+        // Keep generated pages clear of the site index, tag pages and assets.
+        if base == "index"
+            || base.starts_with(&format!("{TAGS_DIR}/"))
+            || base.starts_with(&format!("{ASSETS_DIR}/"))
+        {
+            base = format!("notes/{base}");
+        }
+        let mut url = format!("{base}.html");
+        let mut suffix = 2;
+        while !used_urls.insert(url.clone()) {
+            url = format!("{base}-{suffix}.html");
+            suffix += 1;
+        }
+        pages.push(SitePage {
+            source: source.clone(),
+            title,
+            tags,
+            url,
+            markdown: body,
+            starts_with_title,
+        });
+    }
+    pages.sort_by_key(|page| page.title.to_lowercase());
+    Ok(pages)
+}
+
+fn write_tag_pages(output: &Path, site_title: &str, pages: &[SitePage]) -> Result<usize> {
+    let mut by_tag: BTreeMap<String, (String, Vec<&SitePage>)> = BTreeMap::new();
+    for page in pages {
+        for tag in &page.tags {
+            by_tag
+                .entry(tag.to_lowercase())
+                .or_insert_with(|| (tag.clone(), Vec::new()))
+                .1
+                .push(page);
+        }
+    }
+    let tags_dir = output.join(TAGS_DIR);
+    fs::create_dir_all(&tags_dir)?;
+    let mut index_items = Vec::new();
+    for (display, tagged) in by_tag.values() {
+        let slug = slugify(display);
+        let main = format!(
+            "<h1>#{}</h1>\n{}",
+            escape_html(display),
+            page_list_html(tagged, TAGS_DIR)
+        );
+        fs::write(
+            tags_dir.join(format!("{slug}.html")),
+            layout_html(site_title, &format!("#{display}"), "../", &main),
+        )?;
+        index_items.push(format!(
+            "<li><a href=\"{slug}.html\">#{}</a> ({})</li>",
+            escape_html(display),
+            tagged.len()
+        ));
+    }
+    let main = format!("<h1>Tags</h1>\n<ul>\n{}\n</ul>\n", index_items.join("\n"));
+    fs::write(
+        tags_dir.join("index.html"),
+        layout_html(site_title, "Tags", "../", &main),
+    )?;
+    Ok(by_tag.len())
+}
+
+fn export_site(root: &Path, payload: StaticSiteExportPayload) -> Result<StaticSiteExportResult> {
+    let output = PathBuf::from(payload.output_dir.trim());
+    if !output.is_absolute() || root.starts_with(&output) {
+        return Err(AppError::InvalidPath);
+    }
+    let folder = payload
+        .folder
+        .as_deref()
+        .unwrap_or_default()
+        .trim()
+        .trim_matches('/')
+        .replace('\\', "/");
+    if !folder.is_empty() && !root.join(&folder).is_dir() {
+        return Err(AppError::InvalidPath);
+    }
+
+    let mut paths = Vec::new();
+    for file in list_markdown_files_via_find(root)? {
+        if file.starts_with(&output) {
+            continue;
+        }
+        if let Ok(relative) = normalize_workspace_relative_path(root, &file) {
+            paths.push(relative);
+        }
+    }
+    paths.sort();
+    let pages = collect_pages(root, &paths, &folder, payload.include_unpublished)?;
+    prepare_output_dir(&output)?;
+
+    let site_title = payload
+        .site_title
+        .as_deref()
+        .map(str::trim)
+        .filter(|title| !title.is_empty())
+        .map(str::to_string)
+        .or_else(|| {
+            root.join(&folder)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+        })
+        .unwrap_or_else(|| "Notes".to_string());
+
+    let mut builder = SiteBuilder {
+        root,
+        output: &output,
+        resolver: NoteKeyResolver::from_paths(root, &paths),
+        url_by_source: pages
+            .iter()
+            .map(|page| (page.source.clone(), page.url.clone()))
+            .collect(),
+        asset_urls: HashMap::new(),
+        attachments_by_name: index_attachments(root, &output)?,
+        diagram_format: payload.diagram_format,
+        copied_assets: 0,
+        rendered_diagrams: 0,
+        unresolved_links: 0,
+        warnings: Vec::new(),
+    };
+
+    let mut options = Options::default();
+    options.extension.table = true;
+    options.extension.strikethrough = true;
+    options.extension.tasklist = true;
+    options.extension.autolink = true;
+    options.extension.footnotes = true;
+    options.extension.header_ids = Some(String::new());
+    let mut rendered = Vec::with_capacity(pages.len());
+    let mut backlinks: HashMap<String, Vec<usize>> = HashMap::new();
+    for (index, page) in pages.iter().enumerate() {
+        let mut context = PageContext {
+            source: &page.source,
+            note_dir: parent_dir(&page.source),
+            page_dir: parent_dir(&page.url),
+            diagram_prefix: page.url.trim_end_matches(".html").replace('/', "-"),
+            diagram_count: 0,
+            outgoing: HashSet::new(),
+        };
+        let markdown = builder.rewrite_markdown(&page.markdown, &mut context);
+        for target in context.outgoing {
+            backlinks.entry(target).or_default().push(index);
+        }
+        rendered.push(markdown_to_html(&markdown, &options));
+    }
+
+    let mut search_entries = Vec::with_capacity(pages.len());
+    for (page, body_html) in pages.iter().zip(&rendered) {
+        let sources = backlinks
+            .get(&page.source)
+            .map(|sources| {
+                sources
+                    .iter()
+                    .map(|index| (pages[*index].title.as_str(), pages[*index].url.as_str()))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let destination = output.join(&page.url);
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(
+            &destination,
+            page_html(&site_title, page, body_html, &sources),
+        )?;
+
+        let text = plain_text_from_html(body_html);
+        search_entries.push(SearchEntry {
+            title: page.title.clone(),
+            url: page.url.clone(),
+            tags: page.tags.clone(),
+            excerpt: text.chars().take(SEARCH_EXCERPT_MAX_CHARS).collect(),
+            text: text
+                .to_lowercase()
+                .chars()
+                .take(SEARCH_TEXT_MAX_CHARS)
+                .collect(),
+        });
+    }
+
+    let tags = write_tag_pages(&output, &site_title, &pages)?;
+    let page_refs = pages.iter().collect::<Vec<_>>();
+    let main = format!(
+        "<h1>{}</h1>\n{}",
+        escape_html(&site_title),
+        page_list_html(&page_refs, "")
+    );
+    fs::write(
+        output.join("index.html"),
+        layout_html(&site_title, &site_title, "", &main),
+    )?;
+    let index_json = serde_json::to_string(&search_entries)
+        .map_err(|err| AppError::InvalidOperation(format!("Search index failed: {err}")))?;
+    fs::write(output.join("search-index.json"), &index_json)?;
+    fs::write(
+        output.join("search-index.js"),
+        format!("window.TOMOSONA_SEARCH_INDEX = {index_json};\n"),
+    )?;
+    fs::write(output.join("search.js"), SEARCH_JS)?;
+    fs::write(output.join("style.css"), STYLE_CSS)?;
+
+    Ok(StaticSiteExportResult {
+        output_dir: output.to_string_lossy().to_string(),
+        pages: pages.len(),
+        tags,
+        copied_assets: builder.copied_assets,
+        rendered_diagrams: builder.rendered_diagrams,
+        unresolved_links: builder.unresolved_links,
+        warnings: builder.warnings,
+    })
+}
+
+/// Exports published notes of the workspace (or one folder) as a static website.
+#[tauri::command]
+pub async fn export_static_site(
+    payload: StaticSiteExportPayload,
+) -> Result<StaticSiteExportResult> {
+    let root = active_workspace_root()?;
+    tauri::async_runtime::spawn_blocking(move || export_site(&root, payload))
+        .await
+        .map_err(|_| AppError::OperationFailed)?
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::*;
+
+    fn create_temp_dir(prefix: &str) -> PathBuf {
+        let nonce = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|value| value.as_nanos())
+            .unwrap_or(0);
+        let dir = std::env::temp_dir().join(format!("{prefix}-{nonce}"));
+        fs::create_dir_all(&dir).expect("create temp dir");
+        fs::canonicalize(dir).expect("canonical dir")
+    }
+
+    fn payload(output: &Path) -> StaticSiteExportPayload {
+        StaticSiteExportPayload {
+            folder: None,
+            output_dir: output.to_string_lossy().to_string(),
+            include_unpublished: false,
+            site_title: Some("Garden".to_string()),
+            diagram_format: DiagramFormat::Svg,
+        }
+    }
+
+    #[test]
+    fn slugs_and_anchors_match_site_urls() {
+        assert_eq!(slugify("My Plan (v2)"), "my-plan-v2");
+        assert_eq!(slugify("???"), "page");
+        assert_eq!(heading_anchor("Next steps: Q1!"), "next-steps-q1");
+        assert_eq!(
+            encode_url_path("assets/My file.png"),
+            "assets/My%20file.png"
+        );
+    }
+
+    #[test]
+    fn inline_code_is_left_untouched() {
+        let rewritten =
+            map_outside_inline_code("a [[x]] `[[y]]` b", |segment| segment.replace("[[", "<<"));
+        assert_eq!(rewritten, "a <<x]] `[[y]]` b");
+    }
+
+    #[test]
+    fn exports_published_notes_with_links_tags_and_backlinks() {
+        let workspace = create_temp_dir("tomosona-site-workspace");
+        let output = create_temp_dir("tomosona-site-output");
+        fs::create_dir_all(workspace.join("Projects/img")).expect("create folders");
+        fs::write(workspace.join("Projects/img/chart.png"), [1u8, 2, 3]).expect("write image");
+        fs::write(
+            workspace.join("Home.md"),
+            "---\npublish: true\ntags: [garden, Intro]\n---\n# Home\n\nSee [[Plan#Next steps|the plan]], [[Secret]] and [[Nowhere]].\n\n`[[Plan]]` stays code.\n",
+        )
+        .expect("write home");
+        fs::write(
+            workspace.join("Projects/Plan.md"),
+            "---\npublish: true\ntitle: Project plan\ntags:\n  - garden\n---\n## Next steps\n\n![[chart.png]] and [home](../Home.md).\n",
+        )
+        .expect("write plan");
+        fs::write(
+            workspace.join("Secret.md"),
+            "---\npublish: false\n---\nPrivate.\n",
+        )
+        .expect("write secret");
+
+        let result = export_site(&workspace, payload(&output)).expect("export site");
+        assert_eq!(result.pages, 2);
+        assert_eq!(result.tags, 2);
+        assert_eq!(result.copied_assets, 1);
+        assert_eq!(result.unresolved_links, 1);
+
+        let home = fs::read_to_string(output.join("home.html")).expect("read home");
+        assert!(home.contains("href=\"projects/plan.html#next-steps\">the plan</a>"));
+        assert!(home.contains("Secret"));
+        assert!(!home.contains("secret.html"));
+        assert!(home.contains("<code>[[Plan]]</code>"));
+        assert!(home.contains("<h2>Backlinks</h2>"));
+
+        let plan = fs::read_to_string(output.join("projects/plan.html")).expect("read plan");
+        assert!(plan.contains("<h1>Project plan</h1>"));
+        assert!(plan.contains("src=\"../assets/Projects/img/chart.png\""));
+        assert!(plan.contains("href=\"../home.html\""));
+        assert!(output.join("assets/Projects/img/chart.png").is_file());
+        assert!(!output.join("secret.html").exists());
+
+        let tag_page = fs::read_to_string(output.join("tags/garden.html")).expect("read tag");
+        assert!(tag_page.contains("../home.html"));
+        assert!(tag_page.contains("../projects/plan.html"));
+        let index = fs::read_to_string(output.join("search-index.json")).expect("read index");
+        assert!(index.contains("\"title\":\"Project plan\""));
+        assert!(!index.contains("Private"));
+
+        fs::remove_dir_all(workspace).expect("cleanup workspace");
+        fs::remove_dir_all(output).expect("cleanup output");
+    }
+
+    #[test]
+    fn refuses_unrelated_non_empty_output_folder() {
+        let workspace = create_temp_dir("tomosona-site-guard-workspace");
+        let output = create_temp_dir("tomosona-site-guard-output");
+        fs::write(output.join("keep.txt"), "mine").expect("write foreign file");
+
+        let err = export_site(&workspace, payload(&output)).expect_err("refuse output");
+        assert!(matches!(err, AppError::InvalidOperation(_)));
+        assert!(output.join("keep.txt").is_file());
+
+        fs::remove_dir_all(workspace).expect("cleanup workspace");
+        fs::remove_dir_all(output).expect("cleanup output");
+    }
+}
@@ -0,0 +1,653 @@
+//! Workspace trash browser.
+//!
+//! `fs_ops::trash_entry` moves entries to `.tomosona-trash/<unix_ts>_<name>` and records
+//! where they came from in `.tomosona/trash.json`. This module lists, restores and
+//! purges those entries. Entries trashed before the manifest existed are still listed;
+//! their original location is assumed to be the workspace root. Restoring over an
+//! existing file with the `overwrite` strategy moves that file to the trash first.
+//!
+//! The retention setting is applied when a workspace is opened, after every trash
+//! operation and whenever the trash is listed.
+
+use std::{
+    fs,
+    io::Write,
+    path::{Component, Path, PathBuf},
+};
+
+use atomicwrites::{AllowOverwrite, AtomicFile};
+use serde::{Deserialize, Serialize};
+
+use crate::fs_ops::{move_to_trash, resolve_destination, ConflictStrategy};
+use crate::markdown_index::{
+    reindex_markdown_file_lexical_sync, reindex_markdown_file_semantic_sync,
+};
+use crate::wikilink_graph::{update_wikilinks_for_path_moves, PathMoveInput};
+use crate::{
+    active_workspace_root, list_markdown_files_via_find, now_ms,
+    refresh_semantic_edges_cache_now_sync, AppError, Result,
+};
+
+const TRASH_DIR_NAME: &str = ".tomosona-trash";
+const INTERNAL_DIR_NAME: &str = ".tomosona";
+const TRASH_MANIFEST_FILE_NAME: &str = "trash.json";
+const TRASH_MANIFEST_VERSION: u8 = 1;
+const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TrashStoredItem {
+    trash_name: String,
+    original_path: String,
+    deleted_at_ms: u64,
+    size_bytes: u64,
+    is_dir: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct TrashManifest {
+    version: u8,
+    #[serde(default)]
+    auto_purge_after_days: Option<u32>,
+    #[serde(default)]
+    items: Vec<TrashStoredItem>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TrashEntry {
+    pub trash_name: String,
+    pub trash_path: String,
+    /// Workspace-relative path the entry is restored to.
+    pub original_path: String,
+    /// False for entries trashed before the manifest existed.
+    pub original_path_known: bool,
+    pub deleted_at_ms: u64,
+    pub size_bytes: u64,
+    pub is_dir: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TrashListing {
+    pub entries: Vec<TrashEntry>,
+    pub auto_purge_after_days: Option<u32>,
+    pub purged_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RestoreTrashEntryResult {
+    pub path: String,
+    pub updated_link_files: usize,
+    pub reindexed_files: usize,
+}
+
+fn manifest_path(root: &Path) -> Result<PathBuf> {
+    let dir = root.join(INTERNAL_DIR_NAME);
+    fs::create_dir_all(&dir)?;
+    Ok(dir.join(TRASH_MANIFEST_FILE_NAME))
+}
+
+fn read_manifest(root: &Path) -> Result<TrashManifest> {
+    let path = manifest_path(root)?;
+    if !path.exists() {
+        return Ok(TrashManifest {
+            version: TRASH_MANIFEST_VERSION,
+            ..Default::default()
+        });
+    }
+
+    let raw = fs::read_to_string(path)?;
+    let parsed: TrashManifest = serde_json::from_str(&raw)
+        .map_err(|_| AppError::InvalidOperation("Trash manifest is invalid.".to_string()))?;
+    if parsed.version != TRASH_MANIFEST_VERSION {
+        return Err(AppError::InvalidOperation(
+            "Trash manifest version is not supported.".to_string(),
+        ));
+    }
+    Ok(parsed)
+}
+
+/// Writes the manifest through a temp file, so a crash mid-write never truncates it.
+fn write_manifest(root: &Path, manifest: &TrashManifest) -> Result<()> {
+    let path = manifest_path(root)?;
+    let content = serde_json::to_string_pretty(manifest).map_err(|_| AppError::OperationFailed)?;
+    AtomicFile::new(path, AllowOverwrite)
+        .write(|file| {
+            file.write_all(content.as_bytes())?;
+            file.write_all(b"\n")?;
+            file.flush()?;
+            file.sync_all()?;
+            Ok(())
+        })
+        .map_err(|err| match err {
+            atomicwrites::Error::Internal(error) | atomicwrites::Error::User(error) => {
+                AppError::Io(error)
+            }
+        })
+}
+
+fn entry_size(path: &Path) -> u64 {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry_size(&entry.path()))
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+/// Splits `<unix_ts>_<name>` into the deletion time and the original file name.
+fn parse_trash_name(trash_name: &str) -> (Option<u64>, &str) {
+    let Some((prefix, name)) = trash_name.split_once('_') else {
+        return (None, trash_name);
+    };
+    match prefix.parse::<u64>() {
+        Ok(seconds) if !name.is_empty() => (Some(seconds.saturating_mul(1000)), name),
+        _ => (None, trash_name),
+    }
+}
+
+fn validate_trash_name(trash_name: &str) -> Result<String> {
+    let name = trash_name.trim();
+    let mut components = Path::new(name).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(_)), None) => Ok(name.to_string()),
+        _ => Err(AppError::InvalidPath),
+    }
+}
+
+fn normalize_original_path(raw: &str) -> Result<PathBuf> {
+    let mut normalized = PathBuf::new();
+    for component in Path::new(&raw.replace('\\', "/")).components() {
+        match component {
+            Component::Normal(part) => normalized.push(part),
+            Component::CurDir => {}
+            _ => return Err(AppError::InvalidPath),
+        }
+    }
+    if normalized.as_os_str().is_empty() {
+        return Err(AppError::InvalidPath);
+    }
+    Ok(normalized)
+}
+
+/// Records an entry that `fs_ops::trash_entry` just moved to the trash folder.
+pub(crate) fn record_trashed_entry(
+    root: &Path,
+    trash_path: &Path,
+    original_path: &str,
+    size_bytes: u64,
+    is_dir: bool,
+) -> Result<()> {
+    let trash_name = trash_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or(AppError::InvalidPath)?
+        .to_string();
+    let mut manifest = read_manifest(root)?;
+    manifest.items.retain(|item| item.trash_name != trash_name);
+    manifest.items.push(TrashStoredItem {
+        trash_name,
+        original_path: original_path.to_string(),
+        deleted_at_ms: now_ms(),
+        size_bytes,
+        is_dir,
+    });
+    write_manifest(root, &manifest)
+}
+
+/// Returns the size of an entry before it is moved to the trash.
+pub(crate) fn trashed_entry_size(path: &Path) -> u64 {
+    entry_size(path)
+}
+
+/// Merges manifest records with the trash folder content, dropping stale records.
+fn collect_entries(root: &Path, manifest: &mut TrashManifest) -> Result<Vec<TrashEntry>> {
+    let trash_dir = root.join(TRASH_DIR_NAME);
+    let mut entries = Vec::new();
+    let mut present = Vec::new();
+    if trash_dir.is_dir() {
+        for entry in fs::read_dir(&trash_dir)? {
+            let entry = entry?;
+            let Some(trash_name) = entry.file_name().to_str().map(ToOwned::to_owned) else {
+                continue;
+            };
+            let path = entry.path();
+            let stored = manifest
+                .items
+                .iter()
+                .find(|item| item.trash_name == trash_name)
+                .cloned();
+            let item = match stored {
+                Some(item) => (item, true),
+                None => {
+                    let (deleted_at_ms, original_name) = parse_trash_name(&trash_name);
+                    let deleted_at_ms = deleted_at_ms.unwrap_or_else(|| {
+                        fs::symlink_metadata(&path)
+                            .and_then(|meta| meta.modified())
+                            .ok()
+                            .and_then(|modified| {
+                                modified.duration_since(std::time::UNIX_EPOCH).ok()
+                            })
+                            .map(|value| value.as_millis() as u64)
+                            .unwrap_or(0)
+                    });
+                    (
+                        TrashStoredItem {
+                            trash_name: trash_name.clone(),
+                            original_path: original_name.to_string(),
+                            deleted_at_ms,
+                            size_bytes: entry_size(&path),
+                            is_dir: path.is_dir(),
+                        },
+                        false,
+                    )
+                }
+            };
+            present.push(trash_name);
+            entries.push(TrashEntry {
+                trash_name: item.0.trash_name,
+                trash_path: path.to_string_lossy().to_string(),
+                original_path: item.0.original_path,
+                original_path_known: item.1,
+                deleted_at_ms: item.0.deleted_at_ms,
+                size_bytes: item.0.size_bytes,
+                is_dir: item.0.is_dir,
+            });
+        }
+    }
+    manifest
+        .items
+        .retain(|item| present.contains(&item.trash_name));
+    entries.sort_by(|left, right| right.deleted_at_ms.cmp(&left.deleted_at_ms));
+    Ok(entries)
+}
+
+fn remove_trash_path(path: &Path) -> Result<()> {
+    if path.is_dir() {
+        fs::remove_dir_all(path)?;
+    } else {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+/// Deletes entries older than the configured retention and returns how many were removed.
+fn purge_expired(
+    root: &Path,
+    manifest: &mut TrashManifest,
+    entries: &mut Vec<TrashEntry>,
+    now: u64,
+) -> Result<usize> {
+    let Some(days) = manifest.auto_purge_after_days.filter(|days| *days > 0) else {
+        return Ok(0);
+    };
+    let cutoff = now.saturating_sub(u64::from(days) * DAY_MS);
+    let mut purged = 0usize;
+    let mut kept = Vec::new();
+    for entry in entries.drain(..) {
+        if entry.deleted_at_ms >= cutoff {
+            kept.push(entry);
+            continue;
+        }
+        remove_trash_path(&root.join(TRASH_DIR_NAME).join(&entry.trash_name))?;
+        manifest
+            .items
+            .retain(|item| item.trash_name != entry.trash_name);
+        purged += 1;
+    }
+    *entries = kept;
+    Ok(purged)
+}
+
+fn list_trash_entries_inner(root: &Path, now: u64) -> Result<TrashListing> {
+    let mut manifest = read_manifest(root)?;
+    let mut entries = collect_entries(root, &mut manifest)?;
+    let purged_count = purge_expired(root, &mut manifest, &mut entries, now)?;
+    write_manifest(root, &manifest)?;
+    Ok(TrashListing {
+        entries,
+        auto_purge_after_days: manifest.auto_purge_after_days,
+        purged_count,
+    })
+}
+
+/// Deletes entries past the retention setting of `root` and returns how many were removed.
+///
+/// The trash folder is not scanned when no retention is set.
+pub(crate) fn purge_expired_entries(root: &Path) -> Result<usize> {
+    let manifest = read_manifest(root)?;
+    if manifest
+        .auto_purge_after_days
+        .filter(|days| *days > 0)
+        .is_none()
+    {
+        return Ok(0);
+    }
+    list_trash_entries_inner(root, now_ms()).map(|listing| listing.purged_count)
+}
+
+/// Reindexes restored notes and rewrites links whose original note no longer exists.
+///
+/// Links are only redirected when the restore had to pick another name and nothing
+/// else took the original note path in the meantime.
+fn repair_restored_notes(original: &Path, destination: &Path) -> Result<(usize, usize)> {
+    let restored_notes = if destination.is_dir() {
+        list_markdown_files_via_find(destination)?
+    } else if destination
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown"))
+    {
+        vec![destination.to_path_buf()]
+    } else {
+        Vec::new()
+    };
+    if restored_notes.is_empty() {
+        return Ok((0, 0));
+    }
+
+    let mut moves = Vec::new();
+    let mut reindex_only = Vec::new();
+    for note in restored_notes {
+        let original_note = match note.strip_prefix(destination) {
+            Ok(relative) if !relative.as_os_str().is_empty() => original.join(relative),
+            _ => original.to_path_buf(),
+        };
+        if original != destination && !original_note.exists() {
+            moves.push(PathMoveInput {
+                from_path: original_note.to_string_lossy().to_string(),
+                to_path: note.to_string_lossy().to_string(),
+            });
+        } else {
+            reindex_only.push(note);
+        }
+    }
+
+    let mut updated_files = 0usize;
+    let mut reindexed_files = 0usize;
+    if !moves.is_empty() {
+        let rewrite = update_wikilinks_for_path_moves(moves)?;
+        updated_files = rewrite.updated_files;
+        reindexed_files = rewrite.reindexed_files;
+    }
+    for note in &reindex_only {
+        let path = note.to_string_lossy().to_string();
+        reindex_markdown_file_lexical_sync(path.clone())?;
+        reindex_markdown_file_semantic_sync(path)?;
+    }
+    if !reindex_only.is_empty() {
+        refresh_semantic_edges_cache_now_sync()?;
+    }
+    Ok((updated_files, reindexed_files + reindex_only.len()))
+}
+
+#[tauri::command]
+pub fn list_trash_entries() -> Result<TrashListing> {
+    let root = active_workspace_root()?;
+    list_trash_entries_inner(&root, now_ms())
+}
+
+/// Moves a trashed entry back to its original location, recreating missing folders.
+#[tauri::command]
+pub fn restore_trash_entry(
+    trash_name: String,
+    conflict_strategy: ConflictStrategy,
+) -> Result<RestoreTrashEntryResult> {
+    let root = active_workspace_root()?;
+    let trash_name = validate_trash_name(&trash_name)?;
+    let mut manifest = read_manifest(&root)?;
+    let entries = collect_entries(&root, &mut manifest)?;
+    let entry = entries
+        .into_iter()
+        .find(|item| item.trash_name == trash_name)
+        .ok_or_else(|| AppError::InvalidOperation("Trash entry not found.".to_string()))?;
+
+    let source = root.join(TRASH_DIR_NAME).join(&entry.trash_name);
+    let original = root.join(normalize_original_path(&entry.original_path)?);
+    if let Some(parent) = original.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let destination = resolve_destination(original.clone(), conflict_strategy, entry.is_dir)?;
+    // An overwritten note goes to the trash in turn instead of being deleted.
+    let replaced = if destination.exists() && !entry.is_dir {
+        Some(move_to_trash(&root, &destination)?)
+    } else {
+        None
+    };
+    if let Err(err) = fs::rename(&source, &destination) {
+        if let Some(replaced) = replaced {
+            fs::rename(&replaced.path, &destination)?;
+        }
+        return Err(err.into());
+    }
+
+    let mut manifest = read_manifest(&root)?;
+    manifest
+        .items
+        .retain(|item| item.trash_name != entry.trash_name);
+    write_manifest(&root, &manifest)?;
+    let _ = purge_expired_entries(&root);
+
+    let (updated_link_files, reindexed_files) = repair_restored_notes(&original, &destination)?;
+    Ok(RestoreTrashEntryResult {
+        path: destination.to_string_lossy().to_string(),
+        updated_link_files,
+        reindexed_files,
+    })
+}
+
+/// Permanently deletes one trashed entry.
+#[tauri::command]
+pub fn purge_trash_entry(trash_name: String) -> Result<()> {
+    let root = active_workspace_root()?;
+    let trash_name = validate_trash_name(&trash_name)?;
+    let path = root.join(TRASH_DIR_NAME).join(&trash_name);
+    if !path.exists() && fs::symlink_metadata(&path).is_err() {
+        return Err(AppError::InvalidOperation(
+            "Trash entry not found.".to_string(),
+        ));
+    }
+    remove_trash_path(&path)?;
+    let mut manifest = read_manifest(&root)?;
+    manifest.items.retain(|item| item.trash_name != trash_name);
+    write_manifest(&root, &manifest)?;
+    let _ = purge_expired_entries(&root);
+    Ok(())
+}
+
+/// Sets how many days trashed entries are kept; `None` or `0` keeps them forever.
+#[tauri::command]
+pub fn set_trash_auto_purge(days: Option<u32>) -> Result<TrashListing> {
+    let root = active_workspace_root()?;
+    let mut manifest = read_manifest(&root)?;
+    manifest.auto_purge_after_days = days.filter(|value| *value > 0);
+    write_manifest(&root, &manifest)?;
+    list_trash_entries_inner(&root, now_ms())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::*;
+    use crate::fs_ops::trash_entry;
+    use crate::{clear_active_workspace, set_active_workspace, workspace_test_guard};
+
+    fn create_temp_workspace(prefix: &str) -> PathBuf {
+        let nonce = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|value| value.as_nanos())
+            .unwrap_or(0);
+        let dir = std::env::temp_dir().join(format!("{prefix}-{nonce}"));
+        fs::create_dir_all(&dir).expect("create temp workspace");
+        dir
+    }
+
+    #[test]
+    fn parses_legacy_trash_names() {
+        assert_eq!(
+            parse_trash_name("1700000000_note.md"),
+            (Some(1_700_000_000_000), "note.md")
+        );
+        assert_eq!(parse_trash_name("note_draft.md"), (None, "note_draft.md"));
+        assert_eq!(parse_trash_name("12_"), (None, "12_"));
+    }
+
+    #[test]
+    fn rejects_trash_names_outside_the_trash_folder() {
+        assert!(validate_trash_name("../notes.md").is_err());
+        assert!(validate_trash_name("a/b.md").is_err());
+        assert!(validate_trash_name("").is_err());
+        assert_eq!(validate_trash_name("12_note.md").unwrap(), "12_note.md");
+    }
+
+    #[test]
+    fn trashed_file_is_listed_with_its_origin_and_restored_in_place() {
+        let _guard = workspace_test_guard();
+        let workspace = create_temp_workspace("tomosona-trash-restore");
+        fs::create_dir_all(workspace.join("projects")).expect("create folder");
+        let note = workspace.join("projects").join("plan.txt");
+        fs::write(&note, "plan").expect("write note");
+        set_active_workspace(&workspace.to_string_lossy()).expect("set workspace");
+
+        trash_entry(note.to_string_lossy().to_string()).expect("trash note");
+        fs::remove_dir_all(workspace.join("projects")).expect("remove parent");
+
+        let listing = list_trash_entries().expect("list trash");
+        assert_eq!(listing.entries.len(), 1);
+        let entry = &listing.entries[0];
+        assert_eq!(entry.original_path, "projects/plan.txt");
+        assert!(entry.original_path_known);
+        assert_eq!(entry.size_bytes, 4);
+        assert!(!entry.is_dir);
+
+        let restored = restore_trash_entry(entry.trash_name.clone(), ConflictStrategy::Fail)
+            .expect("restore entry");
+        assert!(restored.path.ends_with("plan.txt"));
+        assert_eq!(fs::read_to_string(&note).expect("read restored"), "plan");
+        assert!(list_trash_entries().expect("list after").entries.is_empty());
+
+        clear_active_workspace().expect("clear workspace");
+        fs::remove_dir_all(workspace).expect("cleanup workspace");
+    }
+
+    #[test]
+    fn restore_applies_conflict_strategy() {
+        let _guard = workspace_test_guard();
+        let workspace = create_temp_workspace("tomosona-trash-conflict");
+        let note = workspace.join("todo.txt");
+        fs::write(&note, "old").expect("write note");
+        set_active_workspace(&workspace.to_string_lossy()).expect("set workspace");
+
+        trash_entry(note.to_string_lossy().to_string()).expect("trash note");
+        fs::write(&note, "new").expect("write replacement");
+        let trash_name = list_trash_entries().expect("list").entries[0]
+            .trash_name
+            .clone();
+
+        assert!(restore_trash_entry(trash_name.clone(), ConflictStrategy::Fail).is_err());
+        let restored =
+            restore_trash_entry(trash_name, ConflictStrategy::Rename).expect("restore renamed");
+        assert!(restored.path.ends_with("todo (1).txt"));
+        assert_eq!(fs::read_to_string(&note).expect("read current"), "new");
+
+        clear_active_workspace().expect("clear workspace");
+        fs::remove_dir_all(workspace).expect("cleanup workspace");
+    }
+
+    #[test]
+    fn lists_legacy_entries_and_purges_expired_ones() {
+        let _guard = workspace_test_guard();
+        let workspace = create_temp_workspace("tomosona-trash-purge");
+        let trash_dir = workspace.join(TRASH_DIR_NAME);
+        fs::create_dir_all(&trash_dir).expect("create trash");
+        fs::write(trash_dir.join("1000_old.md"), "old").expect("write old entry");
+        fs::write(trash_dir.join("1000000_recent.md"), "recent").expect("write recent entry");
+        set_active_workspace(&workspace.to_string_lossy()).expect("set workspace");
+
+        let listing = list_trash_entries_inner(&workspace, 1_000_000_000).expect("list trash");
+        assert_eq!(listing.entries.len(), 2);
+        assert!(listing
+            .entries
+            .iter()
+            .all(|entry| !entry.original_path_known));
+        assert_eq!(listing.entries[0].original_path, "recent.md");
+
+        let mut manifest = read_manifest(&workspace).expect("read manifest");
+        manifest.auto_purge_after_days = Some(1);
+        write_manifest(&workspace, &manifest).expect("write manifest");
+        let now = 1_000_000 * 1000 + DAY_MS / 2;
+        let listing = list_trash_entries_inner(&workspace, now).expect("list after purge");
+        assert_eq!(listing.purged_count, 1);
+        assert_eq!(listing.entries.len(), 1);
+        assert!(!trash_dir.join("1000_old.md").exists());
+
+        purge_trash_entry("1000000_recent.md".to_string()).expect("purge entry");
+        assert!(list_trash_entries().expect("list empty").entries.is_empty());
+
+        clear_active_workspace().expect("clear workspace");
+        fs::remove_dir_all(workspace).expect("cleanup workspace");
+    }
+
+    #[test]
+    fn trashing_an_entry_purges_expired_ones() {
+        let _guard = workspace_test_guard();
+        let workspace = create_temp_workspace("tomosona-trash-purge-on-trash");
+        let trash_dir = workspace.join(TRASH_DIR_NAME);
+        fs::create_dir_all(&trash_dir).expect("create trash");
+        fs::write(trash_dir.join("1000_old.md"), "old").expect("write old entry");
+        let note = workspace.join("draft.txt");
+        fs::write(&note, "draft").expect("write note");
+        set_active_workspace(&workspace.to_string_lossy()).expect("set workspace");
+        assert_eq!(purge_expired_entries(&workspace).expect("no retention"), 0);
+        assert!(trash_dir.join("1000_old.md").exists());
+
+        let mut manifest = read_manifest(&workspace).expect("read manifest");
+        manifest.auto_purge_after_days = Some(30);
+        write_manifest(&workspace, &manifest).expect("write manifest");
+        trash_entry(note.to_string_lossy().to_string()).expect("trash note");
+
+        assert!(!trash_dir.join("1000_old.md").exists());
+        let entries = read_manifest(&workspace).expect("read manifest").items;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].original_path, "draft.txt");
+
+        clear_active_workspace().expect("clear workspace");
+        fs::remove_dir_all(workspace).expect("cleanup workspace");
+    }
+
+    #[test]
+    fn overwriting_restore_moves_the_current_note_to_the_trash() {
+        let _guard = workspace_test_guard();
+        let workspace = create_temp_workspace("tomosona-trash-overwrite");
+        let note = workspace.join("todo.txt");
+        fs::write(&note, "old").expect("write note");
+        set_active_workspace(&workspace.to_string_lossy()).expect("set workspace");
+
+        trash_entry(note.to_string_lossy().to_string()).expect("trash note");
+        fs::write(&note, "new").expect("write replacement");
+        let trash_name = list_trash_entries().expect("list").entries[0]
+            .trash_name
+            .clone();
+
+        restore_trash_entry(trash_name, ConflictStrategy::Overwrite).expect("restore");
+        assert_eq!(fs::read_to_string(&note).expect("read restored"), "old");
+        let entries = list_trash_entries().expect("list after").entries;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].original_path, "todo.txt");
+        assert!(entries[0].original_path_known);
+        assert_eq!(
+            fs::read_to_string(&entries[0].trash_path).expect("read trashed"),
+            "new"
+        );
+
+        clear_active_workspace().expect("clear workspace");
+        fs::remove_dir_all(workspace).expect("cleanup workspace");
+    }
+}
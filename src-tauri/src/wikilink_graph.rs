@@ -14,6 +14,7 @@ use crate::editor_sync::record_workspace_mutation_write;
 use crate::markdown_index::{
     reindex_markdown_file_lexical_sync, reindex_markdown_file_semantic_sync,
 };
+use crate::operation_journal::record_link_rewrites;
 use crate::{
     active_workspace_root, list_markdown_files_via_find, normalize_note_key,
     normalize_note_key_from_workspace_path, normalize_workspace_path,
@@ -83,6 +84,61 @@ pub(crate) struct PathMoveRewriteResult {
     pub expanded_markdown_moves: Vec<PathMoveInput>,
 }
 
+/// Resolves normalized wikilink target keys to workspace-relative note paths.
+///
+/// A key matches a note path exactly first; keys without a folder then fall back
+/// to the note basename when exactly one note has it.
+pub(crate) struct NoteKeyResolver {
+    path_by_key: HashMap<String, String>,
+    path_by_unique_basename: HashMap<String, Option<String>>,
+}
+
+impl NoteKeyResolver {
+    pub(crate) fn from_paths(root_canonical: &Path, markdown_paths: &[String]) -> Self {
+        let mut path_by_key: HashMap<String, String> = HashMap::new();
+        let mut path_by_unique_basename: HashMap<String, Option<String>> = HashMap::new();
+        for path in markdown_paths {
+            if let Some(key) = normalize_note_key_from_workspace_path(root_canonical, path) {
+                let basename = note_key_basename(&key);
+                let existing = path_by_key.get(&key).cloned();
+                if let Some(previous) = existing {
+                    if path.to_lowercase() < previous.to_lowercase() {
+                        path_by_key.insert(key.clone(), path.clone());
+                    }
+                } else {
+                    path_by_key.insert(key.clone(), path.clone());
+                }
+
+                match path_by_unique_basename.get(&basename) {
+                    Some(Some(previous)) if !previous.eq_ignore_ascii_case(path) => {
+                        path_by_unique_basename.insert(basename, None);
+                    }
+                    Some(None) => {}
+                    _ => {
+                        path_by_unique_basename.insert(basename, Some(path.clone()));
+                    }
+                }
+            }
+        }
+        Self {
+            path_by_key,
+            path_by_unique_basename,
+        }
+    }
+
+    pub(crate) fn resolve(&self, target_key: &str) -> Option<&str> {
+        if let Some(path) = self.path_by_key.get(target_key) {
+            return Some(path);
+        }
+        if target_key.contains('/') {
+            return None;
+        }
+        self.path_by_unique_basename
+            .get(target_key)
+            .and_then(|value| value.as_deref())
+    }
+}
+
 pub(crate) fn build_wikilink_graph_from_index(
     conn: &Connection,
     root_canonical: &Path,
@@ -93,31 +149,7 @@ pub(crate) fn build_wikilink_graph_from_index(
         nodes_set.insert(path.clone());
     }
 
-    let mut path_by_key: HashMap<String, String> = HashMap::new();
-    let mut path_by_unique_basename: HashMap<String, Option<String>> = HashMap::new();
-    for path in markdown_paths {
-        if let Some(key) = normalize_note_key_from_workspace_path(root_canonical, path) {
-            let basename = note_key_basename(&key);
-            let existing = path_by_key.get(&key).cloned();
-            if let Some(previous) = existing {
-                if path.to_lowercase() < previous.to_lowercase() {
-                    path_by_key.insert(key.clone(), path.clone());
-                }
-            } else {
-                path_by_key.insert(key.clone(), path.clone());
-            }
-
-            match path_by_unique_basename.get(&basename) {
-                Some(Some(previous)) if !previous.eq_ignore_ascii_case(path) => {
-                    path_by_unique_basename.insert(basename, None);
-                }
-                Some(None) => {}
-                _ => {
-                    path_by_unique_basename.insert(basename, Some(path.clone()));
-                }
-            }
-        }
-    }
+    let resolver = NoteKeyResolver::from_paths(root_canonical, markdown_paths);
 
     let mut tags_by_path: HashMap<String, Vec<String>> = HashMap::new();
     let mut tag_stmt = conn.prepare(
@@ -163,14 +195,7 @@ pub(crate) fn build_wikilink_graph_from_index(
         if !nodes_set.contains(&source_path) {
             continue;
         }
-        let target_path = path_by_key.get(&target_key).cloned().or_else(|| {
-            if target_key.contains('/') {
-                return None;
-            }
-            path_by_unique_basename
-                .get(&target_key)
-                .and_then(|value| value.clone())
-        });
+        let target_path = resolver.resolve(&target_key).map(str::to_string);
         let Some(target_path) = target_path else {
             continue;
         };
@@ -390,6 +415,7 @@ pub(crate) fn update_wikilinks_for_rename(
 
     let markdown_files = list_markdown_files_via_find(&root_canonical)?;
     let mut changed_files = 0usize;
+    let mut rewrites = Vec::new();
 
     for candidate in markdown_files {
         let canonical_candidate = match fs::canonicalize(&candidate) {
@@ -410,8 +436,10 @@ pub(crate) fn update_wikilinks_for_rename(
         fs::write(&canonical_candidate, &updated_markdown)?;
         record_workspace_mutation_write(&canonical_candidate, &updated_markdown);
         reindex_markdown_file_now_sync(canonical_candidate.to_string_lossy().to_string())?;
+        rewrites.push((canonical_candidate, markdown, updated_markdown));
         changed_files += 1;
     }
+    let _ = record_link_rewrites(&root_canonical, &[new_note_path], rewrites);
 
     Ok(WikilinkRewriteResult {
         updated_files: changed_files,
@@ -478,6 +506,10 @@ pub(crate) fn update_wikilinks_for_path_moves(
 ) -> Result<PathMoveRewriteResult> {
     let root_canonical = active_workspace_root()?;
     let mut note_moves: Vec<(String, String, PathBuf, PathBuf)> = Vec::new();
+    let moved_to = moves
+        .iter()
+        .filter_map(|path_move| normalize_workspace_path(&root_canonical, &path_move.to_path).ok())
+        .collect::<Vec<_>>();
 
     for path_move in moves {
         note_moves.extend(collect_note_moves_for_path_move(
@@ -497,6 +529,7 @@ pub(crate) fn update_wikilinks_for_path_moves(
 
     let markdown_files = list_markdown_files_via_find(&root_canonical)?;
     let mut changed_files = 0usize;
+    let mut rewrites = Vec::new();
     let mut reindex_paths: HashSet<String> = HashSet::new();
     let moved_markdown_files = note_moves.len();
 
@@ -510,7 +543,7 @@ pub(crate) fn update_wikilinks_for_path_moves(
             Err(_) => continue,
         };
 
-        let mut updated_markdown = markdown;
+        let mut updated_markdown = markdown.clone();
         let mut changed = false;
         for (old_target_key, new_target, _, _) in &note_moves {
             let (rewritten, rewritten_changed) =
@@ -526,8 +559,10 @@ pub(crate) fn update_wikilinks_for_path_moves(
         fs::write(&canonical_candidate, &updated_markdown)?;
         record_workspace_mutation_write(&canonical_candidate, &updated_markdown);
         reindex_paths.insert(canonical_candidate.to_string_lossy().to_string());
+        rewrites.push((canonical_candidate, markdown, updated_markdown));
         changed_files += 1;
     }
+    let _ = record_link_rewrites(&root_canonical, &moved_to, rewrites);
 
     for (_, _, _, moved_note_path) in &note_moves {
         reindex_paths.insert(moved_note_path.to_string_lossy().to_string());
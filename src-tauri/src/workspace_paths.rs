@@ -283,6 +283,41 @@ pub(crate) fn split_wikilink_target_suffix(content: &str) -> (&str, &str) {
     }
 }
 
+/// Joins `relative` onto `base_dir`, resolving `.` and `..` segments.
+pub(crate) fn join_relative(base_dir: &str, relative: &str) -> Option<String> {
+    let mut segments: Vec<&str> = base_dir
+        .split('/')
+        .filter(|part| !part.is_empty())
+        .collect();
+    for part in relative.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                segments.pop()?;
+            }
+            other => segments.push(other),
+        }
+    }
+    Some(segments.join("/"))
+}
+
+/// Path of `target` relative to the folder `from_dir`, both workspace-relative.
+pub(crate) fn relative_from(from_dir: &str, target: &str) -> String {
+    let from: Vec<&str> = from_dir
+        .split('/')
+        .filter(|part| !part.is_empty())
+        .collect();
+    let to: Vec<&str> = target.split('/').filter(|part| !part.is_empty()).collect();
+    let common = from
+        .iter()
+        .zip(to.iter())
+        .take_while(|(left, right)| left == right)
+        .count();
+    let mut parts = vec![".."; from.len() - common];
+    parts.extend(&to[common..]);
+    parts.join("/")
+}
+
 pub(crate) fn rewrite_wikilinks_for_note(
     markdown: &str,
     old_target_key: &str,
@@ -28,7 +28,7 @@ const hoisted = vi.hoisted(() => ({
           model: 'gpt-4.1',
           default_temperature: 0.15,
           system_prompt: 'Always answer tersely.',
-          api_key: 'openai-secret',
+          has_api_key: true,
           base_url: null,
           default_mode: 'freestyle',
           capabilities: {
@@ -60,7 +60,21 @@ const hoisted = vi.hoisted(() => ({
   discoverEmbeddingModels: vi.fn(async () => [
     { id: 'text-embedding-3-small', display_name: 'Text Embedding 3 Small' },
     { id: 'text-embedding-3-large', display_name: 'Text Embedding 3 Large' }
-  ])
+  ]),
+  readSecretStoreStatus: vi.fn(async () => ({
+    path: '/Users/test/.tomosona/secrets.vault.json',
+    exists: true,
+    protection: 'passphrase' as const,
+    locked: true,
+    secret_ids: ['llm/openai-profile']
+  })),
+  unlockSecretStore: vi.fn(async () => ({
+    path: '/Users/test/.tomosona/secrets.vault.json',
+    exists: true,
+    protection: 'passphrase' as const,
+    locked: false,
+    secret_ids: ['llm/openai-profile']
+  }))
 }))
 
 vi.mock('./shared/api/appApi', () => ({
@@ -136,7 +150,11 @@ vi.mock('./shared/api/settingsApi', () => ({
   writeAppSettings: hoisted.writeAppSettings,
   discoverCodexModels: hoisted.discoverCodexModels,
   discoverLlmModels: hoisted.discoverLlmModels,
-  discoverEmbeddingModels: hoisted.discoverEmbeddingModels
+  discoverEmbeddingModels: hoisted.discoverEmbeddingModels,
+  readSecretStoreStatus: hoisted.readSecretStoreStatus,
+  unlockSecretStore: hoisted.unlockSecretStore,
+  lockSecretStore: vi.fn(async () => ({})),
+  setSecretStorePassphrase: vi.fn(async () => ({}))
 }))
 
 vi.mock('./shared/api/favoritesApi', () => ({
@@ -505,7 +523,7 @@ describe('App settings modal', () => {
 
     const llmKey = mounted.root.querySelector<HTMLInputElement>('#settings-llm-apikey')
     expect(llmKey?.getAttribute('type')).toBe('password')
-    expect(llmKey?.value).toBe('openai-secret')
+    expect(llmKey?.value).toBe('')
 
     const revealLlm = Array.from(mounted.root.querySelectorAll('button')).find((item) => item.getAttribute('aria-label') === 'Reveal API key')
     revealLlm?.click()
@@ -540,7 +558,7 @@ describe('App settings modal', () => {
             model: 'gpt-4.1',
             default_temperature: 0.15,
             system_prompt: '',
-            api_key: 'openai-secret',
+            has_api_key: true,
             base_url: null,
             default_mode: 'freestyle',
             capabilities: {
@@ -560,7 +578,7 @@ describe('App settings modal', () => {
           label: 'OpenAI Embeddings',
           provider: 'openai',
           model: 'text-embedding-3-small',
-          api_key: 'emb-secret',
+          has_api_key: true,
           base_url: 'https://albert.api.etalab.gouv.fr/v1/'
         }
       },
@@ -580,7 +598,8 @@ describe('App settings modal', () => {
     await flushUi()
 
     const llmKey = mounted.root.querySelector<HTMLInputElement>('#settings-llm-apikey')
-    expect(llmKey?.value).toBe('openai-secret')
+    expect(llmKey?.value).toBe('')
+    expect(llmKey?.getAttribute('placeholder')).toBe('saved in the secret store, type to replace')
 
     const embTab = Array.from(mounted.root.querySelectorAll('button')).find((item) => item.textContent === 'Embeddings')
     embTab?.click()
@@ -591,7 +610,8 @@ describe('App settings modal', () => {
     await flushUi()
 
     const embKey = mounted.root.querySelector<HTMLInputElement>('#settings-emb-apikey')
-    expect(embKey?.value).toBe('emb-secret')
+    expect(embKey?.value).toBe('')
+    expect(embKey?.getAttribute('placeholder')).toBe('saved in the secret store, type to replace')
     mounted.app.unmount()
   })
 
@@ -674,4 +694,122 @@ describe('App settings modal', () => {
     expect(typed.embeddings.external?.model).toBe('text-embedding-3-large')
     mounted.app.unmount()
   })
+
+  it('unlocks the secret store and saves environment key sources', async () => {
+    const mounted = mountApp()
+    await flushUi()
+    mounted.root.querySelector<HTMLButtonElement>('button[aria-label="View options"]')?.click()
+    await flushUi()
+    const settingsBtn = Array.from(mounted.root.querySelectorAll('button')).find((item) => item.textContent?.includes('Open Settings'))
+    settingsBtn?.click()
+    await flushUi()
+
+    expect(mounted.root.textContent).toContain('Unlock the store to use saved keys.')
+    const passphrase = mounted.root.querySelector<HTMLInputElement>('#settings-secret-store-passphrase')
+    if (passphrase) {
+      passphrase.value = 'correct horse'
+      passphrase.dispatchEvent(new Event('input', { bubbles: true }))
+    }
+    await flushUi()
+    const unlockBtn = Array.from(mounted.root.querySelectorAll('button')).find((item) => item.textContent?.trim() === 'Unlock')
+    unlockBtn?.click()
+    await flushUi()
+    expect(hoisted.unlockSecretStore).toHaveBeenCalledWith('correct horse')
+
+    const source = mounted.root.querySelector<HTMLSelectElement>('#settings-llm-key-source')
+    if (source) {
+      source.value = 'env'
+      source.dispatchEvent(new Event('change', { bubbles: true }))
+    }
+    await flushUi()
+    const env = mounted.root.querySelector<HTMLInputElement>('#settings-llm-apikey-env')
+    expect(env).toBeTruthy()
+    if (env) {
+      env.value = 'OPENAI_API_KEY'
+      env.dispatchEvent(new Event('input', { bubbles: true }))
+    }
+    await flushUi()
+    const saveBtn = Array.from(mounted.root.querySelectorAll('button')).find((item) => item.textContent === 'Save')
+    saveBtn?.click()
+    await flushUi()
+    const envCall = hoisted.writeAppSettings.mock.calls[0] as unknown[] | undefined
+    const fromEnv = envCall?.[0] as { llm: { profiles: Array<{ api_key?: string; api_key_env?: string }> } }
+    expect(fromEnv.llm.profiles[0]?.api_key_env).toBe('OPENAI_API_KEY')
+    expect(fromEnv.llm.profiles[0]?.api_key).toBeUndefined()
+    mounted.app.unmount()
+  })
+
+  it('shows the saved prompt language and sends the picked one back', async () => {
+    const settings = await hoisted.readAppSettings()
+    hoisted.readAppSettings.mockResolvedValueOnce({
+      ...settings,
+      llm: settings.llm ? { ...settings.llm, prompt_language: 'en' } : null
+    })
+    const mounted = mountApp()
+    await flushUi()
+    mounted.root.querySelector<HTMLButtonElement>('button[aria-label="View options"]')?.click()
+    await flushUi()
+    const settingsBtn = Array.from(mounted.root.querySelectorAll('button')).find((item) => item.textContent?.includes('Open Settings'))
+    settingsBtn?.click()
+    await flushUi()
+
+    const language = mounted.root.querySelector<HTMLSelectElement>('#settings-llm-prompt-language')
+    expect(language?.value).toBe('en')
+    if (language) {
+      language.value = 'auto'
+      language.dispatchEvent(new Event('change', { bubbles: true }))
+    }
+    await flushUi()
+
+    const saveBtn = Array.from(mounted.root.querySelectorAll('button')).find((item) => item.textContent === 'Save')
+    saveBtn?.click()
+    await flushUi()
+
+    const call = hoisted.writeAppSettings.mock.calls[0] as unknown[] | undefined
+    const payload = call?.[0] as { llm: { prompt_language?: string } }
+    expect(payload.llm.prompt_language).toBe('auto')
+    mounted.app.unmount()
+  })
+
+  it('saves model roles and keeps the other saved profiles', async () => {
+    const mounted = mountApp()
+    await flushUi()
+    mounted.root.querySelector<HTMLButtonElement>('button[aria-label="View options"]')?.click()
+    await flushUi()
+    const settingsBtn = Array.from(mounted.root.querySelectorAll('button')).find((item) => item.textContent?.includes('Open Settings'))
+    settingsBtn?.click()
+    await flushUi()
+
+    const provider = mounted.root.querySelector<HTMLSelectElement>('#settings-llm-provider')
+    if (provider) {
+      provider.value = 'codex'
+      provider.dispatchEvent(new Event('change', { bubbles: true }))
+    }
+    await flushUi()
+    const deepRole = mounted.root.querySelector<HTMLSelectElement>('#settings-llm-role-deep')
+    expect(deepRole).toBeTruthy()
+    if (deepRole) {
+      deepRole.value = 'openai-profile'
+      deepRole.dispatchEvent(new Event('change', { bubbles: true }))
+    }
+    await flushUi()
+
+    const saveBtn = Array.from(mounted.root.querySelectorAll('button')).find((item) => item.textContent === 'Save')
+    saveBtn?.click()
+    await flushUi()
+
+    const call = hoisted.writeAppSettings.mock.calls[0] as unknown[] | undefined
+    const payload = call?.[0] as {
+      llm: {
+        active_profile: string
+        profiles: Array<{ id: string }>
+        model_roles: Record<string, string | null>
+      }
+    }
+    expect(payload.llm.active_profile).toBe('openai-codex-profile')
+    expect(payload.llm.profiles.map((item) => item.id)).toEqual(['openai-codex-profile', 'openai-profile'])
+    expect(payload.llm.model_roles.deep).toBe('openai-profile')
+    expect(payload.llm.model_roles.fast).toBeNull()
+    mounted.app.unmount()
+  })
 })
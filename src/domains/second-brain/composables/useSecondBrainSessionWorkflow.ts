@@ -469,6 +469,8 @@ export function useSecondBrainSessionWorkflow(options: UseSecondBrainSessionWork
       const status = await fetchSecondBrainConfigStatus()
       if (!status.configured) {
         configError.value = status.error || 'Second Brain config is missing.'
+      } else if (status.error) {
+        configError.value = status.error
       }
     } catch (err) {
       configError.value = err instanceof Error ? err.message : 'Could not read config status.'
@@ -46,6 +46,12 @@ export type FileMetadata = {
   updated_at_ms: number | null
 }
 
+export type CreatedEntry = {
+  path: string
+  /** Byte offset of the template `{{cursor}}` marker in the written note. */
+  cursor_offset: number | null
+}
+
 export type CreateExtractedNoteResult = {
   path: string
   link_target: string
@@ -164,12 +170,19 @@ export type SecondBrainConfigStatus = {
   error: string | null
 }
 
-export type AppSettingsLlmProfile = {
+/** Where a profile reads its API key from; keys themselves are never sent back. */
+export type ApiKeySource = {
+  api_key_secret?: string | null
+  api_key_env?: string | null
+  api_key_cmd?: string | null
+}
+
+export type AppSettingsLlmProfile = ApiKeySource & {
   id: string
   label: string
   provider: string
   model: string
-  api_key: string
+  has_api_key: boolean
   default_temperature: number
   system_prompt: string
   base_url: string | null
@@ -183,17 +196,28 @@ export type AppSettingsLlmProfile = {
   }
 }
 
+/** Workloads that can use a profile other than the active one. */
+export type ModelRoleName = 'fast' | 'deep' | 'summarizer' | 'titler' | 'frontmatter' | 'pulse'
+
+/** Profile id assigned to each role; unassigned roles use the active profile. */
+export type ModelRoles = Partial<Record<ModelRoleName, string | null>>
+
+/** Language of the built-in prompts; unset follows the language of the material. */
+export type PromptLocale = 'fr' | 'en'
+
 export type AppSettingsLlm = {
   active_profile: string
   profiles: AppSettingsLlmProfile[]
+  prompt_language?: PromptLocale | null
+  model_roles?: ModelRoles
 }
 
-export type AppSettingsEmbeddingProfile = {
+export type AppSettingsEmbeddingProfile = ApiKeySource & {
   id: string
   label: string
   provider: string
   model: string
-  api_key: string
+  has_api_key: boolean
   base_url: string | null
 }
 
@@ -216,6 +240,7 @@ export type AppSettingsView = {
   llm: AppSettingsLlm | null
   embeddings: AppSettingsEmbeddings
   alters: AppSettingsAlters
+  key_migration_error?: string | null
 }
 
 export type SaveAppSettingsPayload = {
@@ -227,6 +252,8 @@ export type SaveAppSettingsPayload = {
       provider: string
       model: string
       api_key?: string
+      api_key_env?: string
+      api_key_cmd?: string
       default_temperature: number
       system_prompt: string
       preserve_existing_api_key: boolean
@@ -240,6 +267,8 @@ export type SaveAppSettingsPayload = {
         streaming: boolean
       }
     }>
+    prompt_language?: PromptLocale | 'auto'
+    model_roles?: ModelRoles
   }
   embeddings: {
     mode: 'internal' | 'external'
@@ -249,6 +278,8 @@ export type SaveAppSettingsPayload = {
       provider: string
       model: string
       api_key?: string
+      api_key_env?: string
+      api_key_cmd?: string
       preserve_existing_api_key: boolean
       base_url?: string | null
     } | null
@@ -266,6 +297,14 @@ export type WriteAppSettingsResult = {
   alters: AppSettingsAlters
 }
 
+export type SecretStoreStatus = {
+  path: string
+  exists: boolean
+  protection: 'machine' | 'passphrase'
+  locked: boolean
+  secret_ids: string[]
+}
+
 export type CodexDiscoveredModel = {
   id: string
   display_name: string
@@ -281,6 +320,8 @@ export type DiscoverLlmModelsPayload = {
   profile_id: string
   provider: string
   api_key?: string
+  api_key_env?: string
+  api_key_cmd?: string
   preserve_existing_api_key: boolean
   base_url?: string | null
 }
@@ -288,6 +329,8 @@ export type DiscoverLlmModelsPayload = {
 export type DiscoverEmbeddingModelsPayload = {
   profile_id: string
   api_key?: string
+  api_key_env?: string
+  api_key_cmd?: string
   preserve_existing_api_key: boolean
   base_url?: string | null
 }
@@ -479,6 +522,8 @@ export type AlterExplorationRoundResult = {
   alter_name?: string
   content: string
   references_alter_ids: string[]
+  /** Unix ms when the result was produced; 0 for older explorations. */
+  created_at_ms?: number
 }
 
 export type AlterExplorationSession = {
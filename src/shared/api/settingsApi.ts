@@ -6,6 +6,7 @@ import type {
   DiscoverLlmModelsPayload,
   LlmDiscoveredModel,
   SaveAppSettingsPayload,
+  SecretStoreStatus,
   WriteAppSettingsResult
 } from './apiTypes'
 
@@ -37,3 +38,23 @@ export async function discoverLlmModels(payload: DiscoverLlmModelsPayload): Prom
 export async function discoverEmbeddingModels(payload: DiscoverEmbeddingModelsPayload): Promise<LlmDiscoveredModel[]> {
   return await invoke('discover_embedding_models', { payload })
 }
+
+/** Reads whether the API key secret store exists, how it is protected and if it is locked. */
+export async function readSecretStoreStatus(): Promise<SecretStoreStatus> {
+  return await invoke('read_secret_store_status')
+}
+
+/** Unlocks a passphrase-protected secret store for the rest of the session. */
+export async function unlockSecretStore(passphrase: string): Promise<SecretStoreStatus> {
+  return await invoke('unlock_secret_store', { payload: { passphrase } })
+}
+
+/** Locks the secret store again and forgets cached command keys. */
+export async function lockSecretStore(): Promise<SecretStoreStatus> {
+  return await invoke('lock_secret_store')
+}
+
+/** Protects the secret store with a passphrase, or with the machine key when `null`. */
+export async function setSecretStorePassphrase(passphrase: string | null): Promise<SecretStoreStatus> {
+  return await invoke('set_secret_store_passphrase', { payload: { passphrase } })
+}
@@ -1,6 +1,7 @@
 import { invoke } from '@tauri-apps/api/core'
 import { listen, type UnlistenFn } from '@tauri-apps/api/event'
 import type {
+  CreatedEntry,
   CreateExtractedNoteResult,
   ConflictStrategy,
   EntryKind,
@@ -91,7 +92,8 @@ export async function createEntry(
   kind: EntryKind,
   conflictStrategy: ConflictStrategy
 ): Promise<string> {
-  return await invoke('create_entry', { parentPath, name, kind, conflictStrategy })
+  const created = await invoke<CreatedEntry>('create_entry', { parentPath, name, kind, conflictStrategy })
+  return created.path
 }
 
 /** Creates a new markdown note from extracted selection content. */